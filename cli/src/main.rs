@@ -1,14 +1,19 @@
-use crate::analyzers::ReportConfig;
-use ariadne::sources;
+use crate::analyzers::{findings_json, Finding, OutputFormat, ReportConfig};
+use ariadne::Cache;
 use clap::{ArgAction, Parser, ValueHint};
 use pyrometer::context::queries::storage_write::StorageRangeQuery;
 use pyrometer::{
     context::{
-        analyzers::{bounds::FunctionVarsBoundAnalyzer, ReportDisplay},
+        analyzers::{
+            bounds::FunctionVarsBoundAnalyzer, dead_code::DeadCodeAnalyzer,
+            diagnostics::DiagnosticsAnalyzer, overflow::OverflowAnalyzer,
+            shadowing::ShadowingAnalyzer, value_flow::ValueFlowAnalyzer, ReportDisplay,
+        },
+        func::FuncCaller,
         queries::storage_write::AccessStorageWriteQuery,
         *,
     },
-    Analyzer,
+    Analyzer, Metrics,
 };
 use shared::nodes::Concrete;
 use shared::range::SolcRange;
@@ -16,13 +21,15 @@ use shared::{
     analyzer::{GraphLike, Search},
     nodes::ContractNode,
 };
-use std::collections::{BTreeMap, HashMap};
 use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use shared::context::ContextNode;
 use shared::nodes::FunctionNode;
 
 use shared::Edge;
+use std::collections::{BTreeMap, BTreeSet};
 use std::env::temp_dir;
 use std::fs;
 
@@ -41,6 +48,9 @@ struct Args {
     pub dot: bool,
     #[clap(long, short, default_value = "false")]
     pub open_dot: bool,
+    /// Print the analysis graph as GraphML
+    #[clap(long, default_value = "false")]
+    pub graphml: bool,
     #[clap(long, short)]
     pub eval: Option<bool>,
     #[clap(long, short)]
@@ -49,105 +59,201 @@ struct Args {
     pub access_query: Vec<String>,
     #[clap(long, short)]
     pub write_query: Vec<String>,
+    /// Treat every public/external function parameter as adversarial
+    #[clap(long, default_value = "false")]
+    pub security: bool,
+    /// How many iterations of a loop to execute before widening its variables
+    #[clap(long, default_value = "0")]
+    pub max_unroll: usize,
+    /// Widen every variable an inline assembly block mentions, not just those it assigns to
+    #[clap(long, default_value = "false")]
+    pub assembly_unknown: bool,
+    /// Report arithmetic that may overflow or underflow its type, or divide by zero
+    #[clap(long, default_value = "false")]
+    pub overflow: bool,
+    /// Report statements and branches that can never be reached
+    #[clap(long, default_value = "false")]
+    pub dead_code: bool,
+    /// Report local variables and parameters that shadow another declaration
+    #[clap(long, default_value = "false")]
+    pub shadowing: bool,
+    /// Report ether sent out of the contract, with the range of each value sent
+    #[clap(long, default_value = "false")]
+    pub value_flow: bool,
+    /// Print all findings as a single JSON array instead of annotated source
+    #[clap(long, default_value = "false")]
+    pub json: bool,
+    /// Report internal functions with their parameters limited to the arguments they are
+    /// called with
+    #[clap(long, default_value = "false")]
+    pub interprocedural: bool,
+    /// Only report variables whose bounds end up narrower than their type
+    #[clap(long, default_value = "false")]
+    pub only_changed: bool,
+    /// Report each variable once per function, with its bounds joined across all branches
+    #[clap(long, default_value = "false")]
+    pub merge_subctxs: bool,
+    /// Also report constructs that were skipped or could not be fully analyzed
+    #[clap(long, default_value = "false")]
+    pub diagnostics: bool,
+    /// Print a profile of where the time went while analyzing to stderr
+    #[clap(long, default_value = "false")]
+    pub metrics: bool,
+}
+
+/// Prints `report` or, when JSON output is requested, collects its findings to print at the end
+fn emit(
+    report: &impl ReportDisplay,
+    config: ReportConfig,
+    findings: &mut Vec<Finding>,
+    src: &mut impl Cache<String>,
+    analyzer: &Analyzer,
+) {
+    match config.output {
+        OutputFormat::Text => report.print_reports(src, analyzer),
+        OutputFormat::Json => findings.extend(report.findings(analyzer)),
+    }
+}
+
+/// Emits the bounds of `ctx`, along with the checks enabled in `config`
+fn emit_func(
+    file_mapping: &BTreeMap<usize, String>,
+    ctx: ContextNode,
+    config: ReportConfig,
+    findings: &mut Vec<Finding>,
+    src: &mut impl Cache<String>,
+    analyzer: &Analyzer,
+) {
+    let analysis = analyzer.bounds_for_all(file_mapping, ctx, config);
+    emit(&analysis, config, findings, src, analyzer);
+    if config.overflow {
+        let report = analyzer.overflow_report(file_mapping, ctx, config);
+        emit(&report, config, findings, src, analyzer);
+    }
+    if config.dead_code {
+        let report = analyzer.dead_code_report(file_mapping, ctx, config);
+        emit(&report, config, findings, src, analyzer);
+    }
+    if config.shadowing {
+        let report = analyzer.shadowing_report(file_mapping, ctx, config);
+        emit(&report, config, findings, src, analyzer);
+    }
+    if config.value_flow {
+        let report = analyzer.value_flow_report(file_mapping, ctx, config);
+        emit(&report, config, findings, src, analyzer);
+    }
 }
 
 fn main() {
     let args = Args::parse();
-    let path_str = args.path.to_string();
     let verbosity = args.verbosity;
-    let config = match verbosity {
-        0 => ReportConfig {
-            eval_bounds: args.eval.unwrap_or(true),
-            simplify_bounds: false,
-            show_tmps: false,
-            show_consts: false,
-            show_subctxs: true,
-            show_initial_bounds: args.show_inits.unwrap_or(false),
-            show_all_lines: false,
+    let base = ReportConfig {
+        eval_bounds: args.eval.unwrap_or(true),
+        show_initial_bounds: args.show_inits.unwrap_or(false),
+        interprocedural: args.interprocedural,
+        only_changed: args.only_changed,
+        merge_subctxs: args.merge_subctxs,
+        overflow: args.overflow,
+        dead_code: args.dead_code,
+        shadowing: args.shadowing,
+        value_flow: args.value_flow,
+        output: if args.json {
+            OutputFormat::Json
+        } else {
+            OutputFormat::Text
         },
+        ..ReportConfig::default()
+    };
+    let config = match verbosity {
+        0 => base,
         1 => ReportConfig {
-            eval_bounds: args.eval.unwrap_or(true),
-            simplify_bounds: false,
-            show_tmps: false,
             show_consts: true,
-            show_subctxs: true,
-            show_initial_bounds: args.show_inits.unwrap_or(false),
-            show_all_lines: false,
+            ..base
         },
         2 => ReportConfig {
-            eval_bounds: args.eval.unwrap_or(true),
-            simplify_bounds: false,
             show_tmps: true,
             show_consts: true,
-            show_subctxs: true,
-            show_initial_bounds: args.show_inits.unwrap_or(false),
-            show_all_lines: false,
+            ..base
         },
         3 => ReportConfig {
-            eval_bounds: args.eval.unwrap_or(true),
-            simplify_bounds: false,
-            show_tmps: true,
-            show_consts: true,
-            show_subctxs: true,
-            show_initial_bounds: args.show_inits.unwrap_or(true),
-            show_all_lines: false,
-        },
-        4 => ReportConfig {
-            eval_bounds: args.eval.unwrap_or(true),
-            simplify_bounds: false,
             show_tmps: true,
             show_consts: true,
-            show_subctxs: true,
             show_initial_bounds: args.show_inits.unwrap_or(true),
-            show_all_lines: true,
+            ..base
         },
         _ => ReportConfig {
-            eval_bounds: args.eval.unwrap_or(true),
-            simplify_bounds: false,
             show_tmps: true,
             show_consts: true,
-            show_subctxs: true,
             show_initial_bounds: args.show_inits.unwrap_or(true),
             show_all_lines: true,
+            ..base
         },
     };
 
     let sol = fs::read_to_string(args.path.clone()).expect("Could not find file");
 
-    let mut analyzer = Analyzer::default();
+    let mut analyzer = Analyzer {
+        root: Path::new(&args.path)
+            .parent()
+            .map(|parent| parent.to_path_buf())
+            .unwrap_or_default(),
+        security_mode: args.security,
+        max_unroll: args.max_unroll,
+        assembly_unknown: args.assembly_unknown,
+        metrics: args.metrics.then(Metrics::default),
+        ..Default::default()
+    };
+    analyzer.file_sources.entry(0).or_default().0 = PathBuf::from(&args.path);
     let t0 = std::time::Instant::now();
-    let (maybe_entry, mut all_sources) = analyzer.parse(&sol);
+    let entry = match analyzer.parse(&sol, 0) {
+        Ok(entry) => entry,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
     let _parse_time = t0.elapsed().as_millis();
-    all_sources.push((maybe_entry, args.path, sol, 0));
-    let entry = maybe_entry.unwrap();
-
-    let mut file_mapping: BTreeMap<_, _> = vec![(0usize, path_str)].into_iter().collect();
-    file_mapping.extend(
-        all_sources
-            .iter()
-            .map(|(_entry, name, _src, num)| (*num, name.clone()))
-            .collect::<BTreeMap<_, _>>(),
-    );
-
-    let mut source_map = sources(
-        all_sources
-            .iter()
-            .map(|(_entry, name, src, _num)| (name.clone(), src))
-            .collect::<HashMap<_, _>>(),
-    );
+    let file_mapping = analyzer.file_mapping();
+    let mut source_map = analyzer.source_cache();
 
     if args.dot {
         println!("{}", analyzer.dot_str_no_tmps());
     }
 
+    if args.graphml {
+        println!("{}", analyzer.graphml_str());
+    }
+
+    let narrowed = if config.interprocedural {
+        analyzer
+            .search_children(entry, &Edge::Func)
+            .into_iter()
+            .filter_map(|func| {
+                let func = FunctionNode::from(func);
+                Some((func, analyzer.interprocedural_ctx(func)?))
+            })
+            .collect::<BTreeMap<_, _>>()
+    } else {
+        BTreeMap::default()
+    };
+
     let all_contracts = analyzer
         .search_children(entry, &Edge::Contract)
         .into_iter()
         .map(ContractNode::from)
         .collect::<Vec<_>>();
     let _t1 = std::time::Instant::now();
+    let mut findings = vec![];
     if args.contracts.is_empty() {
-        let funcs = analyzer.search_children(entry, &Edge::Func);
+        let funcs = [
+            Edge::Func,
+            Edge::Constructor,
+            Edge::FallbackFunc,
+            Edge::ReceiveFunc,
+        ]
+        .iter()
+        .flat_map(|edge| analyzer.search_children(entry, edge))
+        .collect::<BTreeSet<_>>();
         for func in funcs.into_iter() {
             if !args.funcs.is_empty() {
                 if args.funcs.iter().any(|analyze_for| {
@@ -155,14 +261,34 @@ fn main() {
                         .name(&analyzer)
                         .starts_with(analyze_for)
                 }) {
-                    if let Some(ctx) = FunctionNode::from(func).maybe_body_ctx(&analyzer) {
-                        let analysis = analyzer.bounds_for_all(&file_mapping, ctx, config);
-                        analysis.print_reports(&mut source_map, &analyzer);
+                    if let Some(ctx) = narrowed
+                        .get(&FunctionNode::from(func))
+                        .copied()
+                        .or_else(|| FunctionNode::from(func).maybe_body_ctx(&analyzer))
+                    {
+                        emit_func(
+                            &file_mapping,
+                            ctx,
+                            config,
+                            &mut findings,
+                            &mut source_map,
+                            &analyzer,
+                        );
                     }
                 }
-            } else if let Some(ctx) = FunctionNode::from(func).maybe_body_ctx(&analyzer) {
-                let analysis = analyzer.bounds_for_all(&file_mapping, ctx, config);
-                analysis.print_reports(&mut source_map, &analyzer);
+            } else if let Some(ctx) = narrowed
+                .get(&FunctionNode::from(func))
+                .copied()
+                .or_else(|| FunctionNode::from(func).maybe_body_ctx(&analyzer))
+            {
+                emit_func(
+                    &file_mapping,
+                    ctx,
+                    config,
+                    &mut findings,
+                    &mut source_map,
+                    &analyzer,
+                );
             }
         }
     } else {
@@ -175,14 +301,32 @@ fn main() {
                 for func in funcs.into_iter() {
                     if !args.funcs.is_empty() {
                         if args.funcs.contains(&func.name(&analyzer)) {
-                            let ctx = func.body_ctx(&analyzer);
-                            let analysis = analyzer.bounds_for_all(&file_mapping, ctx, config);
-                            analysis.print_reports(&mut source_map, &analyzer);
+                            let ctx = narrowed
+                                .get(&func)
+                                .copied()
+                                .unwrap_or_else(|| func.body_ctx(&analyzer));
+                            emit_func(
+                                &file_mapping,
+                                ctx,
+                                config,
+                                &mut findings,
+                                &mut source_map,
+                                &analyzer,
+                            );
                         }
                     } else {
-                        let ctx = func.body_ctx(&analyzer);
-                        let analysis = analyzer.bounds_for_all(&file_mapping, ctx, config);
-                        analysis.print_reports(&mut source_map, &analyzer);
+                        let ctx = narrowed
+                            .get(&func)
+                            .copied()
+                            .unwrap_or_else(|| func.body_ctx(&analyzer));
+                        emit_func(
+                            &file_mapping,
+                            ctx,
+                            config,
+                            &mut findings,
+                            &mut source_map,
+                            &analyzer,
+                        );
                     }
                 }
             });
@@ -190,16 +334,17 @@ fn main() {
 
     args.access_query.iter().for_each(|query| {
         let split: Vec<&str> = query.split('.').collect();
-        analyzer
-            .access_query(
-                entry,
-                &file_mapping,
-                config,
-                split[0].to_string(),
-                split[1].to_string(),
-            )
-            .print_reports(&mut source_map, &analyzer);
-        println!();
+        let report = analyzer.access_query(
+            entry,
+            &file_mapping,
+            config,
+            split[0].to_string(),
+            split[1].to_string(),
+        );
+        emit(&report, config, &mut findings, &mut source_map, &analyzer);
+        if config.output == OutputFormat::Text {
+            println!();
+        }
     });
 
     args.write_query.iter().for_each(|query| {
@@ -218,11 +363,26 @@ fn main() {
                 exclusions: vec![],
             },
         ) {
-            report.print_reports(&mut source_map, &analyzer);
+            emit(&report, config, &mut findings, &mut source_map, &analyzer);
+        }
+        if config.output == OutputFormat::Text {
+            println!();
         }
-        println!();
     });
 
+    if args.diagnostics {
+        let report = analyzer.diagnostics_report(&file_mapping, config);
+        emit(&report, config, &mut findings, &mut source_map, &analyzer);
+    }
+
+    if config.output == OutputFormat::Json {
+        println!("{}", findings_json(&findings));
+    }
+
+    if let Some(metrics) = analyzer.metrics() {
+        eprintln!("{metrics:#?}");
+    }
+
     // println!("parse time: {:?}ms", parse_time);
     // println!("analyzer time: {:?}ms", t1.elapsed().as_millis());
     // println!("total time: {:?}ms", t0.elapsed().as_millis());
@@ -5,8 +5,10 @@ use crate::MsgNode;
 
 use crate::context::ContextVarNode;
 use crate::range::range_string::ToRangeString;
-use crate::{Builtin, Edge, Function, FunctionParam, FunctionReturn, Node, NodeIdx};
+use crate::{Builtin, Concrete, Edge, Function, FunctionParam, FunctionReturn, Node, NodeIdx};
 use petgraph::visit::EdgeRef;
+use serde::Serialize;
+use solang_parser::pt::{Identifier, Loc, Type};
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
 
@@ -14,10 +16,60 @@ use petgraph::dot::Dot;
 use petgraph::{graph::*, Directed, Direction};
 use std::collections::HashMap;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// Something noticed while building the graph, i.e. a suspicious construct or one that could
+/// not be fully analyzed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub loc: Loc,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn new(loc: Loc, severity: Severity, message: impl Into<String>) -> Self {
+        Self { loc, severity, message: message.into() }
+    }
+}
+
 pub trait AnalyzerLike: GraphLike {
     type Expr;
     fn builtin_fns(&self) -> &HashMap<String, Function>;
     fn builtin_fn_inputs(&self) -> &HashMap<String, (Vec<FunctionParam>, Vec<FunctionReturn>)>;
+    fn builtin_fn_nodes(&self) -> &HashMap<String, NodeIdx>;
+    fn builtin_fn_nodes_mut(&mut self) -> &mut HashMap<String, NodeIdx>;
+    /// Gets the node of a builtin function like `keccak256`, adding it along with its parameters
+    /// and returns the first time it is used. Returns `None` if there is no builtin function
+    /// with that name
+    fn builtin_fn_or_maybe_add(&mut self, name: &str) -> Option<NodeIdx> {
+        if let Some(idx) = self.builtin_fn_nodes().get(name) {
+            return Some(*idx);
+        }
+        let func = self.builtin_fns().get(name)?.clone();
+        let (inputs, outputs) = self
+            .builtin_fn_inputs()
+            .get(name)
+            .expect("builtin func but no inputs")
+            .clone();
+        let func_node = self.add_node(Node::Function(func));
+        inputs.into_iter().for_each(|input| {
+            let input_node = self.add_node(input);
+            self.add_edge(input_node, func_node, Edge::FunctionParam);
+        });
+        outputs.into_iter().for_each(|output| {
+            let output_node = self.add_node(output);
+            self.add_edge(output_node, func_node, Edge::FunctionReturn);
+        });
+        self.builtin_fn_nodes_mut().insert(name.to_string(), func_node);
+        Some(func_node)
+    }
     fn builtins(&self) -> &HashMap<Builtin, NodeIdx>;
     fn builtins_mut(&mut self) -> &mut HashMap<Builtin, NodeIdx>;
     fn builtin_or_add(&mut self, builtin: Builtin) -> NodeIdx {
@@ -29,11 +81,96 @@ pub trait AnalyzerLike: GraphLike {
             idx
         }
     }
+    /// Stands in an unresolved node for a type that has no builtin, i.e. a mapping whose key
+    /// or value type is unknown, and records that it was skipped
+    fn unresolved_ty(&mut self, loc: Loc, ty: &Type) -> NodeIdx {
+        let name = match ty {
+            Type::Mapping(..) => "mapping",
+            Type::Function { .. } => "function",
+            _ => "type",
+        };
+        self.add_unsupported(loc, format!("Unsupported {name} type, it is left unresolved"));
+        self.add_node(Node::Unresolved(Identifier { loc, name: name.to_string() }))
+    }
+    fn concretes(&self) -> &HashMap<Concrete, NodeIdx>;
+    fn concretes_mut(&mut self) -> &mut HashMap<Concrete, NodeIdx>;
+    /// Gets the node of a constant, adding it if this is the first time it is used. Concrete
+    /// nodes are never mutated, so every use of the same value can share one
+    fn concrete_or_add(&mut self, concrete: Concrete) -> NodeIdx {
+        if let Some(idx) = self.concretes().get(&concrete) {
+            *idx
+        } else {
+            let idx = self.add_node(Node::Concrete(concrete.clone()));
+            self.concretes_mut().insert(concrete, idx);
+            idx
+        }
+    }
     fn user_types(&self) -> &HashMap<String, NodeIdx>;
     fn user_types_mut(&mut self) -> &mut HashMap<String, NodeIdx>;
     fn parse_expr(&mut self, expr: &Self::Expr) -> NodeIdx;
     fn msg(&mut self) -> MsgNode;
     fn block(&mut self) -> BlockNode;
+    fn security_mode(&self) -> bool;
+    /// How many iterations of a loop to execute one by one before widening
+    fn max_unroll(&self) -> usize;
+    /// Whether inline assembly widens every variable it mentions rather than just those it assigns
+    fn assembly_unknown(&self) -> bool;
+    /// Called for every loop iteration that is executed one by one, for profiling
+    fn record_unrolled_iteration(&mut self) {}
+    fn add_diagnostic(&mut self, diagnostic: Diagnostic);
+    fn add_warning(&mut self, loc: Loc, msg: String) {
+        self.add_diagnostic(Diagnostic::new(loc, Severity::Warning, msg))
+    }
+    /// Records a construct that was skipped or only partly analyzed
+    fn add_unsupported(&mut self, loc: Loc, msg: String) {
+        self.add_diagnostic(Diagnostic::new(loc, Severity::Info, msg))
+    }
+    fn diagnostics(&self) -> &[Diagnostic];
+    /// The `pragma solidity` version requirement of a source unit, if it declared one
+    fn solidity_version(&self, source: NodeIdx) -> Option<&str>;
+    /// Whether arithmetic in a source unit reverts on overflow, which solidity does from 0.8.0
+    /// on. Without a `pragma solidity` the compiler is unknown and arithmetic is assumed to be
+    /// checked, so only a pragma that allows an older compiler makes it wrap
+    fn checked_arithmetic(&self, source: NodeIdx) -> bool {
+        self.solidity_version(source).is_none_or(requires_checked_arithmetic)
+    }
+    /// Functions attached to a type via `using ... for`, including those attached to every type,
+    /// that are in effect in the body of `func`
+    fn using_fns(&self, func: crate::nodes::FunctionNode, ty: NodeIdx) -> Vec<crate::nodes::FunctionNode>;
+}
+
+/// Whether every compiler that a version requirement like `^0.8.0 <0.9.0` allows is 0.8.0 or
+/// newer. One lower bound of at least 0.8.0 is enough, but it has to hold for each `||` alternative
+pub fn requires_checked_arithmetic(requirement: &str) -> bool {
+    requirement.split("||").all(|alternative| {
+        // `>= 0.8.0` may be written with a space after the operator
+        let mut tokens = alternative.split_whitespace().peekable();
+        let mut comparators = vec![];
+        while let Some(token) = tokens.next() {
+            if token.chars().all(|c| "^~<>=".contains(c)) {
+                comparators.push(format!("{token}{}", tokens.next().unwrap_or_default()));
+            } else if token == "-" {
+                // the upper end of a `0.7.0 - 0.8.0` range is not a lower bound
+                tokens.next();
+            } else {
+                comparators.push(token.to_string());
+            }
+        }
+        comparators.iter().any(|comparator| {
+            if comparator.starts_with('<') {
+                return false;
+            }
+            let mut parts = comparator
+                .trim_start_matches(|c| "^~>=v".contains(c))
+                .split('.')
+                .map(|part| part.parse::<u64>().ok());
+            match (parts.next().flatten(), parts.next().flatten()) {
+                (Some(major), Some(minor)) => (major, minor) >= (0, 8),
+                (Some(major), None) => major >= 1,
+                _ => false,
+            }
+        })
+    })
 }
 
 struct G<'a> {
@@ -58,15 +195,21 @@ pub trait GraphLike {
     }
 
     fn node(&self, node: impl Into<NodeIdx>) -> &Node {
-        self.graph()
-            .node_weight(node.into())
-            .expect("Index not in graph")
+        self.try_node(node).expect("Index not in graph")
     }
 
     fn node_mut(&mut self, node: impl Into<NodeIdx>) -> &mut Node {
-        self.graph_mut()
-            .node_weight_mut(node.into())
-            .expect("Index not in graph")
+        self.try_node_mut(node).expect("Index not in graph")
+    }
+
+    /// Gets a node, returning `None` if the index is not in the graph
+    fn try_node(&self, node: impl Into<NodeIdx>) -> Option<&Node> {
+        self.graph().node_weight(node.into())
+    }
+
+    /// Gets a node mutably, returning `None` if the index is not in the graph
+    fn try_node_mut(&mut self, node: impl Into<NodeIdx>) -> Option<&mut Node> {
+        self.graph_mut().node_weight_mut(node.into())
     }
 
     fn add_edge(
@@ -79,6 +222,43 @@ pub trait GraphLike {
             .add_edge(from_node.into(), to_node.into(), edge.into());
     }
 
+    /// The kinds of all edges connecting `a` and `b`, whichever way they point
+    fn edges_between(&self, a: impl Into<NodeIdx>, b: impl Into<NodeIdx>) -> Vec<Edge> {
+        let (a, b) = (a.into(), b.into());
+        let backwards = if a == b { None } else { Some(self.graph().edges_connecting(b, a)) };
+        self.graph()
+            .edges_connecting(a, b)
+            .chain(backwards.into_iter().flatten())
+            .map(|edge| *edge.weight())
+            .collect()
+    }
+
+    /// The kind of the edge going from `from_node` to `to_node`, if there is one. If there are
+    /// several, the one added last is returned
+    fn edge_directed(
+        &self,
+        from_node: impl Into<NodeIdx>,
+        to_node: impl Into<NodeIdx>,
+    ) -> Option<Edge> {
+        self.graph()
+            .edges_connecting(from_node.into(), to_node.into())
+            .map(|edge| *edge.weight())
+            .next()
+    }
+
+    /// Every node in the graph along with its index, in the order they were added
+    fn nodes(&self) -> impl Iterator<Item = (NodeIdx, &Node)> {
+        let graph = self.graph();
+        graph.node_indices().map(move |idx| (idx, &graph[idx]))
+    }
+
+    /// Every edge in the graph as `(from, to, kind)`, in the order they were added
+    fn edges(&self) -> impl Iterator<Item = (NodeIdx, NodeIdx, &Edge)> {
+        self.graph()
+            .edge_references()
+            .map(|edge| (edge.source(), edge.target(), edge.weight()))
+    }
+
     fn dot_str(&self) -> String
     where
         Self: std::marker::Sized,
@@ -162,7 +342,7 @@ pub trait GraphLike {
         let new_graph = self.graph().filter_map(
             |_idx, node| match node {
                 Node::ContextVar(cvar) => {
-                    if !cvar.is_symbolic || cvar.tmp_of.is_some() {
+                    if !cvar.is_symbolic() || cvar.is_tmp() {
                         None
                     } else {
                         Some(node.clone())
@@ -242,7 +422,7 @@ pub trait GraphLike {
                 }
                 Node::ContextVar(cvar) => {
                     if let Some(ctx) = ContextVarNode::from(idx).maybe_ctx(self) {
-                        if ctx.underlying(self).path == fork_name && !cvar.is_symbolic {
+                        if ctx.underlying(self).path == fork_name && !cvar.is_symbolic() {
                             Some(node.clone())
                         } else {
                             None
@@ -311,6 +491,110 @@ pub trait GraphLike {
         dot_str.push(raw_end_str.to_string());
         dot_str.join("\n")
     }
+
+    /// Like [`GraphLike::dot_str`], but with short labels, nodes colored by their kind and edges
+    /// labeled with their full variant name
+    fn dot_str_styled(&self) -> String
+    where
+        Self: AnalyzerLike,
+        Self: Sized,
+    {
+        let mut dot_str = Vec::new();
+        let raw_start_str = r##"digraph G {
+    node [shape=box, style="filled, rounded", color="#565f89", fontcolor="#d5daf0", fontname="Helvetica", fillcolor="#24283b"];
+    edge [color="#414868", fontcolor="#c0caf5", fontname="Helvetica"];
+    bgcolor="#1a1b26";"##;
+        dot_str.push(raw_start_str.to_string());
+        let nodes_and_edges_str = format!(
+            "{:?}",
+            Dot::with_attr_getters(
+                self.graph(),
+                &[
+                    petgraph::dot::Config::GraphContentOnly,
+                    petgraph::dot::Config::NodeNoLabel,
+                    petgraph::dot::Config::EdgeNoLabel
+                ],
+                &|_graph, edge_ref| format!("label = \"{:?}\"", edge_ref.weight()),
+                &|_graph, (idx, node_ref)| {
+                    format!(
+                        "label = \"{}\", color = \"{}\"",
+                        self.styled_label(idx).replace('\"', "\'"),
+                        node_ref.dot_str_color()
+                    )
+                }
+            )
+        );
+        dot_str.push(nodes_and_edges_str);
+        let raw_end_str = r#"}"#;
+        dot_str.push(raw_end_str.to_string());
+        dot_str.join("\n")
+    }
+
+    /// The short label [`GraphLike::dot_str_styled`] shows for the node `idx`
+    fn styled_label(&self, idx: NodeIdx) -> String
+    where
+        Self: AnalyzerLike,
+        Self: Sized,
+    {
+        match self.node(idx) {
+            Node::Context(ctx) => ctx.path.clone(),
+            Node::ContextVar(cvar) => {
+                if let Some(r) = cvar.ty.range(self) {
+                    format!("{}: {}", cvar.display_name, r.as_dot_str(self))
+                } else {
+                    cvar.display_name.clone()
+                }
+            }
+            Node::Concrete(c) => c.as_human_string(),
+            _ => as_dot_str(idx, self),
+        }
+    }
+
+    /// The graph as GraphML, for tools like yEd or Gephi. Nodes carry their kind, the label
+    /// [`GraphLike::dot_str_styled`] shows and its color, and edges carry their kind
+    fn graphml_str(&self) -> String
+    where
+        Self: AnalyzerLike,
+        Self: Sized,
+    {
+        let mut graphml = vec![
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_string(),
+            r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#.to_string(),
+            r#"  <key id="kind" for="node" attr.name="kind" attr.type="string"/>"#.to_string(),
+            r#"  <key id="label" for="node" attr.name="label" attr.type="string"/>"#.to_string(),
+            r#"  <key id="color" for="node" attr.name="color" attr.type="string"/>"#.to_string(),
+            r#"  <key id="edge" for="edge" attr.name="edge" attr.type="string"/>"#.to_string(),
+            r#"  <graph id="G" edgedefault="directed">"#.to_string(),
+        ];
+        self.nodes().for_each(|(idx, node)| {
+            graphml.push(format!(
+                r#"    <node id="n{}"><data key="kind">{}</data><data key="label">{}</data><data key="color">{}</data></node>"#,
+                idx.index(),
+                node.kind_name(),
+                xml_escape(&self.styled_label(idx)),
+                node.dot_str_color()
+            ));
+        });
+        self.edges().enumerate().for_each(|(i, (from, to, edge))| {
+            graphml.push(format!(
+                r#"    <edge id="e{i}" source="n{}" target="n{}"><data key="edge">{}</data></edge>"#,
+                from.index(),
+                to.index(),
+                xml_escape(&format!("{edge:?}"))
+            ));
+        });
+        graphml.push("  </graph>".to_string());
+        graphml.push("</graphml>".to_string());
+        graphml.join("\n")
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
 }
 
 impl<T> Search for T where T: GraphLike {}
@@ -333,24 +617,30 @@ pub trait Search: GraphLike {
     ///
     /// This function would build a set { b, d } if we are looking for `my_edge` and start at a.
     fn search_children(&self, start: NodeIdx, edge_ty: &Edge) -> BTreeSet<NodeIdx> {
-        let edges = self.graph().edges_directed(start, Direction::Incoming);
-        let mut this_children: BTreeSet<NodeIdx> = edges
-            .clone()
-            .filter_map(|edge| {
+        self.search_children_depth(start, edge_ty, usize::MAX)
+    }
+
+    /// Like [`Search::search_children`], but only looks at most `max_depth` edges away from
+    /// `start`, so a depth of 1 only finds its direct children. Each node is visited once, so
+    /// cycles are fine
+    fn search_children_depth(&self, start: NodeIdx, edge_ty: &Edge, max_depth: usize) -> BTreeSet<NodeIdx> {
+        let mut children = BTreeSet::default();
+        let mut seen = BTreeSet::from([start]);
+        let mut queue = std::collections::VecDeque::from([(start, 0)]);
+        while let Some((node, depth)) = queue.pop_front() {
+            if depth == max_depth {
+                continue;
+            }
+            for edge in self.graph().edges_directed(node, Direction::Incoming) {
                 if edge.weight() == edge_ty {
-                    Some(edge.source())
-                } else {
-                    None
+                    children.insert(edge.source());
                 }
-            })
-            .collect();
-
-        this_children.extend(
-            edges
-                .flat_map(|edge| self.search_children(edge.source(), edge_ty))
-                .collect::<BTreeSet<NodeIdx>>(),
-        );
-        this_children
+                if seen.insert(edge.source()) {
+                    queue.push_back((edge.source(), depth + 1));
+                }
+            }
+        }
+        children
     }
 
     /// Finds any child nodes that have some edge `edge_ty` incoming. Builds up a mapping of these
@@ -392,6 +682,46 @@ pub trait Search: GraphLike {
             Some(map)
         }
     }
+
+    /// Visits each node reachable from `start` over edges whose weight passes `edge_filter`,
+    /// breadth first and at most once. `Direction::Incoming` walks towards children (the
+    /// sources of edges into a node), `Direction::Outgoing` towards parents. `start` itself is
+    /// not visited
+    fn walk(
+        &self,
+        start: NodeIdx,
+        direction: Direction,
+        edge_filter: impl Fn(&Edge) -> bool,
+        mut visitor: impl FnMut(NodeIdx, &Node),
+    ) {
+        let mut seen = BTreeSet::from([start]);
+        let mut queue = std::collections::VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            for edge in self.graph().edges_directed(node, direction) {
+                if !edge_filter(edge.weight()) {
+                    continue;
+                }
+                let next = match direction {
+                    Direction::Incoming => edge.source(),
+                    Direction::Outgoing => edge.target(),
+                };
+                if seen.insert(next) {
+                    visitor(next, self.node(next));
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+
+    /// The nodes with an `edge` directly into `node`, i.e. the functions of a contract for
+    /// `Edge::Func`
+    fn children_of_kind(&self, node: impl Into<NodeIdx>, edge: Edge) -> Vec<NodeIdx> {
+        self.graph()
+            .edges_directed(node.into(), Direction::Incoming)
+            .filter(|e| *e.weight() == edge)
+            .map(|e| e.source())
+            .collect()
+    }
 }
 
 pub trait AsDotStr {
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use crate::FunctionParamNode;
 use crate::ContractNode;
@@ -5,7 +6,8 @@ use crate::GraphLike;
 use petgraph::{Direction, visit::EdgeRef};
 use crate::{Node, NodeIdx, Edge};
 use crate::analyzer::{AnalyzerLike, Search};
-use crate::nodes::FunctionNode;
+use crate::nodes::{Concrete, EventNode, FunctionNode};
+use crate::range::elem::RangeOp;
 use solang_parser::pt::Loc;
 use std::collections::HashMap;
 
@@ -13,7 +15,7 @@ use std::collections::HashMap;
 mod var;
 pub use var::*;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
 pub enum ContextEdge {
     // Control flow
     Context,
@@ -30,6 +32,7 @@ pub enum ContextEdge {
     Index,
     IndexAccess,
     FuncAccess,
+    LibraryAccess,
 
     // Variable incoming edges
     Assign,
@@ -40,11 +43,21 @@ pub enum ContextEdge {
     // Control flow
     Return,
 
+    // Call options, from the `value` or `gas` given to a call to what the call returns
+    CallValue,
+    CallGas,
+
+    // Events, from the context to the event it emits
+    Emit,
+
+    // Storage references, from a local `storage` variable to the variable it points at
+    StorageAlias,
+
     // Range analysis
     Range,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ModifierState {
     pub entry_call: bool,
     pub num: usize,
@@ -54,6 +67,9 @@ pub struct ModifierState {
     pub inputs: Vec<ContextVarNode>,
     pub params: Vec<FunctionParamNode>,
     pub renamed_inputs: BTreeMap<ContextVarNode, ContextVarNode>,
+    /// What the modified function returned when the modifier resumed it at `_`, which is what
+    /// a call to the function evaluates to
+    pub returned: Vec<ContextVarNode>,
 }
 
 impl ModifierState {
@@ -67,12 +83,147 @@ impl ModifierState {
         params: Vec<FunctionParamNode>
     ) -> Self {
         Self {
-            entry_call, num, loc, parent_fn, parent_ctx, inputs, params, renamed_inputs: Default::default(),
+            entry_call, num, loc, parent_fn, parent_ctx, inputs, params, renamed_inputs: Default::default(), returned: vec![],
         }
     }
+
+    /// The nodes the modifier call refers to
+    pub fn referenced_idxs(&self) -> Vec<NodeIdx> {
+        let mut idxs: Vec<NodeIdx> = vec![self.parent_fn.into(), self.parent_ctx.into()];
+        idxs.extend(self.inputs.iter().map(|input| NodeIdx::from(*input)));
+        idxs.extend(self.params.iter().map(|param| NodeIdx::from(*param)));
+        idxs.extend(self.renamed_inputs.iter().flat_map(|(from, to)| [NodeIdx::from(*from), NodeIdx::from(*to)]));
+        idxs.extend(self.returned.iter().map(|ret| NodeIdx::from(*ret)));
+        idxs
+    }
+
+    /// Updates the nodes the modifier call refers to after the graph has been compacted
+    pub fn update_idxs(&mut self, mapping: &BTreeMap<NodeIdx, NodeIdx>) {
+        self.parent_fn = remap_idx(mapping, self.parent_fn);
+        self.parent_ctx = remap_idx(mapping, self.parent_ctx);
+        self.inputs.iter_mut().for_each(|input| *input = remap_idx(mapping, *input));
+        self.params.iter_mut().for_each(|param| *param = remap_idx(mapping, *param));
+        self.renamed_inputs = std::mem::take(&mut self.renamed_inputs)
+            .into_iter()
+            .map(|(from, to)| (remap_idx(mapping, from), remap_idx(mapping, to)))
+            .collect();
+        self.returned.iter_mut().for_each(|ret| *ret = remap_idx(mapping, *ret));
+    }
+}
+
+/// Where `node` ended up after the graph was compacted
+pub(crate) fn remap_idx<N: Copy + Into<NodeIdx> + From<NodeIdx>>(mapping: &BTreeMap<NodeIdx, NodeIdx>, node: N) -> N {
+    let idx = node.into();
+    (*mapping.get(&idx).unwrap_or(&idx)).into()
+}
+
+/// The reason a context stopped executing
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Termination {
+    /// The context hit a `return` statement
+    Return(Loc),
+    /// The context reverted, via `revert` or a failed `require`
+    Revert(Loc),
+    /// The context reverted with a `Panic` error, via a failed `assert` or arithmetic that
+    /// always fails
+    Panic(Loc),
+    /// The context called `selfdestruct`
+    SelfDestruct(Loc),
+}
+
+/// Why a context was killed
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum KilledKind {
+    /// A `revert`, or a `require` whose condition can't hold
+    Revert,
+    /// An `assert` whose condition can't hold, or arithmetic that always overflows or divides
+    /// by zero
+    Panic,
+}
+
+impl KilledKind {
+    /// How a context that was killed this way at `loc` terminated
+    pub fn termination(&self, loc: Loc) -> Termination {
+        match self {
+            KilledKind::Revert => Termination::Revert(loc),
+            KilledKind::Panic => Termination::Panic(loc),
+        }
+    }
+
+    /// The message to report where the kill happened
+    pub fn analysis_str(&self) -> &'static str {
+        match self {
+            KilledKind::Revert => "Execution guaranteed to revert here!",
+            KilledKind::Panic => "Execution guaranteed to panic here!",
+        }
+    }
+}
+
+/// How a context left the current iteration of the loop it is in
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum LoopExit {
+    /// The context hit a `break` statement, and skips the rest of the loop
+    Break(Loc),
+    /// The context hit a `continue` statement, and skips the rest of the iteration
+    Continue(Loc),
 }
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+/// Arithmetic whose result may not fit in its type
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Overflow {
+    pub loc: Loc,
+    pub op: RangeOp,
+    /// Whether the result may fall below the type's minimum instead of exceeding its maximum
+    pub underflow: bool,
+    /// Whether the arithmetic is in an `unchecked` block, so the result wraps instead of reverting
+    pub unchecked: bool,
+    /// Values of the left and right hand side, within their ranges, whose result doesn't fit
+    pub witness: (Concrete, Concrete),
+    /// Whether either side depends on a tainted input, only set in security mode
+    pub tainted: bool,
+}
+
+/// An array index that may be past the end of a fixed length array
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+pub struct OutOfBounds {
+    pub loc: Loc,
+    /// A value within the index's range that is past the end
+    pub witness: Concrete,
+    /// Whether the index depends on a tainted input, only set in security mode
+    pub tainted: bool,
+}
+
+/// Ether sent out of the contract by `transfer`, `send` or a call with a `value` option
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+pub struct ValueTransfer {
+    pub loc: Loc,
+    /// How the value is sent, i.e. `transfer` or `call`
+    pub via: String,
+    /// The expression of the value sent
+    pub value: String,
+    /// The bounds of the value at the point it is sent, if they evaluate to a concrete value
+    pub min: Option<Concrete>,
+    pub max: Option<Concrete>,
+}
+
+/// A local variable or parameter named the same as a state variable, hiding it in the function
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+pub struct Shadowing {
+    pub loc: Loc,
+    pub name: String,
+    /// Where the hidden state variable is declared
+    pub state_var_loc: Loc,
+}
+
+/// A branch of an `if` statement, as reached by a context
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+pub struct Branch {
+    pub loc: Loc,
+    /// Whether the condition for taking the branch could hold
+    pub reachable: bool,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Context {
     /// The function associated with this context
     pub parent_fn: FunctionNode,
@@ -86,7 +237,9 @@ pub struct Context {
     /// A string that represents the path taken from the root context (i.e. `fn_entry.fork.1`)
     pub path: String,
     /// Denotes whether this context was killed by an unsatisfiable require, assert, etc. statement
-    pub killed: Option<Loc>,
+    pub killed: Option<(Loc, KilledKind)>,
+    /// Denotes whether this context was ended by a `selfdestruct` call
+    pub selfdestructed: Option<Loc>,
     /// Denotes whether this context is a fork of another context
     pub is_fork: bool,
     /// Denotes whether this context is the result of a internal function call, and points to the FunctionNode
@@ -105,6 +258,26 @@ pub struct Context {
     pub ret: Vec<(Loc, ContextVarNode)>,
     /// Range adjustments to occur after the statement finishes. Useful for post in/decrement 
     pub post_statement_range_adjs: Vec<(ContextVarNode, Loc, bool)>,
+    /// Whether the statements currently being parsed are inside of an `unchecked` block
+    pub unchecked: bool,
+    /// Arithmetic in this context that may overflow or underflow
+    pub overflows: Vec<Overflow>,
+    /// Divisions and modulos in this context whose divisor may be zero, and whether the divisor
+    /// depends on a tainted input
+    pub zero_divisors: Vec<(Loc, bool)>,
+    /// Array indexes in this context that may be past the end of the array
+    pub out_of_bounds: Vec<OutOfBounds>,
+    /// Locals and parameters in this context that hide a state variable
+    pub shadowing: Vec<Shadowing>,
+    /// Conversions to an enum in this context whose value may not be one of its variants, and
+    /// whether the value depends on a tainted input
+    pub invalid_enum_casts: Vec<(Loc, bool)>,
+    /// Ether sent out of the contract in this context
+    pub value_transfers: Vec<ValueTransfer>,
+    /// Set while the context sits out the rest of a loop body due to a `break` or `continue`
+    pub loop_exit: Option<LoopExit>,
+    /// Branches of the `if` statements this context reached
+    pub branches: Vec<Branch>,
 }
 
 impl Context {
@@ -116,6 +289,7 @@ impl Context {
             path: fn_name,
             tmp_var_ctr: 0,
             killed: None,
+            selfdestructed: None,
             ctx_deps: Default::default(),
             is_fork: false,
             fn_call: None,
@@ -126,6 +300,15 @@ impl Context {
             loc,
             modifier_state: None,
             post_statement_range_adjs: vec![],
+            unchecked: false,
+            overflows: vec![],
+            zero_divisors: vec![],
+            out_of_bounds: vec![],
+            shadowing: vec![],
+            invalid_enum_casts: vec![],
+            value_transfers: vec![],
+            loop_exit: None,
+            branches: vec![],
         }
     }
 
@@ -139,6 +322,8 @@ impl Context {
         analyzer: &impl AnalyzerLike,
         modifier_state: Option<ModifierState>,
     ) -> Self {
+        // a called function has its own checked arithmetic regardless of the caller
+        let unchecked = fn_call.is_none() && parent_ctx.underlying(analyzer).unchecked;
         let (ext_fn_call, fn_call) = if let Some(fn_call) = fn_call {
             if fn_ext {
                 (Some(fn_call), None)
@@ -166,6 +351,7 @@ impl Context {
             ext_fn_call,
             ctx_deps: parent_ctx.underlying(analyzer).ctx_deps.clone(),
             killed: None,
+            selfdestructed: None,
             forks: vec![],
             children: vec![],
             tmp_var_ctr: parent_ctx.underlying(analyzer).tmp_var_ctr,
@@ -173,6 +359,15 @@ impl Context {
             loc,
             modifier_state,
             post_statement_range_adjs: vec![],
+            unchecked,
+            overflows: vec![],
+            zero_divisors: vec![],
+            out_of_bounds: vec![],
+            shadowing: vec![],
+            invalid_enum_casts: vec![],
+            value_transfers: vec![],
+            loop_exit: None,
+            branches: vec![],
         }
     }
 
@@ -189,24 +384,95 @@ impl Context {
     pub fn as_string(&mut self) -> String {
         "Context".to_string()
     }
+
+    /// The nodes this context refers to: its function, related contexts and the variables it
+    /// depends on or returns
+    pub fn referenced_idxs(&self) -> Vec<NodeIdx> {
+        let mut idxs: Vec<NodeIdx> = vec![self.parent_fn.into()];
+        idxs.extend(self.modifier_state.iter().flat_map(|state| state.referenced_idxs()));
+        idxs.extend(self.parent_ctx.map(NodeIdx::from));
+        idxs.extend(self.ctx_deps.values().map(|dep| NodeIdx::from(*dep)));
+        idxs.extend(self.fn_call.map(NodeIdx::from));
+        idxs.extend(self.ext_fn_call.map(NodeIdx::from));
+        idxs.extend(self.forks.iter().chain(&self.children).map(|ctx| NodeIdx::from(*ctx)));
+        idxs.extend(self.ret.iter().map(|(_, ret)| NodeIdx::from(*ret)));
+        idxs.extend(self.post_statement_range_adjs.iter().map(|(var, ..)| NodeIdx::from(*var)));
+        idxs
+    }
+
+    /// Updates the nodes this context refers to after the graph has been compacted
+    pub fn update_idxs(&mut self, mapping: &BTreeMap<NodeIdx, NodeIdx>) {
+        self.parent_fn = remap_idx(mapping, self.parent_fn);
+        if let Some(state) = &mut self.modifier_state {
+            state.update_idxs(mapping);
+        }
+        self.parent_ctx = self.parent_ctx.map(|ctx| remap_idx(mapping, ctx));
+        self.ctx_deps.values_mut().for_each(|dep| *dep = remap_idx(mapping, *dep));
+        self.fn_call = self.fn_call.map(|func| remap_idx(mapping, func));
+        self.ext_fn_call = self.ext_fn_call.map(|func| remap_idx(mapping, func));
+        self.forks.iter_mut().chain(self.children.iter_mut()).for_each(|ctx| *ctx = remap_idx(mapping, *ctx));
+        self.ret.iter_mut().for_each(|(_, ret)| *ret = remap_idx(mapping, *ret));
+        self.post_statement_range_adjs.iter_mut().for_each(|(var, ..)| *var = remap_idx(mapping, *var));
+    }
 }
 
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 /// A wrapper of a node index that corresponds to a [`Context`]
 pub struct ContextNode(pub usize);
+
+node_newtype!(ContextNode, Context, Context);
+
 impl ContextNode {
     /// The path of the underlying context
     pub fn path(&self, analyzer: &impl GraphLike) -> String {
         self.underlying(analyzer).path.clone()
     }
 
-    /// *All* subcontexts (including subcontexts of subcontexts, recursively)
+    /// *All* subcontexts (including subcontexts of subcontexts, recursively), nearest first
     pub fn subcontexts(&self, analyzer: &(impl GraphLike + Search)) -> Vec<ContextNode> {
-        analyzer
-            .search_children(self.0.into(), &Edge::Context(ContextEdge::Subcontext))
-            .into_iter()
-            .map(ContextNode::from)
-            .collect()
+        let mut subctxs = vec![];
+        // forks and function calls hang off their context through an intermediate node
+        analyzer.walk(
+            self.0.into(),
+            Direction::Incoming,
+            Self::is_subctx_edge,
+            |idx, node| {
+                if matches!(node, Node::Context(_)) {
+                    subctxs.push(ContextNode::from(idx));
+                }
+            },
+        );
+        subctxs
+    }
+
+    /// The context this one was forked or called from, if it is not a function's root context
+    pub fn parent(&self, analyzer: &impl GraphLike) -> Option<ContextNode> {
+        let mut node: NodeIdx = self.0.into();
+        loop {
+            node = analyzer
+                .graph()
+                .edges_directed(node, Direction::Outgoing)
+                .find(|edge| Self::is_subctx_edge(edge.weight()))?
+                .target();
+            if matches!(analyzer.node(node), Node::Context(_)) {
+                return Some(node.into());
+            }
+        }
+    }
+
+    fn is_subctx_edge(edge: &Edge) -> bool {
+        matches!(edge, Edge::Context(ContextEdge::Subcontext | ContextEdge::ContextFork))
+    }
+
+    /// The parent contexts of this one, from its direct parent up to the root context
+    pub fn parent_chain(&self, analyzer: &impl GraphLike) -> Vec<ContextNode> {
+        let mut parents = vec![];
+        let mut curr = *self;
+        while let Some(parent) = curr.parent(analyzer) {
+            parents.push(parent);
+            curr = parent;
+        }
+        parents
     }
 
     /// Gets the associated contract for the function for the context
@@ -214,6 +480,19 @@ impl ContextNode {
         self.associated_fn(analyzer).contract(analyzer).expect("No associated contract for context")
     }
 
+    /// Gets the function whose body the context is in. Unlike [`Self::associated_fn`], this is the
+    /// called function for the subcontexts of a call
+    pub fn executing_fn(&self, analyzer: &impl GraphLike) -> FunctionNode {
+        let underlying = self.underlying(analyzer);
+        if let Some(func) = underlying.fn_call.or(underlying.ext_fn_call) {
+            return func;
+        }
+        match underlying.parent_ctx {
+            Some(parent) => parent.executing_fn(analyzer),
+            None => underlying.parent_fn,
+        }
+    }
+
     /// Tries to get the associated function for the context
     pub fn maybe_associated_contract(&self, analyzer: &(impl GraphLike + Search)) -> Option<ContractNode> {
         self.associated_fn(analyzer).contract(analyzer)
@@ -223,14 +502,40 @@ impl ContextNode {
         analyzer.search_for_ancestor(self.0.into(), &Edge::Part)
     }
 
-    /// Gets all visible functions
+    /// Gets the events emitted directly in this context
+    pub fn emitted(&self, analyzer: &impl GraphLike) -> Vec<EventNode> {
+        analyzer.graph().edges_directed(self.0.into(), Direction::Outgoing)
+            .filter(|edge| *edge.weight() == Edge::Context(ContextEdge::Emit))
+            .map(|edge| EventNode::from(edge.target()))
+            .collect()
+    }
+
+    /// Gets all visible functions: those of the associated contract and the contracts it inherits
+    /// from, along with the free functions of the source unit. An overridden function is only
+    /// visible through its most derived definition
     pub fn visible_funcs(&self, analyzer: &(impl GraphLike + Search)) -> Vec<FunctionNode> {
         // TODO: filter privates
+        let mut funcs: Vec<FunctionNode> = vec![];
+        self.maybe_associated_contract(analyzer)
+            .map(|contract| contract.with_bases(analyzer))
+            .unwrap_or_default()
+            .iter()
+            .flat_map(|contract| contract.funcs(analyzer))
+            .for_each(|func| {
+                let name = func.name(analyzer);
+                if !funcs.iter().any(|seen| seen.name(analyzer) == name) {
+                    funcs.push(func);
+                }
+            });
         if let Some(source) = self.associated_source(analyzer) {
-            analyzer.search_children(source, &Edge::Func).into_iter().map(FunctionNode::from).collect::<Vec<_>>()
-        } else {
-            vec![]
+            funcs.extend(
+                analyzer.search_children(source, &Edge::Func)
+                    .into_iter()
+                    .map(FunctionNode::from)
+                    .filter(|func| func.contract(analyzer).is_none())
+            );
         }
+        funcs
     }
 
     /// Gets the associated function for the context
@@ -245,7 +550,7 @@ impl ContextNode {
             Some(fn_ctrt) => {
                 if let Some(self_ctrt) = self.associated_fn(analyzer).contract(analyzer) {
                     Some(self_ctrt) != Some(fn_ctrt)
-                    && !self_ctrt.underlying(analyzer).inherits.iter().any(|inherited| *inherited == fn_ctrt)    
+                    && !self_ctrt.with_bases(analyzer).contains(&fn_ctrt)
                 } else {
                     false
                 }
@@ -258,62 +563,22 @@ impl ContextNode {
         self.underlying(analyzer).parent_fn.name(analyzer)
     }
 
-    /// Gets a mutable reference to the underlying context in the graph
-    pub fn underlying_mut<'a>(&self, analyzer: &'a mut impl GraphLike) -> &'a mut Context {
-        match analyzer.node_mut(*self) {
-            Node::Context(c) => c,
-            e => panic!(
-                "Node type confusion: expected node to be Context but it was: {:?}",
-                e
-            ),
-        }
-    }
-
-    /// Gets an immutable reference to the underlying context in the graph
-    pub fn underlying<'a>(&self, analyzer: &'a impl GraphLike) -> &'a Context {
-        match analyzer.node(*self) {
-            Node::Context(c) => c,
-            e => panic!(
-                "Node type confusion: expected node to be Context but it was: {:?}",
-                e
-            ),
-        }
-    }
-
     /// Gets a variable by name in the context
     pub fn var_by_name(&self, analyzer: &impl GraphLike, name: &str) -> Option<ContextVarNode> {
-        analyzer
-            .search_children(self.0.into(), &Edge::Context(ContextEdge::Variable))
-            .into_iter()
-            .filter_map(|cvar_node| {
-                let cvar_node = ContextVarNode::from(cvar_node);
-                let cvar = cvar_node.underlying(analyzer);
-                if cvar.name == name {
-                    Some(cvar_node)
-                } else {
-                    None
-                }
-            })
-            .take(1)
-            .next()
+        // the context's own variables come first, so a callee's parameter never shadows
+        // a same named variable of its caller
+        [1, usize::MAX].into_iter().find_map(|depth| {
+            analyzer
+                .search_children_depth(self.0.into(), &Edge::Context(ContextEdge::Variable), depth)
+                .into_iter()
+                .map(ContextVarNode::from)
+                .find(|cvar_node| cvar_node.underlying(analyzer).name == name)
+        })
     }
 
     pub fn var_by_name_or_recurse(&self, analyzer: &impl GraphLike, name: &str) -> Option<ContextVarNode> {
-        if let Some(var) = analyzer
-            .search_children(self.0.into(), &Edge::Context(ContextEdge::Variable))
-            .into_iter()
-            .filter_map(|cvar_node| {
-                let cvar_node = ContextVarNode::from(cvar_node);
-                let cvar = cvar_node.underlying(analyzer);
-                if cvar.name == name {
-                    Some(cvar_node)
-                } else {
-                    None
-                }
-            })
-            .take(1)
-            .next() {
-                Some(var)
+        if let Some(var) = self.var_by_name(analyzer, name) {
+            Some(var)
         } else if let Some(parent) = self.underlying(analyzer).parent_ctx {
             parent.var_by_name_or_recurse(analyzer, name)
         } else {
@@ -321,6 +586,22 @@ impl ContextNode {
         }
     }
 
+    /// Gets the names of the non-temporary variables in scope for a context, including those
+    /// of its parent contexts, sorted and without duplicates
+    pub fn var_names(&self, analyzer: &impl AnalyzerLike) -> Vec<String> {
+        let mut names = self.local_vars(analyzer)
+            .into_iter()
+            .filter(|var| !var.is_tmp(analyzer))
+            .map(|var| var.name(analyzer))
+            .collect::<Vec<_>>();
+        if let Some(parent) = self.underlying(analyzer).parent_ctx {
+            names.extend(parent.var_names(analyzer));
+        }
+        names.sort();
+        names.dedup();
+        names
+    }
+
     /// Gets all variables associated with a context
     pub fn vars(&self, analyzer: &impl AnalyzerLike) -> Vec<ContextVarNode> {
         analyzer
@@ -373,7 +654,7 @@ impl ContextNode {
         context
             .forks
             .iter()
-            .filter(|fork_ctx| !fork_ctx.is_ended(analyzer))
+            .filter(|fork_ctx| !fork_ctx.is_ended(analyzer) && !fork_ctx.is_suspended(analyzer))
             .cloned()
             .collect()
     }
@@ -392,39 +673,117 @@ impl ContextNode {
 
     /// Kills the context by denoting it as killed. Recurses up the contexts and kills
     /// parent contexts if all subcontexts of that context are killed
-    pub fn kill(&self, analyzer: &mut impl AnalyzerLike, kill_loc: Loc) {
+    pub fn kill(&self, analyzer: &mut impl AnalyzerLike, kill_loc: Loc, kind: KilledKind) {
         let context = self.underlying_mut(analyzer);
-        context.killed = Some(kill_loc);
+        context.killed = Some((kill_loc, kind));
         if let Some(parent_ctx) = context.parent_ctx {
-            parent_ctx.end_if_all_forks_ended(analyzer, kill_loc);
+            parent_ctx.end_if_all_forks_ended(analyzer, kill_loc, kind.termination(kill_loc));
         }
     }
 
-    /// Kills if and only if all subcontexts are killed
-    pub fn end_if_all_forks_ended(&self, analyzer: &mut impl AnalyzerLike, kill_loc: Loc) {
-        let context = self.underlying(analyzer);
-        if context
-            .forks
-            .iter()
-            .all(|fork_ctx| fork_ctx.is_ended(analyzer))
-        {
-            let context = self.underlying_mut(analyzer);
-            context.killed = Some(kill_loc);
-            if let Some(parent_ctx) = context.parent_ctx {
-                parent_ctx.end_if_all_forks_ended(analyzer, kill_loc);
+    /// Changes why this context and its forks were killed, for the kills that happened within
+    /// `loc`. I.e. an `assert` refines bounds like a `require`, but fails with a panic
+    pub fn set_killed_kind_within(&self, analyzer: &mut impl AnalyzerLike, loc: Loc, kind: KilledKind) {
+        let within = |kill_loc: Loc| match (loc, kill_loc) {
+            (Loc::File(file, start, end), Loc::File(kill_file, kill_start, kill_end)) => {
+                file == kill_file && start <= kill_start && kill_end <= end
+            }
+            _ => false,
+        };
+        let forks = self.forks(analyzer);
+        if !forks.is_empty() {
+            forks.iter().for_each(|fork| fork.set_killed_kind_within(analyzer, loc, kind));
+            return;
+        }
+        let context = self.underlying_mut(analyzer);
+        if let Some((kill_loc, killed_kind)) = &mut context.killed {
+            if within(*kill_loc) && *killed_kind != kind {
+                *killed_kind = kind;
+                // the contexts this one ended are ended the new way too
+                let kill_loc = *kill_loc;
+                if let Some(parent_ctx) = context.parent_ctx {
+                    parent_ctx.end_if_all_forks_ended(analyzer, kill_loc, kind.termination(kill_loc));
+                }
             }
         }
     }
 
-    /// Gets parent list
-    pub fn parent_list(&self, analyzer: &impl AnalyzerLike) -> Vec<ContextNode> {
-        let context = self.underlying(analyzer);
-        let mut parents = vec![];
+    /// Ends the context due to a `selfdestruct` call. Like a kill, parent contexts are ended
+    /// if all of their subcontexts have ended
+    pub fn self_destruct(&self, analyzer: &mut impl AnalyzerLike, loc: Loc) {
+        let context = self.underlying_mut(analyzer);
+        context.selfdestructed = Some(loc);
         if let Some(parent_ctx) = context.parent_ctx {
-            parents.push(parent_ctx);
-            parents.extend(parent_ctx.parent_list(analyzer));
+            parent_ctx.end_if_all_forks_ended(analyzer, loc, Termination::SelfDestruct(loc));
         }
-        parents
+    }
+
+    /// Ends the context the way its subcontext did, because of `ended`, if it called it. If it
+    /// forked, it is ended once every fork is: it is killed if all of them were killed, or
+    /// selfdestructed if all of them selfdestructed. Forks that ended in different ways leave
+    /// it ended through its forks alone
+    pub fn end_if_all_forks_ended(&self, analyzer: &mut impl AnalyzerLike, loc: Loc, ended: Termination) {
+        let reason = if self.underlying(analyzer).forks.is_empty() {
+            Some(ended)
+        } else if self.is_ended(analyzer) {
+            self.termination_reason(analyzer)
+        } else {
+            return;
+        };
+        let context = self.underlying_mut(analyzer);
+        let reason = match reason {
+            Some(reason @ Termination::SelfDestruct(_)) => {
+                context.selfdestructed = Some(loc);
+                reason
+            }
+            Some(reason @ Termination::Revert(_)) => {
+                context.killed = Some((loc, KilledKind::Revert));
+                reason
+            }
+            Some(reason @ Termination::Panic(_)) => {
+                context.killed = Some((loc, KilledKind::Panic));
+                reason
+            }
+            // a path returns, so whatever called the context carries on
+            _ => return,
+        };
+        if let Some(parent_ctx) = context.parent_ctx {
+            parent_ctx.end_if_all_forks_ended(analyzer, loc, reason);
+        }
+    }
+
+    /// Marks the context as having left the current loop iteration
+    pub fn exit_loop(&self, analyzer: &mut impl AnalyzerLike, exit: LoopExit) {
+        self.underlying_mut(analyzer).loop_exit = Some(exit);
+    }
+
+    /// Returns whether the context, or every one of its forks that has not ended, left the current
+    /// loop iteration. Statements are not executed in suspended contexts
+    pub fn is_suspended(&self, analyzer: &impl AnalyzerLike) -> bool {
+        let context = self.underlying(analyzer);
+        context.loop_exit.is_some()
+            || (!context.forks.is_empty()
+                && context.forks.iter().all(|fork| fork.is_ended(analyzer) || fork.is_suspended(analyzer))
+                && context.forks.iter().any(|fork| fork.is_suspended(analyzer)))
+    }
+
+    /// Resumes the contexts that hit a `continue` for the next loop iteration
+    pub fn end_iteration(&self, analyzer: &mut impl AnalyzerLike) {
+        self.resume(analyzer, |exit| matches!(exit, LoopExit::Continue(_)));
+    }
+
+    /// Resumes every context that left the loop, for the statements after it
+    pub fn end_loop(&self, analyzer: &mut impl AnalyzerLike) {
+        self.resume(analyzer, |_| true);
+    }
+
+    fn resume(&self, analyzer: &mut impl AnalyzerLike, should_resume: impl Fn(&LoopExit) -> bool + Copy) {
+        let context = self.underlying_mut(analyzer);
+        if context.loop_exit.as_ref().is_some_and(should_resume) {
+            context.loop_exit = None;
+        }
+        let subctxs = context.forks.iter().chain(&context.children).copied().collect::<Vec<_>>();
+        subctxs.into_iter().for_each(|subctx| subctx.resume(analyzer, should_resume));
     }
 
     /// Gets all terminal children
@@ -450,6 +809,28 @@ impl ContextNode {
         }
     }
 
+    /// Returns whether arithmetic in the context is currently unchecked
+    pub fn unchecked(&self, analyzer: &impl AnalyzerLike) -> bool {
+        self.underlying(analyzer).unchecked
+    }
+
+    /// Whether overflowing arithmetic wraps around rather than reverting, either in an
+    /// `unchecked` block or because the source unit allows a compiler older than 0.8.0
+    pub fn wraps(&self, analyzer: &impl AnalyzerLike) -> bool {
+        // a function reaches its source unit through its contract, if it has one
+        let func = self.associated_fn(analyzer);
+        self.unchecked(analyzer)
+            || analyzer
+                .search_for_ancestor(func.into(), &Edge::Part)
+                .is_some_and(|source| !analyzer.checked_arithmetic(source))
+    }
+
+    /// Sets the unchecked status of the context and all of its forks
+    pub fn set_unchecked(&self, unchecked: bool, analyzer: &mut impl AnalyzerLike) {
+        self.underlying_mut(analyzer).unchecked = unchecked;
+        self.forks(analyzer).iter().for_each(|fork| fork.set_unchecked(unchecked, analyzer));
+    }
+
     /// Returns whether the context is killed
     pub fn is_killed(&self, analyzer: &impl AnalyzerLike) -> bool {
         self.underlying(analyzer).killed.is_some()
@@ -458,12 +839,62 @@ impl ContextNode {
     /// Returns whether the context is killed
     pub fn is_ended(&self, analyzer: &impl AnalyzerLike) -> bool {
         let underlying = self.underlying(analyzer);
-        underlying.killed.is_some() || !underlying.ret.is_empty()
+        underlying.killed.is_some()
+            || underlying.selfdestructed.is_some()
+            || !underlying.ret.is_empty()
+            || (!underlying.forks.is_empty()
+                && underlying.forks.iter().all(|fork| fork.is_ended(analyzer)))
+    }
+
+    /// Returns whether the context returned, reverted or selfdestructed
+    pub fn is_terminated(&self, analyzer: &impl AnalyzerLike) -> bool {
+        self.termination_reason(analyzer).is_some()
+    }
+
+    /// Returns how the context stopped executing, if it did. A context that forked stopped the
+    /// way all of its forks did, so there is no reason while one of them is still running, or
+    /// when one path returns while another reverts
+    pub fn termination_reason(&self, analyzer: &impl AnalyzerLike) -> Option<Termination> {
+        let underlying = self.underlying(analyzer);
+        if !underlying.forks.is_empty() {
+            let reasons = underlying
+                .forks
+                .iter()
+                .map(|fork| fork.termination_reason(analyzer))
+                .collect::<Option<Vec<_>>>()?;
+            let first = reasons[0];
+            let all = |pred: fn(&Termination) -> bool| reasons.iter().all(pred);
+            return if all(|reason| matches!(reason, Termination::Return(_)))
+                || all(|reason| matches!(reason, Termination::SelfDestruct(_)))
+                || all(|reason| matches!(reason, Termination::Panic(_)))
+            {
+                Some(first)
+            } else if all(|reason| {
+                matches!(reason, Termination::Revert(_) | Termination::Panic(_))
+            }) {
+                // a panic reverts too
+                reasons.into_iter().find(|reason| matches!(reason, Termination::Revert(_)))
+            } else {
+                None
+            };
+        }
+        if let Some(loc) = underlying.selfdestructed {
+            Some(Termination::SelfDestruct(loc))
+        } else if let Some((loc, kind)) = underlying.killed {
+            Some(kind.termination(loc))
+        } else {
+            underlying.ret.first().map(|(loc, _)| Termination::Return(*loc))
+        }
     }
 
     /// Returns an option to where the context was killed
     pub fn killed_loc(&self, analyzer: &impl AnalyzerLike) -> Option<Loc> {
-        self.underlying(analyzer).killed
+        self.underlying(analyzer).killed.map(|(loc, _)| loc)
+    }
+
+    /// Returns why the context was killed, if it was
+    pub fn killed_kind(&self, analyzer: &impl AnalyzerLike) -> Option<KilledKind> {
+        self.underlying(analyzer).killed.map(|(_, kind)| kind)
     }
 
     /// Returns a map of variable dependencies for this context
@@ -480,6 +911,76 @@ impl ContextNode {
         }
     }
 
+    /// Records arithmetic in this context that may overflow or underflow
+    pub fn add_overflow(&self, overflow: Overflow, analyzer: &mut impl AnalyzerLike) {
+        self.underlying_mut(analyzer).overflows.push(overflow);
+    }
+
+    /// Arithmetic in this context that may overflow or underflow
+    pub fn overflows(&self, analyzer: &impl AnalyzerLike) -> Vec<Overflow> {
+        self.underlying(analyzer).overflows.clone()
+    }
+
+    /// Records a division or modulo in this context whose divisor may be zero
+    pub fn add_zero_divisor(&self, loc: Loc, tainted: bool, analyzer: &mut impl AnalyzerLike) {
+        self.underlying_mut(analyzer).zero_divisors.push((loc, tainted));
+    }
+
+    /// Divisions and modulos in this context whose divisor may be zero
+    pub fn zero_divisors(&self, analyzer: &impl AnalyzerLike) -> Vec<(Loc, bool)> {
+        self.underlying(analyzer).zero_divisors.clone()
+    }
+
+    /// Records an array index in this context that may be past the end of the array
+    pub fn add_out_of_bounds(&self, out_of_bounds: OutOfBounds, analyzer: &mut impl AnalyzerLike) {
+        self.underlying_mut(analyzer).out_of_bounds.push(out_of_bounds);
+    }
+
+    /// Array indexes in this context that may be past the end of the array
+    pub fn out_of_bounds(&self, analyzer: &impl AnalyzerLike) -> Vec<OutOfBounds> {
+        self.underlying(analyzer).out_of_bounds.clone()
+    }
+
+    /// Records ether sent out of the contract in this context
+    pub fn add_value_transfer(&self, transfer: ValueTransfer, analyzer: &mut impl AnalyzerLike) {
+        self.underlying_mut(analyzer).value_transfers.push(transfer);
+    }
+
+    /// Ether sent out of the contract in this context
+    pub fn value_transfers(&self, analyzer: &impl AnalyzerLike) -> Vec<ValueTransfer> {
+        self.underlying(analyzer).value_transfers.clone()
+    }
+
+    /// Records a local or parameter in this context that hides a state variable
+    pub fn add_shadowing(&self, shadowing: Shadowing, analyzer: &mut impl AnalyzerLike) {
+        self.underlying_mut(analyzer).shadowing.push(shadowing);
+    }
+
+    /// Locals and parameters in this context that hide a state variable
+    pub fn shadowing(&self, analyzer: &impl AnalyzerLike) -> Vec<Shadowing> {
+        self.underlying(analyzer).shadowing.clone()
+    }
+
+    /// Records a conversion to an enum in this context whose value may not be one of its variants
+    pub fn add_invalid_enum_cast(&self, loc: Loc, tainted: bool, analyzer: &mut impl AnalyzerLike) {
+        self.underlying_mut(analyzer).invalid_enum_casts.push((loc, tainted));
+    }
+
+    /// Conversions to an enum in this context whose value may not be one of its variants
+    pub fn invalid_enum_casts(&self, analyzer: &impl AnalyzerLike) -> Vec<(Loc, bool)> {
+        self.underlying(analyzer).invalid_enum_casts.clone()
+    }
+
+    /// Records a branch of an `if` statement this context reached
+    pub fn add_branch(&self, branch: Branch, analyzer: &mut impl AnalyzerLike) {
+        self.underlying_mut(analyzer).branches.push(branch);
+    }
+
+    /// Branches of the `if` statements this context reached
+    pub fn branches(&self, analyzer: &impl AnalyzerLike) -> Vec<Branch> {
+        self.underlying(analyzer).branches.clone()
+    }
+
     pub fn add_return_node(
         &self,
         ret_stmt_loc: Loc,
@@ -497,15 +998,3 @@ impl ContextNode {
         "Context".to_string()
     }
 }
-
-impl From<ContextNode> for NodeIdx {
-    fn from(val: ContextNode) -> Self {
-        val.0.into()
-    }
-}
-
-impl From<NodeIdx> for ContextNode {
-    fn from(idx: NodeIdx) -> Self {
-        ContextNode(idx.index())
-    }
-}
\ No newline at end of file
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use crate::AnalyzerLike;
 use crate::Builtin;
 use crate::ContractNode;
@@ -14,18 +15,22 @@ use crate::range::elem_ty::Elem;
 use crate::range::SolcRange;
 use crate::{
     analyzer::{Search},
-    context::ContextNode,
+    context::{remap_idx, ContextNode},
     range::elem::RangeOp,
     nodes::ConcreteNode, ContextEdge, Edge, Field, FunctionParam, FunctionReturn, Node,
     NodeIdx, VarType,
 };
 
 use petgraph::visit::EdgeRef;
+use std::collections::BTreeMap;
 use petgraph::Direction;
 use solang_parser::pt::{Loc, StorageLocation};
 
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct ContextVarNode(pub usize);
+
+node_newtype!(ContextVarNode, ContextVar, ContextVar);
+
 impl AsDotStr for ContextVarNode {
     fn as_dot_str(&self, analyzer: &impl GraphLike) -> String {
         let underlying = self.underlying(analyzer);
@@ -40,45 +45,50 @@ impl AsDotStr for ContextVarNode {
     }
 }
 
-impl From<ContextVarNode> for NodeIdx {
-    fn from(val: ContextVarNode) -> Self {
-        val.0.into()
+impl ContextVarNode {
+    pub fn storage<'a>(&self, analyzer: &'a impl GraphLike) -> &'a Option<StorageLocation> {
+        &self.underlying(analyzer).storage
     }
-}
 
-impl From<NodeIdx> for ContextVarNode {
-    fn from(idx: NodeIdx) -> Self {
-        ContextVarNode(idx.index())
+    pub fn is_storage(&self, analyzer: &impl GraphLike) -> bool {
+        self.underlying(analyzer).is_storage()
     }
-}
 
-impl ContextVarNode {
-    pub fn underlying<'a>(&self, analyzer: &'a impl GraphLike) -> &'a ContextVar {
-        match analyzer.node(*self) {
-            Node::ContextVar(c) => c,
-            e => panic!(
-                "Node type confusion: expected node to be ContextVar but it was: {:?}",
-                e
-            ),
-        }
+    pub fn is_memory(&self, analyzer: &impl GraphLike) -> bool {
+        matches!(self.underlying(analyzer).storage, Some(StorageLocation::Memory(..)))
     }
 
-    pub fn underlying_mut<'a>(&self, analyzer: &'a mut impl GraphLike) -> &'a mut ContextVar {
-        match analyzer.node_mut(*self) {
-            Node::ContextVar(c) => c,
-            e => panic!(
-                "Node type confusion: expected node to be ContextVar but it was: {:?}",
-                e
-            ),
-        }
+    pub fn is_calldata(&self, analyzer: &impl GraphLike) -> bool {
+        matches!(self.underlying(analyzer).storage, Some(StorageLocation::Calldata(..)))
     }
 
-    pub fn storage<'a>(&self, analyzer: &'a impl GraphLike) -> &'a Option<StorageLocation> {
-        &self.underlying(analyzer).storage
+    /// The variable a local `storage` reference currently points at, if this is one. Writes
+    /// through the reference change that variable too
+    pub fn storage_alias(&self, analyzer: &impl GraphLike) -> Option<ContextVarNode> {
+        let mut version = Some(*self);
+        while let Some(var) = version {
+            let alias = analyzer
+                .graph()
+                .edges_directed(var.0.into(), Direction::Outgoing)
+                .find(|edge| Edge::Context(ContextEdge::StorageAlias) == *edge.weight())
+                .map(|edge| ContextVarNode::from(edge.target()));
+            if let Some(alias) = alias {
+                return Some(alias.latest_version(analyzer));
+            }
+            version = var.previous_version(analyzer);
+        }
+        None
     }
 
-    pub fn is_storage(&self, analyzer: &impl GraphLike) -> bool {
-        matches!(self.underlying(analyzer).storage, Some(StorageLocation::Storage(..)))
+    /// The struct variable this is a field of, if it is one
+    pub fn struct_parent(&self, analyzer: &impl GraphLike) -> Option<ContextVarNode> {
+        analyzer
+            .graph()
+            .edges_directed(self.first_version(analyzer).0.into(), Direction::Outgoing)
+            .filter(|edge| Edge::Context(ContextEdge::AttrAccess) == *edge.weight())
+            .map(|edge| ContextVarNode::from(edge.target()))
+            .find(|parent| matches!(parent.ty(analyzer), VarType::User(TypeNode::Struct(_), _)))
+            .map(|parent| parent.latest_version(analyzer))
     }
 
     pub fn ty<'a>(&self, analyzer: &'a impl GraphLike) -> &'a VarType {
@@ -149,12 +159,18 @@ impl ContextVarNode {
     }
 
     pub fn is_const(&self, analyzer: &impl GraphLike) -> bool {
-        let underlying = self.underlying(analyzer);
-        underlying.storage.is_none() && underlying.ty.is_const(analyzer)
+        self.underlying(analyzer).is_const(analyzer)
     }
 
     pub fn is_symbolic(&self, analyzer: &impl GraphLike) -> bool {
-        self.underlying(analyzer).is_symbolic
+        self.underlying(analyzer).is_symbolic()
+    }
+
+    /// Whether the variable is, or is derived from, caller controlled input (i.e. `msg.data`)
+    pub fn is_tainted(&self, analyzer: &impl GraphLike) -> bool {
+        self.dependent_on(analyzer, true)
+            .iter()
+            .any(|dep| dep.underlying(analyzer).is_tainted)
     }
 
     pub fn is_tmp(&self, analyzer: &impl GraphLike) -> bool {
@@ -307,11 +323,14 @@ impl ContextVarNode {
         latest
     }
 
+    /// The latest version of this variable in `ctx`. A variable of the same name that isn't a
+    /// version of this one, like the parameter of a recursive call, is a different variable
     pub fn latest_version_in_ctx(&self, ctx: ContextNode, analyzer: &'_ impl GraphLike) -> Self {
-        if let Some(cvar) = ctx.var_by_name(analyzer, &self.name(analyzer)) {
-            cvar.latest_version(analyzer)
-        } else {
-            *self
+        match ctx.var_by_name(analyzer, &self.name(analyzer)) {
+            Some(cvar) if cvar.first_version(analyzer) == self.first_version(analyzer) => {
+                cvar.latest_version(analyzer)
+            }
+            _ => *self,
         }
     }
 
@@ -361,6 +380,18 @@ impl ContextVarNode {
             .next()
     }
 
+    /// The value assigned to the variable, if this version was created by an assignment rather
+    /// than by refining the previous version
+    pub fn assigned_from(&self, analyzer: &'_ impl GraphLike) -> Option<Self> {
+        analyzer
+            .graph()
+            .edges_directed(self.0.into(), Direction::Outgoing)
+            .filter(|edge| Edge::Context(ContextEdge::Assign) == *edge.weight())
+            .map(|edge| ContextVarNode::from(edge.target()))
+            .take(1)
+            .next()
+    }
+
     pub fn range_deps(&self, analyzer: &impl GraphLike) -> Vec<Self> {
         if let Some(range) = self.range(analyzer) {
             range.dependent_on()
@@ -461,7 +492,7 @@ impl ContextVarNode {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ContextVar {
     pub loc: Option<Loc>,
     pub name: String,
@@ -470,10 +501,11 @@ pub struct ContextVar {
     pub is_tmp: bool,
     pub tmp_of: Option<TmpConstruction>,
     pub is_symbolic: bool,
+    pub is_tainted: bool,
     pub ty: VarType,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TmpConstruction {
     pub lhs: ContextVarNode,
     pub op: RangeOp,
@@ -487,28 +519,84 @@ impl TmpConstruction {
 }
 
 impl ContextVar {
+    /// A plain, non-symbolic variable named `name` with no data location. Other fields are set
+    /// with struct update syntax, i.e. `ContextVar { is_tmp: true, ..ContextVar::new(..) }`
+    pub fn new(loc: Loc, name: String, ty: VarType) -> Self {
+        ContextVar {
+            loc: Some(loc),
+            display_name: name.clone(),
+            name,
+            storage: None,
+            is_tmp: false,
+            tmp_of: None,
+            is_symbolic: false,
+            is_tainted: false,
+            ty,
+        }
+    }
+
+    /// Whether the variable is an intermediate value, like a literal or the result of `a + b`,
+    /// rather than something declared in the source
     pub fn is_tmp(&self) -> bool {
         self.is_tmp || self.tmp_of.is_some()
     }
 
+    /// Whether the value is known, either as a literal or a range collapsed to a single value.
+    /// A variable with a data location can change behind its back and never is
+    pub fn is_const(&self, analyzer: &impl GraphLike) -> bool {
+        self.storage.is_none() && self.ty.is_const(analyzer)
+    }
+
+    /// Whether the variable lives in contract storage, as a state variable or a `storage`
+    /// reference to one
+    pub fn is_storage(&self) -> bool {
+        matches!(self.storage, Some(StorageLocation::Storage(..)))
+    }
+
+    /// Whether the variable stands for a value that is not known up front, like a parameter or
+    /// anything derived from one
+    pub fn is_symbolic(&self) -> bool {
+        self.is_symbolic
+    }
+
     pub fn tmp_of(&self) -> Option<TmpConstruction> {
         self.tmp_of
     }
 
+    /// The nodes this variable's range and construction refer to
+    pub fn referenced_idxs(&self) -> Vec<NodeIdx> {
+        let mut idxs = match &self.ty {
+            VarType::BuiltIn(_, Some(range)) => range.referenced_idxs(),
+            _ => vec![],
+        };
+        if let Some(tmp_of) = self.tmp_of {
+            idxs.push(tmp_of.lhs.into());
+            idxs.extend(tmp_of.rhs.map(NodeIdx::from));
+        }
+        idxs
+    }
+
+    /// Updates the nodes this variable refers to after the graph has been compacted
+    pub fn update_idxs(&mut self, mapping: &BTreeMap<NodeIdx, NodeIdx>) {
+        self.ty.update_idxs(mapping);
+        if let Some(tmp_of) = &mut self.tmp_of {
+            tmp_of.lhs = remap_idx(mapping, tmp_of.lhs);
+            tmp_of.rhs = tmp_of.rhs.map(|rhs| remap_idx(mapping, rhs));
+        }
+    }
+
     pub fn new_from_concrete(
         loc: Loc,
         concrete_node: ConcreteNode,
         analyzer: &impl GraphLike,
     ) -> Self {
         ContextVar {
-            loc: Some(loc),
-            name: concrete_node.underlying(analyzer).as_string(),
-            display_name: concrete_node.underlying(analyzer).as_string(),
-            storage: None,
             is_tmp: true,
-            tmp_of: None,
-            is_symbolic: false,
-            ty: VarType::Concrete(concrete_node),
+            ..ContextVar::new(
+                loc,
+                concrete_node.underlying(analyzer).as_string(),
+                VarType::Concrete(concrete_node),
+            )
         }
     }
 
@@ -532,14 +620,12 @@ impl ContextVar {
         analyzer: &impl GraphLike,
     ) -> Self {
         ContextVar {
-            loc: Some(loc),
-            name: contract_node.name(analyzer),
-            display_name: contract_node.name(analyzer),
-            storage: None,
-            is_tmp: false,
-            tmp_of: None,
             is_symbolic: true,
-            ty: VarType::User(TypeNode::Contract(contract_node)),
+            ..ContextVar::new(
+                loc,
+                contract_node.name(analyzer),
+                VarType::User(TypeNode::Contract(contract_node), None),
+            )
         }
     }
 
@@ -549,14 +635,12 @@ impl ContextVar {
         analyzer: &impl GraphLike,
     ) -> Self {
         ContextVar {
-            loc: Some(loc),
-            name: bn_node.underlying(analyzer).as_string(analyzer),
-            display_name: bn_node.underlying(analyzer).as_string(analyzer),
-            storage: None,
             is_tmp: true,
-            tmp_of: None,
-            is_symbolic: false,
-            ty: VarType::try_from_idx(analyzer, bn_node.into()).unwrap(),
+            ..ContextVar::new(
+                loc,
+                bn_node.underlying(analyzer).as_string(analyzer),
+                VarType::try_from_idx(analyzer, bn_node.into()).unwrap(),
+            )
         }
     }
 
@@ -571,13 +655,17 @@ impl ContextVar {
                 }
             }
             VarType::Concrete(cn) => SolcRange::from(cn.underlying(analyzer).clone()),
+            VarType::User(TypeNode::Enum(enum_node), ref maybe_range) => {
+                maybe_range.clone().or_else(|| Some(enum_node.range(analyzer)))
+            }
             _ => None,
         }
     }
 
     pub fn set_range_min(&mut self, new_min: Elem<Concrete>, fallback_range: Option<SolcRange>) {
         match &mut self.ty {
-            VarType::BuiltIn(_, ref mut maybe_range) => {
+            VarType::BuiltIn(_, ref mut maybe_range)
+            | VarType::User(TypeNode::Enum(_), ref mut maybe_range) => {
                 if let Some(range) = maybe_range {
                     range.set_range_min(new_min);
                 } else {
@@ -593,7 +681,8 @@ impl ContextVar {
 
     pub fn try_set_range_min(&mut self, new_min: Elem<Concrete>, fallback_range: Option<SolcRange>) -> bool {
         match &mut self.ty {
-            VarType::BuiltIn(_, ref mut maybe_range) => {
+            VarType::BuiltIn(_, ref mut maybe_range)
+            | VarType::User(TypeNode::Enum(_), ref mut maybe_range) => {
                 if let Some(range) = maybe_range {
                     range.set_range_min(new_min);
                     true
@@ -611,7 +700,8 @@ impl ContextVar {
 
     pub fn set_range_max(&mut self, new_max: Elem<Concrete>, fallback_range: Option<SolcRange>) {
         match &mut self.ty {
-            VarType::BuiltIn(_, ref mut maybe_range) => {
+            VarType::BuiltIn(_, ref mut maybe_range)
+            | VarType::User(TypeNode::Enum(_), ref mut maybe_range) => {
                 if let Some(range) = maybe_range {
                     range.set_range_max(new_max);
                 } else {
@@ -627,7 +717,8 @@ impl ContextVar {
 
     pub fn set_range_exclusions(&mut self, new_exclusions: Vec<Elem<Concrete>>, fallback_range: Option<SolcRange>) {
         match &mut self.ty {
-            VarType::BuiltIn(_, ref mut maybe_range) => {
+            VarType::BuiltIn(_, ref mut maybe_range)
+            | VarType::User(TypeNode::Enum(_), ref mut maybe_range) => {
                 if let Some(range) = maybe_range {
                     range.set_range_exclusions(new_exclusions);
                 } else {
@@ -643,7 +734,8 @@ impl ContextVar {
 
     pub fn try_set_range_max(&mut self, new_max: Elem<Concrete>, fallback_range: Option<SolcRange>) -> bool {
         match &mut self.ty {
-            VarType::BuiltIn(_bn, ref mut maybe_range) => {
+            VarType::BuiltIn(_, ref mut maybe_range)
+            | VarType::User(TypeNode::Enum(_), ref mut maybe_range) => {
                 if let Some(range) = maybe_range {
                     range.set_range_max(new_max);
                     true
@@ -661,7 +753,8 @@ impl ContextVar {
 
     pub fn try_set_range_exclusions(&mut self, new_exclusions: Vec<Elem<Concrete>>, fallback_range: Option<SolcRange>) -> bool {
         match &mut self.ty {
-            VarType::BuiltIn(_bn, ref mut maybe_range) => {
+            VarType::BuiltIn(_, ref mut maybe_range)
+            | VarType::User(TypeNode::Enum(_), ref mut maybe_range) => {
                 if let Some(range) = maybe_range {
                     range.set_range_exclusions(new_exclusions);
                     true
@@ -703,7 +796,7 @@ impl ContextVar {
                 Node::Var(var) => {
                     let name = var.name.clone().expect("Variable had no name").name;
                     let storage = if var.in_contract {
-                        if !var.attrs.iter().any(|attr| matches!(attr, solang_parser::pt::VariableAttribute::Constant(_))) {
+                        if !var.is_constant() {
                             Some(StorageLocation::Storage(var.loc))
                         } else {
                             None
@@ -721,14 +814,9 @@ impl ContextVar {
             };
 
             Some(ContextVar {
-                loc: Some(loc),
-                name: name.clone(),
-                display_name: name,
                 storage,
-                is_tmp: false,
-                tmp_of: None,
                 is_symbolic: true,
-                ty,
+                ..ContextVar::new(loc, name, ty)
             })
         } else {
             None
@@ -742,19 +830,11 @@ impl ContextVar {
         field: Field,
     ) -> Option<Self> {
         if let Some(ty) = VarType::try_from_idx(analyzer, field.ty) {
+            let name = parent_var.name.clone() + "." + &field.name.expect("Field had no name").name;
             Some(ContextVar {
-                loc: Some(loc),
-                name: parent_var.name.clone()
-                    + "."
-                    + &field.name.clone().expect("Field had no name").name,
-                display_name: parent_var.name.clone()
-                    + "."
-                    + &field.name.expect("Field had no name").name,
                 storage: parent_var.storage.clone(),
-                is_tmp: false,
-                tmp_of: None,
                 is_symbolic: true,
-                ty,
+                ..ContextVar::new(loc, name, ty)
             })
         } else {
             None
@@ -784,6 +864,7 @@ impl ContextVar {
             is_tmp: false,
             tmp_of: None,
             is_symbolic: index.underlying(analyzer).is_symbolic,
+            is_tainted: index.underlying(analyzer).is_tainted,
             ty: parent_var.array_underlying_ty(analyzer),
         }
     }
@@ -795,14 +876,9 @@ impl ContextVar {
         if let Some(name) = param.name {
             if let Some(ty) = VarType::try_from_idx(analyzer, param.ty) {
                 Some(ContextVar {
-                    loc: Some(param.loc),
-                    name: name.name.clone(),
-                    display_name: name.name,
                     storage: param.storage,
-                    is_tmp: false,
-                    tmp_of: None,
                     is_symbolic: true,
-                    ty,
+                    ..ContextVar::new(param.loc, name.name, ty)
                 })
             } else {
                 None
@@ -819,14 +895,9 @@ impl ContextVar {
         if let Some(name) = ret.name {
             if let Some(ty) = VarType::try_from_idx(analyzer, ret.ty) {
                 Some(ContextVar {
-                    loc: Some(ret.loc),
-                    name: name.name.clone(),
-                    display_name: name.name,
                     storage: ret.storage,
-                    is_tmp: false,
-                    tmp_of: None,
                     is_symbolic: true,
-                    ty,
+                    ..ContextVar::new(ret.loc, name.name, ty)
                 })
             } else {
                 None
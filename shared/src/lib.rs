@@ -1,4 +1,5 @@
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use crate::analyzer::GraphLike;
 use crate::context::ContextVarNode;
 
@@ -14,6 +15,53 @@ use petgraph::{graph::*};
 use solang_parser::pt::Identifier;
 use lazy_static::lazy_static;
 
+/// Gives a node newtype, i.e. `ContractNode(pub usize)`, conversions to and from [`NodeIdx`] and
+/// `underlying`/`underlying_mut` accessors for the data of its node variant. Accessing a node
+/// of any other kind panics, naming the newtype and the node it found
+macro_rules! node_newtype {
+    ($newtype:ident, $variant:ident, $data:ty) => {
+        impl $newtype {
+            /// Gets the underlying node data
+            pub fn underlying<'a>(&self, analyzer: &'a impl $crate::analyzer::GraphLike) -> &'a $data {
+                match analyzer.node(*self) {
+                    $crate::Node::$variant(inner) => inner,
+                    e => panic!(
+                        "Node type confusion: expected {} to be {} but it was: {:?}",
+                        stringify!($newtype),
+                        stringify!($variant),
+                        e
+                    ),
+                }
+            }
+
+            /// Gets the underlying node data mutably
+            pub fn underlying_mut<'a>(&self, analyzer: &'a mut impl $crate::analyzer::GraphLike) -> &'a mut $data {
+                match analyzer.node_mut(*self) {
+                    $crate::Node::$variant(inner) => inner,
+                    e => panic!(
+                        "Node type confusion: expected {} to be {} but it was: {:?}",
+                        stringify!($newtype),
+                        stringify!($variant),
+                        e
+                    ),
+                }
+            }
+        }
+
+        impl From<$crate::NodeIdx> for $newtype {
+            fn from(idx: $crate::NodeIdx) -> Self {
+                $newtype(idx.index())
+            }
+        }
+
+        impl From<$newtype> for $crate::NodeIdx {
+            fn from(val: $newtype) -> Self {
+                val.0.into()
+            }
+        }
+    };
+}
+
 pub mod nodes;
 pub mod analyzer;
 pub mod context;
@@ -22,7 +70,7 @@ pub mod range;
 pub type NodeIdx = NodeIndex<usize>;
 pub type EdgeIdx = EdgeIndex<usize>;
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Node {
     Context(Context),
     ContextVar(ContextVar),
@@ -40,6 +88,8 @@ pub enum Node {
     Enum(Enum),
     Error(Error),
     ErrorParam(ErrorParam),
+    Event(Event),
+    EventParam(EventParam),
     Field(Field),
     Var(Var),
     Ty(Ty),
@@ -65,6 +115,7 @@ pub fn as_dot_str(idx: NodeIdx, analyzer: &impl GraphLike) -> String {
         FunctionReturn(_fr) => FunctionReturnNode::from(idx).as_dot_str(analyzer),
         Struct(_s) => StructNode::from(idx).as_dot_str(analyzer),
         Enum(_e) => EnumNode::from(idx).as_dot_str(analyzer),
+        Event(_e) => EventNode::from(idx).as_dot_str(analyzer),
         Field(_f) => FieldNode::from(idx).as_dot_str(analyzer),
         Var(_v) => VarNode::from(idx).as_dot_str(analyzer),
         Ty(_t) => TyNode::from(idx).as_dot_str(analyzer),
@@ -74,6 +125,66 @@ pub fn as_dot_str(idx: NodeIdx, analyzer: &impl GraphLike) -> String {
 }
 
 impl Node {
+    /// Updates any nodes referenced by this node after the graph has been compacted
+    pub fn update_idxs(&mut self, mapping: &BTreeMap<NodeIdx, NodeIdx>) {
+        use crate::Node::*;
+        let update = |idx: &mut NodeIdx| *idx = *mapping.get(idx).unwrap_or(idx);
+        match self {
+            Builtin(b) => b.update_idxs(mapping),
+            VarType(v_ty) => v_ty.update_idxs(mapping),
+            Contract(c) => c.inherits.iter_mut().for_each(|inherited| {
+                *inherited = (*mapping.get(&(*inherited).into()).unwrap_or(&(*inherited).into())).into()
+            }),
+            FunctionParam(param) => update(&mut param.ty),
+            FunctionReturn(ret) => update(&mut ret.ty),
+            ErrorParam(param) => update(&mut param.ty),
+            EventParam(param) => update(&mut param.ty),
+            Field(field) => update(&mut field.ty),
+            Ty(ty) => update(&mut ty.ty),
+            Var(var) => {
+                update(&mut var.ty);
+                if let Some(init) = &mut var.initializer {
+                    update(init);
+                }
+            }
+            Context(ctx) => ctx.update_idxs(mapping),
+            ContextVar(var) => var.update_idxs(mapping),
+            _ => {}
+        }
+    }
+
+    /// The name of the node's variant, i.e. `ContextVar`
+    pub fn kind_name(&self) -> &'static str {
+        use crate::Node::*;
+        match self {
+            Context(_) => "Context",
+            ContextVar(_) => "ContextVar",
+            ContextFork => "ContextFork",
+            FunctionCall => "FunctionCall",
+            Builtin(_) => "Builtin",
+            VarType(_) => "VarType",
+            SourceUnit(_) => "SourceUnit",
+            SourceUnitPart(..) => "SourceUnitPart",
+            Contract(_) => "Contract",
+            Function(_) => "Function",
+            FunctionParam(_) => "FunctionParam",
+            FunctionReturn(_) => "FunctionReturn",
+            Struct(_) => "Struct",
+            Enum(_) => "Enum",
+            Error(_) => "Error",
+            ErrorParam(_) => "ErrorParam",
+            Event(_) => "Event",
+            EventParam(_) => "EventParam",
+            Field(_) => "Field",
+            Var(_) => "Var",
+            Ty(_) => "Ty",
+            Unresolved(_) => "Unresolved",
+            Concrete(_) => "Concrete",
+            Msg(_) => "Msg",
+            Block(_) => "Block",
+        }
+    }
+
     pub fn dot_str_color(&self) -> String {
         use crate::Node::*;
         let c = match self {
@@ -84,6 +195,7 @@ impl Node {
             Function(_f) => TOKYO_NIGHT_COLORS.get("cyan").unwrap(),
             Struct(_s) => TOKYO_NIGHT_COLORS.get("yellow").unwrap(),
             Enum(_e) => TOKYO_NIGHT_COLORS.get("yellow").unwrap(),
+            Concrete(_c) => TOKYO_NIGHT_COLORS.get("red").unwrap(),
             _ => TOKYO_NIGHT_COLORS.get("default").unwrap()
         };
         c.to_string()
@@ -110,18 +222,21 @@ lazy_static! {
 }
 
 
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum Edge {
     Part,
+    Import,
     Context(ContextEdge),
     Contract,
-    InheritedContract,
+    /// From a contract to each contract it directly inherits from
+    Inherits,
     Field,
     Enum,
     Struct,
     Error,
     ErrorParam,
     Event,
+    EventParam,
     Var,
     Ty,
     Func,
@@ -1,27 +1,15 @@
+use serde::{Deserialize, Serialize};
 use ethers_core::types::Address;
 use ethers_core::types::U256;
 use ethers_core::types::H256;
 use crate::GraphLike;
 use crate::analyzer::AsDotStr;
-use crate::Node;
-use crate::NodeIdx;
 
 /// An index in the graph that references a Block node
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct BlockNode(pub usize);
 
-impl BlockNode {
-    /// Gets the underlying node data for the block environment
-    pub fn underlying<'a>(&self, analyzer: &'a impl GraphLike) -> &'a Block {
-        match analyzer.node(*self) {
-            Node::Block(st) => st,
-            e => panic!(
-                "Node type confusion: expected node to be Msg but it was: {:?}",
-                e
-            ),
-        }
-    }
-}
+node_newtype!(BlockNode, Block, Block);
 
 impl AsDotStr for BlockNode {
     fn as_dot_str(&self, analyzer: &impl GraphLike) -> String {
@@ -29,22 +17,10 @@ impl AsDotStr for BlockNode {
     }
 }
 
-impl From<BlockNode> for NodeIdx {
-    fn from(val: BlockNode) -> Self {
-        val.0.into()
-    }
-}
-
-impl From<NodeIdx> for BlockNode {
-    fn from(idx: NodeIdx) -> Self {
-        BlockNode(idx.index())
-    }
-}
-
 /// Represents block-based environment variables available in solidity. These can
 /// be set in the configuration (TODO) - if they are not set they are assumed to be
 /// in their types default full range (e.g.: `uint256 -> [0, 2**256 - 1]`).
-#[derive(Debug, Default, Clone, Eq, PartialEq)]
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Block {
     /// The block's hash
     pub hash: Option<H256>,
@@ -1,49 +1,29 @@
+use serde::{Deserialize, Serialize};
 use crate::Builtin;
-use crate::{Node, NodeIdx, analyzer::{GraphLike}};
+use crate::analyzer::{AnalyzerLike, GraphLike};
 use ethers_core::types::{U256, I256, H256, Address};
 
 /// An index in the graph that references a [`Concrete`] node
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct ConcreteNode(pub usize);
 
-impl ConcreteNode {
-    /// Gets the underlying node data for the [`Concrete`]
-    pub fn underlying<'a>(&self, analyzer: &'a impl GraphLike) -> &'a Concrete {
-        match analyzer.node(*self) {
-            Node::Concrete(c) => c,
-            e => panic!(
-                "Node type confusion: expected node to be Concrete but it was: {:?}",
-                e
-            ),
-        }
-    }
+node_newtype!(ConcreteNode, Concrete, Concrete);
 
-    pub fn max_size(&self, analyzer: &mut impl GraphLike) -> Self {
+impl ConcreteNode {
+    pub fn max_size(&self, analyzer: &mut (impl GraphLike + AnalyzerLike)) -> Self {
         let c = self.underlying(analyzer).max_size();
-        analyzer.add_node(Node::Concrete(c)).into()
-    }
-}
-
-impl From<NodeIdx> for ConcreteNode {
-    fn from(idx: NodeIdx) -> Self {
-        ConcreteNode(idx.index())
+        analyzer.concrete_or_add(c).into()
     }
 }
 
-impl From<ConcreteNode> for NodeIdx {
-    fn from(val: ConcreteNode) -> Self {
-        val.0.into()
-    }
-}
-
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
 pub enum DynCapacity {
     Cap(U256),
     Unlimited,
 }
 
 /// EVM/Solidity basic concrete types
-#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
 pub enum Concrete {
     /// An unsigned integer, in the form of (bits, value) 
     Uint(u16, U256),
@@ -121,8 +101,44 @@ impl<T: Into<Concrete>> From<Vec<T>> for Concrete {
     }
 }
 
+/// The widest integer type solidity supports, in bits
+pub const MAX_INT_WIDTH: u16 = 256;
+
+/// An integer width that no solidity type has, i.e. `uint7`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InvalidIntWidth(pub u16);
+
+impl std::fmt::Display for InvalidIntWidth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid integer width: {}, expected a multiple of 8", self.0)
+    }
+}
+
+impl std::error::Error for InvalidIntWidth {}
 
 impl Concrete {
+    /// Creates a `uintN`, checking that `N` is a valid solidity width and masking `val` to it
+    pub fn uint(width: u16, val: U256) -> Result<Self, InvalidIntWidth> {
+        Self::uint_with_max_width(width, val, MAX_INT_WIDTH)
+    }
+
+    /// Like [`Concrete::uint`], but allowing widths up to `max_width` instead of 256 bits
+    pub fn uint_with_max_width(
+        width: u16,
+        val: U256,
+        max_width: u16,
+    ) -> Result<Self, InvalidIntWidth> {
+        if width == 0 || !width.is_multiple_of(8) || width > max_width {
+            return Err(InvalidIntWidth(width));
+        }
+        let val = if width >= 256 {
+            val
+        } else {
+            val & ((U256::one() << width) - 1)
+        };
+        Ok(Concrete::Uint(width, val))
+    }
+
     /// Convert a U256 back into it's original type. This is used mostly
     /// for range calculations to improve ergonomics. Basically
     /// the EVM only operates on U256 words, so most of this should
@@ -175,7 +191,8 @@ impl Concrete {
         matches!(self, Concrete::Int(_, _))
     }
 
-    /// Cast the concrete to another type as denoted by a [`Builtin`].
+    /// Cast the concrete to another type as denoted by a [`Builtin`], following the conversion
+    /// rules of the EVM. `None` if there is no such conversion, i.e. from a `string` to a `uint`
     pub fn cast(self, builtin: Builtin) -> Option<Self> {
         match self {
             Concrete::Uint(_, val) => {
@@ -185,45 +202,11 @@ impl Concrete {
                         val.to_big_endian(&mut bytes);
                         Some(Concrete::Address(Address::from_slice(&bytes[12..])))
                     }
-                    Builtin::Uint(size) => {
-                        let mask = if size == 256 {
-                            U256::MAX
-                        } else {
-                            U256::from(2).pow(size.into()) - 1
-                        };
-                        if val < mask {
-                            Some(Concrete::Uint(size, val))    
-                        } else {
-                            Some(Concrete::Uint(size, mask))
-                        }
-                    }
-                    Builtin::Int(size) => {
-                        let mask = if size == 256 {
-                            // we ignore the top bit
-                            U256::from(2).pow(255.into()) - 1
-                        } else {
-                            U256::from(2).pow(size.into()) - 1
-                        };
-
-                        if val < mask {
-                            Some(Concrete::Int(size, I256::from_raw(val)))
-                        } else {
-                            Some(Concrete::Int(size, I256::from_raw(mask)))
-                        }
-                    }
-                    Builtin::Bytes(size) => {
-                        let mask = if size == 32 {
-                            U256::MAX
-                        } else {
-                            let v = U256::from(2).pow((size as u16 * 8).into()) - 1;
-                            v << v.leading_zeros()
-                        };
-
-                        // let h = H256::from_slice(&(val & mask).0.iter().flat_map(|v| v.to_le_bytes()).collect::<Vec<_>>()[..]);
-                        let mut h = H256::default();
-                        (val & mask).to_big_endian(&mut h.0);
-                        Some(Concrete::Bytes(size, h))
-                    }
+                    // integer conversions keep the low bits, i.e. `uint8(257) == 1`
+                    Builtin::Uint(size) => Self::uint(size, val).ok(),
+                    Builtin::Int(size) => Some(Concrete::Int(size, Self::sign_extend(size, val))),
+                    Builtin::Bytes(size) => Some(Self::left_aligned(size, val)),
+                    Builtin::Bool => Some(Concrete::Bool(!val.is_zero())),
                     _ => None
                 }
             }
@@ -234,47 +217,10 @@ impl Concrete {
                         val.to_big_endian(&mut bytes);
                         Some(Concrete::Address(Address::from_slice(&bytes[12..])))
                     }
-                    Builtin::Uint(size) => {
-                        let mask = if size == 256 {
-                            U256::MAX
-                        } else {
-                            U256::from(2).pow(size.into()) - 1
-                        };
-
-                        let (_sign, abs) = val.into_sign_and_abs();
-
-                        if val.signum() <= 0.into() {
-                            Some(Concrete::Uint(size, U256::zero()))
-                        } else if abs < mask {
-                            Some(Concrete::Uint(size, abs))
-                        } else {
-                            Some(Concrete::Uint(size, mask))
-                        }
-                    }
-                    Builtin::Int(size) => {
-                        let mask = if size == 256 {
-                            U256::MAX / 2
-                        } else {
-                            U256::from(2).pow((size - 1).into()) - 1
-                        };
-
-                        let (sign, abs) = val.into_sign_and_abs();
-                        if abs < mask {
-                            Some(Concrete::Int(size, val))
-                        } else {
-                            Some(Concrete::Int(size, I256::checked_from_sign_and_abs(sign, mask).unwrap())) 
-                        }
-                    }
-                    Builtin::Bytes(size) => {
-                        let mask = if size == 32 {
-                            U256::MAX
-                        } else {
-                            U256::from(2).pow((size as u16 * 8).into()) - 1
-                        };
-                        let mut h = H256::default();
-                        (val.into_raw() & mask).to_big_endian(h.as_mut());
-                        Some(Concrete::Bytes(size, h))
-                    }
+                    // two's complement, i.e. `uint8(int8(-1)) == 255`
+                    Builtin::Uint(size) => Self::uint(size, val.into_raw()).ok(),
+                    Builtin::Int(size) => Some(Concrete::Int(size, Self::sign_extend(size, val.into_raw()))),
+                    Builtin::Bytes(size) => Some(Self::left_aligned(size, val.into_raw())),
                     _ => None
                 }
             }
@@ -283,23 +229,14 @@ impl Concrete {
                     Builtin::Address => {
                         Some(Concrete::Address(Address::from_slice(&b[12..])))
                     }
+                    // the bytes are the high end of the word, `uint32(bytes4(0xdeadbeef)) == 0xdeadbeef`
                     Builtin::Uint(size) => {
-                        let mask = if size == 256 {
-                            U256::MAX
-                        } else {
-                            U256::from(2).pow(size.into()) - 1
-                        };
-                        let val = U256::from_big_endian(b.as_bytes());
-                        Some(Concrete::Uint(size, val & mask))
+                        let val = U256::from_big_endian(b.as_bytes()) >> (256 - cap as usize * 8);
+                        Self::uint(size, val).ok()
                     }
                     Builtin::Int(size) => {
-                        let mask = if size == 256 {
-                            U256::MAX
-                        } else {
-                            U256::from(2).pow(size.into()) - 1
-                        };
-                        let val = U256::from_big_endian(b.as_bytes());
-                        Some(Concrete::Int(size, I256::from_raw(val & mask)))
+                        let val = U256::from_big_endian(b.as_bytes()) >> (256 - cap as usize * 8);
+                        Some(Concrete::Int(size, Self::sign_extend(size, val)))
                     }
                     Builtin::Bytes(size) => {
                         let mut h = H256::default();
@@ -317,15 +254,7 @@ impl Concrete {
             Concrete::Address(a) => {
                 match builtin {
                     Builtin::Address => Some(self),
-                    Builtin::Uint(size) => {
-                        let mask = if size == 256 {
-                            U256::MAX
-                        } else {
-                            U256::from(2).pow(size.into()) - 1
-                        };
-                        let val = U256::from_big_endian(a.as_bytes());
-                        Some(Concrete::Uint(size, val & mask))
-                    }
+                    Builtin::Uint(size) => Self::uint(size, U256::from_big_endian(a.as_bytes())).ok(),
                     Builtin::Int(size) => {
                         let mask = if size == 256 {
                             U256::MAX
@@ -357,10 +286,74 @@ impl Concrete {
                     _ => None
                 }
             }
+            Concrete::String(ref st) => {
+                match builtin {
+                    Builtin::String => Some(self),
+                    Builtin::DynamicBytes => Some(Concrete::DynBytes(st.as_bytes().to_vec())),
+                    // a string literal fills a `bytesN` from the left, `bytes32 b = "abc"`
+                    Builtin::Bytes(size) if st.len() <= size as usize => {
+                        let mut h = H256::default();
+                        h.0[..st.len()].copy_from_slice(st.as_bytes());
+                        Some(Concrete::Bytes(size, h))
+                    }
+                    _ => None
+                }
+            }
+            Concrete::Bool(b) => {
+                match builtin {
+                    Builtin::Bool => Some(self),
+                    Builtin::Uint(size) => Self::uint(size, U256::from(b as u8)).ok(),
+                    _ => None
+                }
+            }
             _ => None
         }
     }
 
+    /// A `bytesN` of the low `size` bytes of `val`, which are stored at the start of the word
+    fn left_aligned(size: u8, val: U256) -> Self {
+        let bits = size as usize * 8;
+        let mut h = H256::default();
+        ((val & Self::low_bits(bits as u16)) << (256 - bits)).to_big_endian(&mut h.0);
+        Concrete::Bytes(size, h)
+    }
+
+    /// The bytes of the value as `abi.encodePacked` lays them out: integers in as many bytes as
+    /// their type, fixed bytes without padding and dynamic values as they are
+    pub fn packed_bytes(&self) -> Option<Vec<u8>> {
+        let word = |val: U256, bits: u16| {
+            let mut bytes = [0u8; 32];
+            val.to_big_endian(&mut bytes);
+            bytes[32 - bits as usize / 8..].to_vec()
+        };
+        match self {
+            Concrete::Uint(size, val) => Some(word(*val, *size)),
+            Concrete::Int(size, val) => Some(word(val.into_raw(), *size)),
+            Concrete::Bytes(size, b) => Some(b.0[..*size as usize].to_vec()),
+            Concrete::Address(a) => Some(a.as_bytes().to_vec()),
+            Concrete::Bool(b) => Some(vec![*b as u8]),
+            Concrete::DynBytes(b) => Some(b.clone()),
+            Concrete::String(st) => Some(st.as_bytes().to_vec()),
+            Concrete::Array(_) => None,
+        }
+    }
+
+    /// A mask of the lowest `bits` bits
+    fn low_bits(bits: u16) -> U256 {
+        U256::MAX >> (256 - bits as usize)
+    }
+
+    /// Reads the lowest `bits` bits of a two's complement value as a signed integer of that width
+    fn sign_extend(bits: u16, raw: U256) -> I256 {
+        let mask = Self::low_bits(bits);
+        let low = raw & mask;
+        if low.bit(bits as usize - 1) {
+            I256::from_raw(low | !mask)
+        } else {
+            I256::from_raw(low)
+        }
+    }
+
     /// Converts a concrete into a [`Builtin`].
     pub fn as_builtin(&self) -> Builtin {
         match self {
@@ -397,6 +390,18 @@ impl Concrete {
         }
     }
 
+    /// The same value at the width of `other` when `other` is an integer of the same signedness
+    /// and a wider type, i.e. how Solidity implicitly converts the narrower operand of `uint8 + uint256`
+    pub fn widened_to(&self, other: &Self) -> Self {
+        match (self, other) {
+            (Concrete::Uint(size, _), Concrete::Uint(other_size, _))
+            | (Concrete::Int(size, _), Concrete::Int(other_size, _)) if other_size > size => {
+                self.clone().cast(other.as_builtin()).unwrap_or_else(|| self.clone())
+            },
+            _ => self.clone()
+        }
+    }
+
     pub fn max_size(&self) -> Self {
         match self {
             Concrete::Uint(_, val) => {
@@ -443,6 +448,7 @@ impl Concrete {
             Concrete::Address(_) => {
                 Some(Concrete::Address(Address::from_slice(&[0xff; 20])))
             },
+            Concrete::Bool(_) => Some(Concrete::Bool(true)),
             _ => None,
         }
     }
@@ -450,16 +456,18 @@ impl Concrete {
     pub fn possible_builtins_from_ty_inf(&self) -> Vec<Builtin> {
         let mut builtins = vec![];
         match self {
-            Concrete::Uint(size, val) => {
+            // a literal is evaluated at its smallest size but converts implicitly to any
+            // type wide enough to hold it
+            Concrete::Uint(_, val) => {
                 let mut min_bits = (256 - val.leading_zeros()) as u16;
-                let mut s = *size;
+                let mut s = 256;
                 while s > min_bits {
                     builtins.push(Builtin::Uint(s));
                     s -= 8;
                 }
                 // now ints
                 min_bits = min_bits.saturating_sub(1);
-                let mut s = *size;
+                let mut s = 256;
                 while s > min_bits {
                     builtins.push(Builtin::Int(s));
                     s -= 8;
@@ -472,7 +480,7 @@ impl Concrete {
                     builtins.push(Builtin::Int(*size))
                 } else {
                     let min_bits = (255 - abs.leading_zeros()) as u16;
-                    let mut s = *size;
+                    let mut s = 256;
                     while s > min_bits {
                         builtins.push(Builtin::Int(s));
                         s -= 8;
@@ -535,7 +543,7 @@ impl Concrete {
                     I256::from_raw(U256::from(1u8) << U256::from(*size - 1)) - I256::from(1)
                 };
 
-                let min = max * I256::from(-1i32);
+                let min = max * I256::from(-1i32) - I256::from(1);
                 Some(Concrete::Int(*size, min))
             },
             Concrete::Bytes(size, _) => {
@@ -547,7 +555,8 @@ impl Concrete {
             Concrete::Address(_) => {
                 Some(Concrete::Address(Address::from_slice(&[0x00; 20])))
             },
-            e => { println!("was other: {e:?}"); None},
+            Concrete::Bool(_) => Some(Concrete::Bool(false)),
+            _ => None,
         }
     }
 
@@ -577,6 +586,171 @@ impl Concrete {
         }
     }
 
+    /// If its `Concrete::Bool`, gets the value
+    pub fn bool_val(&self) -> Option<bool> {
+        match self {
+            Concrete::Bool(val) => Some(*val),
+            _ => None,
+        }
+    }
+
+    /// If its `Concrete::Address`, gets the value
+    pub fn address_val(&self) -> Option<Address> {
+        match self {
+            Concrete::Address(val) => Some(*val),
+            _ => None,
+        }
+    }
+
+    /// If its `Concrete::Bytes`, gets the value
+    pub fn bytes_val(&self) -> Option<H256> {
+        match self {
+            Concrete::Bytes(_, val) => Some(*val),
+            _ => None,
+        }
+    }
+
+    /// If its `Concrete::String`, gets the value
+    pub fn string_val(&self) -> Option<String> {
+        match self {
+            Concrete::String(val) => Some(val.clone()),
+            _ => None,
+        }
+    }
+
+    /// Whether `self < other`, or `None` if the two can not be compared
+    pub fn lt(&self, other: &Self) -> Option<bool> {
+        self.numeric_cmp(other).map(|ord| ord.is_lt())
+    }
+
+    /// Whether `self <= other`, or `None` if the two can not be compared
+    pub fn le(&self, other: &Self) -> Option<bool> {
+        self.numeric_cmp(other).map(|ord| ord.is_le())
+    }
+
+    /// Whether `self > other`, or `None` if the two can not be compared
+    pub fn gt(&self, other: &Self) -> Option<bool> {
+        self.numeric_cmp(other).map(|ord| ord.is_gt())
+    }
+
+    /// Whether `self >= other`, or `None` if the two can not be compared
+    pub fn ge(&self, other: &Self) -> Option<bool> {
+        self.numeric_cmp(other).map(|ord| ord.is_ge())
+    }
+
+    /// Orders two values by what they represent rather than how they are stored, so a negative
+    /// `Int` is below every `Uint` regardless of sizes. Integers compare with integers, and
+    /// bytes, addresses and bools only with their own kind
+    fn numeric_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        match (self, other) {
+            (Concrete::Uint(_, a), Concrete::Uint(_, b)) => Some(a.cmp(b)),
+            (Concrete::Int(_, a), Concrete::Int(_, b)) => Some(a.cmp(b)),
+            (Concrete::Int(_, a), Concrete::Uint(_, b)) => {
+                if a.is_negative() {
+                    Some(std::cmp::Ordering::Less)
+                } else {
+                    Some(a.into_raw().cmp(b))
+                }
+            }
+            (Concrete::Uint(..), Concrete::Int(..)) => other.numeric_cmp(self).map(|ord| ord.reverse()),
+            (Concrete::Bytes(_, a), Concrete::Bytes(_, b)) => Some(a.cmp(b)),
+            (Concrete::Address(a), Concrete::Address(b)) => Some(a.cmp(b)),
+            (Concrete::Bool(a), Concrete::Bool(b)) => Some(a.cmp(b)),
+            _ => None,
+        }
+    }
+
+    /// Adds two integers of the same signedness, or `None` if the sum does not fit in the wider
+    /// of the two types
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        self.overflowing_op(other, |a, b| Some(a.overflowing_add(b)), |a, b| Some(a.overflowing_add(b))).and_then(Self::unless_overflowed)
+    }
+
+    /// Subtracts two integers of the same signedness, or `None` if the difference does not fit in
+    /// the wider of the two types
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        self.overflowing_op(other, |a, b| Some(a.overflowing_sub(b)), |a, b| Some(a.overflowing_sub(b))).and_then(Self::unless_overflowed)
+    }
+
+    /// Multiplies two integers of the same signedness, or `None` if the product does not fit in
+    /// the wider of the two types
+    pub fn checked_mul(&self, other: &Self) -> Option<Self> {
+        self.overflowing_op(other, |a, b| Some(a.overflowing_mul(b)), |a, b| Some(a.overflowing_mul(b))).and_then(Self::unless_overflowed)
+    }
+
+    /// Divides two integers of the same signedness, or `None` when dividing by zero or the
+    /// quotient does not fit, i.e. `type(int8).min / -1`
+    pub fn checked_div(&self, other: &Self) -> Option<Self> {
+        self.overflowing_op(other, Self::uint_div, Self::int_div).and_then(Self::unless_overflowed)
+    }
+
+    /// Adds two integers of the same signedness, wrapping around at the bounds of the wider type
+    /// like in an `unchecked` block
+    pub fn wrapping_add(&self, other: &Self) -> Option<Self> {
+        self.overflowing_op(other, |a, b| Some(a.overflowing_add(b)), |a, b| Some(a.overflowing_add(b))).map(|(val, _)| val)
+    }
+
+    /// Subtracts two integers of the same signedness, wrapping around at the bounds of the wider
+    /// type like in an `unchecked` block
+    pub fn wrapping_sub(&self, other: &Self) -> Option<Self> {
+        self.overflowing_op(other, |a, b| Some(a.overflowing_sub(b)), |a, b| Some(a.overflowing_sub(b))).map(|(val, _)| val)
+    }
+
+    /// Multiplies two integers of the same signedness, wrapping around at the bounds of the wider
+    /// type like in an `unchecked` block
+    pub fn wrapping_mul(&self, other: &Self) -> Option<Self> {
+        self.overflowing_op(other, |a, b| Some(a.overflowing_mul(b)), |a, b| Some(a.overflowing_mul(b))).map(|(val, _)| val)
+    }
+
+    /// Divides two integers of the same signedness, wrapping around at the bounds of the wider type.
+    /// Division by zero still has no result
+    pub fn wrapping_div(&self, other: &Self) -> Option<Self> {
+        self.overflowing_op(other, Self::uint_div, Self::int_div).map(|(val, _)| val)
+    }
+
+    fn uint_div(a: U256, b: U256) -> Option<(U256, bool)> {
+        (!b.is_zero()).then(|| (a / b, false))
+    }
+
+    fn int_div(a: I256, b: I256) -> Option<(I256, bool)> {
+        (!b.is_zero()).then(|| a.overflowing_div(b))
+    }
+
+    fn unless_overflowed((val, overflowed): (Self, bool)) -> Option<Self> {
+        (!overflowed).then_some(val)
+    }
+
+    /// Applies an integer operation at the width of the wider operand, returning the result reduced
+    /// to that width and whether it overflowed. Non-integers, mixed signedness and operations
+    /// without a result have `None`
+    fn overflowing_op(
+        &self,
+        other: &Self,
+        uint_op: impl Fn(U256, U256) -> Option<(U256, bool)>,
+        int_op: impl Fn(I256, I256) -> Option<(I256, bool)>,
+    ) -> Option<(Self, bool)> {
+        match (self, other) {
+            (Concrete::Uint(s, a), Concrete::Uint(s2, b)) => {
+                let size = *s.max(s2);
+                let max = Concrete::max(&Concrete::Uint(size, U256::zero()))?.uint_val()?;
+                let (val, overflowed) = uint_op(*a, *b)?;
+                // the type max is `2**n - 1`, so masking is a reduction mod `2**n`
+                Some((Concrete::Uint(size, val & max), overflowed || val > max))
+            }
+            (Concrete::Int(s, a), Concrete::Int(s2, b)) => {
+                let size = *s.max(s2);
+                let min = Concrete::min(&Concrete::Int(size, I256::zero()))?.int_val()?;
+                let max = Concrete::max(&Concrete::Int(size, I256::zero()))?.int_val()?;
+                let (val, overflowed) = int_op(*a, *b)?;
+                // two's complement wraps by keeping the low `n` bits and extending the sign bit
+                let wrapped = Self::sign_extend(size, val.into_raw());
+                Some((Concrete::Int(size, wrapped), overflowed || val < min || val > max))
+            }
+            _ => None,
+        }
+    }
+
+
     /// Converts to a string
     pub fn as_string(&self) -> String {
         match self {
@@ -585,9 +759,25 @@ impl Concrete {
             Concrete::Bytes(size, b) => format!("0x{}", b.0.iter().take(*size as usize).map(|byte| format!("{:02x}", byte)).collect::<Vec<_>>().join("")),
             Concrete::String(s) => s.to_string(),
             Concrete::Bool(b) => b.to_string(),
-            Concrete::Address(a) => a.to_string(),
-            Concrete::DynBytes(a) => if a.is_empty() { "0x".to_string() } else { hex::encode(a) },
-            e => todo!("Concrete as string {e:?}"),
+            Concrete::Address(a) => ethers_core::utils::to_checksum(a, None),
+            Concrete::DynBytes(a) => format!("0x{}", hex::encode(a)),
+            Concrete::Array(arr) => format!("[{}]", arr.iter().map(|elem| elem.as_string()).collect::<Vec<_>>().join(", ")),
+        }
+    }
+
+    /// Converts to a string like [`Concrete::as_string`], but with integers in hexadecimal
+    pub fn as_hex_string(&self) -> String {
+        match self {
+            Concrete::Uint(_, val) => format!("{:#x}", val),
+            Concrete::Int(_, val) => {
+                let abs = val.into_sign_and_abs().1;
+                if val.is_negative() {
+                    format!("-{:#x}", abs)
+                } else {
+                    format!("{:#x}", abs)
+                }
+            }
+            _ => self.as_string(),
         }
     }
 
@@ -642,7 +832,13 @@ impl Concrete {
                     Concrete::Uint(*size, val).as_human_string()
                 } else {
                     let val = val.into_sign_and_abs().1;
-                    format!("-1 * {}", Concrete::Uint(*size, val).as_human_string())
+                    let abs = Concrete::Uint(*size, val).as_human_string();
+                    // `2**255 - 1` has to be negated as a whole
+                    if abs.contains(' ') {
+                        format!("-({})", abs)
+                    } else {
+                        format!("-{}", abs)
+                    }
                 }
             },
             Concrete::Bytes(size, b) => {
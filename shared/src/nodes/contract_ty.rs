@@ -1,4 +1,5 @@
-use crate::FunctionNode;
+use serde::{Deserialize, Serialize};
+use crate::{EnumNode, ErrorNode, EventNode, FunctionNode, StructNode, VarNode};
 use crate::AsDotStr;
 use crate::analyzer::Search;
 use crate::analyzer::{GraphLike, AnalyzerLike};
@@ -9,9 +10,11 @@ use solang_parser::pt::{ContractDefinition, ContractTy, Identifier, Loc};
 
 
 /// An index in the graph that references a [`Contract`] node
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct ContractNode(pub usize);
 
+node_newtype!(ContractNode, Contract, Contract);
+
 impl AsDotStr for ContractNode {
     fn as_dot_str(&self, analyzer: &impl GraphLike) -> String {
         let underlying = self.underlying(analyzer);
@@ -27,17 +30,6 @@ impl AsDotStr for ContractNode {
 }
 
 impl ContractNode {
-    /// Gets the underlying node data for the [`Contract`]
-    pub fn underlying<'a>(&self, analyzer: &'a impl GraphLike) -> &'a Contract {
-        match analyzer.node(*self) {
-            Node::Contract(func) => func,
-            e => panic!(
-                "Node type confusion: expected node to be Contract but it was: {:?}",
-                e
-            ),
-        }
-    }
-
     /// Gets the name from the underlying node data for the [`Contract`]
     pub fn name(&self, analyzer: &'_ impl GraphLike) -> String {
         self.underlying(analyzer)
@@ -58,12 +50,139 @@ impl ContractNode {
         self.underlying(analyzer).loc
     }
 
-    /// Gets all associated functions from the underlying node data for the [`Contract`]
+    /// Gets all associated functions from the underlying node data for the [`Contract`],
+    /// including its constructor, fallback and receive functions
     pub fn funcs(&self, analyzer: &'_ (impl GraphLike + Search)) -> Vec<FunctionNode> {
-        analyzer.search_children(self.0.into(), &Edge::Func)
-        .into_iter()
-        .map(FunctionNode::from)
-        .collect()
+        // only direct edges, inheriting contracts are connected to this one as well
+        let mut funcs = [Edge::Func, Edge::Constructor, Edge::FallbackFunc, Edge::ReceiveFunc].into_iter()
+            .flat_map(|edge| self.defined(analyzer, edge))
+            .collect::<Vec<_>>();
+        funcs.sort();
+        funcs.into_iter().map(FunctionNode::from).collect()
+    }
+
+    /// Gets the constructor defined in the contract, if it has one
+    pub fn constructor(&self, analyzer: &'_ impl GraphLike) -> Option<FunctionNode> {
+        self.defined(analyzer, Edge::Constructor).first().map(|idx| FunctionNode::from(*idx))
+    }
+
+    /// Gets the `fallback()` function that handles calls to the contract, its own or the one it
+    /// inherits
+    pub fn fallback(&self, analyzer: &'_ impl GraphLike) -> Option<FunctionNode> {
+        self.most_derived(analyzer, Edge::FallbackFunc)
+    }
+
+    /// Gets the `receive()` function that handles plain ether transfers to the contract, its own
+    /// or the one it inherits
+    pub fn receive(&self, analyzer: &'_ impl GraphLike) -> Option<FunctionNode> {
+        self.most_derived(analyzer, Edge::ReceiveFunc)
+    }
+
+    fn most_derived(&self, analyzer: &'_ impl GraphLike, edge: Edge) -> Option<FunctionNode> {
+        self.with_bases(analyzer).iter().find_map(|contract| {
+            contract.defined(analyzer, edge).first().map(|idx| FunctionNode::from(*idx))
+        })
+    }
+
+    /// Gets the state variables defined in the contract, not including those of its bases
+    pub fn vars(&self, analyzer: &'_ impl GraphLike) -> Vec<VarNode> {
+        self.defined(analyzer, Edge::Var).into_iter().map(VarNode::from).collect()
+    }
+
+    /// Gets the modifiers defined in the contract, not including those of its bases
+    pub fn modifiers(&self, analyzer: &'_ impl GraphLike) -> Vec<FunctionNode> {
+        self.defined(analyzer, Edge::Modifier).into_iter().map(FunctionNode::from).collect()
+    }
+
+    /// Gets the structs defined in the contract, not including those of its bases
+    pub fn structs(&self, analyzer: &'_ impl GraphLike) -> Vec<StructNode> {
+        self.defined(analyzer, Edge::Struct).into_iter().map(StructNode::from).collect()
+    }
+
+    /// Gets the enums defined in the contract, not including those of its bases
+    pub fn enums(&self, analyzer: &'_ impl GraphLike) -> Vec<EnumNode> {
+        self.defined(analyzer, Edge::Enum).into_iter().map(EnumNode::from).collect()
+    }
+
+    /// Gets the errors defined in the contract, not including those of its bases
+    pub fn errors(&self, analyzer: &'_ impl GraphLike) -> Vec<ErrorNode> {
+        self.defined(analyzer, Edge::Error).into_iter().map(ErrorNode::from).collect()
+    }
+
+    /// Gets the events defined directly in this contract
+    pub fn events(&self, analyzer: &'_ impl GraphLike) -> Vec<EventNode> {
+        self.defined(analyzer, Edge::Event).into_iter().map(EventNode::from).collect()
+    }
+
+    /// The nodes directly connected to the contract by `edge`, in definition order
+    fn defined(&self, analyzer: &'_ impl GraphLike, edge: Edge) -> Vec<NodeIdx> {
+        let mut nodes = analyzer.children_of_kind(*self, edge);
+        nodes.sort();
+        nodes
+    }
+
+    /// The contract followed by every contract it inherits from, directly or not, in C3
+    /// linearization order. A member found in an earlier contract overrides the same member of
+    /// a later one.
+    ///
+    /// If the hierarchy cannot be linearized, which solc rejects, the bases are visited depth
+    /// first instead
+    pub fn with_bases(&self, analyzer: &'_ impl GraphLike) -> Vec<ContractNode> {
+        self.linearize(analyzer, &mut vec![]).unwrap_or_else(|| {
+            let mut contracts: Vec<ContractNode> = vec![];
+            let mut to_visit = vec![*self];
+            while let Some(contract) = to_visit.pop() {
+                if !contracts.contains(&contract) {
+                    to_visit.extend(contract.underlying(analyzer).inherits.iter().copied());
+                    contracts.push(contract);
+                }
+            }
+            contracts
+        })
+    }
+
+    /// `L(C) = C + merge(L(Bn), ..., L(B1), [Bn, ..., B1])` for `contract C is B1, ..., Bn`,
+    /// as solidity lists bases from the most base-like to the most derived
+    fn linearize(&self, analyzer: &'_ impl GraphLike, visiting: &mut Vec<ContractNode>) -> Option<Vec<ContractNode>> {
+        if visiting.contains(self) {
+            return None;
+        }
+        visiting.push(*self);
+        let bases = self.underlying(analyzer).inherits.iter().rev().copied().collect::<Vec<_>>();
+        let mut to_merge = bases.iter()
+            .map(|base| base.linearize(analyzer, visiting))
+            .collect::<Option<Vec<_>>>()?;
+        to_merge.push(bases);
+        visiting.pop();
+
+        let mut linearized = vec![*self];
+        loop {
+            to_merge.retain(|list| !list.is_empty());
+            if to_merge.is_empty() {
+                return Some(linearized);
+            }
+            // the first head that is not in the tail of any list
+            let next = to_merge.iter()
+                .map(|list| list[0])
+                .find(|head| to_merge.iter().all(|list| !list[1..].contains(head)))?;
+            linearized.push(next);
+            to_merge.iter_mut().for_each(|list| list.retain(|contract| *contract != next));
+        }
+    }
+
+    /// Looks up a state variable of this contract or, if it has none by that name, of its bases
+    pub fn state_var(&self, analyzer: &'_ impl GraphLike, name: &str) -> Option<VarNode> {
+        self.with_bases(analyzer).iter().find_map(|contract| {
+            contract.vars(analyzer).into_iter().find(|var| var.name(analyzer) == name)
+        })
+    }
+
+    /// Looks up a user type defined in this contract or one of its bases, i.e. a struct that is
+    /// keyed as `Contract.Name`
+    pub fn scoped_user_ty(&self, analyzer: &'_ impl AnalyzerLike, name: &str) -> Option<NodeIdx> {
+        self.with_bases(analyzer).iter().find_map(|contract| {
+            analyzer.user_types().get(&format!("{}.{}", contract.name(analyzer), name)).copied()
+        })
     }
 
     pub fn associated_source_unit_part(&self, analyzer: &impl GraphLike) -> Option<NodeIdx> {
@@ -76,20 +195,8 @@ impl ContractNode {
     }
 }
 
-impl From<ContractNode> for NodeIdx {
-    fn from(val: ContractNode) -> Self {
-        val.0.into()
-    }
-}
-
-impl From<NodeIdx> for ContractNode {
-    fn from(idx: NodeIdx) -> Self {
-        ContractNode(idx.index())
-    }
-}
-
 /// A solidity contract representation
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Contract {
     /// Sourcecode location
     pub loc: Loc,
@@ -97,7 +204,8 @@ pub struct Contract {
     pub ty: ContractTy,
     /// An optional name in the form of an identifier (`(Loc, String)`)
     pub name: Option<Identifier>,
-    /// A list of contracts that this contract inherits (TODO: inheritance linearization)
+    /// The contracts this contract directly inherits from, in declaration order. See
+    /// [`ContractNode::with_bases`] for the linearized hierarchy
     pub inherits: Vec<ContractNode>,
 }
 
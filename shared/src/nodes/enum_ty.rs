@@ -1,14 +1,19 @@
+use serde::{Deserialize, Serialize};
 use crate::AsDotStr;
 use crate::analyzer::{GraphLike};
 use crate::Node;
-use crate::NodeIdx;
+use crate::Concrete;
+use crate::range::SolcRange;
+use crate::range::elem_ty::Elem;
 use solang_parser::pt::{EnumDefinition, Identifier, Loc};
 
 
 /// An index in the graph that references a [`Enum`] node
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct EnumNode(pub usize);
 
+node_newtype!(EnumNode, Enum, Enum);
+
 impl AsDotStr for EnumNode {
     fn as_dot_str(&self, analyzer: &impl GraphLike) -> String {
         let underlying = self.underlying(analyzer);
@@ -24,41 +29,28 @@ impl AsDotStr for EnumNode {
 }
 
 impl EnumNode {
-    /// Gets the underlying node data for the [`Enum`]
-    pub fn underlying<'a>(&self, analyzer: &'a impl GraphLike) -> &'a Enum {
-        match analyzer.node(*self) {
-            Node::Enum(e) => e,
-            e => panic!(
-                "Node type confusion: expected node to be Contract but it was: {:?}",
-                e
-            ),
-        }
-    }
-
     /// Gets the name of the enum from the underlying node data for the [`Enum`]
     pub fn name(&self, analyzer: &'_ impl GraphLike) -> String {
         self.underlying(analyzer)
             .name
             .clone()
-            .expect("Unnamed contract")
+            .expect("Unnamed enum")
             .name
     }
-}
 
-impl From<EnumNode> for NodeIdx {
-    fn from(val: EnumNode) -> Self {
-        val.0.into()
-    }
-}
-
-impl From<NodeIdx> for EnumNode {
-    fn from(idx: NodeIdx) -> Self {
-        EnumNode(idx.index())
+    /// The values the enum can take, `[0, variants - 1]` as the `uint8` it is stored as
+    pub fn range(&self, analyzer: &'_ impl GraphLike) -> SolcRange {
+        let variants = self.underlying(analyzer).values.len();
+        SolcRange {
+            min: Elem::from(Concrete::Uint(8, 0.into())),
+            max: Elem::from(Concrete::Uint(8, variants.saturating_sub(1).into())),
+            exclusions: vec![],
+        }
     }
 }
 
 /// A solidity enum representation
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Enum {
     pub loc: Loc,
     pub name: Option<Identifier>,
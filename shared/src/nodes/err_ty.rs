@@ -1,21 +1,31 @@
+use serde::{Deserialize, Serialize};
 use crate::AnalyzerLike;
 use crate::AsDotStr;
-use crate::{analyzer::GraphLike, Node, NodeIdx};
+use crate::{analyzer::{GraphLike, Search}, Edge, Node, NodeIdx};
 use solang_parser::pt::{ErrorDefinition, ErrorParameter, Identifier, Loc, Expression};
 
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct ErrorNode(pub usize);
+
+node_newtype!(ErrorNode, Error, Error);
+
 impl ErrorNode {
-    pub fn underlying<'a>(&self, analyzer: &'a impl GraphLike) -> &'a Error {
-        match analyzer.node(*self) {
-            Node::Error(err) => err,
-            e => panic!(
-                "Node type confusion: expected node to be Var but it was: {:?}",
-                e
-            ),
-        }
+    pub fn name(&self, analyzer: &impl GraphLike) -> String {
+        self.underlying(analyzer)
+            .name
+            .as_ref()
+            .map(|name| name.name.clone())
+            .unwrap_or_default()
+    }
+
+    /// The parameters of the error, in the order they are declared
+    pub fn params(&self, analyzer: &impl GraphLike) -> Vec<ErrorParamNode> {
+        let mut params = analyzer.children_of_kind(*self, Edge::ErrorParam);
+        params.sort();
+        params.into_iter().map(ErrorParamNode::from).collect()
     }
 }
+
 impl AsDotStr for ErrorNode {
     fn as_dot_str(&self, analyzer: &impl GraphLike) -> String {
         let underlying = self.underlying(analyzer);
@@ -29,19 +39,7 @@ impl AsDotStr for ErrorNode {
     }
 }
 
-impl From<ErrorNode> for NodeIdx {
-    fn from(val: ErrorNode) -> Self {
-        val.0.into()
-    }
-}
-
-impl From<NodeIdx> for ErrorNode {
-    fn from(idx: NodeIdx) -> Self {
-        ErrorNode(idx.index())
-    }
-}
-
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Error {
     pub loc: Loc,
     pub name: Option<Identifier>,
@@ -62,22 +60,12 @@ impl From<ErrorDefinition> for Error {
     }
 }
 
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct ErrorParamNode(pub usize);
 
-impl From<NodeIdx> for ErrorParamNode {
-    fn from(idx: NodeIdx) -> Self {
-        ErrorParamNode(idx.index())
-    }
-}
-
-impl From<ErrorParamNode> for NodeIdx {
-    fn from(val: ErrorParamNode) -> Self {
-        val.0.into()
-    }
-}
+node_newtype!(ErrorParamNode, ErrorParam, ErrorParam);
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ErrorParam {
     pub loc: Loc,
     pub ty: NodeIdx,
@@ -0,0 +1,107 @@
+use crate::analyzer::Search;
+use crate::AnalyzerLike;
+use crate::AsDotStr;
+use crate::Edge;
+use crate::{analyzer::GraphLike, Node, NodeIdx};
+use serde::{Deserialize, Serialize};
+use solang_parser::pt::{EventDefinition, EventParameter, Expression, Identifier, Loc};
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct EventNode(pub usize);
+
+node_newtype!(EventNode, Event, Event);
+
+impl EventNode {
+    /// Gets the name of the event, empty if it was not named
+    pub fn name(&self, analyzer: &impl GraphLike) -> String {
+        self.underlying(analyzer)
+            .name
+            .as_ref()
+            .map(|name| name.name.clone())
+            .unwrap_or_default()
+    }
+
+    /// Gets the parameters of the event, in declaration order
+    pub fn params(&self, analyzer: &impl GraphLike) -> Vec<EventParamNode> {
+        let mut params = analyzer
+            .children_of_kind(*self, Edge::EventParam)
+            .into_iter()
+            .map(EventParamNode::from)
+            .collect::<Vec<_>>();
+        params.sort_by_key(|param| param.underlying(analyzer).order);
+        params
+    }
+}
+
+impl AsDotStr for EventNode {
+    fn as_dot_str(&self, analyzer: &impl GraphLike) -> String {
+        let underlying = self.underlying(analyzer);
+        format!(
+            "event {}",
+            if let Some(name) = &underlying.name {
+                name.name.clone()
+            } else {
+                "".to_string()
+            },
+        )
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+    pub loc: Loc,
+    pub name: Option<Identifier>,
+    pub anonymous: bool,
+}
+
+impl From<Event> for Node {
+    fn from(val: Event) -> Self {
+        Node::Event(val)
+    }
+}
+
+impl From<EventDefinition> for Event {
+    fn from(con: EventDefinition) -> Event {
+        Event {
+            loc: con.loc,
+            name: con.name,
+            anonymous: con.anonymous,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct EventParamNode(pub usize);
+
+node_newtype!(EventParamNode, EventParam, EventParam);
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct EventParam {
+    pub loc: Loc,
+    pub ty: NodeIdx,
+    pub order: usize,
+    pub indexed: bool,
+    pub name: Option<Identifier>,
+}
+
+impl From<EventParam> for Node {
+    fn from(val: EventParam) -> Self {
+        Node::EventParam(val)
+    }
+}
+
+impl EventParam {
+    pub fn new(
+        analyzer: &mut impl AnalyzerLike<Expr = Expression>,
+        param: EventParameter,
+        order: usize,
+    ) -> Self {
+        EventParam {
+            loc: param.loc,
+            ty: analyzer.parse_expr(&param.ty),
+            order,
+            indexed: param.indexed,
+            name: param.name,
+        }
+    }
+}
@@ -1,7 +1,10 @@
+use serde::{Deserialize, Serialize};
+use ethers_core::utils::keccak256;
 use solang_parser::pt::VariableDefinition;
 use std::collections::BTreeMap;
 use solang_parser::pt::ParameterList;
 use crate::analyzer::AsDotStr;
+use crate::analyzer::Search;
 use crate::nodes::ContractNode;
 use crate::range::SolcRange;
 use crate::VarType;
@@ -11,23 +14,22 @@ use crate::context::{ContextEdge, ContextNode};
 use petgraph::{Direction, visit::EdgeRef};
 use crate::{analyzer::{GraphLike, AnalyzerLike}, Node, NodeIdx};
 use solang_parser::pt::{
-    Visibility, FunctionAttribute, FunctionDefinition, FunctionTy, Identifier, Loc, Parameter, StorageLocation, Expression, Base,
+    Visibility, FunctionAttribute, FunctionDefinition, Mutability, FunctionTy, Identifier, Loc, Parameter, StorageLocation, Expression, Base,
 };
 
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct FunctionNode(pub usize);
+
+node_newtype!(FunctionNode, Function, Function);
+
 impl FunctionNode {
-    pub fn underlying<'a>(&self, analyzer: &'a impl GraphLike) -> &'a Function {
-        match analyzer.node(*self) {
-            Node::Function(func) => func,
-            e => panic!(
-                "Node type confusion: expected node to be Function but it was: {:?}",
-                e
-            ),
-        }
+    pub fn modifiers(&self, analyzer: &impl GraphLike) -> Vec<FunctionNode> {
+        self.modifier_edges(analyzer).values().copied().collect()
     }
 
-    pub fn modifiers(&self, analyzer: &impl GraphLike) -> Vec<FunctionNode> {
+    /// The modifiers of the function keyed by their position in the function's attributes, which
+    /// can have gaps where a constructor invokes a base constructor
+    fn modifier_edges(&self, analyzer: &impl GraphLike) -> BTreeMap<usize, FunctionNode> {
         analyzer
             .graph()
             .edges_directed(self.0.into(), Direction::Incoming)
@@ -38,13 +40,15 @@ impl FunctionNode {
                     None
                 }
             })
-            .collect::<BTreeMap<_,_>>().values().copied()
             .collect()
     }
 
     pub fn modifier_input_vars(&self, mod_num: usize, analyzer: &impl GraphLike) -> Vec<Expression> {
         let modifiers = self.underlying(analyzer).modifiers_as_base();
-        if let Some(modifier) = modifiers.get(mod_num) {
+        let Some(order) = self.modifier_edges(analyzer).keys().nth(mod_num).copied() else {
+            return vec![];
+        };
+        if let Some(modifier) = modifiers.get(order) {
             if let Some(args) = &modifier.args {
                 args.to_vec()
             } else {
@@ -55,16 +59,6 @@ impl FunctionNode {
         }
     }
 
-    pub fn underlying_mut<'a>(&self, analyzer: &'a mut impl GraphLike) -> &'a mut Function {
-        match analyzer.node_mut(*self) {
-            Node::Function(func) => func,
-            e => panic!(
-                "Node type confusion: expected node to be Function but it was: {:?}",
-                e
-            ),
-        }
-    }
-
     pub fn name(&self, analyzer: &'_ impl GraphLike) -> String {
         match self.underlying(analyzer).ty {
             FunctionTy::Constructor => format!("constructor({})", self.params(analyzer).iter().map(|param| {param.ty_str(analyzer)}).collect::<Vec<_>>().join(", ")),
@@ -89,6 +83,24 @@ impl FunctionNode {
             .expect("No context for function")
     }
 
+    /// The subcontexts this function was called into, one for each time an internal call to it
+    /// was analyzed
+    pub fn call_sites(&self, analyzer: &'_ impl GraphLike) -> Vec<ContextNode> {
+        analyzer
+            .graph()
+            .edges_directed(self.0.into(), Direction::Incoming)
+            .filter(|edge| Edge::Context(ContextEdge::Call) == *edge.weight())
+            .flat_map(|edge| {
+                analyzer
+                    .graph()
+                    .edges_directed(edge.source(), Direction::Incoming)
+                    .filter(|edge| Edge::Context(ContextEdge::Subcontext) == *edge.weight())
+                    .map(|edge| ContextNode::from(edge.source()))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
     pub fn maybe_body_ctx(&self, analyzer: &'_ impl GraphLike) -> Option<ContextNode> {
         analyzer
             .graph()
@@ -101,10 +113,9 @@ impl FunctionNode {
 
     pub fn params(&self, analyzer: &'_ impl GraphLike) -> Vec<FunctionParamNode> {
         let mut params = analyzer
-            .graph()
-            .edges_directed(self.0.into(), Direction::Incoming)
-            .filter(|edge| Edge::FunctionParam == *edge.weight())
-            .map(|edge| FunctionParamNode::from(edge.source()))
+            .children_of_kind(*self, Edge::FunctionParam)
+            .into_iter()
+            .map(FunctionParamNode::from)
             .collect::<Vec<_>>();
         params.sort_by(|a, b| a.underlying(analyzer).order.cmp(&b.underlying(analyzer).order));
         params
@@ -136,26 +147,29 @@ impl FunctionNode {
     }
 
     pub fn set_modifiers(&self, analyzer: &mut (impl GraphLike + AnalyzerLike<Expr = Expression>)) {
-        let modifiers = self.underlying(analyzer).clone().modifiers(self.params(analyzer), analyzer);
-        modifiers.iter().enumerate().for_each(|(i, modifier)| {
-            analyzer.add_edge(*modifier, *self, Edge::FuncModifier(i))
+        let modifiers = self.underlying(analyzer).clone().modifiers(self.params(analyzer), self.contract(analyzer), analyzer);
+        modifiers.into_iter().for_each(|(i, modifier)| {
+            analyzer.add_edge(modifier, *self, Edge::FuncModifier(i))
         });
     }
 
+    /// Gets the return parameters of the function, in declaration order
     pub fn returns(&self, analyzer: &'_ impl GraphLike) -> Vec<FunctionReturnNode> {
-        analyzer
-            .graph()
-            .edges_directed(self.0.into(), Direction::Incoming)
-            .filter(|edge| Edge::FunctionReturn == *edge.weight())
-            .map(|edge| FunctionReturnNode::from(edge.source()))
-            .collect()
+        let mut rets = analyzer
+            .children_of_kind(*self, Edge::FunctionReturn)
+            .into_iter()
+            .map(FunctionReturnNode::from)
+            .collect::<Vec<_>>();
+        rets.sort();
+        rets
     }
 
+    /// The contract the function, constructor or modifier is defined in
     pub fn contract(&self, analyzer: &'_ impl GraphLike) -> Option<ContractNode> {
         analyzer
             .graph()
             .edges_directed(self.0.into(), Direction::Outgoing)
-            .filter(|edge| Edge::Func == *edge.weight())
+            .filter(|edge| matches!(edge.weight(), Edge::Func | Edge::Modifier | Edge::Constructor | Edge::FallbackFunc | Edge::ReceiveFunc))
             .map(|edge| edge.target())
             .filter(|node| matches!(analyzer.node(*node), Node::Contract(_)))
             .map(ContractNode::from)
@@ -171,6 +185,96 @@ impl FunctionNode {
             )
         })
     }
+    /// The 4 byte selector an external call to the function starts with, which is the start
+    /// of the hash of its canonical signature, i.e. `transfer(address,uint256)`. Only public
+    /// and external functions have one
+    pub fn selector(&self, analyzer: &'_ impl GraphLike) -> Option<[u8; 4]> {
+        if self.underlying(analyzer).ty != FunctionTy::Function || !self.is_public_or_ext(analyzer) {
+            return None;
+        }
+        let name = self.name(analyzer);
+        let name = name.split('(').next().unwrap_or_default();
+        let params = self.params(analyzer).iter().map(|param| {
+            VarType::try_from_idx(analyzer, param.ty(analyzer))?.canonical_abi_string(analyzer)
+        }).collect::<Option<Vec<_>>>()?;
+        let hash = keccak256(format!("{}({})", name, params.join(",")));
+        Some([hash[0], hash[1], hash[2], hash[3]])
+    }
+
+    /// The declared state mutability of the function
+    pub fn mutability(&self, analyzer: &'_ impl GraphLike) -> StateMutability {
+        self.underlying(analyzer).mutability()
+    }
+
+    /// Whether every path through the function's body reverts, making it unusable
+    pub fn always_reverts(&self, analyzer: &'_ impl AnalyzerLike) -> bool {
+        if let Some(body_ctx) = self.maybe_body_ctx(analyzer) {
+            body_ctx.terminal_child_list(analyzer).iter().all(|ctx| ctx.is_killed(analyzer))
+        } else {
+            false
+        }
+    }
+
+    /// The user defined functions directly called by this function's body, in call order
+    pub fn callees(&self, analyzer: &'_ impl GraphLike) -> Vec<FunctionNode> {
+        let mut callees = vec![];
+        let mut stack: Vec<ContextNode> = self.maybe_body_ctx(analyzer).into_iter().collect();
+        while let Some(ctx) = stack.pop() {
+            let underlying = ctx.underlying(analyzer);
+            let mut descend = vec![];
+            underlying.children.iter().for_each(|child| {
+                let child_ctx = child.underlying(analyzer);
+                match child_ctx.fn_call.or(child_ctx.ext_fn_call) {
+                    Some(func) if func != *self && !matches!(func.underlying(analyzer).ty, FunctionTy::Modifier) => {
+                        if !callees.contains(&func) {
+                            callees.push(func);
+                        }
+                    }
+                    // loops, modifiers and resuming this function after a modifier all
+                    // execute code on behalf of this function
+                    _ => descend.push(*child),
+                }
+            });
+            stack.extend(underlying.forks.iter().rev());
+            stack.extend(descend.into_iter().rev());
+        }
+        callees
+    }
+
+    /// A rough measure of how hard the function is to follow: the number of points where its
+    /// body forks into separate paths, plus one for each loop whose body is analyzed as a
+    /// separate context. Calls into other functions don't add to it, since they have their own
+    pub fn complexity(&self, analyzer: &'_ impl GraphLike) -> usize {
+        let mut complexity = 0;
+        let mut stack: Vec<ContextNode> = self.maybe_body_ctx(analyzer).into_iter().collect();
+        while let Some(ctx) = stack.pop() {
+            complexity += analyzer
+                .graph()
+                .edges_directed(ctx.0.into(), Direction::Incoming)
+                .filter(|edge| {
+                    *edge.weight() == Edge::Context(ContextEdge::ContextFork)
+                        && matches!(analyzer.node(edge.source()), Node::ContextFork)
+                })
+                .count();
+            let underlying = ctx.underlying(analyzer);
+            underlying.children.iter().for_each(|child| {
+                let child_ctx = child.underlying(analyzer);
+                match child_ctx.fn_call.or(child_ctx.ext_fn_call) {
+                    // the body of a loop that wasn't unrolled, which loops back on itself
+                    None => {
+                        complexity += 1;
+                        stack.push(*child);
+                    }
+                    Some(func) if func == *self || matches!(func.underlying(analyzer).ty, FunctionTy::Modifier) => {
+                        stack.push(*child);
+                    }
+                    Some(_) => {}
+                }
+            });
+            stack.extend(underlying.forks.iter());
+        }
+        complexity
+    }
 }
 
 impl AsDotStr for FunctionNode {
@@ -193,19 +297,7 @@ impl AsDotStr for FunctionNode {
     }
 }
 
-impl From<FunctionNode> for NodeIdx {
-    fn from(val: FunctionNode) -> Self {
-        val.0.into()
-    }
-}
-
-impl From<NodeIdx> for FunctionNode {
-    fn from(idx: NodeIdx) -> Self {
-        FunctionNode(idx.index())
-    }
-}
-
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Function {
     pub loc: Loc,
     pub ty: FunctionTy,
@@ -217,39 +309,59 @@ pub struct Function {
     pub returns: ParameterList,
 }
 
+/// Whether a function may read or modify state and receive ether, as declared by its attributes
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum StateMutability {
+    Pure,
+    View,
+    Payable,
+    NonPayable,
+}
+
 impl Function {
-    pub fn modifiers(&self, func_params: Vec<FunctionParamNode>, analyzer: &(impl GraphLike + AnalyzerLike<Expr = Expression>)) -> Vec<FunctionNode> {
-        use std::fmt::Write;
-        let modifiers = self.modifiers_as_base();
-        if modifiers.is_empty() {
-            vec![]
-        } else {
-            modifiers.iter().filter_map(|modifier| {
-                assert_eq!(modifier.name.identifiers.len(), 1);
-                // construct arg string for function selector
-                let mut mod_name = format!("{}(", modifier.name.identifiers[0]);
-                if let Some(args) = &modifier.args {
-                    let args_str = args.iter().map(|expr| {
-                        match expr {
-                            Expression::Variable(ident) => {
-                                let func_param_ty_str = func_params.iter().find(|param| {
-                                    if let Some(param_name) = param.maybe_name(analyzer) {
-                                        param_name == ident.name
-                                    } else {
-                                        false
-                                    }
-                                }).map(|param| param.ty_str(analyzer)).unwrap_or_else(|| "".to_string());
-                                func_param_ty_str
-                            }
-                            e => todo!("here: {e:?}")
-                        }
-                    }).collect::<Vec<_>>().join(", ");
-                    let _ = write!(mod_name, "{}", args_str);
+    /// The declared state mutability, functions without a mutability attribute are nonpayable
+    pub fn mutability(&self) -> StateMutability {
+        self.attributes.iter().find_map(|attr| match attr {
+            FunctionAttribute::Mutability(Mutability::Pure(_)) => Some(StateMutability::Pure),
+            // `constant` is the pre 0.5 spelling of `view`
+            FunctionAttribute::Mutability(Mutability::View(_) | Mutability::Constant(_)) => Some(StateMutability::View),
+            FunctionAttribute::Mutability(Mutability::Payable(_)) => Some(StateMutability::Payable),
+            _ => None,
+        }).unwrap_or(StateMutability::NonPayable)
+    }
+
+    /// Resolves the modifiers of the function, looking in the contract it is defined in first,
+    /// along with their position in the function's attributes. Base constructor invocations of a
+    /// constructor are left out
+    pub fn modifiers(&self, func_params: Vec<FunctionParamNode>, contract: Option<ContractNode>, analyzer: &(impl GraphLike + AnalyzerLike<Expr = Expression>)) -> Vec<(usize, FunctionNode)> {
+        self.modifiers_as_base().iter().enumerate().filter_map(|(i, modifier)| {
+            let name = &modifier.name.identifiers.last()?.name;
+            // construct arg string for function selector, which is only possible when every
+            // argument is a parameter of the function
+            let args_str = modifier.args.iter().flatten().map(|expr| {
+                match expr {
+                    Expression::Variable(ident) => {
+                        func_params.iter().find(|param| {
+                            param.maybe_name(analyzer).as_ref() == Some(&ident.name)
+                        }).map(|param| param.ty_str(analyzer))
+                    }
+                    _ => None
                 }
-                let _ = write!(mod_name, ")");
-                analyzer.user_types().get(&mod_name).map(|idx| FunctionNode::from(*idx))
-            }).collect()
-        }
+            }).collect::<Option<Vec<_>>>().map(|args| args.join(", "));
+            let exact = args_str.and_then(|args_str| {
+                let mod_name = format!("{name}({args_str})");
+                contract.and_then(|contract| contract.scoped_user_ty(analyzer, &mod_name))
+                    .or_else(|| analyzer.user_types().get(&mod_name).copied())
+            });
+            // modifiers cannot be overloaded, so the name alone identifies one
+            let modifier = exact.map(FunctionNode::from).or_else(|| {
+                contract?.with_bases(analyzer).iter().find_map(|contract| {
+                    contract.modifiers(analyzer).into_iter()
+                        .find(|modifier| modifier.name(analyzer).starts_with(&format!("{name}(")))
+                })
+            })?;
+            Some((i, modifier))
+        }).collect()
     }
 
     pub fn modifiers_as_base(&self) -> Vec<&Base> {
@@ -290,7 +402,10 @@ impl From<VariableDefinition> for Function {
             ty: FunctionTy::Function,
             name: var.name.clone(),
             name_loc: var.loc,
-            attributes: vec![FunctionAttribute::Visibility(Visibility::Public(Some(var.loc)))],
+            attributes: vec![
+                FunctionAttribute::Visibility(Visibility::Public(Some(var.loc))),
+                FunctionAttribute::Mutability(Mutability::View(var.loc)),
+            ],
             body: Some(Statement::Block {
                 loc: var.loc,
                 unchecked: false,
@@ -307,9 +422,11 @@ impl From<VariableDefinition> for Function {
     }
 }
 
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct FunctionParamNode(pub usize);
 
+node_newtype!(FunctionParamNode, FunctionParam, FunctionParam);
+
 impl AsDotStr for FunctionParamNode {
     fn as_dot_str(&self, analyzer: &impl GraphLike) -> String {
         let var_ty = VarType::try_from_idx(analyzer, self.underlying(analyzer).ty).expect("Non-typeable as type");
@@ -330,16 +447,6 @@ impl AsDotStr for FunctionParamNode {
 }
 
 impl FunctionParamNode {
-    pub fn underlying<'a>(&self, analyzer: &'a impl GraphLike) -> &'a FunctionParam {
-        match analyzer.node(*self) {
-            Node::FunctionParam(param) => param,
-            e => panic!(
-                "Node type confusion: expected node to be FunctionParam but it was: {:?}",
-                e
-            ),
-        }
-    }
-
     pub fn name(&self, analyzer: &'_ impl GraphLike) -> String {
         self.underlying(analyzer)
             .name
@@ -375,19 +482,7 @@ impl FunctionParamNode {
     }
 }
 
-impl From<FunctionParamNode> for NodeIdx {
-    fn from(val: FunctionParamNode) -> Self {
-        val.0.into()
-    }
-}
-
-impl From<NodeIdx> for FunctionParamNode {
-    fn from(idx: NodeIdx) -> Self {
-        FunctionParamNode(idx.index())
-    }
-}
-
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct FunctionParam {
     pub loc: Loc,
     pub ty: NodeIdx,
@@ -414,9 +509,11 @@ impl FunctionParam {
     }
 }
 
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct FunctionReturnNode(pub usize);
 
+node_newtype!(FunctionReturnNode, FunctionReturn, FunctionReturn);
+
 impl AsDotStr for FunctionReturnNode {
     fn as_dot_str(&self, analyzer: &impl GraphLike) -> String {
         let var_ty = VarType::try_from_idx(analyzer, self.underlying(analyzer).ty).expect("Non-typeable as type");
@@ -437,33 +534,16 @@ impl AsDotStr for FunctionReturnNode {
 }
 
 impl FunctionReturnNode {
-    pub fn underlying<'a>(&self, analyzer: &'a impl GraphLike) -> &'a FunctionReturn {
-        match analyzer.node(*self) {
-            Node::FunctionReturn(ret) => ret,
-            e => panic!(
-                "Node type confusion: expected node to be FunctionParam but it was: {:?}",
-                e
-            ),
-        }
-    }
-
     pub fn maybe_name(&self, analyzer: &'_ impl GraphLike) -> Option<String> {
         Some(self.underlying(analyzer)
             .name
             .clone()?
             .name)
     }
-}
-
-impl From<FunctionReturnNode> for NodeIdx {
-    fn from(val: FunctionReturnNode) -> Self {
-        val.0.into()
-    }
-}
 
-impl From<NodeIdx> for FunctionReturnNode {
-    fn from(idx: NodeIdx) -> Self {
-        FunctionReturnNode(idx.index())
+    /// The range of the values returned here, if the function has returned through it
+    pub fn range(&self, analyzer: &'_ impl GraphLike) -> Option<SolcRange> {
+        self.underlying(analyzer).range.clone()
     }
 }
 
@@ -473,12 +553,15 @@ impl From<FunctionReturn> for Node {
     }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct FunctionReturn {
     pub loc: Loc,
     pub ty: NodeIdx,
     pub storage: Option<StorageLocation>,
     pub name: Option<Identifier>,
+    /// The union of the ranges of every value returned here across all analyzed `return`s
+    #[serde(default)]
+    pub range: Option<SolcRange>,
 }
 
 impl FunctionReturn {
@@ -488,6 +571,7 @@ impl FunctionReturn {
             ty: analyzer.parse_expr(&param.ty),
             storage: param.storage,
             name: param.name,
+            range: None,
         }
     }
 }
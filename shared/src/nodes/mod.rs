@@ -1,4 +1,5 @@
 //! Solidity and EVM specific representations as nodes in the graph
+use serde::{Deserialize, Serialize};
 use solang_parser::pt::Expression;
 use crate::GraphLike;
 use crate::analyzer::AsDotStr;
@@ -10,6 +11,8 @@ use crate::analyzer::AnalyzerLike;
 use crate::Node;
 use crate::NodeIdx;
 use solang_parser::pt::Type;
+use std::collections::BTreeMap;
+use ethers_core::types::{Address, H256, I256, U256};
 
 mod contract_ty;
 pub use contract_ty::*;
@@ -21,6 +24,8 @@ mod func_ty;
 pub use func_ty::*;
 mod err_ty;
 pub use err_ty::*;
+mod event_ty;
+pub use event_ty::*;
 mod var_ty;
 pub use var_ty::*;
 mod ty_ty;
@@ -32,9 +37,11 @@ pub use msg::*;
 mod block;
 pub use block::*;
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
 pub enum VarType {
-    User(TypeNode),
+    /// A user defined type, with the range of its values if it has one (i.e. an enum variable
+    /// narrowed down to some of its variants)
+    User(TypeNode, Option<SolcRange>),
     BuiltIn(BuiltInNode, Option<SolcRange>),
     Concrete(ConcreteNode),
 }
@@ -54,6 +61,55 @@ impl VarType {
 
     }
 
+    pub fn is_dyn(&self, analyzer: &impl GraphLike) -> bool {
+        match self {
+            Self::BuiltIn(node, _) => node.is_dyn(analyzer),
+            Self::Concrete(node) => matches!(node.underlying(analyzer), Concrete::DynBytes(..) | Concrete::String(..) | Concrete::Array(..)),
+            _ => false
+        }
+    }
+
+    pub fn is_mapping(&self, analyzer: &impl GraphLike) -> bool {
+        match self {
+            Self::BuiltIn(node, _) => matches!(node.underlying(analyzer), Builtin::Mapping(..)),
+            _ => false
+        }
+    }
+
+    /// Updates the nodes this type refers to after the graph has been compacted
+    pub fn update_idxs(&mut self, mapping: &BTreeMap<NodeIdx, NodeIdx>) {
+        let update = |idx: NodeIdx| *mapping.get(&idx).unwrap_or(&idx);
+        match self {
+            Self::User(ty_node, range) => {
+                match ty_node {
+                    TypeNode::Contract(n) => *n = update((*n).into()).into(),
+                    TypeNode::Struct(n) => *n = update((*n).into()).into(),
+                    TypeNode::Enum(n) => *n = update((*n).into()).into(),
+                    TypeNode::Func(n) => *n = update((*n).into()).into(),
+                }
+                if let Some(range) = range {
+                    range.update_idxs(mapping);
+                }
+            }
+            Self::BuiltIn(n, range) => {
+                *n = update((*n).into()).into();
+                if let Some(range) = range {
+                    range.update_idxs(mapping);
+                }
+            }
+            Self::Concrete(n) => *n = update((*n).into()).into(),
+        }
+    }
+
+    /// The node of the type itself, ignoring any range information
+    pub fn ty_idx(&self) -> NodeIdx {
+        match self {
+            Self::User(ty_node, _) => (*ty_node).into(),
+            Self::BuiltIn(node, _) => (*node).into(),
+            Self::Concrete(node) => (*node).into(),
+        }
+    }
+
     pub fn concrete_to_builtin(&mut self, analyzer: &mut impl AnalyzerLike) {
         if let VarType::Concrete(cnode) = self {
             let c = cnode.underlying(analyzer).clone();
@@ -93,9 +149,13 @@ impl VarType {
                     );
                     *self = new_ty;
                 }
-                // Concrete::Bytes(size, val) => ,
-                // Concrete::Address(Address),
-                // Concrete::DynBytes(Vec<u8>),
+                crate::Concrete::DynBytes(_) => {
+                    let new_ty = VarType::BuiltIn(
+                        BuiltInNode::from(analyzer.builtin_or_add(Builtin::DynamicBytes)),
+                        SolcRange::from(c)
+                    );
+                    *self = new_ty;
+                }
                 // Concrete::Array(Vec<Concrete>),
                 _ => {}
             }
@@ -117,10 +177,10 @@ impl VarType {
             //     )),
             //     DynBuiltin::Mapping(_, _) => Some(VarType::Mapping(node.into())),
             // },
-            Node::Contract(_) => Some(VarType::User(TypeNode::Contract(node.into()))),
-            Node::Function(_) => Some(VarType::User(TypeNode::Func(node.into()))),
-            Node::Struct(_) => Some(VarType::User(TypeNode::Struct(node.into()))),
-            Node::Enum(_) => Some(VarType::User(TypeNode::Enum(node.into()))),
+            Node::Contract(_) => Some(VarType::User(TypeNode::Contract(node.into()), None)),
+            Node::Function(_) => Some(VarType::User(TypeNode::Func(node.into()), None)),
+            Node::Struct(_) => Some(VarType::User(TypeNode::Struct(node.into()), None)),
+            Node::Enum(_) => Some(VarType::User(TypeNode::Enum(node.into()), None)),
             Node::Concrete(_) => Some(VarType::Concrete(node.into())),
             Node::ContextVar(cvar) => Some(cvar.ty.clone()),
             Node::Var(var) => VarType::try_from_idx(analyzer, var.ty),
@@ -131,6 +191,8 @@ impl VarType {
             | Node::FunctionParam(..)
             | Node::FunctionReturn(..)
             | Node::ErrorParam(..)
+            | Node::Event(..)
+            | Node::EventParam(..)
             | Node::Field(..)
             | Node::SourceUnitPart(..)
             | Node::SourceUnit(..)
@@ -154,14 +216,14 @@ impl VarType {
                 let c = from_c.underlying(analyzer).clone();
                 let b = to_bn.underlying(analyzer);
                 let casted = c.cast(b.clone())?;
-                let node = analyzer.add_node(Node::Concrete(casted));
+                let node = analyzer.concrete_or_add(casted);
                 Some(Self::Concrete(node.into()))
             }
             (Self::Concrete(from_c), Self::Concrete(to_c)) => {
                 let c = from_c.underlying(analyzer).clone();
                 let to_c = to_c.underlying(analyzer);
                 let casted = c.cast_from(to_c)?;
-                let node = analyzer.add_node(Node::Concrete(casted));
+                let node = analyzer.concrete_or_add(casted);
                 Some(Self::Concrete(node.into()))
             }
             _ => None,
@@ -198,6 +260,8 @@ impl VarType {
             Self::BuiltIn(_, Some(range)) => Some(range.clone()),
             Self::BuiltIn(bn, None) => SolcRange::try_from_builtin(bn.underlying(analyzer)),
             Self::Concrete(cnode) => SolcRange::from(cnode.underlying(analyzer).clone()),
+            Self::User(TypeNode::Enum(_), Some(range)) => Some(range.clone()),
+            Self::User(TypeNode::Enum(enum_node), None) => Some(enum_node.range(analyzer)),
             _ => None,
         }
     }
@@ -206,6 +270,7 @@ impl VarType {
         match self {
             Self::BuiltIn(bn, _) => SolcRange::try_from_builtin(bn.underlying(analyzer)),
             Self::Concrete(cnode) => SolcRange::from(cnode.underlying(analyzer).clone()),
+            Self::User(TypeNode::Enum(enum_node), _) => Some(enum_node.range(analyzer)),
             _ => None,
         }
     }
@@ -213,7 +278,7 @@ impl VarType {
     pub fn is_const(&self, analyzer: &impl GraphLike) -> bool {
         match self {
             Self::Concrete(_) => true,
-            Self::User(TypeNode::Func(_)) => false,
+            Self::User(TypeNode::Func(_), _) => false,
             _ => {
                 if let Some(range) = self.range(analyzer) {
                     let min = range.evaled_range_min(analyzer);
@@ -228,7 +293,7 @@ impl VarType {
 
     pub fn func_node(&self, _analyzer: &impl AnalyzerLike) -> Option<FunctionNode> {
         match self {
-            Self::User(TypeNode::Func(func_node)) => Some(*func_node),
+            Self::User(TypeNode::Func(func_node), _) => Some(*func_node),
             _ => None
         }
     }
@@ -254,7 +319,7 @@ impl VarType {
 
     pub fn ty_eq(&self, other: &Self, analyzer: &impl GraphLike) -> bool {
         match (self, other) {
-            (VarType::User(s), VarType::User(o)) => s == o,
+            (VarType::User(s, _), VarType::User(o, _)) => s == o,
             (VarType::BuiltIn(s, _), VarType::BuiltIn(o, _)) => s.underlying(analyzer) == o.underlying(analyzer),
             (VarType::Concrete(s), VarType::Concrete(o)) => {
                 s.underlying(analyzer).equivalent_ty(o.underlying(analyzer))
@@ -265,7 +330,7 @@ impl VarType {
 
     pub fn as_string(&self, analyzer: &impl GraphLike) -> String {
         match self {
-            VarType::User(ty_node) => ty_node.as_string(analyzer),
+            VarType::User(ty_node, _) => ty_node.as_string(analyzer),
             VarType::BuiltIn(bn, _) => {
                 match analyzer.node(*bn) {
                     Node::Builtin(bi) => bi.as_string(analyzer),
@@ -278,6 +343,26 @@ impl VarType {
         }
     }
 
+    /// The name of the type as it appears in a canonical function signature, i.e. `uint256`
+    /// for `uint`, `address` for a contract and a tuple of the field types for a struct.
+    /// `None` for types that can't cross an external call, like mappings
+    pub fn canonical_abi_string(&self, analyzer: &impl GraphLike) -> Option<String> {
+        match self {
+            VarType::User(TypeNode::Contract(_), _) => Some("address".to_string()),
+            // enums are at most 256 members, so they are always encoded as a `uint8`
+            VarType::User(TypeNode::Enum(_), _) => Some("uint8".to_string()),
+            VarType::User(TypeNode::Struct(strukt), _) => {
+                let fields = strukt.fields(analyzer).iter().map(|field| {
+                    VarType::try_from_idx(analyzer, field.underlying(analyzer).ty)?.canonical_abi_string(analyzer)
+                }).collect::<Option<Vec<_>>>()?;
+                Some(format!("({})", fields.join(",")))
+            }
+            VarType::User(TypeNode::Func(_), _) => Some("function".to_string()),
+            VarType::BuiltIn(bn, _) => bn.underlying(analyzer).canonical_abi_string(analyzer),
+            VarType::Concrete(c) => c.underlying(analyzer).as_builtin().canonical_abi_string(analyzer),
+        }
+    }
+
     pub fn is_int(&self, analyzer: &impl GraphLike) -> bool {
         match self {
             VarType::BuiltIn(bn, _) => {
@@ -291,7 +376,7 @@ impl VarType {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
 pub enum TypeNode {
     Contract(ContractNode),
     Struct(StructNode),
@@ -321,20 +406,12 @@ impl From<TypeNode> for NodeIdx {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
 pub struct BuiltInNode(pub usize);
 
-impl BuiltInNode {
-    pub fn underlying<'a>(&self, analyzer: &'a impl GraphLike) -> &'a Builtin {
-        match analyzer.node(*self) {
-            Node::Builtin(b) => b,
-            e => panic!(
-                "Node type confusion: expected node to be Builtin but it was: {:?}",
-                e
-            ),
-        }
-    }
+node_newtype!(BuiltInNode, Builtin, Builtin);
 
+impl BuiltInNode {
     pub fn num_size(&self, analyzer: &impl AnalyzerLike) -> Option<u16> {
         let underlying = self.underlying(analyzer);
         underlying.num_size()
@@ -351,11 +428,14 @@ impl BuiltInNode {
 
     pub fn array_underlying_ty(&self, analyzer: &mut impl AnalyzerLike) -> VarType {
         match self.underlying(analyzer) {
-            Builtin::Array(v_ty) => {
+            Builtin::Array(v_ty) | Builtin::SizedArray(_, v_ty) | Builtin::Mapping(_, v_ty) => {
                 v_ty.clone()
             },
             Builtin::DynamicBytes => {
-                VarType::BuiltIn(analyzer.builtin_or_add(Builtin::Bytes(1)).into(), Some(SolcRange { min: Elem::from(Concrete::from(vec![0x00])), max: Elem::from(Concrete::from(vec![0xff])), exclusions: vec![]}))
+                // a single byte, typed as `bytes1` so it compares with and is assigned to one
+                let byte = Builtin::Bytes(1);
+                let range = SolcRange::try_from_builtin(&byte);
+                VarType::BuiltIn(analyzer.builtin_or_add(byte).into(), range)
             },
             e => panic!(
                 "Node type confusion: expected node to be Builtin::Array but it was: {:?}",
@@ -369,19 +449,7 @@ impl BuiltInNode {
     }
 }
 
-impl From<NodeIdx> for BuiltInNode {
-    fn from(idx: NodeIdx) -> Self {
-        BuiltInNode(idx.index())
-    }
-}
-
-impl From<BuiltInNode> for NodeIdx {
-    fn from(val: BuiltInNode) -> Self {
-        val.0.into()
-    }
-}
-
-#[derive(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Ord, PartialOrd, Serialize, Deserialize)]
 pub enum Builtin {
     Address,
     AddressPayable,
@@ -392,19 +460,38 @@ pub enum Builtin {
     Uint(u16),
     Bytes(u8),
     Rational,
+    /// A signed fixed point number, with its size in bits and its number of decimals. Fixed
+    /// point numbers can be declared but not operated on, so they carry no range
+    Fixed(u16, u8),
+    /// An unsigned fixed point number, see [`Builtin::Fixed`]
+    Ufixed(u16, u8),
     DynamicBytes,
     Array(VarType),
+    SizedArray(U256, VarType),
     Mapping(VarType, VarType),
     Func(Vec<VarType>, Vec<VarType>),
 }
 
 impl Builtin {
+    /// Updates the nodes referenced by inner types after the graph has been compacted
+    pub fn update_idxs(&mut self, mapping: &BTreeMap<NodeIdx, NodeIdx>) {
+        match self {
+            Builtin::Array(inner) | Builtin::SizedArray(_, inner) => inner.update_idxs(mapping),
+            Builtin::Mapping(key, val) => {
+                key.update_idxs(mapping);
+                val.update_idxs(mapping);
+            }
+            Builtin::Func(inputs, outputs) => inputs.iter_mut().chain(outputs.iter_mut()).for_each(|ty| ty.update_idxs(mapping)),
+            _ => {}
+        }
+    }
+
     pub fn try_from_ty(ty: Type, analyzer: &mut impl AnalyzerLike<Expr = Expression>) -> Option<Builtin> {
         use Type::*;
         match ty {
             Address => Some(Builtin::Address),
-            AddressPayable => Some(Builtin::AddressPayable),
-            Payable => Some(Builtin::Payable),
+            // `payable` on its own only shows up in `payable(addr)` conversions
+            AddressPayable | Payable => Some(Builtin::AddressPayable),
             Bool => Some(Builtin::Bool),
             String => Some(Builtin::String),
             Int(size) => Some(Builtin::Int(size)),
@@ -426,6 +513,84 @@ impl Builtin {
                 }
                 Some(Builtin::Func(inputs, outputs))
             }
+            Mapping(_loc, key, val) => {
+                // nested mappings are parsed, and interned, as the value type
+                let key_idx = analyzer.parse_expr(&key);
+                let val_idx = analyzer.parse_expr(&val);
+                let key = VarType::try_from_idx(analyzer, key_idx)?;
+                let val = VarType::try_from_idx(analyzer, val_idx)?;
+                Some(Builtin::Mapping(key, val))
+            }
+        }
+    }
+
+    /// Parses the name of a fixed point type, i.e. `ufixed128x18`. The parser doesn't know
+    /// these types and hands them over as identifiers
+    pub fn try_from_fixed_name(name: &str) -> Option<Builtin> {
+        let (signed, rest) = match name.strip_prefix("ufixed") {
+            Some(rest) => (false, rest),
+            None => (true, name.strip_prefix("fixed")?),
+        };
+        let (size, decimals) = if rest.is_empty() {
+            (128, 18)
+        } else {
+            let (size, decimals) = rest.split_once('x')?;
+            (size.parse::<u16>().ok()?, decimals.parse::<u8>().ok()?)
+        };
+        if size == 0 || size > 256 || size % 8 != 0 || decimals > 80 {
+            return None;
+        }
+        if signed {
+            Some(Builtin::Fixed(size, decimals))
+        } else {
+            Some(Builtin::Ufixed(size, decimals))
+        }
+    }
+
+    /// The bit width of a fixed size builtin, `None` for types without one like `address`
+    pub fn size(&self) -> Option<u16> {
+        match self {
+            Builtin::Uint(size) | Builtin::Int(size) => Some(*size),
+            Builtin::Bytes(size) => Some(*size as u16 * 8),
+            _ => None,
+        }
+    }
+
+    /// The largest value of a sized builtin, i.e. what `type(T).max` evaluates to for integers
+    pub fn max_value(&self) -> Option<Concrete> {
+        match self {
+            Builtin::Uint(size) => Concrete::max(&Concrete::Uint(*size, U256::zero())),
+            Builtin::Int(size) => Concrete::max(&Concrete::Int(*size, I256::zero())),
+            Builtin::Bytes(size) => {
+                let mut h = H256::default();
+                h.0[..*size as usize].fill(0xff);
+                Some(Concrete::Bytes(*size, h))
+            }
+            _ => None,
+        }
+    }
+
+    /// The smallest value of a sized builtin, i.e. what `type(T).min` evaluates to for integers
+    pub fn min_value(&self) -> Option<Concrete> {
+        match self {
+            Builtin::Uint(size) => Some(Concrete::Uint(*size, U256::zero())),
+            Builtin::Int(size) => match self.max_value()? {
+                Concrete::Int(_, max) => Some(Concrete::Int(*size, -max - I256::one())),
+                _ => None,
+            },
+            Builtin::Bytes(size) => Some(Concrete::Bytes(*size, H256::zero())),
+            _ => None,
+        }
+    }
+
+    /// The value a variable of the builtin holds before it is assigned, or after a `delete`
+    pub fn zero_value(&self) -> Option<Concrete> {
+        match self {
+            Builtin::Uint(size) => Some(Concrete::Uint(*size, U256::zero())),
+            Builtin::Int(size) => Some(Concrete::Int(*size, I256::zero())),
+            Builtin::Bytes(size) => Some(Concrete::Bytes(*size, H256::zero())),
+            Builtin::Address | Builtin::AddressPayable | Builtin::Payable => Some(Concrete::Address(Address::zero())),
+            Builtin::Bool => Some(Concrete::Bool(false)),
             _ => None,
         }
     }
@@ -451,9 +616,14 @@ impl Builtin {
         match (self, other) {
             (Address, Address) => true,
             (AddressPayable, Address) => true,
+            (AddressPayable, AddressPayable) => true,
             (Payable, Address) => true,
             (Bool, Bool) => true,
             (Rational, Rational) => true,
+            (Fixed(from_size, from_decimals), Fixed(to_size, to_decimals))
+            | (Ufixed(from_size, from_decimals), Ufixed(to_size, to_decimals)) => {
+                from_size <= to_size && from_decimals == to_decimals
+            }
             (DynamicBytes, DynamicBytes) => true,
             (String, String) => true,
             (Uint(from_size), Uint(to_size)) => {
@@ -489,21 +659,36 @@ impl Builtin {
         use Builtin::*;
         match self {
             Address => "address".to_string(),
-            AddressPayable => "payable".to_string(),
-            Payable => "payable".to_string(),
+            AddressPayable | Payable => "address payable".to_string(),
             Bool => "bool".to_string(),
             String => "string".to_string(),
             Int(size) => format!("int{}", size),
             Uint(size) => format!("uint{}", size),
             Bytes(size) => format!("bytes{}", size),
             Rational => "rational".to_string(),
+            Fixed(size, decimals) => format!("fixed{}x{}", size, decimals),
+            Ufixed(size, decimals) => format!("ufixed{}x{}", size, decimals),
             DynamicBytes => "bytes".to_string(),
             Array(v_ty) => format!("{}[]", v_ty.as_string(analyzer)),
+            SizedArray(len, v_ty) => format!("{}[{}]", v_ty.as_string(analyzer), len),
             Mapping(key_ty, v_ty) => format!("mapping ({} => {})", key_ty.as_string(analyzer), v_ty.as_string(analyzer)),
             Func(inputs, outputs) => format!("function({}) returns ({})",
                 inputs.iter().map(|input| input.as_string(analyzer)).collect::<Vec<_>>().join(", "), outputs.iter().map(|output| output.as_string(analyzer)).collect::<Vec<_>>().join(", "))
         }
     }
+
+    /// See [`VarType::canonical_abi_string`]
+    pub fn canonical_abi_string(&self, analyzer: &impl GraphLike) -> Option<String> {
+        use Builtin::*;
+        match self {
+            Address | AddressPayable | Payable => Some("address".to_string()),
+            Array(v_ty) => Some(format!("{}[]", v_ty.canonical_abi_string(analyzer)?)),
+            SizedArray(len, v_ty) => Some(format!("{}[{}]", v_ty.canonical_abi_string(analyzer)?, len)),
+            Func(..) => Some("function".to_string()),
+            Mapping(..) | Rational => None,
+            _ => Some(self.as_string(analyzer)),
+        }
+    }
 }
 
 // #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use crate::AnalyzerLike;
 use solang_parser::pt::Loc;
 use crate::ContextVar;
@@ -7,24 +8,11 @@ use ethers_core::types::Address;
 use ethers_core::types::U256;
 use crate::GraphLike;
 use crate::analyzer::AsDotStr;
-use crate::Node;
-use crate::NodeIdx;
 
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct MsgNode(pub usize);
 
-impl MsgNode {
-    pub fn underlying<'a>(&self, analyzer: &'a impl GraphLike) -> &'a Msg {
-        match analyzer.node(*self) {
-            Node::Msg(st) => st,
-            e => panic!(
-                "Node type confusion: expected node to be Msg but it was: {:?}",
-                e
-            ),
-        }
-    }
-}
-
+node_newtype!(MsgNode, Msg, Msg);
 
 impl AsDotStr for MsgNode {
     fn as_dot_str(&self, analyzer: &impl GraphLike) -> String {
@@ -32,19 +20,7 @@ impl AsDotStr for MsgNode {
     }
 }
 
-impl From<MsgNode> for NodeIdx {
-    fn from(val: MsgNode) -> Self {
-        val.0.into()
-    }
-}
-
-impl From<NodeIdx> for MsgNode {
-    fn from(idx: NodeIdx) -> Self {
-        MsgNode(idx.index())
-    }
-}
-
-#[derive(Debug, Clone, Default, Eq, PartialEq)]
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Msg {
     pub data: Option<Vec<u8>>,
     pub sender: Option<Address>,
@@ -62,7 +38,7 @@ impl Msg {
                 if let Some(d) = self.data.clone() {
                     let c = Concrete::from(d);
                     (
-                        analyzer.add_node(Node::Concrete(c)).into(),
+                        analyzer.concrete_or_add(c).into(),
                         "msg.data".to_string(),
                     )
                 } else {
@@ -73,6 +49,7 @@ impl Msg {
                     var.display_name = "msg.data".to_string();
                     var.is_tmp = false;
                     var.is_symbolic = true;
+                    var.is_tainted = true;
                     return var;
                 }
             }
@@ -80,7 +57,7 @@ impl Msg {
                 if let Some(d) = self.sender {
                     let c = Concrete::from(d);
                     (
-                        analyzer.add_node(Node::Concrete(c)).into(),
+                        analyzer.concrete_or_add(c).into(),
                         "msg.sender".to_string(),
                     )
                 } else {
@@ -97,7 +74,7 @@ impl Msg {
                 if let Some(d) = self.sig {
                     let c = Concrete::from(d);
                     (
-                        analyzer.add_node(Node::Concrete(c)).into(),
+                        analyzer.concrete_or_add(c).into(),
                         "msg.sig".to_string(),
                     )
                 } else {
@@ -107,6 +84,7 @@ impl Msg {
                     var.display_name = "msg.sig".to_string();
                     var.is_tmp = false;
                     var.is_symbolic = true;
+                    var.is_tainted = true;
                     return var
                 }
             }
@@ -114,7 +92,7 @@ impl Msg {
                 if let Some(d) = self.value {
                     let c = Concrete::from(d);
                     (
-                        analyzer.add_node(Node::Concrete(c)).into(),
+                        analyzer.concrete_or_add(c).into(),
                         "msg.value".to_string(),
                     )
                 } else {
@@ -131,7 +109,7 @@ impl Msg {
                 if let Some(d) = self.origin {
                     let c = Concrete::from(d);
                     (
-                        analyzer.add_node(Node::Concrete(c)).into(),
+                        analyzer.concrete_or_add(c).into(),
                         "tx.origin".to_string(),
                     )
                 } else {
@@ -148,7 +126,7 @@ impl Msg {
                 if let Some(d) = self.gasprice {
                     let c = Concrete::from(d);
                     (
-                        analyzer.add_node(Node::Concrete(c)).into(),
+                        analyzer.concrete_or_add(c).into(),
                         "tx.gasprice".to_string(),
                     )
                 } else {
@@ -164,7 +142,7 @@ impl Msg {
             "gaslimit" => {
                 if let Some(d) = self.gaslimit {
                     let c = Concrete::from(d);
-                    (analyzer.add_node(Node::Concrete(c)).into(), "".to_string())
+                    (analyzer.concrete_or_add(c).into(), "".to_string())
                 } else {
                     let node = analyzer.builtin_or_add(Builtin::Uint(64));
                     let mut var = ContextVar::new_from_builtin(loc, node.into(), analyzer);
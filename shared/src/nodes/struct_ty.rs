@@ -1,5 +1,6 @@
+use serde::{Deserialize, Serialize};
 use crate::GraphLike;
-use petgraph::{Direction, visit::EdgeRef};
+use crate::analyzer::Search;
 use crate::analyzer::AsDotStr;
 use crate::VarType;
 use crate::analyzer::AnalyzerLike;
@@ -8,20 +9,12 @@ use crate::Edge;
 use crate::NodeIdx;
 use solang_parser::pt::{Identifier, Loc, StructDefinition, VariableDeclaration, Expression};
 
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct StructNode(pub usize);
 
-impl StructNode {
-    pub fn underlying<'a>(&self, analyzer: &'a impl GraphLike) -> &'a Struct {
-        match analyzer.node(*self) {
-            Node::Struct(st) => st,
-            e => panic!(
-                "Node type confusion: expected node to be Struct but it was: {:?}",
-                e
-            ),
-        }
-    }
+node_newtype!(StructNode, Struct, Struct);
 
+impl StructNode {
     pub fn loc(&self, analyzer: &impl GraphLike) -> Loc {
         Struct::maybe_from_node(analyzer.node(*self).clone())
             .expect("Node wasnt struct")
@@ -36,13 +29,15 @@ impl StructNode {
             .to_string()
     }
 
+    /// The struct's fields, in the order they are declared
     pub fn fields(&self, analyzer: &impl GraphLike) -> Vec<FieldNode> {
-        analyzer
-            .graph()
-            .edges_directed(self.0.into(), Direction::Incoming)
-            .filter(|edge| Edge::Field == *edge.weight())
-            .map(|edge| FieldNode::from(edge.source()))
-            .collect()
+        let mut fields: Vec<_> = analyzer
+            .children_of_kind(*self, Edge::Field)
+            .into_iter()
+            .map(FieldNode::from)
+            .collect();
+        fields.sort();
+        fields
     }
 }
 
@@ -63,19 +58,7 @@ impl AsDotStr for StructNode {
     }
 }
 
-impl From<StructNode> for NodeIdx {
-    fn from(val: StructNode) -> Self {
-        val.0.into()
-    }
-}
-
-impl From<NodeIdx> for StructNode {
-    fn from(idx: NodeIdx) -> Self {
-        StructNode(idx.index())
-    }
-}
-
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Struct {
     pub loc: Loc,
     pub name: Option<Identifier>,
@@ -105,20 +88,12 @@ impl From<StructDefinition> for Struct {
     }
 }
 
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct FieldNode(pub usize);
 
-impl FieldNode {
-    pub fn underlying<'a>(&self, analyzer: &'a impl GraphLike) -> &'a Field {
-        match analyzer.node(*self) {
-            Node::Field(field) => field,
-            e => panic!(
-                "Node type confusion: expected node to be Field but it was: {:?}",
-                e
-            ),
-        }
-    }
+node_newtype!(FieldNode, Field, Field);
 
+impl FieldNode {
     pub fn name(&self, analyzer: &impl GraphLike) -> String {
         self.underlying(analyzer)
             .name
@@ -146,19 +121,7 @@ impl AsDotStr for FieldNode {
     }
 }
 
-impl From<NodeIdx> for FieldNode {
-    fn from(idx: NodeIdx) -> Self {
-        FieldNode(idx.index())
-    }
-}
-
-impl From<FieldNode> for NodeIdx {
-    fn from(val: FieldNode) -> Self {
-        val.0.into()
-    }
-}
-
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Field {
     pub loc: Loc,
     pub ty: NodeIdx,
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use crate::analyzer::AsDotStr;
 use crate::VarType;
 use crate::analyzer::{GraphLike, AnalyzerLike};
@@ -5,30 +6,10 @@ use crate::Node;
 use crate::NodeIdx;
 use solang_parser::pt::{Identifier, Loc, TypeDefinition, Expression};
 
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct TyNode(pub usize);
-impl TyNode {
-    pub fn underlying<'a>(&self, analyzer: &'a impl GraphLike) -> &'a Ty {
-        match analyzer.node(*self) {
-            Node::Ty(ty) => ty,
-            e => panic!(
-                "Node type confusion: expected node to be Ty but it was: {:?}",
-                e
-            ),
-        }
-    }
-}
-impl From<TyNode> for NodeIdx {
-    fn from(val: TyNode) -> Self {
-        val.0.into()
-    }
-}
 
-impl From<NodeIdx> for TyNode {
-    fn from(idx: NodeIdx) -> Self {
-        TyNode(idx.index())
-    }
-}
+node_newtype!(TyNode, Ty, Ty);
 
 impl AsDotStr for TyNode {
     fn as_dot_str(&self, analyzer: &impl GraphLike) -> String {
@@ -44,7 +25,7 @@ impl AsDotStr for TyNode {
     }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Ty {
     pub loc: Loc,
     pub ty: NodeIdx,
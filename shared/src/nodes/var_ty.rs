@@ -1,29 +1,49 @@
+use serde::{Deserialize, Serialize};
 use crate::VarType;
 use crate::analyzer::AsDotStr;
 use crate::{analyzer::{GraphLike, AnalyzerLike}, Node, NodeIdx};
+use crate::context::ContextVarNode;
+use crate::nodes::Concrete;
+use crate::range::SolcRange;
 use solang_parser::pt::{Identifier, Loc, VariableAttribute, VariableDefinition, Expression, Visibility};
 
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct VarNode(pub usize);
 
-impl VarNode {
-    pub fn underlying<'a>(&self, analyzer: &'a impl GraphLike) -> &'a Var {
-        match analyzer.node(*self) {
-            Node::Var(func) => func,
-            e => panic!(
-                "Node type confusion: expected node to be Var but it was: {:?}",
-                e
-            ),
-        }
-    }
+node_newtype!(VarNode, Var, Var);
 
+impl VarNode {
     pub fn name(&self, analyzer: &'_ impl GraphLike) -> String {
         self.underlying(analyzer)
             .name
             .clone()
-            .expect("Unnamed function")
+            .expect("Unnamed variable")
             .name
     }
+
+    /// The value of a `constant` or `immutable` variable that is initialized where it is
+    /// declared, which it keeps for the whole life of the contract
+    pub fn fixed_range(&self, analyzer: &impl GraphLike) -> Option<SolcRange> {
+        let var = self.underlying(analyzer);
+        if !var.is_constant() && !var.is_immutable() {
+            return None;
+        }
+        let init = var.initializer?;
+        match analyzer.node(init) {
+            Node::Concrete(Concrete::String(_)) => None,
+            Node::Concrete(c) => SolcRange::from(c.clone()),
+            Node::ContextVar(_) => {
+                let init = ContextVarNode::from(init);
+                Some(SolcRange {
+                    min: init.evaled_range_min(analyzer)?,
+                    max: init.evaled_range_max(analyzer)?,
+                    exclusions: vec![],
+                })
+            }
+            Node::Var(_) => VarNode::from(init).fixed_range(analyzer),
+            _ => None,
+        }
+    }
 }
 
 impl AsDotStr for VarNode {
@@ -52,19 +72,7 @@ impl AsDotStr for VarNode {
     }
 }
 
-impl From<VarNode> for NodeIdx {
-    fn from(val: VarNode) -> Self {
-        val.0.into()
-    }
-}
-
-impl From<NodeIdx> for VarNode {
-    fn from(idx: NodeIdx) -> Self {
-        VarNode(idx.index())
-    }
-}
-
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct Var {
     pub loc: Loc,
     pub ty: NodeIdx,
@@ -101,4 +109,12 @@ impl Var {
             matches!(var_attr, VariableAttribute::Visibility(Visibility::Public(_)))
         })
     }
+
+    pub fn is_constant(&self) -> bool {
+        self.attrs.iter().any(|var_attr| matches!(var_attr, VariableAttribute::Constant(_)))
+    }
+
+    pub fn is_immutable(&self) -> bool {
+        self.attrs.iter().any(|var_attr| matches!(var_attr, VariableAttribute::Immutable(_)))
+    }
 }
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use crate::NodeIdx;
 use crate::GraphLike;
 use std::collections::BTreeMap;
@@ -6,7 +7,7 @@ use crate::range::elem_ty::RangeExpr;
 use crate::range::elem_ty::Elem;
 
 /// An operation to be performed on a range element
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
 pub enum RangeOp {
     /// Addition
     Add,
@@ -54,8 +55,40 @@ pub enum RangeOp {
     BitOr,
     /// Bitwise XOR
     BitXor,
+    /// Bitwise NOT
+    BitNot,
     /// Exponentiation
     Exp,
+    /// Addition that wraps around on overflow, i.e. inside an `unchecked` block
+    WrappingAdd,
+    /// Subtraction that wraps around on underflow, i.e. inside an `unchecked` block
+    WrappingSub,
+    /// Multiplication that wraps around on overflow, i.e. inside an `unchecked` block
+    WrappingMul,
+}
+
+impl RangeOp {
+    /// The wrapping version of the op, if it can overflow
+    pub fn wrapping(&self) -> Option<Self> {
+        use RangeOp::*;
+        match self {
+            Add | WrappingAdd => Some(WrappingAdd),
+            Sub | WrappingSub => Some(WrappingSub),
+            Mul | WrappingMul => Some(WrappingMul),
+            _ => None,
+        }
+    }
+
+    /// The checked version of a wrapping op
+    pub fn checked(&self) -> Self {
+        use RangeOp::*;
+        match self {
+            WrappingAdd => Add,
+            WrappingSub => Sub,
+            WrappingMul => Mul,
+            other => *other,
+        }
+    }
 }
 
 
@@ -76,15 +109,27 @@ impl RangeOp {
             // e => panic!("tried to inverse unreversable op: {:?}", e),
         }
     }
+
+    /// The comparison with its operands swapped (e.g.: `a < b` is `b > a`)
+    pub fn flip(self) -> Self {
+        use RangeOp::*;
+        match self {
+            Lt => Gt,
+            Gt => Lt,
+            Lte => Gte,
+            Gte => Lte,
+            other => other,
+        }
+    }
 }
 
 impl ToString for RangeOp {
     fn to_string(&self) -> String {
         use RangeOp::*;
         match self {
-            Add => "+".to_string(),
-            Mul => "*".to_string(),
-            Sub => "-".to_string(),
+            Add | WrappingAdd => "+".to_string(),
+            Mul | WrappingMul => "*".to_string(),
+            Sub | WrappingSub => "-".to_string(),
             Div => "/".to_string(),
             Shl => "<<".to_string(),
             Shr => ">>".to_string(),
@@ -106,6 +151,7 @@ impl ToString for RangeOp {
             BitAnd => "&".to_string(),
             BitOr => "|".to_string(),
             BitXor => "^".to_string(),
+            BitNot => "~".to_string(),
         }
     }
 }
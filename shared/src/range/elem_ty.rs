@@ -1,15 +1,17 @@
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use crate::range::Range;
 use std::ops::*;
 use crate::range::range_ops::*;
 use crate::context::ContextVarNode;
-use crate::{nodes::VarType};
+use crate::{nodes::{TypeNode, VarType}};
 use crate::{Concrete, NodeIdx};
 use crate::range::{elem::RangeOp, *};
 use solang_parser::pt::Loc;
+use ethers_core::types::{I256, U256};
 
 /// A dynamic range element value
-#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Dynamic {
 	/// Index of the node that is referenced
 	pub idx: NodeIdx,
@@ -62,6 +64,12 @@ impl RangeElem<Concrete> for Dynamic {
             		}
             	)
             },
+            VarType::User(TypeNode::Enum(_), _) => {
+                match cvar.ty.range(analyzer) {
+                    Some(range) => range.evaled_range_max(analyzer),
+                    None => Elem::Dynamic(*self),
+                }
+            }
             e => {println!("dynamic {:?}", e); Elem::Dynamic(*self)},
         }
 	}
@@ -84,6 +92,12 @@ impl RangeElem<Concrete> for Dynamic {
             		}
             	)
             },
+            VarType::User(TypeNode::Enum(_), _) => {
+                match cvar.ty.range(analyzer) {
+                    Some(range) => range.evaled_range_min(analyzer),
+                    None => Elem::Dynamic(*self),
+                }
+            }
             e => {println!("dynamic {:?}", e); Elem::Dynamic(*self)},
         }
 	}
@@ -97,7 +111,8 @@ impl RangeElem<Concrete> for Dynamic {
 }
 
 /// A concrete value for a range element
-#[derive(Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(bound(serialize = "T: Serialize", deserialize = "T: Deserialize<'de> + Ord"))]
 pub struct RangeDyn<T> {
 	pub len: Elem<T>,
 	pub val: BTreeMap<Elem<T>, Elem<T>>,
@@ -159,7 +174,7 @@ impl RangeElem<Concrete> for RangeDyn<Concrete> {
 }
 
 /// A concrete value for a range element
-#[derive(Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct RangeConcrete<T> {
 	pub val: T,
 	pub loc: Loc,
@@ -174,6 +189,17 @@ impl From<Concrete> for RangeConcrete<Concrete> {
 	}
 }
 
+impl RangeConcrete<Concrete> {
+	/// This value promoted to the wider of its own and `other`'s integer type, so arithmetic
+	/// between the two saturates at the bounds of the result type rather than of `self`
+	pub fn widened_to(&self, other: &Self) -> Self {
+		Self {
+			val: self.val.widened_to(&other.val),
+			loc: self.loc,
+		}
+	}
+}
+
 impl RangeElem<Concrete> for RangeConcrete<Concrete> {
 	// fn simplify(&self, _analyzer: &impl GraphLike) -> Elem<Concrete> {
 	// 	Elem::Concrete(self.clone())
@@ -271,7 +297,8 @@ impl RangeElem<Concrete> for RangeConcrete<Concrete> {
 }
 
 /// A range expression composed of other range [`Elem`]
-#[derive(Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(bound(serialize = "T: Serialize", deserialize = "T: Deserialize<'de> + Ord"))]
 pub struct RangeExpr<T> {
 	pub lhs: Box<Elem<T>>,
 	pub op: RangeOp,
@@ -332,7 +359,8 @@ impl RangeElem<Concrete> for RangeExpr<Concrete> {
 }
 
 /// A core range element.
-#[derive(Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(bound(serialize = "T: Serialize", deserialize = "T: Deserialize<'de> + Ord"))]
 pub enum Elem<T> {
 	/// A range element that is a reference to another node
 	Dynamic(Dynamic),
@@ -466,6 +494,47 @@ impl Elem<Concrete> {
     		_ => None
     	}
     }
+
+    /// The nodes this element refers to, including those used as keys of array values
+    pub fn referenced_idxs(&self) -> Vec<NodeIdx> {
+    	match self {
+    		Elem::Dynamic(d) => vec![d.idx],
+    		Elem::ConcreteDyn(d) => {
+    			let mut idxs = d.len.referenced_idxs();
+    			d.val.iter().for_each(|(key, val)| {
+    				idxs.extend(key.referenced_idxs());
+    				idxs.extend(val.referenced_idxs());
+    			});
+    			idxs
+    		}
+    		Elem::Expr(expr) => {
+    			let mut idxs = expr.lhs.referenced_idxs();
+    			idxs.extend(expr.rhs.referenced_idxs());
+    			idxs
+    		}
+    		Elem::Concrete(_) | Elem::Null => vec![],
+    	}
+    }
+
+    /// Updates the nodes this element refers to after the graph has been compacted
+    pub fn update_idxs(&mut self, mapping: &BTreeMap<NodeIdx, NodeIdx>) {
+    	match self {
+    		Elem::Dynamic(d) => d.idx = *mapping.get(&d.idx).unwrap_or(&d.idx),
+    		Elem::ConcreteDyn(d) => {
+    			d.len.update_idxs(mapping);
+    			d.val = std::mem::take(&mut d.val).into_iter().map(|(mut key, mut val)| {
+    				key.update_idxs(mapping);
+    				val.update_idxs(mapping);
+    				(key, val)
+    			}).collect();
+    		}
+    		Elem::Expr(expr) => {
+    			expr.lhs.update_idxs(mapping);
+    			expr.rhs.update_idxs(mapping);
+    		}
+    		Elem::Concrete(_) | Elem::Null => {}
+    	}
+    }
 }
 
 impl RangeElem<Concrete> for Elem<Concrete> {
@@ -747,6 +816,116 @@ pub trait ExecOp<T> {
 	fn simplify_exec_op(&self, maximize: bool, analyzer: &impl GraphLike) -> Elem<T>;
 }
 
+/// The value of a concrete range element that is a non-negative integer
+fn non_negative(elem: &Elem<Concrete>) -> Option<U256> {
+	match elem.maybe_concrete()?.val {
+		Concrete::Uint(_, val) => Some(val),
+		Concrete::Int(_, val) if val >= I256::zero() => Some(val.into_raw()),
+		_ => None,
+	}
+}
+
+/// The minimum or maximum of the type of a concrete range element
+fn type_bound(elem: &Elem<Concrete>, maximize: bool) -> Option<Elem<Concrete>> {
+	let val = elem.maybe_concrete()?.val;
+	let bound = if maximize { Concrete::max(&val)? } else { Concrete::min(&val)? };
+	Some(Elem::from(bound))
+}
+
+/// Bounds `&`, `|`, `^` and `<<` over the whole ranges of their operands. Single values are
+/// computed exactly, otherwise only non-negative integers are bounded: `&` by the smaller maximum,
+/// `|` and `^` by every bit below the highest bit of the larger maximum, and `<<` by shifting the
+/// bounds as long as no bit is shifted out of the type
+fn bitwise_bound(
+	op: RangeOp,
+	lhs_min: &Elem<Concrete>,
+	lhs_max: &Elem<Concrete>,
+	rhs_min: &Elem<Concrete>,
+	rhs_max: &Elem<Concrete>,
+	maximize: bool,
+) -> Option<Elem<Concrete>> {
+	let lhs = lhs_min.maybe_concrete()?.val;
+	let exact = lhs_min.range_eq(lhs_max) && rhs_min.range_eq(rhs_max);
+	let as_lhs = |val: U256| Some(Elem::from(lhs.u256_as_original(val)));
+	match op {
+		RangeOp::BitAnd if exact => lhs_min.range_bit_and(rhs_min),
+		RangeOp::BitOr if exact => lhs_min.range_bit_or(rhs_min),
+		RangeOp::BitXor if exact => lhs_min.range_bit_xor(rhs_min),
+		RangeOp::BitAnd => {
+			// either side being non-negative caps the result, i.e. `x & 0xff` is at most `0xff`
+			let cap = [(lhs_min, lhs_max), (rhs_min, rhs_max)]
+				.into_iter()
+				.filter_map(|(min, max)| non_negative(min).and(non_negative(max)))
+				.min()?;
+			if maximize { as_lhs(cap) } else { as_lhs(U256::zero()) }
+		}
+		RangeOp::BitOr | RangeOp::BitXor => {
+			let (lmin, lmax) = (non_negative(lhs_min)?, non_negative(lhs_max)?);
+			let (rmin, rmax) = (non_negative(rhs_min)?, non_negative(rhs_max)?);
+			if maximize {
+				let max = lmax.max(rmax);
+				as_lhs(if max.is_zero() { max } else { U256::MAX >> max.leading_zeros() })
+			} else if op == RangeOp::BitOr {
+				as_lhs(lmin.max(rmin))
+			} else {
+				as_lhs(U256::zero())
+			}
+		}
+		RangeOp::Shl => {
+			let (lmin, lmax) = (non_negative(lhs_min)?, non_negative(lhs_max)?);
+			let (rmin, rmax) = (non_negative(rhs_min)?, non_negative(rhs_max)?);
+			let ty_max = Concrete::max(&lhs)?.into_u256()?;
+			let in_width = U256::from(256);
+			if exact && matches!(lhs, Concrete::Uint(..)) {
+				// bits shifted past the width of the type are dropped
+				return as_lhs(if rmin >= in_width { U256::zero() } else { (lmin << rmin) & ty_max });
+			}
+			let fits = rmax < in_width
+				&& rmax <= U256::from(lmax.leading_zeros())
+				&& (lmax << rmax) <= ty_max;
+			match (fits, maximize) {
+				(true, true) => as_lhs(lmax << rmax),
+				(true, false) => as_lhs(lmin << rmin),
+				_ => None,
+			}
+		}
+		_ => None,
+	}
+}
+
+/// Bounds a conversion between integer types. Conversions keep the low bits of the two's
+/// complement value, so the result is contiguous unless the source range wraps around the
+/// width of the target, in which case it can be any value of the target type
+fn int_cast_bound(
+	lhs_min: &Elem<Concrete>,
+	lhs_max: &Elem<Concrete>,
+	target: &Elem<Concrete>,
+	maximize: bool,
+) -> Option<Elem<Concrete>> {
+	let raw = |elem: &Elem<Concrete>| match elem.maybe_concrete()?.val {
+		Concrete::Uint(_, val) => Some(val),
+		Concrete::Int(_, val) => Some(val.into_raw()),
+		_ => None,
+	};
+	let (min, max) = (raw(lhs_min)?, raw(lhs_max)?);
+	let target_val = target.maybe_concrete()?.val;
+	let size = match target_val {
+		Concrete::Uint(size, _) | Concrete::Int(size, _) => size,
+		_ => return None,
+	};
+	let builtin = target_val.as_builtin();
+	let cast_min = lhs_min.maybe_concrete()?.val.cast(builtin.clone())?;
+	let cast_max = lhs_max.maybe_concrete()?.val.cast(builtin)?;
+	// the difference of the raw values is the width of the range even across the sign bit
+	let width = max.overflowing_sub(min).0;
+	let fits = size == 256 || width < U256::one() << size;
+	if fits && cast_min <= cast_max {
+		Some(Elem::from(if maximize { cast_max } else { cast_min }))
+	} else {
+		type_bound(target, maximize)
+	}
+}
+
 impl ExecOp<Concrete> for RangeExpr<Concrete> {
 	fn exec_op(&self, maximize: bool, analyzer: &impl GraphLike) -> Elem<Concrete> {
 		let lhs_min = self.lhs.minimize(analyzer);
@@ -1329,29 +1508,10 @@ impl ExecOp<Concrete> for RangeExpr<Concrete> {
 				}
 			}
 			RangeOp::Shl => {
-				let candidates = vec![
-					lhs_min.range_shl(&rhs_min),
-					lhs_min.range_shl(&rhs_max),
-					lhs_max.range_shl(&rhs_min),
-					lhs_max.range_shl(&rhs_max),
-				];
-				let mut candidates = candidates.into_iter().flatten().collect::<Vec<_>>();
-				candidates.sort_by(|a, b| {
-					match a.range_ord(b) {
-						Some(r) => r,
-						_ => std::cmp::Ordering::Less
-					}
-				});
-
-				if candidates.is_empty() {
-					return Elem::Expr(self.clone());
-				}
-
-				if maximize {
-					candidates[candidates.len() - 1].clone()
-				} else {
-					candidates[0].clone()
+				if let Some(elem) = bitwise_bound(self.op, &lhs_min, &lhs_max, &rhs_min, &rhs_max, maximize) {
+					return elem;
 				}
+				type_bound(&lhs_min, maximize).unwrap_or(Elem::Expr(self.clone()))
 			}
 			RangeOp::Shr => {
 				let candidates = vec![
@@ -1454,6 +1614,9 @@ impl ExecOp<Concrete> for RangeExpr<Concrete> {
 
 			}
 			RangeOp::Cast => {
+				if let Some(elem) = int_cast_bound(&lhs_min, &lhs_max, &rhs_min, maximize) {
+					return elem;
+				}
 				// the weird thing about cast is that we really dont know until after the cast due to sizing things
 				// so we should just try them all then compare
 				let candidates = vec![
@@ -1506,26 +1669,45 @@ impl ExecOp<Concrete> for RangeExpr<Concrete> {
 					candidates[0].clone()
 				}
 			}
-			RangeOp::BitAnd => {
-				if maximize {
-					lhs_max.range_bit_and(&rhs_max).unwrap_or(Elem::Expr(self.clone()))
-				} else {
-					lhs_min.range_bit_and(&rhs_min).unwrap_or(Elem::Expr(self.clone()))
+			RangeOp::WrappingAdd | RangeOp::WrappingSub | RangeOp::WrappingMul => {
+				let candidates = [
+					lhs_min.range_wrapping_op(&rhs_min, self.op),
+					lhs_min.range_wrapping_op(&rhs_max, self.op),
+					lhs_max.range_wrapping_op(&rhs_min, self.op),
+					lhs_max.range_wrapping_op(&rhs_max, self.op),
+				].into_iter().collect::<Option<Vec<_>>>();
+
+				// signed or non-concrete operands, fall back to the checked op
+				let Some(mut candidates) = candidates else {
+					return RangeExpr::new(*self.lhs.clone(), self.op.checked(), *self.rhs.clone()).exec_op(maximize, analyzer);
+				};
+
+				// if only some of the extremes wrap (or a multiplication wraps an unknown number of
+				// times), the result can be anywhere in the range of the type
+				let num_wrapped = candidates.iter().filter(|(_, wrapped)| *wrapped).count();
+				let all_same = candidates.iter().all(|(val, _)| *val == candidates[0].0);
+				if num_wrapped > 0 && !all_same && (num_wrapped < candidates.len() || self.op == RangeOp::WrappingMul) {
+					let bound = match (&candidates[0].0, maximize) {
+						(Elem::Concrete(c), true) => Concrete::max(&c.val),
+						(Elem::Concrete(c), false) => Concrete::min(&c.val),
+						_ => None,
+					};
+					return bound.map(Elem::from).unwrap_or(Elem::Expr(self.clone()));
 				}
-			}
-			RangeOp::BitOr => {
+
+				candidates.sort_by(|(a, _), (b, _)| a.range_ord(b).unwrap_or(std::cmp::Ordering::Less));
 				if maximize {
-					lhs_max.range_bit_or(&rhs_max).unwrap_or(Elem::Expr(self.clone()))
+					candidates[candidates.len() - 1].0.clone()
 				} else {
-					lhs_min.range_bit_or(&rhs_min).unwrap_or(Elem::Expr(self.clone()))
+					candidates[0].0.clone()
 				}
 			}
-			RangeOp::BitXor => {
-				if maximize {
-					lhs_max.range_bit_xor(&rhs_max).unwrap_or(Elem::Expr(self.clone()))
-				} else {
-					lhs_min.range_bit_xor(&rhs_min).unwrap_or(Elem::Expr(self.clone()))
+			RangeOp::BitAnd | RangeOp::BitOr | RangeOp::BitXor => {
+				if let Some(elem) = bitwise_bound(self.op, &lhs_min, &lhs_max, &rhs_min, &rhs_max, maximize) {
+					return elem;
 				}
+				// negative operands, bytes and the like can set any bit
+				type_bound(&lhs_min, maximize).unwrap_or(Elem::Expr(self.clone()))
 			}
 			_ => Elem::Expr(self.clone())
 		}
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use crate::analyzer::AnalyzerLike;
 use crate::analyzer::AsDotStr;
 use crate::context::ContextNode;
@@ -8,6 +9,7 @@ use crate::range::elem_ty::Dynamic;
 use crate::range::elem_ty::Elem;
 use crate::range::elem_ty::RangeConcrete;
 use crate::range::elem_ty::RangeDyn;
+use crate::range::elem_ty::RangeExpr;
 use crate::range::range_string::ToRangeString;
 use crate::Builtin;
 use crate::Concrete;
@@ -26,7 +28,7 @@ pub mod elem_ty;
 pub mod range_ops;
 pub mod range_string;
 
-#[derive(Clone, Debug, PartialEq, Eq, Ord, PartialOrd, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
 pub struct SolcRange {
     pub min: Elem<Concrete>,
     pub max: Elem<Concrete>,
@@ -68,6 +70,22 @@ impl From<bool> for SolcRange {
 }
 
 impl SolcRange {
+    /// The nodes the bounds and exclusions of this range refer to
+    pub fn referenced_idxs(&self) -> Vec<NodeIdx> {
+        std::iter::once(&self.min)
+            .chain(std::iter::once(&self.max))
+            .chain(self.exclusions.iter())
+            .flat_map(|elem| elem.referenced_idxs())
+            .collect()
+    }
+
+    /// Updates the nodes this range refers to after the graph has been compacted
+    pub fn update_idxs(&mut self, mapping: &BTreeMap<NodeIdx, NodeIdx>) {
+        self.min.update_idxs(mapping);
+        self.max.update_idxs(mapping);
+        self.exclusions.iter_mut().for_each(|excl| excl.update_idxs(mapping));
+    }
+
     pub fn min_is_negative(&self, analyzer: &impl GraphLike) -> bool {
         self.min.is_negative(false, analyzer)
     }
@@ -135,63 +153,17 @@ impl SolcRange {
     }
     pub fn try_from_builtin(builtin: &Builtin) -> Option<Self> {
         match builtin {
-            Builtin::Uint(size) => {
-                if *size == 256 {
-                    Some(SolcRange {
-                        min: Elem::Concrete(RangeConcrete {
-                            val: Concrete::Uint(*size, 0.into()),
-                            loc: Loc::Implicit,
-                        }),
-                        max: Elem::Concrete(RangeConcrete {
-                            val: Concrete::Uint(*size, U256::MAX),
-                            loc: Loc::Implicit,
-                        }),
-                        exclusions: vec![],
-                    })
-                } else {
-                    Some(SolcRange {
-                        min: Elem::Concrete(RangeConcrete {
-                            val: Concrete::Uint(*size, 0.into()),
-                            loc: Loc::Implicit,
-                        }),
-                        max: Elem::Concrete(RangeConcrete {
-                            val: Concrete::Uint(*size, U256::from(2).pow(U256::from(*size)) - 1),
-                            loc: Loc::Implicit,
-                        }),
-                        exclusions: vec![],
-                    })
-                }
-            }
-            Builtin::Int(size) => {
-                if *size == 256 {
-                    Some(SolcRange {
-                        min: Elem::Concrete(RangeConcrete {
-                            val: Concrete::Int(*size, I256::MIN),
-                            loc: Loc::Implicit,
-                        }),
-                        max: Elem::Concrete(RangeConcrete {
-                            val: Concrete::Int(*size, I256::MAX),
-                            loc: Loc::Implicit,
-                        }),
-                        exclusions: vec![],
-                    })
-                } else {
-                    let max: I256 =
-                        I256::from_raw(U256::from(1u8) << U256::from(size - 1)) - 1.into();
-                    let min = max * I256::from(-1i32);
-                    Some(SolcRange {
-                        min: Elem::Concrete(RangeConcrete {
-                            val: Concrete::Int(*size, min),
-                            loc: Loc::Implicit,
-                        }),
-                        max: Elem::Concrete(RangeConcrete {
-                            val: Concrete::Int(*size, max),
-                            loc: Loc::Implicit,
-                        }),
-                        exclusions: vec![],
-                    })
-                }
-            }
+            Builtin::Uint(_) | Builtin::Int(_) | Builtin::Bytes(_) => Some(SolcRange {
+                min: Elem::Concrete(RangeConcrete {
+                    val: builtin.min_value()?,
+                    loc: Loc::Implicit,
+                }),
+                max: Elem::Concrete(RangeConcrete {
+                    val: builtin.max_value()?,
+                    loc: Loc::Implicit,
+                }),
+                exclusions: vec![],
+            }),
             Builtin::Bool => Some(SolcRange {
                 min: Elem::Concrete(RangeConcrete {
                     val: Concrete::Bool(false),
@@ -203,7 +175,7 @@ impl SolcRange {
                 }),
                 exclusions: vec![],
             }),
-            Builtin::Address => Some(SolcRange {
+            Builtin::Address | Builtin::AddressPayable | Builtin::Payable => Some(SolcRange {
                 min: Elem::Concrete(RangeConcrete {
                     val: Concrete::Address(Address::from_slice(&[0x00; 20])),
                     loc: Loc::Implicit,
@@ -214,22 +186,19 @@ impl SolcRange {
                 }),
                 exclusions: vec![],
             }),
-            Builtin::Bytes(size) => {
-                let v: Vec<_> = (0..32u8)
-                    .map(|i| if i < *size { 0xff } else { 0x00 })
-                    .collect();
-                Some(SolcRange {
-                    min: Elem::Concrete(RangeConcrete {
-                        val: Concrete::Bytes(*size, H256::from_slice(&[0x00; 32])),
-                        loc: Loc::Implicit,
-                    }),
-                    max: Elem::Concrete(RangeConcrete {
-                        val: Concrete::Bytes(*size, H256::from_slice(&v[..])),
-                        loc: Loc::Implicit,
-                    }),
-                    exclusions: vec![],
-                })
-            }
+            Builtin::SizedArray(len, _) => Some(SolcRange {
+                min: Elem::ConcreteDyn(Box::new(RangeDyn {
+                    len: Elem::from(Concrete::from(*len)),
+                    val: Default::default(),
+                    loc: Loc::Implicit,
+                })),
+                max: Elem::ConcreteDyn(Box::new(RangeDyn {
+                    len: Elem::from(Concrete::from(*len)),
+                    val: Default::default(),
+                    loc: Loc::Implicit,
+                })),
+                exclusions: vec![],
+            }),
             Builtin::DynamicBytes | Builtin::String | Builtin::Array(_) => Some(SolcRange {
                 min: Elem::ConcreteDyn(Box::new(RangeDyn {
                     len: Elem::from(Concrete::from(U256::zero())),
@@ -326,6 +295,55 @@ impl SolcRange {
         }
     }
 
+    /// The range of `lhs op rhs` inside an `unchecked` block. Both bounds reference the whole
+    /// range of `lhs`, so that evaluation can tell whether only part of it wraps around
+    pub fn wrapping_dyn(lhs: ContextVarNode, op: RangeOp, rhs: ContextVarNode, loc: Loc) -> Option<Self> {
+        let expr = Elem::Expr(RangeExpr::new(
+            Elem::Dynamic(Dynamic::new(lhs.into(), loc)),
+            op.wrapping()?,
+            Elem::Dynamic(Dynamic::new(rhs.into(), loc)),
+        ));
+        Some(Self {
+            min: expr.clone(),
+            max: expr,
+            exclusions: vec![],
+        })
+    }
+
+    /// The range of a bitwise operation or shift. How far bits spread depends on every value of
+    /// `lhs`, so both bounds reference its whole range rather than just the matching bound
+    pub fn bitwise_dyn(lhs: ContextVarNode, op: RangeOp, rhs: ContextVarNode, loc: Loc) -> Option<Self> {
+        if !matches!(op, RangeOp::BitAnd | RangeOp::BitOr | RangeOp::BitXor | RangeOp::Shl) {
+            return None;
+        }
+        let expr = Elem::Expr(RangeExpr::new(
+            Elem::Dynamic(Dynamic::new(lhs.into(), loc)),
+            op,
+            Elem::Dynamic(Dynamic::new(rhs.into(), loc)),
+        ));
+        Some(Self {
+            min: expr.clone(),
+            max: expr,
+            exclusions: vec![],
+        })
+    }
+
+    /// The range of `~var`. Flipping every bit within the width of the type is `max - var` for
+    /// unsigned integers and `-1 - var` for signed ones, which reverses the range of `var`
+    pub fn bit_not_dyn(builtin: &Builtin, var: ContextVarNode, loc: Loc) -> Option<Self> {
+        let flipped = match builtin {
+            Builtin::Uint(size) => Concrete::max(&Concrete::Uint(*size, 0.into()))?,
+            Builtin::Int(size) => Concrete::Int(*size, I256::from(-1i32)),
+            _ => return None,
+        };
+        let expr = Elem::from(flipped) - Elem::Dynamic(Dynamic::new(var.into(), loc));
+        Some(Self {
+            min: expr.clone(),
+            max: expr,
+            exclusions: vec![],
+        })
+    }
+
     pub fn add_dyn(self, other: ContextVarNode, loc: Loc) -> Self {
         Self {
             min: self.min + Elem::Dynamic(Dynamic::new(other.into(), loc)),
@@ -542,6 +560,14 @@ pub trait RangeEval<E, T: RangeElem<E>>: Range<E, ElemTy = T> {
     fn contains(&self, other: &Self, analyzer: &impl AnalyzerLike) -> bool;
     fn contains_elem(&self, other: &T, analyzer: &impl AnalyzerLike) -> bool;
     fn overlaps(&self, other: &Self, analyzer: &impl AnalyzerLike) -> bool;
+    /// The values in both ranges, or `None` if they share none
+    fn intersect(&self, other: &Self, analyzer: &impl AnalyzerLike) -> Option<Self>
+    where
+        Self: Sized;
+    /// The smallest range containing both ranges
+    fn union(&self, other: &Self, analyzer: &impl AnalyzerLike) -> Self
+    where
+        Self: Sized;
 }
 
 impl RangeEval<Concrete, Elem<Concrete>> for SolcRange {
@@ -613,4 +639,73 @@ impl RangeEval<Concrete, Elem<Concrete>> for SolcRange {
 
         min_contains || max_contains
     }
+
+    fn intersect(&self, other: &Self, analyzer: &impl AnalyzerLike) -> Option<Self> {
+        let (self_min, other_min) = (self.evaled_range_min(analyzer), other.evaled_range_min(analyzer));
+        let (self_max, other_max) = (self.evaled_range_max(analyzer), other.evaled_range_max(analyzer));
+        // bounds that can't be compared, i.e. symbolic ones, are kept as a min/max expression
+        let min = match self_min.range_ord(&other_min) {
+            Some(std::cmp::Ordering::Less) => other_min,
+            Some(_) => self_min,
+            None => self_min.max(other_min),
+        };
+        let max = match self_max.range_ord(&other_max) {
+            Some(std::cmp::Ordering::Greater) => other_max,
+            Some(_) => self_max,
+            None => self_max.min(other_max),
+        };
+        if matches!(min.range_ord(&max), Some(std::cmp::Ordering::Greater)) {
+            return None;
+        }
+
+        let mut exclusions = self.exclusions.clone();
+        other.exclusions.iter().for_each(|excl| {
+            if !exclusions.iter().any(|existing| same_value(existing, excl, analyzer)) {
+                exclusions.push(excl.clone());
+            }
+        });
+        // a single remaining value that is excluded, i.e. `x == 0` after `x != 0`
+        if matches!(min.range_ord(&max), Some(std::cmp::Ordering::Equal))
+            && exclusions.iter().any(|excl| same_value(excl, &min, analyzer))
+        {
+            return None;
+        }
+
+        Some(SolcRange { min, max, exclusions })
+    }
+
+    fn union(&self, other: &Self, analyzer: &impl AnalyzerLike) -> Self {
+        let (self_min, other_min) = (self.evaled_range_min(analyzer), other.evaled_range_min(analyzer));
+        let (self_max, other_max) = (self.evaled_range_max(analyzer), other.evaled_range_max(analyzer));
+        let min = match self_min.range_ord(&other_min) {
+            Some(std::cmp::Ordering::Greater) => other_min,
+            Some(_) => self_min,
+            None => self_min.min(other_min),
+        };
+        let max = match self_max.range_ord(&other_max) {
+            Some(std::cmp::Ordering::Less) => other_max,
+            Some(_) => self_max,
+            None => self_max.max(other_max),
+        };
+
+        // a value stays excluded only if neither range can take it
+        let excluded_from = |range: &SolcRange, excl: &Elem<Concrete>| {
+            !range.contains_elem(excl, analyzer)
+                || range.exclusions.iter().any(|other_excl| same_value(other_excl, excl, analyzer))
+        };
+        let mut exclusions: Vec<Elem<Concrete>> = vec![];
+        self.exclusions.iter().chain(other.exclusions.iter()).for_each(|excl| {
+            let seen = exclusions.iter().any(|existing| same_value(existing, excl, analyzer));
+            if !seen && excluded_from(self, excl) && excluded_from(other, excl) {
+                exclusions.push(excl.clone());
+            }
+        });
+
+        SolcRange { min, max, exclusions }
+    }
+}
+
+/// Whether two elements evaluate to the same value
+fn same_value(a: &Elem<Concrete>, b: &Elem<Concrete>, analyzer: &impl GraphLike) -> bool {
+    a.minimize(analyzer).range_ord(&b.minimize(analyzer)) == Some(std::cmp::Ordering::Equal)
 }
@@ -1,3 +1,4 @@
+use crate::range::elem::RangeOp;
 use crate::range::Elem;
 use crate::range::RangeConcrete;
 use crate::range::RangeDyn;
@@ -68,7 +69,7 @@ impl RangeAdd<Concrete> for RangeConcrete<Concrete> {
 impl RangeAdd<Concrete> for Elem<Concrete> {
     fn range_add(&self, other: &Self) -> Option<Elem<Concrete>> {
         match (self, other) {
-            (Elem::Concrete(a), Elem::Concrete(b)) => a.range_add(b),
+            (Elem::Concrete(a), Elem::Concrete(b)) => a.widened_to(b).range_add(b),
             (Elem::Concrete(a), _) if a.val.into_u256() == Some(U256::zero()) => {
                 Some(other.clone())
             }
@@ -144,8 +145,11 @@ impl RangeSub<Concrete> for RangeConcrete<Concrete> {
                     }))
                 }
                 (Concrete::Int(lhs_size, l), Concrete::Int(_rhs_size, r)) => {
+                    // i.e. `0 - x` for an `int8 x` of -128 does not fit either
+                    let min = Concrete::min(&self.val).unwrap().int_val().unwrap();
+                    let max = Concrete::max(&self.val).unwrap().int_val().unwrap();
                     Some(Elem::Concrete(RangeConcrete {
-                        val: Concrete::Int(*lhs_size, l.saturating_sub(*r)),
+                        val: Concrete::Int(*lhs_size, l.saturating_sub(*r).max(min).min(max)),
                         loc: self.loc,
                     }))
                 }
@@ -158,7 +162,7 @@ impl RangeSub<Concrete> for RangeConcrete<Concrete> {
 impl RangeSub<Concrete> for Elem<Concrete> {
     fn range_sub(&self, other: &Self) -> Option<Elem<Concrete>> {
         match (self, other) {
-            (Elem::Concrete(a), Elem::Concrete(b)) => a.range_sub(b),
+            (Elem::Concrete(a), Elem::Concrete(b)) => a.widened_to(b).range_sub(b),
             (_, Elem::Concrete(b)) if b.val.into_u256() == Some(U256::zero()) => Some(self.clone()),
             _ => None,
         }
@@ -220,7 +224,7 @@ impl RangeMul<Concrete> for RangeConcrete<Concrete> {
 impl RangeMul<Concrete> for Elem<Concrete> {
     fn range_mul(&self, other: &Self) -> Option<Elem<Concrete>> {
         match (self, other) {
-            (Elem::Concrete(a), Elem::Concrete(b)) => a.range_mul(b),
+            (Elem::Concrete(a), Elem::Concrete(b)) => a.widened_to(b).range_mul(b),
             (Elem::Concrete(a), _) if a.val.into_u256() == Some(U256::zero()) => Some(self.clone()),
             (_, Elem::Concrete(b)) if b.val.into_u256() == Some(U256::zero()) => {
                 Some(other.clone())
@@ -252,10 +256,11 @@ impl RangeExp<Concrete> for RangeConcrete<Concrete> {
                     }))
                 }
             }
-            _ => match (&self.val, &other.val) {
-                (Concrete::Int(lhs_size, neg_v), Concrete::Uint(_, val)) => {
+            // a non-negative exponent may be signed too, i.e. `x ** 2` for an `int256 x`
+            _ => match (&self.val, other.val.into_u256()) {
+                (Concrete::Int(lhs_size, neg_v), Some(val)) => {
                     let pow2 = val % U256::from(2) == 0.into();
-                    if val > &U256::from(u32::MAX) {
+                    if val > U256::from(u32::MAX) {
                         if pow2 {
                             Some(Elem::Concrete(RangeConcrete {
                                 val: Concrete::max(&self.val).unwrap(),
@@ -361,7 +366,7 @@ impl RangeDiv<Concrete> for RangeConcrete<Concrete> {
 impl RangeDiv<Concrete> for Elem<Concrete> {
     fn range_div(&self, other: &Self) -> Option<Elem<Concrete>> {
         match (self, other) {
-            (Elem::Concrete(a), Elem::Concrete(b)) => a.range_div(b),
+            (Elem::Concrete(a), Elem::Concrete(b)) => a.widened_to(b).range_div(b),
             _ => None,
         }
     }
@@ -407,7 +412,7 @@ impl RangeMod<Concrete> for RangeConcrete<Concrete> {
 impl RangeMod<Concrete> for Elem<Concrete> {
     fn range_mod(&self, other: &Self) -> Option<Elem<Concrete>> {
         match (self, other) {
-            (Elem::Concrete(a), Elem::Concrete(b)) => a.range_mod(b),
+            (Elem::Concrete(a), Elem::Concrete(b)) => a.widened_to(b).range_mod(b),
             _ => None,
         }
     }
@@ -453,7 +458,7 @@ impl RangeMin<Concrete> for RangeConcrete<Concrete> {
 impl RangeMin<Concrete> for Elem<Concrete> {
     fn range_min(&self, other: &Self) -> Option<Elem<Concrete>> {
         match (self, other) {
-            (Elem::Concrete(a), Elem::Concrete(b)) => a.range_min(b),
+            (Elem::Concrete(a), Elem::Concrete(b)) => a.widened_to(b).range_min(b),
             _ => None,
         }
     }
@@ -499,7 +504,7 @@ impl RangeMax<Concrete> for RangeConcrete<Concrete> {
 impl RangeMax<Concrete> for Elem<Concrete> {
     fn range_max(&self, other: &Self) -> Option<Elem<Concrete>> {
         match (self, other) {
-            (Elem::Concrete(a), Elem::Concrete(b)) => a.range_max(b),
+            (Elem::Concrete(a), Elem::Concrete(b)) => a.widened_to(b).range_max(b),
             _ => None,
         }
     }
@@ -1029,7 +1034,22 @@ impl RangeCast<Concrete, Box<RangeDyn<Concrete>>> for RangeConcrete<Concrete> {
                     .collect::<BTreeMap<_, _>>();
                 existing.extend(new);
                 Some(Elem::ConcreteDyn(Box::new(RangeDyn {
-                    len: other.len.clone(),
+                    // the converted value keeps its own length
+                    len: Elem::from(Concrete::from(U256::from(val.len()))),
+                    val: existing,
+                    loc: other.loc,
+                })))
+            }
+            (Concrete::Array(val), _) => {
+                let mut existing = other.val.clone();
+                let new = val
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, v)| (Elem::from(Concrete::from(U256::from(i))), Elem::from(v)))
+                    .collect::<BTreeMap<_, _>>();
+                existing.extend(new);
+                Some(Elem::ConcreteDyn(Box::new(RangeDyn {
+                    len: Elem::from(Concrete::from(U256::from(existing.len()))),
                     val: existing,
                     loc: other.loc,
                 })))
@@ -1116,7 +1136,8 @@ impl RangeCast<Concrete, RangeDyn<Concrete>> for RangeDyn<Concrete> {
                     loc: other.loc,
                 })))
             }
-            (None, None) => Some(Elem::ConcreteDyn(Box::new(self.clone()))),
+            // the target carries no values of its own, e.g. the default range of `bytes`
+            (_, None) => Some(Elem::ConcreteDyn(Box::new(self.clone()))),
             e => panic!("here0: {e:?}"),
         }
     }
@@ -1224,3 +1245,36 @@ impl RangeBitwise<Concrete> for Elem<Concrete> {
         }
     }
 }
+
+pub trait RangeWrapping<T, Rhs = Self> {
+    /// Perform an addition, subtraction or multiplication that wraps at the bounds of the type,
+    /// returning the result and whether it wrapped
+    fn range_wrapping_op(&self, other: &Rhs, op: RangeOp) -> Option<(Elem<T>, bool)>;
+}
+
+impl RangeWrapping<Concrete> for RangeConcrete<Concrete> {
+    fn range_wrapping_op(&self, other: &Self, op: RangeOp) -> Option<(Elem<Concrete>, bool)> {
+        let (val, checked) = match op.checked() {
+            RangeOp::Add => (self.val.wrapping_add(&other.val)?, self.val.checked_add(&other.val)),
+            RangeOp::Sub => (self.val.wrapping_sub(&other.val)?, self.val.checked_sub(&other.val)),
+            RangeOp::Mul => (self.val.wrapping_mul(&other.val)?, self.val.checked_mul(&other.val)),
+            _ => return None,
+        };
+        Some((
+            Elem::Concrete(RangeConcrete {
+                val,
+                loc: self.loc,
+            }),
+            checked.is_none(),
+        ))
+    }
+}
+
+impl RangeWrapping<Concrete> for Elem<Concrete> {
+    fn range_wrapping_op(&self, other: &Self, op: RangeOp) -> Option<(Elem<Concrete>, bool)> {
+        match (self, other) {
+            (Elem::Concrete(a), Elem::Concrete(b)) => a.range_wrapping_op(b, op),
+            _ => None,
+        }
+    }
+}
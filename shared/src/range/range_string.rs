@@ -5,6 +5,7 @@ use crate::range::elem_ty::Dynamic;
 use crate::range::elem_ty::RangeExpr;
 use crate::range::Elem;
 use crate::range::RangeDyn;
+use crate::range::{Range, SolcRange};
 use crate::Concrete;
 use crate::GraphLike;
 use std::collections::BTreeMap;
@@ -39,6 +40,32 @@ impl RangeString {
     }
 }
 
+/// How [`SolcRange::to_string_with`] writes out a range
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RangeStringConfig {
+    /// Evaluate the bounds to constants where possible instead of showing what they are built from
+    pub eval_bounds: bool,
+    /// Write integer bounds in hexadecimal instead of decimal
+    pub hex: bool,
+}
+
+impl SolcRange {
+    /// Writes out the range as `min..=max`
+    pub fn to_string_with(&self, config: RangeStringConfig, analyzer: &impl GraphLike) -> String {
+        let (min, max) = if config.eval_bounds {
+            (self.evaled_range_min(analyzer), self.evaled_range_max(analyzer))
+        } else {
+            (self.range_min(), self.range_max())
+        };
+        let elem_string = |elem: Elem<Concrete>, maximize: bool| match elem {
+            Elem::Concrete(c) if config.hex => c.val.as_hex_string(),
+            Elem::Concrete(c) => c.val.as_string(),
+            elem => elem.to_range_string(maximize, analyzer).s,
+        };
+        format!("{}..={}", elem_string(min, false), elem_string(max, true))
+    }
+}
+
 /// String related functions for ranges
 pub trait ToRangeString {
     /// Gets the definition string of the range element
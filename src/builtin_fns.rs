@@ -248,6 +248,21 @@ pub fn builtin_fns() -> HashMap<String, Function> {
             params: vec![],
             returns: vec![],
         },
+        Function {
+            body: None,
+            loc: Loc::Builtin,
+            ty: FunctionTy::Function,
+            name: Some(Identifier {
+                loc: Loc::Builtin,
+                name: "bytes.concat".to_string(),
+            }),
+            name_loc: Loc::Builtin,
+            attributes: vec![FunctionAttribute::Visibility(Visibility::Internal(Some(
+                Loc::Builtin,
+            )))],
+            params: vec![],
+            returns: vec![],
+        },
         Function {
             body: None,
             loc: Loc::Builtin,
@@ -293,6 +308,111 @@ pub fn builtin_fns() -> HashMap<String, Function> {
             params: vec![],
             returns: vec![],
         },
+        Function {
+            body: None,
+            loc: Loc::Builtin,
+            ty: FunctionTy::Function,
+            name: Some(Identifier {
+                loc: Loc::Builtin,
+                name: "address.transfer".to_string(),
+            }),
+            name_loc: Loc::Builtin,
+            attributes: vec![FunctionAttribute::Visibility(Visibility::Internal(Some(
+                Loc::Builtin,
+            )))],
+            params: vec![],
+            returns: vec![],
+        },
+        Function {
+            body: None,
+            loc: Loc::Builtin,
+            ty: FunctionTy::Function,
+            name: Some(Identifier {
+                loc: Loc::Builtin,
+                name: "address.send".to_string(),
+            }),
+            name_loc: Loc::Builtin,
+            attributes: vec![FunctionAttribute::Visibility(Visibility::Internal(Some(
+                Loc::Builtin,
+            )))],
+            params: vec![],
+            returns: vec![],
+        },
+        Function {
+            body: None,
+            loc: Loc::Builtin,
+            ty: FunctionTy::Function,
+            name: Some(Identifier {
+                loc: Loc::Builtin,
+                name: "address.call".to_string(),
+            }),
+            name_loc: Loc::Builtin,
+            attributes: vec![FunctionAttribute::Visibility(Visibility::Internal(Some(
+                Loc::Builtin,
+            )))],
+            params: vec![],
+            returns: vec![],
+        },
+        Function {
+            body: None,
+            loc: Loc::Builtin,
+            ty: FunctionTy::Function,
+            name: Some(Identifier {
+                loc: Loc::Builtin,
+                name: "address.delegatecall".to_string(),
+            }),
+            name_loc: Loc::Builtin,
+            attributes: vec![FunctionAttribute::Visibility(Visibility::Internal(Some(
+                Loc::Builtin,
+            )))],
+            params: vec![],
+            returns: vec![],
+        },
+        Function {
+            body: None,
+            loc: Loc::Builtin,
+            ty: FunctionTy::Function,
+            name: Some(Identifier {
+                loc: Loc::Builtin,
+                name: "address.staticcall".to_string(),
+            }),
+            name_loc: Loc::Builtin,
+            attributes: vec![FunctionAttribute::Visibility(Visibility::Internal(Some(
+                Loc::Builtin,
+            )))],
+            params: vec![],
+            returns: vec![],
+        },
+        Function {
+            body: None,
+            loc: Loc::Builtin,
+            ty: FunctionTy::Function,
+            name: Some(Identifier {
+                loc: Loc::Builtin,
+                name: "ty.wrap".to_string(),
+            }),
+            name_loc: Loc::Builtin,
+            attributes: vec![FunctionAttribute::Visibility(Visibility::Internal(Some(
+                Loc::Builtin,
+            )))],
+            params: vec![],
+            returns: vec![],
+        },
+        Function {
+            body: None,
+            loc: Loc::Builtin,
+            ty: FunctionTy::Function,
+            name: Some(Identifier {
+                loc: Loc::Builtin,
+                name: "ty.unwrap".to_string(),
+            }),
+            name_loc: Loc::Builtin,
+            attributes: vec![FunctionAttribute::Visibility(Visibility::Internal(Some(
+                Loc::Builtin,
+            )))],
+            params: vec![],
+            returns: vec![],
+        },
     ];
     funcs
         .into_iter()
@@ -316,7 +436,7 @@ pub fn builtin_fns_inputs(
                 },
                 FunctionParam {
                     loc: Loc::Builtin,
-                    ty: analyzer.builtin_or_add(Builtin::Bytes(32)),
+                    ty: analyzer.builtin_or_add(Builtin::Uint(8)),
                     order: 1,
                     storage: None,
                     name: None,
@@ -330,7 +450,7 @@ pub fn builtin_fns_inputs(
                 },
                 FunctionParam {
                     loc: Loc::Builtin,
-                    ty: analyzer.builtin_or_add(Builtin::Uint(8)),
+                    ty: analyzer.builtin_or_add(Builtin::Bytes(32)),
                     order: 3,
                     storage: None,
                     name: None,
@@ -341,6 +461,7 @@ pub fn builtin_fns_inputs(
                 ty: analyzer.builtin_or_add(Builtin::Address),
                 storage: None,
                 name: None,
+                range: None,
             }],
         ),
         ("type", vec![], vec![]),
@@ -423,6 +544,7 @@ pub fn builtin_fns_inputs(
                 ty: analyzer.builtin_or_add(Builtin::Bytes(32)),
                 storage: None,
                 name: None,
+                range: None,
             }],
         ),
         (
@@ -439,6 +561,7 @@ pub fn builtin_fns_inputs(
                 ty: analyzer.builtin_or_add(Builtin::Bytes(20)),
                 storage: None,
                 name: None,
+                range: None,
             }],
         ),
         (
@@ -455,6 +578,7 @@ pub fn builtin_fns_inputs(
                 ty: analyzer.builtin_or_add(Builtin::Bytes(32)),
                 storage: None,
                 name: None,
+                range: None,
             }],
         ),
         (
@@ -465,6 +589,7 @@ pub fn builtin_fns_inputs(
                 ty: analyzer.builtin_or_add(Builtin::Uint(64)),
                 storage: None,
                 name: None,
+                range: None,
             }],
         ),
         (
@@ -481,6 +606,7 @@ pub fn builtin_fns_inputs(
                 ty: analyzer.builtin_or_add(Builtin::Bytes(32)),
                 storage: None,
                 name: None,
+                range: None,
             }],
         ),
         (
@@ -502,6 +628,7 @@ pub fn builtin_fns_inputs(
                 ty: analyzer.builtin_or_add(Builtin::DynamicBytes),
                 storage: Some(StorageLocation::Memory(Loc::Implicit)),
                 name: None,
+                range: None,
             }],
         ),
         (
@@ -512,6 +639,18 @@ pub fn builtin_fns_inputs(
                 ty: analyzer.builtin_or_add(Builtin::DynamicBytes),
                 storage: Some(StorageLocation::Memory(Loc::Implicit)),
                 name: None,
+                range: None,
+            }],
+        ),
+        (
+            "bytes.concat",
+            vec![],
+            vec![FunctionReturn {
+                loc: Loc::Builtin,
+                ty: analyzer.builtin_or_add(Builtin::DynamicBytes),
+                storage: Some(StorageLocation::Memory(Loc::Implicit)),
+                name: None,
+                range: None,
             }],
         ),
         (
@@ -528,6 +667,7 @@ pub fn builtin_fns_inputs(
                 ty: analyzer.builtin_or_add(Builtin::DynamicBytes),
                 storage: Some(StorageLocation::Memory(Loc::Implicit)),
                 name: None,
+                range: None,
             }],
         ),
         (
@@ -544,9 +684,119 @@ pub fn builtin_fns_inputs(
                 ty: analyzer.builtin_or_add(Builtin::DynamicBytes),
                 storage: Some(StorageLocation::Memory(Loc::Implicit)),
                 name: None,
+                range: None,
             }],
         ),
         ("abi.encodeCall", vec![], vec![]),
+        (
+            "address.transfer",
+            vec![FunctionParam {
+                loc: Loc::Builtin,
+                ty: analyzer.builtin_or_add(Builtin::Uint(256)),
+                order: 0,
+                storage: None,
+                name: None,
+            }],
+            vec![],
+        ),
+        (
+            "address.send",
+            vec![FunctionParam {
+                loc: Loc::Builtin,
+                ty: analyzer.builtin_or_add(Builtin::Uint(256)),
+                order: 0,
+                storage: None,
+                name: None,
+            }],
+            vec![FunctionReturn {
+                loc: Loc::Builtin,
+                ty: analyzer.builtin_or_add(Builtin::Bool),
+                storage: None,
+                name: None,
+                range: None,
+            }],
+        ),
+        (
+            "address.call",
+            vec![FunctionParam {
+                loc: Loc::Builtin,
+                ty: analyzer.builtin_or_add(Builtin::DynamicBytes),
+                order: 0,
+                storage: Some(StorageLocation::Memory(Loc::Implicit)),
+                name: None,
+            }],
+            vec![
+                FunctionReturn {
+                    loc: Loc::Builtin,
+                    ty: analyzer.builtin_or_add(Builtin::Bool),
+                    storage: None,
+                    name: None,
+                    range: None,
+                },
+                FunctionReturn {
+                    loc: Loc::Builtin,
+                    ty: analyzer.builtin_or_add(Builtin::DynamicBytes),
+                    storage: Some(StorageLocation::Memory(Loc::Implicit)),
+                    name: None,
+                    range: None,
+                },
+            ],
+        ),
+        (
+            "address.delegatecall",
+            vec![FunctionParam {
+                loc: Loc::Builtin,
+                ty: analyzer.builtin_or_add(Builtin::DynamicBytes),
+                order: 0,
+                storage: Some(StorageLocation::Memory(Loc::Implicit)),
+                name: None,
+            }],
+            vec![
+                FunctionReturn {
+                    loc: Loc::Builtin,
+                    ty: analyzer.builtin_or_add(Builtin::Bool),
+                    storage: None,
+                    name: None,
+                    range: None,
+                },
+                FunctionReturn {
+                    loc: Loc::Builtin,
+                    ty: analyzer.builtin_or_add(Builtin::DynamicBytes),
+                    storage: Some(StorageLocation::Memory(Loc::Implicit)),
+                    name: None,
+                    range: None,
+                },
+            ],
+        ),
+        (
+            "address.staticcall",
+            vec![FunctionParam {
+                loc: Loc::Builtin,
+                ty: analyzer.builtin_or_add(Builtin::DynamicBytes),
+                order: 0,
+                storage: Some(StorageLocation::Memory(Loc::Implicit)),
+                name: None,
+            }],
+            vec![
+                FunctionReturn {
+                    loc: Loc::Builtin,
+                    ty: analyzer.builtin_or_add(Builtin::Bool),
+                    storage: None,
+                    name: None,
+                    range: None,
+                },
+                FunctionReturn {
+                    loc: Loc::Builtin,
+                    ty: analyzer.builtin_or_add(Builtin::DynamicBytes),
+                    storage: Some(StorageLocation::Memory(Loc::Implicit)),
+                    name: None,
+                    range: None,
+                },
+            ],
+        ),
+        // the value is the same on both sides, only the name of its type changes
+        ("ty.wrap", vec![], vec![]),
+        ("ty.unwrap", vec![], vec![]),
     ];
 
     funcs
@@ -1,12 +1,14 @@
-use crate::analyzers::{LocStrSpan, ReportConfig, ReportDisplay};
+use crate::analyzers::{Finding, LocStrSpan, ReportConfig, ReportDisplay, Severity};
 use shared::{
     analyzer::{AnalyzerLike, Search},
     context::*,
-    range::{range_string::*, Range, RangeEval, SolcRange},
+    nodes::{Concrete, TypeNode, VarType},
+    range::{elem::RangeElem, elem_ty::Elem, range_string::*, Range, RangeEval, SolcRange},
 };
 
-use ariadne::{Cache, Color, Config, Fmt, Label, Report, ReportKind, Span};
+use ariadne::{Color, Config, Fmt, Label, Report, ReportKind, Span};
 use solang_parser::pt::{CodeLocation, StorageLocation};
+use std::cmp::Ordering;
 use std::collections::{BTreeMap, BTreeSet};
 
 #[derive(Debug, Clone)]
@@ -20,7 +22,9 @@ pub struct BoundAnalysis {
     pub bound_changes: Vec<(LocStrSpan, SolcRange)>,
     pub report_config: ReportConfig,
     pub sub_ctxs: Vec<Self>,
-    pub ctx_killed: Option<LocStrSpan>,
+    pub ctx_killed: Option<(LocStrSpan, KilledKind)>,
+    /// Whether the variable depends on adversarial input, only tracked in security mode
+    pub tainted: bool,
 }
 
 impl Default for BoundAnalysis {
@@ -36,6 +40,7 @@ impl Default for BoundAnalysis {
             sub_ctxs: Default::default(),
             storage: None,
             ctx_killed: None,
+            tainted: false,
         }
     }
 }
@@ -44,6 +49,14 @@ static MIN_COLOR: Color = Color::Fixed(111);
 static MAX_COLOR: Color = Color::Fixed(106);
 
 impl BoundAnalysis {
+    /// The range the variable ends up with on this path
+    pub fn final_range(&self) -> Option<&SolcRange> {
+        self.bound_changes
+            .last()
+            .map(|(_, range)| range)
+            .or(self.var_def.1.as_ref())
+    }
+
     pub fn flatten_by_ctx(mut self) -> BTreeMap<ContextNode, BoundAnalysis> {
         let mut map =
             self.sub_ctxs
@@ -97,6 +110,42 @@ impl BoundAnalysis {
         map
     }
 
+    /// The minimum and maximum of `range` as strings, evaluated or simplified per the config
+    pub fn range_strs(
+        &self,
+        range: &SolcRange,
+        analyzer: &(impl AnalyzerLike + Search),
+    ) -> (String, String) {
+        if self.report_config.eval_bounds {
+            (
+                range
+                    .evaled_range_min(analyzer)
+                    .to_range_string(false, analyzer)
+                    .s,
+                range
+                    .evaled_range_max(analyzer)
+                    .to_range_string(true, analyzer)
+                    .s,
+            )
+        } else if self.report_config.simplify_bounds {
+            (
+                range
+                    .simplified_range_min(analyzer)
+                    .to_range_string(false, analyzer)
+                    .s,
+                range
+                    .simplified_range_max(analyzer)
+                    .to_range_string(true, analyzer)
+                    .s,
+            )
+        } else {
+            (
+                range.range_min().to_range_string(false, analyzer).s,
+                range.range_max().to_range_string(true, analyzer).s,
+            )
+        }
+    }
+
     pub fn only_tails(mut self) -> BTreeMap<ContextNode, BoundAnalysis> {
         let children = self.sub_ctxs.len();
         let mut map =
@@ -123,8 +172,12 @@ impl BoundAnalysis {
 }
 
 impl ReportDisplay for BoundAnalysis {
-    fn report_kind(&self) -> ReportKind {
-        ReportKind::Custom("Bounds", Color::Cyan)
+    fn report_kind(&self) -> ReportKind<'_> {
+        if self.tainted {
+            ReportKind::Custom("Tainted Bounds", Color::Red)
+        } else {
+            ReportKind::Custom("Bounds", Color::Cyan)
+        }
     }
     fn msg(&self, analyzer: &(impl AnalyzerLike + Search)) -> String {
         format!(
@@ -136,32 +189,7 @@ impl ReportDisplay for BoundAnalysis {
     fn labels(&self, analyzer: &(impl AnalyzerLike + Search)) -> Vec<Label<LocStrSpan>> {
         let mut labels = if self.report_config.show_initial_bounds {
             if let Some(init_range) = &self.var_def.1 {
-                let min = if self.report_config.eval_bounds {
-                    init_range
-                        .evaled_range_min(analyzer)
-                        .to_range_string(false, analyzer)
-                        .s
-                } else if self.report_config.simplify_bounds {
-                    init_range
-                        .simplified_range_min(analyzer)
-                        .to_range_string(false, analyzer)
-                        .s
-                } else {
-                    init_range.range_min().to_range_string(false, analyzer).s
-                };
-                let max = if self.report_config.eval_bounds {
-                    init_range
-                        .evaled_range_max(analyzer)
-                        .to_range_string(true, analyzer)
-                        .s
-                } else if self.report_config.simplify_bounds {
-                    init_range
-                        .simplified_range_max(analyzer)
-                        .to_range_string(true, analyzer)
-                        .s
-                } else {
-                    init_range.range_max().to_range_string(true, analyzer).s
-                };
+                let (min, max) = self.range_strs(init_range, analyzer);
 
                 let range_excl = init_range.range_exclusions();
                 let mut range_excl_str = range_excl
@@ -224,41 +252,7 @@ impl ReportDisplay for BoundAnalysis {
                 .iter()
                 .enumerate()
                 .map(|(i, bound_change)| {
-                    let min = if self.report_config.eval_bounds {
-                        bound_change
-                            .1
-                            .evaled_range_min(analyzer)
-                            .to_range_string(false, analyzer)
-                            .s
-                    } else if self.report_config.simplify_bounds {
-                        bound_change
-                            .1
-                            .simplified_range_min(analyzer)
-                            .to_range_string(false, analyzer)
-                            .s
-                    } else {
-                        bound_change
-                            .1
-                            .range_min()
-                            .to_range_string(false, analyzer)
-                            .s
-                    };
-
-                    let max = if self.report_config.eval_bounds {
-                        bound_change
-                            .1
-                            .evaled_range_max(analyzer)
-                            .to_range_string(true, analyzer)
-                            .s
-                    } else if self.report_config.simplify_bounds {
-                        bound_change
-                            .1
-                            .simplified_range_max(analyzer)
-                            .to_range_string(true, analyzer)
-                            .s
-                    } else {
-                        bound_change.1.range_max().to_range_string(true, analyzer).s
-                    };
+                    let (min, max) = self.range_strs(&bound_change.1, analyzer);
 
                     let range_excl = bound_change.1.range_exclusions();
                     let mut range_excl_str = range_excl
@@ -319,7 +313,7 @@ impl ReportDisplay for BoundAnalysis {
         labels
     }
 
-    fn reports(&self, analyzer: &(impl AnalyzerLike + Search)) -> Vec<Report<LocStrSpan>> {
+    fn reports(&self, analyzer: &(impl AnalyzerLike + Search)) -> Vec<Report<'_, LocStrSpan>> {
         let mut report = Report::build(
             self.report_kind(),
             self.var_def.0.source(),
@@ -335,10 +329,10 @@ impl ReportDisplay for BoundAnalysis {
 
         report.add_labels(self.labels(analyzer));
 
-        if let Some(killed_span) = &self.ctx_killed {
+        if let Some((killed_span, kind)) = &self.ctx_killed {
             report = report.with_label(
                 Label::new(killed_span.clone())
-                    .with_message("Execution guaranteed to revert here!".fg(Color::Red))
+                    .with_message(kind.analysis_str().fg(Color::Red))
                     .with_color(Color::Red),
             );
         }
@@ -357,31 +351,68 @@ impl ReportDisplay for BoundAnalysis {
         reports
     }
 
-    fn print_reports(
-        &self,
-        mut src: &mut impl Cache<String>,
-        analyzer: &(impl AnalyzerLike + Search),
-    ) {
-        let reports = self.reports(analyzer);
-        reports.into_iter().for_each(|report| {
-            report.print(&mut src).unwrap();
-        });
-    }
-
-    fn eprint_reports(
-        &self,
-        mut src: &mut impl Cache<String>,
-        analyzer: &(impl AnalyzerLike + Search),
-    ) {
-        let reports = self.reports(analyzer);
-        reports.into_iter().for_each(|report| {
-            report.eprint(&mut src).unwrap();
-        });
+    fn findings(&self, analyzer: &(impl AnalyzerLike + Search)) -> Vec<Finding> {
+        let severity = if self.tainted {
+            Severity::Warning
+        } else {
+            Severity::Info
+        };
+        let msg = self.msg(analyzer);
+        let mut findings = vec![];
+        if self.report_config.show_initial_bounds {
+            if let Some(init_range) = &self.var_def.1 {
+                let (min, max) = self.range_strs(init_range, analyzer);
+                findings.push(
+                    Finding::new(severity, &self.var_def.0, msg.clone()).with_range(
+                        &self.var_display_name,
+                        min,
+                        max,
+                    ),
+                );
+            }
+        }
+        findings.extend(self.bound_changes.iter().map(|(span, range)| {
+            let (min, max) = self.range_strs(range, analyzer);
+            Finding::new(severity, span, msg.clone()).with_range(&self.var_display_name, min, max)
+        }));
+        if let Some((killed_span, kind)) = &self.ctx_killed {
+            findings.push(Finding::new(
+                Severity::Error,
+                killed_span,
+                kind.analysis_str(),
+            ));
+        }
+        if self.report_config.show_subctxs {
+            findings.extend(
+                self.sub_ctxs
+                    .iter()
+                    .flat_map(|analysis| analysis.findings(analyzer)),
+            );
+        }
+        findings
     }
 }
 
 impl<T> BoundAnalyzer for T where T: Search + AnalyzerLike + Sized {}
 pub trait BoundAnalyzer: Search + AnalyzerLike + Sized {
+    /// The range of `var_name` once the forks of `ctx` join back together, i.e. the union of its
+    /// range at the end of every path that was not killed
+    fn joined_range_for_var(&self, ctx: ContextNode, var_name: &str) -> Option<SolcRange> {
+        ctx.terminal_child_list(self)
+            .into_iter()
+            .filter(|leaf| !leaf.is_killed(self))
+            .filter_map(|leaf| {
+                let var = leaf.latest_var_by_name(self, var_name)?;
+                Some((var.evaled_range_min(self)?, var.evaled_range_max(self)?))
+            })
+            .reduce(widen_bounds)
+            .map(|(min, max)| SolcRange {
+                min,
+                max,
+                exclusions: vec![],
+            })
+    }
+
     fn bounds_for_var_in_family_tree(
         &self,
         file_mapping: &'_ BTreeMap<usize, String>,
@@ -407,6 +438,47 @@ pub trait BoundAnalyzer: Search + AnalyzerLike + Sized {
         inherited.unwrap_or_default()
     }
 
+    /// Resolves `var_name` to the name of a variable in `ctx` or one of its subcontexts. An
+    /// exact match is preferred, otherwise a variable whose name only differs in case is used if
+    /// there is exactly one.
+    ///
+    /// On a miss, returns the names that contain `var_name` ignoring case, or every name in
+    /// scope if none do, for the caller to suggest instead
+    fn resolve_var_name(&self, ctx: ContextNode, var_name: &str) -> Result<String, Vec<String>> {
+        let mut names = std::iter::once(ctx)
+            .chain(ctx.subcontexts(self))
+            .flat_map(|ctx| ctx.var_names(self))
+            .collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+
+        if names.iter().any(|name| name == var_name) {
+            return Ok(var_name.to_string());
+        }
+        let lower = var_name.to_lowercase();
+        let case_insensitive = names
+            .iter()
+            .filter(|name| name.to_lowercase() == lower)
+            .collect::<Vec<_>>();
+        if let [name] = case_insensitive[..] {
+            return Ok(name.clone());
+        }
+        let similar = names
+            .iter()
+            .filter(|name| name.to_lowercase().contains(&lower))
+            .cloned()
+            .collect::<Vec<_>>();
+        if similar.is_empty() {
+            Err(names)
+        } else {
+            Err(similar)
+        }
+    }
+
+    /// Analyzes the bounds of `var_name` through `ctx` and its forks. If no variable has exactly
+    /// that name, a top level call falls back to the one [`resolve_var_name`] finds, if any.
+    ///
+    /// [`resolve_var_name`]: BoundAnalyzer::resolve_var_name
     fn bounds_for_var(
         &self,
         inherited: Option<BoundAnalysis>,
@@ -430,6 +502,22 @@ pub trait BoundAnalyzer: Search + AnalyzerLike + Sized {
 
         let forks = ctx.forks(self);
 
+        if !is_subctx && inherited.is_none() {
+            match self.resolve_var_name(ctx, &var_name) {
+                Ok(resolved) if resolved != var_name => {
+                    return self.bounds_for_var(
+                        None,
+                        file_mapping,
+                        ctx,
+                        resolved,
+                        report_config,
+                        false,
+                    );
+                }
+                _ => {}
+            }
+        }
+
         let mut sub_analyses = forks
             .iter()
             .flat_map(|fork_ctx| {
@@ -501,8 +589,10 @@ pub trait BoundAnalyzer: Search + AnalyzerLike + Sized {
             new_ba.ctx = ctx;
             new_ba.func_span = func_span;
             new_ba.ctx_killed = ctx
-                .killed_loc(self)
-                .map(|loc| LocStrSpan::new(file_mapping, loc));
+                .underlying(self)
+                .killed
+                .map(|(loc, kind)| (LocStrSpan::new(file_mapping, loc), kind));
+            new_ba.tainted |= self.security_mode() && cvar.is_tainted(self);
             new_ba
         } else {
             BoundAnalysis {
@@ -519,8 +609,10 @@ pub trait BoundAnalyzer: Search + AnalyzerLike + Sized {
                 sub_ctxs: vec![],
                 storage: curr.underlying(self).storage.clone(),
                 ctx_killed: ctx
-                    .killed_loc(self)
-                    .map(|loc| LocStrSpan::new(file_mapping, loc)),
+                    .underlying(self)
+                    .killed
+                    .map(|(loc, kind)| (LocStrSpan::new(file_mapping, loc), kind)),
+                tainted: self.security_mode() && cvar.is_tainted(self),
             }
         };
 
@@ -562,17 +654,17 @@ pub trait BoundAnalyzer: Search + AnalyzerLike + Sized {
 }
 
 #[derive(Debug, Clone)]
-pub struct FunctionVarsBoundAnalysis<'a> {
-    pub file_mapping: &'a BTreeMap<usize, String>,
+pub struct FunctionVarsBoundAnalysis {
+    pub file_mapping: BTreeMap<usize, String>,
     pub ctx_loc: LocStrSpan,
     pub ctx: ContextNode,
-    pub ctx_killed: Option<LocStrSpan>,
+    pub ctx_killed: Option<(LocStrSpan, KilledKind)>,
     pub report_config: ReportConfig,
     pub vars_by_ctx: BTreeMap<ContextNode, Vec<BoundAnalysis>>,
 }
 
-impl<'a> ReportDisplay for FunctionVarsBoundAnalysis<'a> {
-    fn report_kind(&self) -> ReportKind {
+impl ReportDisplay for FunctionVarsBoundAnalysis {
+    fn report_kind(&self) -> ReportKind<'_> {
         ReportKind::Custom("Bounds", Color::Cyan)
     }
     fn msg(&self, analyzer: &(impl AnalyzerLike + Search)) -> String {
@@ -586,7 +678,7 @@ impl<'a> ReportDisplay for FunctionVarsBoundAnalysis<'a> {
         vec![]
     }
 
-    fn reports(&self, analyzer: &(impl AnalyzerLike + Search)) -> Vec<Report<LocStrSpan>> {
+    fn reports(&self, analyzer: &(impl AnalyzerLike + Search)) -> Vec<Report<'_, LocStrSpan>> {
         let mut report = Report::build(
             self.report_kind(),
             self.ctx_loc.source(),
@@ -601,10 +693,10 @@ impl<'a> ReportDisplay for FunctionVarsBoundAnalysis<'a> {
         );
 
         report.add_labels(self.labels(analyzer));
-        if let Some(killed_span) = &self.ctx_killed {
+        if let Some((killed_span, kind)) = &self.ctx_killed {
             report = report.with_label(
                 Label::new(killed_span.clone())
-                    .with_message("Execution guaranteed to revert here!".fg(Color::Red))
+                    .with_message(kind.analysis_str().fg(Color::Red))
                     .with_color(Color::Red),
             );
         }
@@ -711,10 +803,10 @@ impl<'a> ReportDisplay for FunctionVarsBoundAnalysis<'a> {
 
                     report.add_labels(labels);
 
-                    if let Some(killed_span) = &self.ctx_killed {
+                    if let Some((killed_span, kind)) = &self.ctx_killed {
                         report = report.with_label(
                             Label::new(killed_span.clone())
-                                .with_message("Execution guaranteed to revert here!".fg(Color::Red))
+                                .with_message(kind.analysis_str().fg(Color::Red))
                                 .with_color(Color::Red),
                         );
                     }
@@ -727,7 +819,7 @@ impl<'a> ReportDisplay for FunctionVarsBoundAnalysis<'a> {
                             .for_each(|child| {
                                 report.add_label(
                                     Label::new(LocStrSpan::new(
-                                        self.file_mapping,
+                                        &self.file_mapping,
                                         child.underlying(analyzer).loc,
                                     ))
                                     .with_color(Color::Fixed(140))
@@ -756,7 +848,7 @@ impl<'a> ReportDisplay for FunctionVarsBoundAnalysis<'a> {
                                     format!(" ∈ [ {}, {} ]", min.fg(MIN_COLOR), max.fg(MAX_COLOR),)
                                 };
                                 report.add_label(
-                                    Label::new(LocStrSpan::new(self.file_mapping, loc))
+                                    Label::new(LocStrSpan::new(&self.file_mapping, loc))
                                         .with_message(
                                             format!(
                                                 "returns: \"{}\"{}",
@@ -773,7 +865,7 @@ impl<'a> ReportDisplay for FunctionVarsBoundAnalysis<'a> {
 
                     report.add_label(
                         Label::new(LocStrSpan::new(
-                            self.file_mapping,
+                            &self.file_mapping,
                             ctx.underlying(analyzer)
                                 .parent_fn
                                 .underlying(analyzer)
@@ -793,7 +885,7 @@ impl<'a> ReportDisplay for FunctionVarsBoundAnalysis<'a> {
                             if !called_fns.contains(&fn_name) {
                                 report.add_label(
                                     Label::new(LocStrSpan::new(
-                                        self.file_mapping,
+                                        &self.file_mapping,
                                         fn_call
                                             .underlying(analyzer)
                                             .body
@@ -815,7 +907,7 @@ impl<'a> ReportDisplay for FunctionVarsBoundAnalysis<'a> {
                             if !called_external_fns.contains(&fn_name) {
                                 if let Some(body) = &ext_fn_call.underlying(analyzer).body {
                                     report.add_label(
-                                        Label::new(LocStrSpan::new(self.file_mapping, body.loc()))
+                                        Label::new(LocStrSpan::new(&self.file_mapping, body.loc()))
                                             .with_message("External function call")
                                             .with_priority(-2)
                                             .with_order(-2)
@@ -831,7 +923,7 @@ impl<'a> ReportDisplay for FunctionVarsBoundAnalysis<'a> {
                                     if !called_contracts.contains(&cname) {
                                         report.add_label(
                                             Label::new(LocStrSpan::new(
-                                                self.file_mapping,
+                                                &self.file_mapping,
                                                 c.loc(analyzer),
                                             ))
                                             .with_message("External Contract")
@@ -869,7 +961,7 @@ impl<'a> ReportDisplay for FunctionVarsBoundAnalysis<'a> {
                                         )
                                     };
                                     report.add_label(
-                                        Label::new(LocStrSpan::new(self.file_mapping, loc))
+                                        Label::new(LocStrSpan::new(&self.file_mapping, loc))
                                             .with_message(
                                                 format!(
                                                     "returns: \"{}\"{}",
@@ -892,34 +984,155 @@ impl<'a> ReportDisplay for FunctionVarsBoundAnalysis<'a> {
         reports
     }
 
-    fn print_reports(
-        &self,
-        mut src: &mut impl Cache<String>,
-        analyzer: &(impl AnalyzerLike + Search),
-    ) {
-        let reports = &self.reports(analyzer);
-        for report in reports.iter() {
-            report.print(&mut src).unwrap();
+    fn findings(&self, analyzer: &(impl AnalyzerLike + Search)) -> Vec<Finding> {
+        let mut findings: Vec<Finding> = self
+            .vars_by_ctx
+            .iter()
+            .flat_map(|(ctx, analyses)| {
+                let returns = ctx
+                    .return_nodes(analyzer)
+                    .into_iter()
+                    .filter_map(|(loc, var)| {
+                        let range = var.range(analyzer)?;
+                        Some(
+                            Finding::new(
+                                Severity::Info,
+                                &LocStrSpan::new(&self.file_mapping, loc),
+                                format!("Return value in {}", ctx.path(analyzer)),
+                            )
+                            .with_range(
+                                var.display_name(analyzer),
+                                range
+                                    .evaled_range_min(analyzer)
+                                    .to_range_string(false, analyzer)
+                                    .s,
+                                range
+                                    .evaled_range_max(analyzer)
+                                    .to_range_string(true, analyzer)
+                                    .s,
+                            ),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                analyses
+                    .iter()
+                    .flat_map(|analysis| analysis.findings(analyzer))
+                    .chain(returns)
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        if let Some((killed_span, kind)) = &self.ctx_killed {
+            findings.push(Finding::new(
+                Severity::Error,
+                killed_span,
+                kind.analysis_str(),
+            ));
         }
+        findings
     }
+}
 
-    fn eprint_reports(
-        &self,
-        mut src: &mut impl Cache<String>,
-        analyzer: &(impl AnalyzerLike + Search),
-    ) {
-        let reports = &self.reports(analyzer);
-        reports.iter().for_each(|report| {
-            report.eprint(&mut src).unwrap();
-        });
-    }
+/// The smaller of the minimums and the larger of the maximums. Bounds that can not be ordered
+/// keep the first one
+fn widen_bounds(
+    (min, max): (Elem<Concrete>, Elem<Concrete>),
+    (other_min, other_max): (Elem<Concrete>, Elem<Concrete>),
+) -> (Elem<Concrete>, Elem<Concrete>) {
+    let min = match other_min.range_ord(&min) {
+        Some(Ordering::Less) => other_min,
+        _ => min,
+    };
+    let max = match other_max.range_ord(&max) {
+        Some(Ordering::Greater) => other_max,
+        _ => max,
+    };
+    (min, max)
+}
+
+/// Joins the per fork analyses of each variable into one, whose only bound change is the union
+/// of the ranges the variable ends up with. Paths that were killed are left out unless every
+/// path of the variable was
+fn merge_forks(
+    ctx: ContextNode,
+    vars_by_ctx: BTreeMap<ContextNode, Vec<BoundAnalysis>>,
+    analyzer: &(impl AnalyzerLike + Search),
+) -> Vec<BoundAnalysis> {
+    let mut by_name: BTreeMap<String, Vec<BoundAnalysis>> = BTreeMap::default();
+    vars_by_ctx.into_values().flatten().for_each(|analysis| {
+        by_name
+            .entry(analysis.var_name.clone())
+            .or_default()
+            .push(analysis)
+    });
+
+    by_name
+        .into_values()
+        .filter_map(|analyses| {
+            let live = analyses
+                .iter()
+                .filter(|analysis| !analysis.ctx.is_killed(analyzer))
+                .collect::<Vec<_>>();
+            let paths = if live.is_empty() {
+                analyses.iter().collect()
+            } else {
+                live
+            };
+            let (min, max) = paths
+                .iter()
+                .filter_map(|analysis| {
+                    let range = analysis.final_range()?;
+                    Some((
+                        range.evaled_range_min(analyzer),
+                        range.evaled_range_max(analyzer),
+                    ))
+                })
+                .reduce(widen_bounds)?;
+            let tainted = paths.iter().any(|analysis| analysis.tainted);
+            let first = paths[0];
+            Some(BoundAnalysis {
+                ctx,
+                bound_changes: vec![(
+                    first.var_def.0.clone(),
+                    SolcRange {
+                        min,
+                        max,
+                        exclusions: vec![],
+                    },
+                )],
+                sub_ctxs: vec![],
+                ctx_killed: None,
+                tainted,
+                ..first.clone()
+            })
+        })
+        .collect()
+}
+
+/// Whether the variable of `analysis` can still be any value of its type once the path ends
+fn ends_at_type_range(
+    analysis: &BoundAnalysis,
+    var: ContextVarNode,
+    analyzer: &(impl AnalyzerLike + Search),
+) -> bool {
+    let type_range = match var.ty(analyzer) {
+        VarType::BuiltIn(bn, _) => SolcRange::try_from_builtin(bn.underlying(analyzer)),
+        VarType::User(TypeNode::Enum(enum_node), _) => Some(enum_node.range(analyzer)),
+        _ => None,
+    };
+    let (Some(type_range), Some(range)) = (type_range, analysis.final_range()) else {
+        return false;
+    };
+    let val = |elem: Elem<Concrete>| elem.maybe_concrete().map(|c| c.val);
+    range.exclusions.is_empty()
+        && val(range.evaled_range_min(analyzer)) == val(type_range.range_min())
+        && val(range.evaled_range_max(analyzer)) == val(type_range.range_max())
 }
 
 impl<T> FunctionVarsBoundAnalyzer for T where T: BoundAnalyzer + Search + AnalyzerLike + Sized {}
 pub trait FunctionVarsBoundAnalyzer: BoundAnalyzer + Search + AnalyzerLike + Sized {
-    fn bounds_for_all<'a>(
-        &'a self,
-        file_mapping: &'a BTreeMap<usize, String>,
+    fn bounds_for_all(
+        &self,
+        file_mapping: &BTreeMap<usize, String>,
         ctx: ContextNode,
         report_config: ReportConfig,
     ) -> FunctionVarsBoundAnalysis {
@@ -927,7 +1140,7 @@ pub trait FunctionVarsBoundAnalyzer: BoundAnalyzer + Search + AnalyzerLike + Siz
             .terminal_child_list(self)
             .iter()
             .map(|child| {
-                let mut parents = child.parent_list(self);
+                let mut parents = child.parent_chain(self);
                 parents.reverse();
                 parents.push(*child);
                 let _children: Vec<_> = parents
@@ -951,12 +1164,19 @@ pub trait FunctionVarsBoundAnalyzer: BoundAnalyzer + Search + AnalyzerLike + Siz
                                 && !var.is_const(self)
                             {
                                 // println!("var: {}", name);
-                                Some(self.bounds_for_var_in_family_tree(
+                                let analysis = self.bounds_for_var_in_family_tree(
                                     file_mapping,
                                     parents.clone(),
                                     name,
                                     report_config,
-                                ))
+                                );
+                                if report_config.only_changed
+                                    && ends_at_type_range(&analysis, *var, self)
+                                {
+                                    None
+                                } else {
+                                    Some(analysis)
+                                }
                             } else {
                                 None
                             }
@@ -965,17 +1185,42 @@ pub trait FunctionVarsBoundAnalyzer: BoundAnalyzer + Search + AnalyzerLike + Siz
                 )
             })
             .collect::<BTreeMap<ContextNode, Vec<BoundAnalysis>>>();
+        let analyses = if report_config.merge_subctxs {
+            BTreeMap::from([(ctx, merge_forks(ctx, analyses, self))])
+        } else {
+            analyses
+        };
 
         FunctionVarsBoundAnalysis {
-            file_mapping,
+            file_mapping: file_mapping.clone(),
             ctx_loc: LocStrSpan::new(file_mapping, ctx.underlying(self).loc),
             ctx,
             ctx_killed: ctx
-                .killed_loc(self)
-                .map(|loc| LocStrSpan::new(file_mapping, loc)),
+                .underlying(self)
+                .killed
+                .map(|(loc, kind)| (LocStrSpan::new(file_mapping, loc), kind)),
             vars_by_ctx: analyses,
             report_config,
         }
         // todo!()
     }
+
+    /// Runs [`FunctionVarsBoundAnalyzer::bounds_for_all`] for each of `ctxs` across rayon's
+    /// thread pool. The graph isn't modified during analysis, so the contexts are independent
+    /// and the results come back in the order of `ctxs`
+    #[cfg(feature = "rayon")]
+    fn bounds_for_all_parallel(
+        &self,
+        file_mapping: &BTreeMap<usize, String>,
+        ctxs: &[ContextNode],
+        report_config: ReportConfig,
+    ) -> Vec<FunctionVarsBoundAnalysis>
+    where
+        Self: Sync,
+    {
+        use rayon::prelude::*;
+        ctxs.par_iter()
+            .map(|ctx| self.bounds_for_all(file_mapping, *ctx, report_config))
+            .collect()
+    }
 }
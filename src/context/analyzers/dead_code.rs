@@ -0,0 +1,109 @@
+use crate::analyzers::{Finding, LocStrSpan, ReportConfig, ReportDisplay, Severity};
+use shared::{
+    analyzer::{AnalyzerLike, Search},
+    context::*,
+};
+
+use ariadne::{Color, Config, Fmt, Label, Report, ReportKind, Span};
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Debug, Clone)]
+pub struct DeadCodeAnalysis<'a> {
+    pub file_mapping: &'a BTreeMap<usize, String>,
+    pub ctx_loc: LocStrSpan,
+    pub ctx: ContextNode,
+    pub report_config: ReportConfig,
+    /// Branches of `if` statements whose condition never holds when they are reached
+    pub dead_branches: Vec<LocStrSpan>,
+}
+
+impl<'a> ReportDisplay for DeadCodeAnalysis<'a> {
+    fn report_kind(&self) -> ReportKind<'_> {
+        ReportKind::Custom("Dead code", Color::Yellow)
+    }
+    fn msg(&self, analyzer: &(impl AnalyzerLike + Search)) -> String {
+        format!(
+            "Branches never taken in function: {}",
+            format!("function {}", self.ctx.associated_fn_name(analyzer)).fg(Color::Cyan)
+        )
+    }
+
+    fn labels(&self, _analyzer: &(impl AnalyzerLike + Search)) -> Vec<Label<LocStrSpan>> {
+        self.dead_branches
+            .iter()
+            .map(|span| {
+                Label::new(span.clone())
+                    .with_message(DEAD_BRANCH_MSG.fg(Color::Yellow))
+                    .with_color(Color::Yellow)
+            })
+            .collect()
+    }
+
+    fn reports(&self, analyzer: &(impl AnalyzerLike + Search)) -> Vec<Report<'_, LocStrSpan>> {
+        if self.dead_branches.is_empty() {
+            return vec![];
+        }
+        let report = Report::build(
+            self.report_kind(),
+            self.ctx_loc.source(),
+            self.ctx_loc.start(),
+        )
+        .with_message(self.msg(analyzer))
+        .with_config(
+            Config::default()
+                .with_cross_gap(false)
+                .with_underlines(true)
+                .with_tab_width(4),
+        )
+        .with_labels(self.labels(analyzer));
+        vec![report.finish()]
+    }
+
+    fn findings(&self, _analyzer: &(impl AnalyzerLike + Search)) -> Vec<Finding> {
+        self.dead_branches
+            .iter()
+            .map(|span| Finding::new(Severity::Warning, span, DEAD_BRANCH_MSG))
+            .collect()
+    }
+}
+
+const DEAD_BRANCH_MSG: &str = "Branch never taken, its condition can not hold here";
+
+impl<T> DeadCodeAnalyzer for T where T: Search + AnalyzerLike + Sized {}
+pub trait DeadCodeAnalyzer: Search + AnalyzerLike + Sized {
+    /// Collects the branches of `if` statements in `ctx` and its subcontexts that are never
+    /// taken. A branch reached more than once, i.e. in an unrolled loop or by several calls,
+    /// only counts as dead if its condition fails every time
+    fn dead_code_report<'a>(
+        &'a self,
+        file_mapping: &'a BTreeMap<usize, String>,
+        ctx: ContextNode,
+        report_config: ReportConfig,
+    ) -> DeadCodeAnalysis<'a> {
+        let branches = std::iter::once(ctx)
+            .chain(ctx.subcontexts(self))
+            .flat_map(|ctx| ctx.branches(self))
+            .collect::<Vec<_>>();
+        let reachable = branches
+            .iter()
+            .filter(|branch| branch.reachable)
+            .map(|branch| branch.loc)
+            .collect::<BTreeSet<_>>();
+        let dead = branches
+            .iter()
+            .map(|branch| branch.loc)
+            .filter(|loc| !reachable.contains(loc))
+            .collect::<BTreeSet<_>>();
+
+        DeadCodeAnalysis {
+            file_mapping,
+            ctx_loc: LocStrSpan::new(file_mapping, ctx.underlying(self).loc),
+            ctx,
+            report_config,
+            dead_branches: dead
+                .into_iter()
+                .map(|loc| LocStrSpan::new(file_mapping, loc))
+                .collect(),
+        }
+    }
+}
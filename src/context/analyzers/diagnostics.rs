@@ -0,0 +1,105 @@
+use crate::analyzers::{Finding, LocStrSpan, ReportConfig, ReportDisplay, Severity};
+use shared::analyzer::{AnalyzerLike, Diagnostic, Search};
+
+use ariadne::{Color, Config, Fmt, Label, Report, ReportKind, Span};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone)]
+pub struct DiagnosticsAnalysis<'a> {
+    pub file_mapping: &'a BTreeMap<usize, String>,
+    pub report_config: ReportConfig,
+    /// Everything recorded while building the graph, in the order it was encountered
+    pub diagnostics: Vec<(LocStrSpan, Diagnostic)>,
+}
+
+fn severity_color(severity: Severity) -> Color {
+    match severity {
+        Severity::Info => Color::Cyan,
+        Severity::Warning => Color::Yellow,
+        Severity::Error => Color::Red,
+    }
+}
+
+impl<'a> ReportDisplay for DiagnosticsAnalysis<'a> {
+    fn report_kind(&self) -> ReportKind<'_> {
+        ReportKind::Custom("Diagnostic", Color::Cyan)
+    }
+    fn msg(&self, _analyzer: &(impl AnalyzerLike + Search)) -> String {
+        format!(
+            "{} constructs were not fully analyzed or look suspicious",
+            self.diagnostics.len()
+        )
+    }
+
+    fn labels(&self, _analyzer: &(impl AnalyzerLike + Search)) -> Vec<Label<LocStrSpan>> {
+        self.diagnostics
+            .iter()
+            .map(|(span, diagnostic)| {
+                let color = severity_color(diagnostic.severity);
+                Label::new(span.clone())
+                    .with_message(diagnostic.message.clone().fg(color))
+                    .with_color(color)
+            })
+            .collect()
+    }
+
+    fn reports(&self, analyzer: &(impl AnalyzerLike + Search)) -> Vec<Report<'_, LocStrSpan>> {
+        // a report is tied to one source, so each diagnostic gets its own
+        self.diagnostics
+            .iter()
+            .zip(self.labels(analyzer))
+            .map(|((span, diagnostic), label)| {
+                let kind = match diagnostic.severity {
+                    Severity::Info => ReportKind::Advice,
+                    Severity::Warning => ReportKind::Warning,
+                    Severity::Error => ReportKind::Error,
+                };
+                Report::build(kind, span.source(), span.start())
+                    .with_message(diagnostic.message.clone())
+                    .with_config(
+                        Config::default()
+                            .with_cross_gap(false)
+                            .with_underlines(true)
+                            .with_tab_width(4),
+                    )
+                    .with_label(label)
+                    .finish()
+            })
+            .collect()
+    }
+
+    fn findings(&self, _analyzer: &(impl AnalyzerLike + Search)) -> Vec<Finding> {
+        self.diagnostics
+            .iter()
+            .map(|(span, diagnostic)| {
+                Finding::new(diagnostic.severity, span, diagnostic.message.clone())
+            })
+            .collect()
+    }
+}
+
+impl<T> DiagnosticsAnalyzer for T where T: Search + AnalyzerLike + Sized {}
+pub trait DiagnosticsAnalyzer: Search + AnalyzerLike + Sized {
+    /// Collects the diagnostics recorded while parsing and analyzing, i.e. constructs that were
+    /// skipped and where the results may be incomplete
+    fn diagnostics_report<'a>(
+        &'a self,
+        file_mapping: &'a BTreeMap<usize, String>,
+        report_config: ReportConfig,
+    ) -> DiagnosticsAnalysis<'a> {
+        DiagnosticsAnalysis {
+            file_mapping,
+            report_config,
+            diagnostics: self
+                .diagnostics()
+                .iter()
+                .map(|diagnostic| {
+                    (
+                        LocStrSpan::new(file_mapping, diagnostic.loc),
+                        diagnostic.clone(),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use shared::{
+    analyzer::{AnalyzerLike, Search},
+    context::*,
+    nodes::{Concrete, ContractNode, FunctionNode},
+    range::{elem::RangeOp, elem_ty::Elem},
+    Node, NodeIdx,
+};
+
+use ethers_core::types::I256;
+use solang_parser::pt::{Expression, FunctionTy};
+use std::collections::BTreeMap;
+
+impl<T> InvariantAnalyzer for T where T: Search + AnalyzerLike + Sized {}
+pub trait InvariantAnalyzer: Search + AnalyzerLike + Sized {
+    /// Checks a user supplied invariant, a comparison between sums and differences of state
+    /// variables like `total == a + b`, against the end state of every function in `contract`.
+    ///
+    /// Assuming the invariant held on entry, a function is returned if any of its surviving
+    /// paths changes the two sides of the comparison in a way that may break it. Returns `None`
+    /// if the invariant is not of a supported shape.
+    fn invariant_violations(
+        &self,
+        contract: ContractNode,
+        invariant: &Expression,
+    ) -> Option<Vec<FunctionNode>> {
+        use Expression::*;
+        let (lhs, rhs, op) = match invariant {
+            Equal(_, lhs, rhs) => (lhs, rhs, RangeOp::Eq),
+            NotEqual(_, lhs, rhs) => (lhs, rhs, RangeOp::Neq),
+            Less(_, lhs, rhs) => (lhs, rhs, RangeOp::Lt),
+            LessEqual(_, lhs, rhs) => (lhs, rhs, RangeOp::Lte),
+            More(_, lhs, rhs) => (lhs, rhs, RangeOp::Gt),
+            MoreEqual(_, lhs, rhs) => (lhs, rhs, RangeOp::Gte),
+            _ => return None,
+        };
+
+        // the invariant as `lhs - rhs op 0`
+        let mut names = vec![];
+        invariant_terms(lhs, 1, &mut names)?;
+        invariant_terms(rhs, -1, &mut names)?;
+
+        let violations = contract
+            .funcs(self)
+            .into_iter()
+            .filter(|func| func.underlying(self).ty != FunctionTy::Constructor)
+            .filter(|func| {
+                let Some(body_ctx) = func.maybe_body_ctx(self) else {
+                    return false;
+                };
+                body_ctx
+                    .terminal_child_list(self)
+                    .into_iter()
+                    .filter(|leaf| !leaf.is_killed(self))
+                    .any(|leaf| {
+                        let delta = names.iter().fold(Linear::default(), |acc, (name, sign)| {
+                            let Some(var) = leaf.latest_var_by_name(self, name) else {
+                                return acc;
+                            };
+                            let change = self.linear(var) - self.linear(var.first_version(self));
+                            if *sign > 0 {
+                                acc + change
+                            } else {
+                                acc - change
+                            }
+                        });
+                        !delta.preserves(op)
+                    })
+            })
+            .collect();
+        Some(violations)
+    }
+
+    /// Expresses the value of `var` as a sum of opaque values and a constant by following
+    /// assignments, additions and subtractions back through its versions
+    fn linear(&self, var: ContextVarNode) -> Linear {
+        // a sum or difference keeps its terms, even when their current bounds make it constant
+        if let Some(tmp) = var.tmp_of(self) {
+            match (tmp.op, tmp.rhs) {
+                (RangeOp::Add, Some(rhs)) => return self.linear(tmp.lhs) + self.linear(rhs),
+                (RangeOp::Sub, Some(rhs)) => return self.linear(tmp.lhs) - self.linear(rhs),
+                _ => {}
+            }
+        }
+
+        if var.is_const(self) {
+            match var
+                .evaled_range_min(self)
+                .and_then(|min| min.maybe_concrete())
+                .map(|min| min.val)
+            {
+                Some(Concrete::Uint(_, val)) => return Linear::constant(I256::from_raw(val)),
+                Some(Concrete::Int(_, val)) => return Linear::constant(val),
+                _ => {}
+            }
+        }
+
+        if var.tmp_of(self).is_some() {
+            return Linear::atom(var.into());
+        }
+
+        let Some(prev) = var.previous_version(self) else {
+            return Linear::atom(var.into());
+        };
+        match (var.range_min(self), var.range_max(self)) {
+            // an assignment sets both bounds to the assigned value
+            (Some(Elem::Dynamic(min)), Some(Elem::Dynamic(max)))
+                if min.idx == max.idx && self.is_var(min.idx) =>
+            {
+                self.linear(min.idx.into())
+            }
+            (Some(Elem::Expr(expr)), _) => match (expr.op, *expr.rhs) {
+                (RangeOp::Add, Elem::Dynamic(rhs)) if self.is_var(rhs.idx) => {
+                    self.linear(prev) + self.linear(rhs.idx.into())
+                }
+                (RangeOp::Sub, Elem::Dynamic(rhs)) if self.is_var(rhs.idx) => {
+                    self.linear(prev) - self.linear(rhs.idx.into())
+                }
+                // bounds narrowed by overflow checks, the value is unchanged
+                (RangeOp::Max | RangeOp::Min, _) => self.linear(prev),
+                _ => Linear::atom(var.into()),
+            },
+            // anything else only narrows the bounds of the previous version
+            _ => self.linear(prev),
+        }
+    }
+
+    fn is_var(&self, idx: NodeIdx) -> bool {
+        matches!(self.node(idx), Node::ContextVar(..))
+    }
+}
+
+/// Collects the variables of one side of an invariant along with the sign they contribute with
+fn invariant_terms(expr: &Expression, sign: i64, names: &mut Vec<(String, i64)>) -> Option<()> {
+    use Expression::*;
+    match expr {
+        Variable(ident) => names.push((ident.name.clone(), sign)),
+        Add(_, lhs, rhs) => {
+            invariant_terms(lhs, sign, names)?;
+            invariant_terms(rhs, sign, names)?;
+        }
+        Subtract(_, lhs, rhs) => {
+            invariant_terms(lhs, sign, names)?;
+            invariant_terms(rhs, -sign, names)?;
+        }
+        Parenthesis(_, inner) => invariant_terms(inner, sign, names)?,
+        // constants do not change between function entry and exit
+        NumberLiteral(..) | HexNumberLiteral(..) => {}
+        _ => return None,
+    }
+    Some(())
+}
+
+/// A sum of opaque values, each with an integer coefficient, plus a constant
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Linear {
+    pub constant: I256,
+    pub terms: BTreeMap<NodeIdx, i64>,
+}
+
+impl Linear {
+    pub fn constant(constant: I256) -> Self {
+        Self {
+            constant,
+            terms: Default::default(),
+        }
+    }
+
+    pub fn atom(idx: NodeIdx) -> Self {
+        Self {
+            constant: I256::zero(),
+            terms: BTreeMap::from([(idx, 1)]),
+        }
+    }
+
+    fn combine(mut self, other: Self, sign: i64) -> Self {
+        self.constant = if sign > 0 {
+            self.constant.saturating_add(other.constant)
+        } else {
+            self.constant.saturating_sub(other.constant)
+        };
+        other.terms.into_iter().for_each(|(idx, coeff)| {
+            let entry = self.terms.entry(idx).or_default();
+            *entry += sign * coeff;
+            if *entry == 0 {
+                self.terms.remove(&idx);
+            }
+        });
+        self
+    }
+
+    /// Whether `x op 0` still holds after adding `self` to an `x` for which it held
+    fn preserves(&self, op: RangeOp) -> bool {
+        if !self.terms.is_empty() {
+            return false;
+        }
+        match op {
+            RangeOp::Gt | RangeOp::Gte => self.constant >= I256::zero(),
+            RangeOp::Lt | RangeOp::Lte => self.constant <= I256::zero(),
+            _ => self.constant.is_zero(),
+        }
+    }
+}
+
+impl std::ops::Add for Linear {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        self.combine(other, 1)
+    }
+}
+
+impl std::ops::Sub for Linear {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        self.combine(other, -1)
+    }
+}
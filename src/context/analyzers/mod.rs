@@ -1,18 +1,56 @@
 pub mod bounds;
 use bounds::*;
+pub mod dead_code;
+use dead_code::*;
+pub mod diagnostics;
+use diagnostics::*;
+pub mod invariant;
+use invariant::*;
+pub mod overflow;
+use overflow::*;
+pub mod revert;
+use revert::*;
+pub mod shadowing;
+use shadowing::*;
+pub mod value_flow;
+use value_flow::*;
 
-use crate::AnalyzerLike;
+use crate::{AnalyzerLike, Metrics};
 use ariadne::{Cache, Label, Report, ReportKind, Span};
+use serde::Serialize;
 use shared::analyzer::Search;
+pub use shared::analyzer::Severity;
+use shared::nodes::FunctionNode;
 use solang_parser::pt::Loc;
 use std::collections::BTreeMap;
+use std::io::{self, Write};
 
 pub trait ContextAnalyzer:
-    AnalyzerLike + Search + BoundAnalyzer + FunctionVarsBoundAnalyzer
+    AnalyzerLike
+    + Search
+    + BoundAnalyzer
+    + FunctionVarsBoundAnalyzer
+    + RevertAnalyzer
+    + InvariantAnalyzer
+    + OverflowAnalyzer
+    + ShadowingAnalyzer
+    + DeadCodeAnalyzer
+    + DiagnosticsAnalyzer
+    + ValueFlowAnalyzer
 {
 }
 impl<T> ContextAnalyzer for T where
-    T: AnalyzerLike + Search + BoundAnalyzer + FunctionVarsBoundAnalyzer
+    T: AnalyzerLike
+        + Search
+        + BoundAnalyzer
+        + FunctionVarsBoundAnalyzer
+        + RevertAnalyzer
+        + InvariantAnalyzer
+        + OverflowAnalyzer
+        + ShadowingAnalyzer
+        + DeadCodeAnalyzer
+        + DiagnosticsAnalyzer
+        + ValueFlowAnalyzer
 {
 }
 
@@ -101,6 +139,8 @@ impl Span for LocStrSpan {
     }
 }
 
+/// What the reports include. Set the fields that matter and fill in the rest with
+/// `..ReportConfig::default()`
 #[derive(Debug, Clone, Copy)]
 pub struct ReportConfig {
     pub eval_bounds: bool,
@@ -110,28 +150,36 @@ pub struct ReportConfig {
     pub show_subctxs: bool,
     pub show_initial_bounds: bool,
     pub show_all_lines: bool,
+    pub output: OutputFormat,
+    /// Whether internal functions are reported with their parameters limited to the arguments
+    /// they are called with, instead of their full type range
+    pub interprocedural: bool,
+    /// Leave out variables whose range ends up as the full range of their type
+    pub only_changed: bool,
+    /// Report each variable once for the whole function, with the union of its ranges at the
+    /// end of every fork, instead of once per fork
+    pub merge_subctxs: bool,
+    /// Whether [`crate::Analyzer::analyze_all`] also checks arithmetic for overflow and division
+    /// by zero
+    pub overflow: bool,
+    /// Whether [`crate::Analyzer::analyze_all`] also looks for branches that are never taken
+    pub dead_code: bool,
+    /// Whether [`crate::Analyzer::analyze_all`] also looks for locals and parameters that shadow
+    /// a state variable
+    pub shadowing: bool,
+    /// Whether [`crate::Analyzer::analyze_all`] also lists the ether sent out of the contract,
+    /// with the range of each value sent
+    pub value_flow: bool,
 }
 
-impl ReportConfig {
-    pub fn new(
-        eval_bounds: bool,
-        simplify_bounds: bool,
-        show_tmps: bool,
-        show_consts: bool,
-        show_subctxs: bool,
-        show_initial_bounds: bool,
-        show_all_lines: bool,
-    ) -> Self {
-        Self {
-            eval_bounds,
-            simplify_bounds,
-            show_tmps,
-            show_consts,
-            show_subctxs,
-            show_initial_bounds,
-            show_all_lines,
-        }
-    }
+/// How reports are written out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Annotated source code, for reading in a terminal
+    #[default]
+    Text,
+    /// A JSON array of [`Finding`]s, for other tools to consume
+    Json,
 }
 
 impl Default for ReportConfig {
@@ -144,15 +192,188 @@ impl Default for ReportConfig {
             show_subctxs: true,
             show_initial_bounds: true,
             show_all_lines: false,
+            output: OutputFormat::Text,
+            interprocedural: false,
+            only_changed: false,
+            merge_subctxs: false,
+            overflow: false,
+            dead_code: false,
+            shadowing: false,
+            value_flow: false,
+        }
+    }
+}
+
+/// A single entry of a report in a machine readable form
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    pub severity: Severity,
+    pub message: String,
+    /// The variable the finding is about, if any
+    pub var: Option<String>,
+    pub min: Option<String>,
+    pub max: Option<String>,
+    /// Values that trigger the finding, if it is about values that may be out of range
+    pub witness: Option<String>,
+    pub file: String,
+    /// Byte offset of the start of the source span
+    pub start: usize,
+    /// Byte offset of the end of the source span
+    pub end: usize,
+}
+
+impl Finding {
+    pub fn new(severity: Severity, span: &LocStrSpan, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+            var: None,
+            min: None,
+            max: None,
+            witness: None,
+            file: span.0.clone(),
+            start: span.start(),
+            end: span.end(),
+        }
+    }
+
+    /// Sets the variable the finding is about along with the bounds of its range
+    pub fn with_range(mut self, var: impl Into<String>, min: String, max: String) -> Self {
+        self.var = Some(var.into());
+        self.min = Some(min);
+        self.max = Some(max);
+        self
+    }
+
+    /// Sets the values that trigger the finding
+    pub fn with_witness(mut self, witness: String) -> Self {
+        self.witness = Some(witness);
+        self
+    }
+}
+
+/// The reports for one function, see [`crate::Analyzer::analyze_all`]. The checks that weren't
+/// enabled in the [`ReportConfig`] are `None`
+#[derive(Debug, Clone)]
+pub struct FunctionReport<'a> {
+    pub func: FunctionNode,
+    pub bounds: FunctionVarsBoundAnalysis,
+    pub overflow: Option<OverflowAnalysis<'a>>,
+    pub dead_code: Option<DeadCodeAnalysis<'a>>,
+    pub shadowing: Option<ShadowingAnalysis<'a>>,
+    pub value_flow: Option<ValueFlowAnalysis<'a>>,
+}
+
+impl<'a> FunctionReport<'a> {
+    pub fn findings(&self, analyzer: &(impl AnalyzerLike + Search)) -> Vec<Finding> {
+        let mut findings = self.bounds.findings(analyzer);
+        if let Some(overflow) = &self.overflow {
+            findings.extend(overflow.findings(analyzer));
+        }
+        if let Some(dead_code) = &self.dead_code {
+            findings.extend(dead_code.findings(analyzer));
         }
+        if let Some(shadowing) = &self.shadowing {
+            findings.extend(shadowing.findings(analyzer));
+        }
+        if let Some(value_flow) = &self.value_flow {
+            findings.extend(value_flow.findings(analyzer));
+        }
+        findings
+    }
+
+    pub fn write_reports(
+        &self,
+        src: &mut impl Cache<String>,
+        w: &mut impl Write,
+        analyzer: &(impl AnalyzerLike + Search),
+    ) -> io::Result<()> {
+        self.bounds.write_reports(src, w, analyzer)?;
+        if let Some(overflow) = &self.overflow {
+            overflow.write_reports(src, w, analyzer)?;
+        }
+        if let Some(dead_code) = &self.dead_code {
+            dead_code.write_reports(src, w, analyzer)?;
+        }
+        if let Some(shadowing) = &self.shadowing {
+            shadowing.write_reports(src, w, analyzer)?;
+        }
+        if let Some(value_flow) = &self.value_flow {
+            value_flow.write_reports(src, w, analyzer)?;
+        }
+        Ok(())
     }
 }
 
+/// The reports for every function of the analyzer, in the order the functions were parsed
+#[derive(Debug, Clone, Default)]
+pub struct FullReport<'a> {
+    pub functions: Vec<FunctionReport<'a>>,
+    /// The analyzer's metrics, if it was collecting them
+    pub metrics: Option<Metrics>,
+}
+
+impl<'a> FullReport<'a> {
+    /// The findings of every report, i.e. to print them all with [`findings_json`]
+    pub fn findings(&self, analyzer: &(impl AnalyzerLike + Search)) -> Vec<Finding> {
+        self.functions
+            .iter()
+            .flat_map(|report| report.findings(analyzer))
+            .collect()
+    }
+
+    /// Writes out every report, see [`ReportDisplay::write_reports`]
+    pub fn write_reports(
+        &self,
+        src: &mut impl Cache<String>,
+        w: &mut impl Write,
+        analyzer: &(impl AnalyzerLike + Search),
+    ) -> io::Result<()> {
+        self.functions
+            .iter()
+            .try_for_each(|report| report.write_reports(src, w, analyzer))
+    }
+}
+
+/// Serializes findings, possibly from several reports, to a single JSON array
+pub fn findings_json(findings: &[Finding]) -> String {
+    serde_json::to_string(findings).expect("Findings are always serializable")
+}
+
 pub trait ReportDisplay {
-    fn report_kind(&self) -> ReportKind;
+    fn report_kind(&self) -> ReportKind<'_>;
     fn msg(&self, analyzer: &(impl AnalyzerLike + Search)) -> String;
     fn labels(&self, analyzer: &(impl AnalyzerLike + Search)) -> Vec<Label<LocStrSpan>>;
-    fn reports(&self, analyzer: &(impl AnalyzerLike + Search)) -> Vec<Report<LocStrSpan>>;
-    fn print_reports(&self, src: &mut impl Cache<String>, analyzer: &(impl AnalyzerLike + Search));
-    fn eprint_reports(&self, src: &mut impl Cache<String>, analyzer: &(impl AnalyzerLike + Search));
+    fn reports(&self, analyzer: &(impl AnalyzerLike + Search)) -> Vec<Report<'_, LocStrSpan>>;
+    /// Writes the rendered reports to `w`, so callers that embed the analyzer decide where
+    /// the output goes
+    fn write_reports(
+        &self,
+        src: &mut impl Cache<String>,
+        w: &mut impl Write,
+        analyzer: &(impl AnalyzerLike + Search),
+    ) -> io::Result<()> {
+        self.reports(analyzer)
+            .into_iter()
+            .try_for_each(|report| report.write_for_stdout(&mut *src, &mut *w))
+    }
+    fn print_reports(&self, src: &mut impl Cache<String>, analyzer: &(impl AnalyzerLike + Search)) {
+        self.write_reports(src, &mut io::stdout(), analyzer)
+            .unwrap();
+    }
+    fn eprint_reports(
+        &self,
+        src: &mut impl Cache<String>,
+        analyzer: &(impl AnalyzerLike + Search),
+    ) {
+        self.reports(analyzer).into_iter().for_each(|report| {
+            report.eprint(&mut *src).unwrap();
+        });
+    }
+    /// The entries of the report, for [`OutputFormat::Json`]
+    fn findings(&self, analyzer: &(impl AnalyzerLike + Search)) -> Vec<Finding>;
+    /// The findings of the report as a JSON array
+    fn reports_json(&self, analyzer: &(impl AnalyzerLike + Search)) -> String {
+        findings_json(&self.findings(analyzer))
+    }
 }
@@ -0,0 +1,245 @@
+use crate::analyzers::{Finding, LocStrSpan, ReportConfig, ReportDisplay, Severity};
+use shared::{
+    analyzer::{AnalyzerLike, Search},
+    context::*,
+};
+
+use ariadne::{Color, Config, Fmt, Label, Report, ReportKind, Span};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone)]
+pub struct OverflowAnalysis<'a> {
+    pub file_mapping: &'a BTreeMap<usize, String>,
+    pub ctx_loc: LocStrSpan,
+    pub ctx: ContextNode,
+    pub report_config: ReportConfig,
+    /// Arithmetic anywhere in the function, including calls it makes, that may not fit its type
+    pub overflows: Vec<(LocStrSpan, Overflow)>,
+    /// Divisions and modulos whose divisor may be zero, and whether the divisor is tainted
+    pub zero_divisors: Vec<(LocStrSpan, bool)>,
+    /// Indexes that may be past the end of a fixed length array
+    pub out_of_bounds: Vec<(LocStrSpan, OutOfBounds)>,
+    /// Conversions to an enum of values that may not be one of its variants, and whether the
+    /// value is tainted
+    pub invalid_enum_casts: Vec<(LocStrSpan, bool)>,
+}
+
+impl<'a> ReportDisplay for OverflowAnalysis<'a> {
+    fn report_kind(&self) -> ReportKind<'_> {
+        ReportKind::Custom("Overflow", Color::Red)
+    }
+    fn msg(&self, analyzer: &(impl AnalyzerLike + Search)) -> String {
+        format!(
+            "Possible arithmetic reverts in function: {}",
+            format!("function {}", self.ctx.associated_fn_name(analyzer)).fg(Color::Cyan)
+        )
+    }
+
+    fn labels(&self, _analyzer: &(impl AnalyzerLike + Search)) -> Vec<Label<LocStrSpan>> {
+        self.overflows
+            .iter()
+            .map(|(span, overflow)| {
+                Label::new(span.clone())
+                    .with_message(overflow_msg(overflow).fg(Color::Red))
+                    .with_color(Color::Red)
+            })
+            .chain(self.zero_divisors.iter().map(|(span, _)| {
+                Label::new(span.clone())
+                    .with_message(ZERO_DIVISOR_MSG.fg(Color::Red))
+                    .with_color(Color::Red)
+            }))
+            .chain(self.out_of_bounds.iter().map(|(span, out_of_bounds)| {
+                Label::new(span.clone())
+                    .with_message(out_of_bounds_msg(out_of_bounds).fg(Color::Red))
+                    .with_color(Color::Red)
+            }))
+            .chain(self.invalid_enum_casts.iter().map(|(span, _)| {
+                Label::new(span.clone())
+                    .with_message(INVALID_ENUM_CAST_MSG.fg(Color::Red))
+                    .with_color(Color::Red)
+            }))
+            .collect()
+    }
+
+    fn reports(&self, analyzer: &(impl AnalyzerLike + Search)) -> Vec<Report<'_, LocStrSpan>> {
+        if self.overflows.is_empty()
+            && self.zero_divisors.is_empty()
+            && self.out_of_bounds.is_empty()
+            && self.invalid_enum_casts.is_empty()
+        {
+            return vec![];
+        }
+        let report = Report::build(
+            self.report_kind(),
+            self.ctx_loc.source(),
+            self.ctx_loc.start(),
+        )
+        .with_message(self.msg(analyzer))
+        .with_config(
+            Config::default()
+                .with_cross_gap(false)
+                .with_underlines(true)
+                .with_tab_width(4),
+        )
+        .with_labels(self.labels(analyzer));
+        vec![report.finish()]
+    }
+
+    fn findings(&self, _analyzer: &(impl AnalyzerLike + Search)) -> Vec<Finding> {
+        self.overflows
+            .iter()
+            .map(|(span, overflow)| {
+                // wrapping silently produces a wrong value, a revert at least stops execution
+                let severity = if overflow.unchecked {
+                    Severity::Error
+                } else {
+                    revert_severity(overflow.tainted)
+                };
+                Finding::new(severity, span, overflow_msg(overflow))
+                    .with_witness(overflow_witness(overflow))
+            })
+            .chain(self.zero_divisors.iter().map(|(span, tainted)| {
+                Finding::new(revert_severity(*tainted), span, ZERO_DIVISOR_MSG)
+            }))
+            .chain(self.out_of_bounds.iter().map(|(span, out_of_bounds)| {
+                let severity = revert_severity(out_of_bounds.tainted);
+                Finding::new(severity, span, out_of_bounds_msg(out_of_bounds))
+                    .with_witness(out_of_bounds.witness.as_human_string())
+            }))
+            .chain(self.invalid_enum_casts.iter().map(|(span, tainted)| {
+                Finding::new(revert_severity(*tainted), span, INVALID_ENUM_CAST_MSG)
+            }))
+            .collect()
+    }
+}
+
+/// Statements that may revert are warnings, or errors if a tainted input can make them revert
+fn revert_severity(tainted: bool) -> Severity {
+    if tainted {
+        Severity::Error
+    } else {
+        Severity::Warning
+    }
+}
+
+const ZERO_DIVISOR_MSG: &str = "Divisor may be zero, the statement reverts";
+const INVALID_ENUM_CAST_MSG: &str =
+    "Value may not be a variant of the enum, the conversion reverts";
+
+fn overflow_msg(overflow: &Overflow) -> String {
+    let kind = if overflow.underflow {
+        "underflow"
+    } else {
+        "overflow"
+    };
+    let effect = if overflow.unchecked {
+        "the result wraps around"
+    } else {
+        "the statement reverts"
+    };
+    format!(
+        "\"{}\" may {kind}, {effect}, e.g. for {}",
+        overflow.op.to_string(),
+        overflow_witness(overflow)
+    )
+}
+
+/// The operation applied to the operands that don't fit, i.e. `255 + 1`
+fn overflow_witness(overflow: &Overflow) -> String {
+    let (lhs, rhs) = &overflow.witness;
+    format!(
+        "{} {} {}",
+        lhs.as_human_string(),
+        overflow.op.to_string(),
+        rhs.as_human_string()
+    )
+}
+
+fn out_of_bounds_msg(out_of_bounds: &OutOfBounds) -> String {
+    format!(
+        "Index may be out of bounds, the statement reverts, e.g. for index {}",
+        out_of_bounds.witness.as_human_string()
+    )
+}
+
+impl<T> OverflowAnalyzer for T where T: Search + AnalyzerLike + Sized {}
+pub trait OverflowAnalyzer: Search + AnalyzerLike + Sized {
+    /// Collects the additions, subtractions and multiplications in `ctx` and its subcontexts
+    /// whose result may exceed the bounds of its type, the divisions and modulos whose
+    /// divisor may be zero, the indexes that may be past the end of their array and the enum
+    /// conversions that may be out of range, given the ranges known at that point
+    fn overflow_report<'a>(
+        &'a self,
+        file_mapping: &'a BTreeMap<usize, String>,
+        ctx: ContextNode,
+        report_config: ReportConfig,
+    ) -> OverflowAnalysis<'a> {
+        let mut overflows = std::iter::once(ctx)
+            .chain(ctx.subcontexts(self))
+            .flat_map(|ctx| ctx.overflows(self))
+            .collect::<Vec<_>>();
+        // the same statement is evaluated once per fork it is reached in, keep the first witness.
+        // It is tainted if it is in any of the forks
+        overflows.sort_by_key(|overflow| (overflow.loc, overflow.underflow));
+        overflows.dedup_by(|later, first| {
+            let same = (later.loc, later.underflow) == (first.loc, first.underflow);
+            first.tainted |= same && later.tainted;
+            same
+        });
+        let mut zero_divisors = std::iter::once(ctx)
+            .chain(ctx.subcontexts(self))
+            .flat_map(|ctx| ctx.zero_divisors(self))
+            .collect::<Vec<_>>();
+        zero_divisors.sort();
+        zero_divisors.dedup_by(|later, first| {
+            first.1 |= later.0 == first.0 && later.1;
+            later.0 == first.0
+        });
+        let mut out_of_bounds = std::iter::once(ctx)
+            .chain(ctx.subcontexts(self))
+            .flat_map(|ctx| ctx.out_of_bounds(self))
+            .collect::<Vec<_>>();
+        out_of_bounds.sort_by_key(|out_of_bounds| out_of_bounds.loc);
+        out_of_bounds.dedup_by(|later, first| {
+            first.tainted |= later.loc == first.loc && later.tainted;
+            later.loc == first.loc
+        });
+        let mut invalid_enum_casts = std::iter::once(ctx)
+            .chain(ctx.subcontexts(self))
+            .flat_map(|ctx| ctx.invalid_enum_casts(self))
+            .collect::<Vec<_>>();
+        invalid_enum_casts.sort();
+        invalid_enum_casts.dedup_by(|later, first| {
+            first.1 |= later.0 == first.0 && later.1;
+            later.0 == first.0
+        });
+
+        OverflowAnalysis {
+            file_mapping,
+            ctx_loc: LocStrSpan::new(file_mapping, ctx.underlying(self).loc),
+            ctx,
+            report_config,
+            overflows: overflows
+                .into_iter()
+                .map(|overflow| (LocStrSpan::new(file_mapping, overflow.loc), overflow))
+                .collect(),
+            zero_divisors: zero_divisors
+                .into_iter()
+                .map(|(loc, tainted)| (LocStrSpan::new(file_mapping, loc), tainted))
+                .collect(),
+            out_of_bounds: out_of_bounds
+                .into_iter()
+                .map(|out_of_bounds| {
+                    (
+                        LocStrSpan::new(file_mapping, out_of_bounds.loc),
+                        out_of_bounds,
+                    )
+                })
+                .collect(),
+            invalid_enum_casts: invalid_enum_casts
+                .into_iter()
+                .map(|(loc, tainted)| (LocStrSpan::new(file_mapping, loc), tainted))
+                .collect(),
+        }
+    }
+}
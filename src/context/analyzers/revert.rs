@@ -0,0 +1,117 @@
+use shared::{
+    analyzer::{AnalyzerLike, Search},
+    context::*,
+    nodes::{Concrete, FunctionNode, FunctionParamNode},
+    range::{
+        elem::RangeElem,
+        elem_ty::{Elem, RangeConcrete},
+        Range,
+    },
+    Edge,
+};
+
+use petgraph::{visit::EdgeRef, Direction};
+use std::cmp::Ordering;
+
+impl<T> RevertAnalyzer for T where T: Search + AnalyzerLike + Sized {}
+pub trait RevertAnalyzer: Search + AnalyzerLike + Sized {
+    /// Finds the smallest value of `param` that causes every path through `func` to revert,
+    /// based on the bounds each context that did not get killed puts on the input
+    fn min_reverting_input(
+        &self,
+        func: FunctionNode,
+        param: FunctionParamNode,
+    ) -> Option<Concrete> {
+        let body_ctx = self
+            .graph()
+            .edges_directed(func.0.into(), Direction::Incoming)
+            .filter(|edge| *edge.weight() == Edge::Context(ContextEdge::Context))
+            .map(|edge| ContextNode::from(edge.source()))
+            .next()?;
+
+        let name = param.name(self);
+        let full_range = param.range(self)?;
+        let ty_min = full_range.evaled_range_min(self).maybe_concrete()?.val;
+        let ty_max = full_range.evaled_range_max(self).maybe_concrete()?.val;
+
+        // the values each surviving path accepts
+        let mut accepted = body_ctx
+            .terminal_child_list(self)
+            .into_iter()
+            .filter(|leaf| !leaf.is_killed(self))
+            .map(|leaf| {
+                let range = input_version(self, body_ctx, leaf, &name)?.range(self)?;
+                Some((
+                    range.evaled_range_min(self).maybe_concrete()?.val,
+                    range.evaled_range_max(self).maybe_concrete()?.val,
+                ))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        accepted.sort_by(|(a, _), (b, _)| concrete_ord(a, b));
+
+        // walk the accepted intervals in order, the first gap is the smallest reverting value
+        let mut candidate = ty_min;
+        for (min, max) in accepted {
+            if concrete_ord(&min, &candidate) == Ordering::Greater {
+                return Some(candidate);
+            }
+            if concrete_ord(&max, &candidate) != Ordering::Less {
+                if concrete_ord(&max, &ty_max) != Ordering::Less {
+                    return None;
+                }
+                candidate = increment(max)?;
+            }
+        }
+        Some(candidate)
+    }
+}
+
+/// The latest version of the variable `name` on the path from `body_ctx` to `leaf` that still
+/// holds the value the function was called with, i.e. the last one before it was assigned to
+fn input_version(
+    analyzer: &(impl AnalyzerLike + Search),
+    body_ctx: ContextNode,
+    leaf: ContextNode,
+    name: &str,
+) -> Option<ContextVarNode> {
+    // versions from the leaf back to the parameter. A fork starts its own chain of versions, which
+    // continues the one of the context it forked from
+    let mut versions = vec![];
+    let mut ctx = leaf;
+    loop {
+        let own_var = analyzer
+            .search_children_depth(ctx.0.into(), &Edge::Context(ContextEdge::Variable), 1)
+            .into_iter()
+            .map(ContextVarNode::from)
+            .find(|var| var.name(analyzer) == name);
+        let mut version = own_var.map(|var| var.latest_version(analyzer));
+        while let Some(var) = version {
+            versions.push(var);
+            version = var.previous_version(analyzer);
+        }
+        if ctx == body_ctx {
+            break;
+        }
+        ctx = ctx.underlying(analyzer).parent_ctx?;
+    }
+
+    versions
+        .into_iter()
+        .rev()
+        .take_while(|var| var.assigned_from(analyzer).is_none())
+        .last()
+}
+
+fn concrete_ord(a: &Concrete, b: &Concrete) -> Ordering {
+    Elem::Concrete(RangeConcrete::from(a.clone()))
+        .range_ord(&Elem::Concrete(RangeConcrete::from(b.clone())))
+        .unwrap_or(Ordering::Equal)
+}
+
+fn increment(val: Concrete) -> Option<Concrete> {
+    match val {
+        Concrete::Uint(size, val) => Some(Concrete::Uint(size, val.checked_add(1.into())?)),
+        Concrete::Int(size, val) => Some(Concrete::Int(size, val.checked_add(1.into())?)),
+        _ => None,
+    }
+}
@@ -0,0 +1,114 @@
+use crate::analyzers::{Finding, LocStrSpan, ReportConfig, ReportDisplay, Severity};
+use shared::{
+    analyzer::{AnalyzerLike, Search},
+    context::*,
+};
+
+use ariadne::{Color, Config, Fmt, Label, Report, ReportKind, Span};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone)]
+pub struct ShadowingAnalysis<'a> {
+    pub file_mapping: &'a BTreeMap<usize, String>,
+    pub ctx_loc: LocStrSpan,
+    pub ctx: ContextNode,
+    pub report_config: ReportConfig,
+    /// Locals and parameters hiding a state variable, along with the state variable's declaration
+    pub shadowed: Vec<(LocStrSpan, LocStrSpan, String)>,
+}
+
+impl<'a> ReportDisplay for ShadowingAnalysis<'a> {
+    fn report_kind(&self) -> ReportKind<'_> {
+        ReportKind::Custom("Shadowing", Color::Yellow)
+    }
+    fn msg(&self, analyzer: &(impl AnalyzerLike + Search)) -> String {
+        format!(
+            "State variables shadowed in function: {}",
+            format!("function {}", self.ctx.associated_fn_name(analyzer)).fg(Color::Cyan)
+        )
+    }
+
+    fn labels(&self, _analyzer: &(impl AnalyzerLike + Search)) -> Vec<Label<LocStrSpan>> {
+        self.shadowed
+            .iter()
+            .flat_map(|(span, state_var_span, name)| {
+                [
+                    Label::new(span.clone())
+                        .with_message(shadowing_msg(name).fg(Color::Yellow))
+                        .with_color(Color::Yellow),
+                    Label::new(state_var_span.clone())
+                        .with_message(format!("state variable \"{name}\" declared here"))
+                        .with_color(Color::Cyan),
+                ]
+            })
+            .collect()
+    }
+
+    fn reports(&self, analyzer: &(impl AnalyzerLike + Search)) -> Vec<Report<'_, LocStrSpan>> {
+        if self.shadowed.is_empty() {
+            return vec![];
+        }
+        let report = Report::build(
+            self.report_kind(),
+            self.ctx_loc.source(),
+            self.ctx_loc.start(),
+        )
+        .with_message(self.msg(analyzer))
+        .with_config(
+            Config::default()
+                .with_cross_gap(false)
+                .with_underlines(true)
+                .with_tab_width(4),
+        )
+        .with_labels(self.labels(analyzer));
+        vec![report.finish()]
+    }
+
+    fn findings(&self, _analyzer: &(impl AnalyzerLike + Search)) -> Vec<Finding> {
+        self.shadowed
+            .iter()
+            .map(|(span, _, name)| Finding::new(Severity::Warning, span, shadowing_msg(name)))
+            .collect()
+    }
+}
+
+fn shadowing_msg(name: &str) -> String {
+    format!("\"{name}\" shadows a state variable, uses of it here do not touch storage")
+}
+
+impl<T> ShadowingAnalyzer for T where T: Search + AnalyzerLike + Sized {}
+pub trait ShadowingAnalyzer: Search + AnalyzerLike + Sized {
+    /// Collects the locals and parameters in `ctx` and its subcontexts that are named the same
+    /// as a state variable of the function's contract or one of its bases
+    fn shadowing_report<'a>(
+        &'a self,
+        file_mapping: &'a BTreeMap<usize, String>,
+        ctx: ContextNode,
+        report_config: ReportConfig,
+    ) -> ShadowingAnalysis<'a> {
+        let mut shadowed = std::iter::once(ctx)
+            .chain(ctx.subcontexts(self))
+            .flat_map(|ctx| ctx.shadowing(self))
+            .collect::<Vec<_>>();
+        // a definition is reached once per fork that is live at that point
+        shadowed.sort();
+        shadowed.dedup();
+
+        ShadowingAnalysis {
+            file_mapping,
+            ctx_loc: LocStrSpan::new(file_mapping, ctx.underlying(self).loc),
+            ctx,
+            report_config,
+            shadowed: shadowed
+                .into_iter()
+                .map(|shadowing| {
+                    (
+                        LocStrSpan::new(file_mapping, shadowing.loc),
+                        LocStrSpan::new(file_mapping, shadowing.state_var_loc),
+                        shadowing.name,
+                    )
+                })
+                .collect(),
+        }
+    }
+}
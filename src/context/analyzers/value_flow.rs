@@ -0,0 +1,151 @@
+use crate::analyzers::{Finding, LocStrSpan, ReportConfig, ReportDisplay, Severity};
+use shared::{
+    analyzer::{AnalyzerLike, Search},
+    context::*,
+    nodes::Concrete,
+};
+
+use ariadne::{Color, Config, Fmt, Label, Report, ReportKind, Span};
+use ethers_core::types::U256;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone)]
+pub struct ValueFlowAnalysis<'a> {
+    pub file_mapping: &'a BTreeMap<usize, String>,
+    pub ctx_loc: LocStrSpan,
+    pub ctx: ContextNode,
+    pub report_config: ReportConfig,
+    /// Ether sent out of the contract anywhere in the function, including calls it makes
+    pub transfers: Vec<(LocStrSpan, ValueTransfer)>,
+}
+
+impl<'a> ReportDisplay for ValueFlowAnalysis<'a> {
+    fn report_kind(&self) -> ReportKind<'_> {
+        ReportKind::Custom("Value flow", Color::Magenta)
+    }
+    fn msg(&self, analyzer: &(impl AnalyzerLike + Search)) -> String {
+        format!(
+            "Value sent out of the contract in function: {}",
+            format!("function {}", self.ctx.associated_fn_name(analyzer)).fg(Color::Cyan)
+        )
+    }
+
+    fn labels(&self, _analyzer: &(impl AnalyzerLike + Search)) -> Vec<Label<LocStrSpan>> {
+        self.transfers
+            .iter()
+            .map(|(span, transfer)| {
+                let color = if is_unbounded(transfer) {
+                    Color::Red
+                } else {
+                    Color::Magenta
+                };
+                Label::new(span.clone())
+                    .with_message(value_transfer_msg(transfer).fg(color))
+                    .with_color(color)
+            })
+            .collect()
+    }
+
+    fn reports(&self, analyzer: &(impl AnalyzerLike + Search)) -> Vec<Report<'_, LocStrSpan>> {
+        if self.transfers.is_empty() {
+            return vec![];
+        }
+        let report = Report::build(
+            self.report_kind(),
+            self.ctx_loc.source(),
+            self.ctx_loc.start(),
+        )
+        .with_message(self.msg(analyzer))
+        .with_config(
+            Config::default()
+                .with_cross_gap(false)
+                .with_underlines(true)
+                .with_tab_width(4),
+        )
+        .with_labels(self.labels(analyzer));
+        vec![report.finish()]
+    }
+
+    fn findings(&self, _analyzer: &(impl AnalyzerLike + Search)) -> Vec<Finding> {
+        self.transfers
+            .iter()
+            .map(|(span, transfer)| {
+                let severity = if is_unbounded(transfer) {
+                    Severity::Warning
+                } else {
+                    Severity::Info
+                };
+                Finding::new(severity, span, value_transfer_msg(transfer)).with_range(
+                    transfer.value.clone(),
+                    bound_str(&transfer.min),
+                    bound_str(&transfer.max),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Whether nothing limits the value below the maximum of its type
+fn is_unbounded(transfer: &ValueTransfer) -> bool {
+    transfer
+        .max
+        .as_ref()
+        .and_then(|max| max.into_u256())
+        .is_none_or(|max| max == U256::MAX)
+}
+
+fn bound_str(bound: &Option<Concrete>) -> String {
+    bound
+        .as_ref()
+        .map_or_else(|| "unknown".to_string(), |bound| bound.as_human_string())
+}
+
+fn value_transfer_msg(transfer: &ValueTransfer) -> String {
+    let range = format!(
+        "[{}, {}]",
+        bound_str(&transfer.min),
+        bound_str(&transfer.max)
+    );
+    if is_unbounded(transfer) {
+        format!(
+            "Value sent through \"{}\" is unbounded, value range = {range}",
+            transfer.via
+        )
+    } else {
+        format!(
+            "Value sent through \"{}\", value range = {range}",
+            transfer.via
+        )
+    }
+}
+
+impl<T> ValueFlowAnalyzer for T where T: Search + AnalyzerLike + Sized {}
+pub trait ValueFlowAnalyzer: Search + AnalyzerLike + Sized {
+    /// Collects the `transfer`s, `send`s and calls with a `value` option in `ctx` and its
+    /// subcontexts, with the range of the value sent at that point
+    fn value_flow_report<'a>(
+        &'a self,
+        file_mapping: &'a BTreeMap<usize, String>,
+        ctx: ContextNode,
+        report_config: ReportConfig,
+    ) -> ValueFlowAnalysis<'a> {
+        let mut transfers = std::iter::once(ctx)
+            .chain(ctx.subcontexts(self))
+            .flat_map(|ctx| ctx.value_transfers(self))
+            .collect::<Vec<_>>();
+        // each fork reaching the same statement sends its own range of values
+        transfers.sort();
+        transfers.dedup();
+
+        ValueFlowAnalysis {
+            file_mapping,
+            ctx_loc: LocStrSpan::new(file_mapping, ctx.underlying(self).loc),
+            ctx,
+            report_config,
+            transfers: transfers
+                .into_iter()
+                .map(|transfer| (LocStrSpan::new(file_mapping, transfer.loc), transfer))
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use crate::context::ContextBuilder;
+use shared::analyzer::AnalyzerLike;
+use shared::context::*;
+use shared::Node;
+use solang_parser::pt::{
+    Expression, Loc, YulBlock, YulExpression, YulFunctionCall, YulStatement, YulSwitchOptions,
+};
+use std::collections::BTreeSet;
+
+impl<T> Assembly for T where T: AnalyzerLike<Expr = Expression> + Sized {}
+/// Inline assembly is not interpreted, only its effect on the surrounding Solidity is approximated
+pub trait Assembly: AnalyzerLike<Expr = Expression> + Sized {
+    /// Skips over an `assembly { .. }` block, widening the variables it assigns to their full
+    /// range. With `assembly_unknown` set, every variable the block mentions is widened
+    fn assembly_block(&mut self, loc: Loc, block: &YulBlock, ctx: ContextNode) {
+        self.add_warning(loc, "Inline assembly is not analyzed".to_string());
+
+        let mut idents = YulIdents::default();
+        idents.block(block);
+        let touched = if self.assembly_unknown() {
+            &idents.used
+        } else {
+            &idents.assigned
+        };
+
+        touched
+            .difference(&idents.declared)
+            .cloned()
+            .collect::<Vec<_>>()
+            .into_iter()
+            .for_each(|name| {
+                let var = match ctx.var_by_name_or_recurse(self, &name) {
+                    Some(var) => var,
+                    None => {
+                        // a state variable that has not been used in this context yet
+                        let is_state_var = ctx
+                            .maybe_associated_contract(self)
+                            .and_then(|contract| contract.state_var(self, &name))
+                            .is_some();
+                        if !is_state_var {
+                            return;
+                        }
+                        let ident = solang_parser::pt::Identifier { loc, name };
+                        let (_, idx) = self
+                            .parse_ctx_expr(&Expression::Variable(ident), ctx)
+                            .expect_single();
+                        if !matches!(self.node(idx), Node::ContextVar(_)) {
+                            return;
+                        }
+                        ContextVarNode::from(idx)
+                    }
+                };
+                let var = var.latest_version(self);
+                if let Some(r) = var.underlying(self).ty.default_range(self) {
+                    let new_var = self.advance_var_in_ctx(var, loc, ctx);
+                    new_var.set_range_min(self, r.min);
+                    new_var.set_range_max(self, r.max);
+                }
+            });
+    }
+}
+
+/// The names a block of Yul assigns to, mentions at all, and declares itself
+#[derive(Default)]
+struct YulIdents {
+    assigned: BTreeSet<String>,
+    used: BTreeSet<String>,
+    declared: BTreeSet<String>,
+}
+
+impl YulIdents {
+    fn block(&mut self, block: &YulBlock) {
+        block.statements.iter().for_each(|stmt| self.stmt(stmt));
+    }
+
+    fn stmt(&mut self, stmt: &YulStatement) {
+        match stmt {
+            YulStatement::Assign(_, lhs, rhs) => {
+                lhs.iter().for_each(|expr| {
+                    if let Some(name) = yul_var(expr) {
+                        self.assigned.insert(name);
+                    }
+                    self.expr(expr);
+                });
+                self.expr(rhs);
+            }
+            YulStatement::VariableDeclaration(_, idents, rhs) => {
+                idents.iter().for_each(|ident| {
+                    self.declared.insert(ident.id.name.clone());
+                });
+                if let Some(rhs) = rhs {
+                    self.expr(rhs);
+                }
+            }
+            YulStatement::If(_, cond, body) => {
+                self.expr(cond);
+                self.block(body);
+            }
+            YulStatement::For(for_loop) => {
+                self.block(&for_loop.init_block);
+                self.expr(&for_loop.condition);
+                self.block(&for_loop.post_block);
+                self.block(&for_loop.execution_block);
+            }
+            YulStatement::Switch(switch) => {
+                self.expr(&switch.condition);
+                switch
+                    .cases
+                    .iter()
+                    .chain(&switch.default)
+                    .for_each(|case| match case {
+                        YulSwitchOptions::Case(_, expr, body) => {
+                            self.expr(expr);
+                            self.block(body);
+                        }
+                        YulSwitchOptions::Default(_, body) => self.block(body),
+                    });
+            }
+            YulStatement::Block(block) => self.block(block),
+            YulStatement::FunctionDefinition(def) => {
+                def.params.iter().chain(&def.returns).for_each(|ident| {
+                    self.declared.insert(ident.id.name.clone());
+                });
+                self.block(&def.body);
+            }
+            YulStatement::FunctionCall(call) => self.call(call),
+            YulStatement::Leave(_)
+            | YulStatement::Break(_)
+            | YulStatement::Continue(_)
+            | YulStatement::Error(_) => {}
+        }
+    }
+
+    fn call(&mut self, call: &YulFunctionCall) {
+        // `sstore(x.slot, ..)` writes to the storage variable `x`
+        if call.id.name == "sstore" {
+            if let Some(name) = call.arguments.first().and_then(yul_var) {
+                self.assigned.insert(name);
+            }
+        }
+        call.arguments.iter().for_each(|arg| self.expr(arg));
+    }
+
+    fn expr(&mut self, expr: &YulExpression) {
+        match expr {
+            YulExpression::Variable(ident) => {
+                self.used.insert(ident.name.clone());
+            }
+            YulExpression::SuffixAccess(_, base, _) => self.expr(base),
+            YulExpression::FunctionCall(call) => self.call(call),
+            _ => {}
+        }
+    }
+}
+
+/// The Solidity variable a Yul expression refers to, i.e. `x` for both `x` and `x.slot`
+fn yul_var(expr: &YulExpression) -> Option<String> {
+    match expr {
+        YulExpression::Variable(ident) => Some(ident.name.clone()),
+        YulExpression::SuffixAccess(_, base, _) => yul_var(base),
+        _ => None,
+    }
+}
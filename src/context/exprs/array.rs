@@ -2,7 +2,17 @@ use crate::{
     context::exprs::{member_access::MemberAccess, require::Require},
     Builtin, ContextBuilder, Edge, ExprRet, Node, VarType,
 };
-use shared::{analyzer::AnalyzerLike, context::*, range::elem::RangeOp};
+use ethers_core::types::U256;
+use shared::{
+    analyzer::AnalyzerLike,
+    context::*,
+    nodes::{BuiltInNode, Concrete, ConcreteNode},
+    range::{
+        elem::RangeOp,
+        elem_ty::{Dynamic, Elem, RangeDyn},
+        Range, SolcRange,
+    },
+};
 
 use solang_parser::pt::{Expression, Loc};
 
@@ -25,6 +35,102 @@ pub trait Array: AnalyzerLike<Expr = Expression> + Sized {
         }
     }
 
+    /// Parses an inline array, i.e. `[1, 2, x]`. If every element is a constant, the result is
+    /// a `Concrete::Array`. Otherwise it is a memory array of the elements' common type, with a
+    /// range holding its length and elements
+    fn array_literal(&mut self, loc: Loc, elem_exprs: &[Expression], ctx: ContextNode) -> ExprRet {
+        let mut elems = vec![];
+        for expr in elem_exprs {
+            match self.parse_ctx_expr(expr, ctx) {
+                ExprRet::CtxKilled => return ExprRet::CtxKilled,
+                ret => elems.push(ContextVarNode::from(ret.expect_single().1)),
+            }
+        }
+
+        if elems.iter().all(|elem| elem.is_concrete(self)) {
+            let arr = Concrete::Array(elems.iter().map(|elem| elem.as_concrete(self)).collect());
+            let concrete_node = ConcreteNode::from(self.concrete_or_add(arr));
+            let cvar = ContextVar::new_from_concrete(loc, concrete_node, self);
+            let node = self.add_node(Node::ContextVar(cvar));
+            self.add_edge(node, ctx, Edge::Context(ContextEdge::Variable));
+            return ExprRet::Single((ctx, node));
+        }
+
+        // there is at least one non constant element here
+        let elem_ty = self.literal_elem_ty(&elems);
+        let arr_ty = BuiltInNode::from(self.builtin_or_add(Builtin::Array(elem_ty)));
+        let arr_range = Elem::ConcreteDyn(Box::new(RangeDyn {
+            len: Elem::from(Concrete::from(U256::from(elems.len()))),
+            val: elems
+                .iter()
+                .enumerate()
+                .map(|(i, elem)| {
+                    (
+                        Elem::from(Concrete::from(U256::from(i))),
+                        Elem::Dynamic(Dynamic::new((*elem).into(), loc)),
+                    )
+                })
+                .collect(),
+            loc,
+        }));
+        let display = elems
+            .iter()
+            .map(|elem| elem.display_name(self))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let arr = ContextVar {
+            loc: Some(loc),
+            name: format!("tmp_arr{}", ctx.new_tmp(self)),
+            display_name: format!("[{display}]"),
+            storage: None,
+            is_tmp: true,
+            is_symbolic: false,
+            is_tainted: elems.iter().any(|elem| elem.is_tainted(self)),
+            tmp_of: None,
+            ty: VarType::BuiltIn(
+                arr_ty,
+                Some(SolcRange {
+                    min: arr_range.clone(),
+                    max: arr_range,
+                    exclusions: vec![],
+                }),
+            ),
+        };
+        let node = self.add_node(Node::ContextVar(arr));
+        self.add_edge(node, ctx, Edge::Context(ContextEdge::Variable));
+        ExprRet::Single((ctx, node))
+    }
+
+    /// The type every element of a non empty array literal converts to. Integers are widened to
+    /// the largest of their sizes, and become signed if any of them is
+    fn literal_elem_ty(&mut self, elems: &[ContextVarNode]) -> VarType {
+        let builtin = |analyzer: &Self, elem: &ContextVarNode| match elem.ty(analyzer) {
+            VarType::BuiltIn(bn, _) => Some(bn.underlying(analyzer).clone()),
+            VarType::Concrete(c) => match c.underlying(analyzer) {
+                Concrete::Array(_) => None,
+                c => Some(c.as_builtin()),
+            },
+            VarType::User(..) => None,
+        };
+        let common = elems
+            .iter()
+            .map(|elem| builtin(self, elem))
+            .reduce(|a, b| match (a?, b?) {
+                (Builtin::Uint(a), Builtin::Uint(b)) => Some(Builtin::Uint(a.max(b))),
+                (Builtin::Int(a), Builtin::Int(b))
+                | (Builtin::Int(a), Builtin::Uint(b))
+                | (Builtin::Uint(a), Builtin::Int(b)) => Some(Builtin::Int(a.max(b))),
+                (a, b) if a == b => Some(a),
+                _ => None,
+            })
+            .flatten();
+        match common {
+            Some(common) => VarType::BuiltIn(BuiltInNode::from(self.builtin_or_add(common)), None),
+            // structs, contracts and the like have to be of the same type already
+            None => elems[0].ty(self).clone(),
+        }
+    }
+
     /// Indexes into an array
     fn index_into_array(
         &mut self,
@@ -50,17 +156,22 @@ pub trait Array: AnalyzerLike<Expr = Expression> + Sized {
             (ExprRet::CtxKilled, _) => ExprRet::CtxKilled,
             (ExprRet::Single((ctx, parent)), ExprRet::Single((_rhs_ctx, index))) | (ExprRet::Single((ctx, parent)), ExprRet::SingleLiteral((_rhs_ctx, index))) => {
                 let index = ContextVarNode::from(index);
+                let known = self.known_element(ContextVarNode::from(parent), index);
                 let parent = ContextVarNode::from(parent).first_version(self);
-                let len_var = self.tmp_length(parent, ctx, loc);
-                let idx = self.advance_var_in_ctx(index, loc, ctx);
-                self.handle_require_inner(
-                    loc,
-                    &ExprRet::Single((ctx, idx.into())),
-                    &ExprRet::Single((ctx, len_var.into())),
-                    RangeOp::Lt,
-                    RangeOp::Gt,
-                    (RangeOp::Gte, RangeOp::Lte),
-                );
+                // any key is a valid index into a mapping
+                if !parent.ty(self).is_mapping(self) {
+                    let len_var = self.tmp_length(parent, ctx, loc);
+                    self.record_out_of_bounds(loc, index, len_var, ctx);
+                    let idx = self.advance_var_in_ctx(index, loc, ctx);
+                    self.handle_require_inner(
+                        loc,
+                        &ExprRet::Single((ctx, idx.into())),
+                        &ExprRet::Single((ctx, len_var.into())),
+                        RangeOp::Lt,
+                        RangeOp::Gt,
+                        (RangeOp::Gte, RangeOp::Lte),
+                    );
+                }
 
                 let name = format!("{}[{}]", parent.name(self), index.name(self));
                 if let Some(index_var) = ctx.var_by_name_or_recurse(self, &name) {
@@ -69,21 +180,22 @@ pub trait Array: AnalyzerLike<Expr = Expression> + Sized {
                     ExprRet::Single((ctx, index_var.into()))
                 } else {
                     let index_var = ContextVar {
-                        loc: Some(loc),
-                        name: name.clone(),
                         display_name: format!(
                             "{}[{}]",
                             parent.display_name(self),
                             index.display_name(self)
                         ),
                         storage: parent.storage(self).clone(),
-                        is_tmp: false,
-                        tmp_of: None,
                         is_symbolic: true,
-                        ty: parent.ty(self).clone().array_underlying_ty(self),
+                        ..ContextVar::new(loc, name.clone(), parent.ty(self).clone().array_underlying_ty(self))
                     };
 
                     let idx_node = self.add_node(Node::ContextVar(index_var));
+                    if let Some(elem) = known {
+                        let idx_var = ContextVarNode::from(idx_node);
+                        idx_var.set_range_min(self, elem.clone());
+                        idx_var.set_range_max(self, elem);
+                    }
                     self.add_edge(idx_node, parent, Edge::Context(ContextEdge::IndexAccess));
                     self.add_edge(idx_node, ctx, Edge::Context(ContextEdge::Variable));
                     self.add_edge(index, idx_node, Edge::Context(ContextEdge::Index));
@@ -94,4 +206,55 @@ pub trait Array: AnalyzerLike<Expr = Expression> + Sized {
             e => panic!("Expected single expr evaluation of index expression, but was: {e:?}. This is a bug. Please report it at github.com/nascentxyz/pyrometer."),
         }
     }
+
+    /// The element at a constant `index` of `arr` when its contents are known, i.e. the `0x02`
+    /// of `hex"010203"[1]`
+    fn known_element(&self, arr: ContextVarNode, index: ContextVarNode) -> Option<Elem<Concrete>> {
+        if !index.is_const(self) {
+            return None;
+        }
+        let index = index
+            .evaled_range_min(self)?
+            .maybe_concrete()?
+            .val
+            .into_u256()?;
+        let key = Elem::from(Concrete::from(index));
+        let range = arr.latest_version(self).range(self)?;
+        let min = range.range_min().maybe_range_dyn()?.val.get(&key)?.clone();
+        let max = range.range_max().maybe_range_dyn()?.val.get(&key)?.clone();
+        (min == max && min.maybe_concrete().is_some()).then_some(min)
+    }
+
+    /// Records an index that may reach past the end of an array whose length is known,
+    /// before the bounds check narrows the index
+    fn record_out_of_bounds(
+        &mut self,
+        loc: Loc,
+        index: ContextVarNode,
+        len_var: ContextVarNode,
+        ctx: ContextNode,
+    ) {
+        let bound = |elem: Option<Elem<Concrete>>| elem?.maybe_concrete()?.val.into_u256();
+        let (Some(len_min), Some(len_max), Some(index_max)) = (
+            bound(len_var.evaled_range_min(self)),
+            bound(len_var.evaled_range_max(self)),
+            bound(index.evaled_range_max(self)),
+        ) else {
+            return;
+        };
+        if len_min == len_max && index_max >= len_max {
+            // the first index past the end, unless the index can't be that small
+            let index_min = bound(index.evaled_range_min(self)).unwrap_or_default();
+            let witness = Concrete::from(index_min.max(len_max));
+            let tainted = self.security_mode() && index.is_tainted(self);
+            ctx.add_out_of_bounds(
+                OutOfBounds {
+                    loc,
+                    witness,
+                    tainted,
+                },
+                self,
+            );
+        }
+    }
 }
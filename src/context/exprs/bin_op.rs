@@ -1,6 +1,6 @@
 use crate::{context::ContextBuilder, ExprRet};
 use ethers_core::types::{I256, U256};
-use shared::range::elem::RangeElem;
+use shared::range::{elem::RangeElem, range_ops::RangeWrapping};
 use shared::{
     analyzer::AnalyzerLike,
     context::*,
@@ -14,6 +14,7 @@ use shared::{
 };
 
 use solang_parser::pt::{Expression, Loc};
+use std::cmp::Ordering;
 
 impl<T> BinOp for T where T: AnalyzerLike<Expr = Expression> + Sized {}
 pub trait BinOp: AnalyzerLike<Expr = Expression> + Sized {
@@ -28,8 +29,112 @@ pub trait BinOp: AnalyzerLike<Expr = Expression> + Sized {
         assign: bool,
     ) -> ExprRet {
         let lhs_paths = self.parse_ctx_expr(lhs_expr, ctx);
-        let rhs_paths = self.parse_ctx_expr(rhs_expr, ctx);
+        self.op_with_lhs(loc, lhs_paths, rhs_expr, ctx, op, assign)
+    }
+
+    /// Evaluate and execute a bitwise NOT (`~x`)
+    fn bit_not(&mut self, loc: Loc, lhs_expr: &Expression, ctx: ContextNode) -> ExprRet {
+        let lhs = self.parse_ctx_expr(lhs_expr, ctx);
+        self.bit_not_inner(loc, lhs)
+    }
+
+    fn bit_not_inner(&mut self, loc: Loc, lhs_expr: ExprRet) -> ExprRet {
+        match lhs_expr {
+            ExprRet::CtxKilled => lhs_expr,
+            ExprRet::Single((ctx, lhs)) | ExprRet::SingleLiteral((ctx, lhs)) => {
+                let lhs_cvar = ContextVarNode::from(lhs).latest_version(self);
+                let mut out_var = ContextVar {
+                    display_name: format!("~{}", lhs_cvar.display_name(self)),
+                    is_tmp: true,
+                    tmp_of: Some(TmpConstruction::new(lhs_cvar, RangeOp::BitNot, None)),
+                    is_symbolic: lhs_cvar.is_symbolic(self),
+                    ..ContextVar::new(
+                        loc,
+                        format!("tmp{}(~{})", ctx.new_tmp(self), lhs_cvar.name(self)),
+                        lhs_cvar.underlying(self).ty.clone(),
+                    )
+                };
+                out_var.ty.concrete_to_builtin(self);
+                let out_var = ContextVarNode::from(self.add_node(Node::ContextVar(out_var)));
+                self.add_edge(out_var, ctx, Edge::Context(ContextEdge::Variable));
+
+                // anything but an integer falls back to the full range of its type
+                let range = match out_var.ty(self) {
+                    VarType::BuiltIn(bn, _) => {
+                        let builtin = bn.underlying(self).clone();
+                        SolcRange::bit_not_dyn(&builtin, lhs_cvar, loc)
+                    }
+                    _ => None,
+                }
+                .or_else(|| out_var.underlying(self).ty.default_range(self));
+                if let Some(range) = range {
+                    out_var.set_range_min(self, range.range_min());
+                    out_var.set_range_max(self, range.range_max());
+                }
+                ExprRet::Single((ctx, out_var.into()))
+            }
+            ExprRet::Multi(inner) => ExprRet::Multi(
+                inner
+                    .into_iter()
+                    .map(|expr_ret| self.bit_not_inner(loc, expr_ret))
+                    .collect(),
+            ),
+            ExprRet::Fork(world1, world2) => ExprRet::Fork(
+                Box::new(self.bit_not_inner(loc, *world1)),
+                Box::new(self.bit_not_inner(loc, *world2)),
+            ),
+        }
+    }
+
+    /// Evaluates the right hand side of a binary operation once the left hand side is known. If
+    /// the left hand side forked the context, the right hand side is evaluated in each fork
+    fn op_with_lhs(
+        &mut self,
+        loc: Loc,
+        lhs_paths: ExprRet,
+        rhs_expr: &Expression,
+        ctx: ContextNode,
+        op: RangeOp,
+        assign: bool,
+    ) -> ExprRet {
+        match lhs_paths {
+            ExprRet::Fork(world1, world2) => {
+                let ctx1 = world_ctx(&world1).unwrap_or(ctx);
+                let ctx2 = world_ctx(&world2).unwrap_or(ctx);
+                ExprRet::Fork(
+                    Box::new(self.op_with_lhs(loc, *world1, rhs_expr, ctx1, op, assign)),
+                    Box::new(self.op_with_lhs(loc, *world2, rhs_expr, ctx2, op, assign)),
+                )
+            }
+            lhs_paths if assign => {
+                // `x op= y` is checked the same as `x = x op y`
+                let rhs_paths = self.parse_ctx_expr(rhs_expr, ctx);
+                let result = self.op_match(loc, lhs_paths.clone(), rhs_paths, op, false);
+                self.match_assign_sides(loc, &lhs_paths, &result)
+            }
+            lhs_paths => {
+                let rhs_paths = self.parse_ctx_expr(rhs_expr, ctx);
+                self.op_match(loc, lhs_paths, rhs_paths, op, assign)
+            }
+        }
+    }
+
+    fn op_match(
+        &mut self,
+        loc: Loc,
+        lhs_paths: ExprRet,
+        rhs_paths: ExprRet,
+        op: RangeOp,
+        assign: bool,
+    ) -> ExprRet {
         match (lhs_paths, rhs_paths) {
+            // a call returning a single value gives a one element `Multi`
+            (ExprRet::Multi(mut lhs_sides), rhs_paths) if lhs_sides.len() == 1 => {
+                self.op_match(loc, lhs_sides.swap_remove(0), rhs_paths, op, assign)
+            }
+            (lhs_paths, ExprRet::Multi(mut rhs_sides)) if rhs_sides.len() == 1 => {
+                self.op_match(loc, lhs_paths, rhs_sides.swap_remove(0), op, assign)
+            }
             (ExprRet::SingleLiteral((lhs_ctx, lhs)), ExprRet::SingleLiteral((rhs_ctx, rhs))) => {
                 let lhs_cvar = ContextVarNode::from(lhs).latest_version(self);
                 let rhs_cvar = ContextVarNode::from(rhs).latest_version(self);
@@ -129,6 +234,17 @@ pub trait BinOp: AnalyzerLike<Expr = Expression> + Sized {
             }
             (_, ExprRet::CtxKilled) => ExprRet::CtxKilled,
             (ExprRet::CtxKilled, _) => ExprRet::CtxKilled,
+            // the rhs forked the context, the lhs is used in each of the forks
+            (lhs_paths, ExprRet::Fork(world1, world2)) => {
+                let lhs1 = world_ctx(&world1)
+                    .map_or_else(|| lhs_paths.clone(), |ctx| lhs_paths.in_ctx(ctx));
+                let lhs2 = world_ctx(&world2)
+                    .map_or_else(|| lhs_paths.clone(), |ctx| lhs_paths.in_ctx(ctx));
+                ExprRet::Fork(
+                    Box::new(self.op_match(loc, lhs1, *world1, op, assign)),
+                    Box::new(self.op_match(loc, lhs2, *world2, op, assign)),
+                )
+            }
             (l, r) => todo!("here: {l:?} {r:?}"),
         }
     }
@@ -148,25 +264,26 @@ pub trait BinOp: AnalyzerLike<Expr = Expression> + Sized {
             self.advance_var_in_ctx(lhs_cvar, loc, ctx)
         } else {
             let mut new_lhs_underlying = ContextVar {
-                loc: Some(loc),
-                name: format!(
-                    "tmp{}({} {} {})",
-                    ctx.new_tmp(self),
-                    lhs_cvar.name(self),
-                    op.to_string(),
-                    rhs_cvar.name(self)
-                ),
                 display_name: format!(
                     "({} {} {})",
                     lhs_cvar.display_name(self),
                     op.to_string(),
                     rhs_cvar.display_name(self)
                 ),
-                storage: None,
                 is_tmp: true,
-                is_symbolic: lhs_cvar.is_symbolic(self) || rhs_cvar.is_symbolic(self),
                 tmp_of: Some(TmpConstruction::new(lhs_cvar, op, Some(rhs_cvar))),
-                ty: lhs_cvar.underlying(self).ty.clone(),
+                is_symbolic: lhs_cvar.is_symbolic(self) || rhs_cvar.is_symbolic(self),
+                ..ContextVar::new(
+                    loc,
+                    format!(
+                        "tmp{}({} {} {})",
+                        ctx.new_tmp(self),
+                        lhs_cvar.name(self),
+                        op.to_string(),
+                        rhs_cvar.name(self)
+                    ),
+                    self.result_ty(lhs_cvar, rhs_cvar, op),
+                )
             };
 
             // will potentially mutate the ty from concrete to builtin with a concrete range
@@ -179,43 +296,58 @@ pub trait BinOp: AnalyzerLike<Expr = Expression> + Sized {
 
         let mut new_rhs = rhs_cvar.latest_version(self);
 
-        // TODO: change to only hit this path if !uncheck
+        let unchecked = ctx.wraps(self);
+        self.record_overflow(loc, lhs_cvar, new_rhs, new_lhs, ctx, op);
+
+        // checked arithmetic between typed constants that overflows always reverts
+        if !unchecked
+            && op.wrapping().is_some()
+            && lhs_cvar.is_const(self)
+            && new_rhs.is_const(self)
+        {
+            if let (VarType::BuiltIn(..), Some(lhs), Some(rhs)) = (
+                lhs_cvar.ty(self),
+                lhs_cvar.evaled_range_min(self),
+                new_rhs.evaled_range_min(self),
+            ) {
+                if let Some((_, true)) = lhs.range_wrapping_op(&rhs, op) {
+                    ctx.kill(self, loc, KilledKind::Panic);
+                    return ExprRet::CtxKilled;
+                }
+            }
+        }
 
         // TODO: If one of lhs_cvar OR rhs_cvar are not symbolic,
         // apply the requirement on the symbolic expression side instead of
         // ignoring the case where
 
         // if lhs_cvar.is_symbolic(self) && new_rhs.is_symbolic(self) {
+        // overflow checks are skipped in `unchecked` blocks, division by zero still reverts
         if !assign {
             match op {
                 RangeOp::Div | RangeOp::Mod => {
+                    if self.may_be_zero(new_rhs) {
+                        let tainted = self.security_mode() && new_rhs.is_tainted(self);
+                        ctx.add_zero_divisor(loc, tainted, self);
+                    }
                     if new_rhs.is_const(self) {
                         if new_rhs
                             .evaled_range_min(self)
                             .expect("No range?")
                             .range_eq(&Elem::from(Concrete::from(U256::zero())))
                         {
-                            ctx.kill(self, loc);
+                            ctx.kill(self, loc, KilledKind::Panic);
                             return ExprRet::CtxKilled;
                         }
                     } else if new_rhs.is_symbolic(self) {
-                        println!(
-                            "lhs: {}, op: {}, rhs: {:?}",
-                            lhs_cvar.display_name(self),
-                            op.to_string(),
-                            new_rhs.display_name(self)
-                        );
                         let tmp_rhs = self.advance_var_in_ctx(new_rhs, loc, ctx);
-                        let zero_node = self.add_node(Node::Concrete(Concrete::from(U256::zero())));
+                        let zero_node = self.concrete_or_add(Concrete::from(U256::zero()));
                         let zero_node = self.add_node(Node::ContextVar(
-                            ContextVar::new_from_concrete(Loc::Implicit, zero_node.into(), self),
+                            ContextVar::new_from_concrete(loc, zero_node.into(), self),
                         ));
 
                         let tmp_var = ContextVar {
-                            loc: Some(loc),
-                            name: format!("tmp{}({} != 0)", ctx.new_tmp(self), tmp_rhs.name(self),),
                             display_name: format!("({} != 0)", tmp_rhs.display_name(self),),
-                            storage: None,
                             is_tmp: true,
                             tmp_of: Some(TmpConstruction::new(
                                 new_lhs,
@@ -223,10 +355,14 @@ pub trait BinOp: AnalyzerLike<Expr = Expression> + Sized {
                                 Some(zero_node.into()),
                             )),
                             is_symbolic: true,
-                            ty: VarType::BuiltIn(
-                                BuiltInNode::from(self.builtin_or_add(Builtin::Bool)),
-                                SolcRange::from(Concrete::Bool(true)),
-                            ),
+                            ..ContextVar::new(
+                                loc,
+                                format!("tmp{}({} != 0)", ctx.new_tmp(self), tmp_rhs.name(self),),
+                                VarType::BuiltIn(
+                                    BuiltInNode::from(self.builtin_or_add(Builtin::Bool)),
+                                    SolcRange::from(Concrete::Bool(true)),
+                                ),
+                            )
                         };
 
                         let cvar = ContextVarNode::from(self.add_node(Node::ContextVar(tmp_var)));
@@ -251,7 +387,7 @@ pub trait BinOp: AnalyzerLike<Expr = Expression> + Sized {
                         }
                     }
                 }
-                RangeOp::Sub => {
+                RangeOp::Sub if !unchecked => {
                     let lhs_cvar = lhs_cvar.latest_version(self);
                     if lhs_cvar.is_const(self) {
                         if !lhs_cvar.is_int(self) {
@@ -264,7 +400,7 @@ pub trait BinOp: AnalyzerLike<Expr = Expression> + Sized {
                                     Some(std::cmp::Ordering::Less)
                                         | Some(std::cmp::Ordering::Equal)
                                 ) {
-                                    ctx.kill(self, loc);
+                                    ctx.kill(self, loc, KilledKind::Panic);
                                     return ExprRet::CtxKilled;
                                 }
                             }
@@ -279,19 +415,11 @@ pub trait BinOp: AnalyzerLike<Expr = Expression> + Sized {
                         tmp_lhs.set_range_min(self, min);
 
                         let tmp_var = ContextVar {
-                            loc: Some(loc),
-                            name: format!(
-                                "tmp{}({} >= {})",
-                                ctx.new_tmp(self),
-                                tmp_lhs.name(self),
-                                new_rhs.name(self),
-                            ),
                             display_name: format!(
                                 "({} >= {})",
                                 tmp_lhs.display_name(self),
                                 new_rhs.display_name(self),
                             ),
-                            storage: None,
                             is_tmp: true,
                             tmp_of: Some(TmpConstruction::new(
                                 tmp_lhs,
@@ -299,53 +427,70 @@ pub trait BinOp: AnalyzerLike<Expr = Expression> + Sized {
                                 Some(new_rhs),
                             )),
                             is_symbolic: true,
-                            ty: VarType::BuiltIn(
-                                BuiltInNode::from(self.builtin_or_add(Builtin::Bool)),
-                                SolcRange::from(Concrete::Bool(true)),
-                            ),
+                            ..ContextVar::new(
+                                loc,
+                                format!(
+                                    "tmp{}({} >= {})",
+                                    ctx.new_tmp(self),
+                                    tmp_lhs.name(self),
+                                    new_rhs.name(self),
+                                ),
+                                VarType::BuiltIn(
+                                    BuiltInNode::from(self.builtin_or_add(Builtin::Bool)),
+                                    SolcRange::from(Concrete::Bool(true)),
+                                ),
+                            )
                         };
 
                         let cvar = ContextVarNode::from(self.add_node(Node::ContextVar(tmp_var)));
                         ctx.add_ctx_dep(cvar, self);
                     }
                 }
-                RangeOp::Add => {
+                RangeOp::Add if !unchecked => {
                     let lhs_cvar = lhs_cvar.latest_version(self);
                     if lhs_cvar.is_symbolic(self) {
                         let tmp_lhs = self.advance_var_in_ctx(lhs_cvar, loc, ctx);
 
-                        // the new max is min(lhs.max, (2**256 - rhs.min))
+                        // overflow happens at the width of the result, i.e. 2**8 - 1 for a uint8
+                        let (bits, ty_max) = match new_lhs.ty(self) {
+                            VarType::BuiltIn(bn, _) => {
+                                let builtin = bn.underlying(self).clone();
+                                let ty_max = SolcRange::try_from_builtin(&builtin)
+                                    .and_then(|range| range.range_max().maybe_concrete());
+                                (builtin.num_size(), ty_max.map(|max| max.val))
+                            }
+                            _ => (None, None),
+                        };
+                        let bits = bits.unwrap_or(256);
+                        let ty_max = ty_max.unwrap_or_else(|| Concrete::from(U256::MAX));
+
+                        // the new max is min(lhs.max, (2**bits - 1 - rhs.min))
                         let max = Elem::min(
                             tmp_lhs.range_max(self).expect("No range max?"),
-                            Elem::from(Concrete::from(U256::MAX))
+                            Elem::from(ty_max.clone())
                                 - Elem::Dynamic(Dynamic::new(rhs_cvar.into(), loc)),
                         );
 
                         tmp_lhs.set_range_max(self, max);
 
-                        let max_node = self.add_node(Node::Concrete(Concrete::from(U256::MAX)));
+                        let max_node = self.concrete_or_add(ty_max);
                         let max_node = self.add_node(Node::ContextVar(
-                            ContextVar::new_from_concrete(Loc::Implicit, max_node.into(), self),
+                            ContextVar::new_from_concrete(loc, max_node.into(), self),
                         ));
 
-                        let (_, tmp_rhs) = self
-                            .op(loc, max_node.into(), new_rhs, ctx, RangeOp::Sub, false)
-                            .expect_single();
+                        // the rhs alone exceeds the result type, so the sum always overflows
+                        let ExprRet::Single((_, tmp_rhs)) =
+                            self.op(loc, max_node.into(), new_rhs, ctx, RangeOp::Sub, false)
+                        else {
+                            return ExprRet::CtxKilled;
+                        };
 
                         let tmp_var = ContextVar {
-                            loc: Some(loc),
-                            name: format!(
-                                "tmp{}({} <= 2**256 - 1 - {})",
-                                ctx.new_tmp(self),
-                                tmp_lhs.name(self),
-                                new_rhs.name(self),
-                            ),
                             display_name: format!(
-                                "({} <= 2**256 - 1 - {})",
+                                "({} <= 2**{bits} - 1 - {})",
                                 tmp_lhs.display_name(self),
                                 new_rhs.display_name(self),
                             ),
-                            storage: None,
                             is_tmp: true,
                             tmp_of: Some(TmpConstruction::new(
                                 tmp_lhs,
@@ -353,17 +498,26 @@ pub trait BinOp: AnalyzerLike<Expr = Expression> + Sized {
                                 Some(tmp_rhs.into()),
                             )),
                             is_symbolic: true,
-                            ty: VarType::BuiltIn(
-                                BuiltInNode::from(self.builtin_or_add(Builtin::Bool)),
-                                SolcRange::from(Concrete::Bool(true)),
-                            ),
+                            ..ContextVar::new(
+                                loc,
+                                format!(
+                                    "tmp{}({} <= 2**{bits} - 1 - {})",
+                                    ctx.new_tmp(self),
+                                    tmp_lhs.name(self),
+                                    new_rhs.name(self),
+                                ),
+                                VarType::BuiltIn(
+                                    BuiltInNode::from(self.builtin_or_add(Builtin::Bool)),
+                                    SolcRange::from(Concrete::Bool(true)),
+                                ),
+                            )
                         };
 
                         let cvar = ContextVarNode::from(self.add_node(Node::ContextVar(tmp_var)));
                         ctx.add_ctx_dep(cvar, self);
                     }
                 }
-                RangeOp::Mul => {
+                RangeOp::Mul if !unchecked => {
                     let lhs_cvar = lhs_cvar.latest_version(self);
                     if lhs_cvar.is_symbolic(self) {
                         let tmp_lhs = self.advance_var_in_ctx(lhs_cvar, loc, ctx);
@@ -380,29 +534,23 @@ pub trait BinOp: AnalyzerLike<Expr = Expression> + Sized {
 
                         tmp_lhs.set_range_max(self, max);
 
-                        let max_node = self.add_node(Node::Concrete(Concrete::from(U256::MAX)));
+                        let max_node = self.concrete_or_add(Concrete::from(U256::MAX));
                         let max_node = self.add_node(Node::ContextVar(
-                            ContextVar::new_from_concrete(Loc::Implicit, max_node.into(), self),
+                            ContextVar::new_from_concrete(loc, max_node.into(), self),
                         ));
 
-                        let (_, tmp_rhs) = self
-                            .op(loc, max_node.into(), new_rhs, ctx, RangeOp::Div, true)
-                            .expect_single();
+                        let ExprRet::Single((_, tmp_rhs)) =
+                            self.op(loc, max_node.into(), new_rhs, ctx, RangeOp::Div, true)
+                        else {
+                            return ExprRet::CtxKilled;
+                        };
 
                         let tmp_var = ContextVar {
-                            loc: Some(loc),
-                            name: format!(
-                                "tmp{}({} <= (2**256 - 1) / {})",
-                                ctx.new_tmp(self),
-                                tmp_lhs.name(self),
-                                new_rhs.name(self),
-                            ),
                             display_name: format!(
                                 "({} <= (2**256 - 1) / {})",
                                 tmp_lhs.display_name(self),
                                 new_rhs.display_name(self),
                             ),
-                            storage: None,
                             is_tmp: true,
                             tmp_of: Some(TmpConstruction::new(
                                 tmp_lhs,
@@ -410,10 +558,19 @@ pub trait BinOp: AnalyzerLike<Expr = Expression> + Sized {
                                 Some(tmp_rhs.into()),
                             )),
                             is_symbolic: true,
-                            ty: VarType::BuiltIn(
-                                BuiltInNode::from(self.builtin_or_add(Builtin::Bool)),
-                                SolcRange::from(Concrete::Bool(true)),
-                            ),
+                            ..ContextVar::new(
+                                loc,
+                                format!(
+                                    "tmp{}({} <= (2**256 - 1) / {})",
+                                    ctx.new_tmp(self),
+                                    tmp_lhs.name(self),
+                                    new_rhs.name(self),
+                                ),
+                                VarType::BuiltIn(
+                                    BuiltInNode::from(self.builtin_or_add(Builtin::Bool)),
+                                    SolcRange::from(Concrete::Bool(true)),
+                                ),
+                            )
                         };
 
                         let cvar = ContextVarNode::from(self.add_node(Node::ContextVar(tmp_var)));
@@ -429,7 +586,7 @@ pub trait BinOp: AnalyzerLike<Expr = Expression> + Sized {
                                 .range_ord(&Elem::from(Concrete::from(U256::zero()))),
                             Some(std::cmp::Ordering::Less)
                         ) {
-                            ctx.kill(self, loc);
+                            ctx.kill(self, loc, KilledKind::Panic);
                             return ExprRet::CtxKilled;
                         }
                     } else if new_rhs.is_symbolic(self) {
@@ -442,16 +599,13 @@ pub trait BinOp: AnalyzerLike<Expr = Expression> + Sized {
 
                         tmp_rhs.set_range_min(self, min);
 
-                        let zero_node = self.add_node(Node::Concrete(Concrete::from(U256::zero())));
+                        let zero_node = self.concrete_or_add(Concrete::from(U256::zero()));
                         let zero_node = self.add_node(Node::ContextVar(
-                            ContextVar::new_from_concrete(Loc::Implicit, zero_node.into(), self),
+                            ContextVar::new_from_concrete(loc, zero_node.into(), self),
                         ));
 
                         let tmp_var = ContextVar {
-                            loc: Some(loc),
-                            name: format!("tmp{}({} >= 0)", ctx.new_tmp(self), tmp_rhs.name(self),),
                             display_name: format!("({} >= 0)", tmp_rhs.display_name(self),),
-                            storage: None,
                             is_tmp: true,
                             tmp_of: Some(TmpConstruction::new(
                                 tmp_rhs,
@@ -459,10 +613,14 @@ pub trait BinOp: AnalyzerLike<Expr = Expression> + Sized {
                                 Some(zero_node.into()),
                             )),
                             is_symbolic: true,
-                            ty: VarType::BuiltIn(
-                                BuiltInNode::from(self.builtin_or_add(Builtin::Bool)),
-                                SolcRange::from(Concrete::Bool(true)),
-                            ),
+                            ..ContextVar::new(
+                                loc,
+                                format!("tmp{}({} >= 0)", ctx.new_tmp(self), tmp_rhs.name(self),),
+                                VarType::BuiltIn(
+                                    BuiltInNode::from(self.builtin_or_add(Builtin::Bool)),
+                                    SolcRange::from(Concrete::Bool(true)),
+                                ),
+                            )
                         };
 
                         let cvar = ContextVarNode::from(self.add_node(Node::ContextVar(tmp_var)));
@@ -482,8 +640,20 @@ pub trait BinOp: AnalyzerLike<Expr = Expression> + Sized {
                 .expect("Neither lhs nor rhs had a usable range")
         };
 
-        let func = SolcRange::dyn_fn_from_op(op);
-        let new_range = func(lhs_range, new_rhs, loc);
+        // unchecked arithmetic wraps and bitwise ops spread bits, both of which depend on the
+        // entire range of the lhs
+        let whole_lhs = if lhs_cvar.range(self).is_none() {
+            None
+        } else if unchecked {
+            SolcRange::wrapping_dyn(lhs_cvar, op, new_rhs, loc)
+                .or_else(|| SolcRange::bitwise_dyn(lhs_cvar, op, new_rhs, loc))
+        } else {
+            SolcRange::bitwise_dyn(lhs_cvar, op, new_rhs, loc)
+        };
+        let new_range = whole_lhs.unwrap_or_else(|| {
+            let func = SolcRange::dyn_fn_from_op(op);
+            func(lhs_range, new_rhs, loc)
+        });
         new_lhs.set_range_min(self, new_range.range_min());
         new_lhs.set_range_max(self, new_range.range_max());
 
@@ -512,4 +682,194 @@ pub trait BinOp: AnalyzerLike<Expr = Expression> + Sized {
         }
         ExprRet::Single((ctx, new_lhs.into()))
     }
+
+    /// Whether zero is in the range of `var` and not excluded from it
+    fn may_be_zero(&self, var: ContextVarNode) -> bool {
+        let (Some(min), Some(max)) = (var.evaled_range_min(self), var.evaled_range_max(self))
+        else {
+            return false;
+        };
+        let zero = match min.maybe_concrete().map(|min| min.val) {
+            Some(Concrete::Int(size, _)) => Elem::from(Concrete::Int(size, I256::zero())),
+            _ => Elem::from(Concrete::from(U256::zero())),
+        };
+        let excluded = var.range(self).is_some_and(|range| {
+            range
+                .range_exclusions()
+                .iter()
+                .any(|excl| excl.minimize(self).range_eq(&zero))
+        });
+        !excluded
+            && !matches!(min.range_ord(&zero), Some(Ordering::Greater))
+            && !matches!(max.range_ord(&zero), Some(Ordering::Less))
+    }
+
+    /// The type of `lhs op rhs`. Arithmetic between integers of the same signedness is done in
+    /// the wider of the two types, shifts and exponentiation keep the type of the left hand side
+    fn result_ty(
+        &self,
+        lhs_cvar: ContextVarNode,
+        rhs_cvar: ContextVarNode,
+        op: RangeOp,
+    ) -> VarType {
+        let lhs_ty = lhs_cvar.underlying(self).ty.clone();
+        if matches!(op, RangeOp::Shl | RangeOp::Shr | RangeOp::Exp) {
+            return lhs_ty;
+        }
+        match (&lhs_ty, rhs_cvar.ty(self)) {
+            (VarType::BuiltIn(lhs_bn, range), VarType::BuiltIn(rhs_bn, _))
+                if lhs_bn != rhs_bn
+                    && lhs_bn.num_size(self).is_some()
+                    && lhs_bn.implicitly_castable_to(rhs_bn, self) =>
+            {
+                VarType::BuiltIn(*rhs_bn, range.clone())
+            }
+            _ => lhs_ty,
+        }
+    }
+
+    /// Records an addition, subtraction or multiplication whose result may not fit in the type of
+    /// `result`, given the current ranges of both sides
+    fn record_overflow(
+        &mut self,
+        loc: Loc,
+        lhs_cvar: ContextVarNode,
+        rhs_cvar: ContextVarNode,
+        result: ContextVarNode,
+        ctx: ContextNode,
+        op: RangeOp,
+    ) {
+        if !matches!(op, RangeOp::Add | RangeOp::Sub | RangeOp::Mul) {
+            return;
+        }
+        let VarType::BuiltIn(bn, _) = result.ty(self) else {
+            return;
+        };
+        let Some(ty_range) = SolcRange::try_from_builtin(bn.underlying(self)) else {
+            return;
+        };
+        let bounds = |range: Option<Elem<Concrete>>| Some(range?.maybe_concrete()?.val);
+        let (Some(ty_min), Some(ty_max)) = (
+            bounds(Some(ty_range.range_min())),
+            bounds(Some(ty_range.range_max())),
+        ) else {
+            return;
+        };
+        let (Some(lmin), Some(lmax), Some(rmin), Some(rmax)) = (
+            bounds(lhs_cvar.evaled_range_min(self)),
+            bounds(lhs_cvar.evaled_range_max(self)),
+            bounds(rhs_cvar.evaled_range_min(self)),
+            bounds(rhs_cvar.evaled_range_max(self)),
+        ) else {
+            return;
+        };
+
+        // the operations are monotonic in each argument, so the extremes are at the corners
+        let corners = [
+            (&lmin, &rmin),
+            (&lmin, &rmax),
+            (&lmax, &rmin),
+            (&lmax, &rmax),
+        ];
+        let mut overflow = None;
+        let mut underflow = None;
+        corners.into_iter().for_each(|(lhs, rhs)| {
+            let found = match corner_fits(op, lhs, rhs, &ty_min, &ty_max) {
+                Some(Ordering::Greater) => &mut overflow,
+                Some(Ordering::Less) => &mut underflow,
+                _ => return,
+            };
+            found.get_or_insert_with(|| (lhs.clone(), rhs.clone()));
+        });
+
+        let unchecked = ctx.wraps(self);
+        let tainted =
+            self.security_mode() && (lhs_cvar.is_tainted(self) || rhs_cvar.is_tainted(self));
+        [(overflow, false), (underflow, true)]
+            .into_iter()
+            .filter_map(|(witness, underflow)| Some((witness?, underflow)))
+            .for_each(|(witness, underflow)| {
+                ctx.add_overflow(
+                    Overflow {
+                        loc,
+                        op,
+                        underflow,
+                        unchecked,
+                        witness,
+                        tainted,
+                    },
+                    self,
+                )
+            });
+    }
+}
+
+/// Where `lhs op rhs` falls relative to the bounds of a type: `Greater` if it is above the
+/// maximum, `Less` if below the minimum and `Equal` if it fits. `None` if it can't be computed
+fn corner_fits(
+    op: RangeOp,
+    lhs: &Concrete,
+    rhs: &Concrete,
+    ty_min: &Concrete,
+    ty_max: &Concrete,
+) -> Option<Ordering> {
+    match (ty_min, ty_max) {
+        (Concrete::Uint(..), Concrete::Uint(_, max)) => {
+            let (lhs, rhs) = (lhs.into_u256()?, rhs.into_u256()?);
+            let (val, wrapped) = match op {
+                RangeOp::Add => lhs.overflowing_add(rhs),
+                RangeOp::Sub => lhs.overflowing_sub(rhs),
+                RangeOp::Mul => lhs.overflowing_mul(rhs),
+                _ => return None,
+            };
+            if wrapped && op == RangeOp::Sub {
+                Some(Ordering::Less)
+            } else if wrapped || val > *max {
+                Some(Ordering::Greater)
+            } else {
+                Some(Ordering::Equal)
+            }
+        }
+        (Concrete::Int(_, min), Concrete::Int(_, max)) => {
+            let as_int = |val: &Concrete| match val {
+                Concrete::Int(_, val) => Some(*val),
+                Concrete::Uint(_, val) => I256::try_from(*val).ok(),
+                _ => None,
+            };
+            let (lhs, rhs) = (as_int(lhs)?, as_int(rhs)?);
+            let (val, wrapped) = match op {
+                RangeOp::Add => lhs.overflowing_add(rhs),
+                RangeOp::Sub => lhs.overflowing_sub(rhs),
+                RangeOp::Mul => lhs.overflowing_mul(rhs),
+                _ => return None,
+            };
+            if wrapped {
+                // beyond 256 bits, the sign of the true result follows from the operands
+                let positive = match op {
+                    RangeOp::Mul => lhs.is_negative() == rhs.is_negative(),
+                    _ => !lhs.is_negative(),
+                };
+                if positive {
+                    Some(Ordering::Greater)
+                } else {
+                    Some(Ordering::Less)
+                }
+            } else if val > *max {
+                Some(Ordering::Greater)
+            } else if val < *min {
+                Some(Ordering::Less)
+            } else {
+                Some(Ordering::Equal)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The context a single path of a fork ended up in
+fn world_ctx(world: &ExprRet) -> Option<ContextNode> {
+    match world {
+        ExprRet::Single((ctx, _)) | ExprRet::SingleLiteral((ctx, _)) => Some(*ctx),
+        _ => None,
+    }
 }
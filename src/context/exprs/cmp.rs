@@ -1,5 +1,4 @@
 use crate::{ContextBuilder, ExprRet};
-use shared::range::elem_ty::Dynamic;
 use shared::{
     analyzer::AnalyzerLike,
     context::*,
@@ -30,17 +29,18 @@ pub trait Cmp: AnalyzerLike<Expr = Expression> + Sized {
                 let range = self.not_eval(ctx, loc, lhs_cvar);
 
                 let out_var = ContextVar {
-                    loc: Some(loc),
-                    name: format!("tmp{}(!{})", lhs_cvar.name(self), ctx.new_tmp(self)),
                     display_name: format!("!{}", lhs_cvar.display_name(self),),
-                    storage: None,
                     is_tmp: true,
                     tmp_of: Some(TmpConstruction::new(lhs_cvar, RangeOp::Not, None)),
                     is_symbolic: lhs_cvar.is_symbolic(self),
-                    ty: VarType::BuiltIn(
-                        BuiltInNode::from(self.builtin_or_add(Builtin::Bool)),
-                        Some(range),
-                    ),
+                    ..ContextVar::new(
+                        loc,
+                        format!("tmp{}(!{})", lhs_cvar.name(self), ctx.new_tmp(self)),
+                        VarType::BuiltIn(
+                            BuiltInNode::from(self.builtin_or_add(Builtin::Bool)),
+                            Some(range),
+                        ),
+                    )
                 };
 
                 ExprRet::Single((ctx, self.add_node(Node::ContextVar(out_var))))
@@ -87,48 +87,34 @@ pub trait Cmp: AnalyzerLike<Expr = Expression> + Sized {
             (ExprRet::Single((ctx, lhs)), ExprRet::Single((_rhs_ctx, rhs))) => {
                 let lhs_cvar = ContextVarNode::from(*lhs);
                 let rhs_cvar = ContextVarNode::from(*rhs);
-                let range = {
-                    let elem = Elem::Expr(RangeExpr {
-                        lhs: Box::new(Elem::Dynamic(Dynamic::new(lhs_cvar.into(), loc))),
-                        op,
-                        rhs: Box::new(Elem::Dynamic(Dynamic::new(rhs_cvar.into(), loc))),
-                    });
-
-                    let exclusions = lhs_cvar
-                        .range(self)
-                        .expect("No lhs rnage")
-                        .range_exclusions();
-                    SolcRange {
-                        min: elem.clone(),
-                        max: elem,
-                        exclusions,
-                    }
-                };
+                // collapses to `true` or `false` when the operand ranges decide the comparison
+                let range = self.range_eval(*ctx, lhs_cvar, rhs_cvar, op);
 
                 let out_var = ContextVar {
-                    loc: Some(loc),
-                    name: format!(
-                        "tmp{}({} {} {})",
-                        lhs_cvar.name(self),
-                        op.to_string(),
-                        rhs_cvar.name(self),
-                        ctx.new_tmp(self)
-                    ),
                     display_name: format!(
                         "{} {} {}",
                         lhs_cvar.display_name(self),
                         op.to_string(),
                         rhs_cvar.display_name(self),
                     ),
-                    storage: None,
                     is_tmp: true,
+                    tmp_of: Some(TmpConstruction::new(lhs_cvar, op, Some(rhs_cvar))),
                     is_symbolic: ContextVarNode::from(*lhs).is_symbolic(self)
                         || ContextVarNode::from(*rhs).is_symbolic(self),
-                    tmp_of: Some(TmpConstruction::new(lhs_cvar, op, Some(rhs_cvar))),
-                    ty: VarType::BuiltIn(
-                        BuiltInNode::from(self.builtin_or_add(Builtin::Bool)),
-                        Some(range),
-                    ),
+                    ..ContextVar::new(
+                        loc,
+                        format!(
+                            "tmp{}({} {} {})",
+                            lhs_cvar.name(self),
+                            op.to_string(),
+                            rhs_cvar.name(self),
+                            ctx.new_tmp(self)
+                        ),
+                        VarType::BuiltIn(
+                            BuiltInNode::from(self.builtin_or_add(Builtin::Bool)),
+                            Some(range),
+                        ),
+                    )
                 };
 
                 ExprRet::Single((*ctx, self.add_node(Node::ContextVar(out_var))))
@@ -350,6 +336,14 @@ pub trait Cmp: AnalyzerLike<Expr = Expression> + Sized {
                         ) {
                             return true.into();
                         }
+
+                        // ranges that don't overlap can never be equal
+                        if let Some(Ordering::Less) = lhs_max.range_ord(&rhs_min) {
+                            return false.into();
+                        }
+                        if let Some(Ordering::Greater) = lhs_min.range_ord(&rhs_max) {
+                            return false.into();
+                        }
                     }
                     RangeOp::Neq => {
                         // if all elems are equal we know its true
@@ -372,6 +366,45 @@ pub trait Cmp: AnalyzerLike<Expr = Expression> + Sized {
                         ) {
                             return false.into();
                         }
+
+                        // ranges that don't overlap are always unequal
+                        if let Some(Ordering::Less) = lhs_max.range_ord(&rhs_min) {
+                            return true.into();
+                        }
+                        if let Some(Ordering::Greater) = lhs_min.range_ord(&rhs_max) {
+                            return true.into();
+                        }
+                    }
+                    RangeOp::And | RangeOp::Or => {
+                        let bool_of = |elem: Elem<Concrete>| match elem.maybe_concrete()?.val {
+                            Concrete::Bool(b) => Some(b),
+                            _ => None,
+                        };
+                        let lhs = (
+                            bool_of(lhs_range.evaled_range_min(self)),
+                            bool_of(lhs_range.evaled_range_max(self)),
+                        );
+                        let rhs = (
+                            bool_of(rhs_range.evaled_range_min(self)),
+                            bool_of(rhs_range.evaled_range_max(self)),
+                        );
+                        // a side whose min is true is always true, one whose max is false never is
+                        let (always, never) = match op {
+                            RangeOp::And => (
+                                lhs.0 == Some(true) && rhs.0 == Some(true),
+                                lhs.1 == Some(false) || rhs.1 == Some(false),
+                            ),
+                            _ => (
+                                lhs.0 == Some(true) || rhs.0 == Some(true),
+                                lhs.1 == Some(false) && rhs.1 == Some(false),
+                            ),
+                        };
+                        if always {
+                            return true.into();
+                        }
+                        if never {
+                            return false.into();
+                        }
                     }
                     e => unreachable!("Cmp with strange op: {:?}", e),
                 }
@@ -35,11 +35,25 @@ pub trait CondOp: AnalyzerLike<Expr = Expression> + Require + Sized {
             Edge::Context(ContextEdge::Subcontext),
         );
 
+        // a side whose condition can't hold is killed, and its statement is skipped
         self.true_fork_if_cvar(true_stmt.loc(), if_expr.clone(), true_subctx);
+        let branch = Branch {
+            loc: true_stmt.loc(),
+            reachable: !true_subctx.is_ended(self),
+        };
+        ctx.add_branch(branch, self);
         self.parse_ctx_statement(true_stmt, false, Some(true_subctx));
 
+        // without an else, the false side of the fork is the code that follows the if statement,
+        // which still only runs when the condition was false
+        let false_loc = false_stmt.as_ref().map(|stmt| stmt.loc()).unwrap_or(loc);
+        self.false_fork_if_cvar(false_loc, if_expr.clone(), false_subctx);
         if let Some(false_stmt) = false_stmt {
-            self.false_fork_if_cvar(false_stmt.loc(), if_expr.clone(), false_subctx);
+            let branch = Branch {
+                loc: false_stmt.loc(),
+                reachable: !false_subctx.is_ended(self),
+            };
+            ctx.add_branch(branch, self);
             self.parse_ctx_statement(false_stmt, false, Some(false_subctx));
         }
     }
@@ -75,58 +89,22 @@ pub trait CondOp: AnalyzerLike<Expr = Expression> + Require + Sized {
             Edge::Context(ContextEdge::Subcontext),
         );
 
-        let true_loc = true_expr.loc();
+        // each side is evaluated with the bounds implied by the condition
+        self.true_fork_if_cvar(true_expr.loc(), if_expr.clone(), true_subctx);
         let true_cvars = self.parse_ctx_expr(true_expr, true_subctx);
-        self.match_true(true_loc, &true_cvars, if_expr);
 
-        let false_loc = false_expr.loc();
+        self.false_fork_if_cvar(false_expr.loc(), if_expr.clone(), false_subctx);
         let false_cvars = self.parse_ctx_expr(false_expr, false_subctx);
-        self.match_false(false_loc, &false_cvars, if_expr);
 
         ExprRet::Fork(Box::new(true_cvars), Box::new(false_cvars))
     }
 
-    fn match_true(&mut self, loc: Loc, true_cvars: &ExprRet, if_expr: &Expression) {
-        match true_cvars {
-            ExprRet::CtxKilled => {}
-            ExprRet::Single((fork_ctx, _true_cvar))
-            | ExprRet::SingleLiteral((fork_ctx, _true_cvar)) => {
-                self.true_fork_if_cvar(loc, if_expr.clone(), *fork_ctx);
-            }
-            ExprRet::Multi(ref true_paths) => true_paths.iter().take(1).for_each(|expr_ret| {
-                let (fork_ctx, _) = expr_ret.expect_single();
-                self.true_fork_if_cvar(loc, if_expr.clone(), fork_ctx);
-            }),
-            ExprRet::Fork(true_paths, other_true_paths) => {
-                self.match_true(loc, true_paths, if_expr);
-                self.match_true(loc, other_true_paths, if_expr);
-            }
-        }
-    }
-
-    fn match_false(&mut self, loc: Loc, false_cvars: &ExprRet, if_expr: &Expression) {
-        match false_cvars {
-            ExprRet::CtxKilled => {}
-            ExprRet::Single((fork_ctx, _false_cvar))
-            | ExprRet::SingleLiteral((fork_ctx, _false_cvar)) => {
-                self.false_fork_if_cvar(loc, if_expr.clone(), *fork_ctx);
-            }
-            ExprRet::Multi(ref false_paths) => false_paths.iter().take(1).for_each(|expr_ret| {
-                let (fork_ctx, _) = expr_ret.expect_single();
-                self.false_fork_if_cvar(loc, if_expr.clone(), fork_ctx);
-            }),
-            ExprRet::Fork(false_paths, other_false_paths) => {
-                self.match_false(loc, false_paths, if_expr);
-                self.match_false(loc, other_false_paths, if_expr);
-            }
-        }
-    }
-
     /// Creates the true_fork cvar (updates bounds assuming its true)
     fn true_fork_if_cvar(&mut self, loc: Loc, if_expr: Expression, true_fork_ctx: ContextNode) {
         let if_expr = match if_expr {
             Expression::Equal(_loc, lhs, rhs) => Expression::Equal(loc, lhs, rhs),
             Expression::And(_loc, lhs, rhs) => Expression::And(loc, lhs, rhs),
+            Expression::Or(_loc, lhs, rhs) => Expression::Or(loc, lhs, rhs),
             Expression::NotEqual(_loc, lhs, rhs) => Expression::NotEqual(loc, lhs, rhs),
             Expression::Less(_loc, lhs, rhs) => Expression::Less(loc, lhs, rhs),
             Expression::More(_loc, lhs, rhs) => Expression::More(loc, lhs, rhs),
@@ -137,6 +115,7 @@ pub trait CondOp: AnalyzerLike<Expr = Expression> + Require + Sized {
                 c.loc = loc;
                 Expression::Variable(c)
             }
+            Expression::Not(_loc, inner) => Expression::Not(loc, inner),
             e => todo!("Wasnt comparator: {:?}", e),
         };
         // println!("true fork if: {if_expr:?} {true_fork_ctx:?}");
@@ -145,17 +124,9 @@ pub trait CondOp: AnalyzerLike<Expr = Expression> + Require + Sized {
 
     /// Creates the false_fork cvar (inverts the expression and sets the bounds assuming its false)
     fn false_fork_if_cvar(&mut self, loc: Loc, if_expr: Expression, false_fork_ctx: ContextNode) {
-        let inv_if_expr = match if_expr {
-            Expression::Equal(_loc, lhs, rhs) => Expression::NotEqual(loc, lhs, rhs),
-            Expression::NotEqual(_loc, lhs, rhs) => Expression::Equal(loc, lhs, rhs),
-            Expression::Less(_loc, lhs, rhs) => Expression::MoreEqual(loc, lhs, rhs),
-            Expression::More(_loc, lhs, rhs) => Expression::LessEqual(loc, lhs, rhs),
-            Expression::MoreEqual(_loc, lhs, rhs) => Expression::Less(loc, lhs, rhs),
-            Expression::LessEqual(_loc, lhs, rhs) => Expression::More(loc, lhs, rhs),
-            Expression::Variable(ref _ident) => Expression::Not(loc, Box::new(if_expr)),
-            e => todo!("Wasnt comparator: {:?}", e),
-        };
-        // println!("inverse if expr: {inv_if_expr:?}");
-        self.handle_require(&[inv_if_expr], false_fork_ctx)
+        match if_expr {
+            Expression::Not(_loc, inner) => self.true_fork_if_cvar(loc, *inner, false_fork_ctx),
+            if_expr => self.handle_require(&[Self::negated(loc, if_expr)], false_fork_ctx),
+        }
     }
 }
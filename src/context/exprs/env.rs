@@ -1,5 +1,6 @@
 use crate::context::func::FuncCaller;
 use crate::{context::ContextNode, AnalyzerLike, ExprRet};
+use shared::context::ContextVarNode;
 use solang_parser::pt::Expression;
 
 use solang_parser::pt::Identifier;
@@ -8,7 +9,8 @@ impl<T> Env for T where T: AnalyzerLike<Expr = Expression> + Sized {}
 pub trait Env: AnalyzerLike<Expr = Expression> + Sized {
     fn env_variable(&mut self, ident: &Identifier, ctx: ContextNode) -> Option<ExprRet> {
         match &*ident.name {
-            "msg" => Some(ExprRet::Single((ctx, self.msg().into()))),
+            // `tx.origin` and `tx.gasprice` are kept with the rest of the message data
+            "msg" | "tx" => Some(ExprRet::Single((ctx, self.msg().into()))),
             "block" => Some(ExprRet::Single((ctx, self.block().into()))),
             "abi" => todo!("abi"),
             "_" => {
@@ -16,6 +18,18 @@ pub trait Env: AnalyzerLike<Expr = Expression> + Sized {
                 if let Some(mod_state) = &ctx.underlying(self).modifier_state.clone() {
                     // println!("going back to function execution from modifier: {}", mod_state.num);
                     let res = self.resume_from_modifier(ctx, mod_state.clone());
+                    if let ExprRet::Multi(rets) = &res {
+                        let returned = rets
+                            .iter()
+                            .filter_map(|ret| match ret {
+                                ExprRet::Single((_, idx)) => Some(ContextVarNode::from(*idx)),
+                                _ => None,
+                            })
+                            .collect();
+                        if let Some(state) = &mut ctx.underlying_mut(self).modifier_state {
+                            state.returned = returned;
+                        }
+                    }
                     // println!("back in modifier: {}", mod_state.num);
 
                     // TODO: inherit the input changes as well
@@ -7,12 +7,105 @@ use shared::{
     nodes::{Concrete, ConcreteNode},
     Edge, Node,
 };
-use solang_parser::pt::HexLiteral;
+use solang_parser::pt::{HexLiteral, Unit};
 
 use ethers_core::types::{Address, U256};
 use solang_parser::pt::Loc;
 use std::str::FromStr;
 
+/// Evaluates a decimal number literal, i.e. `1_000` or `5e18`, saturating at `U256::MAX`
+/// for values that do not fit
+pub fn number_literal_val(integer: &str, exponent: &str) -> U256 {
+    checked_number_literal_val(integer, exponent).unwrap_or(U256::MAX)
+}
+
+/// Evaluates a decimal number literal, or `None` if it does not fit in a `U256`
+pub fn checked_number_literal_val(integer: &str, exponent: &str) -> Option<U256> {
+    let parse = |digits: &str| U256::from_dec_str(&digits.replace('_', "")).ok();
+    let int = parse(integer)?;
+    if exponent.is_empty() || int.is_zero() {
+        return Some(int);
+    }
+
+    if let Some(exponent) = exponent.strip_prefix('-') {
+        Some(
+            parse(exponent)
+                .and_then(|exponent| U256::from(10).checked_pow(exponent))
+                .map(|div| int / div)
+                .unwrap_or_default(),
+        )
+    } else {
+        U256::from(10)
+            .checked_pow(parse(exponent)?)
+            .and_then(|mul| int.checked_mul(mul))
+    }
+}
+
+/// Evaluates a hex number literal, i.e. `0xff_ff`, saturating at `U256::MAX` for values
+/// that do not fit
+pub fn hex_num_literal_val(integer: &str) -> U256 {
+    let digits = integer.replace('_', "");
+    let digits = digits.strip_prefix("0x").unwrap_or(&digits);
+    U256::from_str_radix(digits, 16).unwrap_or(U256::MAX)
+}
+
+/// Evaluates `-val` for a literal, along with the smallest `intN` width that holds it, or
+/// `None` if it is below `type(int256).min`
+pub fn negative_literal_val(val: U256) -> Option<(u16, I256)> {
+    let min = U256::one() << 255;
+    if val > min {
+        return None;
+    }
+    let size = (8..=256)
+        .step_by(8)
+        .find(|size| val <= U256::one() << (size - 1))
+        .unwrap_or(256);
+    // `-2**255` is its own two's complement
+    let val = if val == min {
+        I256::from_raw(val)
+    } else {
+        -I256::from_raw(val)
+    };
+    Some((size as u16, val))
+}
+
+/// How many of the base unit a literal suffix stands for, i.e. `1 ether` is `10**18` wei
+pub fn unit_multiplier(unit: &Unit) -> U256 {
+    match unit {
+        Unit::Seconds(_) | Unit::Wei(_) => U256::from(1),
+        Unit::Minutes(_) => U256::from(60),
+        Unit::Hours(_) => U256::from(60 * 60),
+        Unit::Days(_) => U256::from(24 * 60 * 60),
+        Unit::Weeks(_) => U256::from(7 * 24 * 60 * 60),
+        Unit::Gwei(_) => U256::exp10(9),
+        Unit::Ether(_) => U256::exp10(18),
+    }
+}
+
+/// Evaluates `integer.fraction e exponent * multiplier`, returning the value truncated to an
+/// integer and whether it was whole to begin with, or `None` if it does not fit in a `U256`
+pub fn rational_literal_val(
+    integer: &str,
+    fraction: &str,
+    exponent: &str,
+    multiplier: U256,
+) -> Option<(U256, bool)> {
+    let fraction = fraction.replace('_', "");
+    let (exponent, neg_exponent) = match exponent.strip_prefix('-') {
+        Some(neg_exponent) => ("", neg_exponent),
+        None => (exponent, ""),
+    };
+    let numerator = checked_number_literal_val(&format!("{integer}{fraction}"), exponent)?
+        .checked_mul(multiplier)?;
+    let denominator = U256::from(10).checked_pow(
+        number_literal_val(neg_exponent, "").saturating_add(U256::from(fraction.len())),
+    );
+    Some(match denominator {
+        Some(denominator) => (numerator / denominator, (numerator % denominator).is_zero()),
+        None => (U256::zero(), numerator.is_zero()),
+    })
+}
+
 impl<T> Literal for T where T: AnalyzerLike + Sized {}
 
 pub trait Literal: AnalyzerLike + Sized {
@@ -24,25 +117,50 @@ pub trait Literal: AnalyzerLike + Sized {
         exponent: &str,
         negative: bool,
     ) -> ExprRet {
-        let int = U256::from_dec_str(integer).unwrap();
-        let val = if !exponent.is_empty() {
-            let exp = U256::from_dec_str(exponent).unwrap();
-            int.pow(exp)
-        } else {
-            int
-        };
+        let val = number_literal_val(integer, exponent);
+        self.u256_literal(ctx, loc, val, negative)
+    }
 
-        let size: u16 = ((32 - (val.leading_zeros() / 8)) * 8) as u16;
+    /// Parses a literal with a fractional part and/or a unit, i.e. `2.5 ether`
+    #[allow(clippy::too_many_arguments)]
+    fn rational_number_literal(
+        &mut self,
+        ctx: ContextNode,
+        loc: Loc,
+        integer: &str,
+        fraction: &str,
+        exponent: &str,
+        multiplier: U256,
+        negative: bool,
+    ) -> ExprRet {
+        let (val, whole) = rational_literal_val(integer, fraction, exponent, multiplier)
+            .unwrap_or_else(|| (self.literal_overflow(loc), true));
+        if !whole {
+            self.add_warning(
+                loc,
+                "Literal is not a whole number, it was truncated to an integer".to_string(),
+            );
+        }
+        self.u256_literal(ctx, loc, val, negative)
+    }
+
+    /// Flags a literal that is too large for a `uint256`, returning the maximum it saturates to
+    fn literal_overflow(&mut self, loc: Loc) -> U256 {
+        self.add_warning(
+            loc,
+            "Literal does not fit in a uint256, it was saturated to 2**256 - 1".to_string(),
+        );
+        U256::MAX
+    }
+
+    fn u256_literal(&mut self, ctx: ContextNode, loc: Loc, val: U256, negative: bool) -> ExprRet {
         let concrete_node = if negative {
-            let val = if val == U256::from(2).pow(255.into()) {
-                // no need to set upper bit
-                I256::from_raw(val)
-            } else {
-                I256::from(-1i32) * I256::from_raw(val)
-            };
-            ConcreteNode::from(self.add_node(Node::Concrete(Concrete::Int(size, val))))
+            let (size, val) = negative_literal_val(val)
+                .unwrap_or_else(|| (256, self.negative_literal_overflow(loc)));
+            ConcreteNode::from(self.concrete_or_add(Concrete::Int(size, val)))
         } else {
-            ConcreteNode::from(self.add_node(Node::Concrete(Concrete::Uint(size, val))))
+            let size: u16 = ((32 - (val.leading_zeros() / 8)) * 8) as u16;
+            ConcreteNode::from(self.concrete_or_add(Concrete::Uint(size, val)))
         };
 
         let ccvar = Node::ContextVar(ContextVar::new_from_concrete(loc, concrete_node, self));
@@ -51,6 +169,16 @@ pub trait Literal: AnalyzerLike + Sized {
         ExprRet::SingleLiteral((ctx, node))
     }
 
+    /// Flags a negative literal that is too small for an `int256`, returning the minimum it
+    /// saturates to
+    fn negative_literal_overflow(&mut self, loc: Loc) -> I256 {
+        self.add_warning(
+            loc,
+            "Literal does not fit in an int256, it was saturated to -2**255".to_string(),
+        );
+        I256::MIN
+    }
+
     fn hex_num_literal(
         &mut self,
         ctx: ContextNode,
@@ -58,19 +186,8 @@ pub trait Literal: AnalyzerLike + Sized {
         integer: &str,
         negative: bool,
     ) -> ExprRet {
-        let val = U256::from_str_radix(integer, 16).unwrap();
-        let size: u16 = ((32 - (val.leading_zeros() / 8)) * 8) as u16;
-        let concrete_node = if negative {
-            let val = I256::from(-1i32) * I256::from_raw(val);
-            ConcreteNode::from(self.add_node(Node::Concrete(Concrete::Int(size, val))))
-        } else {
-            ConcreteNode::from(self.add_node(Node::Concrete(Concrete::Uint(size, val))))
-        };
-
-        let ccvar = Node::ContextVar(ContextVar::new_from_concrete(loc, concrete_node, self));
-        let node = self.add_node(ccvar);
-        self.add_edge(node, ctx, Edge::Context(ContextEdge::Variable));
-        ExprRet::SingleLiteral((ctx, node))
+        let val = hex_num_literal_val(integer);
+        self.u256_literal(ctx, loc, val, negative)
     }
 
     fn hex_literals(&mut self, ctx: ContextNode, hexes: &[HexLiteral]) -> ExprRet {
@@ -87,7 +204,7 @@ pub trait Literal: AnalyzerLike + Sized {
             }
 
             let concrete_node =
-                ConcreteNode::from(self.add_node(Node::Concrete(Concrete::Bytes(max + 1, h))));
+                ConcreteNode::from(self.concrete_or_add(Concrete::Bytes(max + 1, h)));
             let ccvar = Node::ContextVar(ContextVar::new_from_concrete(
                 hexes[0].loc,
                 concrete_node,
@@ -104,8 +221,7 @@ pub trait Literal: AnalyzerLike + Sized {
     fn address_literal(&mut self, ctx: ContextNode, loc: Loc, addr: &str) -> ExprRet {
         let addr = Address::from_str(addr).unwrap();
 
-        let concrete_node =
-            ConcreteNode::from(self.add_node(Node::Concrete(Concrete::Address(addr))));
+        let concrete_node = ConcreteNode::from(self.concrete_or_add(Concrete::Address(addr)));
         let ccvar = Node::ContextVar(ContextVar::new_from_concrete(loc, concrete_node, self));
         let node = self.add_node(ccvar);
         self.add_edge(node, ctx, Edge::Context(ContextEdge::Variable));
@@ -114,7 +230,7 @@ pub trait Literal: AnalyzerLike + Sized {
 
     fn string_literal(&mut self, ctx: ContextNode, loc: Loc, s: &str) -> ExprRet {
         let concrete_node =
-            ConcreteNode::from(self.add_node(Node::Concrete(Concrete::String(s.to_string()))));
+            ConcreteNode::from(self.concrete_or_add(Concrete::String(s.to_string())));
         let ccvar = Node::ContextVar(ContextVar::new_from_concrete(loc, concrete_node, self));
         let node = self.add_node(ccvar);
         self.add_edge(node, ctx, Edge::Context(ContextEdge::Variable));
@@ -122,7 +238,7 @@ pub trait Literal: AnalyzerLike + Sized {
     }
 
     fn bool_literal(&mut self, ctx: ContextNode, loc: Loc, b: bool) -> ExprRet {
-        let concrete_node = ConcreteNode::from(self.add_node(Node::Concrete(Concrete::Bool(b))));
+        let concrete_node = ConcreteNode::from(self.concrete_or_add(Concrete::Bool(b)));
         let ccvar = Node::ContextVar(ContextVar::new_from_concrete(loc, concrete_node, self));
         let node = self.add_node(ccvar);
         self.add_edge(node, ctx, Edge::Context(ContextEdge::Variable));
@@ -10,8 +10,7 @@ use shared::{
     {Edge, Node},
 };
 
-use ethers_core::types::{I256, U256};
-use petgraph::{visit::EdgeRef, Direction};
+use ethers_core::types::U256;
 use solang_parser::pt::{Expression, Identifier, Loc};
 
 impl<T> MemberAccess for T where T: AnalyzerLike<Expr = Expression> + Sized {}
@@ -27,59 +26,68 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
         if ident.name == "length" {
             return self.length(loc, member_expr, ctx);
         }
+        if let Expression::Variable(Identifier { name, .. }) = member_expr {
+            if name == "super" {
+                return self.super_access(loc, ident, ctx);
+            }
+            // `Weight.wrap(x)` and `Weight.unwrap(w)` for `type Weight is uint256`
+            if matches!(&*ident.name, "wrap" | "unwrap") {
+                let user_ty = ctx
+                    .maybe_associated_contract(self)
+                    .and_then(|contract| contract.scoped_user_ty(self, name))
+                    .or_else(|| self.user_types().get(name).copied());
+                if user_ty.is_some_and(|idx| matches!(self.node(idx), Node::Ty(_))) {
+                    return self.variable(
+                        &Identifier {
+                            loc,
+                            name: format!("ty.{}", ident.name),
+                        },
+                        ctx,
+                    );
+                }
+            }
+        }
         let (_, member_idx) = self.parse_ctx_expr(member_expr, ctx).expect_single();
+
+        // `using L for T` makes `x.f(..)` a call to `L.f(x, ..)`. Overloads are all returned,
+        // for the call to pick from by its inputs
+        if let Node::ContextVar(cvar) = self.node(member_idx) {
+            let bound = self
+                .using_fns(ctx.executing_fn(self), cvar.ty.ty_idx())
+                .into_iter()
+                .filter(|func| func.name(self).starts_with(&format!("{}(", ident.name)))
+                .collect::<Vec<_>>()
+                .into_iter()
+                .filter_map(|func| {
+                    let func_cvar = ContextVar::maybe_from_user_ty(self, loc, func.into())?;
+                    let fn_node = self.add_node(Node::ContextVar(func_cvar));
+                    self.add_edge(
+                        fn_node,
+                        member_idx,
+                        Edge::Context(ContextEdge::LibraryAccess),
+                    );
+                    Some(ExprRet::Single((ctx, fn_node)))
+                })
+                .collect::<Vec<_>>();
+            match bound.len() {
+                0 => {}
+                1 => return bound.into_iter().next().unwrap(),
+                _ => return ExprRet::Multi(bound),
+            }
+        }
+
         match self.node(member_idx) {
             Node::ContextVar(cvar) => match &cvar.ty {
-                VarType::User(TypeNode::Struct(struct_node)) => {
-                    let name = format!(
-                        "{}.{}",
-                        ContextVarNode::from(member_idx).name(self),
-                        ident.name
-                    );
-                    if let Some(attr_var) = ctx.var_by_name_or_recurse(self, &name) {
-                        return ExprRet::Single((ctx, attr_var.latest_version(self).into()));
-                    } else {
-                        let field = self
-                            .graph()
-                            .edges_directed(struct_node.0.into(), Direction::Incoming)
-                            .filter(|edge| *edge.weight() == Edge::Field)
-                            .map(|edge| FieldNode::from(edge.source()))
-                            .collect::<Vec<FieldNode>>()
-                            .iter()
-                            .filter_map(|field_node| {
-                                let field = field_node.underlying(self);
-                                if field.name.as_ref().expect("field wasnt named").name
-                                    == ident.name
-                                {
-                                    Some(field)
-                                } else {
-                                    None
-                                }
-                            })
-                            .take(1)
-                            .next()
-                            .unwrap_or_else(|| {
-                                panic!(
-                                    "No field with name {:?} in struct: {:?}",
-                                    ident.name,
-                                    struct_node.name(self)
-                                )
-                            });
-                        if let Some(field_cvar) =
-                            ContextVar::maybe_new_from_field(self, loc, cvar, field.clone())
-                        {
-                            let fc_node = self.add_node(Node::ContextVar(field_cvar));
-                            self.add_edge(
-                                fc_node,
-                                ContextVarNode::from(member_idx).first_version(self),
-                                Edge::Context(ContextEdge::AttrAccess),
-                            );
-                            self.add_edge(fc_node, ctx, Edge::Context(ContextEdge::Variable));
-                            return ExprRet::Single((ctx, fc_node));
-                        }
-                    }
+                VarType::User(TypeNode::Struct(struct_node), _) => {
+                    let field =
+                        self.struct_field(loc, *struct_node, member_idx.into(), &ident.name, ctx);
+                    return ExprRet::Single((ctx, field.into()));
                 }
-                VarType::User(TypeNode::Contract(con_node)) => {
+                VarType::User(TypeNode::Enum(enum_node), _) => {
+                    let variant = self.enum_variant(loc, *enum_node, &ident.name, ctx);
+                    return ExprRet::Single((ctx, variant.into()));
+                }
+                VarType::User(TypeNode::Contract(con_node), _) => {
                     // we can only access functions via this pattern
                     // println!(
                     //     "functions {:?}",
@@ -89,30 +97,66 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
                     //         .map(|func| func.name(self))
                     //         .collect::<Vec<_>>()
                     // );
+                    // the most derived definition wins, inherited functions are reachable too.
+                    // Overloads are all returned, for the call to pick from by its inputs
                     let funcs = con_node
-                        .funcs(self)
+                        .with_bases(self)
                         .into_iter()
-                        .filter(|func_node| {
-                            func_node
-                                .name(self)
-                                .starts_with(&format!("{}(", &ident.name))
-                        })
-                        .collect::<Vec<_>>();
+                        .map(|con| self.funcs_named(con, &ident.name))
+                        .find(|funcs| !funcs.is_empty())
+                        .unwrap_or_default();
                     if funcs.is_empty() {
                         panic!(
                             "No function with name {:?} in contract: {:?}",
                             ident.name,
                             con_node.name(self)
                         )
-                    } else if funcs.len() > 1 {
-                        panic!("Function lookup not unique")
-                    } else if let Some(func_cvar) =
-                        ContextVar::maybe_from_user_ty(self, loc, funcs[0].0.into())
-                    {
-                        let fn_node = self.add_node(Node::ContextVar(func_cvar));
-                        self.add_edge(fn_node, member_idx, Edge::Context(ContextEdge::FuncAccess));
-                        return ExprRet::Single((ctx, fn_node));
                     }
+                    let mut funcs = funcs
+                        .into_iter()
+                        .filter_map(|func| {
+                            let func_cvar = ContextVar::maybe_from_user_ty(self, loc, func.into())?;
+                            let fn_node = self.add_node(Node::ContextVar(func_cvar));
+                            self.add_edge(
+                                fn_node,
+                                member_idx,
+                                Edge::Context(ContextEdge::FuncAccess),
+                            );
+                            Some(ExprRet::Single((ctx, fn_node)))
+                        })
+                        .collect::<Vec<_>>();
+                    if funcs.len() == 1 {
+                        return funcs.swap_remove(0);
+                    } else if !funcs.is_empty() {
+                        return ExprRet::Multi(funcs);
+                    }
+                }
+                VarType::BuiltIn(bn, _)
+                    if matches!(
+                        bn.underlying(self),
+                        Builtin::Address | Builtin::AddressPayable | Builtin::Payable
+                    ) && matches!(
+                        &*ident.name,
+                        "transfer" | "send" | "call" | "delegatecall" | "staticcall"
+                    ) =>
+                {
+                    // resolves to the intrinsic, the address itself does not need to be tracked
+                    return self.variable(
+                        &Identifier {
+                            loc,
+                            name: format!("address.{}", ident.name),
+                        },
+                        ctx,
+                    );
+                }
+                VarType::BuiltIn(bn, _)
+                    if matches!(
+                        bn.underlying(self),
+                        Builtin::Address | Builtin::AddressPayable | Builtin::Payable
+                    ) && matches!(&*ident.name, "balance" | "code" | "codehash") =>
+                {
+                    let attr = self.address_member(loc, member_idx.into(), &ident.name, ctx);
+                    return ExprRet::Single((ctx, attr.into()));
                 }
                 VarType::BuiltIn(..) => {
                     // println!("member access {}", self.dot_str_no_tmps());
@@ -121,7 +165,10 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
                 e => todo!("member access: {:?}, {:?}", e, ident),
             },
             Node::Msg(_msg) => {
-                let name = format!("msg.{}", ident.name);
+                let name = match &*ident.name {
+                    "origin" | "gasprice" => format!("tx.{}", ident.name),
+                    _ => format!("msg.{}", ident.name),
+                };
                 if let Some(attr_var) = ctx.var_by_name_or_recurse(self, &name) {
                     return ExprRet::Single((ctx, attr_var.latest_version(self).into()));
                 } else {
@@ -129,10 +176,7 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
                         "data" => {
                             if let Some(d) = self.msg().underlying(self).data.clone() {
                                 let c = Concrete::from(d);
-                                (
-                                    self.add_node(Node::Concrete(c)).into(),
-                                    "msg.data".to_string(),
-                                )
+                                (self.concrete_or_add(c).into(), "msg.data".to_string())
                             } else {
                                 let b = Builtin::DynamicBytes;
                                 let node = self.builtin_or_add(b);
@@ -141,6 +185,7 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
                                 var.display_name = "msg.data".to_string();
                                 var.is_tmp = false;
                                 var.is_symbolic = true;
+                                var.is_tainted = true;
                                 let cvar = self.add_node(Node::ContextVar(var));
                                 self.add_edge(cvar, ctx, Edge::Context(ContextEdge::Variable));
                                 return ExprRet::Single((ctx, cvar));
@@ -149,10 +194,7 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
                         "sender" => {
                             if let Some(d) = self.msg().underlying(self).sender {
                                 let c = Concrete::from(d);
-                                (
-                                    self.add_node(Node::Concrete(c)).into(),
-                                    "msg.sender".to_string(),
-                                )
+                                (self.concrete_or_add(c).into(), "msg.sender".to_string())
                             } else {
                                 let node = self.builtin_or_add(Builtin::Address);
                                 let mut var = ContextVar::new_from_builtin(loc, node.into(), self);
@@ -168,10 +210,7 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
                         "sig" => {
                             if let Some(d) = self.msg().underlying(self).sig {
                                 let c = Concrete::from(d);
-                                (
-                                    self.add_node(Node::Concrete(c)).into(),
-                                    "msg.sig".to_string(),
-                                )
+                                (self.concrete_or_add(c).into(), "msg.sig".to_string())
                             } else {
                                 let node = self.builtin_or_add(Builtin::Bytes(4));
                                 let mut var = ContextVar::new_from_builtin(loc, node.into(), self);
@@ -179,6 +218,7 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
                                 var.display_name = "msg.sig".to_string();
                                 var.is_tmp = false;
                                 var.is_symbolic = true;
+                                var.is_tainted = true;
                                 let cvar = self.add_node(Node::ContextVar(var));
                                 self.add_edge(cvar, ctx, Edge::Context(ContextEdge::Variable));
                                 return ExprRet::Single((ctx, cvar));
@@ -187,10 +227,7 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
                         "value" => {
                             if let Some(d) = self.msg().underlying(self).value {
                                 let c = Concrete::from(d);
-                                (
-                                    self.add_node(Node::Concrete(c)).into(),
-                                    "msg.value".to_string(),
-                                )
+                                (self.concrete_or_add(c).into(), "msg.value".to_string())
                             } else {
                                 let node = self.builtin_or_add(Builtin::Uint(256));
                                 let mut var = ContextVar::new_from_builtin(loc, node.into(), self);
@@ -206,10 +243,7 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
                         "origin" => {
                             if let Some(d) = self.msg().underlying(self).origin {
                                 let c = Concrete::from(d);
-                                (
-                                    self.add_node(Node::Concrete(c)).into(),
-                                    "tx.origin".to_string(),
-                                )
+                                (self.concrete_or_add(c).into(), "tx.origin".to_string())
                             } else {
                                 let node = self.builtin_or_add(Builtin::Address);
                                 let mut var = ContextVar::new_from_builtin(loc, node.into(), self);
@@ -225,10 +259,7 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
                         "gasprice" => {
                             if let Some(d) = self.msg().underlying(self).gasprice {
                                 let c = Concrete::from(d);
-                                (
-                                    self.add_node(Node::Concrete(c)).into(),
-                                    "tx.gasprice".to_string(),
-                                )
+                                (self.concrete_or_add(c).into(), "tx.gasprice".to_string())
                             } else {
                                 let node = self.builtin_or_add(Builtin::Uint(64));
                                 let mut var = ContextVar::new_from_builtin(loc, node.into(), self);
@@ -244,10 +275,12 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
                         "gaslimit" => {
                             if let Some(d) = self.msg().underlying(self).gaslimit {
                                 let c = Concrete::from(d);
-                                (self.add_node(Node::Concrete(c)).into(), "".to_string())
+                                (self.concrete_or_add(c).into(), "msg.gaslimit".to_string())
                             } else {
                                 let node = self.builtin_or_add(Builtin::Uint(64));
                                 let mut var = ContextVar::new_from_builtin(loc, node.into(), self);
+                                var.name = "msg.gaslimit".to_string();
+                                var.display_name = "msg.gaslimit".to_string();
                                 var.is_tmp = false;
                                 var.is_symbolic = true;
                                 let cvar = self.add_node(Node::ContextVar(var));
@@ -269,7 +302,7 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
                 }
             }
             Node::Block(_b) => {
-                let name = format!("msg.{}", ident.name);
+                let name = format!("block.{}", ident.name);
                 if let Some(attr_var) = ctx.var_by_name_or_recurse(self, &name) {
                     return ExprRet::Single((ctx, attr_var.latest_version(self).into()));
                 } else {
@@ -277,15 +310,12 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
                         "hash" => {
                             if let Some(d) = self.block().underlying(self).hash {
                                 let c = Concrete::from(d);
-                                (
-                                    self.add_node(Node::Concrete(c)).into(),
-                                    "block.blockhash".to_string(),
-                                )
+                                (self.concrete_or_add(c).into(), "block.hash".to_string())
                             } else {
                                 let node = self.builtin_or_add(Builtin::Bytes(32));
                                 let mut var = ContextVar::new_from_builtin(loc, node.into(), self);
-                                var.name = "block.blockhash".to_string();
-                                var.display_name = "block.blockhash".to_string();
+                                var.name = "block.hash".to_string();
+                                var.display_name = "block.hash".to_string();
                                 var.is_tmp = false;
                                 var.is_symbolic = true;
                                 let cvar = self.add_node(Node::ContextVar(var));
@@ -296,10 +326,7 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
                         "basefee" => {
                             if let Some(d) = self.block().underlying(self).basefee {
                                 let c = Concrete::from(d);
-                                (
-                                    self.add_node(Node::Concrete(c)).into(),
-                                    "block.basefee".to_string(),
-                                )
+                                (self.concrete_or_add(c).into(), "block.basefee".to_string())
                             } else {
                                 let node = self.builtin_or_add(Builtin::Uint(256));
                                 let mut var = ContextVar::new_from_builtin(loc, node.into(), self);
@@ -315,10 +342,7 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
                         "chainid" => {
                             if let Some(d) = self.block().underlying(self).chainid {
                                 let c = Concrete::from(d);
-                                (
-                                    self.add_node(Node::Concrete(c)).into(),
-                                    "block.chainid".to_string(),
-                                )
+                                (self.concrete_or_add(c).into(), "block.chainid".to_string())
                             } else {
                                 let node = self.builtin_or_add(Builtin::Uint(256));
                                 let mut var = ContextVar::new_from_builtin(loc, node.into(), self);
@@ -334,10 +358,7 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
                         "coinbase" => {
                             if let Some(d) = self.block().underlying(self).coinbase {
                                 let c = Concrete::from(d);
-                                (
-                                    self.add_node(Node::Concrete(c)).into(),
-                                    "block.coinbase".to_string(),
-                                )
+                                (self.concrete_or_add(c).into(), "block.coinbase".to_string())
                             } else {
                                 let node = self.builtin_or_add(Builtin::Address);
                                 let mut var = ContextVar::new_from_builtin(loc, node.into(), self);
@@ -354,7 +375,7 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
                             if let Some(d) = self.block().underlying(self).difficulty {
                                 let c = Concrete::from(d);
                                 (
-                                    self.add_node(Node::Concrete(c)).into(),
+                                    self.concrete_or_add(c).into(),
                                     "block.difficulty".to_string(),
                                 )
                             } else {
@@ -372,10 +393,7 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
                         "gaslimit" => {
                             if let Some(d) = self.block().underlying(self).gaslimit {
                                 let c = Concrete::from(d);
-                                (
-                                    self.add_node(Node::Concrete(c)).into(),
-                                    "block.gaslimit".to_string(),
-                                )
+                                (self.concrete_or_add(c).into(), "block.gaslimit".to_string())
                             } else {
                                 let node = self.builtin_or_add(Builtin::Uint(256));
                                 let mut var = ContextVar::new_from_builtin(loc, node.into(), self);
@@ -391,10 +409,7 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
                         "number" => {
                             if let Some(d) = self.block().underlying(self).number {
                                 let c = Concrete::from(d);
-                                (
-                                    self.add_node(Node::Concrete(c)).into(),
-                                    "block.number".to_string(),
-                                )
+                                (self.concrete_or_add(c).into(), "block.number".to_string())
                             } else {
                                 let node = self.builtin_or_add(Builtin::Uint(256));
                                 let mut var = ContextVar::new_from_builtin(loc, node.into(), self);
@@ -411,7 +426,7 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
                             if let Some(d) = self.block().underlying(self).prevrandao {
                                 let c = Concrete::from(d);
                                 (
-                                    self.add_node(Node::Concrete(c)).into(),
+                                    self.concrete_or_add(c).into(),
                                     "block.prevrandao".to_string(),
                                 )
                             } else {
@@ -430,7 +445,7 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
                             if let Some(d) = self.block().underlying(self).timestamp {
                                 let c = Concrete::from(d);
                                 (
-                                    self.add_node(Node::Concrete(c)).into(),
+                                    self.concrete_or_add(c).into(),
                                     "block.timestamp".to_string(),
                                 )
                             } else {
@@ -467,83 +482,54 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
                     panic!("Unknown member access on bytes{}: {:?}", size, ident.name)
                 }
                 Builtin::Rational => panic!("Unknown member access on rational: {:?}", ident.name),
+                Builtin::Fixed(..) | Builtin::Ufixed(..) => panic!(
+                    "Unknown member access on {}: {:?}",
+                    b.as_string(self),
+                    ident.name
+                ),
+                Builtin::DynamicBytes if ident.name == "concat" => {
+                    return self.variable(
+                        &Identifier {
+                            loc,
+                            name: "bytes.concat".to_string(),
+                        },
+                        ctx,
+                    );
+                }
                 Builtin::DynamicBytes => {
                     panic!("Unknown member access on bytes[]: {:?}", ident.name)
                 }
                 Builtin::Array(_) => panic!("Unknown member access on array[]: {:?}", ident.name),
+                Builtin::SizedArray(len, _) => {
+                    panic!("Unknown member access on array[{len}]: {:?}", ident.name)
+                }
                 Builtin::Mapping(_, _) => {
                     panic!("Unknown member access on mapping: {:?}", ident.name)
                 }
                 Builtin::Func(_, _) => panic!("Unknown member access on func: {:?}", ident.name),
-                Builtin::Int(size) => {
-                    let size = *size;
-                    let max = if size == 256 {
-                        I256::MAX
-                    } else {
-                        I256::from_raw(U256::from(1u8) << U256::from(size - 1)) - 1.into()
+                Builtin::Int(_) | Builtin::Uint(_) => {
+                    let builtin = b.clone();
+                    let c = match &*ident.name {
+                        "max" => builtin.max_value(),
+                        "min" => builtin.min_value(),
+                        _ => None,
                     };
-                    match &*ident.name {
-                        "max" => {
-                            let c = Concrete::from(max);
-                            let node = self.add_node(Node::Concrete(c)).into();
-                            let mut var = ContextVar::new_from_concrete(loc, node, self);
-                            var.name = format!("int{size}.max");
-                            var.display_name = var.name.clone();
-                            var.is_tmp = true;
-                            var.is_symbolic = false;
-                            let cvar = self.add_node(Node::ContextVar(var));
-                            self.add_edge(cvar, ctx, Edge::Context(ContextEdge::Variable));
-                        }
-                        "min" => {
-                            let min = max * I256::from(-1i32);
-                            let c = Concrete::from(min);
-                            let node = self.add_node(Node::Concrete(c)).into();
-                            let mut var = ContextVar::new_from_concrete(loc, node, self);
-                            var.name = format!("int{size}.min");
-                            var.display_name = var.name.clone();
-                            var.is_tmp = true;
-                            var.is_symbolic = false;
-                            let cvar = self.add_node(Node::ContextVar(var));
-                            self.add_edge(cvar, ctx, Edge::Context(ContextEdge::Variable));
-                        }
-                        e => panic!("Unknown type attribute on int{size}: {e:?}"),
-                    }
-                }
-                Builtin::Uint(size) => {
-                    let size = *size;
-                    match &*ident.name {
-                        "max" => {
-                            let max = if size == 256 {
-                                U256::MAX
-                            } else {
-                                U256::from(2).pow(U256::from(size)) - 1
-                            };
-                            let c = Concrete::from(max);
-                            let node = self.add_node(Node::Concrete(c)).into();
-                            let mut var = ContextVar::new_from_concrete(loc, node, self);
-                            var.name = format!("int{size}.max");
-                            var.display_name = var.name.clone();
-                            var.is_tmp = true;
-                            var.is_symbolic = false;
-                            let cvar = self.add_node(Node::ContextVar(var));
-                            self.add_edge(cvar, ctx, Edge::Context(ContextEdge::Variable));
-                            return ExprRet::Single((ctx, cvar));
-                        }
-                        "min" => {
-                            let min = U256::zero();
-                            let c = Concrete::from(min);
-                            let node = self.add_node(Node::Concrete(c)).into();
-                            let mut var = ContextVar::new_from_concrete(loc, node, self);
-                            var.name = format!("int{size}.min");
-                            var.display_name = var.name.clone();
-                            var.is_tmp = true;
-                            var.is_symbolic = false;
-                            let cvar = self.add_node(Node::ContextVar(var));
-                            self.add_edge(cvar, ctx, Edge::Context(ContextEdge::Variable));
-                            return ExprRet::Single((ctx, cvar));
-                        }
-                        e => panic!("Unknown type attribute on int{size}: {e:?}"),
-                    }
+                    let Some(c) = c else {
+                        panic!(
+                            "Unknown type attribute on {}: {:?}",
+                            builtin.as_string(self),
+                            ident.name
+                        )
+                    };
+                    let node = self.concrete_or_add(c).into();
+                    let mut var = ContextVar::new_from_concrete(loc, node, self);
+                    var.name = format!("{}.{}", builtin.as_string(self), ident.name);
+                    var.display_name = var.name.clone();
+                    var.is_tmp = true;
+                    var.is_symbolic = false;
+                    let cvar = self.add_node(Node::ContextVar(var));
+                    self.add_edge(cvar, ctx, Edge::Context(ContextEdge::Variable));
+                    return ExprRet::Single((ctx, cvar));
                 }
             },
             e => todo!("{:?}", e),
@@ -551,6 +537,179 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
         ExprRet::Single((ctx, member_idx))
     }
 
+    /// The variant `Kind.variant` of the enum `Kind`, a constant of the enum type whose range is
+    /// the variant's ordinal
+    fn enum_variant(
+        &mut self,
+        loc: Loc,
+        enum_node: EnumNode,
+        variant: &str,
+        ctx: ContextNode,
+    ) -> ContextVarNode {
+        let Some(ordinal) = enum_node
+            .underlying(self)
+            .values
+            .iter()
+            .position(|value| value.as_ref().is_some_and(|value| value.name == variant))
+        else {
+            panic!(
+                "No variant {:?} in enum: {:?}",
+                variant,
+                enum_node.name(self)
+            )
+        };
+        let ordinal = Elem::from(Concrete::Uint(8, U256::from(ordinal)));
+        let mut var = ContextVar::maybe_from_user_ty(self, loc, enum_node.into())
+            .expect("Enum could not be made into a variable");
+        var.name = format!("{}.{}", enum_node.name(self), variant);
+        var.display_name = var.name.clone();
+        var.is_tmp = true;
+        var.is_symbolic = false;
+        var.ty = VarType::User(
+            TypeNode::Enum(enum_node),
+            Some(SolcRange {
+                min: ordinal.clone(),
+                max: ordinal,
+                exclusions: vec![],
+            }),
+        );
+        let cvar = self.add_node(Node::ContextVar(var));
+        self.add_edge(cvar, ctx, Edge::Context(ContextEdge::Variable));
+        cvar.into()
+    }
+
+    /// Gets the variable tracking `field_name` of the struct variable `strukt`, creating it
+    /// with the field's type if the field hasn't been accessed yet
+    fn struct_field(
+        &mut self,
+        loc: Loc,
+        struct_node: StructNode,
+        strukt: ContextVarNode,
+        field_name: &str,
+        ctx: ContextNode,
+    ) -> ContextVarNode {
+        let name = format!("{}.{}", strukt.name(self), field_name);
+        if let Some(attr_var) = ctx.var_by_name_or_recurse(self, &name) {
+            return attr_var.latest_version(self);
+        }
+        let field = struct_node
+            .fields(self)
+            .iter()
+            .map(|field_node| field_node.underlying(self))
+            .find(|field| field.name.as_ref().expect("field wasnt named").name == field_name)
+            .unwrap_or_else(|| {
+                panic!(
+                    "No field with name {:?} in struct: {:?}",
+                    field_name,
+                    struct_node.name(self)
+                )
+            })
+            .clone();
+        let field_cvar =
+            ContextVar::maybe_new_from_field(self, loc, strukt.underlying(self), field.clone())
+                .unwrap_or_else(|| panic!("Unknown type for struct field: {:?}", field.name));
+        let fc_node = self.add_node(Node::ContextVar(field_cvar));
+        self.add_edge(
+            fc_node,
+            strukt.first_version(self),
+            Edge::Context(ContextEdge::AttrAccess),
+        );
+        self.add_edge(fc_node, ctx, Edge::Context(ContextEdge::Variable));
+        fc_node.into()
+    }
+
+    /// Gets the variable tracking `addr.balance`, `addr.code` or `addr.codehash`, creating it
+    /// with the full range of its type on first access
+    fn address_member(
+        &mut self,
+        loc: Loc,
+        addr: ContextVarNode,
+        member: &str,
+        ctx: ContextNode,
+    ) -> ContextVarNode {
+        let name = format!("{}.{}", addr.name(self), member);
+        if let Some(attr_var) = ctx.var_by_name_or_recurse(self, &name) {
+            return attr_var.latest_version(self);
+        }
+        let builtin = match member {
+            "balance" => Builtin::Uint(256),
+            "code" => Builtin::DynamicBytes,
+            "codehash" => Builtin::Bytes(32),
+            e => panic!("Unknown member access on address: {e:?}"),
+        };
+        let node = self.builtin_or_add(builtin);
+        let mut var = ContextVar::new_from_builtin(loc, node.into(), self);
+        var.name = name;
+        var.display_name = format!("{}.{}", addr.display_name(self), member);
+        var.is_tmp = false;
+        var.is_symbolic = true;
+        let attr_node = self.add_node(Node::ContextVar(var));
+        self.add_edge(
+            attr_node,
+            addr.first_version(self),
+            Edge::Context(ContextEdge::AttrAccess),
+        );
+        self.add_edge(attr_node, ctx, Edge::Context(ContextEdge::Variable));
+        attr_node.into()
+    }
+
+    /// Resolves `super.foo` to the next definition of `foo` after the executing function's
+    /// contract in the linearization of the most derived contract. An overloaded `foo` resolves
+    /// to every overload
+    fn super_access(&mut self, loc: Loc, ident: &Identifier, ctx: ContextNode) -> ExprRet {
+        let funcs = self.super_funcs(ident, ctx);
+        if funcs.is_empty() {
+            self.add_unsupported(
+                loc,
+                format!(
+                    "No function named {} in the bases of the contract",
+                    ident.name
+                ),
+            );
+        }
+        let mut rets = funcs
+            .into_iter()
+            .map(|func| {
+                let func_cvar = ContextVar::maybe_from_user_ty(self, loc, func.into())
+                    .expect("Function could not be made into a variable");
+                let fn_node = self.add_node(Node::ContextVar(func_cvar));
+                self.add_edge(fn_node, ctx, Edge::Context(ContextEdge::Variable));
+                ExprRet::Single((ctx, fn_node))
+            })
+            .collect::<Vec<_>>();
+        if rets.len() == 1 {
+            rets.swap_remove(0)
+        } else {
+            ExprRet::Multi(rets)
+        }
+    }
+
+    /// The functions named `ident` that `super.<ident>` can refer to, i.e. those of the first
+    /// base after the executing function's contract that defines any
+    fn super_funcs(&self, ident: &Identifier, ctx: ContextNode) -> Vec<FunctionNode> {
+        let derived = ctx.associated_contract(self);
+        let current = ctx.executing_fn(self).contract(self).unwrap_or(derived);
+        let linearized = derived.with_bases(self);
+        let next = linearized
+            .iter()
+            .position(|con| *con == current)
+            .map(|i| i + 1)
+            .unwrap_or(linearized.len());
+        linearized[next..]
+            .iter()
+            .map(|con| self.funcs_named(*con, &ident.name))
+            .find(|funcs| !funcs.is_empty())
+            .unwrap_or_default()
+    }
+
+    /// The functions defined directly on `con` with the given name
+    fn funcs_named(&self, con: ContractNode, name: &str) -> Vec<FunctionNode> {
+        con.funcs(self)
+            .into_iter()
+            .filter(|func| func.name(self).starts_with(&format!("{name}(")))
+            .collect()
+    }
+
     fn index_access(
         &mut self,
         loc: Loc,
@@ -608,6 +767,52 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
         self.match_length(loc, elem, true)
     }
 
+    /// The initial range of an array's length, which is a constant for fixed size arrays,
+    /// `bytesN` and values like `hex"0102"` whose length is already known
+    fn length_range(&self, arr: ContextVarNode) -> Option<SolcRange> {
+        let known_len = match &arr.underlying(self).ty {
+            VarType::BuiltIn(node, _) => match node.underlying(self) {
+                Builtin::SizedArray(len, _) => Some(*len),
+                Builtin::Bytes(size) => Some(U256::from(*size)),
+                _ => None,
+            },
+            VarType::Concrete(cnode) => match cnode.underlying(self) {
+                Concrete::Bytes(size, _) => Some(U256::from(*size)),
+                Concrete::DynBytes(bytes) => Some(U256::from(bytes.len())),
+                Concrete::Array(elems) => Some(U256::from(elems.len())),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        if let Some(len) = known_len {
+            let len = Elem::from(Concrete::from(len));
+            return Some(SolcRange {
+                min: len.clone(),
+                max: len,
+                exclusions: vec![],
+            });
+        }
+
+        // the array's range carries its length, i.e. from `new uint[](n)` or a literal
+        let len_bounds = arr.range(self).and_then(|r| {
+            let min = r.evaled_range_min(self).maybe_range_dyn()?.len;
+            let max = r.evaled_range_max(self).maybe_range_dyn()?.len;
+            match (min.maybe_concrete()?.val, max.maybe_concrete()?.val) {
+                (Concrete::Uint(_, min), Concrete::Uint(_, max)) if min <= max => Some((min, max)),
+                _ => None,
+            }
+        });
+        if let Some((min, max)) = len_bounds {
+            return Some(SolcRange {
+                min: Elem::from(Concrete::from(min)),
+                max: Elem::from(Concrete::from(max)),
+                exclusions: vec![],
+            });
+        }
+        SolcRange::try_from_builtin(&Builtin::Uint(256))
+    }
+
     fn tmp_length(
         &mut self,
         arr: ContextVarNode,
@@ -627,9 +832,10 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
                 is_tmp: false,
                 tmp_of: None,
                 is_symbolic: true,
+                is_tainted: arr.is_tainted(self),
                 ty: VarType::BuiltIn(
                     BuiltInNode::from(self.builtin_or_add(Builtin::Uint(256))),
-                    SolcRange::try_from_builtin(&Builtin::Uint(256)),
+                    self.length_range(arr.latest_version(self)),
                 ),
             };
             let len_node = self.add_node(Node::ContextVar(len_var));
@@ -697,9 +903,10 @@ pub trait MemberAccess: AnalyzerLike<Expr = Expression> + Sized {
                         is_tmp: false,
                         tmp_of: None,
                         is_symbolic: true,
+                        is_tainted: arr.is_tainted(self),
                         ty: VarType::BuiltIn(
                             BuiltInNode::from(self.builtin_or_add(Builtin::Uint(256))),
-                            SolcRange::try_from_builtin(&Builtin::Uint(256)),
+                            self.length_range(next_arr),
                         ),
                     };
                     let len_node = self.add_node(Node::ContextVar(len_var));
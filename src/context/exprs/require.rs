@@ -15,14 +15,36 @@ use shared::{
 };
 
 use ethers_core::types::I256;
-use solang_parser::pt::{Expression, Loc};
+use solang_parser::pt::{CodeLocation, Expression, Loc};
 use std::cmp::Ordering;
 
 impl<T> Require for T where T: Variable + BinOp + Sized + AnalyzerLike {}
 pub trait Require: AnalyzerLike + Variable + BinOp + Sized {
     /// Handles a require expression
     fn handle_require(&mut self, inputs: &[Expression], ctx: ContextNode) {
-        match inputs.get(0).expect("No lhs input for require statement") {
+        match inputs.first().expect("No lhs input for require statement") {
+            Expression::And(_loc, lhs, rhs) if Self::refinable(lhs) && Self::refinable(rhs) => {
+                // both sides have to hold, and `rhs` is only evaluated once `lhs` did
+                self.handle_require(std::slice::from_ref(lhs.as_ref()), ctx);
+                self.handle_require(std::slice::from_ref(rhs.as_ref()), ctx);
+            }
+            Expression::Or(loc, lhs, rhs) if Self::refinable(lhs) && Self::refinable(rhs) => {
+                if ctx.is_ended(self) {
+                    return;
+                }
+                let forks = ctx.live_forks(self);
+                if !forks.is_empty() {
+                    forks
+                        .into_iter()
+                        .for_each(|fork| self.handle_require(inputs, fork));
+                    return;
+                }
+                // either `lhs` held, or it didn't and `rhs` did
+                let (lhs_ctx, rhs_ctx) = self.require_fork(*loc, ctx);
+                self.handle_require(std::slice::from_ref(lhs.as_ref()), lhs_ctx);
+                self.handle_require(&[Self::negated(*loc, *lhs.clone())], rhs_ctx);
+                self.handle_require(std::slice::from_ref(rhs.as_ref()), rhs_ctx);
+            }
             Expression::Equal(loc, lhs, rhs) => {
                 let lhs_paths = self.parse_ctx_expr(lhs, ctx);
                 let rhs_paths = self.parse_ctx_expr(rhs, ctx);
@@ -98,9 +120,9 @@ pub trait Require: AnalyzerLike + Variable + BinOp + Sized {
             }
             Expression::Variable(ident) => {
                 let lhs_paths = self.variable(ident, ctx);
-                let cnode = ConcreteNode::from(self.add_node(Node::Concrete(Concrete::Bool(true))));
+                let cnode = ConcreteNode::from(self.concrete_or_add(Concrete::Bool(true)));
                 let tmp_true =
-                    Node::ContextVar(ContextVar::new_from_concrete(Loc::Implicit, cnode, self));
+                    Node::ContextVar(ContextVar::new_from_concrete(ident.loc, cnode, self));
                 let rhs_paths =
                     ExprRet::Single((ctx, ContextVarNode::from(self.add_node(tmp_true)).into()));
                 self.handle_require_inner(
@@ -115,10 +137,8 @@ pub trait Require: AnalyzerLike + Variable + BinOp + Sized {
             Expression::Not(loc, lhs) => {
                 // println!("was not in require");
                 let lhs_paths = self.parse_ctx_expr(lhs, ctx);
-                let cnode =
-                    ConcreteNode::from(self.add_node(Node::Concrete(Concrete::Bool(false))));
-                let tmp_false =
-                    Node::ContextVar(ContextVar::new_from_concrete(Loc::Implicit, cnode, self));
+                let cnode = ConcreteNode::from(self.concrete_or_add(Concrete::Bool(false)));
+                let tmp_false = Node::ContextVar(ContextVar::new_from_concrete(*loc, cnode, self));
                 let rhs_paths =
                     ExprRet::Single((ctx, ContextVarNode::from(self.add_node(tmp_false)).into()));
                 // println!("{:?} {:?}", lhs_paths, rhs_paths);
@@ -133,9 +153,8 @@ pub trait Require: AnalyzerLike + Variable + BinOp + Sized {
             }
             Expression::And(loc, lhs, rhs) => {
                 let lhs_paths = self.cmp(*loc, lhs, RangeOp::And, rhs, ctx);
-                let cnode = ConcreteNode::from(self.add_node(Node::Concrete(Concrete::Bool(true))));
-                let tmp_true =
-                    Node::ContextVar(ContextVar::new_from_concrete(Loc::Implicit, cnode, self));
+                let cnode = ConcreteNode::from(self.concrete_or_add(Concrete::Bool(true)));
+                let tmp_true = Node::ContextVar(ContextVar::new_from_concrete(*loc, cnode, self));
                 let node = self.add_node(tmp_true);
                 self.add_edge(node, ctx, Edge::Context(ContextEdge::Variable));
                 let rhs_paths = ExprRet::Single((ctx, node));
@@ -151,9 +170,8 @@ pub trait Require: AnalyzerLike + Variable + BinOp + Sized {
             }
             Expression::Or(loc, lhs, rhs) => {
                 let lhs_paths = self.cmp(*loc, lhs, RangeOp::Or, rhs, ctx);
-                let cnode = ConcreteNode::from(self.add_node(Node::Concrete(Concrete::Bool(true))));
-                let tmp_true =
-                    Node::ContextVar(ContextVar::new_from_concrete(Loc::Implicit, cnode, self));
+                let cnode = ConcreteNode::from(self.concrete_or_add(Concrete::Bool(true)));
+                let tmp_true = Node::ContextVar(ContextVar::new_from_concrete(*loc, cnode, self));
                 let node = self.add_node(tmp_true);
                 self.add_edge(node, ctx, Edge::Context(ContextEdge::Variable));
                 let rhs_paths = ExprRet::Single((ctx, node));
@@ -166,10 +184,90 @@ pub trait Require: AnalyzerLike + Variable + BinOp + Sized {
                     (RangeOp::Neq, RangeOp::Eq),
                 );
             }
-            e => unreachable!("Require expr with noncomparator: {:?}", e),
+            e => {
+                // `false`, a call, an index or a member access, whatever it evaluates to has to
+                // be `true`
+                let lhs_paths = self.parse_ctx_expr(e, ctx);
+                let cnode = ConcreteNode::from(self.concrete_or_add(Concrete::Bool(true)));
+                let tmp_true =
+                    Node::ContextVar(ContextVar::new_from_concrete(e.loc(), cnode, self));
+                let node = self.add_node(tmp_true);
+                self.add_edge(node, ctx, Edge::Context(ContextEdge::Variable));
+                let rhs_paths = ExprRet::Single((ctx, node));
+                self.handle_require_inner(
+                    e.loc(),
+                    &lhs_paths,
+                    &rhs_paths,
+                    RangeOp::Eq,
+                    RangeOp::Neq,
+                    (RangeOp::Neq, RangeOp::Eq),
+                );
+            }
         }
     }
 
+    /// Whether [`Require::handle_require`] can narrow bounds with `expr`
+    fn refinable(expr: &Expression) -> bool {
+        match expr {
+            Expression::Equal(..)
+            | Expression::NotEqual(..)
+            | Expression::Less(..)
+            | Expression::More(..)
+            | Expression::MoreEqual(..)
+            | Expression::LessEqual(..)
+            | Expression::Variable(..)
+            | Expression::Not(..) => true,
+            Expression::And(_, lhs, rhs) | Expression::Or(_, lhs, rhs) => {
+                Self::refinable(lhs) && Self::refinable(rhs)
+            }
+            _ => false,
+        }
+    }
+
+    /// The condition that holds when `expr` is false. Comparisons are flipped and `&&`/`||`
+    /// are negated by De Morgan's laws, so the result can narrow bounds as well
+    fn negated(loc: Loc, expr: Expression) -> Expression {
+        match expr {
+            Expression::Equal(_loc, lhs, rhs) => Expression::NotEqual(loc, lhs, rhs),
+            Expression::NotEqual(_loc, lhs, rhs) => Expression::Equal(loc, lhs, rhs),
+            Expression::Less(_loc, lhs, rhs) => Expression::MoreEqual(loc, lhs, rhs),
+            Expression::More(_loc, lhs, rhs) => Expression::LessEqual(loc, lhs, rhs),
+            Expression::MoreEqual(_loc, lhs, rhs) => Expression::Less(loc, lhs, rhs),
+            Expression::LessEqual(_loc, lhs, rhs) => Expression::More(loc, lhs, rhs),
+            Expression::And(_loc, lhs, rhs) if Self::refinable(&lhs) && Self::refinable(&rhs) => {
+                Expression::Or(
+                    loc,
+                    Box::new(Self::negated(loc, *lhs)),
+                    Box::new(Self::negated(loc, *rhs)),
+                )
+            }
+            Expression::Or(_loc, lhs, rhs) if Self::refinable(&lhs) && Self::refinable(&rhs) => {
+                Expression::And(
+                    loc,
+                    Box::new(Self::negated(loc, *lhs)),
+                    Box::new(Self::negated(loc, *rhs)),
+                )
+            }
+            Expression::Not(_loc, inner) if Self::refinable(&inner) => *inner,
+            e => Expression::Not(loc, Box::new(e)),
+        }
+    }
+
+    /// Forks `ctx` in two, for a condition that can hold in two different ways
+    fn require_fork(&mut self, loc: Loc, ctx: ContextNode) -> (ContextNode, ContextNode) {
+        let ctx_fork = self.add_node(Node::ContextFork);
+        self.add_edge(ctx_fork, ctx, Edge::Context(ContextEdge::ContextFork));
+        let mut fork = || {
+            let subctx = ContextNode::from(self.add_node(Node::Context(Context::new_subctx(
+                ctx, loc, true, None, false, self, None,
+            ))));
+            ctx.add_fork(subctx, self);
+            self.add_edge(subctx, ctx_fork, Edge::Context(ContextEdge::Subcontext));
+            subctx
+        };
+        (fork(), fork())
+    }
+
     fn handle_require_inner(
         &mut self,
         loc: Loc,
@@ -269,6 +367,10 @@ pub trait Require: AnalyzerLike + Variable + BinOp + Sized {
                 self.handle_require_inner(loc, m, world1, op, rhs_op, recursion_ops);
                 self.handle_require_inner(loc, m, world2, op, rhs_op, recursion_ops);
             }
+            (ExprRet::Fork(world1, world2), r @ (ExprRet::Single(_) | ExprRet::Multi(_))) => {
+                self.handle_require_inner(loc, world1, r, op, rhs_op, recursion_ops);
+                self.handle_require_inner(loc, world2, r, op, rhs_op, recursion_ops);
+            }
             (e, f) => todo!("any: {:?} {:?}", e, f),
         }
     }
@@ -303,7 +405,7 @@ pub trait Require: AnalyzerLike + Variable + BinOp + Sized {
                 match (lhs_is_const, rhs_is_const) {
                     (true, true) => {
                         if self.const_killable(op, lhs_range, rhs_range) {
-                            ctx.kill(self, loc);
+                            ctx.kill(self, loc, KilledKind::Revert);
                             return None;
                         }
                     }
@@ -312,14 +414,20 @@ pub trait Require: AnalyzerLike + Variable + BinOp + Sized {
                         let rhs_range_fn = SolcRange::dyn_fn_from_op(rhs_op);
                         new_var_range = rhs_range_fn(rhs_range.clone(), new_lhs, loc);
 
-                        if self.update_nonconst_from_const(loc, op, new_lhs, new_rhs, rhs_range) {
-                            ctx.kill(self, loc);
+                        if self.update_nonconst_from_const(
+                            loc,
+                            op.flip(),
+                            new_lhs,
+                            new_rhs,
+                            rhs_range,
+                        ) {
+                            ctx.kill(self, loc, KilledKind::Revert);
                             return None;
                         }
                     }
                     (false, true) => {
                         if self.update_nonconst_from_const(loc, op, new_rhs, new_lhs, lhs_range) {
-                            ctx.kill(self, loc);
+                            ctx.kill(self, loc, KilledKind::Revert);
                             return None;
                         }
                     }
@@ -327,7 +435,7 @@ pub trait Require: AnalyzerLike + Variable + BinOp + Sized {
                         if self.update_nonconst_from_nonconst(
                             loc, op, new_lhs, new_rhs, lhs_range, rhs_range,
                         ) {
-                            ctx.kill(self, loc);
+                            ctx.kill(self, loc, KilledKind::Revert);
                             return None;
                         }
                     }
@@ -400,28 +508,29 @@ pub trait Require: AnalyzerLike + Variable + BinOp + Sized {
             }
 
             let tmp_var = ContextVar {
-                loc: Some(loc),
-                name: format!(
-                    "tmp{}({} {} {})",
-                    ctx.new_tmp(self),
-                    new_lhs.name(self),
-                    op.to_string(),
-                    new_rhs.name(self),
-                ),
                 display_name: format!(
                     "({} {} {})",
                     new_lhs.display_name(self),
                     op.to_string(),
                     new_rhs.display_name(self),
                 ),
-                storage: None,
                 is_tmp: true,
                 tmp_of: Some(TmpConstruction::new(new_lhs, op, Some(new_rhs))),
                 is_symbolic: new_lhs.is_symbolic(self) || new_rhs.is_symbolic(self),
-                ty: VarType::BuiltIn(
-                    BuiltInNode::from(self.builtin_or_add(Builtin::Bool)),
-                    SolcRange::from(Concrete::Bool(true)),
-                ),
+                ..ContextVar::new(
+                    loc,
+                    format!(
+                        "tmp{}({} {} {})",
+                        ctx.new_tmp(self),
+                        new_lhs.name(self),
+                        op.to_string(),
+                        new_rhs.name(self),
+                    ),
+                    VarType::BuiltIn(
+                        BuiltInNode::from(self.builtin_or_add(Builtin::Bool)),
+                        SolcRange::from(Concrete::Bool(true)),
+                    ),
+                )
             };
 
             let cvar = ContextVarNode::from(self.add_node(Node::ContextVar(tmp_var)));
@@ -431,7 +540,7 @@ pub trait Require: AnalyzerLike + Variable + BinOp + Sized {
 
             any_unsat |= new_var_range.unsat(self);
             if any_unsat {
-                ctx.kill(self, loc);
+                ctx.kill(self, loc, KilledKind::Revert);
                 return None;
             }
 
@@ -707,8 +816,8 @@ pub trait Require: AnalyzerLike + Variable + BinOp + Sized {
                 let one = Concrete::one(&max_conc.val).expect("Cannot decrement range elem by one");
 
                 // we add/sub one to the element because its strict >
-                new_lhs.set_range_min(self, rhs_elem + one.clone().into());
-                new_rhs.set_range_max(self, lhs_range.range_min() - one.into());
+                self.tighten_min(new_lhs, &lhs_range, rhs_elem + one.clone().into());
+                self.tighten_max(new_rhs, &rhs_range, lhs_range.range_max() - one.into());
                 false
             }
             RangeOp::Gte => {
@@ -725,8 +834,8 @@ pub trait Require: AnalyzerLike + Variable + BinOp + Sized {
                     return true;
                 }
 
-                new_lhs.set_range_min(self, rhs_elem);
-                new_rhs.set_range_max(self, lhs_range.range_min());
+                self.tighten_min(new_lhs, &lhs_range, rhs_elem);
+                self.tighten_max(new_rhs, &rhs_range, lhs_range.range_max());
                 false
             }
             RangeOp::Lt => {
@@ -736,7 +845,7 @@ pub trait Require: AnalyzerLike + Variable + BinOp + Sized {
                 // if lhs min is >= rhs.max, we can't make this true
                 let min = lhs_range.evaled_range_min(self);
                 if matches!(
-                    min.range_ord(&rhs_elem.minimize(self)),
+                    min.range_ord(&rhs_elem.maximize(self)),
                     Some(Ordering::Greater) | Some(Ordering::Equal)
                 ) {
                     return true;
@@ -746,8 +855,8 @@ pub trait Require: AnalyzerLike + Variable + BinOp + Sized {
                 let min_conc = min.maybe_concrete().expect("Was not concrete");
                 let one = Concrete::one(&min_conc.val).expect("Cannot decrement range elem by one");
 
-                new_lhs.set_range_max(self, rhs_elem - one.clone().into());
-                new_rhs.set_range_min(self, lhs_range.range_max() + one.into());
+                self.tighten_max(new_lhs, &lhs_range, rhs_elem - one.clone().into());
+                self.tighten_min(new_rhs, &rhs_range, lhs_range.range_min() + one.into());
                 false
             }
             RangeOp::Lte => {
@@ -757,20 +866,40 @@ pub trait Require: AnalyzerLike + Variable + BinOp + Sized {
                 // if nonconst min is > const, we can't make this true
                 let min = lhs_range.evaled_range_min(self);
                 if matches!(
-                    min.range_ord(&rhs_elem.minimize(self)),
+                    min.range_ord(&rhs_elem.maximize(self)),
                     Some(Ordering::Greater)
                 ) {
                     return true;
                 }
 
-                new_lhs.set_range_max(self, rhs_elem);
-                new_rhs.set_range_max(self, lhs_range.range_min());
+                self.tighten_max(new_lhs, &lhs_range, rhs_elem);
+                self.tighten_min(new_rhs, &rhs_range, lhs_range.range_min());
                 false
             }
             e => todo!("Non-comparator in require, {e:?}"),
         }
     }
 
+    /// Raises the minimum of `var` to `min`, unless its current minimum is already tighter
+    fn tighten_min(&mut self, var: ContextVarNode, range: &SolcRange, min: Elem<Concrete>) {
+        if matches!(
+            min.minimize(self).range_ord(&range.evaled_range_min(self)),
+            Some(Ordering::Greater)
+        ) {
+            var.set_range_min(self, min);
+        }
+    }
+
+    /// Lowers the maximum of `var` to `max`, unless its current maximum is already tighter
+    fn tighten_max(&mut self, var: ContextVarNode, range: &SolcRange, max: Elem<Concrete>) {
+        if matches!(
+            max.maximize(self).range_ord(&range.evaled_range_max(self)),
+            Some(Ordering::Less)
+        ) {
+            var.set_range_max(self, max);
+        }
+    }
+
     fn uninvertable_range_recursion(
         &mut self,
         tmp_construction: TmpConstruction,
@@ -847,7 +976,7 @@ pub trait Require: AnalyzerLike + Variable + BinOp + Sized {
 
                     if new_lhs_range.unsat(self) {
                         *any_unsat = true;
-                        ctx.kill(self, loc);
+                        ctx.kill(self, loc, KilledKind::Revert);
                         return;
                     }
                     if let Some(tmp) = new_underlying_lhs.tmp_of(self) {
@@ -870,7 +999,7 @@ pub trait Require: AnalyzerLike + Variable + BinOp + Sized {
                 let (needs_inverse, adjusted_gt_rhs) = match tmp_construction.op {
                     RangeOp::Sub => {
                         let concrete = ConcreteNode(
-                            self.add_node(Node::Concrete(Concrete::Int(256, I256::from(-1i32))))
+                            self.concrete_or_add(Concrete::Int(256, I256::from(-1i32)))
                                 .index(),
                         );
                         let lhs_cvar = ContextVar::new_from_concrete(loc, concrete, self);
@@ -915,7 +1044,7 @@ pub trait Require: AnalyzerLike + Variable + BinOp + Sized {
 
                         if new_lhs_range.unsat(self) {
                             *any_unsat = true;
-                            ctx.kill(self, loc);
+                            ctx.kill(self, loc, KilledKind::Revert);
                             return;
                         }
 
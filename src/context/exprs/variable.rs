@@ -5,8 +5,9 @@ use crate::{
 use shared::{
     analyzer::AnalyzerLike,
     context::*,
+    nodes::{Builtin, VarNode},
     range::{elem_ty::Elem, Range},
-    Edge, Node,
+    Edge, Node, NodeIdx,
 };
 use solang_parser::pt::Expression;
 
@@ -36,20 +37,60 @@ pub trait Variable: AnalyzerLike<Expr = Expression> + Sized {
                 }
                 _ => ExprRet::Single((ctx, cvar)),
             }
-        } else if let Some(idx) = self.user_types().get(&ident.name) {
-            let mut var = match ContextVar::maybe_from_user_ty(self, ident.loc, *idx) {
+        } else if let funcs @ [_, ..] = &ctx
+            .visible_funcs(self)
+            .into_iter()
+            .filter(|func| func.name(self).starts_with(&format!("{}(", ident.name)))
+            .collect::<Vec<_>>()[..]
+        {
+            // a function used as a value, i.e. `fp = myFunc;`. Overloads are all returned, for a
+            // call to pick from by its inputs
+            let mut funcs = funcs
+                .iter()
+                .map(|func| {
+                    let var = ContextVar::maybe_from_user_ty(self, ident.loc, (*func).into())
+                        .expect("Function could not be made into a context variable");
+                    let cvar = self.add_node(Node::ContextVar(var));
+                    self.add_edge(cvar, ctx, Edge::Context(ContextEdge::Variable));
+                    ExprRet::Single((ctx, cvar))
+                })
+                .collect::<Vec<_>>();
+            if funcs.len() == 1 {
+                funcs.swap_remove(0)
+            } else {
+                ExprRet::Multi(funcs)
+            }
+        } else if let Some(idx) = ctx
+            .maybe_associated_contract(self)
+            .and_then(|contract| {
+                contract
+                    .state_var(self, &ident.name)
+                    .map(NodeIdx::from)
+                    .or_else(|| contract.scoped_user_ty(self, &ident.name))
+            })
+            .or_else(|| self.user_types().get(&ident.name).copied())
+        {
+            let mut var = match ContextVar::maybe_from_user_ty(self, ident.loc, idx) {
                 Some(v) => v,
                 None => panic!(
                     "Could not create context variable from user type: {:?}, {:#?}",
-                    self.node(*idx),
+                    self.node(idx),
                     self.user_types()
                 ),
             };
 
-            // We assume a storage variable is 0 to start with
-            // TODO: check if there is an initializer that we should take into account
-            if let Some(r) = var.fallback_range(self) {
-                if var.storage.is_some() {
+            let (fixed_range, immutable) = match self.node(idx) {
+                Node::Var(def) => (VarNode::from(idx).fixed_range(self), def.is_immutable()),
+                _ => (None, false),
+            };
+            if let Some(r) = fixed_range {
+                let fallback = var.fallback_range(self);
+                var.set_range_min(r.min, fallback.clone());
+                var.set_range_max(r.max, fallback);
+            } else if let Some(r) = var.fallback_range(self) {
+                // We assume a storage variable is 0 to start with, except for an immutable which
+                // holds whatever the constructor set it to
+                if var.storage.is_some() && !immutable {
                     if let Elem::Concrete(c) = r.range_max() {
                         if let Some(size) = c.val.int_size() {
                             var.set_range_max(Elem::from(Concrete::Uint(size, 0.into())), None)
@@ -60,22 +101,10 @@ pub trait Variable: AnalyzerLike<Expr = Expression> + Sized {
             let new_cvarnode = self.add_node(Node::ContextVar(var));
             self.add_edge(new_cvarnode, ctx, Edge::Context(ContextEdge::Variable));
             ExprRet::Single((ctx, new_cvarnode))
-        } else if let Some(func) = self.builtin_fns().get(&ident.name) {
-            let (inputs, outputs) = self
-                .builtin_fn_inputs()
-                .get(&ident.name)
-                .expect("builtin func but no inputs")
-                .clone();
-            let func_node = self.add_node(Node::Function(func.clone()));
-            inputs.into_iter().for_each(|input| {
-                let input_node = self.add_node(input);
-                self.add_edge(input_node, func_node, Edge::FunctionParam);
-            });
-            outputs.into_iter().for_each(|output| {
-                let output_node = self.add_node(output);
-                self.add_edge(output_node, func_node, Edge::FunctionReturn);
-            });
+        } else if let Some(func_node) = self.builtin_fn_or_maybe_add(&ident.name) {
             ExprRet::Single((ctx, func_node))
+        } else if let Some(builtin) = Builtin::try_from_fixed_name(&ident.name) {
+            ExprRet::Single((ctx, self.builtin_or_add(builtin)))
         } else {
             let node = self.add_node(Node::Unresolved(ident.clone()));
             self.user_types_mut().insert(ident.name.clone(), node);
@@ -1,4 +1,4 @@
-use crate::context::exprs::Require;
+use crate::context::exprs::{MemberAccess, Require};
 use crate::context::ContextBuilder;
 use crate::ExprRet;
 use shared::analyzer::AsDotStr;
@@ -8,14 +8,20 @@ use std::collections::BTreeMap;
 
 use shared::range::elem_ty::Dynamic;
 
-use shared::range::Range;
+use shared::range::elem::{RangeElem, RangeOp};
 use shared::range::{elem_ty::Elem, SolcRange};
+use shared::range::{Range, RangeEval};
 use solang_parser::pt::StorageLocation;
 
 use crate::VarType;
 
+use ethers_core::types::U256;
 use shared::{analyzer::AnalyzerLike, nodes::*, Edge, Node, NodeIdx};
-use solang_parser::pt::{Expression, Loc};
+use solang_parser::pt::{Expression, FunctionTy, Loc};
+
+/// How many calls deep a function may recurse into itself before further calls are treated
+/// like calls to a function without a body
+const MAX_RECURSION_DEPTH: usize = 3;
 
 impl<T> FuncCaller for T where T: AnalyzerLike<Expr = Expression> + Sized + GraphLike {}
 pub trait FuncCaller: GraphLike + AnalyzerLike<Expr = Expression> + Sized {
@@ -59,8 +65,8 @@ pub trait FuncCaller: GraphLike + AnalyzerLike<Expr = Expression> + Sized {
                         .zip(&inputs)
                         .enumerate()
                         .all(|(i, (param, input))| {
-                            if param.as_dot_str(self)
-                                == ContextVarNode::from(*input).as_dot_str(self)
+                            if param.ty_str(self)
+                                == ContextVarNode::from(*input).ty(self).as_dot_str(self)
                             {
                                 true
                             } else if literals[i] {
@@ -116,7 +122,7 @@ pub trait FuncCaller: GraphLike + AnalyzerLike<Expr = Expression> + Sized {
         let new_cvarnode = self.add_node(Node::ContextVar(var));
         self.add_edge(new_cvarnode, ctx, Edge::Context(ContextEdge::Variable));
         if let Some(func_node) = ContextVarNode::from(new_cvarnode).ty(self).func_node(self) {
-            self.func_call(ctx, *loc, inputs, func_node)
+            self.func_call(ctx, *loc, inputs, func_node, false)
         } else {
             unreachable!()
         }
@@ -134,26 +140,162 @@ pub trait FuncCaller: GraphLike + AnalyzerLike<Expr = Expression> + Sized {
             Node::Function(underlying) => {
                 if let Some(func_name) = &underlying.name {
                     match &*func_name.name {
-                        "require" | "assert" => {
+                        "require" => {
+                            self.handle_require(input_exprs, ctx);
+                            ExprRet::Multi(vec![])
+                        }
+                        "assert" => {
+                            // bounds are refined the same as for `require`, but a failure panics
                             self.handle_require(input_exprs, ctx);
+                            ctx.set_killed_kind_within(self, *loc, KilledKind::Panic);
+                            ExprRet::Multi(vec![])
+                        }
+                        "selfdestruct" => {
+                            input_exprs.iter().for_each(|expr| {
+                                let _ = self.parse_ctx_expr(expr, ctx);
+                            });
+                            ctx.self_destruct(self, *loc);
                             ExprRet::Multi(vec![])
                         }
                         "type" => ExprRet::Single(
                             self.parse_ctx_expr(&input_exprs[0], ctx).expect_single(),
                         ),
-                        "ecrecover" => {
+                        "abi.encodePacked" | "bytes.concat" => {
+                            let is_abi = func_name.name == "abi.encodePacked";
+                            let inputs: Vec<_> = input_exprs
+                                .iter()
+                                .map(|expr| self.parse_ctx_expr(expr, ctx))
+                                .collect();
+                            // packing two dynamic types next to each other is ambiguous, i.e.
+                            // `("a", "bc")` and `("ab", "c")` pack to the same bytes
+                            let adjacent_dyn = is_abi
+                                && inputs.windows(2).any(|pair| {
+                                    pair.iter().all(|input| match input {
+                                        ExprRet::Single((_, var))
+                                        | ExprRet::SingleLiteral((_, var)) => {
+                                            ContextVarNode::from(*var).ty(self).is_dyn(self)
+                                        }
+                                        _ => false,
+                                    })
+                                });
+                            if adjacent_dyn {
+                                self.add_warning(
+                                    *loc,
+                                    "abi.encodePacked with adjacent dynamic types can produce colliding encodings".to_string(),
+                                );
+                            }
+                            // constant parts pack to constant bytes, i.e. a selector and its
+                            // arguments
+                            let packed = inputs
+                                .iter()
+                                .map(|input| match input {
+                                    ExprRet::Single((_, var))
+                                    | ExprRet::SingleLiteral((_, var)) => self
+                                        .constant_value(ContextVarNode::from(*var))?
+                                        .packed_bytes(),
+                                    _ => None,
+                                })
+                                .collect::<Option<Vec<_>>>();
+                            let var = if let Some(packed) = packed {
+                                let node =
+                                    self.concrete_or_add(Concrete::DynBytes(packed.concat()));
+                                ContextVar::new_from_concrete(*loc, node.into(), self)
+                            } else {
+                                ContextVar::new_from_builtin(
+                                    *loc,
+                                    self.builtin_or_add(Builtin::DynamicBytes).into(),
+                                    self,
+                                )
+                            };
+                            let cvar = self.add_node(Node::ContextVar(var));
+                            ExprRet::Single((ctx, cvar))
+                        }
+                        "abi.decode" => self.abi_decode(*loc, input_exprs, ctx),
+                        "abi.encode"
+                        | "abi.encodeWithSelector"
+                        | "abi.encodeWithSignature"
+                        | "abi.encodeCall" => {
                             input_exprs.iter().for_each(|expr| {
-                                // we want to parse even though we dont need the variables here
                                 let _ = self.parse_ctx_expr(expr, ctx);
                             });
                             let var = ContextVar::new_from_builtin(
                                 *loc,
-                                self.builtin_or_add(Builtin::Address).into(),
+                                self.builtin_or_add(Builtin::DynamicBytes).into(),
                                 self,
                             );
                             let cvar = self.add_node(Node::ContextVar(var));
                             ExprRet::Single((ctx, cvar))
                         }
+                        "ty.wrap" | "ty.unwrap" => {
+                            // a user defined value type has the range of its underlying type
+                            ExprRet::Single(
+                                self.parse_ctx_expr(&input_exprs[0], ctx).expect_single(),
+                            )
+                        }
+                        "address.transfer" | "address.send" => {
+                            let name = func_name.name.clone();
+                            for expr in input_exprs {
+                                match self.parse_ctx_expr(expr, ctx) {
+                                    ExprRet::CtxKilled => return ExprRet::CtxKilled,
+                                    ExprRet::Single((_, value))
+                                    | ExprRet::SingleLiteral((_, value)) => self
+                                        .record_value_transfer(
+                                            *loc,
+                                            name.trim_start_matches("address."),
+                                            value.into(),
+                                            ctx,
+                                        ),
+                                    _ => {}
+                                }
+                            }
+                            if name == "address.transfer" {
+                                return ExprRet::Multi(vec![]);
+                            }
+                            // `send` returns whether it succeeded instead of reverting
+                            let ret_ty = self.builtin_fn_inputs()[&name].1[0].ty;
+                            let var = ContextVar::new_from_builtin(*loc, ret_ty.into(), self);
+                            let cvar = self.add_node(Node::ContextVar(var));
+                            ExprRet::Single((ctx, cvar))
+                        }
+                        "address.call" | "address.delegatecall" | "address.staticcall" => {
+                            let name = func_name.name.clone();
+                            input_exprs.iter().for_each(|expr| {
+                                let _ = self.parse_ctx_expr(expr, ctx);
+                            });
+                            // `(bool success, bytes memory data)`, anything can come back
+                            let rets = self.builtin_fn_inputs()[&name]
+                                .1
+                                .iter()
+                                .map(|ret| ret.ty)
+                                .collect::<Vec<_>>();
+                            ExprRet::Multi(
+                                rets.into_iter()
+                                    .map(|ty| {
+                                        let var =
+                                            ContextVar::new_from_builtin(*loc, ty.into(), self);
+                                        let cvar = self.add_node(Node::ContextVar(var));
+                                        self.add_edge(
+                                            cvar,
+                                            ctx,
+                                            Edge::Context(ContextEdge::Variable),
+                                        );
+                                        ExprRet::Single((ctx, cvar))
+                                    })
+                                    .collect(),
+                            )
+                        }
+                        "keccak256" | "sha256" | "ripemd160" | "ecrecover" => {
+                            let name = func_name.name.clone();
+                            input_exprs.iter().for_each(|expr| {
+                                // we want to parse even though we dont need the variables here
+                                let _ = self.parse_ctx_expr(expr, ctx);
+                            });
+                            // the result is typed by the builtin's signature, i.e. `bytes20` for `ripemd160`
+                            let ret_ty = self.builtin_fn_inputs()[&name].1[0].ty;
+                            let var = ContextVar::new_from_builtin(*loc, ret_ty.into(), self);
+                            let cvar = self.add_node(Node::ContextVar(var));
+                            ExprRet::Single((ctx, cvar))
+                        }
                         e => todo!("builtin function: {:?}", e),
                     }
                 } else {
@@ -161,71 +303,49 @@ pub trait FuncCaller: GraphLike + AnalyzerLike<Expr = Expression> + Sized {
                 }
             }
             Node::Builtin(Builtin::Array(_)) => {
-                // create a new list
-                let (ctx, len_cvar) = self.parse_ctx_expr(&input_exprs[0], ctx).expect_single();
-                let ty = VarType::try_from_idx(self, func_idx);
-
-                let new_arr = ContextVar {
-                    loc: Some(*loc),
-                    name: format!("tmp_arr{}", ctx.new_tmp(self)),
-                    display_name: "arr".to_string(),
-                    storage: None,
-                    is_tmp: true,
-                    is_symbolic: false,
-                    tmp_of: None,
-                    ty: ty.expect("No type for node"),
-                };
-
-                let arr = ContextVarNode::from(self.add_node(Node::ContextVar(new_arr)));
-
-                let len_var = ContextVar {
-                    loc: Some(*loc),
-                    name: arr.name(self) + ".length",
-                    display_name: arr.display_name(self) + ".length",
-                    storage: None,
-                    is_tmp: true,
-                    tmp_of: None,
-                    is_symbolic: true,
-                    ty: ContextVarNode::from(len_cvar).underlying(self).ty.clone(),
-                };
-
-                let len_cvar = self.add_node(Node::ContextVar(len_var));
-                self.add_edge(arr, ctx, Edge::Context(ContextEdge::Variable));
-                self.add_edge(len_cvar, ctx, Edge::Context(ContextEdge::Variable));
-                self.add_edge(len_cvar, arr, Edge::Context(ContextEdge::AttrAccess));
-
-                // update the length
-                if let Some(r) = arr.range(self) {
-                    let min = r.evaled_range_min(self);
-                    let max = r.evaled_range_max(self);
-
-                    if let Some(mut rd) = min.maybe_range_dyn() {
-                        rd.len = Elem::Dynamic(Dynamic::new(len_cvar, *loc));
-                        arr.set_range_min(self, Elem::ConcreteDyn(Box::new(rd)));
-                    }
-
-                    if let Some(mut rd) = max.maybe_range_dyn() {
-                        rd.len = Elem::Dynamic(Dynamic::new(len_cvar, *loc));
-                        arr.set_range_min(self, Elem::ConcreteDyn(Box::new(rd)))
-                    }
-                }
-
-                ExprRet::Single((ctx, arr.into()))
+                self.new_dyn_builtin(*loc, &input_exprs[0], func_idx, ctx)
             }
             Node::Builtin(ty) => {
                 // it is a cast
                 let ty = ty.clone();
                 let (ctx, cvar) = self.parse_ctx_expr(&input_exprs[0], ctx).expect_single();
 
+                // identity conversion, i.e. `uint256(x)` where `x` is already a `uint256`
+                if let VarType::BuiltIn(bn, _) = ContextVarNode::from(cvar).ty(self) {
+                    if *bn.underlying(self) == ty {
+                        return ExprRet::Single((ctx, cvar));
+                    }
+                }
+
                 let new_var = ContextVarNode::from(cvar).as_cast_tmp(*loc, ctx, ty.clone(), self);
 
                 new_var.underlying_mut(self).ty = VarType::try_from_idx(self, func_idx).expect("");
 
+                // `address(myContract)`, keep track of the contract it came from
+                if let VarType::User(TypeNode::Contract(_), _) = ContextVarNode::from(cvar).ty(self)
+                {
+                    new_var.underlying_mut(self).tmp_of =
+                        Some(TmpConstruction::new(cvar.into(), RangeOp::Cast, None));
+                    return ExprRet::Single((ctx, new_var.into()));
+                }
+
+                // string literals carry no range, i.e. `bytes4("abcd")`
+                if let VarType::Concrete(c) = ContextVarNode::from(cvar).ty(self) {
+                    let c = c.underlying(self).clone();
+                    if let (Concrete::String(_), Some(casted)) = (&c, c.clone().cast(ty.clone())) {
+                        let node = self.concrete_or_add(casted);
+                        new_var.underlying_mut(self).ty = VarType::Concrete(node.into());
+                        return ExprRet::Single((ctx, new_var.into()));
+                    }
+                }
+
                 // cast the ranges
                 if let Some(r) = ContextVarNode::from(cvar).range(self) {
                     let curr_range = SolcRange::try_from_builtin(&ty).expect("No default range");
-                    new_var.set_range_min(self, r.range_min().cast(curr_range.range_min()));
-                    new_var.set_range_max(self, r.range_max().cast(curr_range.range_max()));
+                    // whether the range wraps around the new width depends on both of its bounds
+                    let whole = Elem::Dynamic(Dynamic::new(cvar, *loc));
+                    new_var.set_range_min(self, whole.clone().cast(curr_range.range_min()));
+                    new_var.set_range_max(self, whole.cast(curr_range.range_max()));
                     // cast the range exclusions - TODO: verify this is correct
                     let mut exclusions = r.range_exclusions();
                     exclusions.iter_mut().for_each(|range| {
@@ -238,6 +358,79 @@ pub trait FuncCaller: GraphLike + AnalyzerLike<Expr = Expression> + Sized {
                 ExprRet::Single((ctx, new_var.into()))
             }
             Node::ContextVar(_c) => {
+                if let Some(func) = ContextVarNode::from(func_idx).ty(self).func_node(self) {
+                    // calling through a function typed variable
+                    let inputs = ExprRet::Multi(
+                        input_exprs
+                            .iter()
+                            .map(|expr| self.parse_ctx_expr(expr, ctx))
+                            .collect(),
+                    );
+                    return self.setup_fn_call(loc, &inputs, func.into(), ctx);
+                }
+                if let VarType::BuiltIn(bn, _) = ContextVarNode::from(func_idx).ty(self) {
+                    if let Builtin::Func(_, outputs) = bn.underlying(self).clone() {
+                        // a function pointer we don't know the target of, i.e. a parameter,
+                        // returns anything of its return types
+                        input_exprs.iter().for_each(|expr| {
+                            let _ = self.parse_ctx_expr(expr, ctx);
+                        });
+                        let fp_name = ContextVarNode::from(func_idx).display_name(self);
+                        return ExprRet::Multi(
+                            outputs
+                                .into_iter()
+                                .enumerate()
+                                .map(|(i, ty)| {
+                                    let var = ContextVar {
+                                        display_name: format!("{fp_name}.{i}"),
+                                        is_tmp: true,
+                                        is_symbolic: true,
+                                        ..ContextVar::new(
+                                            *loc,
+                                            format!("tmp{}({fp_name}.{i})", ctx.new_tmp(self)),
+                                            ty,
+                                        )
+                                    };
+                                    let cvar = self.add_node(Node::ContextVar(var));
+                                    self.add_edge(cvar, ctx, Edge::Context(ContextEdge::Variable));
+                                    ExprRet::Single((ctx, cvar))
+                                })
+                                .collect(),
+                        );
+                    }
+                }
+                if let VarType::User(TypeNode::Contract(con), _) =
+                    ContextVarNode::from(func_idx).ty(self)
+                {
+                    // `IERC20(addr)`, a contract-typed view of the address
+                    let con = *con;
+                    let (ctx, addr) = self.parse_ctx_expr(&input_exprs[0], ctx).expect_single();
+                    let addr = ContextVarNode::from(addr);
+                    let mut var = ContextVar::new_from_contract(*loc, con, self);
+                    var.name = format!(
+                        "tmp{}_{}({}({}))",
+                        addr.name(self),
+                        ctx.new_tmp(self),
+                        con.name(self),
+                        addr.name(self)
+                    );
+                    var.display_name = format!("{}({})", con.name(self), addr.display_name(self));
+                    var.is_tmp = true;
+                    var.tmp_of = Some(TmpConstruction::new(addr, RangeOp::Cast, None));
+                    let cvar = self.add_node(Node::ContextVar(var));
+                    self.add_edge(cvar, ctx, Edge::Context(ContextEdge::Variable));
+                    return ExprRet::Single((ctx, cvar));
+                }
+                if let VarType::User(TypeNode::Enum(enum_node), _) =
+                    ContextVarNode::from(func_idx).ty(self)
+                {
+                    return self.enum_cast(*loc, *enum_node, &input_exprs[0], ctx);
+                }
+                if let VarType::User(TypeNode::Struct(struct_node), _) =
+                    ContextVarNode::from(func_idx).ty(self)
+                {
+                    return self.construct_struct(*loc, *struct_node, input_exprs, ctx);
+                }
                 // its a user type
                 // TODO: figure out if we actually need to do anything?
                 let _inputs: Vec<_> = input_exprs
@@ -260,28 +453,460 @@ pub trait FuncCaller: GraphLike + AnalyzerLike<Expr = Expression> + Sized {
         }
     }
 
-    /// Matches the input kinds and performs the call
+    /// Decodes `data` into fresh values of the types listed by the second argument, i.e.
+    /// `abi.decode(data, (uint256, address))`
+    fn abi_decode(&mut self, loc: Loc, input_exprs: &[Expression], ctx: ContextNode) -> ExprRet {
+        let data = self.parse_ctx_expr(&input_exprs[0], ctx);
+        self.map_single(loc, ctx, data, &mut |analyzer, ctx, data| {
+            analyzer.abi_decode_data(loc, &input_exprs[1], data, ctx)
+        })
+    }
+
+    /// Decodes the evaluated `data` into values of the types in `tys_expr`, which are tainted
+    /// if `data` is
+    fn abi_decode_data(
+        &mut self,
+        loc: Loc,
+        tys_expr: &Expression,
+        data: NodeIdx,
+        ctx: ContextNode,
+    ) -> ExprRet {
+        let ty_exprs = match tys_expr {
+            Expression::List(_, params) => params
+                .iter()
+                .filter_map(|(_, param)| Some(&param.as_ref()?.ty))
+                .collect(),
+            Expression::Parenthesis(_, ty_expr) => vec![&**ty_expr],
+            ty_expr => vec![ty_expr],
+        };
+        let is_tainted = ContextVarNode::from(data).is_tainted(self);
+        let mut rets = ty_exprs
+            .into_iter()
+            .map(|ty_expr| {
+                let ty_idx = match self.parse_ctx_expr(ty_expr, ctx) {
+                    ExprRet::Single((_, idx)) | ExprRet::SingleLiteral((_, idx)) => Some(idx),
+                    _ => None,
+                };
+                let Some(ty) = ty_idx.and_then(|idx| VarType::try_from_idx(self, idx)) else {
+                    return self.unsupported_value(
+                        loc,
+                        "abi.decode to something other than a type is not supported".to_string(),
+                        ctx,
+                    );
+                };
+                let name = format!("tmp{}(abi.decode)", ctx.new_tmp(self));
+                let var = ContextVar {
+                    loc: Some(loc),
+                    name,
+                    display_name: format!("abi.decode({})", ty.as_string(self)),
+                    storage: None,
+                    is_tmp: true,
+                    tmp_of: None,
+                    is_symbolic: true,
+                    is_tainted,
+                    ty,
+                };
+                let cvar = self.add_node(Node::ContextVar(var));
+                self.add_edge(cvar, ctx, Edge::Context(ContextEdge::Variable));
+                ExprRet::Single((ctx, cvar))
+            })
+            .collect::<Vec<_>>();
+        if rets.len() == 1 {
+            rets.swap_remove(0)
+        } else {
+            ExprRet::Multi(rets)
+        }
+    }
+
+    /// The value of a variable that can only take one value, i.e. a literal or a variable
+    /// assigned one
+    fn constant_value(&self, var: ContextVarNode) -> Option<Concrete> {
+        let var = var.latest_version(self);
+        if var.is_concrete(self) {
+            return Some(var.as_concrete(self));
+        }
+        let min = var.evaled_range_min(self)?.maybe_concrete()?.val;
+        let max = var.evaled_range_max(self)?.maybe_concrete()?.val;
+        (min == max).then_some(min)
+    }
+
+    /// Converts an integer to an enum, recording the conversion if the value may be past the
+    /// last variant, which reverts
+    fn enum_cast(
+        &mut self,
+        loc: Loc,
+        enum_node: EnumNode,
+        input_expr: &Expression,
+        ctx: ContextNode,
+    ) -> ExprRet {
+        let input = self.parse_ctx_expr(input_expr, ctx);
+        self.map_single(loc, ctx, input, &mut |analyzer, ctx, input| {
+            analyzer.enum_cast_input(loc, enum_node, input.into(), ctx)
+        })
+    }
+
+    /// Converts an already evaluated integer to an enum
+    fn enum_cast_input(
+        &mut self,
+        loc: Loc,
+        enum_node: EnumNode,
+        input: ContextVarNode,
+        ctx: ContextNode,
+    ) -> ExprRet {
+        let input = input.latest_version(self);
+        let variants = enum_node.underlying(self).values.len();
+        if input
+            .evaled_range_max(self)
+            .and_then(|max| max.maybe_concrete()?.val.into_u256())
+            .is_some_and(|max| max >= U256::from(variants))
+        {
+            let tainted = self.security_mode() && input.is_tainted(self);
+            ctx.add_invalid_enum_cast(loc, tainted, self);
+        }
+
+        let mut var = ContextVar::maybe_from_user_ty(self, loc, enum_node.into())
+            .expect("Enum could not be made into a variable");
+        var.name = format!(
+            "tmp{}({}({}))",
+            ctx.new_tmp(self),
+            enum_node.name(self),
+            input.name(self)
+        );
+        var.display_name = format!("{}({})", enum_node.name(self), input.display_name(self));
+        var.is_tmp = true;
+        var.is_symbolic = input.is_symbolic(self);
+        var.tmp_of = Some(TmpConstruction::new(input, RangeOp::Cast, None));
+        let cvar = self.add_node(Node::ContextVar(var));
+        self.add_edge(cvar, ctx, Edge::Context(ContextEdge::Variable));
+        ExprRet::Single((ctx, cvar))
+    }
+
+    /// Builds `S(a, b, ..)`, a temporary struct whose fields are assigned the inputs in the
+    /// order the fields are declared
+    fn construct_struct(
+        &mut self,
+        loc: Loc,
+        struct_node: StructNode,
+        input_exprs: &[Expression],
+        ctx: ContextNode,
+    ) -> ExprRet {
+        let inputs: Vec<_> = input_exprs
+            .iter()
+            .map(|expr| self.parse_ctx_expr(expr, ctx))
+            .collect();
+        if inputs
+            .iter()
+            .any(|input| matches!(input, ExprRet::CtxKilled))
+        {
+            return ExprRet::CtxKilled;
+        }
+
+        let mut var = ContextVar::maybe_from_user_ty(self, loc, struct_node.into())
+            .expect("Struct could not be made into a variable");
+        var.name = format!("tmp{}({})", ctx.new_tmp(self), struct_node.name(self));
+        var.display_name = format!("{}(..)", struct_node.name(self));
+        var.is_tmp = true;
+        let strukt = ContextVarNode::from(self.add_node(Node::ContextVar(var)));
+        self.add_edge(strukt, ctx, Edge::Context(ContextEdge::Variable));
+
+        let fields = struct_node.fields(self);
+        fields.iter().zip(inputs.iter()).for_each(|(field, input)| {
+            let name = field.name(self);
+            let field = self.struct_field(loc, struct_node, strukt, &name, ctx);
+            self.match_assign_sides(loc, &ExprRet::Single((ctx, field.into())), input);
+        });
+        ExprRet::Single((ctx, strukt.into()))
+    }
+
+    /// Handles `new T(..)`: allocating a dynamic array, `bytes` or `string`, or deploying a
+    /// contract
+    fn new_call(
+        &mut self,
+        loc: Loc,
+        ty_expr: &Expression,
+        input_exprs: &[Expression],
+        ctx: ContextNode,
+    ) -> ExprRet {
+        let ty = self.parse_ctx_expr(ty_expr, ctx);
+        self.map_single(loc, ctx, ty, &mut |analyzer, ctx, ty_idx| {
+            analyzer.new_of_ty(loc, ty_idx, input_exprs, ctx)
+        })
+    }
+
+    /// Handles `new T(..)` once `T` is evaluated
+    fn new_of_ty(
+        &mut self,
+        loc: Loc,
+        ty_idx: NodeIdx,
+        input_exprs: &[Expression],
+        ctx: ContextNode,
+    ) -> ExprRet {
+        match self.node(ty_idx) {
+            Node::Builtin(Builtin::Array(_) | Builtin::DynamicBytes | Builtin::String)
+                if input_exprs.len() == 1 =>
+            {
+                self.new_dyn_builtin(loc, &input_exprs[0], ty_idx, ctx)
+            }
+            Node::Contract(_) => self.new_contract(loc, ty_idx.into(), input_exprs, ctx),
+            Node::ContextVar(_) => match ContextVarNode::from(ty_idx).ty(self) {
+                VarType::User(TypeNode::Contract(con), _) => {
+                    let con = *con;
+                    self.new_contract(loc, con, input_exprs, ctx)
+                }
+                _ => self.intrinsic_func_call(&loc, input_exprs, ty_idx, ctx),
+            },
+            _ => self.intrinsic_func_call(&loc, input_exprs, ty_idx, ctx),
+        }
+    }
+
+    /// Allocates a dynamic array, `bytes` or `string` of type `ty_idx` whose length is the
+    /// value of `len_expr`
+    fn new_dyn_builtin(
+        &mut self,
+        loc: Loc,
+        len_expr: &Expression,
+        ty_idx: NodeIdx,
+        ctx: ContextNode,
+    ) -> ExprRet {
+        let (ctx, len_cvar) = match self.parse_ctx_expr(len_expr, ctx) {
+            ExprRet::CtxKilled => return ExprRet::CtxKilled,
+            ret => ret.expect_single(),
+        };
+        let ty = VarType::try_from_idx(self, ty_idx);
+
+        let new_arr = ContextVar {
+            display_name: "arr".to_string(),
+            is_tmp: true,
+            ..ContextVar::new(
+                loc,
+                format!("tmp_arr{}", ctx.new_tmp(self)),
+                ty.expect("No type for node"),
+            )
+        };
+
+        let arr = ContextVarNode::from(self.add_node(Node::ContextVar(new_arr)));
+
+        let len_var = ContextVar {
+            display_name: arr.display_name(self) + ".length",
+            is_tmp: true,
+            is_symbolic: true,
+            ..ContextVar::new(
+                loc,
+                arr.name(self) + ".length",
+                ContextVarNode::from(len_cvar).underlying(self).ty.clone(),
+            )
+        };
+
+        let len_cvar = self.add_node(Node::ContextVar(len_var));
+        self.add_edge(arr, ctx, Edge::Context(ContextEdge::Variable));
+        self.add_edge(len_cvar, ctx, Edge::Context(ContextEdge::Variable));
+        self.add_edge(len_cvar, arr, Edge::Context(ContextEdge::AttrAccess));
+
+        // update the length
+        if let Some(r) = arr.range(self) {
+            let min = r.evaled_range_min(self);
+            let max = r.evaled_range_max(self);
+
+            if let Some(mut rd) = min.maybe_range_dyn() {
+                rd.len = Elem::Dynamic(Dynamic::new(len_cvar, loc));
+                arr.set_range_min(self, Elem::ConcreteDyn(Box::new(rd)));
+            }
+
+            if let Some(mut rd) = max.maybe_range_dyn() {
+                rd.len = Elem::Dynamic(Dynamic::new(len_cvar, loc));
+                arr.set_range_max(self, Elem::ConcreteDyn(Box::new(rd)))
+            }
+        }
+
+        ExprRet::Single((ctx, arr.into()))
+    }
+
+    /// Deploys `con`, resulting in a handle typed as the contract. The constructor runs against
+    /// the new contract's storage so only its arguments are evaluated here
+    fn new_contract(
+        &mut self,
+        loc: Loc,
+        con: ContractNode,
+        input_exprs: &[Expression],
+        ctx: ContextNode,
+    ) -> ExprRet {
+        let inputs = input_exprs
+            .iter()
+            .map(|expr| self.parse_ctx_expr(expr, ctx))
+            .collect::<Vec<_>>();
+        if inputs
+            .iter()
+            .any(|input| matches!(input, ExprRet::CtxKilled))
+        {
+            return ExprRet::CtxKilled;
+        }
+
+        let mut var = ContextVar::new_from_contract(loc, con, self);
+        var.name = format!("tmp_new_{}{}", con.name(self), ctx.new_tmp(self));
+        var.display_name = format!("new {}", con.name(self));
+        var.is_tmp = true;
+        let cvar = self.add_node(Node::ContextVar(var));
+        self.add_edge(cvar, ctx, Edge::Context(ContextEdge::Variable));
+        ExprRet::Single((ctx, cvar))
+    }
+
+    /// Runs the constructors of the contract's bases in `ctx`, from the most base-like one, before
+    /// the body of the contract's own constructor. A base constructor that takes arguments is
+    /// only run if the constructor passes them, as in `constructor() Base(1)`
+    fn call_base_constructors(&mut self, ctx: ContextNode, func_node: FunctionNode, loc: Loc) {
+        let Some(contract) = func_node.contract(self) else {
+            return;
+        };
+        let invocations = func_node
+            .underlying(self)
+            .modifiers_as_base()
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        let bases = contract.with_bases(self);
+        bases.into_iter().skip(1).rev().for_each(|base| {
+            let Some(base_ctor) = base
+                .funcs(self)
+                .into_iter()
+                .find(|func| func.underlying(self).ty == FunctionTy::Constructor)
+            else {
+                return;
+            };
+            let base_name = base.name(self);
+            let args = invocations
+                .iter()
+                .find(|invocation| {
+                    invocation.name.identifiers.last().map(|ident| &ident.name) == Some(&base_name)
+                })
+                .and_then(|invocation| invocation.args.clone())
+                .unwrap_or_default();
+            if args.len() != base_ctor.params(self).len() {
+                return;
+            }
+            let inputs = ExprRet::Multi(
+                args.iter()
+                    .map(|arg| self.parse_ctx_expr(arg, ctx))
+                    .collect(),
+            );
+            self.func_call(ctx, loc, &inputs, base_ctor, false);
+        });
+    }
+
+    /// Analyzes an internal function again, with each parameter limited to the union of the
+    /// arguments it was passed at the call sites reached from the contract's entry points.
+    /// Returns the new context, or `None` if the function can be called from outside of the
+    /// contract or wasn't called at all
+    fn interprocedural_ctx(&mut self, func: FunctionNode) -> Option<ContextNode> {
+        let underlying = func.underlying(self);
+        if func.is_public_or_ext(self)
+            || underlying.ty != FunctionTy::Function
+            || underlying.body.is_none()
+        {
+            return None;
+        }
+        let loc = underlying.loc;
+
+        // calls are inlined, so a call made by another internal function is also found inside
+        // the contexts of whatever called that function
+        let sites = func
+            .call_sites(self)
+            .into_iter()
+            .filter(|site| {
+                let root = site.parent_chain(self).last().copied().unwrap_or(*site);
+                let root_fn = root.underlying(self).parent_fn;
+                root_fn.is_public_or_ext(self)
+                    || matches!(
+                        root_fn.underlying(self).ty,
+                        FunctionTy::Constructor | FunctionTy::Fallback | FunctionTy::Receive
+                    )
+            })
+            .collect::<Vec<_>>();
+        if sites.is_empty() {
+            return None;
+        }
+
+        let params = func.params(self);
+        let ranges = params
+            .iter()
+            .map(|param| {
+                let name = param.maybe_name(self)?;
+                sites
+                    .iter()
+                    .map(|site| {
+                        let range = site.var_by_name(self, &name)?.range(self)?;
+                        Some(SolcRange {
+                            min: range.evaled_range_min(self),
+                            max: range.evaled_range_max(self),
+                            exclusions: range
+                                .exclusions
+                                .iter()
+                                .map(|excl| excl.minimize(self))
+                                .collect(),
+                        })
+                    })
+                    .collect::<Option<Vec<_>>>()?
+                    .into_iter()
+                    .reduce(|acc, range| acc.union(&range, self))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let ctx = ContextNode::from(self.add_node(Node::Context(Context::new(
+            func,
+            func.name(self),
+            loc,
+        ))));
+        let inputs = params
+            .iter()
+            .zip(ranges)
+            .map(|(param, range)| {
+                let var =
+                    ContextVar::maybe_new_from_func_param(self, param.underlying(self).clone())?;
+                let var = ContextVarNode::from(self.add_node(Node::ContextVar(var)));
+                var.set_range_min(self, range.min);
+                var.set_range_max(self, range.max);
+                var.set_range_exclusions(self, range.exclusions);
+                self.add_edge(var, ctx, Edge::Context(ContextEdge::Variable));
+                Some(var)
+            })
+            .collect::<Option<Vec<_>>>()?;
+        self.func_call_inner(false, false, ctx, func, loc, inputs, params, None);
+        Some(ctx)
+    }
+
+    /// Matches the input kinds and performs the call. `ext_call` marks calls made through a
+    /// contract value, i.e. `this.foo()`, which leave the contract even when `func` is its own
     fn func_call(
         &mut self,
         ctx: ContextNode,
         loc: Loc,
         input_paths: &ExprRet,
         func: FunctionNode,
+        ext_call: bool,
     ) -> ExprRet {
         let params = func.params(self);
         match input_paths {
+            ExprRet::CtxKilled => ExprRet::CtxKilled,
+            ExprRet::Multi(inputs)
+                if inputs
+                    .iter()
+                    .any(|input| matches!(input, ExprRet::CtxKilled)) =>
+            {
+                // an argument reverted, i.e. `n - 1` on a recursive call with `n == 0`
+                ExprRet::CtxKilled
+            }
             ExprRet::Single((_ctx, input_var)) => {
                 // if we get a single var, we expect the func to only take a single
                 // variable
-                return self.func_call_inner(
+                self.func_call_inner(
                     false,
+                    ext_call,
                     ctx,
                     func,
                     loc,
                     vec![ContextVarNode::from(*input_var).latest_version(self)],
                     params,
                     None,
-                );
+                )
             }
             ExprRet::Multi(inputs) => {
                 // check if the inputs length matchs func params length
@@ -295,8 +920,9 @@ pub trait FuncCaller: GraphLike + AnalyzerLike<Expr = Expression> + Sized {
                                 ContextVarNode::from(var).latest_version(self)
                             })
                             .collect();
-                        return self
-                            .func_call_inner(false, ctx, func, loc, input_vars, params, None);
+                        self.func_call_inner(
+                            false, ext_call, ctx, func, loc, input_vars, params, None,
+                        )
                     } else {
                         panic!("input has fork - need to flatten")
                     }
@@ -309,9 +935,11 @@ pub trait FuncCaller: GraphLike + AnalyzerLike<Expr = Expression> + Sized {
     }
 
     /// Checks if there are any modifiers and executes them prior to executing the function
+    #[allow(clippy::too_many_arguments)]
     fn func_call_inner(
         &mut self,
         entry_call: bool,
+        ext_call: bool,
         ctx: ContextNode,
         func_node: FunctionNode,
         loc: Loc,
@@ -326,11 +954,12 @@ pub trait FuncCaller: GraphLike + AnalyzerLike<Expr = Expression> + Sized {
                 // use the next modifier
                 let mut mstate = mod_state;
                 mstate.num += 1;
-                self.call_modifier_for_fn(ctx, func_node, mstate)
+                self.call_modifier_for_fn(ctx, func_node, mstate, false)
             } else {
                 // out of modifiers, execute the actual function call
                 self.execute_call_inner(
                     entry_call,
+                    false,
                     loc,
                     ctx,
                     func_node,
@@ -342,11 +971,12 @@ pub trait FuncCaller: GraphLike + AnalyzerLike<Expr = Expression> + Sized {
         } else if !mods.is_empty() {
             // we have modifiers and havent executed them, start the process of executing them
             let state = ModifierState::new(entry_call, 0, loc, func_node, ctx, inputs, params);
-            self.call_modifier_for_fn(ctx, func_node, state)
+            self.call_modifier_for_fn(ctx, func_node, state, ext_call)
         } else {
             // no modifiers, just execute the function
             self.execute_call_inner(
                 entry_call,
+                ext_call,
                 loc,
                 ctx,
                 func_node,
@@ -358,9 +988,11 @@ pub trait FuncCaller: GraphLike + AnalyzerLike<Expr = Expression> + Sized {
     }
 
     /// Actually executes the function
+    #[allow(clippy::too_many_arguments)]
     fn execute_call_inner(
         &mut self,
         entry_call: bool,
+        ext_call: bool,
         loc: Loc,
         ctx: ContextNode,
         func_node: FunctionNode,
@@ -368,7 +1000,14 @@ pub trait FuncCaller: GraphLike + AnalyzerLike<Expr = Expression> + Sized {
         params: Vec<FunctionParamNode>,
         modifier_state: Option<ModifierState>,
     ) -> ExprRet {
-        let fn_ext = ctx.is_fn_ext(func_node, self);
+        let fn_ext = ext_call || ctx.is_fn_ext(func_node, self);
+        let recursion_depth = std::iter::once(ctx)
+            .chain(ctx.parent_chain(self))
+            .filter(|ctx| {
+                let underlying = ctx.underlying(self);
+                underlying.fn_call.or(underlying.ext_fn_call) == Some(func_node)
+            })
+            .count();
         let subctx = if entry_call {
             ctx
         } else {
@@ -393,6 +1032,7 @@ pub trait FuncCaller: GraphLike + AnalyzerLike<Expr = Expression> + Sized {
             subctx
         };
 
+        let adversarial_inputs = self.security_mode() && func_node.is_public_or_ext(self);
         let renamed_inputs = params
             .iter()
             .zip(inputs.iter())
@@ -421,6 +1061,15 @@ pub trait FuncCaller: GraphLike + AnalyzerLike<Expr = Expression> + Sized {
                             }
                         }
 
+                        if adversarial_inputs {
+                            // ignore whatever the call site passed in and assume the worst
+                            if let Some(param_ty) = VarType::try_from_idx(self, param.ty(self)) {
+                                new_cvar.ty = param_ty;
+                            }
+                            new_cvar.is_symbolic = true;
+                            new_cvar.is_tainted = true;
+                        }
+
                         let node = ContextVarNode::from(self.add_node(Node::ContextVar(new_cvar)));
 
                         if let (Some(r), Some(r2)) = (node.range(self), param.range(self)) {
@@ -445,7 +1094,12 @@ pub trait FuncCaller: GraphLike + AnalyzerLike<Expr = Expression> + Sized {
             mod_state.renamed_inputs = renamed_inputs.clone();
         }
 
-        if let Some(body) = func_node.underlying(self).body.clone() {
+        let body = if recursion_depth < MAX_RECURSION_DEPTH {
+            func_node.underlying(self).body.clone()
+        } else {
+            None
+        };
+        if let Some(body) = body {
             // add return nodes into the subctx
             func_node.returns(self).iter().for_each(|ret| {
                 if let Some(var) =
@@ -461,14 +1115,21 @@ pub trait FuncCaller: GraphLike + AnalyzerLike<Expr = Expression> + Sized {
             self.inherit_input_changes(loc, ctx, subctx, &renamed_inputs);
             self.inherit_storage_changes(ctx, subctx);
 
-            // adjust the output type to match the return type of the function call
-            ExprRet::Multi(
-                subctx
+            // a modifier evaluates to whatever the function it wraps returned
+            let rets = match &subctx.underlying(self).modifier_state {
+                Some(mod_state) if mod_state.parent_fn != func_node => mod_state.returned.clone(),
+                _ => subctx
                     .underlying(self)
                     .ret
-                    .clone()
-                    .into_iter()
-                    .map(|(_, node)| ExprRet::Single((ctx, node.into())))
+                    .iter()
+                    .map(|(_, node)| *node)
+                    .collect(),
+            };
+
+            // adjust the output type to match the return type of the function call
+            ExprRet::Multi(
+                rets.into_iter()
+                    .map(|node| ExprRet::Single((ctx, node.into())))
                     .collect(),
             )
         } else {
@@ -479,9 +1140,28 @@ pub trait FuncCaller: GraphLike + AnalyzerLike<Expr = Expression> + Sized {
                 func_node
                     .returns(self)
                     .iter()
-                    .filter_map(|ret| {
-                        let underlying = ret.underlying(self);
-                        let var = ContextVar::maybe_new_from_func_ret(self, underlying.clone())?;
+                    .enumerate()
+                    .filter_map(|(i, ret)| {
+                        let underlying = ret.underlying(self).clone();
+                        let var =
+                            match ContextVar::maybe_new_from_func_ret(self, underlying.clone()) {
+                                Some(var) => var,
+                                None => {
+                                    // unnamed return, i.e. `returns (uint256)` on an interface
+                                    let fn_name = func_node.name(self);
+                                    ContextVar {
+                                        display_name: format!("{fn_name}.{i}"),
+                                        storage: underlying.storage,
+                                        is_tmp: true,
+                                        is_symbolic: true,
+                                        ..ContextVar::new(
+                                            underlying.loc,
+                                            format!("tmp{}({fn_name}.{i})", ctx.new_tmp(self)),
+                                            VarType::try_from_idx(self, underlying.ty)?,
+                                        )
+                                    }
+                                }
+                            };
                         let node = self.add_node(Node::ContextVar(var));
                         Some(ExprRet::Single((ctx, node)))
                     })
@@ -496,11 +1176,26 @@ pub trait FuncCaller: GraphLike + AnalyzerLike<Expr = Expression> + Sized {
         ctx: ContextNode,
         func_node: FunctionNode,
         mod_state: ModifierState,
+        ext_call: bool,
     ) -> ExprRet {
         let input_exprs = func_node.modifier_input_vars(mod_state.num, self);
         let input_vars: Vec<_> = input_exprs
             .iter()
             .map(|expr| {
+                // the parameters of the modified function are only in scope of `ctx` if it is the
+                // function's own body, otherwise they are bound to the call's inputs
+                if let Expression::Variable(ident) = expr {
+                    if !mod_state.entry_call {
+                        if let Some(input) = mod_state
+                            .params
+                            .iter()
+                            .position(|param| param.maybe_name(self).as_ref() == Some(&ident.name))
+                            .and_then(|i| mod_state.inputs.get(i))
+                        {
+                            return input.latest_version(self);
+                        }
+                    }
+                }
                 let (_ctx, input) = self.parse_ctx_expr(expr, ctx).expect_single();
                 input.into()
             })
@@ -509,6 +1204,7 @@ pub trait FuncCaller: GraphLike + AnalyzerLike<Expr = Expression> + Sized {
         let mod_node = func_node.modifiers(self)[mod_state.num];
         self.execute_call_inner(
             false,
+            ext_call,
             mod_node.underlying(self).loc,
             ctx,
             mod_node,
@@ -532,6 +1228,7 @@ pub trait FuncCaller: GraphLike + AnalyzerLike<Expr = Expression> + Sized {
         // actually execute the parent function
         self.func_call_inner(
             modifier_state.entry_call,
+            false,
             modifier_state.parent_ctx,
             modifier_state.parent_fn,
             modifier_state.loc,
@@ -1,9 +1,13 @@
+use ethers_core::types::U256;
 use solang_parser::pt::Loc;
 use solang_parser::pt::Statement;
 
-use crate::context::ContextBuilder;
+use crate::context::exprs::checked_number_literal_val;
+use crate::context::{ContextBuilder, ExprRet};
 use shared::analyzer::GraphLike;
 use shared::context::*;
+use shared::nodes::{Builtin, Concrete};
+use shared::range::elem_ty::Elem;
 use shared::{analyzer::AnalyzerLike, Edge, Node, NodeIdx};
 use solang_parser::pt::Expression;
 
@@ -13,13 +17,69 @@ pub trait Looper: GraphLike + AnalyzerLike<Expr = Expression> + Sized {
         &mut self,
         loc: Loc,
         ctx: ContextNode,
-        _maybe_init: &Option<Box<Statement>>,
-        _maybe_limiter: &Option<Box<Expression>>,
-        _maybe_post: &Option<Box<Statement>>,
+        maybe_init: &Option<Box<Statement>>,
+        maybe_limiter: &Option<Box<Expression>>,
+        maybe_post: &Option<Box<Statement>>,
         maybe_body: &Option<Box<Statement>>,
     ) {
+        if let Some(init) = maybe_init {
+            self.parse_ctx_statement(init, false, Some(ctx));
+        }
+
+        let unrolled = self.unroll(
+            ctx,
+            maybe_limiter.as_deref(),
+            maybe_body.as_deref(),
+            maybe_post.as_deref(),
+        );
+        ctx.end_loop(self);
+        if unrolled {
+            return;
+        }
+
+        // a loop like `for (..; i < 10; i += 2)` lets us bound `i` by its start, step and limit,
+        // as long as it starts at a known value and only the post statement changes it
+        let induction = maybe_post
+            .as_deref()
+            .and_then(loop_step)
+            .filter(|(name, _)| {
+                !maybe_body
+                    .as_deref()
+                    .is_some_and(|body| assigns(body, name))
+            })
+            .and_then(|(name, step)| {
+                let var = ctx.latest_var_by_name(self, &name)?;
+                let start = var.evaled_range_min(self)?.maybe_concrete()?.val;
+                // `for (uint256 i; ..)` starts at zero, though the declaration leaves it unbounded
+                let zeroed = matches!(
+                    maybe_init.as_deref(),
+                    Some(Statement::VariableDefinition(_, decl, None))
+                        if decl.name.as_ref().is_some_and(|ident| ident.name == name)
+                );
+                if !zeroed && var.evaled_range_max(self)?.maybe_concrete()?.val != start {
+                    return None;
+                }
+                let (inclusive, limit) = match maybe_limiter.as_deref()? {
+                    Expression::Less(_, lhs, limit) if var_name(lhs)? == name => (false, limit),
+                    Expression::LessEqual(_, lhs, limit) if var_name(lhs)? == name => (true, limit),
+                    _ => return None,
+                };
+                // after a partial unroll, each fork may see a different limit
+                let limit = match self.parse_ctx_expr(limit, ctx) {
+                    ExprRet::Single((_, limit)) | ExprRet::SingleLiteral((_, limit)) => {
+                        ContextVarNode::from(limit)
+                    }
+                    _ => return None,
+                };
+                if !limit.is_const(self) {
+                    return None;
+                }
+                let limit = limit.evaled_range_max(self)?.maybe_concrete()?.val;
+                Some((name, start, step, limit.uint_val()?, inclusive))
+            });
+
         // TODO: improve this
-        if let Some(body) = maybe_body {
+        if maybe_body.is_some() || maybe_post.is_some() {
             let subctx = ContextNode::from(self.add_node(Node::Context(Context::new_subctx(
                 ctx, loc, false, None, false, self, None,
             ))));
@@ -31,23 +91,51 @@ pub trait Looper: GraphLike + AnalyzerLike<Expr = Expression> + Sized {
                 ctx_fork,
                 Edge::Context(ContextEdge::Subcontext),
             );
-            self.parse_ctx_statement(body, false, Some(subctx));
-            let vars = subctx.local_vars(self);
-            vars.iter().for_each(|var| {
-                // widen to max range
-                if let Some(inheritor_var) = ctx.var_by_name(self, &var.name(self)) {
-                    let inheritor_var = inheritor_var.latest_version(self);
-                    if let Some(r) = var.underlying(self).ty.default_range(self) {
-                        let new_inheritor_var = self.advance_var_in_ctx(inheritor_var, loc, ctx);
-                        new_inheritor_var.set_range_min(self, r.min);
-                        new_inheritor_var.set_range_max(self, r.max);
-                    }
-                }
+            if let Some(body) = maybe_body {
+                self.parse_ctx_statement(body, false, Some(subctx));
+            }
+            subctx.end_iteration(self);
+            if let Some(post) = maybe_post {
+                self.parse_ctx_statement(post, false, Some(subctx));
+            }
+            subctx.end_loop(self);
+            self.widen_loop_vars(loc, ctx, subctx);
+        }
+
+        if let Some((name, Concrete::Uint(size, start), step, limit, inclusive)) = induction {
+            // the value the induction variable has when the limiter first fails
+            let exit = if limit < start || (!inclusive && limit == start) {
+                Some(start)
+            } else {
+                // `span / step` rounded up for `<`, or plus one for `<=`, steps are taken
+                let span = limit - start;
+                let rounds_up = inclusive || !(span % step).is_zero();
+                (span / step)
+                    .checked_add(U256::from(rounds_up as u8))
+                    .and_then(|steps| steps.checked_mul(step))
+                    .and_then(|stepped| stepped.checked_add(start))
+            }
+            // past the type's max the post statement overflows before the limiter fails
+            .filter(|exit| {
+                Builtin::Uint(size)
+                    .max_value()
+                    .and_then(|max| max.uint_val())
+                    .is_some_and(|max| *exit <= max)
             });
+            if let (Some(exit), Some(var)) = (exit, ctx.latest_var_by_name(self, &name)) {
+                var.set_range_min(self, Elem::from(Concrete::Uint(size, start)));
+                var.set_range_max(self, Elem::from(Concrete::Uint(size, exit)));
+            }
         }
     }
 
-    fn while_loop(&mut self, loc: Loc, ctx: ContextNode, _limiter: &Expression, body: &Statement) {
+    fn while_loop(&mut self, loc: Loc, ctx: ContextNode, limiter: &Expression, body: &Statement) {
+        let unrolled = self.unroll(ctx, Some(limiter), Some(body), None);
+        ctx.end_loop(self);
+        if unrolled {
+            return;
+        }
+
         // TODO: improve this
         let subctx = ContextNode::from(self.add_node(Node::Context(Context::new_subctx(
             ctx, loc, false, None, false, self, None,
@@ -61,17 +149,287 @@ pub trait Looper: GraphLike + AnalyzerLike<Expr = Expression> + Sized {
             Edge::Context(ContextEdge::Subcontext),
         );
         self.parse_ctx_statement(body, false, Some(subctx));
-        let vars = subctx.local_vars(self);
-        vars.iter().for_each(|var| {
-            // widen to max range
-            if let Some(inheritor_var) = ctx.var_by_name(self, &var.name(self)) {
-                let inheritor_var = inheritor_var.latest_version(self);
-                if let Some(r) = var.underlying(self).ty.default_range(self) {
-                    let new_inheritor_var = self.advance_var_in_ctx(inheritor_var, loc, ctx);
-                    new_inheritor_var.set_range_min(self, r.min);
-                    new_inheritor_var.set_range_max(self, r.max);
+        subctx.end_loop(self);
+        self.widen_loop_vars(loc, ctx, subctx);
+    }
+
+    fn do_while_loop(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        body: &Statement,
+        limiter: &Expression,
+    ) {
+        // the body always runs once before the limiter is first checked
+        self.parse_ctx_statement(body, false, Some(ctx));
+        ctx.end_iteration(self);
+        self.while_loop(loc, ctx, limiter, body);
+    }
+
+    /// Widens the variables the loop body in `subctx` touched to their full range in `ctx`. A
+    /// partially unrolled loop may have forked `ctx`, in which case each live fork is widened
+    fn widen_loop_vars(&mut self, loc: Loc, ctx: ContextNode, subctx: ContextNode) {
+        let targets = ctx
+            .terminal_child_list(self)
+            .into_iter()
+            .filter(|target| !target.is_ended(self))
+            .collect::<Vec<_>>();
+        subctx.local_vars(self).iter().for_each(|var| {
+            let Some(r) = var.underlying(self).ty.default_range(self) else {
+                return;
+            };
+            targets.iter().for_each(|target| {
+                if let Some(inheritor_var) = target.var_by_name_or_recurse(self, &var.name(self)) {
+                    let inheritor_var = inheritor_var.latest_version(self);
+                    let new_inheritor_var = self.advance_var_in_ctx(inheritor_var, loc, *target);
+                    new_inheritor_var.set_range_min(self, r.min.clone());
+                    new_inheritor_var.set_range_max(self, r.max.clone());
                 }
-            }
+            });
         });
     }
+
+    /// Executes the loop in `ctx` one iteration at a time for as long as its limiter is known to
+    /// hold, up to `max_unroll` iterations. Returns whether the loop was run to completion, if
+    /// not the remaining iterations have to be widened. Forks that `break` out of the loop are
+    /// left suspended for the caller to resume once the loop is done
+    fn unroll(
+        &mut self,
+        ctx: ContextNode,
+        limiter: Option<&Expression>,
+        body: Option<&Statement>,
+        post: Option<&Statement>,
+    ) -> bool {
+        let Some(limiter) = limiter else {
+            return false;
+        };
+        if self.max_unroll() == 0 || body.is_some_and(exits_early) {
+            return false;
+        }
+
+        let mut iterations = 0;
+        loop {
+            // every path through the loop has either ended or hit a `break`
+            if ctx.is_ended(self) || ctx.is_suspended(self) {
+                return true;
+            }
+            match self.known_bool(limiter, ctx) {
+                Some(false) => return true,
+                Some(true) if iterations < self.max_unroll() => {}
+                _ => return false,
+            }
+            if let Some(body) = body {
+                self.parse_ctx_statement(body, false, Some(ctx));
+            }
+            ctx.end_iteration(self);
+            if let Some(post) = post {
+                self.parse_ctx_statement(post, false, Some(ctx));
+            }
+            iterations += 1;
+            self.record_unrolled_iteration();
+        }
+    }
+
+    /// Marks the live paths through `ctx` as having left the current loop iteration
+    fn exit_loop(&mut self, ctx: ContextNode, exit: LoopExit) {
+        let forks = ctx.live_forks(self);
+        if forks.is_empty() {
+            ctx.exit_loop(self, exit);
+        } else {
+            forks
+                .into_iter()
+                .for_each(|fork| fork.exit_loop(self, exit));
+        }
+    }
+
+    /// Evaluates a condition, returning its value if it is the same on every input
+    fn known_bool(&mut self, cond: &Expression, ctx: ContextNode) -> Option<bool> {
+        let cond = match self.parse_ctx_expr(cond, ctx) {
+            ExprRet::Single((_, cond)) | ExprRet::SingleLiteral((_, cond)) => {
+                ContextVarNode::from(cond)
+            }
+            _ => return None,
+        };
+        let min = cond.evaled_range_min(self)?.maybe_concrete()?.val;
+        let max = cond.evaled_range_max(self)?.maybe_concrete()?.val;
+        match (min, max) {
+            (Concrete::Bool(min), Concrete::Bool(max)) if min == max => Some(min),
+            _ => None,
+        }
+    }
+}
+
+/// Whether a loop body may return from the function, which unrolling cannot follow
+fn exits_early(stmt: &Statement) -> bool {
+    match stmt {
+        Statement::Return(..) => true,
+        Statement::Block { statements, .. } => statements.iter().any(exits_early),
+        Statement::If(_, _, true_stmt, false_stmt) => {
+            exits_early(true_stmt) || false_stmt.as_deref().is_some_and(exits_early)
+        }
+        Statement::For(_, _, _, _, body) => body.as_deref().is_some_and(exits_early),
+        Statement::While(_, _, body) | Statement::DoWhile(_, body, _) => exits_early(body),
+        _ => false,
+    }
+}
+
+/// Whether `stmt` may assign to the variable `name`. Assembly and `try` blocks are not looked
+/// into, so they are assumed to
+fn assigns(stmt: &Statement, name: &str) -> bool {
+    let in_expr = |expr: &Expression| expr_assigns(expr, name);
+    let in_stmt = |stmt: &Statement| assigns(stmt, name);
+    match stmt {
+        Statement::Block { statements, .. } => statements.iter().any(in_stmt),
+        Statement::If(_, cond, true_stmt, false_stmt) => {
+            in_expr(cond) || in_stmt(true_stmt) || false_stmt.as_deref().is_some_and(in_stmt)
+        }
+        Statement::While(_, cond, body) | Statement::DoWhile(_, body, cond) => {
+            in_expr(cond) || in_stmt(body)
+        }
+        Statement::For(_, init, cond, post, body) => {
+            init.as_deref().is_some_and(in_stmt)
+                || cond.as_deref().is_some_and(in_expr)
+                || post.as_deref().is_some_and(in_stmt)
+                || body.as_deref().is_some_and(in_stmt)
+        }
+        Statement::Expression(_, e) | Statement::Emit(_, e) => in_expr(e),
+        Statement::VariableDefinition(_, _, init) | Statement::Return(_, init) => {
+            init.as_ref().is_some_and(in_expr)
+        }
+        Statement::Revert(_, _, args) => args.iter().any(in_expr),
+        Statement::RevertNamedArgs(_, _, args) | Statement::Args(_, args) => {
+            args.iter().any(|arg| in_expr(&arg.expr))
+        }
+        Statement::Assembly { .. } | Statement::Try(..) => true,
+        Statement::Continue(_) | Statement::Break(_) | Statement::Error(_) => false,
+    }
+}
+
+/// Whether evaluating `in_expr` may assign to the variable `name`
+fn expr_assigns(expr: &Expression, name: &str) -> bool {
+    use Expression::*;
+    let sub = |expr: &Expression| expr_assigns(expr, name);
+    match expr {
+        PostIncrement(_, target)
+        | PostDecrement(_, target)
+        | PreIncrement(_, target)
+        | PreDecrement(_, target)
+        | Delete(_, target) => is_target(target, name) || sub(target),
+        Assign(_, target, rhs)
+        | AssignOr(_, target, rhs)
+        | AssignAnd(_, target, rhs)
+        | AssignXor(_, target, rhs)
+        | AssignShiftLeft(_, target, rhs)
+        | AssignShiftRight(_, target, rhs)
+        | AssignAdd(_, target, rhs)
+        | AssignSubtract(_, target, rhs)
+        | AssignMultiply(_, target, rhs)
+        | AssignDivide(_, target, rhs)
+        | AssignModulo(_, target, rhs) => is_target(target, name) || sub(target) || sub(rhs),
+        New(_, e)
+        | Parenthesis(_, e)
+        | MemberAccess(_, e, _)
+        | Not(_, e)
+        | Complement(_, e)
+        | UnaryPlus(_, e)
+        | UnaryMinus(_, e)
+        | Unit(_, e, _) => sub(e),
+        ArraySubscript(_, e, index) => sub(e) || index.as_deref().is_some_and(sub),
+        ArraySlice(_, e, from, to) => {
+            sub(e) || from.as_deref().is_some_and(sub) || to.as_deref().is_some_and(sub)
+        }
+        FunctionCall(_, func, args) => sub(func) || args.iter().any(sub),
+        FunctionCallBlock(_, func, block) => sub(func) || assigns(block, name),
+        NamedFunctionCall(_, func, args) => sub(func) || args.iter().any(|arg| sub(&arg.expr)),
+        Power(_, lhs, rhs)
+        | Multiply(_, lhs, rhs)
+        | Divide(_, lhs, rhs)
+        | Modulo(_, lhs, rhs)
+        | Add(_, lhs, rhs)
+        | Subtract(_, lhs, rhs)
+        | ShiftLeft(_, lhs, rhs)
+        | ShiftRight(_, lhs, rhs)
+        | BitwiseAnd(_, lhs, rhs)
+        | BitwiseXor(_, lhs, rhs)
+        | BitwiseOr(_, lhs, rhs)
+        | Less(_, lhs, rhs)
+        | More(_, lhs, rhs)
+        | LessEqual(_, lhs, rhs)
+        | MoreEqual(_, lhs, rhs)
+        | Equal(_, lhs, rhs)
+        | NotEqual(_, lhs, rhs)
+        | And(_, lhs, rhs)
+        | Or(_, lhs, rhs) => sub(lhs) || sub(rhs),
+        ConditionalOperator(_, cond, lhs, rhs) => sub(cond) || sub(lhs) || sub(rhs),
+        ArrayLiteral(_, elems) => elems.iter().any(sub),
+        List(_, params) => params
+            .iter()
+            .filter_map(|(_, param)| param.as_ref())
+            .any(|param| sub(&param.ty)),
+        BoolLiteral(..)
+        | NumberLiteral(..)
+        | RationalNumberLiteral(..)
+        | HexNumberLiteral(..)
+        | StringLiteral(..)
+        | Type(..)
+        | HexLiteral(..)
+        | AddressLiteral(..)
+        | Variable(..)
+        | This(..) => false,
+    }
+}
+
+/// Whether an assignment to `target` writes the variable `name`, including as part of a tuple,
+/// i.e. `(name, other) = ..`
+fn is_target(target: &Expression, name: &str) -> bool {
+    match target {
+        Expression::Variable(ident) => ident.name == name,
+        Expression::Parenthesis(_, inner) => is_target(inner, name),
+        Expression::List(_, params) => {
+            params
+                .iter()
+                .filter_map(|(_, param)| param.as_ref())
+                .any(|param| {
+                    is_target(&param.ty, name)
+                        || param.name.as_ref().is_some_and(|ident| ident.name == name)
+                })
+        }
+        _ => false,
+    }
+}
+
+/// Matches loop updates that step a variable up by a constant, i.e. `i++`, `i += 2`
+/// or `i = i + 2`, returning the variable name and the step
+fn loop_step(post: &Statement) -> Option<(String, U256)> {
+    let Statement::Expression(_, expr) = post else {
+        return None;
+    };
+    match expr {
+        Expression::PostIncrement(_, var) | Expression::PreIncrement(_, var) => {
+            Some((var_name(var)?, U256::from(1)))
+        }
+        Expression::AssignAdd(_, var, step) => Some((var_name(var)?, literal(step)?)),
+        Expression::Assign(_, var, rhs) => match &**rhs {
+            Expression::Add(_, lhs, step) if var_name(lhs) == var_name(var) => {
+                Some((var_name(var)?, literal(step)?))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+    .filter(|(_, step)| !step.is_zero())
+}
+
+fn var_name(expr: &Expression) -> Option<String> {
+    match expr {
+        Expression::Variable(ident) => Some(ident.name.clone()),
+        _ => None,
+    }
+}
+
+fn literal(expr: &Expression) -> Option<U256> {
+    match expr {
+        Expression::NumberLiteral(_, int, exp) => checked_number_literal_val(int, exp),
+        _ => None,
+    }
 }
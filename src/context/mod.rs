@@ -1,18 +1,20 @@
 use ethers_core::types::U256;
-use shared::analyzer::AsDotStr;
-use shared::analyzer::GraphLike;
+use shared::analyzer::{AsDotStr, Search};
 use shared::context::*;
 
 use shared::range::elem_ty::Dynamic;
 
 use shared::range::elem_ty::Elem;
-use shared::range::Range;
+use shared::range::{elem::RangeElem, Range, SolcRange};
 use solang_parser::pt::VariableDeclaration;
 
 use crate::VarType;
 use petgraph::{visit::EdgeRef, Direction};
 use shared::{analyzer::AnalyzerLike, nodes::*, range::elem::RangeOp, Edge, Node, NodeIdx};
-use solang_parser::pt::{Expression, Loc, Statement};
+use solang_parser::pt::{
+    Expression, FunctionTy, Identifier, IdentifierPath, Loc, Statement, StorageLocation,
+};
+use std::collections::BTreeSet;
 
 pub mod func;
 use func::*;
@@ -20,6 +22,12 @@ use func::*;
 pub mod loops;
 use loops::*;
 
+pub mod assembly;
+use assembly::*;
+
+pub mod try_catch;
+use try_catch::*;
+
 pub mod exprs;
 use exprs::*;
 
@@ -64,7 +72,16 @@ impl ExprRet {
         }
     }
 
-    pub fn try_as_func_input_str(&self, analyzer: &(impl GraphLike + AnalyzerLike)) -> String {
+    /// The same value, as seen from `ctx`, i.e. a variable from a parent context used in a fork
+    pub fn in_ctx(&self, ctx: ContextNode) -> ExprRet {
+        match self {
+            ExprRet::Single((_, idx)) => ExprRet::Single((ctx, *idx)),
+            ExprRet::SingleLiteral((_, idx)) => ExprRet::SingleLiteral((ctx, *idx)),
+            e => e.clone(),
+        }
+    }
+
+    pub fn try_as_func_input_str(&self, analyzer: &impl AnalyzerLike) -> String {
         match self {
             ExprRet::Single(inner) | ExprRet::SingleLiteral(inner) => {
                 let (_, idx) = inner;
@@ -107,7 +124,7 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
         &mut self,
         stmt: &Statement,
         unchecked: bool,
-        parent_ctx: Option<impl Into<NodeIdx> + Clone + Copy>,
+        parent_ctx: Option<impl Into<NodeIdx> + Copy>,
     ) where
         Self: Sized,
     {
@@ -115,14 +132,15 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
             match self.node(parent) {
                 Node::Context(_) => {
                     let ctx = ContextNode::from(parent.into());
-                    if ctx.is_ended(self) {
+                    if ctx.is_ended(self) || ctx.is_suspended(self) {
                         return;
                     }
                     if ctx.live_forks(self).is_empty() {
                         self.parse_ctx_stmt_inner(stmt, unchecked, parent_ctx)
                     } else {
+                        // forks may be forked themselves, the statement runs in the innermost ones
                         ctx.live_forks(self).iter().for_each(|fork_ctx| {
-                            self.parse_ctx_stmt_inner(stmt, unchecked, Some(*fork_ctx));
+                            self.parse_ctx_statement(stmt, unchecked, Some(*fork_ctx));
                         });
                     }
                 }
@@ -138,7 +156,7 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
         &mut self,
         stmt: &Statement,
         _unchecked: bool,
-        parent_ctx: Option<impl Into<NodeIdx> + Clone + Copy>,
+        parent_ctx: Option<impl Into<NodeIdx> + Copy>,
     ) where
         Self: Sized,
     {
@@ -192,10 +210,15 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
                     .collect::<Vec<FunctionParamNode>>()
                     .iter()
                     .filter_map(|param_node| {
-                        let func_param = param_node.underlying(self);
-                        if let Some(cvar) =
-                            ContextVar::maybe_new_from_func_param(self, func_param.clone())
+                        let func_param = param_node.underlying(self).clone();
+                        if let Some(name) = &func_param.name {
+                            self.record_shadowing(&name.name, func_param.loc, ctx_node.into());
+                        }
+                        if let Some(mut cvar) =
+                            ContextVar::maybe_new_from_func_param(self, func_param)
                         {
+                            cvar.is_tainted = self.security_mode()
+                                && FunctionNode::from(parent.into()).is_public_or_ext(self);
                             let cvar_node = self.add_node(Node::ContextVar(cvar));
                             self.add_edge(
                                 cvar_node,
@@ -231,8 +254,13 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
                     });
 
                 if let Some(fn_loc) = entry_loc {
+                    let func = FunctionNode::from(parent.into());
+                    if func.underlying(self).ty == FunctionTy::Constructor {
+                        self.call_base_constructors(ctx_node.into(), func, fn_loc);
+                    }
                     self.func_call_inner(
                         true,
+                        false,
                         ctx_node.into(),
                         parent.into().into(),
                         fn_loc,
@@ -243,7 +271,14 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
                     return;
                 }
 
-                let forks = ContextNode::from(ctx_node).live_forks(self);
+                let ctx = ContextNode::from(ctx_node);
+                // solidity disallows nesting `unchecked` blocks, so leaving one always
+                // returns to checked arithmetic, even for forks created inside the block
+                if *unchecked {
+                    ctx.set_unchecked(true, self);
+                }
+
+                let forks = ctx.live_forks(self);
                 if forks.is_empty() {
                     statements.iter().for_each(|stmt| {
                         self.parse_ctx_statement(stmt, *unchecked, Some(ctx_node))
@@ -255,6 +290,10 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
                         });
                     });
                 }
+
+                if *unchecked {
+                    ctx.set_unchecked(false, self);
+                }
             }
             VariableDefinition(loc, var_decl, maybe_expr) => {
                 let ctx = ContextNode::from(
@@ -300,8 +339,23 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
                     self.while_loop(*loc, parent.into().into(), cond, body);
                 }
             }
-            Expression(_loc, expr) => {
+            Expression(loc, expr) => {
                 if let Some(parent) = parent_ctx {
+                    if matches!(
+                        expr,
+                        solang_parser::pt::Expression::Less(..)
+                            | solang_parser::pt::Expression::More(..)
+                            | solang_parser::pt::Expression::LessEqual(..)
+                            | solang_parser::pt::Expression::MoreEqual(..)
+                            | solang_parser::pt::Expression::Equal(..)
+                            | solang_parser::pt::Expression::NotEqual(..)
+                    ) {
+                        self.add_warning(
+                            *loc,
+                            "Result of comparison is discarded, did you mean to `require` it?"
+                                .to_string(),
+                        );
+                    }
                     let _paths = self.parse_ctx_expr(expr, ContextNode::from(parent.into()));
                 }
             }
@@ -317,22 +371,30 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
                     );
                 }
             }
-            DoWhile(_loc, _while_stmt, _while_expr) => {
-                todo!("do while not supported");
+            DoWhile(loc, body, limiter) => {
+                if let Some(parent) = parent_ctx {
+                    self.do_while_loop(*loc, parent.into().into(), body, limiter);
+                }
             }
-            Continue(_loc) => {
-                // TODO: We cheat in loops by just widening so continues dont matter yet
+            Continue(loc) => {
+                if let Some(parent) = parent_ctx {
+                    self.exit_loop(ContextNode::from(parent.into()), LoopExit::Continue(*loc));
+                }
             }
-            Break(_loc) => {
-                // TODO: We cheat in loops by just widening so breaks dont matter yet
+            Break(loc) => {
+                if let Some(parent) = parent_ctx {
+                    self.exit_loop(ContextNode::from(parent.into()), LoopExit::Break(*loc));
+                }
             }
             Assembly {
-                loc: _,
+                loc,
                 dialect: _,
                 flags: _,
-                block: _yul_block,
+                block: yul_block,
             } => {
-                todo!("assembly not supported")
+                if let Some(parent) = parent_ctx {
+                    self.assembly_block(*loc, yul_block, ContextNode::from(parent.into()));
+                }
             }
             Return(loc, maybe_ret_expr) => {
                 if let Some(ret_expr) = maybe_ret_expr {
@@ -376,83 +438,226 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
                             });
                         }
                     }
+                } else if let Some(parent) = parent_ctx {
+                    // a naked `return;` returns the named return variables as they are
+                    let parent = ContextNode::from(parent.into());
+                    let forks = parent.live_forks(self);
+                    let ctxs = if forks.is_empty() {
+                        vec![parent]
+                    } else {
+                        forks
+                    };
+                    ctxs.into_iter().for_each(|ctx| {
+                        let rets = ctx
+                            .executing_fn(self)
+                            .returns(self)
+                            .iter()
+                            .filter_map(|ret| {
+                                let name = ret.maybe_name(self)?;
+                                let var = ctx.var_by_name_or_recurse(self, &name)?;
+                                Some(ExprRet::Single((ctx, var.latest_version(self).into())))
+                            })
+                            .collect();
+                        self.return_match(loc, &ExprRet::Multi(rets));
+                    });
+                }
+            }
+            Revert(loc, maybe_err_path, exprs) => {
+                if let Some(parent) = parent_ctx {
+                    let parent = ContextNode::from(parent.into());
+                    let forks = parent.live_forks(self);
+                    let ctxs = if forks.is_empty() {
+                        vec![parent]
+                    } else {
+                        forks
+                    };
+                    ctxs.into_iter().for_each(|ctx| {
+                        self.revert_stmt(*loc, maybe_err_path.as_ref(), exprs, ctx)
+                    });
                 }
             }
-            Revert(loc, _maybe_err_path, _exprs) => {
+            RevertNamedArgs(loc, maybe_err_path, named_args) => {
                 if let Some(parent) = parent_ctx {
                     let parent = ContextNode::from(parent.into());
+                    // `revert E({b: 2, a: 1})` is `revert E(1, 2)` once the arguments are put in
+                    // parameter order
+                    let params = maybe_err_path
+                        .as_ref()
+                        .and_then(|path| self.resolve_error(path, parent))
+                        .map(|err| {
+                            err.params(self)
+                                .iter()
+                                .map(|param| {
+                                    let name = param.underlying(self).name.as_ref();
+                                    name.map(|name| name.name.clone()).unwrap_or_default()
+                                })
+                                .collect::<Vec<_>>()
+                        });
+                    let ordered = params.as_ref().and_then(|params| {
+                        if params.len() != named_args.len() {
+                            return None;
+                        }
+                        params
+                            .iter()
+                            .map(|param| {
+                                let arg = named_args.iter().find(|arg| arg.name.name == *param)?;
+                                Some(arg.expr.clone())
+                            })
+                            .collect::<Option<Vec<_>>>()
+                    });
+                    let exprs = ordered.unwrap_or_else(|| {
+                        if params.is_some() {
+                            self.add_warning(
+                                *loc,
+                                "Named arguments do not match the parameters of the error"
+                                    .to_string(),
+                            );
+                        }
+                        named_args.iter().map(|arg| arg.expr.clone()).collect()
+                    });
                     let forks = parent.live_forks(self);
+                    let ctxs = if forks.is_empty() {
+                        vec![parent]
+                    } else {
+                        forks
+                    };
+                    ctxs.into_iter().for_each(|ctx| {
+                        self.revert_stmt(*loc, maybe_err_path.as_ref(), &exprs, ctx)
+                    });
+                }
+            }
+            Emit(loc, emit_expr) => {
+                if let Some(parent) = parent_ctx {
+                    self.emit_event(*loc, emit_expr, ContextNode::from(parent.into()));
+                }
+            }
+            Try(loc, try_expr, maybe_returns, clauses) => {
+                if let Some(parent) = parent_ctx {
+                    let ctx = ContextNode::from(parent.into());
+                    let forks = ctx.live_forks(self);
                     if forks.is_empty() {
-                        parent.kill(self, *loc);
+                        self.try_catch_stmt(*loc, try_expr, maybe_returns, clauses, ctx)
                     } else {
                         forks.into_iter().for_each(|parent| {
-                            parent.kill(self, *loc);
-                        });
+                            self.try_catch_stmt(*loc, try_expr, maybe_returns, clauses, parent)
+                        })
                     }
                 }
             }
-            RevertNamedArgs(_loc, _maybe_err_path, _named_args) => {}
-            Emit(_loc, _emit_expr) => {}
-            Try(_loc, _try_expr, _maybe_returns, _clauses) => {}
             Error(_loc) => {}
         };
 
         if let Some(parent) = parent_ctx {
-            if let Node::Context(c) = self.node(parent.into()) {
-                let adjusts = c.post_statement_range_adjs.clone();
-                adjusts.into_iter().for_each(|(var, loc, increment)| {
-                    let one_node = self.add_node(Node::Concrete(Concrete::from(U256::from(1))));
-                    let one_node = self.add_node(Node::ContextVar(ContextVar::new_from_concrete(
-                        Loc::Implicit,
-                        one_node.into(),
-                        self,
-                    )));
-                    let (_, _var) = self
-                        .op(
-                            loc,
-                            var.latest_version(self),
-                            one_node.into(),
-                            parent.into().into(),
-                            if increment {
-                                RangeOp::Add
-                            } else {
-                                RangeOp::Sub
-                            },
-                            true,
-                        )
-                        .expect_single();
+            if let Node::Context(_) = self.node(parent.into()) {
+                // the statement may have run in the forks of `parent`, each of which holds the
+                // adjustments for its own versions of the variables
+                let parent = ContextNode::from(parent.into());
+                let mut ctxs = parent.terminal_child_list(self);
+                if !ctxs.contains(&parent) {
+                    ctxs.push(parent);
+                }
+                ctxs.into_iter().for_each(|ctx| {
+                    let adjusts =
+                        std::mem::take(&mut ctx.underlying_mut(self).post_statement_range_adjs);
+                    adjusts.into_iter().for_each(|(var, loc, increment)| {
+                        let one_node = self.concrete_or_add(Concrete::from(U256::from(1)));
+                        let one_node = self.add_node(Node::ContextVar(
+                            ContextVar::new_from_concrete(loc, one_node.into(), self),
+                        ));
+                        let (_, _var) = self
+                            .op(
+                                loc,
+                                var.latest_version(self),
+                                one_node.into(),
+                                ctx,
+                                if increment {
+                                    RangeOp::Add
+                                } else {
+                                    RangeOp::Sub
+                                },
+                                true,
+                            )
+                            .expect_single();
+                    });
                 });
-                ContextNode::from(parent.into())
-                    .underlying_mut(self)
-                    .post_statement_range_adjs = vec![];
             }
         }
     }
 
+    /// Evaluates the arguments of an `emit` and links the context to the event it emits. The
+    /// event is looked up in the contract named by `emit C.Ev(..)`, the context's contract and
+    /// its bases, then the source unit
+    fn emit_event(&mut self, loc: Loc, emit_expr: &Expression, ctx: ContextNode) {
+        let Expression::FunctionCall(_, func_expr, input_exprs) = emit_expr else {
+            self.add_warning(loc, "Unsupported emit expression".to_string());
+            return;
+        };
+        input_exprs.iter().for_each(|expr| {
+            let _ = self.parse_ctx_expr(expr, ctx);
+        });
+
+        let (scope, ident) = match &**func_expr {
+            Expression::Variable(ident) => (ctx.maybe_associated_contract(self), ident),
+            Expression::MemberAccess(_, base, ident) => {
+                let scope = match &**base {
+                    Expression::Variable(base) => self
+                        .user_types()
+                        .get(&base.name)
+                        .filter(|idx| matches!(self.node(**idx), Node::Contract(_)))
+                        .map(|idx| ContractNode::from(*idx)),
+                    _ => None,
+                };
+                (scope, ident)
+            }
+            _ => {
+                self.add_warning(loc, "Unsupported emit expression".to_string());
+                return;
+            }
+        };
+
+        let mut events = scope
+            .map(|contract| contract.with_bases(self))
+            .unwrap_or_default()
+            .iter()
+            .flat_map(|contract| contract.events(self))
+            .collect::<Vec<_>>();
+        if let Some(source) = ctx.associated_source(self) {
+            // file level events hang off of the parts of the source unit
+            events.extend(
+                self.graph()
+                    .edges_directed(source, Direction::Incoming)
+                    .filter(|edge| *edge.weight() == Edge::Part)
+                    .flat_map(|part| {
+                        self.graph()
+                            .edges_directed(part.source(), Direction::Incoming)
+                    })
+                    .filter(|edge| *edge.weight() == Edge::Event)
+                    .map(|edge| EventNode::from(edge.source())),
+            );
+        }
+
+        // overloaded events are told apart by their number of parameters
+        match events.into_iter().find(|event| {
+            event.name(self) == ident.name && event.params(self).len() == input_exprs.len()
+        }) {
+            Some(event) => self.add_edge(ctx, event, Edge::Context(ContextEdge::Emit)),
+            None => self.add_warning(
+                loc,
+                format!("Could not resolve emitted event: {}", ident.name),
+            ),
+        }
+    }
+
     fn return_match(&mut self, loc: &Loc, paths: &ExprRet) {
         match paths {
             ExprRet::CtxKilled => {}
             ExprRet::Single((ctx, expr)) | ExprRet::SingleLiteral((ctx, expr)) => {
-                self.add_edge(
-                    ContextVarNode::from(*expr).latest_version(self),
-                    *ctx,
-                    Edge::Context(ContextEdge::Return),
-                );
-                ctx.add_return_node(*loc, ContextVarNode::from(*expr).latest_version(self), self);
+                self.add_return(*loc, *ctx, 0, ContextVarNode::from(*expr));
             }
             ExprRet::Multi(rets) => {
-                rets.iter().for_each(|expr_ret| {
+                rets.iter().enumerate().for_each(|(i, expr_ret)| {
                     let (ctx, expr) = expr_ret.expect_single();
-                    self.add_edge(
-                        ContextVarNode::from(expr).latest_version(self),
-                        ctx,
-                        Edge::Context(ContextEdge::Return),
-                    );
-                    ctx.add_return_node(
-                        *loc,
-                        ContextVarNode::from(expr).latest_version(self),
-                        self,
-                    );
+                    self.add_return(*loc, ctx, i, ContextVarNode::from(expr));
                 });
             }
             ExprRet::Fork(world1, world2) => {
@@ -462,6 +667,58 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
         }
     }
 
+    /// Returns `var` as the `i`th return value of the context's function, widening the range of
+    /// the function's return parameter to cover it
+    fn add_return(&mut self, loc: Loc, ctx: ContextNode, i: usize, var: ContextVarNode) {
+        let var = var.latest_version(self);
+        self.add_edge(var, ctx, Edge::Context(ContextEdge::Return));
+        ctx.add_return_node(loc, var, self);
+
+        let Some(ret) = ctx.executing_fn(self).returns(self).get(i).copied() else {
+            return;
+        };
+        let Some(range) = var.range(self) else {
+            return;
+        };
+        // the returned variable's bounds only mean something in this context, so keep them evaluated
+        let (min, max) = (range.evaled_range_min(self), range.evaled_range_max(self));
+        let range = match ret.range(self) {
+            Some(prev) => SolcRange {
+                min: prev.range_min().min(min).minimize(self),
+                max: prev.range_max().max(max).maximize(self),
+                exclusions: vec![],
+            },
+            None => SolcRange {
+                min,
+                max,
+                exclusions: vec![],
+            },
+        };
+        if let Node::FunctionReturn(underlying) = self.node_mut(ret) {
+            underlying.range = Some(range);
+        }
+    }
+
+    /// Records `name` as shadowing a state variable if the contract of `ctx`, or one of its
+    /// bases, declares a state variable of that name
+    fn record_shadowing(&mut self, name: &str, loc: Loc, ctx: ContextNode) {
+        let Some(state_var) = ctx
+            .maybe_associated_contract(self)
+            .and_then(|contract| contract.state_var(self, name))
+        else {
+            return;
+        };
+        let state_var_loc = state_var.underlying(self).loc;
+        ctx.add_shadowing(
+            Shadowing {
+                loc,
+                name: name.to_string(),
+                state_var_loc,
+            },
+            self,
+        );
+    }
+
     fn match_var_def(
         &mut self,
         var_decl: &VariableDeclaration,
@@ -485,6 +742,7 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
             }
             (ExprRet::Single((_lhs_ctx, ty)), Some(ExprRet::Single((rhs_ctx, rhs)))) => {
                 let name = var_decl.name.clone().expect("Variable wasn't named");
+                self.record_shadowing(&name.name, var_decl.loc, *rhs_ctx);
                 let ty = VarType::try_from_idx(self, *ty).expect("Not a known type");
                 // if let VarType::Array(_, ref mut range) = ty {
                 //     *range = Some(self.tmp_length(ContextVarNode::from(*rhs), *rhs_ctx, loc))
@@ -508,33 +766,31 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
                 // }
 
                 let var = ContextVar {
-                    loc: Some(loc),
-                    name: name.to_string(),
-                    display_name: name.to_string(),
                     storage: var_decl.storage.clone(),
-                    is_tmp: false,
                     is_symbolic: true,
-                    tmp_of: None,
-                    ty,
+                    ..ContextVar::new(loc, name.to_string(), ty)
                 };
                 let lhs = ContextVarNode::from(self.add_node(Node::ContextVar(var)));
                 self.add_edge(lhs, *rhs_ctx, Edge::Context(ContextEdge::Variable));
                 let rhs = ContextVarNode::from(*rhs);
                 let (_, new_lhs) = self.assign(loc, lhs, rhs, *rhs_ctx).expect_single();
                 self.add_edge(new_lhs, *rhs_ctx, Edge::Context(ContextEdge::Variable));
+                // a local `storage` variable references what it is initialized with, it isn't
+                // a copy
+                if matches!(var_decl.storage, Some(StorageLocation::Storage(_)))
+                    && rhs.is_storage(self)
+                {
+                    self.add_edge(new_lhs, rhs, Edge::Context(ContextEdge::StorageAlias));
+                }
             }
             (ExprRet::Single((lhs_ctx, ty)), None) => {
                 let name = var_decl.name.clone().expect("Variable wasn't named");
+                self.record_shadowing(&name.name, var_decl.loc, *lhs_ctx);
                 let ty = VarType::try_from_idx(self, *ty).expect("Not a known type");
                 let var = ContextVar {
-                    loc: Some(loc),
-                    name: name.to_string(),
-                    display_name: name.to_string(),
                     storage: var_decl.storage.clone(),
-                    is_tmp: false,
                     is_symbolic: true,
-                    tmp_of: None,
-                    ty,
+                    ..ContextVar::new(loc, name.to_string(), ty)
                 };
                 let lhs = ContextVarNode::from(self.add_node(Node::ContextVar(var)));
                 self.add_edge(lhs, *lhs_ctx, Edge::Context(ContextEdge::Variable));
@@ -609,7 +865,7 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
     }
 
     fn parse_ctx_expr(&mut self, expr: &Expression, ctx: ContextNode) -> ExprRet {
-        if ctx.is_ended(self) {
+        if ctx.is_ended(self) || ctx.is_suspended(self) {
             return ExprRet::CtxKilled;
         }
 
@@ -644,11 +900,20 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
             BoolLiteral(loc, b) => self.bool_literal(ctx, *loc, *b),
             HexNumberLiteral(loc, b) => self.hex_num_literal(ctx, *loc, b, false),
             HexLiteral(hexes) => self.hex_literals(ctx, hexes),
-            RationalNumberLiteral(_, _, _, _) => todo!("Rational literal"),
-            UnaryMinus(_loc, expr) => match &**expr {
+            RationalNumberLiteral(loc, int, frac, exp) => {
+                self.rational_number_literal(ctx, *loc, int, frac, exp, U256::from(1), false)
+            }
+            UnaryMinus(loc, expr) => match &**expr {
                 NumberLiteral(loc, int, exp) => self.number_literal(ctx, *loc, int, exp, true),
                 HexNumberLiteral(loc, b) => self.hex_num_literal(ctx, *loc, b, true),
-                e => todo!("UnaryMinus unexpected rhs: {e:?}"),
+                RationalNumberLiteral(loc, int, frac, exp) => {
+                    self.rational_number_literal(ctx, *loc, int, frac, exp, U256::from(1), true)
+                }
+                // `-x` is `0 - x`, the operand is a signed integer
+                e => {
+                    let zero = NumberLiteral(*loc, "0".to_string(), "".to_string());
+                    self.parse_ctx_expr(&Subtract(*loc, Box::new(zero), Box::new(e.clone())), ctx)
+                }
             },
             UnaryPlus(_loc, e) => todo!("UnaryPlus unexpected rhs: {e:?}"),
 
@@ -721,7 +986,7 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
             AssignOr(loc, lhs_expr, rhs_expr) => {
                 self.op_expr(*loc, lhs_expr, rhs_expr, ctx, RangeOp::BitOr, true)
             }
-            Complement(_loc, _expr) => todo!("Complement"),
+            Complement(loc, expr) => self.bit_not(*loc, expr, ctx),
 
             // assign
             Assign(loc, lhs_expr, rhs_expr) => self.assign_exprs(*loc, lhs_expr, rhs_expr, ctx),
@@ -732,7 +997,7 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
                 self.index_into_array(*loc, ty_expr, index_expr, ctx)
             }
             ArraySlice(_loc, _lhs_expr, _maybe_middle_expr, _maybe_rhs) => todo!("Array slice"),
-            ArrayLiteral(_, _) => todo!("Array literal"),
+            ArrayLiteral(loc, elems) => self.array_literal(*loc, elems, ctx),
 
             // Comparator
             Equal(loc, lhs, rhs) => self.cmp(*loc, lhs, RangeOp::Eq, rhs, ctx),
@@ -748,34 +1013,166 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
             Or(loc, lhs, rhs) => self.cmp(*loc, lhs, RangeOp::Or, rhs, ctx),
 
             // Function calls
-            FunctionCallBlock(_loc, _func_expr, _input_exprs) => todo!("Function call block"),
-            NamedFunctionCall(_loc, _func_expr, _input_exprs) => todo!("Named function call"),
+            FunctionCallBlock(_loc, func_expr, block) => {
+                if self.parse_call_options(block, ctx).is_none() {
+                    return ExprRet::CtxKilled;
+                }
+                self.parse_ctx_expr(func_expr, ctx)
+            }
+            NamedFunctionCall(loc, func_expr, input_args) => {
+                // `f({b: 2, a: 1})` is `f(1, 2)` once the arguments are put in parameter order
+                let candidates = self.named_call_candidates(func_expr, ctx);
+                let given: BTreeSet<&str> = input_args
+                    .iter()
+                    .map(|arg| arg.name.name.as_str())
+                    .collect();
+                let matching = candidates.iter().find(|params| {
+                    params.len() == input_args.len()
+                        && params.iter().all(|param| given.contains(param.as_str()))
+                });
+                if let Some(params) = matching {
+                    let ordered = params
+                        .iter()
+                        .filter_map(|param| input_args.iter().find(|arg| arg.name.name == *param))
+                        .map(|arg| arg.expr.clone())
+                        .collect();
+                    return self
+                        .parse_ctx_expr(&FunctionCall(*loc, func_expr.clone(), ordered), ctx);
+                }
+
+                let msg = match &candidates[..] {
+                    [params] => {
+                        let unknown = given
+                            .iter()
+                            .filter(|name| !params.iter().any(|param| param == *name))
+                            .map(|name| format!("\"{name}\""))
+                            .collect::<Vec<_>>();
+                        let missing = params
+                            .iter()
+                            .filter(|param| !given.contains(param.as_str()))
+                            .map(|param| format!("\"{param}\""))
+                            .collect::<Vec<_>>();
+                        let mut problems = vec![];
+                        if !unknown.is_empty() {
+                            problems.push(format!("unknown {}", unknown.join(", ")));
+                        }
+                        if !missing.is_empty() {
+                            problems.push(format!("missing {}", missing.join(", ")));
+                        }
+                        if problems.is_empty() {
+                            problems.push("an argument is given more than once".to_string());
+                        }
+                        format!(
+                            "Named arguments do not match the callee: {}",
+                            problems.join("; ")
+                        )
+                    }
+                    _ => "Named arguments do not match the parameters of any callee".to_string(),
+                };
+                self.add_warning(*loc, msg);
+                ctx.kill(self, *loc, KilledKind::Revert);
+                ExprRet::CtxKilled
+            }
             FunctionCall(loc, func_expr, input_exprs) => {
                 match &**func_expr {
-                    MemberAccess(loc, _member_expr, _ident) => {
-                        let (_func_ctx, func_idx) = match self.parse_ctx_expr(func_expr, ctx) {
-                            ExprRet::Single((ctx, idx)) => (ctx, idx),
-                            m @ ExprRet::Multi(_) => m.expect_single(),
-                            ExprRet::CtxKilled => return ExprRet::CtxKilled,
-                            e => todo!("got fork in func call: {:?}", e),
+                    FunctionCallBlock(_, inner, block) => {
+                        // `c.f{value: v}(x)` is `c.f(x)` once its options are evaluated, with the
+                        // options attached to what the call returns
+                        let Some(options) = self.parse_call_options(block, ctx) else {
+                            return ExprRet::CtxKilled;
                         };
-
-                        let inputs = ExprRet::Multi(
-                            input_exprs
-                                .iter()
-                                .map(|expr| self.parse_ctx_expr(expr, ctx))
-                                .collect(),
-                        );
-
-                        self.func_call(
-                            ctx,
-                            *loc,
-                            &inputs,
-                            ContextVarNode::from(func_idx)
-                                .ty(self)
-                                .func_node(self)
-                                .expect(""),
-                        )
+                        let ret = match &**inner {
+                            MemberAccess(member_loc, member_expr, member) if !matches!(&**member_expr, Variable(Identifier { name, .. }) if name == "abi") =>
+                            {
+                                let mut on_callee = |analyzer: &mut Self, func_ctx, func_idx| {
+                                    if let Some((_, value)) =
+                                        options.iter().find(|(name, _)| name == "value")
+                                    {
+                                        analyzer.check_payable(*loc, func_idx, *value);
+                                        analyzer.record_value_transfer(
+                                            *loc,
+                                            &member.name,
+                                            *value,
+                                            func_ctx,
+                                        );
+                                    }
+                                };
+                                let func = self.parse_ctx_expr(inner, ctx);
+                                if let Some(overloads) = self.overload_set(&func) {
+                                    self.call_overload(
+                                        member_loc,
+                                        overloads,
+                                        input_exprs,
+                                        ctx,
+                                        &mut on_callee,
+                                    )
+                                } else {
+                                    self.map_single(
+                                        *loc,
+                                        ctx,
+                                        func,
+                                        &mut |analyzer, func_ctx, func_idx| {
+                                            on_callee(analyzer, func_ctx, func_idx);
+                                            analyzer.member_func_call(
+                                                member_loc,
+                                                input_exprs,
+                                                func_idx,
+                                                func_ctx,
+                                                ctx,
+                                            )
+                                        },
+                                    )
+                                }
+                            }
+                            _ => self.parse_ctx_expr(
+                                &FunctionCall(*loc, inner.clone(), input_exprs.clone()),
+                                ctx,
+                            ),
+                        };
+                        self.attach_call_options(&ret, &options);
+                        ret
+                    }
+                    MemberAccess(loc, member_expr, ident) if matches!(&**member_expr, Variable(Identifier { name, .. }) if name == "super") =>
+                    {
+                        let possible_funcs = self.super_funcs(ident, ctx);
+                        if possible_funcs.is_empty() {
+                            let msg = format!(
+                                "No function named {} in the bases of the contract",
+                                ident.name
+                            );
+                            self.unsupported_value(*loc, msg, ctx)
+                        } else {
+                            let possible_funcs = possible_funcs.iter().collect::<Vec<_>>();
+                            self.call_possible_funcs(loc, ident, input_exprs, &possible_funcs, ctx)
+                        }
+                    }
+                    MemberAccess(loc, member_expr, ident) if matches!(&**member_expr, Variable(Identifier { name, .. }) if name == "abi") =>
+                    {
+                        let (func_ctx, func_idx) = self
+                            .variable(
+                                &Identifier {
+                                    loc: *loc,
+                                    name: format!("abi.{}", ident.name),
+                                },
+                                ctx,
+                            )
+                            .expect_single();
+                        self.intrinsic_func_call(loc, input_exprs, func_idx, func_ctx)
+                    }
+                    MemberAccess(loc, _member_expr, _ident) => {
+                        let func = self.parse_ctx_expr(func_expr, ctx);
+                        if let Some(overloads) = self.overload_set(&func) {
+                            return self.call_overload(
+                                loc,
+                                overloads,
+                                input_exprs,
+                                ctx,
+                                &mut |_, _, _| {},
+                            );
+                        }
+                        self.map_single(*loc, ctx, func, &mut |analyzer, func_ctx, func_idx| {
+                            analyzer.member_func_call(loc, input_exprs, func_idx, func_ctx, ctx)
+                        })
                     }
                     Variable(ident) => {
                         // It is a function call, check if we have the ident in scope
@@ -788,73 +1185,38 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
 
                         if possible_funcs.is_empty() {
                             // this is a builtin, cast, or unknown function?
-                            let (func_ctx, func_idx) = match self.parse_ctx_expr(func_expr, ctx) {
-                                ExprRet::Single((ctx, idx)) => (ctx, idx),
-                                m @ ExprRet::Multi(_) => m.expect_single(),
-                                ExprRet::CtxKilled => return ExprRet::CtxKilled,
-                                e => todo!("got fork in func call: {:?}", e),
-                            };
-                            self.intrinsic_func_call(loc, input_exprs, func_idx, func_ctx)
-                        } else if possible_funcs.len() == 1 {
-                            let inputs = ExprRet::Multi(
-                                input_exprs
-                                    .iter()
-                                    .map(|expr| self.parse_ctx_expr(expr, ctx))
-                                    .collect(),
-                            );
-                            self.setup_fn_call(
-                                &ident.loc,
-                                &inputs,
-                                (*possible_funcs[0]).into(),
-                                ctx,
-                            )
+                            let func = self.parse_ctx_expr(func_expr, ctx);
+                            self.map_single(*loc, ctx, func, &mut |analyzer, func_ctx, func_idx| {
+                                analyzer.intrinsic_func_call(loc, input_exprs, func_idx, func_ctx)
+                            })
                         } else {
-                            // this is the annoying case due to function overloading & type inference on number literals
-                            let lits = input_exprs
-                                .iter()
-                                .map(|expr| {
-                                    match expr {
-                                        UnaryMinus(_, expr) => {
-                                            // negative number potentially
-                                            matches!(**expr, NumberLiteral(..) | HexLiteral(..))
-                                        }
-                                        NumberLiteral(..) | HexLiteral(..) => true,
-                                        _ => false,
-                                    }
-                                })
-                                .collect();
-                            let inputs = ExprRet::Multi(
-                                input_exprs
-                                    .iter()
-                                    .map(|expr| self.parse_ctx_expr(expr, ctx))
-                                    .collect(),
-                            );
-
-                            if let Some(func) = self.disambiguate_fn_call(
-                                &ident.name,
-                                lits,
-                                &inputs,
-                                &possible_funcs,
-                            ) {
-                                self.setup_fn_call(loc, &inputs, func.into(), ctx)
-                            } else {
-                                ExprRet::CtxKilled
-                            }
+                            self.call_possible_funcs(loc, ident, input_exprs, &possible_funcs, ctx)
                         }
                     }
                     _ => {
-                        let (func_ctx, func_idx) = match self.parse_ctx_expr(func_expr, ctx) {
-                            ExprRet::Single((ctx, idx)) => (ctx, idx),
-                            m @ ExprRet::Multi(_) => m.expect_single(),
-                            ExprRet::CtxKilled => return ExprRet::CtxKilled,
-                            e => todo!("got fork in func call: {:?}", e),
-                        };
-                        self.intrinsic_func_call(loc, input_exprs, func_idx, func_ctx)
+                        let func = self.parse_ctx_expr(func_expr, ctx);
+                        if let Some(overloads) = self.overload_set(&func) {
+                            return self.call_overload(
+                                loc,
+                                overloads,
+                                input_exprs,
+                                ctx,
+                                &mut |_, _, _| {},
+                            );
+                        }
+                        self.map_single(*loc, ctx, func, &mut |analyzer, func_ctx, func_idx| {
+                            analyzer.intrinsic_func_call(loc, input_exprs, func_idx, func_ctx)
+                        })
                     }
                 }
             }
             // member
-            New(_loc, expr) => self.parse_ctx_expr(expr, ctx),
+            New(_loc, expr) => match &**expr {
+                FunctionCall(loc, ty_expr, input_exprs) => {
+                    self.new_call(*loc, ty_expr, input_exprs, ctx)
+                }
+                _ => self.parse_ctx_expr(expr, ctx),
+            },
             This(loc) => {
                 let var = ContextVar::new_from_contract(*loc, ctx.associated_contract(self), self);
                 let cvar = self.add_node(Node::ContextVar(var));
@@ -865,7 +1227,10 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
                 self.member_access(*loc, member_expr, ident, ctx)
             }
 
-            Delete(_loc, _expr) => todo!("Delete"),
+            Delete(loc, expr) => {
+                let resp = self.parse_ctx_expr(expr, ctx);
+                self.delete(*loc, &resp)
+            }
 
             // de/increment stuff
             PreIncrement(loc, expr) => {
@@ -887,7 +1252,7 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
 
             // Misc.
             Variable(ident) => self.variable(ident, ctx),
-            Type(_loc, ty) => {
+            Type(loc, ty) => {
                 if let Some(builtin) = Builtin::try_from_ty(ty.clone(), self) {
                     if let Some(idx) = self.builtins().get(&builtin) {
                         ExprRet::Single((ctx, *idx))
@@ -897,14 +1262,116 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
                         ExprRet::Single((ctx, idx))
                     }
                 } else {
-                    todo!("??")
+                    ExprRet::Single((ctx, self.unresolved_ty(*loc, ty)))
                 }
             }
             Parenthesis(_loc, expr) => self.parse_ctx_expr(expr, ctx),
-            Unit(_, _, _) => todo!("Unit"),
+            Unit(loc, expr, unit) => match &**expr {
+                NumberLiteral(_, int, exp) => self.rational_number_literal(
+                    ctx,
+                    *loc,
+                    int,
+                    "",
+                    exp,
+                    unit_multiplier(unit),
+                    false,
+                ),
+                RationalNumberLiteral(_, int, frac, exp) => self.rational_number_literal(
+                    ctx,
+                    *loc,
+                    int,
+                    frac,
+                    exp,
+                    unit_multiplier(unit),
+                    false,
+                ),
+                _ => self.unsupported_value(
+                    *loc,
+                    "Denominations are only supported on number literals".to_string(),
+                    ctx,
+                ),
+            },
         }
     }
 
+    /// Applies `f` to a single value, or to the value of each side of a fork so that a value
+    /// that differs per branch, i.e. `(c ? f : g)(x)`, is handled in every branch. A tuple
+    /// cannot be used where a single value is expected, so it is left unsupported
+    fn map_single(
+        &mut self,
+        loc: Loc,
+        ctx: ContextNode,
+        ret: ExprRet,
+        f: &mut impl FnMut(&mut Self, ContextNode, NodeIdx) -> ExprRet,
+    ) -> ExprRet {
+        match ret {
+            ExprRet::CtxKilled => ExprRet::CtxKilled,
+            ExprRet::Single((ctx, idx)) | ExprRet::SingleLiteral((ctx, idx)) => f(self, ctx, idx),
+            ExprRet::Multi(mut inner) if inner.len() == 1 => {
+                self.map_single(loc, ctx, inner.swap_remove(0), f)
+            }
+            ExprRet::Fork(world1, world2) => {
+                let world1 = self.map_single(loc, ctx, *world1, f);
+                let world2 = self.map_single(loc, ctx, *world2, f);
+                ExprRet::Fork(Box::new(world1), Box::new(world2))
+            }
+            ExprRet::Multi(_) => self.unsupported_value(
+                loc,
+                "A tuple used as a single value is not supported".to_string(),
+                ctx,
+            ),
+        }
+    }
+
+    /// Records that an expression is not supported and stands in a `uint256` that can take any
+    /// value for its result, so the rest of the function is still analyzed
+    fn unsupported_value(&mut self, loc: Loc, msg: String, ctx: ContextNode) -> ExprRet {
+        self.add_unsupported(loc, msg);
+        let ty = self.builtin_or_add(Builtin::Uint(256));
+        let mut var = ContextVar::new_from_builtin(loc, ty.into(), self);
+        var.name = format!("tmp{}(unsupported)", ctx.new_tmp(self));
+        var.display_name = "unsupported".to_string();
+        var.is_symbolic = true;
+        let cvar = self.add_node(Node::ContextVar(var));
+        self.add_edge(cvar, ctx, Edge::Context(ContextEdge::Variable));
+        ExprRet::Single((ctx, cvar))
+    }
+
+    /// Resets a variable to the zero value of its type, i.e. `delete x`. For arrays, bytes,
+    /// strings and mappings, the length is reset instead
+    fn delete(&mut self, loc: Loc, paths: &ExprRet) -> ExprRet {
+        match paths {
+            ExprRet::CtxKilled => return ExprRet::CtxKilled,
+            ExprRet::Single((ctx, var)) | ExprRet::SingleLiteral((ctx, var)) => {
+                let var = ContextVarNode::from(*var).latest_version(self);
+                let ty = var.underlying(self).ty.clone();
+                let (var, zero) = if ty.is_dyn_builtin(self) {
+                    let len = self.tmp_length(var, *ctx, loc).latest_version(self);
+                    (len, Some(Concrete::from(U256::zero())))
+                } else {
+                    let zero = match ty {
+                        VarType::BuiltIn(bn, _) => bn.underlying(self).zero_value(),
+                        _ => None,
+                    };
+                    (var, zero)
+                };
+                let new_var = self.advance_var_in_ctx(var, loc, *ctx);
+                if let Some(zero) = zero {
+                    new_var.set_range_min(self, Elem::from(zero.clone()));
+                    new_var.set_range_max(self, Elem::from(zero));
+                }
+            }
+            ExprRet::Multi(paths) => paths.iter().for_each(|path| {
+                self.delete(loc, path);
+            }),
+            ExprRet::Fork(world1, world2) => {
+                self.delete(loc, world1);
+                self.delete(loc, world2);
+            }
+        }
+        ExprRet::Multi(vec![])
+    }
+
     fn match_in_de_crement(
         &mut self,
         pre: bool,
@@ -983,6 +1450,8 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
     ) -> ExprRet {
         match (lhs_paths, rhs_paths) {
             (ExprRet::CtxKilled, _) | (_, ExprRet::CtxKilled) => ExprRet::CtxKilled,
+            // a skipped element of a tuple
+            (ExprRet::Multi(lhs_sides), _) if lhs_sides.is_empty() => ExprRet::Multi(vec![]),
             (ExprRet::Single((_lhs_ctx, lhs)), ExprRet::SingleLiteral((rhs_ctx, rhs))) => {
                 let lhs_cvar = ContextVarNode::from(*lhs).latest_version(self);
                 let rhs_cvar = ContextVarNode::from(*rhs).latest_version(self);
@@ -1007,8 +1476,32 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
                     .collect(),
             ),
             (ExprRet::Multi(lhs_sides), ExprRet::Multi(rhs_sides)) => {
-                // try to zip sides if they are the same length
-                if lhs_sides.len() == rhs_sides.len() {
+                let is_tuple = |sides: &[ExprRet]| {
+                    sides.iter().all(|side| match side {
+                        ExprRet::Single(_) | ExprRet::SingleLiteral(_) => true,
+                        ExprRet::Multi(skipped) => skipped.is_empty(),
+                        _ => false,
+                    })
+                };
+                let same_len = lhs_sides.len() == rhs_sides.len();
+                if !same_len && is_tuple(lhs_sides) && is_tuple(rhs_sides) {
+                    self.add_warning(
+                        loc,
+                        format!(
+                            "Tuple assignment has {} values for {} variables",
+                            rhs_sides.len(),
+                            lhs_sides.len()
+                        ),
+                    );
+                }
+                // try to zip sides if they are the same length, or are both tuples
+                if same_len || (is_tuple(lhs_sides) && is_tuple(rhs_sides)) {
+                    // every value is read before any of the variables are written, so that
+                    // `(a, b) = (b, a)` swaps them
+                    let rhs_sides = rhs_sides
+                        .iter()
+                        .map(|rhs_expr_ret| self.tuple_value_snapshot(rhs_expr_ret))
+                        .collect::<Vec<_>>();
                     ExprRet::Multi(
                         lhs_sides
                             .iter()
@@ -1053,6 +1546,475 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
         }
     }
 
+    /// Copies a variable that is part of a tuple into a temporary, so that it keeps its current
+    /// value while the other elements of the tuple are assigned
+    fn tuple_value_snapshot(&mut self, expr_ret: &ExprRet) -> ExprRet {
+        let (ctx, idx) = match expr_ret {
+            ExprRet::Single(inner) | ExprRet::SingleLiteral(inner) => *inner,
+            e => return e.clone(),
+        };
+        if !matches!(self.node(idx), Node::ContextVar(..)) {
+            return expr_ret.clone();
+        }
+        let cvar = ContextVarNode::from(idx).latest_version(self);
+        let mut tmp = cvar.underlying(self).clone();
+        tmp.name = format!("tmp{}", ctx.new_tmp(self));
+        tmp.is_tmp = true;
+        let tmp_node = self.add_node(Node::ContextVar(tmp));
+        self.add_edge(tmp_node, ctx, Edge::Context(ContextEdge::Variable));
+        match expr_ret {
+            ExprRet::SingleLiteral(_) => ExprRet::SingleLiteral((ctx, tmp_node)),
+            _ => ExprRet::Single((ctx, tmp_node)),
+        }
+    }
+
+    /// Evaluates the `{value: .., gas: ..}` options of a call, returning `None` if one of them
+    /// killed the context
+    fn parse_call_options(
+        &mut self,
+        block: &Statement,
+        ctx: ContextNode,
+    ) -> Option<Vec<(String, ContextVarNode)>> {
+        let Statement::Args(_, args) = block else {
+            return Some(vec![]);
+        };
+        let mut options = vec![];
+        for arg in args {
+            match self.parse_ctx_expr(&arg.expr, ctx) {
+                ExprRet::CtxKilled => return None,
+                ExprRet::Single((_, idx)) | ExprRet::SingleLiteral((_, idx)) => {
+                    options.push((arg.name.name.clone(), ContextVarNode::from(idx)));
+                }
+                _ => {}
+            }
+        }
+        Some(options)
+    }
+
+    /// Links the `value` and `gas` options of a call to each variable the call returns
+    fn attach_call_options(&mut self, ret: &ExprRet, options: &[(String, ContextVarNode)]) {
+        let rets = match ret {
+            ExprRet::Single((_, idx)) | ExprRet::SingleLiteral((_, idx)) => vec![*idx],
+            ExprRet::Multi(rets) => rets
+                .iter()
+                .filter_map(|ret| match ret {
+                    ExprRet::Single((_, idx)) | ExprRet::SingleLiteral((_, idx)) => Some(*idx),
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
+        };
+        let rets = rets
+            .into_iter()
+            .filter(|ret| matches!(self.node(*ret), Node::ContextVar(_)))
+            .collect::<Vec<_>>();
+        options.iter().for_each(|(name, option)| {
+            let edge = match name.as_str() {
+                "value" => ContextEdge::CallValue,
+                "gas" => ContextEdge::CallGas,
+                _ => return,
+            };
+            rets.iter()
+                .for_each(|ret| self.add_edge(*option, *ret, Edge::Context(edge)));
+        });
+    }
+
+    /// Warns about sending a nonzero `value` to an external function that is not payable,
+    /// which always reverts
+    fn check_payable(&mut self, loc: Loc, func_idx: NodeIdx, value: ContextVarNode) {
+        let Node::ContextVar(_) = self.node(func_idx) else {
+            // a member of a builtin, i.e. `addr.call`
+            return;
+        };
+        let Some(func) = ContextVarNode::from(func_idx).ty(self).func_node(self) else {
+            return;
+        };
+        if func.underlying(self).mutability() == StateMutability::Payable {
+            return;
+        }
+        let is_zero = value
+            .evaled_range_max(self)
+            .and_then(|max| max.maybe_concrete())
+            .and_then(|max| max.val.into_u256())
+            .is_some_and(|max| max.is_zero());
+        if !is_zero {
+            self.add_warning(
+                loc,
+                format!(
+                    "Value sent to \"{}\", which is not payable, the call reverts",
+                    func.name(self)
+                ),
+            );
+        }
+    }
+
+    /// Records `value` being sent out of the contract at `loc`, along with its bounds at this
+    /// point
+    fn record_value_transfer(
+        &mut self,
+        loc: Loc,
+        via: &str,
+        value: ContextVarNode,
+        ctx: ContextNode,
+    ) {
+        let value = value.latest_version(self);
+        let bound = |elem: Option<Elem<Concrete>>| Some(elem?.maybe_concrete()?.val);
+        let transfer = ValueTransfer {
+            loc,
+            via: via.to_string(),
+            value: value.display_name(self),
+            min: bound(value.evaled_range_min(self)),
+            max: bound(value.evaled_range_max(self)),
+        };
+        ctx.add_value_transfer(transfer, self);
+    }
+
+    /// Evaluates the arguments of `revert E(..)`, `revert(..)` or `revert()` in `ctx` for their
+    /// effects, then ends every path through it
+    fn revert_stmt(
+        &mut self,
+        loc: Loc,
+        maybe_err_path: Option<&IdentifierPath>,
+        exprs: &[Expression],
+        ctx: ContextNode,
+    ) {
+        if let Some(path) = maybe_err_path {
+            if self.resolve_error(path, ctx).is_none() {
+                self.add_warning(path.loc, format!("Unknown error \"{path}\""));
+            }
+        }
+        for expr in exprs {
+            if let ExprRet::CtxKilled = self.parse_ctx_expr(expr, ctx) {
+                return;
+            }
+        }
+        let forks = ctx.live_forks(self);
+        if forks.is_empty() {
+            ctx.kill(self, loc, KilledKind::Revert);
+        } else {
+            forks
+                .into_iter()
+                .for_each(|fork| fork.kill(self, loc, KilledKind::Revert));
+        }
+    }
+
+    /// The error `path` names. `C.E` is looked up in contract `C` and its bases, a plain `E` in
+    /// the contract of `ctx` and its bases, then at file level in its source unit and the files
+    /// it imports
+    fn resolve_error(&self, path: &IdentifierPath, ctx: ContextNode) -> Option<ErrorNode> {
+        let (name, qualifier) = path.identifiers.split_last()?;
+        let contract = match qualifier.last() {
+            Some(qualifier) => {
+                let idx = *self.user_types().get(&qualifier.name)?;
+                if !matches!(self.node(idx), Node::Contract(_)) {
+                    return None;
+                }
+                Some(ContractNode::from(idx))
+            }
+            None => ctx.maybe_associated_contract(self),
+        };
+        let inherited = contract
+            .into_iter()
+            .flat_map(|contract| contract.with_bases(self))
+            .flat_map(|contract| contract.errors(self));
+        let file_level = if qualifier.is_empty() {
+            self.visible_sources(ctx)
+        } else {
+            vec![]
+        }
+        .into_iter()
+        .flat_map(|source| self.children_of_kind(source, Edge::Part))
+        .flat_map(|part| self.children_of_kind(part, Edge::Error))
+        .map(ErrorNode::from);
+        inherited
+            .chain(file_level)
+            .find(|err| err.name(self) == name.name)
+    }
+
+    /// The source unit of `ctx` followed by the ones it imports, directly or through another
+    /// import
+    fn visible_sources(&self, ctx: ContextNode) -> Vec<NodeIdx> {
+        let mut sources: Vec<NodeIdx> = ctx.associated_source(self).into_iter().collect();
+        let mut i = 0;
+        while let Some(source) = sources.get(i).copied() {
+            self.children_of_kind(source, Edge::Import)
+                .into_iter()
+                .for_each(|imported| {
+                    if !sources.contains(&imported) {
+                        sources.push(imported);
+                    }
+                });
+            i += 1;
+        }
+        sources
+    }
+
+    /// Calls the function `func_idx` that `loc` accessed as a member, i.e. `c.f(x)` or
+    /// `x.add(y)` through `using`
+    fn member_func_call(
+        &mut self,
+        loc: &Loc,
+        input_exprs: &[Expression],
+        func_idx: NodeIdx,
+        func_ctx: ContextNode,
+        ctx: ContextNode,
+    ) -> ExprRet {
+        // members of builtins, i.e. `payable(to).transfer(amount)`
+        if let Node::Function(_) = self.node(func_idx) {
+            return self.intrinsic_func_call(loc, input_exprs, func_idx, func_ctx);
+        }
+
+        let inputs = self.member_call_inputs(func_idx, input_exprs, ctx);
+        self.member_func_call_with(loc, &inputs, func_idx, ctx)
+    }
+
+    /// The inputs of a call to the function value `func_idx`. Functions attached with `using` get
+    /// what they were accessed on as the first input
+    fn member_call_inputs(
+        &mut self,
+        func_idx: NodeIdx,
+        input_exprs: &[Expression],
+        ctx: ContextNode,
+    ) -> ExprRet {
+        let mut inputs: Vec<_> = self
+            .graph()
+            .edges_directed(func_idx, Direction::Outgoing)
+            .filter(|edge| *edge.weight() == Edge::Context(ContextEdge::LibraryAccess))
+            .map(|edge| ExprRet::Single((ctx, edge.target())))
+            .collect();
+        inputs.extend(
+            input_exprs
+                .iter()
+                .map(|expr| self.parse_ctx_expr(expr, ctx)),
+        );
+        ExprRet::Multi(inputs)
+    }
+
+    /// Calls the function value `func_idx` with inputs that are already parsed
+    fn member_func_call_with(
+        &mut self,
+        loc: &Loc,
+        inputs: &ExprRet,
+        func_idx: NodeIdx,
+        ctx: ContextNode,
+    ) -> ExprRet {
+        // functions accessed on a contract value, `this.foo()` included, are external calls
+        let ext_call = self
+            .graph()
+            .edges_directed(func_idx, Direction::Outgoing)
+            .any(|edge| *edge.weight() == Edge::Context(ContextEdge::FuncAccess));
+        self.func_call(
+            ctx,
+            *loc,
+            inputs,
+            ContextVarNode::from(func_idx)
+                .ty(self)
+                .func_node(self)
+                .expect(""),
+            ext_call,
+        )
+    }
+
+    /// Calls whichever of the functions named by `ident` the inputs select
+    fn call_possible_funcs(
+        &mut self,
+        loc: &Loc,
+        ident: &Identifier,
+        input_exprs: &[Expression],
+        possible_funcs: &[&FunctionNode],
+        ctx: ContextNode,
+    ) -> ExprRet {
+        if possible_funcs.len() == 1 {
+            let inputs = ExprRet::Multi(
+                input_exprs
+                    .iter()
+                    .map(|expr| self.parse_ctx_expr(expr, ctx))
+                    .collect(),
+            );
+            self.setup_fn_call(&ident.loc, &inputs, (*possible_funcs[0]).into(), ctx)
+        } else {
+            // this is the annoying case due to function overloading & type inference on number literals
+            let lits = Self::literal_inputs(input_exprs);
+            let inputs = ExprRet::Multi(
+                input_exprs
+                    .iter()
+                    .map(|expr| self.parse_ctx_expr(expr, ctx))
+                    .collect(),
+            );
+
+            if let Some(func) =
+                self.disambiguate_fn_call(&ident.name, lits, &inputs, possible_funcs)
+            {
+                self.setup_fn_call(loc, &inputs, func.into(), ctx)
+            } else {
+                ExprRet::CtxKilled
+            }
+        }
+    }
+
+    /// Which of the inputs are number literals, whose type is inferred from the parameter they
+    /// are passed to
+    fn literal_inputs(input_exprs: &[Expression]) -> Vec<bool> {
+        input_exprs
+            .iter()
+            .map(|expr| {
+                match expr {
+                    Expression::UnaryMinus(_, expr) => {
+                        // negative number potentially
+                        matches!(
+                            **expr,
+                            Expression::NumberLiteral(..) | Expression::HexLiteral(..)
+                        )
+                    }
+                    Expression::NumberLiteral(..) | Expression::HexLiteral(..) => true,
+                    _ => false,
+                }
+            })
+            .collect()
+    }
+
+    /// The overloads of an overloaded function used as a value, i.e. `lib.add` with two `add`
+    /// functions, or `None` if `ret` is anything else
+    fn overload_set(&self, ret: &ExprRet) -> Option<Vec<(ContextNode, NodeIdx)>> {
+        let ExprRet::Multi(rets) = ret else {
+            return None;
+        };
+        if rets.len() < 2 {
+            return None;
+        }
+        rets.iter()
+            .map(|ret| match ret {
+                ExprRet::Single((ctx, idx))
+                    if matches!(self.node(*idx), Node::ContextVar(_))
+                        && ContextVarNode::from(*idx)
+                            .ty(self)
+                            .func_node(self)
+                            .is_some() =>
+                {
+                    Some((*ctx, *idx))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Calls whichever of `overloads` the types of the inputs select. `on_callee` sees the
+    /// function value that is called before the call is made
+    fn call_overload(
+        &mut self,
+        loc: &Loc,
+        overloads: Vec<(ContextNode, NodeIdx)>,
+        input_exprs: &[Expression],
+        ctx: ContextNode,
+        on_callee: &mut impl FnMut(&mut Self, ContextNode, NodeIdx),
+    ) -> ExprRet {
+        // overloads accessed on the same value share what they were accessed on
+        let (func_ctx, first) = overloads[0];
+        let inputs = self.member_call_inputs(first, input_exprs, ctx);
+        let funcs = overloads
+            .iter()
+            .map(|(_, idx)| {
+                ContextVarNode::from(*idx)
+                    .ty(self)
+                    .func_node(self)
+                    .expect("Not a function")
+            })
+            .collect::<Vec<_>>();
+        // what the overloads were accessed on, if anything, comes before the inputs
+        let receivers = match &inputs {
+            ExprRet::Multi(inputs) => inputs.len() - input_exprs.len(),
+            _ => 0,
+        };
+        let mut lits = vec![false; receivers];
+        lits.extend(Self::literal_inputs(input_exprs));
+        let name = funcs[0].name(self);
+        let name = name.split('(').next().unwrap_or_default().to_string();
+        match self.disambiguate_fn_call(&name, lits, &inputs, &funcs.iter().collect::<Vec<_>>()) {
+            Some(func) => {
+                let (_, func_idx) = overloads[funcs.iter().position(|f| *f == func).unwrap()];
+                on_callee(self, func_ctx, func_idx);
+                self.member_func_call_with(loc, &inputs, func_idx, ctx)
+            }
+            None => self.unsupported_value(
+                *loc,
+                format!("No overload of {name} matches the inputs"),
+                ctx,
+            ),
+        }
+    }
+
+    /// The parameter names of each function, or the field names of the struct, that a call
+    /// with named arguments to `func_expr` could resolve to
+    fn named_call_candidates(
+        &mut self,
+        func_expr: &Expression,
+        ctx: ContextNode,
+    ) -> Vec<Vec<String>> {
+        let param_names = |func: FunctionNode, analyzer: &Self| {
+            func.params(analyzer)
+                .iter()
+                .map(|param| param.maybe_name(analyzer).unwrap_or_default())
+                .collect::<Vec<_>>()
+        };
+        if let Expression::Variable(ident) = func_expr {
+            let funcs = ctx
+                .visible_funcs(self)
+                .into_iter()
+                .filter(|func| func.name(self).starts_with(&format!("{}(", ident.name)))
+                .collect::<Vec<_>>();
+            if !funcs.is_empty() {
+                return funcs
+                    .into_iter()
+                    .map(|func| param_names(func, self))
+                    .collect();
+            }
+        }
+
+        let ExprRet::Single((_, idx)) = self.parse_ctx_expr(func_expr, ctx) else {
+            return vec![];
+        };
+        let Node::ContextVar(_) = self.node(idx) else {
+            return vec![];
+        };
+        let ty = ContextVarNode::from(idx).ty(self).clone();
+        if let VarType::User(TypeNode::Struct(struct_node), _) = ty {
+            vec![struct_node
+                .fields(self)
+                .iter()
+                .map(|field| field.name(self))
+                .collect()]
+        } else if let Some(func) = ty.func_node(self) {
+            vec![param_names(func, self)]
+        } else {
+            vec![]
+        }
+    }
+
+    /// Warns about a write to an `immutable` state variable from anywhere but a constructor
+    fn check_immutable_write(&mut self, loc: Loc, var: ContextVarNode, ctx: ContextNode) {
+        let Some(StorageLocation::Storage(def_loc)) = var.underlying(self).storage else {
+            return;
+        };
+        let name = var.name(self);
+        let Some(def) = ctx
+            .maybe_associated_contract(self)
+            .and_then(|contract| contract.state_var(self, &name))
+        else {
+            return;
+        };
+        let def = def.underlying(self);
+        // a local `storage` pointer with the same name is not the state variable
+        if def.loc != def_loc || !def.is_immutable() {
+            return;
+        }
+        if ctx.executing_fn(self).underlying(self).ty != FunctionTy::Constructor {
+            self.add_warning(
+                loc,
+                format!("Immutable variable \"{name}\" is assigned outside of a constructor"),
+            );
+        }
+    }
+
     fn assign(
         &mut self,
         loc: Loc,
@@ -1060,12 +2022,33 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
         rhs_cvar: ContextVarNode,
         ctx: ContextNode,
     ) -> ExprRet {
+        self.check_immutable_write(loc, lhs_cvar, ctx);
+        if let Some(func) = rhs_cvar.ty(self).func_node(self) {
+            // function values carry no range, the variable just points at the function now
+            let new_lhs = self.advance_var_in_ctx(lhs_cvar.latest_version(self), loc, ctx);
+            new_lhs.underlying_mut(self).ty = VarType::User(TypeNode::Func(func), None);
+            self.add_edge(new_lhs, rhs_cvar, Edge::Context(ContextEdge::Assign));
+            return ExprRet::Single((ctx, new_lhs.into()));
+        }
+
         let (new_lower_bound, new_upper_bound): (Elem<Concrete>, Elem<Concrete>) = (
             Elem::Dynamic(Dynamic::new(rhs_cvar.latest_version(self).into(), loc)),
             Elem::Dynamic(Dynamic::new(rhs_cvar.latest_version(self).into(), loc)),
         );
 
         let new_lhs = self.advance_var_in_ctx(lhs_cvar.latest_version(self), loc, ctx);
+        self.add_edge(
+            new_lhs,
+            rhs_cvar.latest_version(self),
+            Edge::Context(ContextEdge::Assign),
+        );
+        // assigning to a storage reference points it somewhere else
+        if lhs_cvar.storage_alias(self).is_some()
+            && rhs_cvar.is_storage(self)
+            && rhs_cvar.first_version(self) != lhs_cvar.first_version(self)
+        {
+            self.add_edge(new_lhs, rhs_cvar, Edge::Context(ContextEdge::StorageAlias));
+        }
         if !lhs_cvar.ty_eq(&rhs_cvar, self) {
             let _ = new_lhs.try_set_range_min(
                 self,
@@ -1083,6 +2066,11 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
             new_lhs.try_set_range_exclusions(self, rhs_range.exclusions);
         }
 
+        if let VarType::User(TypeNode::Struct(struct_node), _) = new_lhs.ty(self) {
+            let struct_node = *struct_node;
+            self.assign_struct_fields(loc, struct_node, new_lhs, rhs_cvar, ctx);
+        }
+
         if let Some(arr) = lhs_cvar.index_to_array(self) {
             if let Some(index) = lhs_cvar.index_access_to_index(self) {
                 let next_arr = self.advance_var_in_ctx(arr, loc, ctx);
@@ -1108,12 +2096,77 @@ pub trait ContextBuilder: AnalyzerLike<Expr = Expression> + Sized + ExprParser {
                         }
                     }
                 }
+                if let Some(target) = arr.storage_alias(self) {
+                    let target_elem = self.index_into_array_inner(
+                        loc,
+                        ExprRet::Single((ctx, target.into())),
+                        ExprRet::Single((ctx, index.latest_version(self).into())),
+                    );
+                    if let ExprRet::Single((_, target_elem)) = target_elem {
+                        self.assign(loc, target_elem.into(), rhs_cvar, ctx);
+                    }
+                }
             }
         }
 
+        self.write_through_storage_field(loc, lhs_cvar, rhs_cvar, ctx);
+
         ExprRet::Single((ctx, new_lhs.into()))
     }
 
+    /// Mirrors a write to a field of a local `storage` reference onto the same field of the
+    /// variable the reference points at
+    fn write_through_storage_field(
+        &mut self,
+        loc: Loc,
+        field: ContextVarNode,
+        rhs_cvar: ContextVarNode,
+        ctx: ContextNode,
+    ) {
+        let Some(parent) = field.struct_parent(self) else {
+            return;
+        };
+        let Some(target) = parent.storage_alias(self) else {
+            return;
+        };
+        if target.first_version(self) == parent.first_version(self) {
+            return;
+        }
+        let VarType::User(TypeNode::Struct(struct_node), _) = target.ty(self).clone() else {
+            return;
+        };
+        let name = field.name(self);
+        let Some(field_name) = name.strip_prefix(&format!("{}.", parent.name(self))) else {
+            return;
+        };
+        let target_field = self.struct_field(loc, struct_node, target, field_name, ctx);
+        self.assign(loc, target_field, rhs_cvar, ctx);
+    }
+
+    /// Copies the fields of `rhs` into `lhs`. Only the fields either side has accessed are
+    /// tracked, a field that `rhs` hasn't accessed yet is unknown to `lhs` afterwards as well
+    fn assign_struct_fields(
+        &mut self,
+        loc: Loc,
+        struct_node: StructNode,
+        lhs: ContextVarNode,
+        rhs: ContextVarNode,
+        ctx: ContextNode,
+    ) {
+        struct_node.fields(self).iter().for_each(|field| {
+            let name = field.name(self);
+            let tracked = |analyzer: &Self, var: ContextVarNode| {
+                ctx.var_by_name_or_recurse(analyzer, &format!("{}.{name}", var.name(analyzer)))
+                    .is_some()
+            };
+            if tracked(self, lhs) || tracked(self, rhs) {
+                let rhs_field = self.struct_field(loc, struct_node, rhs, &name, ctx);
+                let lhs_field = self.struct_field(loc, struct_node, lhs, &name, ctx);
+                self.assign(loc, lhs_field, rhs_field, ctx);
+            }
+        });
+    }
+
     fn advance_var_in_ctx(
         &mut self,
         cvar_node: ContextVarNode,
@@ -7,7 +7,7 @@ use shared::{
     NodeIdx,
 };
 
-use ariadne::{Cache, Color, Config, Label, Report, ReportKind};
+use ariadne::{Color, Config, Label, Report, ReportKind};
 use std::collections::BTreeMap;
 
 #[derive(Debug, Clone)]
@@ -22,7 +22,7 @@ impl AccessStorageWriteReport {
 }
 
 impl ReportDisplay for AccessStorageWriteReport {
-    fn report_kind(&self) -> ReportKind {
+    fn report_kind(&self) -> ReportKind<'_> {
         ReportKind::Custom("Access Analysis", Color::Green)
     }
     fn msg(&self, _analyzer: &(impl AnalyzerLike + Search)) -> String {
@@ -33,7 +33,7 @@ impl ReportDisplay for AccessStorageWriteReport {
         vec![]
     }
 
-    fn reports(&self, analyzer: &(impl AnalyzerLike + Search)) -> Vec<Report<LocStrSpan>> {
+    fn reports(&self, analyzer: &(impl AnalyzerLike + Search)) -> Vec<Report<'_, LocStrSpan>> {
         let report = Report::build(self.report_kind(), "".to_string(), 0)
             .with_message(self.msg(analyzer))
             .with_config(
@@ -45,22 +45,11 @@ impl ReportDisplay for AccessStorageWriteReport {
         vec![report.finish()]
     }
 
-    fn print_reports(&self, src: &mut impl Cache<String>, analyzer: &(impl AnalyzerLike + Search)) {
-        let reports = &self.reports(analyzer);
-        for report in reports.iter() {
-            report.print(&mut *src).unwrap();
-        }
-    }
-
-    fn eprint_reports(
-        &self,
-        mut src: &mut impl Cache<String>,
-        analyzer: &(impl AnalyzerLike + Search),
-    ) {
-        let reports = &self.reports(analyzer);
-        reports.iter().for_each(|report| {
-            report.eprint(&mut src).unwrap();
-        });
+    fn findings(&self, _analyzer: &(impl AnalyzerLike + Search)) -> Vec<Finding> {
+        self.msgs
+            .iter()
+            .map(|msg| Finding::new(Severity::Info, &LocStrSpan::default(), msg.clone()))
+            .collect()
     }
 }
 
@@ -99,7 +88,7 @@ pub trait AccessStorageWriteQuery: BoundAnalyzer + Search + AnalyzerLike + Sized
                 for analysis in terminals
                     .iter()
                     .map(|child| {
-                        let mut parents = child.parent_list(self);
+                        let mut parents = child.parent_chain(self);
                         parents.reverse();
                         parents.push(*child);
                         self.bounds_for_var_in_family_tree(
@@ -141,8 +130,8 @@ pub trait AccessStorageWriteQuery: BoundAnalyzer + Search + AnalyzerLike + Sized
                 .map(|ctx| {
                     let bounds_string = ctx
                         .ctx_deps(self)
-                        .iter()
-                        .filter_map(|(_name, cvar)| {
+                        .values()
+                        .filter_map(|cvar| {
                             let min = if report_config.eval_bounds {
                                 cvar.range(self)?
                                     .evaled_range_min(self)
@@ -207,7 +196,7 @@ pub trait AccessStorageWriteQuery: BoundAnalyzer + Search + AnalyzerLike + Sized
     fn recurse(&self, ctx: ContextNode, storage_var_name: String) -> Vec<String> {
         if let Some(cvar) = ctx.var_by_name(self, &storage_var_name) {
             match cvar.ty(self) {
-                VarType::User(TypeNode::Struct(s_node)) => {
+                VarType::User(TypeNode::Struct(s_node), _) => {
                     let fields = s_node
                         .fields(self)
                         .iter()
@@ -9,7 +9,7 @@ use shared::{
     NodeIdx,
 };
 
-use ariadne::{Cache, Color, Config, Fmt, Label, Report, ReportKind, Span};
+use ariadne::{Color, Config, Fmt, Label, Report, ReportKind, Span};
 use std::collections::BTreeMap;
 
 #[derive(Debug, Clone)]
@@ -20,7 +20,7 @@ pub struct StorageRangeReport {
 }
 
 impl ReportDisplay for StorageRangeReport {
-    fn report_kind(&self) -> ReportKind {
+    fn report_kind(&self) -> ReportKind<'_> {
         ReportKind::Custom("Storage Write Query", Color::Green)
     }
     fn msg(&self, analyzer: &(impl AnalyzerLike + Search)) -> String {
@@ -28,8 +28,8 @@ impl ReportDisplay for StorageRangeReport {
             .analysis
             .ctx
             .ctx_deps(analyzer)
-            .iter()
-            .filter_map(|(_name, cvar)| {
+            .values()
+            .filter_map(|cvar| {
                 let min = if self.analysis.report_config.eval_bounds {
                     cvar.range(analyzer)?
                         .evaled_range_min(analyzer)
@@ -96,7 +96,7 @@ impl ReportDisplay for StorageRangeReport {
         vec![]
     }
 
-    fn reports(&self, analyzer: &(impl AnalyzerLike + Search)) -> Vec<Report<LocStrSpan>> {
+    fn reports(&self, analyzer: &(impl AnalyzerLike + Search)) -> Vec<Report<'_, LocStrSpan>> {
         let mut report = Report::build(
             self.analysis.report_kind(),
             self.analysis.var_def.0.source(),
@@ -127,22 +127,23 @@ impl ReportDisplay for StorageRangeReport {
         reports
     }
 
-    fn print_reports(&self, src: &mut impl Cache<String>, analyzer: &(impl AnalyzerLike + Search)) {
-        let reports = &self.reports(analyzer);
-        for report in reports.iter() {
-            report.print(&mut *src).unwrap();
+    fn findings(&self, analyzer: &(impl AnalyzerLike + Search)) -> Vec<Finding> {
+        let mut findings = self.analysis.findings(analyzer);
+        if let Some(write_loc) = &self.write_loc {
+            let (min, max) = self.analysis.range_strs(&self.target, analyzer);
+            findings.push(
+                Finding::new(
+                    Severity::Info,
+                    write_loc,
+                    format!(
+                        "Storage write that could lead to target value in ctx {}",
+                        self.analysis.ctx.path(analyzer)
+                    ),
+                )
+                .with_range(self.analysis.var_name.clone(), min, max),
+            );
         }
-    }
-
-    fn eprint_reports(
-        &self,
-        mut src: &mut impl Cache<String>,
-        analyzer: &(impl AnalyzerLike + Search),
-    ) {
-        let reports = &self.reports(analyzer);
-        reports.iter().for_each(|report| {
-            report.eprint(&mut src).unwrap();
-        });
+        findings
     }
 }
 
@@ -184,7 +185,7 @@ pub trait StorageRangeQuery: BoundAnalyzer + Search + AnalyzerLike + Sized {
         for analysis in terminals
             .iter()
             .map(|child| {
-                let mut parents = child.parent_list(self);
+                let mut parents = child.parent_chain(self);
                 parents.reverse();
                 parents.push(*child);
                 self.bounds_for_var_in_family_tree(
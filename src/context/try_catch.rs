@@ -0,0 +1,86 @@
+use crate::context::{ContextBuilder, ExprRet};
+use shared::context::*;
+use shared::{analyzer::AnalyzerLike, Edge, Node, NodeIdx};
+use solang_parser::pt::{
+    CatchClause, Expression, Loc, Parameter, ParameterList, Statement, VariableDeclaration,
+};
+
+impl<T> TryCatch for T where T: AnalyzerLike<Expr = Expression> + Sized {}
+pub trait TryCatch: AnalyzerLike<Expr = Expression> + Sized {
+    /// Forks `ctx` into the path where the call in `try_expr` succeeded, and one path per
+    /// `catch` clause where it reverted. The call and its arguments are evaluated once, before
+    /// the fork, since a revert while evaluating the arguments is not caught
+    fn try_catch_stmt(
+        &mut self,
+        loc: Loc,
+        try_expr: &Expression,
+        maybe_returns: &Option<(ParameterList, Box<Statement>)>,
+        clauses: &[CatchClause],
+        ctx: ContextNode,
+    ) {
+        let rets = match self.parse_ctx_expr(try_expr, ctx) {
+            ExprRet::CtxKilled => return,
+            ExprRet::Multi(rets) => rets,
+            ret => vec![ret],
+        };
+
+        let ctx_fork = self.add_node(Node::ContextFork);
+        self.add_edge(ctx_fork, ctx, Edge::Context(ContextEdge::ContextFork));
+        let fork = |analyzer: &mut Self| {
+            let subctx = ContextNode::from(analyzer.add_node(Node::Context(Context::new_subctx(
+                ctx, loc, true, None, false, analyzer, None,
+            ))));
+            ctx.add_fork(subctx, analyzer);
+            analyzer.add_edge(
+                NodeIdx::from(subctx.0),
+                ctx_fork,
+                Edge::Context(ContextEdge::Subcontext),
+            );
+            subctx
+        };
+
+        // the `returns (..)` clause has to repeat the callee's return types, so the values are
+        // bound with those types
+        let success_ctx = fork(self);
+        if let Some((params, block)) = maybe_returns {
+            params
+                .iter()
+                .zip(rets.iter())
+                .for_each(|((_, param), ret)| {
+                    if let (Some(param), ExprRet::Single((_, ret))) = (param, ret) {
+                        let ret = ExprRet::Single((success_ctx, *ret));
+                        self.bind_try_param(param, Some(&ret), success_ctx);
+                    }
+                });
+            self.parse_ctx_statement(block, false, Some(success_ctx));
+        }
+
+        clauses.iter().for_each(|clause| {
+            let catch_ctx = fork(self);
+            let (param, block) = match clause {
+                CatchClause::Simple(_, param, block) => (param.as_ref(), block),
+                CatchClause::Named(_, _, param, block) => (Some(param), block),
+            };
+            if let Some(param) = param {
+                self.bind_try_param(param, None, catch_ctx);
+            }
+            self.parse_ctx_statement(block, false, Some(catch_ctx));
+        });
+    }
+
+    /// Defines a variable for a named parameter of a `returns` or `catch` clause, holding
+    /// `value` if there is one and anything of its type otherwise
+    fn bind_try_param(&mut self, param: &Parameter, value: Option<&ExprRet>, ctx: ContextNode) {
+        if param.name.is_none() {
+            return;
+        }
+        let var_decl = VariableDeclaration {
+            loc: param.loc,
+            ty: param.ty.clone(),
+            storage: param.storage.clone(),
+            name: param.name.clone(),
+        };
+        let lhs_paths = self.parse_ctx_expr(&param.ty, ctx);
+        self.match_var_def(&var_decl, param.loc, &lhs_paths, value);
+    }
+}
@@ -1,49 +1,232 @@
+use ariadne::{sources, Cache};
 use ethers_core::types::U256;
 use shared::analyzer::*;
+use shared::context::{ContextEdge, ContextNode, ContextVar, ContextVarNode, TmpConstruction};
 use shared::nodes::*;
+use shared::range::{
+    elem::RangeOp,
+    elem_ty::{Dynamic, Elem},
+    Range, RangeEval, SolcRange,
+};
 use shared::{Edge, Node, NodeIdx};
-use solang_parser::pt::Import;
+use solang_parser::pt::{Annotation, Identifier, IdentifierPath, Import, Using, UsingList};
 
+use serde::{Deserialize, Serialize};
 use solang_parser::pt::{
-    ContractDefinition, ContractPart, EnumDefinition, ErrorDefinition, Expression,
-    FunctionDefinition, FunctionTy, SourceUnit, SourceUnitPart, StructDefinition, TypeDefinition,
-    VariableDefinition,
+    ContractDefinition, ContractPart, EnumDefinition, ErrorDefinition, EventDefinition, Expression,
+    FunctionDefinition, FunctionTy, Loc, SourceUnit, SourceUnitPart, StructDefinition,
+    TypeDefinition, VariableDefinition,
+};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
 };
-use std::{collections::HashMap, fs};
 
-use petgraph::{graph::*, Directed};
+use petgraph::{graph::*, visit::EdgeRef, Directed, Direction};
 
 mod builtin_fns;
 
 pub mod context;
 // pub mod range;
+use context::analyzers::{
+    bounds::{FunctionVarsBoundAnalysis, FunctionVarsBoundAnalyzer},
+    dead_code::DeadCodeAnalyzer,
+    overflow::OverflowAnalyzer,
+    shadowing::ShadowingAnalyzer,
+    value_flow::ValueFlowAnalyzer,
+    FullReport, FunctionReport, ReportConfig,
+};
+use context::exprs::Literal;
+use context::func::FuncCaller;
 use context::*;
 
 #[derive(Debug, Clone)]
 pub struct Analyzer {
     pub remappings: HashMap<String, String>,
+    /// Directory of the file currently being parsed, used to resolve relative imports
+    pub root: PathBuf,
+    /// Source unit nodes of files that have already been imported, keyed by their canonical path
+    pub imported_files: HashMap<PathBuf, NodeIdx>,
     pub file_no: usize,
     pub msg: MsgNode,
     pub block: BlockNode,
     pub graph: Graph<Node, Edge, Directed, usize>,
     pub builtins: HashMap<Builtin, NodeIdx>,
+    /// Constant nodes keyed by their value, so each distinct constant is only added once
+    pub concretes: HashMap<Concrete, NodeIdx>,
     pub user_types: HashMap<String, NodeIdx>,
     pub builtin_fns: HashMap<String, Function>,
     pub builtin_fn_inputs: HashMap<String, (Vec<FunctionParam>, Vec<FunctionReturn>)>,
+    /// Nodes of the builtin functions that have been used, keyed by name
+    pub builtin_fn_nodes: HashMap<String, NodeIdx>,
+    /// Treat every public/external function parameter as adversarial, ignoring call sites
+    pub security_mode: bool,
+    /// How many iterations of a loop with a known limiter to execute before widening
+    pub max_unroll: usize,
+    /// Treat every variable an inline assembly block mentions as unknown, not just those it
+    /// assigns to
+    pub assembly_unknown: bool,
+    /// Suspicious constructs, and those that could not be fully analyzed, encountered while
+    /// parsing
+    pub diagnostics: Vec<Diagnostic>,
+    /// `pragma solidity` version requirements, keyed by source unit
+    pub pragmas: HashMap<NodeIdx, String>,
+    /// `@name(value)` annotations, keyed by the source unit or contract they appear in
+    pub annotations: HashMap<NodeIdx, Vec<Annotation>>,
+    /// Functions attached to a type by `using ... for`, keyed by the scope of the directive and
+    /// the type's node. The scope is the contract the directive is in, or the source unit for
+    /// one at file level. A `global` directive goes wherever the type does, so its scope is the
+    /// type itself
+    pub using: HashMap<(NodeIdx, NodeIdx), Vec<FunctionNode>>,
+    /// Functions attached to every type by `using ... for *`, keyed by the scope of the directive
+    pub using_any: HashMap<NodeIdx, Vec<FunctionNode>>,
+    /// The contract whose definitions are being parsed, names are looked up in its scope first
+    pub contract_scope: Option<ContractNode>,
+    /// Files pulled in by imports: their source unit node, path, source and file number
+    pub sources: Vec<(Option<NodeIdx>, String, String, usize)>,
+    /// The path and source of every parsed file, keyed by file number
+    pub file_sources: HashMap<usize, (PathBuf, String)>,
+    /// Arrays whose element type was used before it was declared: their location, placeholder
+    /// node, element type node and length if fixed. They are resolved once the file is parsed
+    pub unresolved_arrays: Vec<(Loc, NodeIdx, NodeIdx, Option<U256>)>,
+    /// Where time goes while parsing and analyzing. Nothing is collected unless this is set,
+    /// i.e. to `Some(Metrics::default())`, before parsing
+    pub metrics: Option<Metrics>,
+}
+
+/// What [`Analyzer::to_json`] writes: the graph, along with what the analyzer knows about it
+/// that can't be derived from the graph alone. Maps are written as lists of entries, since
+/// their keys aren't all strings
+#[derive(Serialize, Deserialize)]
+struct SavedAnalyzer<'a> {
+    graph: Cow<'a, Graph<Node, Edge, Directed, usize>>,
+    file_no: usize,
+    imported_files: Vec<(PathBuf, NodeIdx)>,
+    pragmas: Vec<(NodeIdx, String)>,
+    annotations: Vec<(NodeIdx, Vec<Annotation>)>,
+    using: Vec<((NodeIdx, NodeIdx), Vec<FunctionNode>)>,
+    using_any: Vec<(NodeIdx, Vec<FunctionNode>)>,
+    sources: Vec<(Option<NodeIdx>, String, String, usize)>,
+    file_sources: Vec<(usize, (PathBuf, String))>,
+}
+
+/// A profile of the work done by an [`Analyzer`], see [`Analyzer::metrics`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Metrics {
+    /// Time spent in [`Analyzer::parse`] and [`Analyzer::reparse_file`], which includes building
+    /// the contexts of the function bodies
+    pub parse_time: Duration,
+    /// Time spent building the contexts of each function body, keyed by the function's name
+    /// qualified with its contract's
+    pub ctx_times: BTreeMap<String, Duration>,
+    /// Loop iterations executed one by one, across all loops, see [`Analyzer::max_unroll`]
+    pub unrolled_iterations: usize,
+    /// Time spent running the reports of [`Analyzer::analyze_all`]
+    pub report_time: Duration,
+    /// The size of the graph when the metrics were taken
+    pub node_count: usize,
+    pub edge_count: usize,
+}
+
+/// A file that could not be parsed, along with solang's diagnostics for it
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    pub file_no: usize,
+    pub diagnostics: Vec<solang_parser::diagnostics::Diagnostic>,
+}
+
+impl ParseError {
+    /// The byte span of each diagnostic in the file
+    pub fn spans(&self) -> Vec<(usize, usize)> {
+        self.diagnostics
+            .iter()
+            .map(|diagnostic| match diagnostic.loc {
+                Loc::File(_, start, end) => (start, end),
+                _ => (0, 0),
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to parse file {}", self.file_no)?;
+        self.diagnostics
+            .iter()
+            .zip(self.spans())
+            .try_for_each(|(diagnostic, (start, end))| {
+                write!(f, "\n  {}..{}: {}", start, end, diagnostic.message)
+            })
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Why [`Analyzer::analyze_file`] could not produce any analyses
+#[derive(Debug)]
+pub enum AnalyzeError {
+    /// The file could not be read
+    Io(PathBuf, std::io::Error),
+    /// The file could not be parsed
+    Parse(ParseError),
+}
+
+impl std::fmt::Display for AnalyzeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnalyzeError::Io(path, e) => write!(f, "Could not read {}: {e}", path.display()),
+            AnalyzeError::Parse(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for AnalyzeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AnalyzeError::Io(_, e) => Some(e),
+            AnalyzeError::Parse(e) => Some(e),
+        }
+    }
+}
+
+impl From<ParseError> for AnalyzeError {
+    fn from(e: ParseError) -> Self {
+        AnalyzeError::Parse(e)
+    }
 }
 
 impl Default for Analyzer {
     fn default() -> Self {
         let mut a = Self {
             remappings: Default::default(),
+            root: Default::default(),
+            imported_files: Default::default(),
             file_no: 0,
             msg: MsgNode(0),
             block: BlockNode(0),
             graph: Default::default(),
             builtins: Default::default(),
+            concretes: Default::default(),
             user_types: Default::default(),
             builtin_fns: builtin_fns::builtin_fns(),
             builtin_fn_inputs: Default::default(),
+            builtin_fn_nodes: Default::default(),
+            security_mode: false,
+            max_unroll: 0,
+            assembly_unknown: false,
+            diagnostics: vec![],
+            pragmas: Default::default(),
+            annotations: Default::default(),
+            using: Default::default(),
+            using_any: Default::default(),
+            contract_scope: None,
+            sources: vec![],
+            file_sources: Default::default(),
+            unresolved_arrays: vec![],
+            metrics: None,
         };
         a.builtin_fn_inputs = builtin_fns::builtin_fns_inputs(&mut a);
 
@@ -78,6 +261,56 @@ impl AnalyzerLike for Analyzer {
         self.block
     }
 
+    fn security_mode(&self) -> bool {
+        self.security_mode
+    }
+
+    fn max_unroll(&self) -> usize {
+        self.max_unroll
+    }
+
+    fn assembly_unknown(&self) -> bool {
+        self.assembly_unknown
+    }
+
+    fn record_unrolled_iteration(&mut self) {
+        if let Some(metrics) = &mut self.metrics {
+            metrics.unrolled_iterations += 1;
+        }
+    }
+
+    fn add_diagnostic(&mut self, diagnostic: Diagnostic) {
+        // forked contexts revisit the same statement, only report once per location
+        if !self.diagnostics.contains(&diagnostic) {
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    fn solidity_version(&self, source: NodeIdx) -> Option<&str> {
+        self.pragmas.get(&source).map(|version| version.as_str())
+    }
+
+    fn using_fns(&self, func: FunctionNode, ty: NodeIdx) -> Vec<FunctionNode> {
+        let scopes = [
+            func.contract(self).map(NodeIdx::from),
+            self.search_for_ancestor(func.into(), &Edge::Part),
+            Some(ty),
+        ];
+        scopes
+            .into_iter()
+            .flatten()
+            .flat_map(|scope| {
+                let typed = self.using.get(&(scope, ty)).into_iter().flatten();
+                typed.chain(self.using_any.get(&scope).into_iter().flatten())
+            })
+            .copied()
+            .collect()
+    }
+
     fn builtin_fns(&self) -> &HashMap<String, Function> {
         &self.builtin_fns
     }
@@ -86,12 +319,24 @@ impl AnalyzerLike for Analyzer {
         &self.builtin_fn_inputs
     }
 
+    fn builtin_fn_nodes(&self) -> &HashMap<String, NodeIdx> {
+        &self.builtin_fn_nodes
+    }
+    fn builtin_fn_nodes_mut(&mut self) -> &mut HashMap<String, NodeIdx> {
+        &mut self.builtin_fn_nodes
+    }
     fn builtins(&self) -> &HashMap<Builtin, NodeIdx> {
         &self.builtins
     }
     fn builtins_mut(&mut self) -> &mut HashMap<Builtin, NodeIdx> {
         &mut self.builtins
     }
+    fn concretes(&self) -> &HashMap<Concrete, NodeIdx> {
+        &self.concretes
+    }
+    fn concretes_mut(&mut self) -> &mut HashMap<Concrete, NodeIdx> {
+        &mut self.concretes
+    }
     fn user_types(&self) -> &HashMap<String, NodeIdx> {
         &self.user_types
     }
@@ -103,7 +348,7 @@ impl AnalyzerLike for Analyzer {
         use Expression::*;
         // println!("top level expr: {:?}", expr);
         match expr {
-            Type(_loc, ty) => {
+            Type(loc, ty) => {
                 if let Some(builtin) = Builtin::try_from_ty(ty.clone(), self) {
                     if let Some(idx) = self.builtins.get(&builtin) {
                         *idx
@@ -113,60 +358,123 @@ impl AnalyzerLike for Analyzer {
                         idx
                     }
                 } else {
-                    0.into()
+                    self.unresolved_ty(*loc, ty)
                 }
             }
             Variable(ident) => {
-                if let Some(idx) = self.user_types.get(&ident.name) {
-                    *idx
+                if let Some(idx) = self
+                    .contract_scope
+                    .and_then(|contract| contract.scoped_user_ty(self, &ident.name))
+                    .or_else(|| self.user_types.get(&ident.name).copied())
+                {
+                    idx
+                } else if let Some(builtin) = Builtin::try_from_fixed_name(&ident.name) {
+                    self.builtin_or_add(builtin)
                 } else {
                     let node = self.add_node(Node::Unresolved(ident.clone()));
                     self.user_types.insert(ident.name.clone(), node);
                     node
                 }
             }
-            ArraySubscript(_loc, ty_expr, None) => {
+            ArraySubscript(loc, ty_expr, maybe_len) => {
                 let inner_ty = self.parse_expr(ty_expr);
-                if let Some(var_type) = VarType::try_from_idx(self, inner_ty) {
-                    let dyn_b = Builtin::Array(var_type);
-                    if let Some(idx) = self.builtins.get(&dyn_b) {
-                        *idx
-                    } else {
-                        let idx = self.add_node(Node::Builtin(dyn_b.clone()));
-                        self.builtins.insert(dyn_b, idx);
-                        idx
-                    }
+                // a length that isn't known at compile time is treated as a dynamic array
+                let len = maybe_len.as_ref().and_then(|len| {
+                    let len = self.parse_expr(len);
+                    self.const_uint(len)
+                });
+                // the same array, spelled before its element type was declared, already has a
+                // placeholder, so reuse it rather than adding a second node for the type
+                if let Some((_, node, ..)) =
+                    self.unresolved_arrays
+                        .iter()
+                        .find(|(_, _, pending_ty, pending_len)| {
+                            *pending_ty == inner_ty && *pending_len == len
+                        })
+                {
+                    *node
+                } else if let Some(var_type) = VarType::try_from_idx(self, inner_ty) {
+                    self.builtin_or_add(Self::array_builtin(var_type, len))
                 } else {
-                    todo!("???")
-                }
-            }
-            ArraySubscript(_loc, ty_expr, Some(index_expr)) => {
-                let _inner_ty = self.parse_expr(ty_expr);
-                let _index_ty = self.parse_expr(index_expr);
-                // println!("here: {:?}", index_expr);
-                // if let Some(var_type) = VarType::try_from_idx(self, inner_ty) {
-                //     let dyn_b = DynBuiltin::Array(var_type);
-                //     if let Some(idx) = self.dyn_builtins.get(&dyn_b) {
-                //         *idx
-                //     } else {
-                //         let idx = self.add_node(Node::DynBuiltin(dyn_b.clone()));
-                //         self.dyn_builtins.insert(dyn_b, idx);
-                //         idx
-                //     }
-                // } else {
-                //     todo!("???")
-                // }
-                0.into()
+                    // the element type is declared further down the file
+                    let node = self.add_node(Node::Unresolved(Identifier {
+                        loc: *loc,
+                        name: "array".to_string(),
+                    }));
+                    self.unresolved_arrays.push((*loc, node, inner_ty, len));
+                    node
+                }
             }
             NumberLiteral(_loc, int, exp) => {
-                let int = U256::from_dec_str(int).unwrap();
-                let val = if !exp.is_empty() {
-                    let exp = U256::from_dec_str(exp).unwrap();
-                    int.pow(exp)
-                } else {
-                    int
+                let val = context::exprs::number_literal_val(int, exp);
+                self.concrete_or_add(Concrete::Uint(256, val))
+            }
+            StringLiteral(lits) => {
+                let val = lits
+                    .iter()
+                    .map(|lit| lit.string.as_str())
+                    .collect::<String>();
+                self.concrete_or_add(Concrete::String(val))
+            }
+            BoolLiteral(_loc, b) => self.concrete_or_add(Concrete::Bool(*b)),
+            HexNumberLiteral(_loc, int) => {
+                let val = context::exprs::hex_num_literal_val(int);
+                self.concrete_or_add(Concrete::Uint(256, val))
+            }
+            RationalNumberLiteral(loc, int, frac, exp) => {
+                self.rational_literal(*loc, int, frac, exp, U256::from(1))
+            }
+            FunctionCall(loc, func, args) if matches!(&**func, Type(..)) && args.len() == 1 => {
+                self.top_level_cast(*loc, func, &args[0])
+            }
+            MemberAccess(_loc, member_expr, ident) => match &**member_expr {
+                FunctionCall(_, func, args)
+                    if matches!(&**func, Variable(func) if func.name == "type")
+                        && args.len() == 1 =>
+                {
+                    let ty = self.parse_expr(&args[0]);
+                    let Node::Builtin(builtin) = self.node(ty) else {
+                        return 0.into();
+                    };
+                    let c = match &*ident.name {
+                        "max" => builtin.max_value(),
+                        "min" => builtin.min_value(),
+                        "bits" => builtin.size().map(|size| Concrete::from(U256::from(size))),
+                        _ => None,
+                    };
+                    c.map(|c| self.concrete_or_add(c))
+                        .unwrap_or_else(|| 0.into())
+                }
+                _ => 0.into(),
+            },
+            Add(loc, lhs, rhs) => self.top_level_op(*loc, lhs, rhs, RangeOp::Add),
+            Subtract(loc, lhs, rhs) => self.top_level_op(*loc, lhs, rhs, RangeOp::Sub),
+            Multiply(loc, lhs, rhs) => self.top_level_op(*loc, lhs, rhs, RangeOp::Mul),
+            Divide(loc, lhs, rhs) => self.top_level_op(*loc, lhs, rhs, RangeOp::Div),
+            Modulo(loc, lhs, rhs) => self.top_level_op(*loc, lhs, rhs, RangeOp::Mod),
+            Power(loc, lhs, rhs) => self.top_level_op(*loc, lhs, rhs, RangeOp::Exp),
+            Parenthesis(_loc, expr) => self.parse_expr(expr),
+            UnaryMinus(loc, expr) => {
+                let inner = self.parse_expr(expr);
+                let Node::Concrete(Concrete::Uint(_, val)) = self.node(inner) else {
+                    return 0.into();
                 };
-                self.add_node(Node::Concrete(Concrete::Uint(256, val)))
+                let val = context::exprs::negative_literal_val(*val)
+                    .map(|(_, val)| val)
+                    .unwrap_or_else(|| self.negative_literal_overflow(*loc));
+                self.concrete_or_add(Concrete::Int(256, val))
+            }
+            Unit(loc, expr, unit) => {
+                let multiplier = context::exprs::unit_multiplier(unit);
+                match &**expr {
+                    NumberLiteral(_, int, exp) => {
+                        self.rational_literal(*loc, int, "", exp, multiplier)
+                    }
+                    RationalNumberLiteral(_, int, frac, exp) => {
+                        self.rational_literal(*loc, int, frac, exp, multiplier)
+                    }
+                    _ => 0.into(),
+                }
             }
             _ => 0.into(),
         }
@@ -174,192 +482,1395 @@ impl AnalyzerLike for Analyzer {
 }
 
 impl Analyzer {
-    pub fn parse(
+    /// Parses and analyzes a file, returning its source unit node. Files it imports are
+    /// recorded in [`Analyzer::sources`]
+    pub fn parse(&mut self, src: &str, file_no: usize) -> Result<NodeIdx, ParseError> {
+        let start = Instant::now();
+        let parsed = self.parse_file(src, file_no);
+        self.record_parse_time(start);
+        let (entry, imported) = parsed?;
+        self.sources.extend(imported);
+        Ok(entry)
+    }
+
+    /// The metrics collected so far, along with the current size of the graph, if collection was
+    /// enabled by setting [`Analyzer::metrics`]
+    pub fn metrics(&self) -> Option<Metrics> {
+        self.metrics.as_ref().map(|metrics| Metrics {
+            node_count: self.graph.node_count(),
+            edge_count: self.graph.edge_count(),
+            ..metrics.clone()
+        })
+    }
+
+    fn record_parse_time(&mut self, start: Instant) {
+        if let Some(metrics) = &mut self.metrics {
+            metrics.parse_time += start.elapsed();
+        }
+    }
+
+    /// Builds the contexts of `func`'s body, if it has one
+    fn analyze_body(&mut self, func: FunctionNode) {
+        let Some(body) = func.underlying(self).body.clone() else {
+            return;
+        };
+        let start = Instant::now();
+        self.parse_ctx_statement(&body, false, Some(func));
+        if self.metrics.is_some() {
+            let name = match func.contract(self) {
+                Some(contract) => format!("{}.{}", contract.name(self), func.name(self)),
+                None => func.name(self),
+            };
+            if let Some(metrics) = &mut self.metrics {
+                *metrics.ctx_times.entry(name).or_default() += start.elapsed();
+            }
+        }
+    }
+
+    /// Like [`Analyzer::parse`], but panics if the file can't be parsed
+    pub fn parse_or_panic(&mut self, src: &str, file_no: usize) -> NodeIdx {
+        self.parse(src, file_no)
+            .unwrap_or_else(|e| panic!("FAIL to parse, {e}"))
+    }
+
+    /// Reads, parses and analyzes the file at `path`, returning the bounds of every function
+    /// body in it. The file is numbered after any file parsed before it and reported under
+    /// `path`, so [`Analyzer::file_mapping`] and [`Analyzer::source_cache`] can print the results
+    pub fn analyze_file(
+        &mut self,
+        path: &Path,
+        config: ReportConfig,
+    ) -> Result<Vec<FunctionVarsBoundAnalysis>, AnalyzeError> {
+        let sol = fs::read_to_string(path).map_err(|e| AnalyzeError::Io(path.to_path_buf(), e))?;
+        let file_no = if self.file_sources.is_empty() {
+            0
+        } else {
+            self.file_no + 1
+        };
+        self.root = path
+            .parent()
+            .map(|parent| parent.to_path_buf())
+            .unwrap_or_default();
+        self.file_sources.entry(file_no).or_default().0 = path.to_path_buf();
+        let entry = self.parse(&sol, file_no)?;
+
+        let file_mapping = self.file_mapping();
+        let ctxs = self
+            .search_children(entry, &Edge::Context(ContextEdge::Context))
+            .into_iter()
+            .map(ContextNode::from)
+            .collect::<Vec<_>>();
+        let ctxs = if config.interprocedural {
+            ctxs.into_iter()
+                .map(|ctx| {
+                    let func = ctx.underlying(self).parent_fn;
+                    self.interprocedural_ctx(func).unwrap_or(ctx)
+                })
+                .collect()
+        } else {
+            ctxs
+        };
+        #[cfg(feature = "rayon")]
+        let analyses = self.bounds_for_all_parallel(&file_mapping, &ctxs, config);
+        #[cfg(not(feature = "rayon"))]
+        let analyses = ctxs
+            .into_iter()
+            .map(|ctx| self.bounds_for_all(&file_mapping, ctx, config))
+            .collect();
+        Ok(analyses)
+    }
+
+    /// Runs the bounds analysis, along with the checks enabled in `config`, over the body of
+    /// every function parsed so far. Unlike [`Analyzer::analyze_file`] this doesn't add
+    /// contexts, so internal functions are reported for their full parameter ranges even with
+    /// `config.interprocedural`. If [`Analyzer::metrics`] are collected, the report includes
+    /// them along with the time taken by the reports
+    pub fn analyze_all<'a>(
+        &'a self,
+        file_mapping: &'a BTreeMap<usize, String>,
+        config: ReportConfig,
+    ) -> FullReport<'a> {
+        let start = Instant::now();
+        let functions = self
+            .graph
+            .node_indices()
+            .filter(|idx| {
+                matches!(self.node(*idx), Node::Function(func) if func.ty != FunctionTy::Modifier)
+            })
+            .map(FunctionNode::from)
+            .filter_map(|func| {
+                let ctx = func.maybe_body_ctx(self)?;
+                Some(FunctionReport {
+                    func,
+                    bounds: self.bounds_for_all(file_mapping, ctx, config),
+                    overflow: config
+                        .overflow
+                        .then(|| self.overflow_report(file_mapping, ctx, config)),
+                    dead_code: config
+                        .dead_code
+                        .then(|| self.dead_code_report(file_mapping, ctx, config)),
+                    shadowing: config
+                        .shadowing
+                        .then(|| self.shadowing_report(file_mapping, ctx, config)),
+                    value_flow: config
+                        .value_flow
+                        .then(|| self.value_flow_report(file_mapping, ctx, config)),
+                })
+            })
+            .collect();
+        let metrics = self.metrics().map(|metrics| Metrics {
+            report_time: start.elapsed(),
+            ..metrics
+        });
+        FullReport { functions, metrics }
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn parse_file(
         &mut self,
         src: &str,
-    ) -> (
-        Option<NodeIdx>,
-        Vec<(Option<NodeIdx>, String, String, usize)>,
-    ) {
-        let file_no = self.file_no;
+        file_no: usize,
+    ) -> Result<(NodeIdx, Vec<(Option<NodeIdx>, String, String, usize)>), ParseError> {
+        // imports are numbered after the highest file seen so far
+        self.file_no = self.file_no.max(file_no);
+        // keep a path registered ahead of time, i.e. by an import or the caller
+        self.file_sources.entry(file_no).or_default().1 = src.to_string();
         let mut imported = vec![];
         match solang_parser::parse(src, file_no) {
             Ok((source_unit, _comments)) => {
+                // along with the name of the contract they are in, if any
+                let usings = source_unit
+                    .0
+                    .iter()
+                    .flat_map(|part| match part {
+                        SourceUnitPart::Using(using) => vec![(None, *using.clone())],
+                        SourceUnitPart::ContractDefinition(def) => def
+                            .parts
+                            .iter()
+                            .filter_map(|cpart| match cpart {
+                                ContractPart::Using(using) => Some((
+                                    def.name.as_ref().map(|name| name.name.clone()),
+                                    *using.clone(),
+                                )),
+                                _ => None,
+                            })
+                            .collect(),
+                        _ => vec![],
+                    })
+                    .collect::<Vec<_>>();
                 let parent = self.add_node(Node::SourceUnit(file_no));
                 let funcs = self.parse_source_unit(source_unit, file_no, parent, &mut imported);
+                self.resolve_arrays();
                 funcs.iter().for_each(|func| {
                     // add params now that parsing is done
+                    self.contract_scope = func.contract(self);
                     func.set_params_and_ret(self);
                     func.set_modifiers(self);
+                    self.contract_scope = None;
                     let name = func.name(self);
-                    if let Some(user_ty_node) = self.user_types.get(&name).cloned() {
-                        let underlying = func.underlying(self).clone();
-                        let unresolved = self.node_mut(user_ty_node);
-                        *unresolved = Node::Function(underlying);
-                    } else {
-                        self.user_types
-                            .insert(name.to_string(), NodeIdx::from(*func));
+                    match self.user_types.get(&name).cloned() {
+                        Some(user_ty_node)
+                            if matches!(self.node(user_ty_node), Node::Unresolved(_)) =>
+                        {
+                            let underlying = func.underlying(self).clone();
+                            let unresolved = self.node_mut(user_ty_node);
+                            *unresolved = Node::Function(underlying);
+                        }
+                        // the same signature in another contract, keep the first one
+                        Some(_) => {}
+                        None => {
+                            self.user_types
+                                .insert(name.to_string(), NodeIdx::from(*func));
+                        }
+                    }
+                    // overloads and functions of different contracts sharing a signature stay
+                    // reachable through their contract qualified name
+                    if let Some(contract) = func.contract(self) {
+                        let qualified = format!("{}.{}", contract.name(self), name);
+                        self.user_types.insert(qualified, NodeIdx::from(*func));
                     }
                 });
 
-                funcs.into_iter().for_each(|func| {
-                    if let Some(body) = &func.underlying(self).body.clone() {
-                        self.parse_ctx_statement(body, false, Some(func));
-                    }
+                // libraries can be declared after they are used, so bind them once everything is named
+                let contracts = self
+                    .search_children(parent, &Edge::Contract)
+                    .into_iter()
+                    .map(ContractNode::from)
+                    .collect::<Vec<_>>();
+                usings.iter().for_each(|(contract_name, using)| {
+                    self.contract_scope = contract_name.as_ref().and_then(|name| {
+                        contracts
+                            .iter()
+                            .find(|contract| contract.name(self) == *name)
+                            .copied()
+                    });
+                    let scope = self.contract_scope.map_or(parent, NodeIdx::from);
+                    self.parse_using(using, scope);
+                    self.contract_scope = None;
                 });
 
-                (Some(parent), imported)
+                funcs.into_iter().for_each(|func| self.analyze_body(func));
+
+                Ok((parent, imported))
             }
-            Err(e) => panic!("FAIL to parse, {e:?}"),
+            Err(diagnostics) => Err(ParseError {
+                file_no,
+                diagnostics,
+            }),
         }
     }
 
-    pub fn parse_source_unit(
-        &mut self,
-        source_unit: SourceUnit,
-        file_no: usize,
-        parent: NodeIdx,
-        imported: &mut Vec<(Option<NodeIdx>, String, String, usize)>,
-    ) -> Vec<FunctionNode> {
-        let mut all_funcs = vec![];
-        source_unit
-            .0
+    /// Serializes the graph to JSON, along with the pragmas, annotations, `using` directives and
+    /// sources of the parsed files. Nodes and edges keep their indices, so any [`NodeIdx`] taken
+    /// from this analyzer is valid in the one returned by [`Analyzer::from_json`]. Settings and
+    /// diagnostics are not saved
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        fn entries<K: Clone, V: Clone>(map: &HashMap<K, V>) -> Vec<(K, V)> {
+            map.iter()
+                .map(|(key, val)| (key.clone(), val.clone()))
+                .collect()
+        }
+        serde_json::to_string(&SavedAnalyzer {
+            graph: Cow::Borrowed(&self.graph),
+            file_no: self.file_no,
+            imported_files: entries(&self.imported_files),
+            pragmas: entries(&self.pragmas),
+            annotations: entries(&self.annotations),
+            using: entries(&self.using),
+            using_any: entries(&self.using_any),
+            sources: self.sources.clone(),
+            file_sources: entries(&self.file_sources),
+        })
+    }
+
+    /// Loads an analyzer written by [`Analyzer::to_json`], rebuilding the lookup tables that can
+    /// be derived from the graph
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        let saved: SavedAnalyzer = serde_json::from_str(json)?;
+        // the builtin function inputs point at the builtins every analyzer starts out with, which
+        // are at the front of the loaded graph as well
+        let mut analyzer = Self {
+            graph: saved.graph.into_owned(),
+            builtins: Default::default(),
+            concretes: Default::default(),
+            user_types: Default::default(),
+            file_no: saved.file_no,
+            imported_files: saved.imported_files.into_iter().collect(),
+            pragmas: saved.pragmas.into_iter().collect(),
+            annotations: saved.annotations.into_iter().collect(),
+            using: saved.using.into_iter().collect(),
+            using_any: saved.using_any.into_iter().collect(),
+            sources: saved.sources,
+            file_sources: saved.file_sources.into_iter().collect(),
+            ..Default::default()
+        };
+        analyzer.graph.node_indices().for_each(|idx| {
+            let name = match analyzer.node(idx) {
+                Node::Msg(_) => {
+                    analyzer.msg = idx.into();
+                    None
+                }
+                Node::Block(_) => {
+                    analyzer.block = idx.into();
+                    None
+                }
+                Node::Builtin(builtin) => {
+                    analyzer.builtins.insert(builtin.clone(), idx);
+                    None
+                }
+                Node::Concrete(concrete) => {
+                    analyzer.concretes.entry(concrete.clone()).or_insert(idx);
+                    None
+                }
+                Node::Contract(_) => Some(ContractNode::from(idx).name(&analyzer)),
+                Node::Function(func) if func.loc == Loc::Builtin => {
+                    let name = func.name.as_ref().expect("unnamed builtin?").name.clone();
+                    analyzer.builtin_fn_nodes.insert(name, idx);
+                    None
+                }
+                Node::Function(_) => Some(FunctionNode::from(idx).name(&analyzer)),
+                Node::Struct(_) => Some(StructNode::from(idx).name(&analyzer)),
+                Node::Enum(_) => Some(EnumNode::from(idx).name(&analyzer)),
+                Node::Var(_) => Some(VarNode::from(idx).name(&analyzer)),
+                Node::Ty(ty) => Some(ty.name.name.clone()),
+                Node::Unresolved(ident) => Some(ident.name.clone()),
+                _ => None,
+            };
+            let Some(name) = name else {
+                return;
+            };
+            // definitions in a contract are also reachable through their qualified name, see
+            // `Analyzer::add_user_ty`
+            let contract = analyzer
+                .graph
+                .edges_directed(idx, Direction::Outgoing)
+                .filter(|edge| {
+                    matches!(
+                        edge.weight(),
+                        Edge::Struct | Edge::Enum | Edge::Ty | Edge::Func | Edge::Modifier
+                    )
+                })
+                .map(|edge| edge.target())
+                .find(|target| matches!(analyzer.node(*target), Node::Contract(_)));
+            match contract {
+                Some(contract) => {
+                    let qualified =
+                        format!("{}.{}", ContractNode::from(contract).name(&analyzer), name);
+                    analyzer.user_types.insert(qualified, idx);
+                    analyzer.user_types.entry(name).or_insert(idx);
+                }
+                None => {
+                    analyzer.user_types.insert(name, idx);
+                }
+            }
+        });
+        Ok(analyzer)
+    }
+
+    /// Maps each parsed file number to the name its source is reported under
+    pub fn file_mapping(&self) -> BTreeMap<usize, String> {
+        self.file_sources
             .iter()
-            .enumerate()
-            .for_each(|(unit_part, source_unit_part)| {
-                let (_sup, funcs) = self.parse_source_unit_part(
-                    source_unit_part,
-                    file_no,
-                    unit_part,
-                    parent,
-                    imported,
-                );
-                all_funcs.extend(funcs);
-            });
-        all_funcs
+            .map(|(file_no, (path, _src))| (*file_no, path.to_string_lossy().to_string()))
+            .collect()
     }
 
-    pub fn parse_source_unit_part(
-        &mut self,
-        sup: &SourceUnitPart,
-        file_no: usize,
-        unit_part: usize,
-        parent: NodeIdx,
-        imported: &mut Vec<(Option<NodeIdx>, String, String, usize)>,
-    ) -> (NodeIdx, Vec<FunctionNode>) {
-        use SourceUnitPart::*;
+    /// A source cache for printing reports, resolving the names from [`Analyzer::file_mapping`]
+    pub fn source_cache(&self) -> impl Cache<String> {
+        sources(
+            self.file_sources
+                .values()
+                .map(|(path, src)| (path.to_string_lossy().to_string(), src.clone()))
+                .collect::<Vec<_>>(),
+        )
+    }
 
-        let sup_node = self.add_node(Node::SourceUnitPart(file_no, unit_part));
-        self.add_edge(sup_node, parent, Edge::Part);
+    /// All parsed contracts, interfaces and libraries, in the order they were added
+    pub fn contracts(&self) -> Vec<ContractNode> {
+        self.nodes()
+            .filter(|(_, node)| matches!(node, Node::Contract(_)))
+            .map(|(idx, _)| ContractNode::from(idx))
+            .collect()
+    }
 
-        let mut func_nodes = vec![];
+    /// All parsed functions, including free functions and modifiers. Builtins like `ecrecover`
+    /// only get a node when called and are left out
+    pub fn functions(&self) -> Vec<FunctionNode> {
+        self.nodes()
+            .filter(|(_, node)| matches!(node, Node::Function(func) if func.loc != Loc::Builtin))
+            .map(|(idx, _)| FunctionNode::from(idx))
+            .collect()
+    }
 
-        match sup {
-            ContractDefinition(def) => {
-                let (node, funcs) = self.parse_contract_def(def, imported);
-                self.add_edge(node, sup_node, Edge::Contract);
-                func_nodes.extend(funcs);
-            }
-            StructDefinition(def) => {
-                let node = self.parse_struct_def(def);
-                self.add_edge(node, sup_node, Edge::Struct);
-            }
-            EnumDefinition(def) => {
-                let node = self.parse_enum_def(def);
-                self.add_edge(node, sup_node, Edge::Enum);
-            }
-            ErrorDefinition(def) => {
-                let node = self.parse_err_def(def);
-                self.add_edge(node, sup_node, Edge::Error);
-            }
-            VariableDefinition(def) => {
-                let (node, maybe_func) = self.parse_var_def(def, false);
-                if let Some(func) = maybe_func {
-                    func_nodes.push(self.handle_func(func, None));
+    /// The first parsed contract named `name`
+    pub fn contract_by_name(&self, name: &str) -> Option<ContractNode> {
+        self.contracts()
+            .into_iter()
+            .find(|contract| contract.name(self) == name)
+    }
+
+    /// The parsed functions named `name`, i.e. every overload and every contract's definition
+    pub fn functions_by_name(&self, name: &str) -> Vec<FunctionNode> {
+        self.functions()
+            .into_iter()
+            .filter(|func| func.name(self).split('(').next() == Some(name))
+            .collect()
+    }
+
+    /// The value of the `@name(..)` annotation in a source unit or contract
+    pub fn annotation(&self, scope: impl Into<NodeIdx>, name: &str) -> Option<&Expression> {
+        self.annotations
+            .get(&scope.into())?
+            .iter()
+            .find(|anno| anno.id.name == name)
+            .map(|anno| &anno.value)
+    }
+
+    /// Removes all contexts and context variables from the graph, keeping the parsed definitions
+    /// so that analysis can be rerun via [`Analyzer::reanalyze`]. The temporaries modeling a
+    /// constant's initializer are part of its definition and are kept
+    pub fn reset_contexts(&mut self) {
+        let is_ctx_node = |node: &Node| {
+            matches!(
+                node,
+                Node::Context(_) | Node::ContextVar(_) | Node::ContextFork | Node::FunctionCall
+            )
+        };
+        let initializers = self.initializer_vars();
+        self.retain_nodes(|idx, node| !is_ctx_node(node) || initializers.contains(&idx));
+    }
+
+    /// The context variables that state variable initializers are made of, i.e. the
+    /// temporaries of `FEE * 2` in `uint256 constant DOUBLE_FEE = FEE * 2`
+    fn initializer_vars(&self) -> BTreeSet<NodeIdx> {
+        let mut vars = BTreeSet::default();
+        let mut stack = self
+            .graph
+            .node_weights()
+            .filter_map(|node| match node {
+                Node::Var(var) => var.initializer,
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        while let Some(idx) = stack.pop() {
+            if let Node::ContextVar(var) = self.node(idx) {
+                if vars.insert(idx) {
+                    stack.extend(var.referenced_idxs());
                 }
-                self.add_edge(node, sup_node, Edge::Var);
-            }
-            FunctionDefinition(def) => {
-                let node = self.parse_func_def(def, None);
-                func_nodes.push(node);
-                self.add_edge(node, sup_node, Edge::Func);
-            }
-            TypeDefinition(def) => {
-                let node = self.parse_ty_def(def);
-                self.add_edge(node, sup_node, Edge::Ty);
             }
-            EventDefinition(_def) => todo!(),
-            Annotation(_anno) => todo!(),
-            Using(_using) => todo!(),
-            StraySemicolon(_loc) => todo!(),
-            PragmaDirective(_, _, _) => {}
-            ImportDirective(import) => imported.extend(self.parse_import(import)),
         }
-        (sup_node, func_nodes)
+        vars
     }
 
-    pub fn parse_import(
-        &mut self,
-        import: &Import,
-    ) -> Vec<(Option<NodeIdx>, String, String, usize)> {
-        match import {
-            Import::Plain(path, _) => {
-                // println!("path: {:?}", path);
-                let sol = fs::read_to_string(path.string.clone()).unwrap_or_else(|_| {
-                    panic!("Could not find file for dependency: {:?}", path.string)
-                });
-                self.file_no += 1;
-                let file_no = self.file_no;
-                let (maybe_entry, mut inner_sources) = self.parse(&sol);
-                inner_sources.push((maybe_entry, path.string.clone(), sol.to_string(), file_no));
-                inner_sources
-            }
-            Import::Rename(path, elems, _) => {
-                println!(
-                    "path: {:?}, elems: {:?}, curr: {:?}",
-                    path,
-                    elems,
-                    std::env::current_dir()
+    /// Removes temporary context variables that nothing refers to anymore, i.e. intermediate
+    /// results of arithmetic and comparisons that no range, return or other variable depends
+    /// on, and returns how many were removed. Like [`Analyzer::reset_contexts`] this compacts
+    /// the graph, so a [`NodeIdx`] held from before the call may point at a different node
+    pub fn clear_tmps(&mut self) -> usize {
+        let is_tmp = |node: &Node| matches!(node, Node::ContextVar(var) if var.is_tmp());
+        let (tmps, others): (Vec<_>, Vec<_>) = self
+            .graph
+            .node_indices()
+            .partition(|idx| is_tmp(self.node(*idx)));
+        let mut live = others.into_iter().collect::<BTreeSet<_>>();
+
+        // a temporary that is returned, indexed or otherwise linked beyond its context and
+        // versions is part of the analysis
+        let linked = |edge: &Edge| {
+            !matches!(
+                edge,
+                Edge::Context(ContextEdge::Variable) | Edge::Context(ContextEdge::Prev)
+            )
+        };
+        tmps.iter()
+            .filter(|tmp| {
+                self.graph
+                    .edges_directed(**tmp, Direction::Outgoing)
+                    .chain(self.graph.edges_directed(**tmp, Direction::Incoming))
+                    .any(|edge| linked(edge.weight()))
+            })
+            .for_each(|tmp| {
+                live.insert(*tmp);
+            });
+
+        // anything a live node refers to is live, and so are the later versions of a live
+        // temporary since its latest version may be looked up
+        let mut stack = live.iter().copied().collect::<Vec<_>>();
+        while let Some(idx) = stack.pop() {
+            let mut refs = match self.node(idx) {
+                Node::Context(ctx) => ctx.referenced_idxs(),
+                Node::ContextVar(var) => var.referenced_idxs(),
+                Node::Var(var) => var.initializer.into_iter().collect(),
+                _ => vec![],
+            };
+            if is_tmp(self.node(idx)) {
+                refs.extend(
+                    self.graph
+                        .edges_directed(idx, Direction::Incoming)
+                        .filter(|edge| *edge.weight() == Edge::Context(ContextEdge::Prev))
+                        .map(|edge| edge.source()),
                 );
-                let sol = fs::read_to_string(path.string.clone()).unwrap_or_else(|_| {
-                    panic!("Could not find file for dependency: {:?}", path.string)
-                });
-                self.file_no += 1;
-                let file_no = self.file_no;
-                let (maybe_entry, mut inner_sources) = self.parse(&sol);
-                inner_sources.push((maybe_entry, path.string.clone(), sol.to_string(), file_no));
-                inner_sources
             }
-            e => todo!("import {:?}", e),
+            refs.into_iter().for_each(|idx| {
+                if live.insert(idx) {
+                    stack.push(idx);
+                }
+            });
+        }
+
+        let removed = tmps.iter().filter(|tmp| !live.contains(tmp)).count();
+        if removed > 0 {
+            self.retain_nodes(|idx, _node| live.contains(&idx));
         }
+        removed
     }
 
-    pub fn parse_contract_def(
+    /// Drops the nodes `keep` rejects, along with their edges, and renumbers the remaining nodes
+    /// in order, updating every index the graph and the analyzer hold. Returns where each
+    /// remaining node ended up
+    fn retain_nodes(
         &mut self,
-        contract_def: &ContractDefinition,
-        imports: &[(Option<NodeIdx>, String, String, usize)],
-    ) -> (ContractNode, Vec<FunctionNode>) {
-        use ContractPart::*;
+        keep: impl Fn(NodeIdx, &Node) -> bool,
+    ) -> BTreeMap<NodeIdx, NodeIdx> {
+        // compacting the graph keeps the order of the remaining nodes
+        let mut mapping = BTreeMap::default();
+        self.graph
+            .node_indices()
+            .filter(|idx| keep(*idx, self.node(*idx)))
+            .enumerate()
+            .for_each(|(new_idx, old_idx)| {
+                mapping.insert(old_idx, NodeIdx::from(new_idx));
+            });
+        self.graph = self.graph.filter_map(
+            |idx, node| keep(idx, node).then(|| node.clone()),
+            |_idx, edge| Some(*edge),
+        );
+        self.graph
+            .node_weights_mut()
+            .for_each(|node| node.update_idxs(&mapping));
 
-        let contract = Contract::from_w_imports(contract_def.clone(), imports, self);
-        let inherits = contract.inherits.clone();
-        let con_node = ContractNode(self.add_node(contract).index());
-        inherits.iter().for_each(|contract_node| {
-            self.add_edge(*contract_node, con_node, Edge::InheritedContract);
-        });
+        let update = |idx: NodeIdx| mapping[&idx];
+        self.msg = update(self.msg.into()).into();
+        self.block = update(self.block.into()).into();
+        self.builtins = std::mem::take(&mut self.builtins)
+            .into_iter()
+            .map(|(mut builtin, idx)| {
+                builtin.update_idxs(&mapping);
+                (builtin, update(idx))
+            })
+            .collect();
+        self.concretes
+            .values_mut()
+            .for_each(|idx| *idx = update(*idx));
+        self.builtin_fn_nodes
+            .values_mut()
+            .for_each(|idx| *idx = update(*idx));
+        self.user_types
+            .values_mut()
+            .for_each(|idx| *idx = update(*idx));
+        self.imported_files
+            .values_mut()
+            .for_each(|idx| *idx = update(*idx));
+        self.pragmas = std::mem::take(&mut self.pragmas)
+            .into_iter()
+            .map(|(idx, version)| (update(idx), version))
+            .collect();
+        self.annotations = std::mem::take(&mut self.annotations)
+            .into_iter()
+            .map(|(idx, annotations)| (update(idx), annotations))
+            .collect();
+        let update_funcs = |funcs: Vec<FunctionNode>| {
+            funcs
+                .into_iter()
+                .map(|func| update(func.into()).into())
+                .collect::<Vec<_>>()
+        };
+        self.using = std::mem::take(&mut self.using)
+            .into_iter()
+            .map(|((scope, ty), funcs)| ((update(scope), update(ty)), update_funcs(funcs)))
+            .collect();
+        self.using_any = std::mem::take(&mut self.using_any)
+            .into_iter()
+            .map(|(scope, funcs)| (update(scope), update_funcs(funcs)))
+            .collect();
+        self.sources
+            .iter_mut()
+            .for_each(|(entry, ..)| *entry = entry.map(update));
+        mapping
+    }
 
-        let mut func_nodes = vec![];
-        contract_def.parts.iter().for_each(|cpart| match cpart {
-            StructDefinition(def) => {
-                let node = self.parse_struct_def(def);
-                self.add_edge(node, con_node, Edge::Struct);
+    /// Adds the graph of `other` to this one, returning where each of its nodes ended up. The
+    /// msg, block, builtin and constant nodes are shared with the ones already in this graph,
+    /// everything else is copied over. Names defined in both keep pointing at the definition in
+    /// this analyzer, except that a file level definition takes the bare name from one in a
+    /// contract, like it would if both had been parsed by the same analyzer. Reports refer to
+    /// files by number, so the merged analyzers should have parsed different file numbers
+    pub fn merge(&mut self, other: Analyzer) -> BTreeMap<NodeIdx, NodeIdx> {
+        let mut mapping = BTreeMap::default();
+        let mut shared = BTreeSet::default();
+        // indices only ever grow, so the types a builtin refers to come before it
+        other.graph.node_indices().for_each(|idx| {
+            let existing = match &other.graph[idx] {
+                Node::Msg(_) => Some(self.msg.into()),
+                Node::Block(_) => Some(self.block.into()),
+                Node::Concrete(concrete) => self.concretes.get(concrete).copied(),
+                Node::Builtin(builtin) => {
+                    let mut builtin = builtin.clone();
+                    builtin.update_idxs(&mapping);
+                    self.builtins.get(&builtin).copied()
+                }
+                _ => None,
+            };
+            let new_idx = match existing {
+                Some(existing) => {
+                    shared.insert(idx);
+                    existing
+                }
+                None => self.add_node(other.graph[idx].clone()),
+            };
+            mapping.insert(idx, new_idx);
+        });
+        other
+            .graph
+            .node_indices()
+            .filter(|idx| !shared.contains(idx))
+            .for_each(|idx| {
+                let new_idx = mapping[&idx];
+                self.node_mut(new_idx).update_idxs(&mapping);
+                match self.node(new_idx).clone() {
+                    Node::Builtin(builtin) => {
+                        self.builtins.insert(builtin, new_idx);
+                    }
+                    Node::Concrete(concrete) => {
+                        self.concretes.insert(concrete, new_idx);
+                    }
+                    _ => {}
+                }
+            });
+        other.graph.edge_references().for_each(|edge| {
+            // both ends were already here, and so is the edge
+            if shared.contains(&edge.source()) && shared.contains(&edge.target()) {
+                return;
             }
-            EnumDefinition(def) => {
-                let node = self.parse_enum_def(def);
-                self.add_edge(node, con_node, Edge::Enum);
+            self.add_edge(
+                mapping[&edge.source()],
+                mapping[&edge.target()],
+                *edge.weight(),
+            );
+        });
+
+        let update = |idx: NodeIdx| mapping[&idx];
+        let scoped = |types: &HashMap<String, NodeIdx>, name: &str, idx: NodeIdx| {
+            types.iter().any(|(key, ty)| {
+                *ty == idx && key.strip_suffix(name).is_some_and(|c| c.ends_with('.'))
+            })
+        };
+        other.user_types.iter().for_each(|(name, idx)| {
+            let idx = update(*idx);
+            let replace = match self.user_types.get(name) {
+                None => true,
+                Some(existing) if matches!(self.node(*existing), Node::Unresolved(_)) => true,
+                Some(existing) => {
+                    !name.contains('.')
+                        && scoped(&self.user_types, name, *existing)
+                        && !scoped(&other.user_types, name, other.user_types[name])
+                }
+            };
+            if replace {
+                self.user_types.insert(name.clone(), idx);
             }
-            ErrorDefinition(def) => {
-                let node = self.parse_err_def(def);
+        });
+        other.builtin_fn_nodes.into_iter().for_each(|(name, idx)| {
+            self.builtin_fn_nodes.entry(name).or_insert(update(idx));
+        });
+        other.imported_files.into_iter().for_each(|(path, idx)| {
+            self.imported_files.entry(path).or_insert(update(idx));
+        });
+        self.pragmas.extend(
+            other
+                .pragmas
+                .into_iter()
+                .map(|(idx, version)| (update(idx), version)),
+        );
+        self.annotations.extend(
+            other
+                .annotations
+                .into_iter()
+                .map(|(idx, annotations)| (update(idx), annotations)),
+        );
+        other.using.into_iter().for_each(|((scope, ty), funcs)| {
+            self.using
+                .entry((update(scope), update(ty)))
+                .or_default()
+                .extend(
+                    funcs
+                        .into_iter()
+                        .map(|func| FunctionNode::from(update(func.into()))),
+                );
+        });
+        other.using_any.into_iter().for_each(|(scope, funcs)| {
+            self.using_any.entry(update(scope)).or_default().extend(
+                funcs
+                    .into_iter()
+                    .map(|func| FunctionNode::from(update(func.into()))),
+            );
+        });
+        self.sources.extend(
+            other
+                .sources
+                .into_iter()
+                .map(|(entry, path, src, file_no)| (entry.map(update), path, src, file_no)),
+        );
+        other
+            .file_sources
+            .into_iter()
+            .for_each(|(file_no, source)| {
+                self.file_sources.entry(file_no).or_insert(source);
+            });
+        self.file_no = self.file_no.max(other.file_no);
+        self.diagnostics.extend(other.diagnostics);
+        mapping
+    }
+
+    /// Analyzes the bodies of all parsed functions, creating fresh contexts for each
+    pub fn reanalyze(&mut self) {
+        let funcs = self
+            .graph
+            .node_indices()
+            .filter(|idx| matches!(self.node(*idx), Node::Function(func) if func.body.is_some()))
+            .map(FunctionNode::from)
+            .collect::<Vec<_>>();
+        funcs.into_iter().for_each(|func| self.analyze_body(func));
+    }
+
+    /// Replaces the definitions parsed from `file_no` with those in `new_src`, returning the new
+    /// source unit node. Definitions in other files that refer to a type, contract or function
+    /// of the file by name are pointed at its new definition, or at an unresolved node if it is
+    /// gone. Every context is rebuilt, and like [`Analyzer::reset_contexts`] the graph is
+    /// compacted, so a [`NodeIdx`] held from before the call may point at a different node.
+    ///
+    /// If `new_src` does not parse, the analyzer is left as it was. A file that was never
+    /// parsed is parsed like with [`Analyzer::parse`]
+    pub fn reparse_file(&mut self, file_no: usize, new_src: &str) -> Result<NodeIdx, ParseError> {
+        if let Err(diagnostics) = solang_parser::parse(new_src, file_no) {
+            return Err(ParseError {
+                file_no,
+                diagnostics,
+            });
+        }
+        let source_unit = |analyzer: &Self| {
+            analyzer
+                .graph
+                .node_indices()
+                .find(|idx| matches!(analyzer.node(*idx), Node::SourceUnit(n) if *n == file_no))
+        };
+        if source_unit(self).is_none() {
+            return self.parse(new_src, file_no);
+        }
+
+        // contexts of every file may refer to the old definitions
+        self.reset_contexts();
+        let old_entry = source_unit(self).expect("source unit was removed with the contexts");
+        let owned = self.file_nodes(old_entry);
+
+        // names other files can refer to the old definitions by, and the edges they do so with
+        let old_names = self
+            .user_types
+            .iter()
+            .filter(|(_name, idx)| owned.contains(idx))
+            .map(|(name, idx)| (name.clone(), *idx))
+            .collect::<Vec<_>>();
+        let outside_edges = self
+            .graph
+            .edge_references()
+            .filter(|edge| !owned.contains(&edge.source()) && owned.contains(&edge.target()))
+            .map(|edge| (edge.source(), edge.target(), *edge.weight()))
+            .collect::<Vec<_>>();
+        let importers = self
+            .graph
+            .edges_directed(old_entry, Direction::Outgoing)
+            .filter(|edge| *edge.weight() == Edge::Import)
+            .map(|edge| edge.target())
+            .collect::<Vec<_>>();
+
+        self.user_types.retain(|_name, idx| !owned.contains(idx));
+        self.pragmas.retain(|idx, _| !owned.contains(idx));
+        self.annotations.retain(|idx, _| !owned.contains(idx));
+        self.using
+            .retain(|(scope, ty), _| !owned.contains(scope) && !owned.contains(ty));
+        self.using_any.retain(|scope, _| !owned.contains(scope));
+        self.using
+            .values_mut()
+            .chain(self.using_any.values_mut())
+            .for_each(|funcs| {
+                funcs.retain(|func| !owned.contains(&NodeIdx::from(*func)));
+            });
+        self.diagnostics
+            .retain(|diagnostic| diagnostic.loc.try_file_no() != Some(file_no));
+
+        // imports are relative to the file itself
+        let prev_root = self.root.clone();
+        if let Some(parent) = self
+            .file_sources
+            .get(&file_no)
+            .and_then(|(path, _)| path.parent())
+        {
+            self.root = parent.to_path_buf();
+        }
+        let start = Instant::now();
+        let parsed = self.parse_file(new_src, file_no);
+        self.record_parse_time(start);
+        self.root = prev_root;
+        let (new_entry, imported) = parsed?;
+        self.sources.extend(imported);
+        self.sources
+            .iter_mut()
+            .filter(|(entry, ..)| *entry == Some(old_entry))
+            .for_each(|(entry, _path, src, _)| {
+                *entry = Some(new_entry);
+                *src = new_src.to_string();
+            });
+        self.imported_files
+            .values_mut()
+            .filter(|idx| **idx == old_entry)
+            .for_each(|idx| *idx = new_entry);
+
+        // point the rest of the graph at the new definitions
+        let mut relink = BTreeMap::from([(old_entry, new_entry)]);
+        old_names.into_iter().for_each(|(name, old)| {
+            let new = match self.user_types.get(&name) {
+                Some(new) => *new,
+                None => {
+                    let ident = Identifier {
+                        loc: Loc::Implicit,
+                        name: name.clone(),
+                    };
+                    let placeholder = self.add_node(Node::Unresolved(ident));
+                    self.user_types.insert(name, placeholder);
+                    placeholder
+                }
+            };
+            relink.entry(old).or_insert(new);
+        });
+        self.graph
+            .node_indices()
+            .filter(|idx| !owned.contains(idx))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .for_each(|idx| {
+                let node = self.node_mut(idx);
+                node.update_idxs(&relink);
+                // a base contract that is gone is no longer inherited from
+                if let Node::Contract(contract) = node.clone() {
+                    let inherits = contract
+                        .inherits
+                        .into_iter()
+                        .filter(|base| matches!(self.node(NodeIdx::from(*base)), Node::Contract(_)))
+                        .collect();
+                    if let Node::Contract(contract) = self.node_mut(idx) {
+                        contract.inherits = inherits;
+                    }
+                }
+            });
+        self.builtins = std::mem::take(&mut self.builtins)
+            .into_iter()
+            .map(|(mut builtin, idx)| {
+                builtin.update_idxs(&relink);
+                (builtin, idx)
+            })
+            .collect();
+        outside_edges
+            .into_iter()
+            .filter_map(|(source, target, edge)| Some((source, *relink.get(&target)?, edge)))
+            .chain(
+                importers
+                    .into_iter()
+                    .map(|importer| (new_entry, importer, Edge::Import)),
+            )
+            .for_each(|(source, target, edge)| {
+                if matches!(self.node(target), Node::Unresolved(_)) {
+                    return;
+                }
+                if !self
+                    .graph
+                    .edges_connecting(source, target)
+                    .any(|existing| *existing.weight() == edge)
+                {
+                    self.add_edge(source, target, edge);
+                }
+            });
+
+        self.retain_nodes(|idx, _node| !owned.contains(&idx));
+        self.reset_contexts();
+        self.reanalyze();
+        Ok(source_unit(self).expect("the new source unit is kept"))
+    }
+
+    /// The nodes defined by the file of `source_unit`: its parts and everything declared in
+    /// them, down to struct fields and function parameters. Builtins, constants and other files
+    /// are shared, and contracts inheriting from or modifiers applied to something in the file
+    /// belong to wherever they are declared
+    fn file_nodes(&self, source_unit: NodeIdx) -> BTreeSet<NodeIdx> {
+        let mut owned = BTreeSet::from([source_unit]);
+        let mut stack = vec![source_unit];
+        while let Some(idx) = stack.pop() {
+            self.graph
+                .edges_directed(idx, Direction::Incoming)
+                .filter(|edge| {
+                    !matches!(
+                        edge.weight(),
+                        Edge::Import | Edge::Inherits | Edge::FuncModifier(_) | Edge::Context(_)
+                    )
+                })
+                .map(|edge| edge.source())
+                .filter(|source| {
+                    !matches!(
+                        self.node(*source),
+                        Node::SourceUnit(_)
+                            | Node::Builtin(_)
+                            | Node::Concrete(_)
+                            | Node::Msg(_)
+                            | Node::Block(_)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .into_iter()
+                .for_each(|source| {
+                    if owned.insert(source) {
+                        stack.push(source);
+                    }
+                });
+        }
+        owned
+    }
+
+    /// Models an arithmetic expression outside of any function, i.e. in a constant initializer,
+    /// as a temporary variable whose range is the op applied to the operands' ranges
+    fn top_level_op(
+        &mut self,
+        loc: Loc,
+        lhs: &Expression,
+        rhs: &Expression,
+        op: RangeOp,
+    ) -> NodeIdx {
+        let lhs = self.parse_expr(lhs);
+        let rhs = self.parse_expr(rhs);
+        let (Some(lhs), Some(rhs)) = (self.top_level_cvar(loc, lhs), self.top_level_cvar(loc, rhs))
+        else {
+            return self.unresolved_expr(loc, &format!("\"{}\" on a non-constant", op.to_string()));
+        };
+
+        if matches!(op, RangeOp::Div | RangeOp::Mod) {
+            let zero = Elem::from(Concrete::from(U256::zero()));
+            let zero_range = SolcRange {
+                min: zero.clone(),
+                max: zero,
+                exclusions: vec![],
+            };
+            if rhs
+                .range(self)
+                .map(|range| range.contains(&zero_range, self))
+                .unwrap_or(true)
+            {
+                self.add_warning(
+                    loc,
+                    format!("{} by a value that may be zero", op.to_string()),
+                );
+            }
+        }
+
+        let mut tmp = ContextVar {
+            display_name: format!(
+                "({} {} {})",
+                lhs.display_name(self),
+                op.to_string(),
+                rhs.display_name(self)
+            ),
+            is_tmp: true,
+            tmp_of: Some(TmpConstruction::new(lhs, op, Some(rhs))),
+            is_symbolic: lhs.is_symbolic(self) || rhs.is_symbolic(self),
+            ..ContextVar::new(
+                loc,
+                format!(
+                    "tmp{}({} {} {})",
+                    self.graph.node_count(),
+                    lhs.name(self),
+                    op.to_string(),
+                    rhs.name(self)
+                ),
+                lhs.underlying(self).ty.clone(),
+            )
+        };
+        tmp.ty.concrete_to_builtin(self);
+        let tmp = ContextVarNode::from(self.add_node(Node::ContextVar(tmp)));
+
+        if let Some(lhs_range) = lhs.range(self) {
+            let range = SolcRange::dyn_fn_from_op(op)(lhs_range, rhs, loc);
+            tmp.set_range_min(self, range.range_min());
+            tmp.set_range_max(self, range.range_max());
+        }
+        tmp.into()
+    }
+
+    /// A conversion in a top level expression, i.e. `uint8(300)` in an array length
+    fn top_level_cast(&mut self, loc: Loc, ty: &Expression, expr: &Expression) -> NodeIdx {
+        let ty = self.parse_expr(ty);
+        let inner = self.parse_expr(expr);
+        let Node::Builtin(builtin) = self.node(ty).clone() else {
+            return self.unresolved_expr(loc, "Conversion to a non-builtin type");
+        };
+        let (Some(inner), Some(ty_range)) = (
+            self.top_level_cvar(loc, inner),
+            SolcRange::try_from_builtin(&builtin),
+        ) else {
+            return self.unresolved_expr(loc, "Conversion of a non-constant");
+        };
+
+        let tmp = ContextVar {
+            display_name: format!("{}({})", builtin.as_string(self), inner.display_name(self)),
+            is_tmp: true,
+            tmp_of: Some(TmpConstruction::new(inner, RangeOp::Cast, None)),
+            is_symbolic: inner.is_symbolic(self),
+            ..ContextVar::new(
+                loc,
+                format!(
+                    "tmp{}({}({}))",
+                    self.graph.node_count(),
+                    builtin.as_string(self),
+                    inner.name(self)
+                ),
+                VarType::BuiltIn(ty.into(), None),
+            )
+        };
+        let tmp = ContextVarNode::from(self.add_node(Node::ContextVar(tmp)));
+
+        if inner.range(self).is_some() {
+            let whole = Elem::Dynamic(Dynamic::new(inner.into(), loc));
+            tmp.set_range_min(self, whole.clone().cast(ty_range.range_min()));
+            tmp.set_range_max(self, whole.cast(ty_range.range_max()));
+        }
+        tmp.into()
+    }
+
+    /// Stands in an unresolved node for a top level expression that can't be modeled and records
+    /// that it was skipped, like [`AnalyzerLike::unresolved_ty`] does for types
+    fn unresolved_expr(&mut self, loc: Loc, what: &str) -> NodeIdx {
+        self.add_unsupported(
+            loc,
+            format!("{what} outside of a function is not supported, it is left unresolved"),
+        );
+        self.add_node(Node::Unresolved(Identifier {
+            loc,
+            name: "expression".to_string(),
+        }))
+    }
+
+    /// Wraps a constant in a context variable so it can be used as an operand
+    fn top_level_cvar(&mut self, loc: Loc, idx: NodeIdx) -> Option<ContextVarNode> {
+        match self.node(idx) {
+            Node::ContextVar(..) => Some(idx.into()),
+            Node::Concrete(..) => {
+                let cvar = ContextVar::new_from_concrete(loc, idx.into(), self);
+                Some(self.add_node(Node::ContextVar(cvar)).into())
+            }
+            // another constant, i.e. `FEE` in `uint256 constant DOUBLE_FEE = FEE * 2`
+            Node::Var(var) => {
+                let init = var.initializer?;
+                self.top_level_cvar(loc, init)
+            }
+            _ => None,
+        }
+    }
+
+    fn array_builtin(var_type: VarType, len: Option<U256>) -> Builtin {
+        match len {
+            Some(len) => Builtin::SizedArray(len, var_type),
+            None => Builtin::Array(var_type),
+        }
+    }
+
+    /// Turns the placeholders of arrays whose element type was declared after them into array
+    /// types, now that every type of the file is declared. The placeholder becomes the array's
+    /// node, so whatever refers to it gets the array type
+    fn resolve_arrays(&mut self) {
+        loop {
+            let pending = std::mem::take(&mut self.unresolved_arrays);
+            let pending_count = pending.len();
+            pending
+                .into_iter()
+                .for_each(|(loc, node, inner_ty, len)| {
+                    match VarType::try_from_idx(self, inner_ty) {
+                        Some(var_type) => {
+                            let builtin = Self::array_builtin(var_type, len);
+                            *self.node_mut(node) = Node::Builtin(builtin.clone());
+                            self.builtins.insert(builtin, node);
+                        }
+                        None => self.unresolved_arrays.push((loc, node, inner_ty, len)),
+                    }
+                });
+            // arrays of arrays resolve from the inside out
+            if self.unresolved_arrays.len() == pending_count {
+                break;
+            }
+        }
+        std::mem::take(&mut self.unresolved_arrays)
+            .into_iter()
+            .for_each(|(loc, ..)| {
+                self.add_unsupported(
+                    loc,
+                    "Array of an undeclared type, it is left unresolved".to_string(),
+                )
+            });
+    }
+
+    /// The value of a compile time constant integer expression, such as a literal, a constant
+    /// variable or arithmetic on them
+    fn const_uint(&self, idx: NodeIdx) -> Option<U256> {
+        match self.node(idx) {
+            Node::Concrete(Concrete::Uint(_, val)) => Some(*val),
+            Node::Var(var) => self.const_uint(var.initializer?),
+            Node::ContextVar(_) => {
+                let cvar = ContextVarNode::from(idx);
+                let min = cvar.evaled_range_min(self)?.maybe_concrete()?.val;
+                let max = cvar.evaled_range_max(self)?.maybe_concrete()?.val;
+                match (min, max) {
+                    (Concrete::Uint(_, min), Concrete::Uint(_, max)) if min == max => Some(min),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn rational_literal(
+        &mut self,
+        loc: Loc,
+        integer: &str,
+        fraction: &str,
+        exponent: &str,
+        multiplier: U256,
+    ) -> NodeIdx {
+        let (val, whole) =
+            context::exprs::rational_literal_val(integer, fraction, exponent, multiplier)
+                .unwrap_or_else(|| (self.literal_overflow(loc), true));
+        if !whole {
+            self.add_warning(
+                loc,
+                "Literal is not a whole number, it was truncated to an integer".to_string(),
+            );
+        }
+        self.concrete_or_add(Concrete::Uint(256, val))
+    }
+
+    pub fn parse_source_unit(
+        &mut self,
+        source_unit: SourceUnit,
+        file_no: usize,
+        parent: NodeIdx,
+        imported: &mut Vec<(Option<NodeIdx>, String, String, usize)>,
+    ) -> Vec<FunctionNode> {
+        let mut all_funcs = vec![];
+        source_unit
+            .0
+            .iter()
+            .enumerate()
+            .for_each(|(unit_part, source_unit_part)| {
+                let (_sup, funcs) = self.parse_source_unit_part(
+                    source_unit_part,
+                    file_no,
+                    unit_part,
+                    parent,
+                    imported,
+                );
+                all_funcs.extend(funcs);
+            });
+        all_funcs
+    }
+
+    pub fn parse_source_unit_part(
+        &mut self,
+        sup: &SourceUnitPart,
+        file_no: usize,
+        unit_part: usize,
+        parent: NodeIdx,
+        imported: &mut Vec<(Option<NodeIdx>, String, String, usize)>,
+    ) -> (NodeIdx, Vec<FunctionNode>) {
+        use SourceUnitPart::*;
+
+        let sup_node = self.add_node(Node::SourceUnitPart(file_no, unit_part));
+        self.add_edge(sup_node, parent, Edge::Part);
+
+        let mut func_nodes = vec![];
+
+        match sup {
+            ContractDefinition(def) => {
+                let (node, funcs) = self.parse_contract_def(def, imported);
+                self.add_edge(node, sup_node, Edge::Contract);
+                func_nodes.extend(funcs);
+            }
+            StructDefinition(def) => {
+                let node = self.parse_struct_def(def);
+                self.add_edge(node, sup_node, Edge::Struct);
+            }
+            EnumDefinition(def) => {
+                let node = self.parse_enum_def(def);
+                self.add_edge(node, sup_node, Edge::Enum);
+            }
+            ErrorDefinition(def) => {
+                let node = self.parse_err_def(def);
+                self.add_edge(node, sup_node, Edge::Error);
+            }
+            VariableDefinition(def) => {
+                let (node, maybe_func) = self.parse_var_def(def, false);
+                if let Some(func) = maybe_func {
+                    func_nodes.push(self.handle_func(func, None));
+                }
+                self.add_edge(node, sup_node, Edge::Var);
+            }
+            FunctionDefinition(def) => {
+                let node = self.parse_func_def(def, None);
+                func_nodes.push(node);
+                self.add_edge(node, sup_node, Edge::Func);
+            }
+            TypeDefinition(def) => {
+                let node = self.parse_ty_def(def);
+                self.add_edge(node, sup_node, Edge::Ty);
+            }
+            EventDefinition(def) => {
+                let node = self.parse_event_def(def);
+                self.add_edge(node, sup_node, Edge::Event);
+            }
+            Annotation(anno) => {
+                self.annotations
+                    .entry(parent)
+                    .or_default()
+                    .push((**anno).clone());
+            }
+            Using(_using) => {}
+            // an empty declaration, i.e. `};` or `;;`
+            StraySemicolon(_loc) => {}
+            PragmaDirective(_loc, Some(ident), Some(version)) if ident.name == "solidity" => {
+                // multiple solidity pragmas in one file must all hold, so keep all of them
+                self.pragmas
+                    .entry(parent)
+                    .and_modify(|existing| {
+                        existing.push(' ');
+                        existing.push_str(&version.string);
+                    })
+                    .or_insert_with(|| version.string.clone());
+            }
+            PragmaDirective(_, _, _) => {}
+            ImportDirective(import) => imported.extend(self.parse_import(import, parent)),
+        }
+        (sup_node, func_nodes)
+    }
+
+    /// Resolves an import path relative to the file currently being parsed, applying any remappings
+    pub fn resolve_import_path(&self, import_path: &str) -> PathBuf {
+        if let Some((from, to)) = self
+            .remappings
+            .iter()
+            .find(|(from, _to)| import_path.starts_with(from.as_str()))
+        {
+            PathBuf::from(import_path.replacen(from.as_str(), to, 1))
+        } else {
+            self.root.join(import_path)
+        }
+    }
+
+    pub fn parse_import(
+        &mut self,
+        import: &Import,
+        parent: NodeIdx,
+    ) -> Vec<(Option<NodeIdx>, String, String, usize)> {
+        let (Import::Plain(path, loc)
+        | Import::GlobalSymbol(path, _, loc)
+        | Import::Rename(path, _, loc)) = import;
+        let resolved = self.resolve_import_path(&path.string);
+        let Ok(canonical) = resolved.canonicalize() else {
+            self.add_warning(
+                *loc,
+                format!("Could not find file for dependency: {:?}", resolved),
+            );
+            return vec![];
+        };
+
+        if let Some(entry) = self.imported_files.get(&canonical).copied() {
+            self.add_edge(entry, parent, Edge::Import);
+            self.register_import_aliases(import);
+            return vec![];
+        }
+
+        let Ok(sol) = fs::read_to_string(&canonical) else {
+            self.add_warning(
+                *loc,
+                format!("Could not read file for dependency: {:?}", canonical),
+            );
+            return vec![];
+        };
+
+        // imports inside of the imported file are relative to that file
+        let prev_root = std::mem::replace(
+            &mut self.root,
+            resolved
+                .parent()
+                .map(|p| p.to_path_buf())
+                .unwrap_or_default(),
+        );
+        self.file_no += 1;
+        let file_no = self.file_no;
+        self.file_sources
+            .entry(file_no)
+            .or_default()
+            .0
+            .clone_from(&resolved);
+        let parsed = self.parse_file(&sol, file_no);
+        self.root = prev_root;
+
+        let (entry, mut inner_sources) = match parsed {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                self.add_warning(*loc, format!("Could not parse dependency: {e}"));
+                return vec![];
+            }
+        };
+        self.imported_files.insert(canonical, entry);
+        self.add_edge(entry, parent, Edge::Import);
+        self.register_import_aliases(import);
+
+        inner_sources.push((
+            Some(entry),
+            resolved.to_string_lossy().to_string(),
+            sol,
+            file_no,
+        ));
+        inner_sources
+    }
+
+    /// Makes `import {A as B} from "..."` style aliases resolvable as user types
+    fn register_import_aliases(&mut self, import: &Import) {
+        if let Import::Rename(_, symbols, _) = import {
+            symbols.iter().for_each(|(symbol, maybe_alias)| {
+                if let Some(alias) = maybe_alias {
+                    if let Some(idx) = self.user_types.get(&symbol.name).cloned() {
+                        self.user_types.insert(alias.name.clone(), idx);
+                    }
+                }
+            });
+        }
+    }
+
+    pub fn parse_contract_def(
+        &mut self,
+        contract_def: &ContractDefinition,
+        imports: &[(Option<NodeIdx>, String, String, usize)],
+    ) -> (ContractNode, Vec<FunctionNode>) {
+        use ContractPart::*;
+
+        let mut contract = Contract::from_w_imports(contract_def.clone(), imports, self);
+        // bases have to be defined before the contracts inheriting them, so ones from this file
+        // have already been parsed
+        contract_def.base.iter().for_each(|base| {
+            if let Some(idx) = self.user_types.get(&base.name.identifiers[0].name) {
+                let base_node = ContractNode::from(*idx);
+                if matches!(self.node(*idx), Node::Contract(_))
+                    && !contract.inherits.contains(&base_node)
+                {
+                    contract.inherits.push(base_node);
+                }
+            }
+        });
+        let inherits = contract.inherits.clone();
+        // uses of the contract from before its definition are resolved to it
+        let con_node = match contract.name.clone() {
+            Some(name) => ContractNode::from(self.add_user_ty(name.name, Node::Contract(contract))),
+            None => ContractNode::from(self.add_node(contract)),
+        };
+        inherits.iter().for_each(|base| {
+            self.add_edge(con_node, *base, Edge::Inherits);
+        });
+
+        // types defined further down in the contract can be used before their definition
+        contract_def.parts.iter().for_each(|cpart| match cpart {
+            StructDefinition(def) => self.add_user_ty_placeholder(con_node, def.name.clone()),
+            EnumDefinition(def) => self.add_user_ty_placeholder(con_node, def.name.clone()),
+            TypeDefinition(def) => self.add_user_ty_placeholder(con_node, Some(def.name.clone())),
+            _ => {}
+        });
+
+        self.contract_scope = Some(con_node);
+        let mut func_nodes = vec![];
+        contract_def.parts.iter().for_each(|cpart| match cpart {
+            StructDefinition(def) => {
+                let node = self.parse_struct_def(def);
+                self.add_edge(node, con_node, Edge::Struct);
+            }
+            EnumDefinition(def) => {
+                let node = self.parse_enum_def(def);
+                self.add_edge(node, con_node, Edge::Enum);
+            }
+            ErrorDefinition(def) => {
+                let node = self.parse_err_def(def);
                 self.add_edge(node, con_node, Edge::Error);
             }
             VariableDefinition(def) => {
@@ -377,49 +1888,96 @@ impl Analyzer {
                 let node = self.parse_ty_def(def);
                 self.add_edge(node, con_node, Edge::Ty);
             }
-            EventDefinition(_def) => {}
-            Annotation(_anno) => todo!(),
-            Using(_using) => todo!(),
-            StraySemicolon(_loc) => todo!(),
+            EventDefinition(def) => {
+                let node = self.parse_event_def(def);
+                self.add_edge(node, con_node, Edge::Event);
+            }
+            Annotation(anno) => {
+                self.annotations
+                    .entry(con_node.into())
+                    .or_default()
+                    .push((**anno).clone());
+            }
+            Using(_using) => {}
+            StraySemicolon(_loc) => {}
         });
+        self.contract_scope = None;
         self.user_types
             .insert(con_node.name(self), con_node.0.into());
         (con_node, func_nodes)
     }
 
-    pub fn parse_enum_def(&mut self, enum_def: &EnumDefinition) -> EnumNode {
-        let enu = Enum::from(enum_def.clone());
-        let name = enu.name.clone().expect("Enum was not named").name;
-
-        // check if we have an unresolved type by the same name
-        let enu_node: EnumNode = if let Some(user_ty_node) = self.user_types.get(&name).cloned() {
-            let unresolved = self.node_mut(user_ty_node);
-            *unresolved = Node::Enum(enu);
-            user_ty_node.into()
-        } else {
-            let node = self.add_node(enu);
-            self.user_types.insert(name, node);
-            node.into()
+    /// Reserves the `Contract.Name` key of a type defined in `contract`. A use of the bare name
+    /// from before the contract was parsed gets resolved by the definition as well.
+    fn add_user_ty_placeholder(&mut self, contract: ContractNode, ident: Option<Identifier>) {
+        let Some(ident) = ident else {
+            return;
+        };
+        let qualified = format!("{}.{}", contract.name(self), ident.name);
+        let node = match self.user_types.get(&ident.name) {
+            Some(idx) if matches!(self.node(*idx), Node::Unresolved(_)) => *idx,
+            _ => self.add_node(Node::Unresolved(ident)),
+        };
+        self.user_types.insert(qualified, node);
+    }
+
+    /// Adds a named type definition, filling in the unresolved node of any use that came before
+    /// it. Definitions in a contract are keyed by `Contract.Name` and only take the bare name if
+    /// nothing else has, while file level definitions always do.
+    fn add_user_ty(&mut self, name: String, def: Node) -> NodeIdx {
+        let qualified = self
+            .contract_scope
+            .map(|contract| format!("{}.{}", contract.name(self), name));
+        let key = qualified.as_ref().unwrap_or(&name);
+        let node = match self.user_types.get(key).copied() {
+            Some(idx) if matches!(self.node(idx), Node::Unresolved(_)) => {
+                *self.node_mut(idx) = def;
+                idx
+            }
+            _ => self.add_node(def),
         };
 
-        enu_node
+        let bare_taken = self
+            .user_types
+            .get(&name)
+            .is_some_and(|idx| !matches!(self.node(*idx), Node::Unresolved(_)));
+        if qualified.is_none() || !bare_taken {
+            self.user_types.insert(name, node);
+        }
+        if let Some(qualified) = qualified {
+            self.user_types.insert(qualified, node);
+        }
+        node
+    }
+
+    /// Returns the name of a definition, naming it after its location if the parser left it
+    /// unnamed (i.e. an old style `function() {}`) so the rest of the file can still be analyzed
+    fn name_or_synthetic(&mut self, name: &mut Option<Identifier>, kind: &str, loc: Loc) -> String {
+        if let Some(ident) = name {
+            return ident.name.clone();
+        }
+        let synthetic = format!("unnamed_{kind}_{}", loc.start());
+        self.add_warning(
+            loc,
+            format!("Unnamed {kind} definition, analyzing it as `{synthetic}`"),
+        );
+        *name = Some(Identifier {
+            loc,
+            name: synthetic.clone(),
+        });
+        synthetic
+    }
+
+    pub fn parse_enum_def(&mut self, enum_def: &EnumDefinition) -> EnumNode {
+        let mut enu = Enum::from(enum_def.clone());
+        let name = self.name_or_synthetic(&mut enu.name, "enum", enu.loc);
+        self.add_user_ty(name, Node::Enum(enu)).into()
     }
 
     pub fn parse_struct_def(&mut self, struct_def: &StructDefinition) -> StructNode {
-        let strukt = Struct::from(struct_def.clone());
-        let name = strukt.name.clone().expect("Struct was not named").name;
-
-        // check if we have an unresolved type by the same name
-        let strukt_node: StructNode =
-            if let Some(user_ty_node) = self.user_types.get(&name).cloned() {
-                let unresolved = self.node_mut(user_ty_node);
-                *unresolved = Node::Struct(strukt);
-                user_ty_node.into()
-            } else {
-                let node = self.add_node(strukt);
-                self.user_types.insert(name, node);
-                node.into()
-            };
+        let mut strukt = Struct::from(struct_def.clone());
+        let name = self.name_or_synthetic(&mut strukt.name, "struct", strukt.loc);
+        let strukt_node: StructNode = self.add_user_ty(name, Node::Struct(strukt)).into();
 
         struct_def.fields.iter().for_each(|field| {
             let f = Field::new(self, field.clone());
@@ -430,7 +1988,7 @@ impl Analyzer {
     }
 
     pub fn parse_err_def(&mut self, err_def: &ErrorDefinition) -> ErrorNode {
-        let err_node = ErrorNode(self.add_node(Error::from(err_def.clone())).index());
+        let err_node = ErrorNode::from(self.add_node(Error::from(err_def.clone())));
         err_def.fields.iter().for_each(|field| {
             let param = ErrorParam::new(self, field.clone());
             let field_node = self.add_node(param);
@@ -439,12 +1997,97 @@ impl Analyzer {
         err_node
     }
 
-    pub fn parse_func_def(
-        &mut self,
-        func_def: &FunctionDefinition,
-        con_node: Option<ContractNode>,
-    ) -> FunctionNode {
-        let func = Function::from(func_def.clone());
+    /// Attaches the functions of a `using ... for` directive to the type they are used for,
+    /// within `scope`: the contract or source unit the directive is in
+    pub fn parse_using(&mut self, using: &Using, scope: NodeIdx) {
+        let funcs = match &using.list {
+            UsingList::Library(path) => self.using_path_fns(path, None),
+            UsingList::Functions(paths) => paths
+                .iter()
+                .flat_map(|path| {
+                    let (func, lib) = path.identifiers.split_last().expect("Empty using path");
+                    let lib = IdentifierPath {
+                        loc: path.loc,
+                        identifiers: lib.to_vec(),
+                    };
+                    self.using_path_fns(&lib, Some(&func.name))
+                })
+                .collect(),
+            UsingList::Error() => vec![],
+        };
+
+        match &using.ty {
+            Some(ty) => {
+                let ty = self.parse_expr(ty);
+                let scope = if using.global.is_some() { ty } else { scope };
+                self.using.entry((scope, ty)).or_default().extend(funcs);
+            }
+            None => self.using_any.entry(scope).or_default().extend(funcs),
+        }
+    }
+
+    /// Functions named `func_name` (or all of them if `None`) in the library at `path`. An empty
+    /// path refers to free functions.
+    fn using_path_fns(
+        &mut self,
+        path: &IdentifierPath,
+        func_name: Option<&str>,
+    ) -> Vec<FunctionNode> {
+        let funcs = if let Some(lib) = path.identifiers.last() {
+            match self.user_types.get(&lib.name) {
+                Some(idx) if matches!(self.node(*idx), Node::Contract(_)) => {
+                    ContractNode::from(*idx).funcs(self)
+                }
+                _ => {
+                    self.add_warning(lib.loc, format!("Could not find library: {}", lib.name));
+                    return vec![];
+                }
+            }
+        } else {
+            self.user_types
+                .values()
+                .filter(|idx| matches!(self.node(**idx), Node::Function(_)))
+                .map(|idx| FunctionNode::from(*idx))
+                .collect()
+        };
+
+        funcs
+            .into_iter()
+            .filter(|func| match func_name {
+                Some(name) => func.name(self).starts_with(&format!("{name}(")),
+                None => true,
+            })
+            .collect()
+    }
+
+    pub fn parse_event_def(&mut self, event_def: &EventDefinition) -> EventNode {
+        let event_node = EventNode::from(self.add_node(Event::from(event_def.clone())));
+        event_def
+            .fields
+            .iter()
+            .enumerate()
+            .for_each(|(order, field)| {
+                let param = EventParam::new(self, field.clone(), order);
+                let field_node = self.add_node(param);
+                self.add_edge(field_node, event_node, Edge::EventParam);
+            });
+        event_node
+    }
+
+    pub fn parse_func_def(
+        &mut self,
+        func_def: &FunctionDefinition,
+        con_node: Option<ContractNode>,
+    ) -> FunctionNode {
+        let mut func = Function::from(func_def.clone());
+        if matches!(func.ty, FunctionTy::Function | FunctionTy::Modifier) {
+            let kind = if func.ty == FunctionTy::Modifier {
+                "modifier"
+            } else {
+                "function"
+            };
+            self.name_or_synthetic(&mut func.name, kind, func.loc);
+        }
         self.handle_func(func, con_node)
     }
 
@@ -547,16 +2190,27 @@ impl Analyzer {
 
     pub fn parse_ty_def(&mut self, ty_def: &TypeDefinition) -> TyNode {
         let ty = Ty::new(self, ty_def.clone());
-        TyNode(self.add_node(ty).index())
+        let name = ty.name.name.clone();
+        self.add_user_ty(name, Node::Ty(ty)).into()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::analyzers::ReportConfig;
-    use crate::context::analyzers::bounds::BoundAnalyzer;
-    use shared::context::{ContextEdge, ContextNode};
+    use crate::analyzers::{OutputFormat, ReportConfig, ReportDisplay, Severity};
+    use crate::context::analyzers::bounds::{BoundAnalyzer, FunctionVarsBoundAnalyzer};
+    use crate::context::analyzers::dead_code::DeadCodeAnalyzer;
+    use crate::context::analyzers::diagnostics::DiagnosticsAnalyzer;
+    use crate::context::analyzers::invariant::InvariantAnalyzer;
+    use crate::context::analyzers::overflow::OverflowAnalyzer;
+    use crate::context::analyzers::revert::RevertAnalyzer;
+    use crate::context::analyzers::shadowing::ShadowingAnalyzer;
+    use crate::context::analyzers::value_flow::ValueFlowAnalyzer;
+    use ethers_core::types::{I256, U256};
+    use shared::context::{ContextEdge, ContextNode, ContextVarNode, KilledKind, Termination};
+    use shared::range::elem::RangeOp;
+    use shared::range::range_string::RangeStringConfig;
 
     #[test]
     fn it_works() {
@@ -575,12 +2229,13 @@ contract Storage {
 
     }
 }"###;
-        let mut analyzer = Analyzer::default();
-        let t0 = std::time::Instant::now();
-        let (maybe_entry, _sources) = analyzer.parse(sol);
-        let entry = maybe_entry.unwrap();
+        let mut analyzer = Analyzer {
+            metrics: Some(Metrics::default()),
+            ..Default::default()
+        };
+        let entry = analyzer.parse_or_panic(sol, 0);
         let file_mapping = vec![(0usize, "test.sol".to_string())].into_iter().collect();
-        println!("parse time: {:?}", t0.elapsed().as_nanos());
+        println!("{:#?}", analyzer.metrics());
         println!("{}", analyzer.dot_str_no_tmps_for_ctx("b5".to_string()));
         let contexts = analyzer.search_children(entry, &crate::Edge::Context(ContextEdge::Context));
         for context in contexts.into_iter() {
@@ -592,6 +2247,14 @@ contract Storage {
                 show_subctxs: true,
                 show_initial_bounds: true,
                 show_all_lines: true,
+                output: OutputFormat::Text,
+                interprocedural: false,
+                only_changed: false,
+                merge_subctxs: false,
+                overflow: false,
+                dead_code: false,
+                shadowing: false,
+                value_flow: false,
             };
             let ctx = ContextNode::from(context);
 
@@ -600,6 +2263,7108 @@ contract Storage {
             println!("{analysis:#?}");
             // analysis.print_reports(("test.sol".to_string(), &sol), &analyzer);
         }
-        println!("total analyze time: {:?}", t0.elapsed().as_nanos());
+    }
+
+    fn fn_ctx(analyzer: &Analyzer, entry: NodeIdx, fn_name: &str) -> ContextNode {
+        analyzer
+            .search_children(entry, &crate::Edge::Context(ContextEdge::Context))
+            .into_iter()
+            .map(ContextNode::from)
+            .find(|ctx| ctx.associated_fn_name(analyzer) == fn_name)
+            .unwrap_or_else(|| panic!("No context for function: {fn_name}"))
+    }
+
+    #[test]
+    fn msg_data_and_sig() {
+        let sol = r###"
+contract Proxy {
+    function f() public {
+        bytes4 s = msg.sig;
+        uint256 l = msg.data.length;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f()");
+
+        let sig = ctx.var_by_name_or_recurse(&analyzer, "msg.sig").unwrap();
+        assert!(matches!(
+            sig.ty(&analyzer),
+            VarType::BuiltIn(bn, _) if *bn.underlying(&analyzer) == Builtin::Bytes(4)
+        ));
+        assert!(sig.is_tainted(&analyzer));
+
+        let len = ctx
+            .var_by_name_or_recurse(&analyzer, "msg.data.length")
+            .unwrap();
+        assert!(matches!(
+            len.ty(&analyzer),
+            VarType::BuiltIn(bn, _) if *bn.underlying(&analyzer) == Builtin::Uint(256)
+        ));
+        assert!(len.is_tainted(&analyzer));
+    }
+
+    #[test]
+    fn env_builtins() {
+        let sol = r###"
+contract Payable {
+    function f() public payable {
+        require(msg.value > 0);
+        require(block.timestamp >= 100);
+        uint256 v = msg.value;
+        uint256 t = block.timestamp;
+        uint256 n = block.number;
+        address s = msg.sender;
+        address o = tx.origin;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f()");
+
+        let range = |name: &str| {
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            let bound = |elem: Option<Elem<Concrete>>| elem.unwrap().maybe_concrete().unwrap().val;
+            (
+                bound(var.evaled_range_min(&analyzer)).uint_val().unwrap(),
+                bound(var.evaled_range_max(&analyzer)).uint_val().unwrap(),
+            )
+        };
+        assert_eq!(range("v"), (1.into(), U256::MAX));
+        assert_eq!(range("t"), (100.into(), U256::MAX));
+        assert_eq!(range("n"), (0.into(), U256::MAX));
+
+        for (name, builtin) in [
+            ("s", Builtin::Address),
+            ("o", Builtin::Address),
+            ("msg.sender", Builtin::Address),
+            ("tx.origin", Builtin::Address),
+            ("block.timestamp", Builtin::Uint(256)),
+        ] {
+            let var = ctx.var_by_name_or_recurse(&analyzer, name).unwrap();
+            assert!(matches!(
+                var.ty(&analyzer),
+                VarType::BuiltIn(bn, _) if *bn.underlying(&analyzer) == builtin
+            ));
+        }
+    }
+
+    #[test]
+    fn hash_builtins() {
+        let sol = r###"
+contract Signed {
+    function f(uint256 x, uint8 v) public {
+        bytes32 k = keccak256(abi.encodePacked(x));
+        bytes32 s = sha256(abi.encode(x));
+        bytes20 r = ripemd160(abi.encodePacked(k));
+        address signer = ecrecover(k, v, s, k);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(uint256, uint8)");
+
+        for (name, builtin) in [
+            ("k", Builtin::Bytes(32)),
+            ("s", Builtin::Bytes(32)),
+            ("r", Builtin::Bytes(20)),
+            ("signer", Builtin::Address),
+        ] {
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            assert!(matches!(
+                var.ty(&analyzer),
+                VarType::BuiltIn(bn, _) if *bn.underlying(&analyzer) == builtin
+            ));
+        }
+    }
+
+    #[test]
+    fn security_mode_params() {
+        let sol = r###"
+contract Vault {
+    function f(uint256 x) public {}
+
+    function g() public {
+        f(5);
+    }
+}"###;
+        let callee_x = |security_mode: bool| {
+            let mut analyzer = Analyzer {
+                security_mode,
+                ..Default::default()
+            };
+            let entry = analyzer.parse_or_panic(sol, 0);
+            let ctx = fn_ctx(&analyzer, entry, "g()");
+            let call_ctx = ctx.subcontexts(&analyzer)[0];
+            let x = call_ctx.var_by_name(&analyzer, "x").unwrap();
+            let max = x
+                .evaled_range_max(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val
+                .into_u256()
+                .unwrap();
+            (x.is_tainted(&analyzer), max)
+        };
+
+        assert_eq!(callee_x(false), (false, U256::from(5)));
+        assert_eq!(callee_x(true), (true, U256::MAX));
+    }
+
+    #[test]
+    fn discarded_comparison_warns() {
+        let sol = r###"
+contract C {
+    function f(uint256 a, uint256 b) public {
+        a == b;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        analyzer.parse_or_panic(sol, 0);
+        assert_eq!(analyzer.diagnostics.len(), 1);
+        assert!(analyzer.diagnostics[0].message.contains("discarded"));
+    }
+
+    #[test]
+    fn identity_cast_is_noop() {
+        let node_count = |body: &str| {
+            let sol = format!(
+                "contract C {{ function f(uint256 x) public {{ require(x < 10); uint256 y = {body}; }} }}"
+            );
+            let mut analyzer = Analyzer::default();
+            let entry = analyzer.parse_or_panic(&sol, 0);
+            let ctx = fn_ctx(&analyzer, entry, "f(uint256)");
+            let x = ctx.latest_var_by_name(&analyzer, "x").unwrap();
+            let y = ctx.latest_var_by_name(&analyzer, "y").unwrap();
+            assert_eq!(x.evaled_range_max(&analyzer), y.evaled_range_max(&analyzer));
+            analyzer.graph.node_count()
+        };
+
+        assert_eq!(node_count("uint256(x)"), node_count("x"));
+    }
+
+    #[test]
+    fn function_callees() {
+        let sol = r###"
+contract C {
+    function a() internal {}
+    function b(uint256 x) internal {}
+    function f() public {
+        a();
+        b(1);
+        a();
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f()");
+        let callees = ctx
+            .associated_fn(&analyzer)
+            .callees(&analyzer)
+            .iter()
+            .map(|func| func.name(&analyzer))
+            .collect::<Vec<_>>();
+        assert_eq!(callees, vec!["a()", "b(uint256)"]);
+    }
+
+    #[test]
+    fn nested_unchecked_blocks() {
+        let sol = r###"
+pragma solidity ^0.8.0;
+contract C {
+    function f(uint256 x) public {
+        unchecked {
+            uint256 a = x + 1;
+            {
+                uint256 b = x + 2;
+            }
+        }
+        uint256 c = x + 3;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(uint256)");
+        let overflow_checks = ctx
+            .ctx_deps(&analyzer)
+            .values()
+            .map(|dep| dep.display_name(&analyzer))
+            .collect::<Vec<_>>();
+        assert_eq!(overflow_checks, vec!["(x <= 2**256 - 1 - 3)"]);
+    }
+
+    #[test]
+    fn unchecked_wrapping() {
+        let sol = r###"
+pragma solidity ^0.8.0;
+contract C {
+    function wraps() public {
+        uint256 x;
+        unchecked {
+            x = type(uint256).max + 1;
+        }
+    }
+
+    function wraps_partially(uint8 a) public {
+        unchecked {
+            uint8 b = a + 1;
+        }
+    }
+
+    function overflows() public {
+        uint256 y = type(uint256).max;
+        uint256 z = y + 1;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let bounds = |fn_name: &str, var: &str| {
+            let var = fn_ctx(&analyzer, entry, fn_name)
+                .latest_var_by_name(&analyzer, var)
+                .unwrap();
+            (
+                var.evaled_range_min(&analyzer)
+                    .unwrap()
+                    .maybe_concrete()
+                    .unwrap()
+                    .val,
+                var.evaled_range_max(&analyzer)
+                    .unwrap()
+                    .maybe_concrete()
+                    .unwrap()
+                    .val,
+            )
+        };
+
+        let zero = Concrete::Uint(256, U256::zero());
+        assert_eq!(bounds("wraps()", "x"), (zero.clone(), zero));
+        assert_eq!(
+            bounds("wraps_partially(uint8)", "b"),
+            (
+                Concrete::Uint(8, U256::zero()),
+                Concrete::Uint(8, U256::from(255))
+            )
+        );
+        assert!(fn_ctx(&analyzer, entry, "overflows()").is_killed(&analyzer));
+    }
+
+    #[test]
+    fn pre_08_arithmetic_wraps() {
+        let sol = r###"
+pragma solidity ^0.7.0;
+contract C {
+    function overflows() public {
+        uint256 y = type(uint256).max;
+        uint256 z = y + 1;
+    }
+
+    function countdown(uint256 n) internal returns (uint256) {
+        if (n == 0) {
+            return 0;
+        }
+        return countdown(n - 1);
+    }
+
+    function recurses() public {
+        countdown(3);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "overflows()");
+        assert!(!ctx.is_killed(&analyzer));
+        let z = ctx.latest_var_by_name(&analyzer, "z").unwrap();
+        let zero = Concrete::Uint(256, U256::zero());
+        assert_eq!(
+            z.evaled_range_min(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val,
+            zero
+        );
+        assert_eq!(
+            z.evaled_range_max(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val,
+            zero
+        );
+
+        // the recursive call's `n` is its own variable, not a later version of the caller's
+        assert!(!fn_ctx(&analyzer, entry, "recurses()").is_killed(&analyzer));
+
+        // without a pragma the overflow reverts, as it does from 0.8.0 on
+        let sol = sol.replace("pragma solidity ^0.7.0;\n", "");
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(&sol, 0);
+        assert!(fn_ctx(&analyzer, entry, "overflows()").is_killed(&analyzer));
+    }
+
+    #[test]
+    fn imports_resolve_relative_to_file() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_data/imports/main.sol");
+        let sol = fs::read_to_string(&path).unwrap();
+        let mut analyzer = Analyzer {
+            root: path.parent().unwrap().to_path_buf(),
+            ..Default::default()
+        };
+        let entry = analyzer.parse_or_panic(&sol, 0);
+        assert_eq!(analyzer.sources.len(), 2);
+        let contracts = analyzer
+            .search_children(entry, &crate::Edge::Contract)
+            .into_iter()
+            .map(|contract| ContractNode::from(contract).name(&analyzer))
+            .collect::<BTreeSet<_>>();
+        assert!(contracts.contains("Base"));
+        assert!(contracts.contains("Math"));
+        assert_eq!(
+            analyzer.user_types.get("M"),
+            analyzer.user_types.get("Math")
+        );
+        // errors declared at file level in an imported file are found
+        assert!(!analyzer
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.message.starts_with("Unknown error")));
+    }
+
+    #[test]
+    fn analyze_file_reports_bounds() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_data/imports/main.sol");
+        let mut analyzer = Analyzer::default();
+        let analyses = analyzer
+            .analyze_file(&path, ReportConfig::default())
+            .unwrap();
+        let funcs = analyses
+            .iter()
+            .map(|analysis| analysis.ctx.associated_fn_name(&analyzer))
+            .collect::<BTreeSet<_>>();
+        assert!(funcs.contains("f(uint256)"));
+        // the file itself is numbered first and imports follow it
+        assert_eq!(analyzer.file_mapping()[&0], path.to_string_lossy());
+        assert_eq!(analyzer.file_mapping().len(), 3);
+
+        let missing = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_data/missing.sol");
+        assert!(matches!(
+            analyzer.analyze_file(&missing, ReportConfig::default()),
+            Err(AnalyzeError::Io(..))
+        ));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_bounds_match_sequential() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_data/math.sol");
+        let sol = fs::read_to_string(&path).unwrap();
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(&sol, 0);
+        let file_mapping = analyzer.file_mapping();
+        let ctxs = analyzer
+            .search_children(entry, &crate::Edge::Context(ContextEdge::Context))
+            .into_iter()
+            .map(ContextNode::from)
+            .collect::<Vec<_>>();
+        assert!(ctxs.len() > 1);
+        let sequential = ctxs
+            .iter()
+            .map(|ctx| analyzer.bounds_for_all(&file_mapping, *ctx, ReportConfig::default()))
+            .collect::<Vec<_>>();
+        let parallel =
+            analyzer.bounds_for_all_parallel(&file_mapping, &ctxs, ReportConfig::default());
+        assert_eq!(format!("{sequential:?}"), format!("{parallel:?}"));
+    }
+
+    #[test]
+    fn function_values() {
+        let sol = r###"
+contract C {
+    function double(uint256 x) internal returns (uint256) {
+        return x * 2;
+    }
+
+    function f() public {
+        function (uint256) internal returns (uint256) fp = double;
+        fp(3);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f()");
+        let fp = ctx.latest_var_by_name(&analyzer, "fp").unwrap();
+        let double = fp.ty(&analyzer).func_node(&analyzer).unwrap();
+        assert_eq!(double.name(&analyzer), "double(uint256)");
+        assert_eq!(
+            ctx.associated_fn(&analyzer).callees(&analyzer),
+            vec![double]
+        );
+    }
+
+    #[test]
+    fn encode_packed_adjacent_dyn() {
+        let sol = r###"
+contract C {
+    function collides(string memory a, string memory b) public returns (bytes memory) {
+        return abi.encodePacked(a, b);
+    }
+
+    function fine(string memory a, uint256 b) public returns (bytes memory) {
+        return abi.encodePacked(a, b);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        analyzer.parse_or_panic(sol, 0);
+        let flagged: Vec<_> = analyzer
+            .diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.message.contains("abi.encodePacked"))
+            .collect();
+        assert_eq!(flagged.len(), 1);
+        assert!(flagged[0].loc.start() < sol.find("function fine").unwrap());
+    }
+
+    #[test]
+    fn pragma_versions() {
+        let mut analyzer = Analyzer::default();
+        let a = analyzer.parse_or_panic(
+            "pragma solidity ^0.8.0;\npragma solidity <0.9.0;\npragma abicoder v2;\ncontract A {}",
+            0,
+        );
+        let b = analyzer.parse_or_panic("pragma solidity 0.7.6;\ncontract B {}", 1);
+        let c = analyzer.parse_or_panic("contract C {}", 2);
+        assert_eq!(analyzer.solidity_version(a), Some("^0.8.0 <0.9.0"));
+        assert_eq!(analyzer.solidity_version(b), Some("0.7.6"));
+        assert_eq!(analyzer.solidity_version(c), None);
+        assert!(analyzer.checked_arithmetic(a));
+        assert!(!analyzer.checked_arithmetic(b));
+        // without a pragma the compiler is unknown, so the checked default applies
+        assert!(analyzer.checked_arithmetic(c));
+
+        assert!(requires_checked_arithmetic(">= 0.8.0"));
+        assert!(requires_checked_arithmetic("0.8.19"));
+        assert!(requires_checked_arithmetic("^0.8.0 || >=1.0"));
+        assert!(!requires_checked_arithmetic(">=0.6.0 <0.9.0"));
+        assert!(!requires_checked_arithmetic("^0.8.0 || ^0.7.0"));
+        assert!(!requires_checked_arithmetic("0.7.0 - 0.8.0"));
+        assert!(!requires_checked_arithmetic("<0.8.0"));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let mut analyzer = Analyzer::default();
+        let err = analyzer.parse("contract C { function f( }", 3).unwrap_err();
+        assert_eq!(err.file_no, 3);
+        assert!(!err.diagnostics.is_empty());
+        let (start, end) = err.spans()[0];
+        assert!(start <= end && end <= "contract C { function f( }".len());
+
+        // the analyzer is still usable afterwards
+        analyzer.parse_or_panic("contract D {}", 4);
+    }
+
+    #[test]
+    fn reports_use_source_of_their_file() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_data/reports/main.sol");
+        let sol = fs::read_to_string(&path).unwrap();
+        let mut analyzer = Analyzer {
+            root: path.parent().unwrap().to_path_buf(),
+            ..Default::default()
+        };
+        analyzer.file_sources.entry(0).or_default().0 = path;
+        analyzer.parse_or_panic(&sol, 0);
+        assert_eq!(analyzer.file_sources.len(), 2);
+        assert!(analyzer.file_sources[&1].0.ends_with("dep.sol"));
+
+        let dep = analyzer.user_types["Dep"];
+        let func = ContractNode::from(dep)
+            .funcs(&analyzer)
+            .into_iter()
+            .find(|func| func.name(&analyzer) == "scaled(uint256)")
+            .unwrap();
+        let ctx = func.maybe_body_ctx(&analyzer).unwrap();
+        assert!(matches!(
+            ctx.underlying(&analyzer).loc,
+            solang_parser::pt::Loc::File(1, ..)
+        ));
+        let file_mapping = analyzer.file_mapping();
+        let analysis = analyzer.bounds_for_all(&file_mapping, ctx, ReportConfig::default());
+        let mut cache = analyzer.source_cache();
+        let mut out = vec![];
+        analysis
+            .reports(&analyzer)
+            .into_iter()
+            .for_each(|report| report.write(&mut cache, &mut out).unwrap());
+        // drop the color codes so the snippet can be matched
+        let out = String::from_utf8(out).unwrap();
+        let mut plain = String::new();
+        let mut in_escape = false;
+        out.chars().for_each(|c| match c {
+            '\x1b' => in_escape = true,
+            'm' if in_escape => in_escape = false,
+            _ if !in_escape => plain.push(c),
+            _ => {}
+        });
+        assert!(plain.contains("dep.sol:2:"));
+        assert!(plain.contains("return z;"));
+    }
+
+    #[test]
+    fn context_var_locs() {
+        let sol = r###"
+contract C {
+    function f(uint256 x, uint256 y, bool flag) public returns (uint256) {
+        require(flag);
+        require(!flag || x > 3);
+        uint256 a = x /
+            y;
+        uint256 b = a -
+            7;
+        b++;
+        return b + 1234;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        fn_ctx(&analyzer, entry, "f(uint256, uint256, bool)");
+        let span = |loc: Loc| &sol[loc.start()..loc.end()];
+        let mut literals = 0;
+        analyzer
+            .graph
+            .node_indices()
+            .filter_map(|idx| match analyzer.node(idx) {
+                Node::ContextVar(var) => Some(var),
+                _ => None,
+            })
+            .for_each(|var| {
+                let loc = var.loc.unwrap();
+                assert!(
+                    !matches!(loc, Loc::Implicit),
+                    "{} has no location",
+                    var.display_name
+                );
+                // literals point at themselves
+                if ["7", "1234"].contains(&var.display_name.as_str()) {
+                    assert_eq!(span(loc), var.display_name);
+                    literals += 1;
+                }
+            });
+        assert_eq!(literals, 2);
+    }
+
+    #[test]
+    fn merge_analyzers() {
+        let a_sol = r###"
+contract A {
+    struct S {
+        uint256 a;
+    }
+
+    function f(uint256 x) public returns (uint256) {
+        require(x < 10);
+        return x + 1;
+    }
+}"###;
+        let b_sol = r###"
+struct S {
+    uint8 b;
+}
+
+contract B {
+    function g(uint8 y) public returns (uint8) {
+        require(y > 200);
+        return y;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let a_entry = analyzer.parse_or_panic(a_sol, 0);
+        let mut other = Analyzer::default();
+        let b_entry = other.parse_or_panic(b_sol, 1);
+        let other_b = other.user_types["B"];
+        let other_uint8 = other.builtins[&Builtin::Uint(8)];
+        let nodes = analyzer.graph.node_count();
+        let other_nodes = other.graph.node_count();
+
+        let mapping = analyzer.merge(other);
+        assert_eq!(mapping.len(), other_nodes);
+        // the builtins each analyzer starts out with are not duplicated
+        assert!(analyzer.graph.node_count() < nodes + other_nodes);
+        assert_eq!(mapping[&other_uint8], analyzer.builtins[&Builtin::Uint(8)]);
+        let builtin_nodes = analyzer
+            .graph
+            .node_indices()
+            .filter(|idx| matches!(analyzer.node(*idx), Node::Builtin(_)))
+            .count();
+        assert_eq!(builtin_nodes, analyzer.builtins.len());
+
+        assert_eq!(analyzer.user_types["B"], mapping[&other_b]);
+        assert_eq!(
+            ContractNode::from(analyzer.user_types["B"]).name(&analyzer),
+            "B"
+        );
+        // the file level struct takes the bare name, the one in `A` is still reachable
+        assert_eq!(
+            StructNode::from(analyzer.user_types["S"]).fields(&analyzer)[0].name(&analyzer),
+            "b"
+        );
+        assert_eq!(
+            StructNode::from(analyzer.user_types["A.S"]).fields(&analyzer)[0].name(&analyzer),
+            "a"
+        );
+
+        // the contexts of both files come along with their bounds
+        let ctx = fn_ctx(&analyzer, a_entry, "f(uint256)");
+        let x = ctx.latest_var_by_name(&analyzer, "x").unwrap();
+        assert_eq!(
+            x.evaled_range_max(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val,
+            Concrete::Uint(256, 9.into())
+        );
+        let ctx = fn_ctx(&analyzer, mapping[&b_entry], "g(uint8)");
+        let y = ctx.latest_var_by_name(&analyzer, "y").unwrap();
+        assert_eq!(
+            y.evaled_range_min(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val,
+            Concrete::Uint(8, 201.into())
+        );
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let sol = r###"
+pragma solidity ^0.8.0;
+contract A {
+    using L for uint256;
+    struct S {
+        uint256 a;
+    }
+    bytes32 constant salt = bytes32(uint256(7));
+    address owner;
+
+    function f(uint256 x) public returns (uint256) {
+        require(x < 10);
+        return x + type(uint128).max;
+    }
+}
+
+library L {
+    function twice(uint256 a) internal pure returns (uint256) {
+        return a * 2;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let json = analyzer.to_json().unwrap();
+        let loaded = Analyzer::from_json(&json).unwrap();
+
+        assert_eq!(loaded.graph.node_count(), analyzer.graph.node_count());
+        assert_eq!(loaded.graph.edge_count(), analyzer.graph.edge_count());
+        analyzer.graph.node_indices().for_each(|idx| {
+            assert_eq!(loaded.node(idx), analyzer.node(idx));
+        });
+        analyzer.graph.edge_indices().for_each(|idx| {
+            assert_eq!(
+                loaded.graph.edge_endpoints(idx),
+                analyzer.graph.edge_endpoints(idx)
+            );
+            assert_eq!(loaded.graph[idx], analyzer.graph[idx]);
+        });
+        assert_eq!(loaded.msg, analyzer.msg);
+        assert_eq!(loaded.block, analyzer.block);
+        assert_eq!(loaded.builtins, analyzer.builtins);
+        assert_eq!(loaded.user_types["A"], analyzer.user_types["A"]);
+        assert_eq!(loaded.user_types["S"], analyzer.user_types["S"]);
+
+        let ctx = fn_ctx(&loaded, entry, "f(uint256)");
+        assert_eq!(ctx, fn_ctx(&analyzer, entry, "f(uint256)"));
+
+        // what the analyzer keeps outside of the graph comes along
+        assert_eq!(loaded.solidity_version(entry), Some("^0.8.0"));
+        assert!(!analyzer.using.is_empty());
+        assert_eq!(loaded.using, analyzer.using);
+        assert_eq!(loaded.using_any, analyzer.using_any);
+        assert_eq!(loaded.file_sources, analyzer.file_sources);
+        assert_eq!(loaded.file_mapping(), analyzer.file_mapping());
+    }
+
+    #[test]
+    fn dot_str_styled() {
+        let sol = r###"
+contract A {
+    function f(uint256 x) public returns (uint256) {
+        require(x < 10);
+        return x + 5;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        analyzer.parse_or_panic(sol, 0);
+        let dot = analyzer.dot_str_styled();
+        let color = |name| shared::TOKYO_NIGHT_COLORS[name];
+        assert!(dot.contains(&format!(
+            "[ label = \"f(uint256)\", color = \"{}\"]",
+            color("purple")
+        )));
+        assert!(dot.contains(&format!(
+            "[ label = \"x: [0, 9] excluding: []\", color = \"{}\"]",
+            color("orange")
+        )));
+        assert!(dot.contains(&format!("[ label = \"10\", color = \"{}\"]", color("red"))));
+        assert!(dot.contains("[ label = \"Context(Variable)\"]"));
+    }
+
+    #[test]
+    fn mapping_types() {
+        let sol = r###"
+contract A {
+    mapping(address => uint8) balances;
+    mapping(address => mapping(uint256 => uint16)) allowances;
+
+    function f(address a, uint256 b) public {
+        uint8 x = balances[a];
+        uint16 y = allowances[a][b];
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let builtin = |name: &str| match analyzer.node(analyzer.user_types[name]) {
+            Node::Var(var) => analyzer.node(var.ty).clone(),
+            e => panic!("Not a variable: {e:?}"),
+        };
+        let ty = |builtin: Builtin| {
+            VarType::try_from_idx(&analyzer, analyzer.builtins[&builtin]).unwrap()
+        };
+        assert_eq!(
+            builtin("balances"),
+            Node::Builtin(Builtin::Mapping(ty(Builtin::Address), ty(Builtin::Uint(8))))
+        );
+        let inner = Builtin::Mapping(ty(Builtin::Uint(256)), ty(Builtin::Uint(16)));
+        assert_eq!(
+            builtin("allowances"),
+            Node::Builtin(Builtin::Mapping(ty(Builtin::Address), ty(inner)))
+        );
+
+        let ctx = fn_ctx(&analyzer, entry, "f(address, uint256)");
+        assert!(!ctx.is_killed(&analyzer));
+        let max = |var: &str| {
+            ctx.latest_var_by_name(&analyzer, var)
+                .unwrap()
+                .evaled_range_max(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val
+        };
+        assert_eq!(max("x"), Concrete::Uint(8, U256::from(255)));
+        assert_eq!(max("y"), Concrete::Uint(16, U256::from(65535)));
+    }
+
+    #[test]
+    fn sized_arrays() {
+        let sol = r###"
+contract A {
+    uint256 constant N = 4;
+    uint256 notConst;
+    uint256[3] fixed;
+    uint8[N] byConst;
+    uint256[notConst] notFixed;
+
+    function f(uint256[3] memory arr, uint256 i) public returns (uint256) {
+        uint256 len = arr.length;
+        return arr[i];
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let builtin = |name: &str| match analyzer.node(analyzer.user_types[name]) {
+            Node::Var(var) => analyzer.node(var.ty).clone(),
+            e => panic!("Not a variable: {e:?}"),
+        };
+        let ty = |builtin: Builtin| {
+            VarType::try_from_idx(&analyzer, analyzer.builtins[&builtin]).unwrap()
+        };
+        assert_eq!(
+            builtin("fixed"),
+            Node::Builtin(Builtin::SizedArray(U256::from(3), ty(Builtin::Uint(256))))
+        );
+        assert_eq!(
+            builtin("byConst"),
+            Node::Builtin(Builtin::SizedArray(U256::from(4), ty(Builtin::Uint(8))))
+        );
+        assert_eq!(
+            builtin("notFixed"),
+            Node::Builtin(Builtin::Array(ty(Builtin::Uint(256))))
+        );
+
+        let ctx = fn_ctx(&analyzer, entry, "f(uint256[3], uint256)");
+        let range = |var: &str| {
+            let var = ctx.latest_var_by_name(&analyzer, var).unwrap();
+            (
+                var.evaled_range_min(&analyzer)
+                    .unwrap()
+                    .maybe_concrete()
+                    .unwrap()
+                    .val,
+                var.evaled_range_max(&analyzer)
+                    .unwrap()
+                    .maybe_concrete()
+                    .unwrap()
+                    .val,
+            )
+        };
+        let three = Concrete::Uint(256, U256::from(3));
+        assert_eq!(range("len"), (three.clone(), three));
+        assert_eq!(
+            range("i"),
+            (
+                Concrete::Uint(256, U256::zero()),
+                Concrete::Uint(256, U256::from(2))
+            )
+        );
+    }
+
+    #[test]
+    fn type_max_and_min() {
+        let sol = r###"
+contract A {
+    uint8 constant U8_MAX = type(uint8).max;
+    uint8 constant U8_MIN = type(uint8).min;
+    int128 constant I128_MAX = type(int128).max;
+    int128 constant I128_MIN = type(int128).min;
+    int256 constant I256_MIN = type(int256).min;
+    uint256 constant BITS = type(int16).bits;
+
+    function f() public {
+        int8 x = type(int8).min;
+        int8 y = type(int8).max;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let constant = |name: &str| match analyzer.node(analyzer.user_types[name]) {
+            Node::Var(var) => match analyzer.node(var.initializer.unwrap()) {
+                Node::Concrete(c) => c.clone(),
+                e => panic!("Not a constant: {e:?}"),
+            },
+            e => panic!("Not a variable: {e:?}"),
+        };
+        let i128_max = I256::from_raw((U256::from(1) << 127) - 1);
+        assert_eq!(constant("U8_MAX"), Concrete::Uint(8, U256::from(255)));
+        assert_eq!(constant("U8_MIN"), Concrete::Uint(8, U256::zero()));
+        assert_eq!(constant("I128_MAX"), Concrete::Int(128, i128_max));
+        assert_eq!(
+            constant("I128_MIN"),
+            Concrete::Int(128, -i128_max - I256::one())
+        );
+        assert_eq!(constant("I256_MIN"), Concrete::Int(256, I256::MIN));
+        assert_eq!(constant("BITS"), Concrete::Uint(256, U256::from(16)));
+
+        let ctx = fn_ctx(&analyzer, entry, "f()");
+        let val = |name: &str| {
+            ctx.latest_var_by_name(&analyzer, name)
+                .unwrap()
+                .evaled_range_min(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val
+        };
+        assert_eq!(val("x"), Concrete::Int(8, I256::from(-128)));
+        assert_eq!(val("y"), Concrete::Int(8, I256::from(127)));
+    }
+
+    #[test]
+    fn builtin_sizes() {
+        assert_eq!(Builtin::Uint(8).size(), Some(8));
+        assert_eq!(Builtin::Int(16).size(), Some(16));
+        assert_eq!(Builtin::Bytes(2).size(), Some(16));
+        assert_eq!(Builtin::Address.size(), None);
+        assert_eq!(Builtin::Bool.size(), None);
+
+        assert_eq!(
+            Builtin::Uint(8).max_value(),
+            Some(Concrete::Uint(8, U256::from(255)))
+        );
+        assert_eq!(
+            Builtin::Uint(8).min_value(),
+            Some(Concrete::Uint(8, U256::zero()))
+        );
+        assert_eq!(
+            Builtin::Int(16).max_value(),
+            Some(Concrete::Int(16, I256::from(32767)))
+        );
+        assert_eq!(
+            Builtin::Int(16).min_value(),
+            Some(Concrete::Int(16, I256::from(-32768)))
+        );
+        assert_eq!(
+            Builtin::Uint(256).max_value(),
+            Some(Concrete::Uint(256, U256::MAX))
+        );
+        assert_eq!(
+            Builtin::Int(256).min_value(),
+            Some(Concrete::Int(256, I256::MIN))
+        );
+
+        let mut max = ethers_core::types::H256::zero();
+        max.0[..2].fill(0xff);
+        assert_eq!(Builtin::Bytes(2).max_value(), Some(Concrete::Bytes(2, max)));
+        assert_eq!(Builtin::Address.max_value(), None);
+        assert_eq!(Builtin::Bool.min_value(), None);
+    }
+
+    #[test]
+    fn tuple_destructuring() {
+        let sol = r###"
+contract A {
+    function g() internal returns (uint256, uint256) {
+        return (1, 2);
+    }
+
+    function define() public {
+        (uint256 a, uint256 b) = (1, 2);
+    }
+
+    function ternary(uint256 s) public {
+        (uint256 a, uint256 b) = s < 5 ? (3, 5) : (7, 6);
+    }
+
+    function swap() public {
+        uint256 a;
+        uint256 b;
+        (a, b) = (3, 4);
+        (a, b) = (b, a);
+    }
+
+    function call() public {
+        (uint256 a, uint256 b) = g();
+    }
+
+    function skip() public {
+        (, uint256 b) = (1, 2);
+    }
+
+    function mismatch() public {
+        (uint256 a, uint256 b) = (1, 2, 3);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let values = |func: &str| {
+            fn_ctx(&analyzer, entry, func)
+                .terminal_child_list(&analyzer)
+                .into_iter()
+                .map(|leaf| {
+                    assert!(!leaf.is_killed(&analyzer));
+                    ["a", "b"].map(|name| {
+                        let var = leaf.latest_var_by_name(&analyzer, name)?;
+                        let min = var.evaled_range_min(&analyzer)?.maybe_concrete()?.val;
+                        let max = var.evaled_range_max(&analyzer)?.maybe_concrete()?.val;
+                        assert_eq!(min, max);
+                        min.uint_val().map(|val| val.as_u64())
+                    })
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(values("define()"), vec![[Some(1), Some(2)]]);
+        assert_eq!(
+            values("ternary(uint256)"),
+            vec![[Some(3), Some(5)], [Some(7), Some(6)]]
+        );
+        assert_eq!(values("swap()"), vec![[Some(4), Some(3)]]);
+        assert_eq!(values("call()"), vec![[Some(1), Some(2)]]);
+        assert_eq!(values("skip()"), vec![[None, Some(2)]]);
+        assert_eq!(values("mismatch()"), vec![[Some(1), Some(2)]]);
+        assert_eq!(analyzer.diagnostics.len(), 1);
+        assert!(analyzer.diagnostics[0]
+            .message
+            .contains("3 values for 2 variables"));
+    }
+
+    #[test]
+    fn ternary_forks() {
+        let sol = r###"
+contract A {
+    function nested(uint256 x) public {
+        uint256 y = x < 10 ? (x < 5 ? 1 : 2) : 3;
+    }
+
+    function chained(uint256 x) public {
+        uint256 y = x < 10 ? 1 : x < 20 ? 2 : 3;
+    }
+
+    function operand(uint256 x) public {
+        uint256 y = 1 + (x < 10 ? 1 : 2);
+    }
+
+    function refined(uint256 x) public {
+        uint256 y = x < 3 ? x : 3;
+    }
+
+    function negated(bool b, uint256 x) public {
+        uint256 y = !b && x > 1 ? 1 : 2;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ranges = |func: &str| {
+            let ctx = fn_ctx(&analyzer, entry, func);
+            assert!(!analyzer
+                .search_children(ctx.into(), &Edge::Context(ContextEdge::ContextFork))
+                .is_empty());
+            ctx.terminal_child_list(&analyzer)
+                .into_iter()
+                .map(|leaf| {
+                    let y = leaf.latest_var_by_name(&analyzer, "y").unwrap();
+                    let bound = |elem: Option<Elem<Concrete>>| {
+                        elem.unwrap()
+                            .maybe_concrete()
+                            .unwrap()
+                            .val
+                            .uint_val()
+                            .unwrap()
+                            .as_u64()
+                    };
+                    (
+                        bound(y.evaled_range_min(&analyzer)),
+                        bound(y.evaled_range_max(&analyzer)),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ranges("nested(uint256)"), vec![(1, 1), (2, 2), (3, 3)]);
+        assert_eq!(ranges("chained(uint256)"), vec![(1, 1), (2, 2), (3, 3)]);
+        assert_eq!(ranges("operand(uint256)"), vec![(2, 2), (3, 3)]);
+        assert_eq!(ranges("refined(uint256)"), vec![(0, 2), (3, 3)]);
+        // the false side is where either `b` or `x <= 1`
+        assert_eq!(
+            ranges("negated(bool, uint256)"),
+            vec![(1, 1), (2, 2), (2, 2)]
+        );
+    }
+
+    #[test]
+    fn require_refines_ranges() {
+        let sol = r###"
+contract A {
+    function lt(uint256 a) public {
+        require(a < 10);
+    }
+
+    function flipped(uint256 a) public {
+        require(10 > a, "too big");
+    }
+
+    function eq(uint256 a) public {
+        require(a == 10);
+    }
+
+    function vars(uint256 a, uint256 b) public {
+        require(b < 100);
+        require(a < b);
+    }
+
+    function keepsTighter(uint256 a, uint256 b) public {
+        require(a < 50);
+        require(b >= a);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let range = |func: &str, name: &str| {
+            let ctx = fn_ctx(&analyzer, entry, func);
+            assert!(!ctx.is_killed(&analyzer));
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            let bound = |elem: Option<Elem<Concrete>>| {
+                elem.unwrap()
+                    .maybe_concrete()
+                    .unwrap()
+                    .val
+                    .uint_val()
+                    .unwrap()
+            };
+            (
+                bound(var.evaled_range_min(&analyzer)),
+                bound(var.evaled_range_max(&analyzer)),
+            )
+        };
+        assert_eq!(range("lt(uint256)", "a"), (0.into(), 9.into()));
+        assert_eq!(range("flipped(uint256)", "a"), (0.into(), 9.into()));
+        assert_eq!(range("eq(uint256)", "a"), (10.into(), 10.into()));
+        assert_eq!(range("vars(uint256, uint256)", "a"), (0.into(), 98.into()));
+        assert_eq!(range("vars(uint256, uint256)", "b"), (1.into(), 99.into()));
+        assert_eq!(
+            range("keepsTighter(uint256, uint256)", "a"),
+            (0.into(), 49.into())
+        );
+        assert_eq!(
+            range("keepsTighter(uint256, uint256)", "b"),
+            (0.into(), U256::MAX)
+        );
+
+        let file_mapping = analyzer.file_mapping();
+        let ctx = fn_ctx(&analyzer, entry, "lt(uint256)");
+        let analysis = analyzer.bounds_for_var(
+            None,
+            &file_mapping,
+            ctx,
+            "a".to_string(),
+            ReportConfig::default(),
+            false,
+        );
+        let (_, bounds) = analysis.last().unwrap();
+        let (_, last) = bounds.bound_changes.last().unwrap();
+        assert_eq!(
+            last.evaled_range_max(&analyzer)
+                .maybe_concrete()
+                .unwrap()
+                .val,
+            Concrete::Uint(256, 9.into())
+        );
+    }
+
+    #[test]
+    fn if_else_forks() {
+        let sol = r###"
+contract A {
+    function ifElse(uint256 s) public {
+        uint256 c = 0;
+        if (s < 7) {
+            c += 1;
+        } else {
+            c += 2;
+        }
+    }
+
+    function noElse(uint256 s) public {
+        if (s < 7) {
+            s = 7;
+        }
+    }
+
+    function elseIf(uint256 s) public {
+        uint256 c;
+        if (s < 7) {
+            c = 1;
+        } else if (s < 20) {
+            c = 2;
+        } else {
+            c = 3;
+        }
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let joined = |func: &str, name: &str| {
+            let ctx = fn_ctx(&analyzer, entry, func);
+            let range = analyzer.joined_range_for_var(ctx, name).unwrap();
+            let bound =
+                |elem: Elem<Concrete>| elem.maybe_concrete().unwrap().val.uint_val().unwrap();
+            (
+                bound(range.evaled_range_min(&analyzer)),
+                bound(range.evaled_range_max(&analyzer)),
+            )
+        };
+        assert_eq!(joined("ifElse(uint256)", "c"), (1.into(), 2.into()));
+        assert_eq!(joined("noElse(uint256)", "s"), (7.into(), U256::MAX));
+        assert_eq!(joined("elseIf(uint256)", "c"), (1.into(), 3.into()));
+
+        let ctx = fn_ctx(&analyzer, entry, "elseIf(uint256)");
+        assert_eq!(ctx.terminal_child_list(&analyzer).len(), 3);
+        let file_mapping = analyzer.file_mapping();
+        let analyses = analyzer.bounds_for_var(
+            None,
+            &file_mapping,
+            ctx,
+            "c".to_string(),
+            ReportConfig::default(),
+            false,
+        );
+        assert_eq!(analyses.len(), 3);
+    }
+
+    #[test]
+    fn var_name_resolution() {
+        let sol = r###"
+contract A {
+    function f(uint256 amountIn, uint256 amountOut) public {
+        uint256 fee = amountIn / 100;
+        if (fee > 10) {
+            fee = 10;
+        }
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(uint256, uint256)");
+        assert_eq!(
+            ctx.var_names(&analyzer),
+            vec!["amountIn", "amountOut", "fee"]
+        );
+
+        assert_eq!(analyzer.resolve_var_name(ctx, "fee"), Ok("fee".to_string()));
+        assert_eq!(
+            analyzer.resolve_var_name(ctx, "AMOUNTIN"),
+            Ok("amountIn".to_string())
+        );
+        assert_eq!(
+            analyzer.resolve_var_name(ctx, "amount"),
+            Err(vec!["amountIn".to_string(), "amountOut".to_string()])
+        );
+        assert_eq!(
+            analyzer.resolve_var_name(ctx, "b"),
+            Err(vec![
+                "amountIn".to_string(),
+                "amountOut".to_string(),
+                "fee".to_string()
+            ])
+        );
+
+        let file_mapping = analyzer.file_mapping();
+        let analyses = |name: &str| {
+            analyzer.bounds_for_var(
+                None,
+                &file_mapping,
+                ctx,
+                name.to_string(),
+                ReportConfig::default(),
+                false,
+            )
+        };
+        let (_, analysis) = analyses("Fee").pop().unwrap();
+        assert_eq!(analysis.var_name, "fee");
+        assert!(analyses("b").is_empty());
+    }
+
+    #[test]
+    fn loop_unrolling() {
+        let sol = r###"
+contract A {
+    function counting() public {
+        uint256 sum = 0;
+        for (uint256 i = 0; i < 10; i++) {
+            sum += i;
+        }
+    }
+
+    function whileLoop() public {
+        uint256 j = 0;
+        while (j < 5) {
+            j += 2;
+        }
+    }
+
+    function unknownLimit(uint256 n) public {
+        uint256 k;
+        for (uint256 i = 0; i < n; i++) {
+            k += 1;
+        }
+    }
+
+    function breaks() public {
+        uint256 k = 0;
+        for (uint256 i = 0; i < 10; i++) {
+            if (k > 3) {
+                break;
+            }
+            k += 1;
+        }
+        uint256 after = k;
+    }
+
+    function continues() public {
+        uint256 k = 0;
+        for (uint256 i = 0; i < 6; i++) {
+            if (i == 2) {
+                continue;
+            }
+            k += 1;
+        }
+        uint256 after = k;
+    }
+
+    function skippedWhile() public {
+        uint256 k = 5;
+        while (k < 3) {
+            k += 1;
+        }
+        uint256 after = k;
+    }
+
+    function doWhile() public {
+        uint256 k = 5;
+        do {
+            k += 1;
+        } while (k < 3);
+        uint256 after = k;
+    }
+
+    function doWhileLoop() public {
+        uint256 k = 0;
+        do {
+            k += 2;
+        } while (k < 7);
+        uint256 after = k;
+    }
+}"###;
+        let range = |max_unroll: usize, func: &str, name: &str| {
+            let mut analyzer = Analyzer {
+                max_unroll,
+                ..Default::default()
+            };
+            let entry = analyzer.parse_or_panic(sol, 0);
+            let ctx = fn_ctx(&analyzer, entry, func);
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            let bound = |elem: Option<Elem<Concrete>>| {
+                elem.unwrap()
+                    .maybe_concrete()
+                    .unwrap()
+                    .val
+                    .uint_val()
+                    .unwrap()
+            };
+            (
+                bound(var.evaled_range_min(&analyzer)),
+                bound(var.evaled_range_max(&analyzer)),
+            )
+        };
+        // without unrolling, the loop variable is bounded by the limiter and the rest is widened
+        assert_eq!(range(0, "counting()", "i"), (0.into(), 10.into()));
+        assert_eq!(range(0, "counting()", "sum"), (0.into(), U256::MAX));
+        // unrolled, the loop is executed exactly
+        assert_eq!(range(16, "counting()", "i"), (10.into(), 10.into()));
+        assert_eq!(range(16, "counting()", "sum"), (45.into(), 45.into()));
+        assert_eq!(range(16, "whileLoop()", "j"), (6.into(), 6.into()));
+        // too many iterations to unroll
+        assert_eq!(range(4, "counting()", "i"), (4.into(), 10.into()));
+        assert_eq!(range(4, "counting()", "sum"), (0.into(), U256::MAX));
+        // nothing to unroll
+        assert_eq!(
+            range(16, "unknownLimit(uint256)", "k"),
+            (0.into(), U256::MAX)
+        );
+        // only the iterations before the `break` add to `k`
+        assert_eq!(range(16, "breaks()", "after"), (4.into(), 4.into()));
+        assert_eq!(range(0, "breaks()", "after"), (0.into(), U256::MAX));
+        // a `continue` skips the rest of its iteration, but not the ones after it
+        assert_eq!(range(16, "continues()", "after"), (5.into(), 5.into()));
+        // unlike a `while`, the body of a `do while` runs before the limiter is checked
+        assert_eq!(range(16, "skippedWhile()", "after"), (5.into(), 5.into()));
+        assert_eq!(range(16, "doWhile()", "after"), (6.into(), 6.into()));
+        assert_eq!(range(0, "doWhile()", "after"), (0.into(), U256::MAX));
+        assert_eq!(range(16, "doWhileLoop()", "after"), (8.into(), 8.into()));
+    }
+
+    #[test]
+    fn function_overloads() {
+        let sol = r###"
+contract A {
+    function f(uint256 x) internal returns (uint256) {
+        return x + 1;
+    }
+
+    function f(address a) internal returns (uint256) {
+        return 7;
+    }
+
+    function g() public {
+        uint256 r = f(5);
+        uint256 s = f(address(this));
+    }
+}
+
+contract B {
+    function f(uint256 x) internal returns (uint256) {
+        return x + 100;
+    }
+
+    function h() public {
+        uint256 t = f(1);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        // overloads and same-signature functions of other contracts are all kept
+        for name in ["A.f(uint256)", "A.f(address)", "B.f(uint256)"] {
+            let func = analyzer
+                .user_types
+                .get(name)
+                .unwrap_or_else(|| panic!("No user type: {name}"));
+            assert!(matches!(analyzer.node(*func), Node::Function(..)));
+        }
+
+        let value = |func: &str, name: &str| {
+            let ctx = fn_ctx(&analyzer, entry, func);
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            var.evaled_range_min(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val
+                .uint_val()
+                .unwrap()
+        };
+        assert_eq!(value("g()", "r"), 6.into());
+        assert_eq!(value("g()", "s"), 7.into());
+        // calls resolve within the calling contract
+        assert_eq!(value("h()", "t"), 101.into());
+    }
+
+    #[test]
+    fn member_overloads() {
+        let sol = r###"
+library L {
+    function add(uint256 a, address b) internal pure returns (uint256) {
+        return 7;
+    }
+
+    function add(uint256 a, uint256 b) internal pure returns (uint256) {
+        return a + b;
+    }
+}
+
+contract C {
+    using L for uint256;
+
+    function get(address a) public returns (uint256) {
+        return 5;
+    }
+
+    function get(uint256 x) public returns (uint256) {
+        return x + 100;
+    }
+
+    function f() public {
+        uint256 x = 1;
+        uint256 a = x.add(address(this));
+        uint256 b = x.add(2);
+        uint256 c = this.get(address(this));
+        uint256 d = this.get(1);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f()");
+        let value = |name: &str| {
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            var.evaled_range_min(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val
+                .uint_val()
+                .unwrap()
+        };
+        // the overload is picked by the types of the inputs, not by which is declared first
+        assert_eq!(value("a"), 7.into());
+        assert_eq!(value("b"), 3.into());
+        assert_eq!(value("c"), 5.into());
+        assert_eq!(value("d"), 101.into());
+    }
+
+    #[test]
+    fn modifiers() {
+        let sol = r###"
+contract Base {
+    modifier small(uint256 v) {
+        require(v < 10);
+        _;
+    }
+}
+
+contract A is Base {
+    modifier nonZero(uint256 v) {
+        require(v > 0);
+        _;
+    }
+
+    function f(uint256 x) public nonZero(x) small(x) returns (uint256) {
+        uint256 y = x;
+        return y;
+    }
+
+    function g() public {
+        uint256 r = f(3);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(uint256)");
+
+        // invocations are linked in order, an inherited modifier to the base's definition
+        let modifiers = ctx.associated_fn(&analyzer).modifiers(&analyzer);
+        assert_eq!(
+            modifiers
+                .iter()
+                .map(|m| NodeIdx::from(*m))
+                .collect::<Vec<_>>(),
+            vec![
+                analyzer.user_types["A.nonZero(uint256)"],
+                analyzer.user_types["Base.small(uint256)"]
+            ]
+        );
+
+        // the requires of both modifiers apply to the body
+        let y = ctx.latest_var_by_name(&analyzer, "y").unwrap();
+        let bound = |elem: Option<Elem<Concrete>>| {
+            elem.unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val
+                .uint_val()
+                .unwrap()
+        };
+        assert_eq!(bound(y.evaled_range_min(&analyzer)), 1.into());
+        assert_eq!(bound(y.evaled_range_max(&analyzer)), 9.into());
+
+        // a call evaluates to what the function returned inside its modifiers, whose arguments
+        // are bound to the call's inputs
+        let ctx = fn_ctx(&analyzer, entry, "g()");
+        let r = ctx.latest_var_by_name(&analyzer, "r").unwrap();
+        assert_eq!(bound(r.evaled_range_min(&analyzer)), 3.into());
+        assert_eq!(bound(r.evaled_range_max(&analyzer)), 3.into());
+
+        // and reports walk its subcontexts without tripping over the call nodes
+        let file_mapping = analyzer.file_mapping();
+        assert!(ctx
+            .subcontexts(&analyzer)
+            .iter()
+            .all(|ctx| matches!(analyzer.node(*ctx), Node::Context(_))));
+        analyzer
+            .overflow_report(&file_mapping, ctx, ReportConfig::default())
+            .findings(&analyzer);
+        assert_eq!(analyzer.resolve_var_name(ctx, "R"), Ok("r".to_string()));
+    }
+
+    #[test]
+    fn inheritance() {
+        let sol = r###"
+contract A {
+    uint256 x;
+
+    function foo() internal virtual returns (uint256) {
+        return 1;
+    }
+}
+
+contract Other {
+    uint256 x;
+}
+
+contract B is A {
+    function foo() internal virtual override returns (uint256) {
+        return 2;
+    }
+}
+
+contract C is A {
+    function foo() internal virtual override returns (uint256) {
+        return 3;
+    }
+}
+
+contract D is B, C {
+    function bar() public {
+        uint256 r = foo();
+    }
+}
+
+contract E is A {
+    function baz() public {
+        x = 5;
+        uint256 s = foo() + x;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let contract = |name: &str| {
+            analyzer
+                .search_children(entry, &Edge::Contract)
+                .into_iter()
+                .map(ContractNode::from)
+                .find(|contract| contract.name(&analyzer) == name)
+                .unwrap()
+        };
+        let bases = |name: &str| {
+            analyzer
+                .graph
+                .edges_directed(contract(name).into(), Direction::Outgoing)
+                .filter(|edge| *edge.weight() == Edge::Inherits)
+                .map(|edge| ContractNode::from(edge.target()).name(&analyzer))
+                .collect::<BTreeSet<_>>()
+        };
+        assert_eq!(
+            bases("D"),
+            BTreeSet::from(["B".to_string(), "C".to_string()])
+        );
+        assert!(bases("A").is_empty());
+        let linearized = contract("D")
+            .with_bases(&analyzer)
+            .iter()
+            .map(|contract| contract.name(&analyzer))
+            .collect::<Vec<_>>();
+        assert_eq!(linearized, vec!["D", "C", "B", "A"]);
+        assert_eq!(
+            contract("E").state_var(&analyzer, "x"),
+            contract("A").vars(&analyzer).first().copied()
+        );
+
+        let value = |func: &str, name: &str| {
+            let ctx = fn_ctx(&analyzer, entry, func);
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            var.evaled_range_min(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val
+                .uint_val()
+                .unwrap()
+        };
+        // the most derived override in the linearization wins
+        assert_eq!(value("bar()", "r"), 3.into());
+        assert_eq!(value("baz()", "s"), 6.into());
+    }
+
+    #[test]
+    fn contract_scoped_user_types() {
+        let sol = r###"
+struct Free {
+    uint256 a;
+}
+
+contract A {
+    struct Point {
+        uint256 x;
+    }
+    enum Kind { One }
+
+    modifier only() {
+        _;
+    }
+
+    function a(Point memory p, Kind k, Free memory fr) public only {}
+}
+
+contract B {
+    Point q;
+
+    struct Point {
+        uint256 x;
+        uint256 y;
+    }
+    enum Kind { One, Two }
+
+    modifier only() {
+        _;
+    }
+
+    function b(Point memory p, Kind k) public only {}
+}
+
+contract C is A {
+    function c(Point memory p) public {}
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let param_tys = |func: &str| {
+            fn_ctx(&analyzer, entry, func)
+                .associated_fn(&analyzer)
+                .params(&analyzer)
+                .iter()
+                .map(|param| param.ty(&analyzer))
+                .collect::<Vec<_>>()
+        };
+        let fields = |name: &str| {
+            StructNode::from(analyzer.user_types[name])
+                .fields(&analyzer)
+                .len()
+        };
+
+        assert_ne!(
+            analyzer.user_types["A.Point"],
+            analyzer.user_types["B.Point"]
+        );
+        assert_eq!(fields("A.Point"), 1);
+        assert_eq!(fields("B.Point"), 2);
+        // the bare name belongs to the first definition
+        assert_eq!(analyzer.user_types["Point"], analyzer.user_types["A.Point"]);
+
+        // types resolve to the definition of the contract they are used in, or one it inherits
+        let a_tys = param_tys("a(struct Point, enum Kind, struct Free)");
+        assert_eq!(a_tys[0], analyzer.user_types["A.Point"]);
+        assert_eq!(a_tys[1], analyzer.user_types["A.Kind"]);
+        assert_eq!(a_tys[2], analyzer.user_types["Free"]);
+        let b_tys = param_tys("b(struct Point, enum Kind)");
+        assert_eq!(b_tys[0], analyzer.user_types["B.Point"]);
+        assert_eq!(b_tys[1], analyzer.user_types["B.Kind"]);
+        assert_eq!(
+            param_tys("c(struct Point)")[0],
+            analyzer.user_types["A.Point"]
+        );
+
+        // used before its definition
+        let q = analyzer
+            .graph
+            .node_indices()
+            .find(|idx| {
+                matches!(analyzer.node(*idx), Node::Var(_))
+                    && VarNode::from(*idx).name(&analyzer) == "q"
+            })
+            .unwrap();
+        assert_eq!(
+            VarNode::from(q).underlying(&analyzer).ty,
+            analyzer.user_types["B.Point"]
+        );
+
+        let modifier = |func: &str| {
+            fn_ctx(&analyzer, entry, func)
+                .associated_fn(&analyzer)
+                .modifiers(&analyzer)[0]
+        };
+        assert_eq!(
+            NodeIdx::from(modifier("a(struct Point, enum Kind, struct Free)")),
+            analyzer.user_types["A.only()"]
+        );
+        assert_eq!(
+            NodeIdx::from(modifier("b(struct Point, enum Kind)")),
+            analyzer.user_types["B.only()"]
+        );
+    }
+
+    #[test]
+    fn overflow_report() {
+        let sol = r###"
+pragma solidity ^0.8.0;
+contract A {
+    function add(uint256 c, uint256 s) public {
+        c += s;
+    }
+
+    function bounded(uint256 c, uint256 s) public {
+        require(c < 10);
+        require(s < 5);
+        uint256 d = c + s;
+    }
+
+    function sub(uint256 a, uint256 b) public {
+        uint256 d = a - b;
+    }
+
+    function wraps(uint8 a) public {
+        unchecked {
+            a += 1;
+        }
+    }
+
+    function signed(int256 a) public {
+        int256 b = a * 2;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let file_mapping = analyzer.file_mapping();
+        let overflows = |func: &str| {
+            let ctx = fn_ctx(&analyzer, entry, func);
+            analyzer
+                .overflow_report(&file_mapping, ctx, ReportConfig::default())
+                .overflows
+                .into_iter()
+                .map(|(_, overflow)| (overflow.op, overflow.underflow, overflow.unchecked))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            overflows("add(uint256, uint256)"),
+            vec![(RangeOp::Add, false, false)]
+        );
+        assert!(overflows("bounded(uint256, uint256)").is_empty());
+        assert_eq!(
+            overflows("sub(uint256, uint256)"),
+            vec![(RangeOp::Sub, true, false)]
+        );
+        assert_eq!(overflows("wraps(uint8)"), vec![(RangeOp::Add, false, true)]);
+        assert_eq!(
+            overflows("signed(int256)"),
+            vec![(RangeOp::Mul, false, false), (RangeOp::Mul, true, false)]
+        );
+    }
+
+    #[test]
+    fn pre_08_overflow_report() {
+        let sol = r###"
+pragma solidity 0.7.6;
+contract A {
+    function add(uint8 a) public {
+        require(a >= 250);
+        uint8 c = a + 10;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let file_mapping = analyzer.file_mapping();
+        let ctx = fn_ctx(&analyzer, entry, "add(uint8)");
+        let findings = analyzer
+            .overflow_report(&file_mapping, ctx, ReportConfig::default())
+            .findings(&analyzer);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, Severity::Error);
+        assert_eq!(
+            findings[0].message,
+            "\"+\" may overflow, the result wraps around, e.g. for 250 + 10"
+        );
+    }
+
+    #[test]
+    fn zero_divisor_report() {
+        let sol = r###"
+contract A {
+    function div(uint256 a, uint256 b) public {
+        uint256 c = a / b;
+    }
+
+    function modulo(uint256 a, uint256 b) public {
+        uint256 c = a % b;
+    }
+
+    function nonZero(uint256 a, uint256 b) public {
+        require(b != 0);
+        uint256 c = a / b;
+    }
+
+    function positive(uint256 a, uint256 b) public {
+        require(b > 0);
+        uint256 c = a % b;
+    }
+
+    function literal(uint256 a) public {
+        uint256 c = a / 2;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let file_mapping = analyzer.file_mapping();
+        let zero_divisors = |func: &str| {
+            let ctx = fn_ctx(&analyzer, entry, func);
+            analyzer
+                .overflow_report(&file_mapping, ctx, ReportConfig::default())
+                .zero_divisors
+                .len()
+        };
+        assert_eq!(zero_divisors("div(uint256, uint256)"), 1);
+        assert_eq!(zero_divisors("modulo(uint256, uint256)"), 1);
+        // proven non-zero by a require
+        assert_eq!(zero_divisors("nonZero(uint256, uint256)"), 0);
+        assert_eq!(zero_divisors("positive(uint256, uint256)"), 0);
+        assert_eq!(zero_divisors("literal(uint256)"), 0);
+    }
+
+    #[test]
+    fn enum_ranges_and_casts() {
+        let sol = r###"
+contract A {
+    enum Kind { One, Two, Three }
+
+    function param(Kind k) public {}
+
+    function literal() public {
+        Kind k = Kind(3);
+    }
+
+    function valid() public {
+        Kind k = Kind(2);
+    }
+
+    function unbounded(uint256 x) public {
+        Kind k = Kind(x);
+    }
+
+    function checked(uint256 x) public {
+        require(x < 3);
+        Kind k = Kind(x);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let file_mapping = analyzer.file_mapping();
+
+        let kind = EnumNode::from(analyzer.user_types["Kind"]);
+        let range = kind.range(&analyzer);
+        assert_eq!(
+            range
+                .evaled_range_min(&analyzer)
+                .maybe_concrete()
+                .unwrap()
+                .val,
+            Concrete::Uint(8, U256::zero())
+        );
+        assert_eq!(
+            range
+                .evaled_range_max(&analyzer)
+                .maybe_concrete()
+                .unwrap()
+                .val,
+            Concrete::Uint(8, U256::from(2))
+        );
+
+        // enum typed variables are bounded by the variants
+        let ctx = fn_ctx(&analyzer, entry, "param(enum Kind)");
+        let k = ctx.latest_var_by_name(&analyzer, "k").unwrap();
+        assert_eq!(
+            k.evaled_range_max(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val,
+            Concrete::Uint(8, U256::from(2))
+        );
+
+        let invalid_enum_casts = |func: &str| {
+            let ctx = fn_ctx(&analyzer, entry, func);
+            analyzer
+                .overflow_report(&file_mapping, ctx, ReportConfig::default())
+                .invalid_enum_casts
+                .len()
+        };
+        assert_eq!(invalid_enum_casts("literal()"), 1);
+        assert_eq!(invalid_enum_casts("valid()"), 0);
+        assert_eq!(invalid_enum_casts("unbounded(uint256)"), 1);
+        assert_eq!(invalid_enum_casts("checked(uint256)"), 0);
+    }
+
+    #[test]
+    fn tainted_revert_severity() {
+        let sol = r###"
+pragma solidity ^0.8.0;
+enum Kind {
+    A,
+    B
+}
+
+contract A {
+    uint256[4] fixedList;
+
+    function f(uint8 a, uint256 d, uint256 i, uint256 k) public {
+        uint8 b = a + 1;
+        uint256 q = 10 / d;
+        uint256 x = fixedList[i];
+        Kind kind = Kind(k);
+    }
+
+    function g() public {
+        uint8 b = uint8(block.number) + 1;
+        uint256 q = 10 / block.timestamp;
+    }
+}"###;
+        let severities = |security_mode: bool, func: &str| {
+            let mut analyzer = Analyzer {
+                security_mode,
+                ..Default::default()
+            };
+            let entry = analyzer.parse_or_panic(sol, 0);
+            let file_mapping = analyzer.file_mapping();
+            let ctx = fn_ctx(&analyzer, entry, func);
+            analyzer
+                .overflow_report(&file_mapping, ctx, ReportConfig::default())
+                .findings(&analyzer)
+                .into_iter()
+                .map(|finding| finding.severity)
+                .collect::<Vec<_>>()
+        };
+
+        let f = "f(uint8, uint256, uint256, uint256)";
+        assert_eq!(severities(false, f), vec![Severity::Warning; 4]);
+        // public inputs can be chosen to make each statement revert
+        assert_eq!(severities(true, f), vec![Severity::Error; 4]);
+        // block values are not chosen by the caller
+        assert_eq!(severities(true, "g()"), vec![Severity::Warning; 2]);
+    }
+
+    #[test]
+    fn out_of_bounds_report() {
+        let sol = r###"
+contract A {
+    uint256[4] fixedList;
+    uint256[] list;
+
+    function write(uint256 i) public {
+        fixedList[i] = 7;
+    }
+
+    function read(uint256 i) public {
+        uint256 x = fixedList[i];
+    }
+
+    function checked(uint256 i) public {
+        require(i < 4);
+        uint256 x = fixedList[i];
+    }
+
+    function literal() public {
+        fixedList[3] = 5;
+    }
+
+    function dynamic(uint256 i) public {
+        uint256 x = list[i];
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let file_mapping = analyzer.file_mapping();
+        let out_of_bounds = |func: &str| {
+            let ctx = fn_ctx(&analyzer, entry, func);
+            analyzer
+                .overflow_report(&file_mapping, ctx, ReportConfig::default())
+                .out_of_bounds
+                .len()
+        };
+        assert_eq!(out_of_bounds("write(uint256)"), 1);
+        assert_eq!(out_of_bounds("read(uint256)"), 1);
+        assert_eq!(out_of_bounds("checked(uint256)"), 0);
+        assert_eq!(out_of_bounds("literal()"), 0);
+        // the length of a dynamic array isn't known
+        assert_eq!(out_of_bounds("dynamic(uint256)"), 0);
+
+        // the element is typed after the array and keeps the assigned value
+        let ctx = fn_ctx(&analyzer, entry, "write(uint256)");
+        let elem = ctx.latest_var_by_name(&analyzer, "fixedList[i]").unwrap();
+        assert_eq!(elem.ty(&analyzer).as_string(&analyzer), "uint256");
+        let val = |elem: Option<Elem<Concrete>>| elem.unwrap().maybe_concrete().unwrap().val;
+        assert_eq!(
+            val(elem.evaled_range_min(&analyzer)),
+            Concrete::from(U256::from(7))
+        );
+        assert_eq!(
+            val(elem.evaled_range_max(&analyzer)),
+            Concrete::from(U256::from(7))
+        );
+        // past the bounds check the index is below the length
+        let i = ctx.latest_var_by_name(&analyzer, "i").unwrap();
+        assert_eq!(
+            val(i.evaled_range_max(&analyzer)),
+            Concrete::from(U256::from(3))
+        );
+    }
+
+    #[test]
+    fn contract_and_function_inventory() {
+        let sol = r###"
+function free(uint256 a) pure returns (uint256) {
+    return a;
+}
+
+interface I {
+    function get() external returns (uint256);
+}
+
+contract A {
+    modifier onlyOwner() {
+        _;
+    }
+
+    function f(uint256 a) public returns (bytes32) {
+        return keccak256(abi.encode(a));
+    }
+
+    function f(uint256 a, uint256 b) public {}
+}
+
+contract B is A {
+    function g() public onlyOwner {}
+}"###;
+        let mut analyzer = Analyzer::default();
+        analyzer.parse_or_panic(sol, 0);
+
+        let contracts = analyzer
+            .contracts()
+            .iter()
+            .map(|contract| contract.name(&analyzer))
+            .collect::<Vec<_>>();
+        assert_eq!(contracts, vec!["I", "A", "B"]);
+
+        // the builtins called in `f` are not part of the inventory
+        let mut funcs = analyzer
+            .functions()
+            .iter()
+            .map(|func| func.name(&analyzer))
+            .collect::<Vec<_>>();
+        funcs.sort();
+        assert_eq!(
+            funcs,
+            vec![
+                "f(uint256)",
+                "f(uint256, uint256)",
+                "free(uint256)",
+                "g()",
+                "get()",
+                "onlyOwner()"
+            ]
+        );
+
+        let b = analyzer.contract_by_name("B").unwrap();
+        assert_eq!(NodeIdx::from(b), analyzer.user_types["B"]);
+        assert!(analyzer.contract_by_name("C").is_none());
+        assert_eq!(analyzer.functions_by_name("f").len(), 2);
+        assert!(analyzer.functions_by_name("keccak256").is_empty());
+    }
+
+    #[test]
+    fn stray_semicolons() {
+        let sol = r###"
+contract A {
+    uint256 x;;
+
+    function f() public returns (uint256) {
+        return 1;
+    };
+};
+"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        fn_ctx(&analyzer, entry, "f()");
+        assert!(analyzer.contract_by_name("A").is_some());
+    }
+
+    #[test]
+    fn annotations() {
+        let sol = r###"
+@program_id("Foo")
+contract A {
+    @seed("x")
+    function f() public {}
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let a = analyzer.contract_by_name("A").unwrap();
+        let string = |expr: Option<&Expression>| match expr {
+            Some(Expression::StringLiteral(lits)) => Some(lits[0].string.clone()),
+            _ => None,
+        };
+        assert_eq!(
+            string(analyzer.annotation(entry, "program_id")),
+            Some("Foo".to_string())
+        );
+        assert_eq!(
+            string(analyzer.annotation(a, "seed")),
+            Some("x".to_string())
+        );
+        assert!(analyzer.annotation(a, "program_id").is_none());
+        fn_ctx(&analyzer, entry, "f()");
+    }
+
+    #[test]
+    fn negative_numbers() {
+        let sol = r###"
+pragma solidity ^0.8.0;
+contract A {
+    int256 constant NEG = -1;
+    int256 constant CUBE = (-2) ** 3;
+
+    function f(int8 y) public {
+        int256 x = -1;
+        int256 a = (-2) ** 3;
+        int256 b = (-2) ** 4;
+        int256 c = NEG;
+        int256 d = CUBE;
+        int8 e = -128;
+        int16 g = -129;
+        int256 h = x ** 2;
+        int256 i = -x;
+        int8 j = -y;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(int8)");
+        let range = |name: &str| {
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            let val = |elem: Option<Elem<Concrete>>| elem.unwrap().maybe_concrete().unwrap().val;
+            (
+                val(var.evaled_range_min(&analyzer)),
+                val(var.evaled_range_max(&analyzer)),
+            )
+        };
+        let int = |size: u16, val: i64| Concrete::Int(size, I256::from(val));
+        assert_eq!(range("x"), (int(256, -1), int(256, -1)));
+        assert_eq!(range("a"), (int(256, -8), int(256, -8)));
+        assert_eq!(range("b"), (int(256, 16), int(256, 16)));
+        // folded constants
+        assert_eq!(range("c"), (int(256, -1), int(256, -1)));
+        assert_eq!(range("d"), (int(256, -8), int(256, -8)));
+        // literals take the smallest width that holds them
+        assert_eq!(range("e"), (int(8, -128), int(8, -128)));
+        assert_eq!(range("g"), (int(16, -129), int(16, -129)));
+        assert_eq!(range("h"), (int(256, 1), int(256, 1)));
+        assert_eq!(range("i"), (int(256, 1), int(256, 1)));
+        // negating -128 does not fit in an int8
+        assert_eq!(range("j"), (int(8, -127), int(8, 127)));
+
+        assert_eq!(
+            context::exprs::negative_literal_val(U256::one() << 255),
+            Some((256, I256::MIN))
+        );
+        assert_eq!(
+            context::exprs::negative_literal_val((U256::one() << 255) + 1),
+            None
+        );
+    }
+
+    #[test]
+    fn range_to_string() {
+        let sol = r###"
+pragma solidity ^0.8.0;
+contract A {
+    function f(uint8 x, int8 y, uint8 z) public {
+        uint8 a = x + 1;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(uint8, int8, uint8)");
+        let range = |name: &str| {
+            ctx.latest_var_by_name(&analyzer, name)
+                .unwrap()
+                .range(&analyzer)
+                .unwrap()
+        };
+        let decimal = RangeStringConfig {
+            eval_bounds: true,
+            hex: false,
+        };
+        let hex = RangeStringConfig {
+            eval_bounds: true,
+            hex: true,
+        };
+        assert_eq!(range("z").to_string_with(decimal, &analyzer), "0..=255");
+        assert_eq!(range("z").to_string_with(hex, &analyzer), "0x0..=0xff");
+        assert_eq!(range("y").to_string_with(decimal, &analyzer), "-128..=127");
+        assert_eq!(range("y").to_string_with(hex, &analyzer), "-0x80..=0x7f");
+        assert_eq!(range("a").to_string_with(decimal, &analyzer), "1..=255");
+        // unevaluated bounds are shown in terms of the variables they depend on
+        assert_eq!(
+            range("a").to_string_with(RangeStringConfig::default(), &analyzer),
+            "(x + 1)..=(x + 1)"
+        );
+    }
+
+    #[test]
+    fn bounds_only_changed() {
+        let sol = r###"
+contract A {
+    enum E { X, Y }
+
+    function f(uint8 x, uint8 y, E e) public {
+        require(x < 10);
+        uint8 z = y;
+        uint8 w = x;
+        y = 3;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let file_mapping = analyzer.file_mapping();
+        let ctx = fn_ctx(&analyzer, entry, "f(uint8, uint8, enum E)");
+        let reported = |only_changed: bool| {
+            let config = ReportConfig {
+                show_consts: true,
+                only_changed,
+                ..Default::default()
+            };
+            let mut names = analyzer
+                .bounds_for_all(&file_mapping, ctx, config)
+                .vars_by_ctx
+                .into_values()
+                .flatten()
+                .map(|analysis| analysis.var_name)
+                .collect::<Vec<_>>();
+            names.sort();
+            names
+        };
+        assert_eq!(reported(false), vec!["e", "w", "x", "y", "z"]);
+        // `z` is a copy of `y` from before it was set, so it can be any uint8
+        assert_eq!(reported(true), vec!["w", "x", "y"]);
+    }
+
+    #[test]
+    fn graph_walk() {
+        let sol = r###"
+contract A {
+    struct Point { uint256 x; uint256 y; }
+
+    function f(uint256 a) public returns (uint256) {
+        return a;
+    }
+
+    function g() public {}
+}
+
+contract B {
+    function h() public {}
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let a = analyzer.user_types["A"];
+
+        let funcs = analyzer
+            .children_of_kind(a, Edge::Func)
+            .into_iter()
+            .map(|func| FunctionNode::from(func).name(&analyzer))
+            .collect::<BTreeSet<_>>();
+        assert_eq!(funcs, BTreeSet::from(["f(uint256)".into(), "g()".into()]));
+
+        // only follows the edges that pass the filter, contracts then their functions
+        let mut visited = vec![];
+        analyzer.walk(
+            entry,
+            Direction::Incoming,
+            |edge| matches!(edge, Edge::Part | Edge::Contract | Edge::Func),
+            |idx, node| {
+                if let Node::Function(_) = node {
+                    visited.push(FunctionNode::from(idx).name(&analyzer));
+                }
+                assert!(!matches!(node, Node::Struct(_) | Node::FunctionParam(_)));
+            },
+        );
+        visited.sort();
+        assert_eq!(visited, vec!["f(uint256)", "g()", "h()"]);
+
+        // and back up from a parameter to its function and contract
+        let f = analyzer
+            .children_of_kind(a, Edge::Func)
+            .into_iter()
+            .find(|func| FunctionNode::from(*func).name(&analyzer) == "f(uint256)")
+            .unwrap();
+        let param = analyzer.children_of_kind(f, Edge::FunctionParam)[0];
+        let mut parents = vec![];
+        analyzer.walk(
+            param,
+            Direction::Outgoing,
+            |_| true,
+            |idx, _| parents.push(idx),
+        );
+        assert!(parents.contains(&a));
+        assert!(parents.contains(&entry));
+    }
+
+    #[test]
+    fn super_overloads() {
+        let sol = r###"
+contract Base {
+    function foo(uint256 x) internal virtual returns (uint256) {
+        return x + 1;
+    }
+
+    function foo(address a) internal virtual returns (uint256) {
+        return 7;
+    }
+}
+
+contract C is Base {
+    function foo(uint256 x) internal override returns (uint256) {
+        return 0;
+    }
+
+    function bar() public {
+        uint256 a = super.foo(2);
+        uint256 b = super.foo(address(this));
+        uint256 c = super.missing(1);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "bar()");
+        let min = |name: &str| {
+            ctx.latest_var_by_name(&analyzer, name)
+                .unwrap()
+                .evaled_range_min(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val
+        };
+        assert_eq!(min("a"), Concrete::Uint(256, U256::from(3)));
+        assert_eq!(min("b"), Concrete::Uint(256, U256::from(7)));
+        assert!(analyzer
+            .diagnostics()
+            .iter()
+            .any(|diag| diag.message.contains("No function named missing")));
+    }
+
+    #[test]
+    fn this_and_super_calls() {
+        let sol = r###"
+contract Base {
+    function foo(uint256 x) public virtual returns (uint256) {
+        return x + 1;
+    }
+
+    function inherited() public returns (uint256) {
+        return 7;
+    }
+}
+
+contract Mid is Base {
+    function foo(uint256 x) public virtual override returns (uint256) {
+        uint256 m = super.foo(x);
+        return m + 10;
+    }
+}
+
+contract C is Mid {
+    function foo(uint256 x) public override returns (uint256) {
+        uint256 r = super.foo(x);
+        return r * 2;
+    }
+
+    function bar() public {
+        uint256 a = super.foo(2);
+        uint256 b = this.foo(1);
+        uint256 c = this.inherited();
+        uint256 d = super.foo(2) + 1;
+        uint256 e = 1 + this.inherited();
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+
+        let ctx = fn_ctx(&analyzer, entry, "bar()");
+        let concrete = |name: &str| {
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            let min = var
+                .evaled_range_min(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val;
+            let max = var
+                .evaled_range_max(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val;
+            assert_eq!(min, max);
+            min
+        };
+        // `super` skips `C.foo` and runs `Mid.foo`, which in turn reaches `Base.foo`
+        assert_eq!(concrete("a"), Concrete::Uint(256, U256::from(13)));
+        // `this` resolves to the most derived override and inherited functions
+        assert_eq!(concrete("b"), Concrete::Uint(256, U256::from(24)));
+        assert_eq!(concrete("c"), Concrete::Uint(256, U256::from(7)));
+        // and their results can be used in arithmetic
+        assert_eq!(concrete("d"), Concrete::Uint(256, U256::from(14)));
+        assert_eq!(concrete("e"), Concrete::Uint(256, U256::from(8)));
+
+        // calls through `this` leave the contract, `super` calls do not
+        let called = |func: Option<FunctionNode>| {
+            func.map(|func| {
+                format!(
+                    "{}.{}",
+                    func.contract(&analyzer).unwrap().name(&analyzer),
+                    func.name(&analyzer)
+                )
+            })
+        };
+        let mut internal = vec![];
+        let mut external = vec![];
+        ctx.subcontexts(&analyzer).into_iter().for_each(|subctx| {
+            let underlying = subctx.underlying(&analyzer);
+            internal.extend(called(underlying.fn_call));
+            external.extend(called(underlying.ext_fn_call));
+        });
+        external.sort();
+        external.dedup();
+        assert_eq!(external, vec!["Base.inherited()", "C.foo(uint256)"]);
+        assert!(internal.contains(&"Mid.foo(uint256)".to_string()));
+        assert!(!internal.contains(&"C.foo(uint256)".to_string()));
+    }
+
+    #[test]
+    fn interprocedural_ranges() {
+        let sol = r###"
+pragma solidity ^0.8.0;
+contract C {
+    function a() public {
+        scale(5);
+    }
+
+    function b(uint256 y) public {
+        require(y < 10);
+        scale(y + 20);
+    }
+
+    function scale(uint256 x) internal returns (uint256) {
+        return x * 2;
+    }
+
+    function unused(uint256 x) internal {}
+
+    function countdown(uint256 n) internal returns (uint256) {
+        if (n == 0) {
+            return 0;
+        }
+        return countdown(n - 1);
+    }
+
+    function c() public {
+        countdown(3);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        analyzer.parse_or_panic(sol, 0);
+        let func = |name: &str| FunctionNode::from(analyzer.user_types[name]);
+        let (scale, unused, a, countdown) = (
+            func("scale(uint256)"),
+            func("unused(uint256)"),
+            func("a()"),
+            func("countdown(uint256)"),
+        );
+
+        let ctx = analyzer.interprocedural_ctx(scale).unwrap();
+        let x = ctx.var_by_name(&analyzer, "x").unwrap();
+        let bound = |elem: Option<Elem<Concrete>>| elem.unwrap().maybe_concrete().unwrap().val;
+        assert_eq!(
+            bound(x.evaled_range_min(&analyzer)),
+            Concrete::Uint(256, 5.into())
+        );
+        assert_eq!(
+            bound(x.evaled_range_max(&analyzer)),
+            Concrete::Uint(256, 29.into())
+        );
+        let file_mapping = analyzer.file_mapping();
+        let analysis = analyzer.bounds_for_all(&file_mapping, ctx, ReportConfig::default());
+        assert!(!analysis.vars_by_ctx.is_empty());
+
+        assert!(analyzer.interprocedural_ctx(unused).is_none());
+        assert!(analyzer.interprocedural_ctx(a).is_none());
+        // recursion is cut off rather than followed forever
+        assert!(analyzer.interprocedural_ctx(countdown).is_some());
+    }
+
+    #[test]
+    fn new_expressions() {
+        let sol = r###"
+contract Foo {
+    uint256 v;
+
+    constructor(uint256 x) {
+        v = x;
+    }
+
+    function get() public returns (uint256) {
+        return 1;
+    }
+}
+
+contract Bar {}
+
+contract C {
+    function array(uint256 n) public {
+        require(n < 10);
+        uint256[] memory a = new uint256[](n);
+        uint256 l = a.length;
+    }
+
+    function dynBytes(uint256 n) public {
+        bytes memory b = new bytes(n + 1);
+    }
+
+    function deploy() public {
+        Foo f = new Foo(5);
+        uint256 r = f.get();
+        Bar b = new Bar();
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let file_mapping = analyzer.file_mapping();
+
+        let ctx = fn_ctx(&analyzer, entry, "array(uint256)");
+        let a = ctx.latest_var_by_name(&analyzer, "a").unwrap();
+        assert_eq!(a.ty(&analyzer).as_string(&analyzer), "uint256[]");
+        let l = ctx.latest_var_by_name(&analyzer, "l").unwrap();
+        assert_eq!(
+            l.evaled_range_min(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val,
+            Concrete::Uint(256, U256::zero())
+        );
+        assert_eq!(
+            l.evaled_range_max(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val,
+            Concrete::Uint(256, U256::from(9))
+        );
+
+        // the size expression is still checked
+        let ctx = fn_ctx(&analyzer, entry, "dynBytes(uint256)");
+        let b = ctx.latest_var_by_name(&analyzer, "b").unwrap();
+        assert_eq!(b.ty(&analyzer).as_string(&analyzer), "bytes");
+        let report = analyzer.overflow_report(&file_mapping, ctx, ReportConfig::default());
+        assert_eq!(report.overflows.len(), 1);
+
+        let ctx = fn_ctx(&analyzer, entry, "deploy()");
+        let f = ctx.latest_var_by_name(&analyzer, "f").unwrap();
+        assert_eq!(f.ty(&analyzer).as_string(&analyzer), "contract Foo");
+        let b = ctx.latest_var_by_name(&analyzer, "b").unwrap();
+        assert_eq!(b.ty(&analyzer).as_string(&analyzer), "contract Bar");
+        let r = ctx.latest_var_by_name(&analyzer, "r").unwrap();
+        assert_eq!(r.ty(&analyzer).as_string(&analyzer), "uint256");
+    }
+
+    #[test]
+    fn shadowing_report() {
+        let sol = r###"
+contract Base {
+    uint256 owner;
+}
+
+contract A is Base {
+    uint256 total;
+
+    function param(uint256 total) public {
+        total = 5;
+    }
+
+    function local(uint256 x) public {
+        if (x > 5) {
+            uint256 owner = x;
+        }
+        uint256 total;
+    }
+
+    function clean(uint256 x) public {
+        uint256 y = x;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let file_mapping = analyzer.file_mapping();
+        let shadowed = |func: &str| {
+            let ctx = fn_ctx(&analyzer, entry, func);
+            analyzer
+                .shadowing_report(&file_mapping, ctx, ReportConfig::default())
+                .shadowed
+                .into_iter()
+                .map(|(_, _, name)| name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(shadowed("param(uint256)"), vec!["total"]);
+        // inherited state variables are shadowed too, and each definition is reported once
+        // even though it is reached in both forks of the `if`
+        assert_eq!(shadowed("local(uint256)"), vec!["owner", "total"]);
+        assert!(shadowed("clean(uint256)").is_empty());
+
+        let ctx = fn_ctx(&analyzer, entry, "param(uint256)");
+        let report = analyzer.shadowing_report(&file_mapping, ctx, ReportConfig::default());
+        assert_eq!(report.labels(&analyzer).len(), 2);
+        assert_eq!(report.findings(&analyzer)[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn dead_code_report() {
+        let sol = r###"
+contract A {
+    function never(uint256 x) public {
+        require(x <= 5);
+        if (x > 10) {
+            x = 1;
+        }
+    }
+
+    function always(uint256 x) public {
+        require(x <= 5);
+        if (x < 10) {
+            x = 1;
+        } else {
+            x = 2;
+        }
+    }
+
+    function loop() public {
+        uint256 y;
+        for (uint256 i = 0; i < 3; i++) {
+            if (i == 1) {
+                y = 1;
+            }
+        }
+    }
+
+    function live(uint256 x) public {
+        if (x > 10) {
+            x = 1;
+        } else {
+            x = 2;
+        }
+    }
+}"###;
+        let mut analyzer = Analyzer {
+            max_unroll: 5,
+            ..Default::default()
+        };
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let file_mapping = analyzer.file_mapping();
+        let dead = |func: &str| {
+            let ctx = fn_ctx(&analyzer, entry, func);
+            analyzer
+                .dead_code_report(&file_mapping, ctx, ReportConfig::default())
+                .dead_branches
+                .into_iter()
+                .map(|span| sol[span.1.start()..span.1.end()].to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            dead("never(uint256)"),
+            vec!["{\n            x = 1;\n        }"]
+        );
+        assert_eq!(
+            dead("always(uint256)"),
+            vec!["{\n            x = 2;\n        }"]
+        );
+        // the branch is only skipped in some of the unrolled iterations
+        assert!(dead("loop()").is_empty());
+        assert!(dead("live(uint256)").is_empty());
+
+        // the dead side is not analyzed
+        let ctx = fn_ctx(&analyzer, entry, "never(uint256)");
+        let x = ctx.latest_var_by_name(&analyzer, "x").unwrap();
+        assert_eq!(
+            x.evaled_range_max(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val,
+            Concrete::from(U256::from(5))
+        );
+
+        let report = analyzer.dead_code_report(&file_mapping, ctx, ReportConfig::default());
+        assert_eq!(report.findings(&analyzer)[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn json_findings() {
+        let sol = r###"
+contract A {
+    function f(uint8 a) public returns (uint8) {
+        require(a < 10);
+        return a;
+    }
+
+    function g(uint8 a, uint8 b) public {
+        uint8 c = a / b;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let file_mapping = analyzer.file_mapping();
+        let config = ReportConfig {
+            output: OutputFormat::Json,
+            ..Default::default()
+        };
+
+        let ctx = fn_ctx(&analyzer, entry, "f(uint8)");
+        let findings = analyzer
+            .bounds_for_all(&file_mapping, ctx, config)
+            .findings(&analyzer);
+        let ret = findings
+            .iter()
+            .find(|finding| finding.message.starts_with("Return value"))
+            .expect("No return value finding");
+        assert_eq!(ret.severity, Severity::Info);
+        assert_eq!(ret.var.as_deref(), Some("a"));
+        assert_eq!(ret.min.as_deref(), Some("0"));
+        assert_eq!(ret.max.as_deref(), Some("9"));
+        assert_eq!(ret.file, file_mapping[&0]);
+        assert_eq!(&sol[ret.start..ret.end], "return a");
+
+        let ctx = fn_ctx(&analyzer, entry, "g(uint8, uint8)");
+        let json: serde_json::Value = serde_json::from_str(
+            &analyzer
+                .overflow_report(&file_mapping, ctx, config)
+                .reports_json(&analyzer),
+        )
+        .unwrap();
+        let findings = json.as_array().unwrap();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0]["severity"], "warning");
+        assert_eq!(findings[0]["var"], serde_json::Value::Null);
+        assert_eq!(
+            &sol[findings[0]["start"].as_u64().unwrap() as usize
+                ..findings[0]["end"].as_u64().unwrap() as usize],
+            "a / b"
+        );
+    }
+
+    #[test]
+    fn concrete_interning() {
+        let sol = r###"
+contract A {
+    function f(uint256 a) public returns (uint256) {
+        uint256 b = a + 7;
+        uint256 c = b * 7;
+        return c - 7;
+    }
+
+    function g(uint256 a) public returns (uint256) {
+        return a + 7;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        analyzer.parse_or_panic(sol, 0);
+        // every distinct constant, e.g. the literal `7` and its cast to uint256, has one node
+        let concretes = analyzer
+            .graph
+            .node_weights()
+            .filter_map(|node| match node {
+                Node::Concrete(c) => Some(c.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let distinct = concretes.iter().collect::<BTreeSet<_>>();
+        assert_eq!(concretes.len(), distinct.len());
+        assert!(concretes.contains(&Concrete::Uint(256, 7.into())));
+
+        let seven = Concrete::Uint(256, 7.into());
+        let node_count = analyzer.graph.node_count();
+        analyzer.concrete_or_add(seven.clone());
+        assert_eq!(analyzer.graph.node_count(), node_count);
+
+        // the cache follows the nodes when the graph is compacted
+        analyzer.reset_contexts();
+        let idx = analyzer.concretes[&seven];
+        assert!(matches!(analyzer.node(idx), Node::Concrete(c) if *c == seven));
+        assert_eq!(analyzer.concrete_or_add(seven), idx);
+    }
+
+    #[test]
+    fn contract_members() {
+        let sol = r###"
+contract Base {
+    uint256 baseVar;
+    struct BaseStruct { uint256 a; }
+}
+
+contract A is Base {
+    uint256 total;
+    address owner;
+    struct Point { uint256 x; uint256 y; }
+    enum Kind { One, Two }
+    error Unauthorized(address caller);
+
+    function f(uint256 local) public {
+        uint256 b = local;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let contract = analyzer
+            .search_children(entry, &Edge::Contract)
+            .into_iter()
+            .map(ContractNode::from)
+            .find(|contract| contract.name(&analyzer) == "A")
+            .unwrap();
+
+        let vars = contract
+            .vars(&analyzer)
+            .iter()
+            .map(|var| var.name(&analyzer))
+            .collect::<Vec<_>>();
+        assert_eq!(vars, vec!["total", "owner"]);
+        let structs = contract
+            .structs(&analyzer)
+            .iter()
+            .map(|strukt| strukt.name(&analyzer))
+            .collect::<Vec<_>>();
+        assert_eq!(structs, vec!["Point"]);
+        let enums = contract
+            .enums(&analyzer)
+            .iter()
+            .map(|enu| enu.name(&analyzer))
+            .collect::<Vec<_>>();
+        assert_eq!(enums, vec!["Kind"]);
+        let errors = contract
+            .errors(&analyzer)
+            .iter()
+            .map(|err| err.underlying(&analyzer).name.clone().unwrap().name)
+            .collect::<Vec<_>>();
+        assert_eq!(errors, vec!["Unauthorized"]);
+        assert_eq!(contract.funcs(&analyzer).len(), 1);
+    }
+
+    #[test]
+    fn constructors() {
+        let sol = r###"
+contract A {
+    uint256 x;
+    uint256 y;
+
+    modifier below(uint256 v, uint256 cap) {
+        require(v < cap);
+        _;
+    }
+
+    constructor(uint256 a) {
+        require(a < 5);
+        x = a;
+    }
+
+    fallback() external {
+        y = 1;
+    }
+
+    receive() external payable {
+        y = 2;
+    }
+
+    function g(uint256 a) public below(a, 10) returns (uint256) {
+        uint256 b = a;
+        return b;
+    }
+}
+
+contract B is A {
+    constructor() A(3) {
+        y = x + 1;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let contract = analyzer
+            .search_children(entry, &Edge::Contract)
+            .into_iter()
+            .map(ContractNode::from)
+            .find(|contract| contract.name(&analyzer) == "A")
+            .unwrap();
+        let names = contract
+            .funcs(&analyzer)
+            .iter()
+            .map(|func| func.name(&analyzer))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                "constructor(uint256)",
+                "fallback()",
+                "receive()",
+                "g(uint256)"
+            ]
+        );
+
+        let range = |func: &str, name: &str| {
+            let ctx = fn_ctx(&analyzer, entry, func);
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            let bound = |elem: Option<Elem<Concrete>>| elem.unwrap().maybe_concrete().unwrap().val;
+            (
+                bound(var.evaled_range_min(&analyzer)).uint_val().unwrap(),
+                bound(var.evaled_range_max(&analyzer)).uint_val().unwrap(),
+            )
+        };
+        assert_eq!(range("constructor(uint256)", "x"), (0.into(), 4.into()));
+        assert_eq!(range("fallback()", "y"), (1.into(), 1.into()));
+        assert_eq!(range("receive()", "y"), (2.into(), 2.into()));
+        // a modifier argument that is not a parameter of the function
+        assert_eq!(range("g(uint256)", "b"), (0.into(), 9.into()));
+        // the base constructor runs first
+        assert_eq!(range("constructor()", "x"), (3.into(), 3.into()));
+        assert_eq!(range("constructor()", "y"), (4.into(), 4.into()));
+    }
+
+    #[test]
+    fn unnamed_definitions() {
+        let sol = r###"
+contract A {
+    uint256 x;
+
+    function() public {
+        x = 1;
+    }
+
+    function f(uint256 a) public returns (uint256) {
+        uint256 b = a + 1;
+        return b;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let contract = ContractNode::from(
+            *analyzer
+                .search_children(entry, &Edge::Contract)
+                .iter()
+                .next()
+                .unwrap(),
+        );
+        let names = contract
+            .funcs(&analyzer)
+            .iter()
+            .map(|func| func.name(&analyzer))
+            .collect::<Vec<_>>();
+        assert_eq!(names.len(), 2);
+        assert!(names
+            .iter()
+            .any(|name| name.starts_with("unnamed_function_")));
+        assert_eq!(analyzer.diagnostics.len(), 1);
+        assert!(analyzer.diagnostics[0].message.contains("Unnamed function"));
+        // the rest of the contract is still analyzed
+        assert!(fn_ctx(&analyzer, entry, "f(uint256)")
+            .latest_var_by_name(&analyzer, "b")
+            .is_some());
+    }
+
+    #[test]
+    fn try_node() {
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic("contract C {}", 0);
+        assert!(matches!(
+            analyzer.try_node(entry),
+            Some(Node::SourceUnit(0))
+        ));
+        let missing = NodeIdx::from(analyzer.graph.node_count());
+        assert!(analyzer.try_node(missing).is_none());
+        assert!(analyzer.try_node_mut(missing).is_none());
+    }
+
+    #[test]
+    fn event_definitions() {
+        let sol = r###"
+event Ping(uint256 n) anonymous;
+
+contract Token {
+    event Transfer(address indexed from, address indexed to, uint256 value);
+
+    function f() public {
+        emit Transfer(msg.sender, msg.sender, 1);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        analyzer.parse_or_panic(sol, 0);
+        let token = analyzer.user_types["Token"];
+        let transfer = EventNode::from(
+            *analyzer
+                .search_children(token, &Edge::Event)
+                .iter()
+                .next()
+                .unwrap(),
+        );
+        assert_eq!(
+            transfer.underlying(&analyzer).name.as_ref().unwrap().name,
+            "Transfer"
+        );
+        let params = transfer.params(&analyzer);
+        assert_eq!(
+            params
+                .iter()
+                .map(|param| param.underlying(&analyzer).indexed)
+                .collect::<Vec<_>>(),
+            vec![true, true, false]
+        );
+        assert_eq!(
+            analyzer.node(params[2].underlying(&analyzer).ty),
+            &Node::Builtin(Builtin::Uint(256))
+        );
+
+        let ping = analyzer
+            .graph()
+            .node_indices()
+            .find(|idx| matches!(analyzer.node(*idx), Node::Event(event) if event.anonymous))
+            .map(EventNode::from)
+            .unwrap();
+        assert_eq!(ping.params(&analyzer).len(), 1);
+    }
+
+    #[test]
+    fn emit_statements() {
+        let sol = r###"
+event Ping(uint256 n);
+
+contract Base {
+    event Moved(uint256 amount);
+}
+
+contract Token is Base {
+    event Transfer(address to, uint256 value);
+    event Transfer(address to);
+
+    uint256 count;
+
+    function f(address to) public {
+        emit Transfer(to, count++);
+        emit Transfer(to);
+        emit Moved(count);
+        emit Base.Moved(2);
+        emit Ping(count);
+        emit Missing(count);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(address)");
+
+        let mut emitted = ctx
+            .emitted(&analyzer)
+            .iter()
+            .map(|event| (event.name(&analyzer), event.params(&analyzer).len()))
+            .collect::<Vec<_>>();
+        emitted.sort();
+        assert_eq!(
+            emitted,
+            vec![
+                ("Moved".to_string(), 1),
+                ("Moved".to_string(), 1),
+                ("Ping".to_string(), 1),
+                ("Transfer".to_string(), 1),
+                ("Transfer".to_string(), 2),
+            ]
+        );
+        assert_eq!(analyzer.diagnostics.len(), 1);
+        assert!(analyzer.diagnostics[0].message.contains("Missing"));
+
+        // the arguments are still evaluated
+        let count = ctx.latest_var_by_name(&analyzer, "count").unwrap();
+        let min = count
+            .evaled_range_min(&analyzer)
+            .unwrap()
+            .maybe_concrete()
+            .unwrap()
+            .val;
+        assert_eq!(min.uint_val().unwrap(), 1.into());
+    }
+
+    #[test]
+    fn return_ranges() {
+        let sol = r###"
+contract C {
+    function f(uint256 a) public returns (uint256, bool) {
+        if (a > 100) {
+            return (7, true);
+        }
+        return (20, false);
+    }
+
+    function g(uint256 a) public returns (uint256 out) {
+        require(a < 10);
+        out = a + 5;
+        return;
+    }
+
+    function h() public {
+        uint256 x = g(2);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ret_range = |func: &str, i: usize| {
+            let func = fn_ctx(&analyzer, entry, func).associated_fn(&analyzer);
+            let range = func.returns(&analyzer)[i].range(&analyzer).unwrap();
+            let bound = |elem: Elem<Concrete>| elem.maybe_concrete().unwrap().val;
+            (bound(range.range_min()), bound(range.range_max()))
+        };
+        // both branches union into the return range
+        assert_eq!(
+            ret_range("f(uint256)", 0),
+            (Concrete::Uint(8, 7.into()), Concrete::Uint(8, 20.into()))
+        );
+        assert_eq!(
+            ret_range("f(uint256)", 1),
+            (Concrete::Bool(false), Concrete::Bool(true))
+        );
+        // a naked return uses the named return variable
+        let (min, max) = ret_range("g(uint256)", 0);
+        assert_eq!(min.uint_val().unwrap(), 5.into());
+        assert_eq!(max.uint_val().unwrap(), 14.into());
+
+        let x = fn_ctx(&analyzer, entry, "h()")
+            .latest_var_by_name(&analyzer, "x")
+            .unwrap();
+        let x_max = x
+            .evaled_range_max(&analyzer)
+            .unwrap()
+            .maybe_concrete()
+            .unwrap();
+        assert_eq!(x_max.val.uint_val().unwrap(), 7.into());
+    }
+
+    #[test]
+    fn assembly_blocks() {
+        let sol = r###"
+contract C {
+    uint256 stored;
+
+    function f(uint256 a) public {
+        require(a < 10);
+        uint256 b = 5;
+        assembly {
+            a := add(a, 1)
+            let t := mul(b, 2)
+            sstore(stored.slot, t)
+        }
+        uint256 c = 3;
+    }
+}"###;
+        let max = |assembly_unknown: bool, name: &str| {
+            let mut analyzer = Analyzer {
+                assembly_unknown,
+                ..Default::default()
+            };
+            let entry = analyzer.parse_or_panic(sol, 0);
+            assert_eq!(analyzer.diagnostics.len(), 1);
+            let ctx = fn_ctx(&analyzer, entry, "f(uint256)");
+            let var = ctx.var_by_name_or_recurse(&analyzer, name).unwrap();
+            var.latest_version(&analyzer)
+                .evaled_range_max(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val
+                .uint_val()
+                .unwrap()
+        };
+        // assigned variables are widened, the rest of the function is still analyzed
+        assert_eq!(max(false, "a"), U256::MAX);
+        assert_eq!(max(false, "stored"), U256::MAX);
+        assert_eq!(max(false, "b"), 5.into());
+        assert_eq!(max(false, "c"), 3.into());
+        // unless every variable the block mentions is treated as unknown
+        assert_eq!(max(true, "b"), U256::MAX);
+    }
+
+    #[test]
+    fn delete_statements() {
+        let sol = r###"
+contract C {
+    uint256 count;
+
+    function f(int8 a, bool b) public {
+        count = 5;
+        delete count;
+        require(a < -3);
+        delete a;
+        delete b;
+        uint256[] memory xs = new uint256[](3);
+        delete xs;
+        uint256 len = xs.length;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(int8, bool)");
+        let range = |name: &str| {
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            let bound = |elem: Option<Elem<Concrete>>| elem.unwrap().maybe_concrete().unwrap().val;
+            (
+                bound(var.evaled_range_min(&analyzer)),
+                bound(var.evaled_range_max(&analyzer)),
+            )
+        };
+        let zero = Concrete::Uint(256, 0.into());
+        assert_eq!(range("count"), (zero.clone(), zero.clone()));
+        assert_eq!(
+            range("a"),
+            (Concrete::Int(8, 0.into()), Concrete::Int(8, 0.into()))
+        );
+        assert_eq!(range("b"), (Concrete::Bool(false), Concrete::Bool(false)));
+        assert_eq!(range("len"), (zero.clone(), zero.clone()));
+
+        let analysis = analyzer.bounds_for_var(
+            None,
+            &analyzer.file_mapping(),
+            ctx,
+            "count".to_string(),
+            ReportConfig::default(),
+            false,
+        );
+        let (_, bounds) = analysis.last().unwrap();
+        let (_, last) = bounds.bound_changes.last().unwrap();
+        assert_eq!(
+            last.evaled_range_max(&analyzer)
+                .maybe_concrete()
+                .unwrap()
+                .val,
+            zero
+        );
+    }
+
+    #[test]
+    fn comparison_results() {
+        let sol = r###"
+contract C {
+    function f(uint256 a, uint256 b) public {
+        require(a < 10);
+        require(b > 20);
+        bool lt = a < b;
+        bool gte = a >= b;
+        bool eq = a == b;
+        bool ne = a != b;
+        bool both = lt && ne;
+        bool maybe = a < 5;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(uint256, uint256)");
+        let range = |name: &str| {
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            assert!(matches!(
+                var.ty(&analyzer),
+                VarType::BuiltIn(bn, _) if *bn.underlying(&analyzer) == Builtin::Bool
+            ));
+            let bound = |elem: Option<Elem<Concrete>>| elem.unwrap().maybe_concrete().unwrap().val;
+            (
+                bound(var.evaled_range_min(&analyzer)),
+                bound(var.evaled_range_max(&analyzer)),
+            )
+        };
+        let (f, t) = (Concrete::Bool(false), Concrete::Bool(true));
+        assert_eq!(range("lt"), (t.clone(), t.clone()));
+        assert_eq!(range("gte"), (f.clone(), f.clone()));
+        assert_eq!(range("eq"), (f.clone(), f.clone()));
+        assert_eq!(range("ne"), (t.clone(), t.clone()));
+        assert_eq!(range("both"), (t.clone(), t.clone()));
+        assert_eq!(range("maybe"), (f, t));
+    }
+
+    #[test]
+    fn bitwise_ops() {
+        let sol = r###"
+contract C {
+    function f(uint256 x, uint8 y, int8 z) public {
+        uint256 masked = x & 0xff;
+        require(y < 16);
+        uint8 shifted = y << 2;
+        uint8 overflowed = y << 5;
+        uint8 down = y >> 2;
+        uint8 flipped = ~y;
+        int8 negated = ~z;
+        uint8 ored = y | 0x20;
+        uint8 xored = y ^ 3;
+        uint8 exact = 0x0f & 0x3c;
+        uint256 both = x | 1;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(uint256, uint8, int8)");
+        let range = |name: &str| {
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            let bound = |elem: Option<Elem<Concrete>>| elem.unwrap().maybe_concrete().unwrap().val;
+            (
+                bound(var.evaled_range_min(&analyzer)),
+                bound(var.evaled_range_max(&analyzer)),
+            )
+        };
+        let uint8 =
+            |min: u64, max: u64| (Concrete::Uint(8, min.into()), Concrete::Uint(8, max.into()));
+        assert_eq!(
+            range("masked"),
+            (
+                Concrete::Uint(256, 0.into()),
+                Concrete::Uint(256, 255.into())
+            )
+        );
+        assert_eq!(range("shifted"), uint8(0, 60));
+        assert_eq!(range("overflowed"), uint8(0, 255));
+        assert_eq!(range("down"), uint8(0, 3));
+        assert_eq!(range("flipped"), uint8(240, 255));
+        assert_eq!(
+            range("negated"),
+            (
+                Concrete::Int(8, I256::from(-128)),
+                Concrete::Int(8, I256::from(127))
+            )
+        );
+        assert_eq!(range("ored"), uint8(32, 63));
+        assert_eq!(range("xored"), uint8(0, 15));
+        assert_eq!(range("exact"), uint8(12, 12));
+        assert_eq!(
+            range("both"),
+            (
+                Concrete::Uint(256, 1.into()),
+                Concrete::Uint(256, U256::MAX)
+            )
+        );
+    }
+
+    #[test]
+    fn integer_casts() {
+        let sol = r###"
+contract C {
+    uint256[uint8(258)] small;
+
+    function f(uint256 a, uint256 b, int8 c, uint8 d) public {
+        require(a <= 1000);
+        require(b >= 300);
+        require(b <= 400);
+        require(c >= -5);
+        require(c <= 5);
+        uint8 wrapped = uint8(a);
+        uint8 shifted = uint8(b);
+        uint8 reinterpreted = uint8(c);
+        uint256 widened = uint256(d);
+        int16 signed = int16(int256(a));
+        uint8 exact = uint8(uint256(257));
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(uint256, uint256, int8, uint8)");
+        let range = |name: &str| {
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            let bound = |elem: Option<Elem<Concrete>>| elem.unwrap().maybe_concrete().unwrap().val;
+            (
+                bound(var.evaled_range_min(&analyzer)),
+                bound(var.evaled_range_max(&analyzer)),
+            )
+        };
+        let uint8 =
+            |min: u64, max: u64| (Concrete::Uint(8, min.into()), Concrete::Uint(8, max.into()));
+        assert_eq!(range("wrapped"), uint8(0, 255));
+        assert_eq!(range("shifted"), uint8(44, 144));
+        assert_eq!(range("reinterpreted"), uint8(0, 255));
+        assert_eq!(
+            range("widened"),
+            (
+                Concrete::Uint(256, 0.into()),
+                Concrete::Uint(256, 255.into())
+            )
+        );
+        assert_eq!(
+            range("signed"),
+            (Concrete::Int(16, 0.into()), Concrete::Int(16, 1000.into()))
+        );
+        assert_eq!(range("exact"), uint8(1, 1));
+
+        // conversions in array lengths are folded as well
+        let small = ContractNode::from(analyzer.user_types["C"])
+            .state_var(&analyzer, "small")
+            .unwrap();
+        let ty = VarType::try_from_idx(&analyzer, small.underlying(&analyzer).ty).unwrap();
+        assert_eq!(ty.as_string(&analyzer), "uint256[2]");
+    }
+
+    #[test]
+    fn range_intersect_union() {
+        let analyzer = Analyzer::default();
+        let uint = |min: u64, max: u64, excl: &[u64]| SolcRange {
+            min: Elem::from(Concrete::from(U256::from(min))),
+            max: Elem::from(Concrete::from(U256::from(max))),
+            exclusions: excl
+                .iter()
+                .map(|val| Elem::from(Concrete::from(U256::from(*val))))
+                .collect(),
+        };
+        let int = |min: i64, max: i64| SolcRange {
+            min: Elem::from(Concrete::from(I256::from(min))),
+            max: Elem::from(Concrete::from(I256::from(max))),
+            exclusions: vec![],
+        };
+
+        assert_eq!(
+            uint(0, 10, &[]).intersect(&uint(5, 20, &[]), &analyzer),
+            Some(uint(5, 10, &[]))
+        );
+        assert_eq!(uint(0, 4, &[]).intersect(&uint(5, 9, &[]), &analyzer), None);
+        assert_eq!(
+            int(-5, 5).intersect(&int(-10, 0), &analyzer),
+            Some(int(-5, 0))
+        );
+        // the only value left is excluded
+        assert_eq!(
+            uint(0, 10, &[0]).intersect(&uint(0, 0, &[]), &analyzer),
+            None
+        );
+        assert_eq!(
+            uint(0, 10, &[0]).intersect(&uint(0, 5, &[]), &analyzer),
+            Some(uint(0, 5, &[0]))
+        );
+
+        assert_eq!(
+            uint(0, 4, &[]).union(&uint(10, 20, &[]), &analyzer),
+            uint(0, 20, &[])
+        );
+        assert_eq!(int(-5, 5).union(&int(-10, 0), &analyzer), int(-10, 5));
+        // zero stays excluded as the other range can't be zero either
+        assert_eq!(
+            uint(0, 10, &[0]).union(&uint(5, 6, &[]), &analyzer),
+            uint(0, 10, &[0])
+        );
+        assert_eq!(
+            uint(0, 10, &[0]).union(&uint(0, 3, &[]), &analyzer),
+            uint(0, 10, &[])
+        );
+    }
+
+    #[test]
+    fn length_members() {
+        let sol = r###"
+contract C {
+    uint256[] list;
+    uint256[4] fixedList;
+
+    function f(uint256[] memory xs, bytes memory b, string memory s, bytes32 word) public {
+        uint256 a = xs.length;
+        uint256 c = b.length;
+        uint256 d = bytes(s).length;
+        uint256 e = fixedList.length;
+        uint256 g = word.length;
+        uint256 h = list.length;
+        bytes memory lit = hex"0102";
+        uint256 k = lit.length;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(uint256[], bytes, string, bytes32)");
+        let range = |name: &str| {
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            assert_eq!(var.ty(&analyzer).as_string(&analyzer), "uint256");
+            let bound = |elem: Option<Elem<Concrete>>| elem.unwrap().maybe_concrete().unwrap().val;
+            (
+                bound(var.evaled_range_min(&analyzer)),
+                bound(var.evaled_range_max(&analyzer)),
+            )
+        };
+        let uint = |min: U256, max: U256| (Concrete::Uint(256, min), Concrete::Uint(256, max));
+        let any = uint(U256::zero(), U256::MAX);
+        assert_eq!(range("a"), any);
+        assert_eq!(range("c"), any);
+        assert_eq!(range("d"), any);
+        assert_eq!(range("e"), uint(4.into(), 4.into()));
+        assert_eq!(range("g"), uint(32.into(), 32.into()));
+        assert_eq!(range("h"), any);
+        assert_eq!(range("k"), uint(2.into(), 2.into()));
+    }
+
+    #[test]
+    fn payable_casts() {
+        let sol = r###"
+contract C {
+    address payable owner;
+
+    function f(address addr) public {
+        address payable p = payable(addr);
+        p.transfer(1);
+        bool ok = payable(msg.sender).send(2);
+        address back = address(p);
+        owner = payable(address(this));
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(address)");
+        let ty = |name: &str| {
+            ctx.latest_var_by_name(&analyzer, name)
+                .unwrap()
+                .ty(&analyzer)
+                .as_string(&analyzer)
+        };
+        assert_eq!(ty("p"), "address payable");
+        assert_eq!(ty("ok"), "bool");
+        assert_eq!(ty("back"), "address");
+        assert_eq!(ty("owner"), "address payable");
+    }
+
+    #[test]
+    fn contract_address_conversions() {
+        let sol = r###"
+contract IERC20 {}
+
+contract C {
+    IERC20 myToken;
+
+    function f(address addr) public {
+        address a = address(myToken);
+        IERC20 t = IERC20(addr);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(address)");
+        let ierc20 = ContractNode::from(analyzer.user_types["IERC20"]);
+
+        let a = ctx.latest_var_by_name(&analyzer, "a").unwrap();
+        assert!(matches!(
+            a.ty(&analyzer),
+            VarType::BuiltIn(bn, _) if *bn.underlying(&analyzer) == Builtin::Address
+        ));
+        let t = ctx.latest_var_by_name(&analyzer, "t").unwrap();
+        assert_eq!(
+            *t.ty(&analyzer),
+            VarType::User(TypeNode::Contract(ierc20), None)
+        );
+
+        // the conversions themselves remember what they were converted from
+        let casts: Vec<_> = analyzer
+            .graph()
+            .node_indices()
+            .filter(|idx| matches!(analyzer.node(*idx), Node::ContextVar(_)))
+            .map(ContextVarNode::from)
+            .filter_map(|cvar| {
+                let tmp = cvar.tmp_of(&analyzer)?;
+                (tmp.op == RangeOp::Cast).then(|| (cvar.ty(&analyzer).clone(), tmp.lhs))
+            })
+            .collect();
+        assert!(casts.iter().any(|(ty, from)| {
+            matches!(ty, VarType::BuiltIn(bn, _) if *bn.underlying(&analyzer) == Builtin::Address)
+                && *from.ty(&analyzer) == VarType::User(TypeNode::Contract(ierc20), None)
+        }));
+        assert!(casts.iter().any(|(ty, from)| {
+            *ty == VarType::User(TypeNode::Contract(ierc20), None)
+                && from.display_name(&analyzer) == "addr"
+        }));
+    }
+
+    #[test]
+    fn min_reverting_input() {
+        let sol = r###"
+pragma solidity ^0.8.0;
+contract C {
+    function f(uint8 x) public returns (uint8) {
+        return x + 1;
+    }
+
+    function g(uint8 x) public returns (uint8) {
+        x += 1;
+        return x;
+    }
+
+    function h(uint8 x) public returns (uint8) {
+        x = 0;
+        return x + 1;
+    }
+
+    function i(uint8 x) public returns (uint8) {
+        require(x < 100);
+        x = 200;
+        return x;
+    }
+
+    function j(uint8 x) public returns (uint8) {
+        if (x < 10) {
+            x = 0;
+        } else {
+            x += 1;
+        }
+        return x;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let min_reverting = |name: &str| {
+            let func = fn_ctx(&analyzer, entry, name).associated_fn(&analyzer);
+            analyzer.min_reverting_input(func, func.params(&analyzer)[0])
+        };
+        assert_eq!(
+            min_reverting("f(uint8)"),
+            Some(Concrete::Uint(8, 255.into()))
+        );
+        // the assigned value says nothing about which inputs are accepted
+        assert_eq!(
+            min_reverting("g(uint8)"),
+            Some(Concrete::Uint(8, 255.into()))
+        );
+        assert_eq!(min_reverting("h(uint8)"), None);
+        assert_eq!(
+            min_reverting("i(uint8)"),
+            Some(Concrete::Uint(8, 100.into()))
+        );
+    }
+
+    #[test]
+    fn using_for() {
+        let sol = r###"
+using Wild for uint8;
+
+contract C {
+    using SafeMath for uint256;
+    using Wild for *;
+
+    function f(uint256 x) public {
+        x.add(1);
+        x.twice();
+    }
+}
+
+contract D {
+    function g(uint8 y) public {
+        y.twice();
+    }
+}
+
+library SafeMath {
+    function add(uint256 a, uint256 b) internal pure returns (uint256) {
+        return a + b;
+    }
+}
+
+library Wild {
+    function twice(uint256 a) internal pure returns (uint256) {
+        return a * 2;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let uint256 = analyzer.builtin_or_add(Builtin::Uint(256));
+        let add = FunctionNode::from(analyzer.user_types["add(uint256, uint256)"]);
+        let twice = FunctionNode::from(analyzer.user_types["twice(uint256)"]);
+        let c = analyzer.user_types["C"];
+        assert_eq!(analyzer.using[&(c, uint256)], vec![add]);
+        assert_eq!(analyzer.using_any[&c], vec![twice]);
+
+        let ctx = fn_ctx(&analyzer, entry, "f(uint256)");
+        assert_eq!(
+            ctx.associated_fn(&analyzer).callees(&analyzer),
+            vec![add, twice]
+        );
+
+        // `using` in a contract stays in it, one at file level applies to the whole file
+        let g = fn_ctx(&analyzer, entry, "g(uint8)").associated_fn(&analyzer);
+        assert_eq!(g.callees(&analyzer), vec![twice]);
+        assert!(analyzer.using_fns(g, uint256).is_empty());
+    }
+
+    #[test]
+    fn concrete_as_string() {
+        let addr: ethers_core::types::Address = "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            Concrete::Address(addr).as_string(),
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+        );
+        assert_eq!(Concrete::Bool(false).as_string(), "false");
+        assert_eq!(Concrete::DynBytes(vec![]).as_string(), "0x");
+        assert_eq!(Concrete::DynBytes(vec![0xde, 0xad]).as_string(), "0xdead");
+        assert_eq!(
+            Concrete::Array(vec![
+                Concrete::Uint(8, 1.into()),
+                Concrete::Array(vec![Concrete::Bool(true)]),
+            ])
+            .as_string(),
+            "[1, [true]]"
+        );
+    }
+
+    #[test]
+    fn signed_range_strings() {
+        let sol = r###"
+contract C {
+    function f(int256 x, int8 z) public {
+        require(x >= -5);
+        require(x <= 5);
+        int256 y = x * 2;
+        require(z < -3);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let file_mapping = analyzer.file_mapping();
+        let ctx = fn_ctx(&analyzer, entry, "f(int256, int8)");
+        let last_bounds = |name: &str| {
+            let analysis = analyzer.bounds_for_var(
+                None,
+                &file_mapping,
+                ctx,
+                name.to_string(),
+                ReportConfig::default(),
+                false,
+            );
+            let (_, bounds) = analysis.last().unwrap();
+            let (_, range) = bounds.bound_changes.last().unwrap();
+            bounds.range_strs(range, &analyzer)
+        };
+        assert_eq!(last_bounds("x"), ("-5".to_string(), "5".to_string()));
+        assert_eq!(last_bounds("y"), ("-10".to_string(), "10".to_string()));
+        assert_eq!(last_bounds("z"), ("-128".to_string(), "-4".to_string()));
+
+        // large negative values are written relative to a power of two
+        assert_eq!(Concrete::Int(256, I256::MIN).as_human_string(), "-2**255");
+        assert_eq!(
+            Concrete::Int(256, I256::MIN + I256::one()).as_human_string(),
+            "-(2**255 - 1)"
+        );
+        assert_eq!(Concrete::Int(256, I256::from(-7)).as_human_string(), "-7");
+    }
+
+    #[test]
+    fn fixed_bytes_and_concat() {
+        let sol = r###"
+contract C {
+    function f() public {
+        bytes4 a = bytes4(0xdeadbeef);
+        bytes4 b = 0xdeadbeef;
+        bytes32 c = "abc";
+        bytes4 d = bytes4("abcd");
+        uint32 e = uint32(a);
+        bytes memory packed = abi.encodePacked(a, uint16(258), true);
+        bytes memory joined = bytes.concat(hex"01", hex"0203");
+        uint256 len = joined.length;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f()");
+        let value = |name: &str| {
+            ctx.latest_var_by_name(&analyzer, name)
+                .unwrap()
+                .evaled_range_min(&analyzer)
+                .unwrap()
+        };
+        let fixed = |size: u8, prefix: &[u8]| {
+            let mut h = ethers_core::types::H256::default();
+            h.0[..prefix.len()].copy_from_slice(prefix);
+            Concrete::Bytes(size, h)
+        };
+
+        // hex and string literals are left aligned in a `bytesN`
+        let selector = fixed(4, &[0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(value("a").maybe_concrete().unwrap().val, selector);
+        assert_eq!(value("b").maybe_concrete().unwrap().val, selector);
+        assert_eq!(value("c").maybe_concrete().unwrap().val, fixed(32, b"abc"));
+        assert_eq!(value("d").maybe_concrete().unwrap().val, fixed(4, b"abcd"));
+        assert_eq!(
+            value("e").maybe_concrete().unwrap().val,
+            Concrete::Uint(32, U256::from(0xdeadbeefu32))
+        );
+
+        // constant parts pack into constant bytes
+        let packed = ctx.latest_var_by_name(&analyzer, "packed").unwrap();
+        let bytes = |var: ContextVarNode| {
+            let rd = var
+                .evaled_range_min(&analyzer)
+                .unwrap()
+                .maybe_range_dyn()
+                .unwrap();
+            (0..rd.val.len())
+                .map(|i| {
+                    let idx = Elem::from(Concrete::from(U256::from(i)));
+                    match rd.val[&idx].maybe_concrete().unwrap().val {
+                        Concrete::Bytes(1, b) => b.0[0],
+                        e => panic!("not a byte: {e:?}"),
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            bytes(packed),
+            vec![0xde, 0xad, 0xbe, 0xef, 0x01, 0x02, 0x01]
+        );
+        let joined = ctx.latest_var_by_name(&analyzer, "joined").unwrap();
+        assert_eq!(bytes(joined), vec![0x01, 0x02, 0x03]);
+        assert_eq!(
+            value("len").maybe_concrete().unwrap().val,
+            Concrete::Uint(256, U256::from(3))
+        );
+    }
+
+    #[test]
+    fn context_navigation() {
+        let sol = r###"
+contract C {
+    function g(uint256 x) internal returns (uint256) {
+        return x + 1;
+    }
+
+    function f(uint256 x) public returns (uint256) {
+        uint256 y = x > 10 ? 1 : 2;
+        if (x > 5) {
+            y = g(y);
+        }
+        return y;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(uint256)");
+        assert_eq!(ctx.parent(&analyzer), None);
+        assert!(ctx.parent_chain(&analyzer).is_empty());
+
+        let subctxs = ctx.subcontexts(&analyzer);
+        assert!(subctxs
+            .iter()
+            .any(|subctx| subctx.underlying(&analyzer).fn_call.is_some()));
+        subctxs.iter().for_each(|subctx| {
+            let parent = subctx.parent(&analyzer).unwrap();
+            assert_eq!(Some(parent), subctx.underlying(&analyzer).parent_ctx);
+            assert!(parent == ctx || subctxs.contains(&parent));
+            let chain = subctx.parent_chain(&analyzer);
+            assert_eq!(chain[0], parent);
+            assert_eq!(chain.last(), Some(&ctx));
+            assert!(parent.subcontexts(&analyzer).contains(subctx));
+        });
+    }
+
+    #[test]
+    fn abi_builtins() {
+        let sol = r###"
+contract C {
+    struct Point { uint256 x; uint256 y; }
+
+    function f(bytes memory data) public {
+        (uint256 a, address b) = abi.decode(data, (uint256, address));
+        uint8 c = abi.decode(data, (uint8));
+        Point memory p = abi.decode(data, (Point));
+        bytes memory encoded = abi.encode(a, b);
+        bytes memory packed = abi.encodePacked(a, c);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(bytes)");
+        let ty = |name: &str| {
+            ctx.latest_var_by_name(&analyzer, name)
+                .unwrap()
+                .ty(&analyzer)
+                .as_string(&analyzer)
+        };
+        assert_eq!(ty("a"), "uint256");
+        assert_eq!(ty("b"), "address");
+        assert_eq!(ty("c"), "uint8");
+        assert_eq!(ty("p"), "struct Point");
+        assert_eq!(ty("encoded"), "bytes");
+        assert_eq!(ty("packed"), "bytes");
+
+        // decoded values can be anything their type allows
+        // a declared local stays symbolic, its range pins the value down
+        let c = ctx.latest_var_by_name(&analyzer, "c").unwrap();
+        assert_eq!(
+            c.evaled_range_max(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val,
+            Concrete::Uint(8, U256::from(255))
+        );
+    }
+
+    #[test]
+    fn for_loop_step() {
+        let sol = r###"
+contract C {
+    function f() public {
+        for (uint256 i; i < 10; i += 2) {}
+    }
+
+    function g() public {
+        for (uint256 j = 1; j <= 10; j = j + 3) {}
+    }
+
+    function h() public {
+        for (uint256 k; k < 2_500; k += 1e3) {}
+    }
+
+    function near_max() public {
+        for (uint256 a; a < type(uint256).max; a += 2) {}
+    }
+
+    function body_writes() public {
+        for (uint256 b; b < 10; b++) {
+            b += 5;
+        }
+    }
+
+    function unknown_start(uint256 s) public {
+        for (uint256 c = s; c < 10; c++) {}
+    }
+
+    function overflows() public {
+        for (uint8 d; d < 255; d += 2) {}
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let bounds = |fn_name: &str, var: &str| {
+            let ctx = fn_ctx(&analyzer, entry, fn_name);
+            let var = ctx.latest_var_by_name(&analyzer, var).unwrap();
+            (
+                var.evaled_range_min(&analyzer)
+                    .unwrap()
+                    .maybe_concrete()
+                    .unwrap()
+                    .val,
+                var.evaled_range_max(&analyzer)
+                    .unwrap()
+                    .maybe_concrete()
+                    .unwrap()
+                    .val,
+            )
+        };
+        assert_eq!(
+            bounds("f()", "i"),
+            (
+                Concrete::Uint(256, 0.into()),
+                Concrete::Uint(256, 10.into())
+            )
+        );
+        // 1, 4, 7, 10 pass the limiter, 13 is the first that doesn't
+        assert_eq!(
+            bounds("g()", "j"),
+            (
+                Concrete::Uint(256, 1.into()),
+                Concrete::Uint(256, 13.into())
+            )
+        );
+        assert_eq!(
+            bounds("h()", "k"),
+            (
+                Concrete::Uint(256, 0.into()),
+                Concrete::Uint(256, 3000.into())
+            )
+        );
+        // without an exact bound the loop variables are widened to their type range
+        let full = (
+            Concrete::Uint(256, 0.into()),
+            Concrete::Uint(256, U256::MAX),
+        );
+        assert_eq!(bounds("near_max()", "a"), full);
+        assert_eq!(bounds("body_writes()", "b"), full);
+        assert_eq!(bounds("unknown_start(uint256)", "c"), full);
+        assert_eq!(
+            bounds("overflows()", "d"),
+            (Concrete::Uint(8, 0.into()), Concrete::Uint(8, 255.into()))
+        );
+    }
+
+    #[test]
+    fn reset_contexts() {
+        let sol = r###"
+contract C {
+    uint256 stored;
+
+    function f(uint256 x) public {
+        require(x < 10);
+        stored = x + 1;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let bounds = |analyzer: &Analyzer, entry: NodeIdx| {
+            let ctx = fn_ctx(analyzer, entry, "f(uint256)");
+            ["x", "stored"]
+                .iter()
+                .map(|name| {
+                    let var = ctx.latest_var_by_name(analyzer, name).unwrap();
+                    (
+                        var.evaled_range_min(analyzer)
+                            .unwrap()
+                            .maybe_concrete()
+                            .unwrap()
+                            .val,
+                        var.evaled_range_max(analyzer)
+                            .unwrap()
+                            .maybe_concrete()
+                            .unwrap()
+                            .val,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let before = bounds(&analyzer, entry);
+
+        let entry = analyzer.node(entry).clone();
+        analyzer.reset_contexts();
+        assert!(!analyzer.graph().node_weights().any(|node| matches!(
+            node,
+            Node::Context(_) | Node::ContextVar(_) | Node::ContextFork | Node::FunctionCall
+        )));
+        assert!(matches!(
+            analyzer.node(analyzer.user_types["C"]),
+            Node::Contract(_)
+        ));
+        assert!(matches!(
+            analyzer.node(analyzer.user_types["f(uint256)"]),
+            Node::Function(_)
+        ));
+        let entry = analyzer
+            .graph()
+            .node_indices()
+            .find(|idx| *analyzer.node(*idx) == entry)
+            .unwrap();
+
+        analyzer.reanalyze();
+        assert_eq!(bounds(&analyzer, entry), before);
+    }
+
+    #[test]
+    fn constant_initializers_survive_reset() {
+        let sol = r###"
+contract C {
+    uint256 constant FEE = 30;
+    uint256 constant DOUBLE_FEE = FEE * 2;
+    uint8 constant SMALL = uint8(300);
+    uint256 immutable LATER = 1 + block.number;
+
+    function f() public {
+        uint256 b = DOUBLE_FEE;
+        uint8 s = SMALL;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        analyzer.parse_or_panic(sol, 0);
+        analyzer.clear_tmps();
+        analyzer.reset_contexts();
+        analyzer.reanalyze();
+
+        let entry = analyzer
+            .graph()
+            .node_indices()
+            .find(|idx| matches!(analyzer.node(*idx), Node::SourceUnit(_)))
+            .unwrap();
+        let ctx = fn_ctx(&analyzer, entry, "f()");
+        let range = |name: &str| {
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            (
+                var.evaled_range_min(&analyzer)
+                    .unwrap()
+                    .maybe_concrete()
+                    .unwrap()
+                    .val,
+                var.evaled_range_max(&analyzer)
+                    .unwrap()
+                    .maybe_concrete()
+                    .unwrap()
+                    .val,
+            )
+        };
+        let uint = |size: u16, val: u64| Concrete::Uint(size, U256::from(val));
+        assert_eq!(range("b"), (uint(256, 60), uint(256, 60)));
+        assert_eq!(range("s"), (uint(8, 44), uint(8, 44)));
+        assert!(analyzer.diagnostics.iter().any(|diagnostic| diagnostic
+            .message
+            .starts_with("\"+\" on a non-constant outside of a function")));
+    }
+
+    #[test]
+    fn clear_tmps() {
+        let sol = r###"
+contract C {
+    uint256 stored;
+
+    function f(uint256 x) public returns (uint256) {
+        require(x < 10);
+        uint256 y = x * 3 + 1;
+        stored = y - 1;
+        return y + x;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let bounds = |analyzer: &Analyzer, entry: NodeIdx| {
+            let ctx = fn_ctx(analyzer, entry, "f(uint256)");
+            let returned = ctx
+                .underlying(analyzer)
+                .ret
+                .iter()
+                .map(|(_, ret)| *ret)
+                .collect::<Vec<_>>();
+            ["x", "y", "stored"]
+                .iter()
+                .map(|name| ctx.latest_var_by_name(analyzer, name).unwrap())
+                .chain(returned)
+                .map(|var| {
+                    (
+                        var.evaled_range_min(analyzer)
+                            .unwrap()
+                            .maybe_concrete()
+                            .unwrap()
+                            .val,
+                        var.evaled_range_max(analyzer)
+                            .unwrap()
+                            .maybe_concrete()
+                            .unwrap()
+                            .val,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let before = bounds(&analyzer, entry);
+        let nodes = analyzer.graph().node_count();
+
+        let entry = analyzer.node(entry).clone();
+        let removed = analyzer.clear_tmps();
+        assert!(removed > 0);
+        assert_eq!(analyzer.graph().node_count(), nodes - removed);
+        let entry = analyzer
+            .graph()
+            .node_indices()
+            .find(|idx| *analyzer.node(*idx) == entry)
+            .unwrap();
+        assert_eq!(bounds(&analyzer, entry), before);
+        // whatever is left is still referenced
+        assert_eq!(analyzer.clear_tmps(), 0);
+    }
+
+    #[test]
+    fn concrete_accessors() {
+        let addr = ethers_core::types::Address::repeat_byte(0x11);
+        let bytes = ethers_core::types::H256::repeat_byte(0x22);
+        assert_eq!(Concrete::Bool(true).bool_val(), Some(true));
+        assert_eq!(Concrete::Address(addr).address_val(), Some(addr));
+        assert_eq!(Concrete::Bytes(32, bytes).bytes_val(), Some(bytes));
+        assert_eq!(
+            Concrete::String("hi".to_string()).string_val(),
+            Some("hi".to_string())
+        );
+
+        let uint = Concrete::Uint(256, 1.into());
+        assert_eq!(uint.bool_val(), None);
+        assert_eq!(uint.address_val(), None);
+        assert_eq!(uint.bytes_val(), None);
+        assert_eq!(uint.string_val(), None);
+    }
+
+    #[test]
+    fn concrete_arithmetic() {
+        let uint8 = |val: u64| Concrete::Uint(8, val.into());
+        let int8 = |val: i64| Concrete::Int(8, I256::from(val));
+        assert_eq!(uint8(254).checked_add(&uint8(1)), Some(uint8(255)));
+        assert_eq!(uint8(255).checked_add(&uint8(1)), None);
+        assert_eq!(uint8(255).wrapping_add(&uint8(1)), Some(uint8(0)));
+        assert_eq!(uint8(0).checked_sub(&uint8(1)), None);
+        assert_eq!(uint8(0).wrapping_sub(&uint8(1)), Some(uint8(255)));
+        assert_eq!(uint8(16).wrapping_mul(&uint8(17)), Some(uint8(16)));
+        assert_eq!(uint8(7).checked_div(&uint8(2)), Some(uint8(3)));
+        assert_eq!(uint8(7).checked_div(&uint8(0)), None);
+        assert_eq!(uint8(7).wrapping_div(&uint8(0)), None);
+        // the wider type decides the width
+        assert_eq!(
+            uint8(255).checked_add(&Concrete::Uint(16, 1.into())),
+            Some(Concrete::Uint(16, 256.into()))
+        );
+
+        assert_eq!(int8(127).checked_add(&int8(1)), None);
+        assert_eq!(int8(127).wrapping_add(&int8(1)), Some(int8(-128)));
+        assert_eq!(int8(-128).checked_sub(&int8(1)), None);
+        assert_eq!(int8(-128).wrapping_sub(&int8(1)), Some(int8(127)));
+        assert_eq!(int8(-8).checked_mul(&int8(16)), Some(int8(-128)));
+        assert_eq!(int8(-128).checked_div(&int8(-1)), None);
+        assert_eq!(int8(-128).wrapping_div(&int8(-1)), Some(int8(-128)));
+        assert_eq!(
+            Concrete::Int(256, I256::MAX).wrapping_add(&Concrete::Int(256, I256::one())),
+            Some(Concrete::Int(256, I256::MIN))
+        );
+
+        assert_eq!(uint8(1).checked_add(&int8(1)), None);
+        assert_eq!(uint8(1).wrapping_add(&Concrete::Bool(true)), None);
+    }
+
+    #[test]
+    fn number_literal_overflow() {
+        assert_eq!(
+            context::exprs::number_literal_val("5", "18"),
+            U256::exp10(18) * 5
+        );
+        assert_eq!(
+            context::exprs::number_literal_val("1_000", ""),
+            U256::from(1000)
+        );
+        assert_eq!(context::exprs::number_literal_val("1", "100"), U256::MAX);
+        assert_eq!(context::exprs::number_literal_val("0", "300"), U256::zero());
+        assert_eq!(
+            context::exprs::number_literal_val(&"9".repeat(100), ""),
+            U256::MAX
+        );
+
+        let sol = r###"
+contract C {
+    uint256[2e300] arr;
+
+    function f() public {
+        uint256 x = 1e100;
+        uint256 y = 1_000e1_8;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f()");
+        let x = ctx.latest_var_by_name(&analyzer, "x").unwrap();
+        assert_eq!(
+            x.evaled_range_max(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val,
+            Concrete::Uint(256, U256::MAX)
+        );
+        let y = ctx.latest_var_by_name(&analyzer, "y").unwrap();
+        assert_eq!(
+            y.evaled_range_max(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val,
+            Concrete::Uint(256, U256::exp10(21))
+        );
+    }
+
+    #[test]
+    fn rational_literals() {
+        let sol = r###"
+contract C {
+    function f() public {
+        uint256 x = 2.5 ether;
+        uint256 y = 1 days;
+        uint256 z = 1.5;
+        uint256 g = 3 gwei;
+        uint256 w = 2 weeks;
+        uint256 huge = 1e60 ether;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f()");
+        let val = |name: &str| {
+            ctx.latest_var_by_name(&analyzer, name)
+                .unwrap()
+                .evaled_range_max(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val
+        };
+        assert_eq!(
+            val("x"),
+            Concrete::Uint(256, U256::from(2500000000000000000u64))
+        );
+        assert_eq!(val("y"), Concrete::Uint(256, U256::from(86400)));
+        assert_eq!(val("z"), Concrete::Uint(256, U256::from(1)));
+        assert_eq!(val("g"), Concrete::Uint(256, U256::from(3_000_000_000u64)));
+        assert_eq!(val("w"), Concrete::Uint(256, U256::from(1_209_600)));
+        assert_eq!(val("huge"), Concrete::Uint(256, U256::MAX));
+        assert!(analyzer
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.message.contains("not a whole number")));
+        assert!(analyzer
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.message.contains("does not fit in a uint256")));
+    }
+
+    #[test]
+    fn hex_number_literals() {
+        assert_eq!(
+            context::exprs::hex_num_literal_val("0xff_ff"),
+            U256::from(0xffff)
+        );
+        assert_eq!(
+            context::exprs::hex_num_literal_val(&format!("0x1{}", "0".repeat(64))),
+            U256::MAX
+        );
+
+        let sol = r###"
+contract C {
+    uint256[0x10] arr;
+}"###;
+        let mut analyzer = Analyzer::default();
+        analyzer.parse_or_panic(sol, 0);
+        assert!(analyzer.graph.node_weights().any(|node| matches!(
+            node,
+            Node::Concrete(Concrete::Uint(256, val)) if *val == U256::from(16)
+        )));
+    }
+
+    #[test]
+    fn invariant_violations() {
+        let sol = r###"
+pragma solidity ^0.8.0;
+contract Token {
+    uint256 total;
+    uint256 balance;
+
+    constructor() {
+        balance = 100;
+    }
+
+    function mint(uint256 amount) public {
+        balance += amount;
+        total = total + amount;
+    }
+
+    function burn(uint256 amount) public {
+        balance -= amount;
+        total -= amount;
+    }
+
+    function leak(uint256 amount) public {
+        balance += amount;
+    }
+
+    function donate() public {
+        total += 1;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        analyzer.parse_or_panic(sol, 0);
+        let token = ContractNode::from(analyzer.user_types["Token"]);
+        let var = |name: &str| {
+            Box::new(Expression::Variable(solang_parser::pt::Identifier {
+                loc: Loc::Implicit,
+                name: name.to_string(),
+            }))
+        };
+
+        let violations = |invariant: Expression| {
+            analyzer
+                .invariant_violations(token, &invariant)
+                .unwrap()
+                .iter()
+                .map(|func| func.name(&analyzer))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            violations(Expression::Equal(
+                Loc::Implicit,
+                var("total"),
+                var("balance")
+            )),
+            vec!["leak(uint256)", "donate()"]
+        );
+        assert_eq!(
+            violations(Expression::MoreEqual(
+                Loc::Implicit,
+                var("total"),
+                var("balance")
+            )),
+            vec!["leak(uint256)"]
+        );
+        assert!(analyzer
+            .invariant_violations(token, &Expression::Not(Loc::Implicit, var("total")))
+            .is_none());
+    }
+
+    #[test]
+    fn string_and_bool_constants() {
+        let sol = r###"
+contract C {
+    string constant NAME = "Tok" "en";
+    bool constant PAUSED = true;
+}"###;
+        let mut analyzer = Analyzer::default();
+        analyzer.parse_or_panic(sol, 0);
+        assert!(analyzer.graph.node_weights().any(|node| matches!(
+            node,
+            Node::Concrete(Concrete::String(s)) if s == "Token"
+        )));
+        assert!(analyzer
+            .graph
+            .node_weights()
+            .any(|node| matches!(node, Node::Concrete(Concrete::Bool(true)))));
+    }
+
+    #[test]
+    fn top_level_arithmetic() {
+        let sol = r###"
+contract C {
+    uint256 constant X = 2 * 3 + 1;
+    uint256 constant Y = 10 - 9 % 4;
+    uint256 constant Z = 1 / 0;
+}"###;
+        let mut analyzer = Analyzer::default();
+        analyzer.parse_or_panic(sol, 0);
+        let init = |name: &str| {
+            analyzer
+                .graph
+                .node_weights()
+                .find_map(|node| match node {
+                    Node::Var(var) if var.name.as_ref().unwrap().name == name => var.initializer,
+                    _ => None,
+                })
+                .map(ContextVarNode::from)
+                .unwrap()
+        };
+
+        let x = init("X");
+        assert!(x.tmp_of(&analyzer).is_some());
+        assert_eq!(
+            x.evaled_range_max(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val,
+            Concrete::Uint(256, U256::from(7))
+        );
+        assert_eq!(
+            init("Y")
+                .evaled_range_min(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val,
+            Concrete::Uint(256, U256::from(7))
+        );
+        assert!(init("Z").tmp_of(&analyzer).is_some());
+        assert!(analyzer
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.message.contains("by a value that may be zero")));
+    }
+
+    #[test]
+    fn fixed_state_vars() {
+        let sol = r###"
+contract C {
+    uint256 constant FEE = 30;
+    uint256 constant DOUBLE_FEE = FEE * 2;
+    bool constant PAUSED = true;
+    uint256 immutable CAP = 1000;
+    uint256 immutable START;
+
+    constructor(uint256 start) {
+        START = start;
+    }
+
+    function f() public {
+        uint256 a = FEE;
+        uint256 b = DOUBLE_FEE;
+        bool c = PAUSED;
+        uint256 d = CAP;
+        uint256 e = START;
+    }
+
+    function g() public {
+        START = 3;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f()");
+        let range = |name: &str| {
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            (
+                var.evaled_range_min(&analyzer)
+                    .unwrap()
+                    .maybe_concrete()
+                    .unwrap()
+                    .val,
+                var.evaled_range_max(&analyzer)
+                    .unwrap()
+                    .maybe_concrete()
+                    .unwrap()
+                    .val,
+            )
+        };
+        let uint = |val: u64| Concrete::Uint(256, U256::from(val));
+        assert_eq!(range("a"), (uint(30), uint(30)));
+        assert_eq!(range("b"), (uint(60), uint(60)));
+        assert_eq!(range("c"), (Concrete::Bool(true), Concrete::Bool(true)));
+        assert_eq!(range("d"), (uint(1000), uint(1000)));
+        // set by the constructor, so it is not assumed to be zero like other storage
+        assert_eq!(range("e"), (uint(0), Concrete::Uint(256, U256::MAX)));
+
+        let immutable_writes = analyzer
+            .diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.message.contains("Immutable variable \"START\""))
+            .map(|diagnostic| &sol[diagnostic.loc.start()..diagnostic.loc.end()])
+            .collect::<Vec<_>>();
+        assert_eq!(immutable_writes, vec!["START = 3"]);
+    }
+
+    #[test]
+    fn struct_fields() {
+        let sol = r###"
+contract C {
+    struct Inner { uint8 c; }
+    struct Outer { uint256 x; Inner b; }
+    Outer s;
+
+    function f(Outer memory a) public {
+        uint8 v = a.b.c;
+        a.x = 5;
+        uint256 w = a.x + 1;
+        s.b.c = 7;
+        uint256 y = s.b.c * 2;
+        Outer memory o = Outer(4, Inner(2));
+        uint256 z = o.x + o.b.c;
+        a = o;
+        uint256 u = a.x;
+        uint256 t = a.b.c;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(struct Outer)");
+        let range = |name: &str| {
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            (
+                var.evaled_range_min(&analyzer)
+                    .unwrap()
+                    .maybe_concrete()
+                    .unwrap()
+                    .val,
+                var.evaled_range_max(&analyzer)
+                    .unwrap()
+                    .maybe_concrete()
+                    .unwrap()
+                    .val,
+            )
+        };
+        let uint = |val: u64| Concrete::Uint(256, U256::from(val));
+        // an untouched field has the full range of its type
+        assert_eq!(
+            range("v"),
+            (Concrete::Uint(8, 0.into()), Concrete::Uint(8, 255.into()))
+        );
+        assert_eq!(range("w"), (uint(6), uint(6)));
+        assert_eq!(range("y"), (uint(14), uint(14)));
+        // constructed fields, in declaration order
+        assert_eq!(range("z"), (uint(6), uint(6)));
+        // assigning the whole struct carries its fields over
+        assert_eq!(range("u"), (uint(4), uint(4)));
+        assert_eq!(range("t"), (uint(2), uint(2)));
+    }
+
+    #[test]
+    fn named_arguments() {
+        let sol = r###"
+contract C {
+    struct P { uint256 x; uint256 y; }
+
+    function sub(uint256 a, uint256 b) internal returns (uint256) {
+        return a - b;
+    }
+
+    function f() public {
+        uint256 d = sub({b: 2, a: 5});
+        P memory p = P({y: 1, x: 7});
+        uint256 e = p.x - p.y;
+    }
+
+    function g() public {
+        uint256 d = sub({a: 5, c: 2});
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f()");
+        let range = |name: &str| {
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            (
+                var.evaled_range_min(&analyzer)
+                    .unwrap()
+                    .maybe_concrete()
+                    .unwrap()
+                    .val,
+                var.evaled_range_max(&analyzer)
+                    .unwrap()
+                    .maybe_concrete()
+                    .unwrap()
+                    .val,
+            )
+        };
+        let uint = |val: u64| Concrete::Uint(256, U256::from(val));
+        // arguments are matched to parameters and fields by name
+        assert_eq!(range("d"), (uint(3), uint(3)));
+        assert_eq!(range("e"), (uint(6), uint(6)));
+
+        let warnings = analyzer
+            .diagnostics
+            .iter()
+            .map(|diagnostic| {
+                (
+                    &sol[diagnostic.loc.start()..diagnostic.loc.end()],
+                    diagnostic.message.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            warnings,
+            vec![(
+                "sub({a: 5, c: 2})",
+                "Named arguments do not match the callee: unknown \"c\"; missing \"b\""
+            )]
+        );
+    }
+
+    #[test]
+    fn address_members() {
+        let sol = r###"
+contract C {
+    function f(address a) public {
+        uint256 b = a.balance;
+        require(a.balance > 10);
+        uint256 c = a.balance;
+        uint256 d = address(this).balance;
+        bytes32 h = a.codehash;
+        bytes memory k = a.code;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(address)");
+        let range = |name: &str| {
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            (
+                var.evaled_range_min(&analyzer)
+                    .unwrap()
+                    .maybe_concrete()
+                    .unwrap()
+                    .val,
+                var.evaled_range_max(&analyzer)
+                    .unwrap()
+                    .maybe_concrete()
+                    .unwrap()
+                    .val,
+            )
+        };
+        let uint = |val: U256| Concrete::Uint(256, val);
+        assert_eq!(range("b"), (uint(U256::zero()), uint(U256::MAX)));
+        // the balance is refined like any other variable
+        assert_eq!(range("c"), (uint(11.into()), uint(U256::MAX)));
+        assert_eq!(range("d"), (uint(U256::zero()), uint(U256::MAX)));
+        let ty = |name: &str| {
+            ctx.latest_var_by_name(&analyzer, name)
+                .unwrap()
+                .ty(&analyzer)
+                .as_string(&analyzer)
+        };
+        assert_eq!(ty("h"), "bytes32");
+        assert_eq!(ty("k"), "bytes");
+    }
+
+    #[test]
+    fn low_level_calls() {
+        let sol = r###"
+pragma solidity ^0.8.0;
+contract C {
+    function f(address a, uint256 v) public {
+        (bool ok, ) = a.call{value: v - 1}("");
+        (bool success, bytes memory data) = a.staticcall(abi.encode(v));
+        require(ok);
+        bool o = ok;
+        uint256 w = v;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(address, uint256)");
+        let var = |name: &str| ctx.latest_var_by_name(&analyzer, name).unwrap();
+        let range = |name: &str| {
+            (
+                var(name)
+                    .evaled_range_min(&analyzer)
+                    .unwrap()
+                    .maybe_concrete()
+                    .unwrap()
+                    .val,
+                var(name)
+                    .evaled_range_max(&analyzer)
+                    .unwrap()
+                    .maybe_concrete()
+                    .unwrap()
+                    .val,
+            )
+        };
+        assert_eq!(
+            range("success"),
+            (Concrete::Bool(false), Concrete::Bool(true))
+        );
+        assert_eq!(range("o"), (Concrete::Bool(true), Concrete::Bool(true)));
+        assert_eq!(var("data").ty(&analyzer).as_string(&analyzer), "bytes");
+        // the value option is evaluated, so its subtraction can not underflow
+        assert_eq!(
+            range("w"),
+            (
+                Concrete::Uint(256, U256::one()),
+                Concrete::Uint(256, U256::MAX)
+            )
+        );
+    }
+
+    #[test]
+    fn builtin_fn_nodes() {
+        let mut analyzer = Analyzer::default();
+        let keccak = analyzer.builtin_fn_or_maybe_add("keccak256").unwrap();
+        assert_eq!(analyzer.builtin_fn_or_maybe_add("keccak256"), Some(keccak));
+        assert_eq!(FunctionNode::from(keccak).params(&analyzer).len(), 1);
+        assert_eq!(analyzer.builtin_fn_or_maybe_add("keccak512"), None);
+
+        let sol = r###"
+contract C {
+    function f(bytes memory a) public {
+        bytes32 x = keccak256(a);
+        bytes32 y = keccak256(abi.encode(x));
+    }
+}"###;
+        let _ = analyzer.parse_or_panic(sol, 0);
+        // every call shares the node
+        let keccaks = analyzer
+            .graph
+            .node_indices()
+            .filter(|idx| matches!(analyzer.node(*idx), Node::Function(func) if func.name.as_ref().unwrap().name == "keccak256"))
+            .collect::<Vec<_>>();
+        assert_eq!(keccaks, vec![keccak]);
+    }
+
+    #[test]
+    fn try_catch() {
+        let sol = r###"
+interface I {
+    function get(uint256 x) external returns (uint256 y);
+}
+
+contract C {
+    function f(I i, uint256 a) public {
+        uint256 r;
+        try i.get(a) returns (uint256 v) {
+            r = v;
+        } catch Error(string memory reason) {
+            r = 1;
+        } catch (bytes memory data) {
+            r = 2;
+        }
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(contract I, uint256)");
+        let forks = ctx.live_forks(&analyzer);
+        assert_eq!(forks.len(), 3);
+        let ty = |ctx: ContextNode, name: &str| {
+            ctx.latest_var_by_name(&analyzer, name)
+                .map(|var| var.ty(&analyzer).as_string(&analyzer))
+        };
+        let min = |ctx: ContextNode| {
+            ctx.latest_var_by_name(&analyzer, "r")
+                .unwrap()
+                .evaled_range_min(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val
+        };
+        // the success path binds the returned value, each catch clause its reason
+        assert_eq!(ty(forks[0], "v").as_deref(), Some("uint256"));
+        assert_eq!(min(forks[0]), Concrete::Uint(256, U256::zero()));
+        assert_eq!(ty(forks[1], "reason").as_deref(), Some("string"));
+        assert_eq!(min(forks[1]), Concrete::Uint(256, U256::one()));
+        assert_eq!(ty(forks[2], "data").as_deref(), Some("bytes"));
+        assert_eq!(ty(forks[2], "reason"), None);
+        assert_eq!(min(forks[2]), Concrete::Uint(256, 2.into()));
+    }
+
+    #[test]
+    fn search_children_cycles() {
+        let mut analyzer = Analyzer::default();
+        let a = analyzer.add_node(Node::ContextFork);
+        let b = analyzer.add_node(Node::ContextFork);
+        let c = analyzer.add_node(Node::ContextFork);
+        let d = analyzer.add_node(Node::ContextFork);
+        let sub = Edge::Context(ContextEdge::Subcontext);
+        analyzer.add_edge(b, a, sub);
+        analyzer.add_edge(c, b, Edge::Func);
+        analyzer.add_edge(d, c, sub);
+        // a cycle back to the start
+        analyzer.add_edge(a, d, Edge::Func);
+
+        assert_eq!(analyzer.search_children(a, &sub), BTreeSet::from([b, d]));
+        assert_eq!(
+            analyzer.search_children_depth(a, &sub, 1),
+            BTreeSet::from([b])
+        );
+        assert_eq!(
+            analyzer.search_children_depth(a, &sub, 2),
+            BTreeSet::from([b])
+        );
+        assert_eq!(
+            analyzer.search_children_depth(a, &sub, 3),
+            BTreeSet::from([b, d])
+        );
+        assert_eq!(analyzer.search_children_depth(a, &sub, 0), BTreeSet::new());
+    }
+
+    #[test]
+    fn diagnostics() {
+        let sol = r###"
+import "./does_not_exist.sol";
+
+contract C {
+    Missing[] xs;
+}"###;
+        let mut analyzer = Analyzer::default();
+        let _ = analyzer.parse_or_panic(sol, 0);
+        let diagnostics = analyzer
+            .diagnostics()
+            .iter()
+            .map(|diagnostic| {
+                (
+                    &sol[diagnostic.loc.start()..diagnostic.loc.end()],
+                    diagnostic.severity,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            diagnostics,
+            vec![
+                ("import \"./does_not_exist.sol\"", Severity::Warning),
+                ("Missing[]", Severity::Info),
+            ]
+        );
+
+        let file_mapping = BTreeMap::from([(0, "test.sol".to_string())]);
+        let findings = analyzer
+            .diagnostics_report(&file_mapping, ReportConfig::default())
+            .findings(&analyzer);
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[1].severity, Severity::Info);
+        assert!(findings[1].message.contains("undeclared type"));
+    }
+
+    #[test]
+    fn user_value_types() {
+        let sol = r###"
+type Weight is uint256;
+
+contract C {
+    function f(Weight w, uint256 x) public {
+        require(x < 100);
+        Weight v = Weight.wrap(x);
+        uint256 raw = Weight.unwrap(v);
+        uint256 u = Weight.unwrap(w);
+        Price p = Price.wrap(5);
+    }
+
+    type Price is uint8;
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        assert!(matches!(
+            analyzer.node(analyzer.user_types["Weight"]),
+            Node::Ty(_)
+        ));
+        assert!(matches!(
+            analyzer.node(analyzer.user_types["C.Price"]),
+            Node::Ty(_)
+        ));
+        let func = analyzer.functions_by_name("f")[0];
+        let ctx = fn_ctx(&analyzer, entry, &func.name(&analyzer));
+        let range = |name: &str| {
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            (
+                var.evaled_range_min(&analyzer)
+                    .unwrap()
+                    .maybe_concrete()
+                    .unwrap()
+                    .val,
+                var.evaled_range_max(&analyzer)
+                    .unwrap()
+                    .maybe_concrete()
+                    .unwrap()
+                    .val,
+            )
+        };
+        let uint = |val: U256| Concrete::Uint(256, val);
+        // wrapping and unwrapping keeps the range
+        assert_eq!(range("v"), (uint(U256::zero()), uint(99.into())));
+        assert_eq!(range("raw"), (uint(U256::zero()), uint(99.into())));
+        // the user type has the range of the type it is defined as
+        assert_eq!(range("u"), (uint(U256::zero()), uint(U256::MAX)));
+        assert_eq!(
+            range("p"),
+            (Concrete::Uint(8, 5.into()), Concrete::Uint(8, 5.into()))
+        );
+    }
+
+    #[test]
+    fn function_pointers() {
+        let sol = r###"
+contract C {
+    function (uint256) internal pure returns (uint8) op;
+
+    function double(uint256 x) internal pure returns (uint256) {
+        return x * 2;
+    }
+
+    function f(uint8 a) public {
+        function (uint256) internal pure returns (uint256) g = double;
+        uint256 r = g(a);
+        uint8 s = op(a);
+    }
+
+    function h(function (uint256) external returns (uint16) cb) public {
+        uint16 t = cb(1);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let range = |ctx: ContextNode, name: &str| {
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            (
+                var.evaled_range_min(&analyzer)
+                    .unwrap()
+                    .maybe_concrete()
+                    .unwrap()
+                    .val,
+                var.evaled_range_max(&analyzer)
+                    .unwrap()
+                    .maybe_concrete()
+                    .unwrap()
+                    .val,
+            )
+        };
+        let ctx = fn_ctx(&analyzer, entry, "f(uint8)");
+        // a pointer to a known function calls it
+        assert_eq!(
+            range(ctx, "r"),
+            (
+                Concrete::Uint(256, U256::zero()),
+                Concrete::Uint(256, 510.into())
+            )
+        );
+        // otherwise the result is anything of the pointer's return type
+        assert_eq!(
+            range(ctx, "s"),
+            (
+                Concrete::Uint(8, U256::zero()),
+                Concrete::Uint(8, 255.into())
+            )
+        );
+        let func = analyzer.functions_by_name("h")[0];
+        let ctx = fn_ctx(&analyzer, entry, &func.name(&analyzer));
+        assert_eq!(
+            range(ctx, "t"),
+            (
+                Concrete::Uint(16, U256::zero()),
+                Concrete::Uint(16, 65535.into())
+            )
+        );
+    }
+
+    #[test]
+    fn fallback_and_receive() {
+        let sol = r###"
+contract A {
+    constructor() {}
+
+    receive() external payable {}
+}
+
+contract B is A {
+    fallback() external {}
+}"###;
+        let mut analyzer = Analyzer::default();
+        let _ = analyzer.parse_or_panic(sol, 0);
+        let a = analyzer.contract_by_name("A").unwrap();
+        let b = analyzer.contract_by_name("B").unwrap();
+        let kind = |func: Option<FunctionNode>| func.map(|func| func.underlying(&analyzer).ty);
+
+        assert_eq!(
+            kind(a.constructor(&analyzer)),
+            Some(FunctionTy::Constructor)
+        );
+        assert_eq!(kind(a.receive(&analyzer)), Some(FunctionTy::Receive));
+        assert_eq!(a.fallback(&analyzer), None);
+        assert_eq!(b.constructor(&analyzer), None);
+        assert_eq!(kind(b.fallback(&analyzer)), Some(FunctionTy::Fallback));
+        // inherited from `A`
+        assert_eq!(b.receive(&analyzer), a.receive(&analyzer));
+    }
+
+    #[test]
+    fn concrete_comparisons() {
+        let neg = Concrete::Int(8, I256::from(-1));
+        let zero = Concrete::Uint(256, 0.into());
+        assert_eq!(neg.lt(&zero), Some(true));
+        assert_eq!(zero.gt(&neg), Some(true));
+        assert_eq!(zero.le(&neg), Some(false));
+        assert_eq!(
+            Concrete::Int(16, 5.into()).ge(&Concrete::Uint(8, 5.into())),
+            Some(true)
+        );
+        assert_eq!(
+            Concrete::Uint(8, 5.into()).ge(&Concrete::Int(16, 6.into())),
+            Some(false)
+        );
+        assert_eq!(
+            Concrete::Int(256, I256::from(-2)).lt(&Concrete::Int(8, I256::from(-1))),
+            Some(true)
+        );
+        assert_eq!(Concrete::Bool(false).lt(&Concrete::Bool(true)), Some(true));
+        assert_eq!(zero.lt(&Concrete::Bool(true)), None);
+        assert_eq!(zero.ge(&Concrete::String("a".to_string())), None);
+    }
+
+    #[test]
+    fn bounds_merge_subctxs() {
+        let sol = r###"
+contract A {
+    function f(bool b) public {
+        uint256 x = 1;
+        if (b) {
+            x = 5;
+        } else {
+            x = 3;
+        }
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let file_mapping = analyzer.file_mapping();
+        let ctx = fn_ctx(&analyzer, entry, "f(bool)");
+        let config = ReportConfig {
+            show_consts: true,
+            merge_subctxs: true,
+            ..Default::default()
+        };
+        let analysis = analyzer.bounds_for_all(&file_mapping, ctx, config);
+        assert_eq!(analysis.vars_by_ctx.keys().collect::<Vec<_>>(), vec![&ctx]);
+        let x = analysis.vars_by_ctx[&ctx]
+            .iter()
+            .find(|analysis| analysis.var_name == "x")
+            .unwrap();
+        let range = x.final_range().unwrap();
+        let bound = |elem: Elem<Concrete>| elem.maybe_concrete().unwrap().val;
+        assert_eq!(bound(range.range_min()), Concrete::Uint(256, 3.into()));
+        assert_eq!(bound(range.range_max()), Concrete::Uint(256, 5.into()));
+
+        let unmerged = analyzer.bounds_for_all(
+            &file_mapping,
+            ctx,
+            ReportConfig {
+                merge_subctxs: false,
+                ..config
+            },
+        );
+        assert_eq!(unmerged.vars_by_ctx.len(), 2);
+    }
+
+    #[test]
+    fn array_literals() {
+        let sol = r###"
+contract A {
+    function f(uint8 x) public {
+        uint8[] memory a = [1, 2, 3];
+        uint256 la = a.length;
+        uint8[] memory b = [x, 5];
+        uint256 lb = b.length;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(uint8)");
+        let var = |name: &str| ctx.latest_var_by_name(&analyzer, name).unwrap();
+        let val = |name: &str| {
+            let bound = |elem: Option<Elem<Concrete>>| elem.unwrap().maybe_concrete().unwrap().val;
+            let var = var(name);
+            let (min, max) = (
+                bound(var.evaled_range_min(&analyzer)),
+                bound(var.evaled_range_max(&analyzer)),
+            );
+            assert_eq!(min, max);
+            min
+        };
+        assert_eq!(val("la"), Concrete::Uint(256, 3.into()));
+        assert_eq!(val("lb"), Concrete::Uint(256, 2.into()));
+
+        let literal = |display: &str| {
+            ctx.vars(&analyzer)
+                .into_iter()
+                .find(|var| var.display_name(&analyzer) == display)
+                .unwrap()
+                .ty(&analyzer)
+                .clone()
+        };
+        let VarType::Concrete(a) = literal("[1, 2, 3]") else {
+            panic!("constant literal is not concrete");
+        };
+        assert_eq!(
+            *a.underlying(&analyzer),
+            Concrete::Array(vec![
+                Concrete::Uint(8, 1.into()),
+                Concrete::Uint(8, 2.into()),
+                Concrete::Uint(8, 3.into()),
+            ])
+        );
+        let VarType::BuiltIn(b, _) = literal("[x, 5]") else {
+            panic!("literal is not an array");
+        };
+        assert_eq!(
+            b.underlying(&analyzer).as_string(&analyzer),
+            "uint8[]".to_string()
+        );
+    }
+
+    #[test]
+    fn reparse_file() {
+        let lib = r###"
+struct S { uint256 a; }
+
+contract A {
+    function g() public virtual returns (uint256) {
+        return 1;
+    }
+}"###;
+        let user = r###"
+contract B is A {
+    function h(S memory s) public returns (uint256) {
+        return s.a + g();
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        analyzer.parse_or_panic(lib, 0);
+        analyzer.parse_or_panic(user, 1);
+
+        let lib = lib.replace("return 1;", "return 2;");
+        let entry = analyzer.reparse_file(0, &lib).unwrap();
+        assert_eq!(*analyzer.node(entry), Node::SourceUnit(0));
+        let count = |analyzer: &Analyzer, f: fn(&Node) -> bool| {
+            analyzer
+                .graph()
+                .node_weights()
+                .filter(|node| f(node))
+                .count()
+        };
+        assert_eq!(
+            count(&analyzer, |node| matches!(node, Node::SourceUnit(_))),
+            2
+        );
+        assert_eq!(count(&analyzer, |node| matches!(node, Node::Struct(_))), 1);
+        assert_eq!(
+            count(&analyzer, |node| matches!(node, Node::Function(_))),
+            2
+        );
+
+        let mut contracts = analyzer
+            .contracts()
+            .into_iter()
+            .map(|contract| contract.name(&analyzer))
+            .collect::<Vec<_>>();
+        contracts.sort();
+        assert_eq!(contracts, vec!["A".to_string(), "B".to_string()]);
+        let a = ContractNode::from(analyzer.user_types["A"]);
+        let b = analyzer.contract_by_name("B").unwrap();
+        assert_eq!(b.underlying(&analyzer).inherits, vec![a]);
+        let h = analyzer.functions_by_name("h")[0];
+        let s = analyzer.user_types["S"];
+        assert!(matches!(analyzer.node(s), Node::Struct(_)));
+        assert!(h
+            .params(&analyzer)
+            .iter()
+            .all(|param| param.underlying(&analyzer).ty == s));
+
+        let ctx = fn_ctx(&analyzer, entry, "g()");
+        let (_, ret) = ctx.return_nodes(&analyzer)[0];
+        assert_eq!(
+            ret.evaled_range_max(&analyzer)
+                .unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val,
+            Concrete::Uint(8, 2.into())
+        );
+
+        let nodes = analyzer.graph().node_count();
+        assert!(analyzer.reparse_file(0, "contract {").is_err());
+        assert_eq!(analyzer.graph().node_count(), nodes);
+    }
+
+    #[test]
+    fn call_options() {
+        let sol = r###"
+interface I {
+    function pay() external payable returns (uint256);
+    function get() external returns (uint256);
+}
+
+contract C {
+    function f(I i, uint256 v) public {
+        uint256 a = i.pay{value: v, gas: 5000}();
+        uint256 b = i.get{value: 1}();
+        uint256 c = i.get{value: 0}();
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(contract I, uint256)");
+        let warnings = analyzer
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.message.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            warnings,
+            vec!["Value sent to \"get()\", which is not payable, the call reverts".to_string()]
+        );
+        let start = sol.find("i.get{value: 1}").unwrap();
+        assert_eq!(analyzer.diagnostics[0].loc.start(), start);
+
+        let options = analyzer
+            .graph()
+            .edge_references()
+            .filter_map(|edge| match edge.weight() {
+                Edge::Context(ContextEdge::CallValue) => Some("value"),
+                Edge::Context(ContextEdge::CallGas) => Some("gas"),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(options, vec!["value", "gas", "value", "value"]);
+        assert!(ctx.latest_var_by_name(&analyzer, "a").is_some());
+    }
+
+    #[test]
+    fn node_newtype_conversions() {
+        let sol = r###"
+contract C {
+    error E(uint256 a);
+    function f() public {}
+}"###;
+        let mut analyzer = Analyzer::default();
+        analyzer.parse_or_panic(sol, 0);
+        let func = analyzer
+            .graph()
+            .node_indices()
+            .find(|idx| matches!(analyzer.node(*idx), Node::Function(_)))
+            .map(FunctionNode::from)
+            .unwrap();
+        let idx: NodeIdx = func.into();
+        assert_eq!(FunctionNode::from(idx), func);
+        assert_eq!(
+            func.underlying(&analyzer).name.as_ref().unwrap().name,
+            "f()"
+        );
+
+        let err = analyzer
+            .graph()
+            .node_indices()
+            .find(|idx| matches!(analyzer.node(*idx), Node::Error(_)))
+            .map(ErrorNode::from)
+            .unwrap();
+        assert_eq!(err.underlying(&analyzer).name.as_ref().unwrap().name, "E");
+
+        let param = analyzer
+            .graph()
+            .node_indices()
+            .find(|idx| matches!(analyzer.node(*idx), Node::ErrorParam(_)))
+            .map(ErrorParamNode::from)
+            .unwrap();
+        assert_eq!(NodeIdx::from(param).index(), param.0);
+        assert!(param.underlying(&analyzer).name.is_some());
+
+        let confused =
+            std::panic::catch_unwind(|| ContractNode::from(idx).underlying(&analyzer).name.clone())
+                .unwrap_err();
+        assert!(confused
+            .downcast_ref::<String>()
+            .unwrap()
+            .starts_with("Node type confusion: expected ContractNode to be Contract"));
+    }
+
+    #[test]
+    fn write_reports_to_sink() {
+        let sol = r###"
+contract C {
+    function dead(uint256 x) public {
+        require(x < 10);
+        if (x > 20) {
+            x = 1;
+        }
+    }
+
+    function live(uint256 x) public {
+        if (x > 10) {
+            x = 1;
+        }
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let file_mapping = analyzer.file_mapping();
+        let mut cache = analyzer.source_cache();
+        let written = |func: &str, cache: &mut _| {
+            let ctx = fn_ctx(&analyzer, entry, func);
+            let report = analyzer.dead_code_report(&file_mapping, ctx, ReportConfig::default());
+            let mut out = vec![];
+            report.write_reports(cache, &mut out, &analyzer).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+        let out = written("dead(uint256)", &mut cache);
+        assert!(out.contains("Branches never taken in function"));
+        assert!(out.contains("Branch never taken, its condition can not hold here"));
+        assert!(written("live(uint256)", &mut cache).is_empty());
+    }
+
+    #[test]
+    fn storage_references_alias() {
+        let sol = r###"
+contract C {
+    struct S {
+        uint256 x;
+    }
+    S item;
+    uint256[] arr;
+
+    function f(uint256 v) public {
+        require(v < 10);
+        S storage s = item;
+        s.x = v;
+        S memory m = item;
+        m.x = 100;
+        uint256[] storage a = arr;
+        a[0] = 5;
+        uint256 r = arr[0];
+    }
+
+    function g(uint256[] calldata c) external {}
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(uint256)");
+        let var = |name: &str| ctx.latest_var_by_name(&analyzer, name).unwrap();
+        let max = |name: &str| {
+            var(name)
+                .range(&analyzer)
+                .unwrap()
+                .evaled_range_max(&analyzer)
+                .maybe_concrete()
+                .unwrap()
+                .val
+        };
+
+        assert!(var("s").is_storage(&analyzer));
+        assert!(var("m").is_memory(&analyzer));
+        assert_eq!(
+            var("s").storage_alias(&analyzer).unwrap().name(&analyzer),
+            "item"
+        );
+        assert!(var("m").storage_alias(&analyzer).is_none());
+        // the write through `s` reaches `item`, the one to the copy in `m` doesn't
+        assert_eq!(max("item.x"), Concrete::Uint(256, 9.into()));
+        assert_eq!(max("m.x"), Concrete::Uint(256, 100.into()));
+        assert_eq!(
+            var("a").storage_alias(&analyzer).unwrap().name(&analyzer),
+            "arr"
+        );
+        assert!(var("arr").num_versions(&analyzer) > 1);
+        assert_eq!(max("r"), Concrete::Uint(256, 5.into()));
+
+        let ctx = fn_ctx(&analyzer, entry, "g(uint256[])");
+        assert!(ctx
+            .latest_var_by_name(&analyzer, "c")
+            .unwrap()
+            .is_calldata(&analyzer));
+    }
+
+    #[test]
+    fn fixed_point_and_dynamic_types() {
+        let sol = r###"
+contract C {
+    ufixed128x18 price;
+    fixed signed;
+
+    function f(bytes memory b, string memory s, fixed64x10 p) public {
+        ufixed8x1 local;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(bytes, string, fixed64x10)");
+        let ty = |name: &str| {
+            ctx.latest_var_by_name(&analyzer, name)
+                .unwrap()
+                .ty(&analyzer)
+                .as_string(&analyzer)
+        };
+        assert_eq!(ty("b"), "bytes");
+        assert_eq!(ty("s"), "string");
+        assert_eq!(ty("p"), "fixed64x10");
+        assert_eq!(ty("local"), "ufixed8x1");
+
+        let contract = ContractNode::from(analyzer.user_types["C"]);
+        let state_vars = contract
+            .vars(&analyzer)
+            .iter()
+            .map(|var| {
+                VarType::try_from_idx(&analyzer, var.underlying(&analyzer).ty)
+                    .unwrap()
+                    .as_string(&analyzer)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(state_vars, vec!["ufixed128x18", "fixed128x18"]);
+        assert!(analyzer
+            .graph()
+            .node_weights()
+            .all(|node| !matches!(node, Node::Unresolved(_))));
+
+        assert_eq!(
+            Builtin::try_from_fixed_name("ufixed"),
+            Some(Builtin::Ufixed(128, 18))
+        );
+        assert_eq!(Builtin::try_from_fixed_name("fixed7x1"), None);
+        assert_eq!(Builtin::try_from_fixed_name("fixedpoint"), None);
+    }
+
+    #[test]
+    fn mapping_writes() {
+        let sol = r###"
+contract Token {
+    mapping(address => uint256) balances;
+    mapping(address => mapping(address => uint256)) allowances;
+
+    function transfer(address to, uint256 amount) public {
+        require(balances[msg.sender] >= amount);
+        require(amount < 100);
+        balances[msg.sender] -= amount;
+        balances[to] = amount;
+        allowances[msg.sender][to] = 7;
+        uint256 sent = balances[to];
+        uint256 allowed = allowances[msg.sender][to];
+        uint256 other = balances[address(0)];
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "transfer(address, uint256)");
+        assert!(!ctx.is_killed(&analyzer));
+        let range = |var: &str| {
+            let var = ctx.latest_var_by_name(&analyzer, var).unwrap();
+            let min = var.evaled_range_min(&analyzer).unwrap();
+            let max = var.evaled_range_max(&analyzer).unwrap();
+            (
+                min.maybe_concrete().unwrap().val.uint_val().unwrap(),
+                max.maybe_concrete().unwrap().val.uint_val().unwrap(),
+            )
+        };
+        assert_eq!(range("sent"), (0.into(), 99.into()));
+        assert_eq!(range("allowed"), (7.into(), 7.into()));
+        assert_eq!(range("other"), (0.into(), U256::MAX));
+    }
+
+    #[test]
+    fn edges_between_nodes() {
+        let mut analyzer = Analyzer::default();
+        let a = analyzer.add_node(Node::ContextFork);
+        let b = analyzer.add_node(Node::ContextFork);
+        let c = analyzer.add_node(Node::ContextFork);
+        let sub = Edge::Context(ContextEdge::Subcontext);
+        analyzer.add_edge(b, a, sub);
+        analyzer.add_edge(a, b, Edge::Func);
+        analyzer.add_edge(b, a, Edge::Modifier);
+        analyzer.add_edge(c, c, Edge::Part);
+
+        assert_eq!(
+            analyzer
+                .edges_between(a, b)
+                .into_iter()
+                .collect::<BTreeSet<_>>(),
+            BTreeSet::from([sub, Edge::Func, Edge::Modifier])
+        );
+        assert_eq!(analyzer.edges_between(b, c), vec![]);
+        assert_eq!(analyzer.edges_between(c, c), vec![Edge::Part]);
+        assert_eq!(analyzer.edge_directed(a, b), Some(Edge::Func));
+        assert_eq!(analyzer.edge_directed(b, a), Some(Edge::Modifier));
+        assert_eq!(analyzer.edge_directed(a, c), None);
+    }
+
+    #[test]
+    fn function_selectors() {
+        let sol = r###"
+interface IERC20 {
+    function transfer(address to, uint amount) external returns (bool);
+}
+
+contract C {
+    struct P {
+        uint x;
+        IERC20 token;
+    }
+    enum E { A, B }
+
+    function balanceOf(address payable owner) public view returns (uint256) {}
+    function complex(P memory p, E e, uint8[2] memory fixedArr, bytes memory b, string memory s) external {}
+    function hidden(uint256 x) internal {}
+    function priv() private {}
+}"###;
+        let mut analyzer = Analyzer::default();
+        analyzer.parse_or_panic(sol, 0);
+        let selector = |contract: &str, func: &str| {
+            ContractNode::from(analyzer.user_types[contract])
+                .funcs(&analyzer)
+                .into_iter()
+                .find(|f| f.name(&analyzer).starts_with(&format!("{func}(")))
+                .unwrap()
+                .selector(&analyzer)
+        };
+        assert_eq!(
+            selector("IERC20", "transfer"),
+            Some([0xa9, 0x05, 0x9c, 0xbb])
+        );
+        assert_eq!(selector("C", "balanceOf"), Some([0x70, 0xa0, 0x82, 0x31]));
+        let hash =
+            ethers_core::utils::keccak256("complex((uint256,address),uint8,uint8[2],bytes,string)");
+        assert_eq!(
+            selector("C", "complex"),
+            Some([hash[0], hash[1], hash[2], hash[3]])
+        );
+        assert_eq!(selector("C", "hidden"), None);
+        assert_eq!(selector("C", "priv"), None);
+    }
+
+    #[test]
+    fn analyze_all_reports() {
+        let sol = r###"
+contract C {
+    uint256 x;
+
+    modifier m() {
+        _;
+    }
+
+    function dead(uint256 a) public m {
+        require(a < 10);
+        if (a > 20) {
+            a = 1;
+        }
+    }
+
+    function shadow(uint256 x) public {
+        x += 1;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        analyzer.parse_or_panic(sol, 0);
+        let file_mapping = analyzer.file_mapping();
+
+        let report = analyzer.analyze_all(&file_mapping, ReportConfig::default());
+        let names = report
+            .functions
+            .iter()
+            .map(|report| report.func.name(&analyzer))
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["dead(uint256)", "shadow(uint256)"]);
+        assert!(report
+            .functions
+            .iter()
+            .all(|report| report.overflow.is_none()
+                && report.dead_code.is_none()
+                && report.shadowing.is_none()));
+
+        let config = ReportConfig {
+            overflow: true,
+            dead_code: true,
+            shadowing: true,
+            ..Default::default()
+        };
+        let report = analyzer.analyze_all(&file_mapping, config);
+        let findings = report.findings(&analyzer);
+        assert!(findings.len() >= report.functions.len());
+        assert!(report
+            .functions
+            .iter()
+            .all(|report| report.overflow.is_some()));
+        assert!(!report.functions[0]
+            .dead_code
+            .as_ref()
+            .unwrap()
+            .dead_branches
+            .is_empty());
+        assert!(!report.functions[1]
+            .shadowing
+            .as_ref()
+            .unwrap()
+            .shadowed
+            .is_empty());
+
+        let mut out = vec![];
+        report
+            .write_reports(&mut analyzer.source_cache(), &mut out, &analyzer)
+            .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("Branch never taken"));
+    }
+
+    #[test]
+    fn nested_dynamic_arrays() {
+        let sol = r###"
+contract C {
+    uint256[][] m;
+
+    function f(uint256 i, uint256 j) public {
+        uint256 y = m[i][j];
+        uint256[] memory row = m[i];
+        uint256[][] memory n = new uint256[][](2);
+        uint256 z = n[i][j];
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(uint256, uint256)");
+        let ty_of = |name: &str| {
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            let ty = var.ty(&analyzer).clone();
+            assert_ne!(ty.ty_idx(), NodeIdx::from(0), "{name} is typed by node 0");
+            ty
+        };
+
+        // `uint256[][]` is a dynamic array of the same `uint256[]` builtin a row is typed by
+        let row_ty = ty_of("row");
+        assert_eq!(row_ty.as_string(&analyzer), "uint256[]");
+        for name in ["m", "n"] {
+            let ty = ty_of(name);
+            assert_eq!(ty.as_string(&analyzer), "uint256[][]");
+            match analyzer.node(ty.ty_idx()) {
+                Node::Builtin(Builtin::Array(inner)) => {
+                    assert_eq!(inner.ty_idx(), row_ty.ty_idx())
+                }
+                node => panic!("{name} is not a dynamic array: {node:?}"),
+            }
+        }
+        for name in ["y", "z"] {
+            assert_eq!(ty_of(name).as_string(&analyzer), "uint256");
+        }
+    }
+
+    #[test]
+    fn short_circuit_conditions() {
+        let sol = r###"
+contract C {
+    function f(uint256 a) public {
+        require(a > 0 && a < 100);
+        uint256 r = a;
+    }
+
+    function g(uint256 x, uint256 y) public {
+        if (x == 0 || y < 10) {
+            uint256 r = y;
+        } else {
+            uint256 r2 = y;
+        }
+    }
+
+    function h(uint256 x) public {
+        if (x > 10 && x < 20) {
+            uint256 r = x;
+        } else {
+            uint256 r2 = x;
+        }
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let bounds = |ctx: ContextNode, name: &str| {
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            let bound = |elem: Option<Elem<Concrete>>| elem.unwrap().maybe_concrete().unwrap().val;
+            (
+                bound(var.evaled_range_min(&analyzer)).uint_val().unwrap(),
+                bound(var.evaled_range_max(&analyzer)).uint_val().unwrap(),
+            )
+        };
+        // `&&` intersects the bounds of both sides
+        let ctx = fn_ctx(&analyzer, entry, "f(uint256)");
+        assert_eq!(bounds(ctx, "r"), (1.into(), 99.into()));
+
+        // the true side of `||` is forked into the path where `x == 0`, and the one where it
+        // isn't but `y < 10`
+        let ctx = fn_ctx(&analyzer, entry, "g(uint256, uint256)");
+        let [true_ctx, false_ctx] = ctx.forks(&analyzer)[..] else {
+            panic!("if was not forked");
+        };
+        let true_bounds = true_ctx
+            .terminal_child_list(&analyzer)
+            .into_iter()
+            .map(|ctx| bounds(ctx, "r"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            true_bounds,
+            vec![(0.into(), U256::MAX), (0.into(), 9.into())]
+        );
+        assert_eq!(bounds(false_ctx, "r2"), (10.into(), U256::MAX));
+
+        // the false side of `&&` holds when either side is false
+        let ctx = fn_ctx(&analyzer, entry, "h(uint256)");
+        let [true_ctx, false_ctx] = ctx.forks(&analyzer)[..] else {
+            panic!("if was not forked");
+        };
+        assert_eq!(bounds(true_ctx, "r"), (11.into(), 19.into()));
+        let false_bounds = false_ctx
+            .terminal_child_list(&analyzer)
+            .into_iter()
+            .map(|ctx| bounds(ctx, "r2"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            false_bounds,
+            vec![(0.into(), 10.into()), (20.into(), U256::MAX)]
+        );
+    }
+
+    #[test]
+    fn analysis_metrics() {
+        let sol = r###"
+contract C {
+    function f() public {
+        uint256 x = 0;
+        for (uint256 i = 0; i < 3; i++) {
+            x += i;
+        }
+    }
+
+    function g() public {}
+}"###;
+        let mut analyzer = Analyzer {
+            max_unroll: 5,
+            ..Default::default()
+        };
+        analyzer.parse_or_panic(sol, 0);
+        assert!(analyzer.metrics().is_none());
+        let file_mapping = analyzer.file_mapping();
+        assert!(analyzer
+            .analyze_all(&file_mapping, ReportConfig::default())
+            .metrics
+            .is_none());
+
+        let mut analyzer = Analyzer {
+            max_unroll: 5,
+            metrics: Some(Metrics::default()),
+            ..Default::default()
+        };
+        analyzer.parse_or_panic(sol, 0);
+        let metrics = analyzer.metrics().unwrap();
+        assert_eq!(metrics.unrolled_iterations, 3);
+        assert_eq!(
+            metrics.ctx_times.keys().collect::<Vec<_>>(),
+            vec!["C.f()", "C.g()"]
+        );
+        assert!(metrics.parse_time >= metrics.ctx_times.values().sum());
+        assert_eq!(metrics.node_count, analyzer.graph.node_count());
+        assert_eq!(metrics.edge_count, analyzer.graph.edge_count());
+
+        let file_mapping = analyzer.file_mapping();
+        let report = analyzer.analyze_all(&file_mapping, ReportConfig::default());
+        let report_metrics = report.metrics.unwrap();
+        assert_eq!(report_metrics.parse_time, metrics.parse_time);
+        assert!(report_metrics.report_time > Duration::ZERO);
+    }
+
+    #[test]
+    fn assert_refines_and_panics() {
+        let sol = r###"
+contract C {
+    function refines(uint256 x) public {
+        assert(x < 10);
+        uint256 y = x;
+    }
+
+    function panics(uint256 x) public {
+        require(x < 5);
+        assert(x > 10);
+    }
+
+    function reverts(uint256 x) public {
+        require(x < 5);
+        require(x > 10);
+    }
+
+    struct S {
+        bool flag;
+    }
+
+    mapping(address => bool) allowed;
+    S s;
+
+    function ok(uint256 x) internal returns (bool) {
+        return x < 10;
+    }
+
+    function alwaysPanics() public {
+        assert(false);
+    }
+
+    function alwaysReverts() public {
+        require(false, "never");
+    }
+
+    function nonComparisons(uint256 x) public {
+        require(ok(x));
+        require(allowed[msg.sender]);
+        require(s.flag);
+        assert(true);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let file_mapping = analyzer.file_mapping();
+
+        let ctx = fn_ctx(&analyzer, entry, "refines(uint256)");
+        let y = ctx.latest_var_by_name(&analyzer, "y").unwrap();
+        let bound = |elem: Option<Elem<Concrete>>| elem.unwrap().maybe_concrete().unwrap().val;
+        assert_eq!(
+            bound(y.evaled_range_min(&analyzer)).uint_val(),
+            Some(0.into())
+        );
+        assert_eq!(
+            bound(y.evaled_range_max(&analyzer)).uint_val(),
+            Some(9.into())
+        );
+        assert!(!ctx.is_killed(&analyzer));
+
+        let killed = |fn_name: &str| {
+            let ctx = fn_ctx(&analyzer, entry, fn_name);
+            let messages = analyzer
+                .bounds_for_all(&file_mapping, ctx, ReportConfig::default())
+                .findings(&analyzer)
+                .into_iter()
+                .filter(|finding| finding.severity == Severity::Error)
+                .map(|finding| finding.message)
+                .collect::<Vec<_>>();
+            (ctx.termination_reason(&analyzer), messages)
+        };
+        let (reason, messages) = killed("panics(uint256)");
+        assert!(matches!(reason, Some(Termination::Panic(_))));
+        assert_eq!(messages, vec!["Execution guaranteed to panic here!"]);
+        let (reason, messages) = killed("reverts(uint256)");
+        assert!(matches!(reason, Some(Termination::Revert(_))));
+        assert_eq!(messages, vec!["Execution guaranteed to revert here!"]);
+
+        // conditions that aren't comparisons are required to be `true`
+        let (reason, _) = killed("alwaysPanics()");
+        assert!(matches!(reason, Some(Termination::Panic(_))));
+        let (reason, _) = killed("alwaysReverts()");
+        assert!(matches!(reason, Some(Termination::Revert(_))));
+        let ctx = fn_ctx(&analyzer, entry, "nonComparisons(uint256)");
+        assert!(!ctx.is_killed(&analyzer));
+    }
+
+    #[test]
+    fn arrays_of_user_types() {
+        let sol = r###"
+struct Before {
+    uint256 a;
+}
+
+contract C {
+    address[] addrs;
+    Before[] befores;
+    After[] afters;
+    After[] moreAfters;
+    After[][] nested;
+    After[2] sized;
+    D[] ds;
+    E[] es;
+
+    function f(uint256 i) public {
+        address a = addrs[i];
+        Before memory b = befores[i];
+        After memory c = afters[i];
+        After memory d = nested[i][i];
+        After memory e = sized[i];
+        D dd = ds[i];
+        E ee = es[i];
+    }
+}
+
+struct After {
+    uint256 a;
+}
+
+contract D {
+    After[] afters;
+}
+
+enum E {
+    X,
+    Y
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        assert!(analyzer.diagnostics.is_empty());
+        let ctx = fn_ctx(&analyzer, entry, "f(uint256)");
+        let ty_str = |name: &str| {
+            ctx.var_by_name_or_recurse(&analyzer, name)
+                .unwrap()
+                .ty(&analyzer)
+                .as_string(&analyzer)
+        };
+        for (name, ty) in [
+            ("addrs", "address[]"),
+            ("befores", "struct Before[]"),
+            ("afters", "struct After[]"),
+            ("nested", "struct After[][]"),
+            ("sized", "struct After[2]"),
+            ("ds", "contract D[]"),
+            ("es", "enum E[]"),
+            ("a", "address"),
+            ("b", "struct Before"),
+            ("c", "struct After"),
+            ("d", "struct After"),
+            ("e", "struct After"),
+            ("dd", "contract D"),
+            ("ee", "enum E"),
+        ] {
+            assert_eq!(ty_str(name), ty, "{name}");
+        }
+        // every spelling of an array type, before or after its element is declared, is one node
+        let after_arrays = analyzer
+            .graph
+            .node_indices()
+            .filter(|idx| matches!(analyzer.node(*idx), Node::Builtin(_)))
+            .filter(|idx| {
+                VarType::try_from_idx(&analyzer, *idx)
+                    .unwrap()
+                    .as_string(&analyzer)
+                    == "struct After[]"
+            })
+            .count();
+        assert_eq!(after_arrays, 1);
+
+        // an element type that is never declared is reported rather than panicking
+        let mut analyzer = Analyzer::default();
+        analyzer.parse_or_panic("contract C { Missing[] xs; }", 0);
+        assert!(analyzer.diagnostics[0].message.contains("undeclared type"));
+    }
+
+    #[test]
+    fn overflow_witnesses() {
+        let sol = r###"
+pragma solidity ^0.8.0;
+contract A {
+    uint256[4] fixedList;
+
+    function add(uint8 a) public {
+        require(a >= 250);
+        uint8 c = a + 10;
+    }
+
+    function sub(uint256 a, uint256 b) public {
+        require(b > 3);
+        require(a < 2);
+        uint256 c = a - b;
+    }
+
+    function index(uint256 i) public {
+        uint256 x = fixedList[i];
+    }
+
+    function farIndex(uint256 i) public {
+        require(i > 9);
+        uint256 x = fixedList[i];
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let file_mapping = analyzer.file_mapping();
+        let findings = |func: &str| {
+            let ctx = fn_ctx(&analyzer, entry, func);
+            analyzer
+                .overflow_report(&file_mapping, ctx, ReportConfig::default())
+                .findings(&analyzer)
+                .into_iter()
+                .map(|finding| (finding.message, finding.witness.unwrap()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            findings("add(uint8)"),
+            vec![(
+                "\"+\" may overflow, the statement reverts, e.g. for 250 + 10".to_string(),
+                "250 + 10".to_string()
+            )]
+        );
+        let ctx = fn_ctx(&analyzer, entry, "sub(uint256, uint256)");
+        let report = analyzer.overflow_report(&file_mapping, ctx, ReportConfig::default());
+        assert_eq!(
+            report.overflows[0].1.witness,
+            (Concrete::from(U256::zero()), Concrete::from(U256::from(4)))
+        );
+        assert_eq!(findings("sub(uint256, uint256)")[0].1, "0 - 4");
+
+        // the first index past the end that the index can take
+        assert_eq!(findings("index(uint256)")[0].1, "4");
+        assert_eq!(findings("farIndex(uint256)")[0].1, "10");
+        assert!(findings("farIndex(uint256)")[0]
+            .0
+            .ends_with("e.g. for index 10"));
+    }
+
+    #[test]
+    fn mixed_width_arithmetic() {
+        let sol = r###"
+contract C {
+    function f(uint8 a, uint256 b) public {
+        require(b < 1000);
+        uint256 c = a + b;
+        uint256 d = b + a;
+        uint256 e = a * b;
+        bool lt = a < b;
+    }
+    function g(uint8 a, uint256 b) public {
+        require(b > 300);
+        require(b < 1000);
+        uint256 c = a + b;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let file_mapping = analyzer.file_mapping();
+        let max = |ctx: ContextNode, name: &str| {
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            (
+                var.ty(&analyzer).as_string(&analyzer),
+                var.evaled_range_max(&analyzer)
+                    .and_then(|max| max.maybe_concrete())
+                    .map(|max| max.val),
+            )
+        };
+
+        // the uint8 is promoted instead of the sum saturating at 255
+        let ctx = fn_ctx(&analyzer, entry, "f(uint8, uint256)");
+        let expected = |val: u64| {
+            (
+                "uint256".to_string(),
+                Some(Concrete::Uint(256, U256::from(val))),
+            )
+        };
+        assert_eq!(max(ctx, "c"), expected(1254));
+        assert_eq!(max(ctx, "d"), expected(1254));
+        assert_eq!(max(ctx, "e"), expected(254745));
+        assert_eq!(max(ctx, "lt").1, Some(Concrete::Bool(true)));
+        let findings = analyzer
+            .overflow_report(&file_mapping, ctx, ReportConfig::default())
+            .findings(&analyzer);
+        assert!(findings.is_empty(), "{findings:?}");
+
+        // a uint256 above 255 does not make the sum overflow
+        let ctx = fn_ctx(&analyzer, entry, "g(uint8, uint256)");
+        assert!(!ctx.is_killed(&analyzer));
+        assert_eq!(max(ctx, "c"), expected(1254));
+        let findings = analyzer
+            .overflow_report(&file_mapping, ctx, ReportConfig::default())
+            .findings(&analyzer);
+        assert!(findings.is_empty(), "{findings:?}");
+    }
+
+    #[test]
+    fn value_flow_report() {
+        let sol = r###"
+contract C {
+    function f(address payable to, uint256 amount) public {
+        require(amount <= 1000);
+        to.transfer(amount);
+    }
+    function g(address payable to, uint256 amount) public {
+        bool ok = to.send(amount);
+        to.call{value: 5}("");
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let file_mapping = analyzer.file_mapping();
+        let findings = |name: &str| {
+            let ctx = fn_ctx(&analyzer, entry, name);
+            analyzer
+                .value_flow_report(&file_mapping, ctx, ReportConfig::default())
+                .findings(&analyzer)
+                .into_iter()
+                .map(|finding| (finding.severity, finding.message))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            findings("f(address payable, uint256)"),
+            vec![(
+                Severity::Info,
+                "Value sent through \"transfer\", value range = [0, 1000]".to_string()
+            )]
+        );
+        // nothing limits the amount sent, unlike the constant sent by the call
+        assert_eq!(
+            findings("g(address payable, uint256)"),
+            vec![
+                (
+                    Severity::Warning,
+                    format!(
+                        "Value sent through \"send\" is unbounded, value range = [0, {}]",
+                        Concrete::from(U256::MAX).as_human_string()
+                    )
+                ),
+                (
+                    Severity::Info,
+                    "Value sent through \"call\", value range = [5, 5]".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn graph_iterators() {
+        let sol = r###"
+contract C {
+    uint256 x;
+    function f(uint256 a) public {
+        x = a;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        analyzer.parse_or_panic(sol, 0);
+
+        let nodes = analyzer.nodes().collect::<Vec<_>>();
+        assert_eq!(nodes.len(), analyzer.graph().node_count());
+        assert!(nodes.iter().all(|(idx, node)| analyzer.node(*idx) == *node));
+        let contracts = nodes
+            .iter()
+            .filter(|(_, node)| matches!(node, Node::Contract(_)))
+            .count();
+        assert_eq!(contracts, 1);
+
+        let edges = analyzer.edges().collect::<Vec<_>>();
+        assert_eq!(edges.len(), analyzer.graph().edge_count());
+        assert!(edges
+            .iter()
+            .all(|(from, to, edge)| analyzer.edges_between(*from, *to).contains(edge)));
+        // the function is linked to its contract
+        let func = analyzer
+            .functions()
+            .into_iter()
+            .find(|func| func.name(&analyzer) == "f(uint256)")
+            .unwrap();
+        assert!(edges.iter().any(|(from, to, edge)| *from == func.into()
+            && matches!(analyzer.node(*to), Node::Contract(_))
+            && **edge == Edge::Func));
+    }
+
+    #[test]
+    fn bytes_length_and_indexing() {
+        let sol = r###"
+contract C {
+    function f(bytes memory data, uint256 i) public {
+        uint256 l = data.length;
+        bytes1 b = data[i];
+    }
+    function g(uint256 i) public {
+        bytes memory data = hex"010203";
+        uint256 l = data.length;
+        bytes1 b = data[1];
+        uint8 u = uint8(data[1]);
+        bytes1 c = data[i];
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let file_mapping = analyzer.file_mapping();
+        let range = |ctx: ContextNode, name: &str| {
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            let bound = |elem: Option<Elem<Concrete>>| elem.and_then(|elem| elem.maybe_concrete());
+            (
+                var.ty(&analyzer).as_string(&analyzer),
+                bound(var.evaled_range_min(&analyzer)).map(|min| min.val),
+                bound(var.evaled_range_max(&analyzer)).map(|max| max.val),
+            )
+        };
+        let byte = |val: u8| {
+            let mut bytes = [0u8; 32];
+            bytes[0] = val;
+            Some(Concrete::Bytes(1, ethers_core::types::H256::from(bytes)))
+        };
+
+        // nothing is known about a parameter's contents
+        let ctx = fn_ctx(&analyzer, entry, "f(bytes, uint256)");
+        assert_eq!(
+            range(ctx, "l"),
+            (
+                "uint256".to_string(),
+                Some(Concrete::from(U256::zero())),
+                Some(Concrete::from(U256::MAX))
+            )
+        );
+        assert_eq!(range(ctx, "b"), ("bytes1".to_string(), byte(0), byte(0xff)));
+
+        let ctx = fn_ctx(&analyzer, entry, "g(uint256)");
+        let three = Some(Concrete::from(U256::from(3)));
+        assert_eq!(
+            range(ctx, "l"),
+            ("uint256".to_string(), three.clone(), three)
+        );
+        assert_eq!(range(ctx, "b"), ("bytes1".to_string(), byte(2), byte(2)));
+        let two = Some(Concrete::Uint(8, U256::from(2)));
+        assert_eq!(range(ctx, "u"), ("uint8".to_string(), two.clone(), two));
+        assert_eq!(range(ctx, "c"), ("bytes1".to_string(), byte(0), byte(0xff)));
+        let findings = analyzer
+            .overflow_report(&file_mapping, ctx, ReportConfig::default())
+            .findings(&analyzer);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(
+            findings[0].message,
+            "Index may be out of bounds, the statement reverts, e.g. for index 3"
+        );
+    }
+
+    #[test]
+    fn context_var_predicates() {
+        let sol = r###"
+contract C {
+    uint256 x;
+    function f(uint256 a) public {
+        uint256 b = a + 1;
+        uint256 c = 5;
+        x = b;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(uint256)");
+        let var = |name: &str| {
+            ctx.vars(&analyzer)
+                .into_iter()
+                .find(|var| var.display_name(&analyzer) == name)
+                .unwrap()
+                .underlying(&analyzer)
+                .clone()
+        };
+        let class = |var: &ContextVar| {
+            (
+                var.is_tmp(),
+                var.is_const(&analyzer),
+                var.is_storage(),
+                var.is_symbolic(),
+            )
+        };
+
+        assert_eq!(class(&var("a")), (false, false, false, true));
+        assert_eq!(class(&var("(a + 1)")), (true, false, false, true));
+        // a declared local stays symbolic, its range pins the value down
+        let c = ctx.latest_var_by_name(&analyzer, "c").unwrap();
+        assert_eq!(class(c.underlying(&analyzer)), (false, true, false, true));
+        let x = ctx.latest_var_by_name(&analyzer, "x").unwrap();
+        assert!(x.underlying(&analyzer).is_storage());
+        assert_eq!(
+            x.is_storage(&analyzer),
+            x.underlying(&analyzer).is_storage()
+        );
+
+        let dot = analyzer.dot_str_no_tmps();
+        assert!(!dot.contains("(a + 1)"));
+    }
+
+    #[test]
+    fn custom_error_reverts() {
+        let sol = r###"
+error Closed();
+
+contract Base {
+    error Insufficient(uint256 have, uint256 want);
+}
+
+contract C is Base {
+    uint256 last;
+
+    function guarded(uint256 have, uint256 want) public {
+        if (have < want) {
+            revert Insufficient(have, want);
+        }
+        last = have;
+    }
+
+    function named(uint256 have, uint256 want) public {
+        revert Insufficient({want: want, have: have + 1});
+    }
+
+    function closed() public {
+        revert Closed();
+    }
+
+    function unknown() public {
+        revert Missing();
+    }
+
+    function qualified() public {
+        revert Base.Insufficient(1, 2);
+    }
+
+    function wrongQualifier() public {
+        // `Closed` is declared at file level, not in `Base`
+        revert Base.Closed();
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+
+        let ctx = fn_ctx(&analyzer, entry, "guarded(uint256, uint256)");
+        let reasons = ctx
+            .subcontexts(&analyzer)
+            .into_iter()
+            .filter_map(|subctx| subctx.termination_reason(&analyzer))
+            .collect::<Vec<_>>();
+        assert!(reasons
+            .iter()
+            .any(|reason| matches!(reason, Termination::Revert(_))));
+        assert!(ctx.live_forks(&analyzer).len() == 1);
+
+        for fn_name in ["named(uint256, uint256)", "closed()"] {
+            let ctx = fn_ctx(&analyzer, entry, fn_name);
+            assert!(matches!(
+                ctx.termination_reason(&analyzer),
+                Some(Termination::Revert(_))
+            ));
+        }
+        // the arguments are still evaluated before reverting
+        let ctx = fn_ctx(&analyzer, entry, "named(uint256, uint256)");
+        assert!(ctx
+            .vars(&analyzer)
+            .iter()
+            .any(|var| var.display_name(&analyzer).contains("have + 1")));
+
+        let warnings = analyzer
+            .diagnostics()
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Warning)
+            .map(|diagnostic| diagnostic.message.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            warnings,
+            vec![
+                "Unknown error \"Missing\"".to_string(),
+                "Unknown error \"Base.Closed\"".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn function_complexity() {
+        let sol = r###"
+contract C {
+    uint256 total;
+
+    function straight(uint256 x) public {
+        total = x;
+    }
+
+    function branchy(uint256 x) public {
+        if (x > 10) {
+            total = 1;
+        }
+        if (x > 20) {
+            total = 2;
+        }
+    }
+
+    function loops(uint256 n) public {
+        for (uint256 i; i < n; i++) {
+            total += i;
+        }
+    }
+
+    function calls(uint256 x) public {
+        branchy(x);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        analyzer.parse_or_panic(sol, 0);
+        let complexity = |name: &str| {
+            ContractNode::from(analyzer.user_types["C"])
+                .funcs(&analyzer)
+                .into_iter()
+                .find(|func| func.name(&analyzer) == name)
+                .unwrap()
+                .complexity(&analyzer)
+        };
+        assert_eq!(complexity("straight(uint256)"), 0);
+        // the second `if` forks each of the paths the first one created
+        assert_eq!(complexity("branchy(uint256)"), 3);
+        assert_eq!(complexity("loops(uint256)"), 1);
+        assert_eq!(complexity("calls(uint256)"), 0);
+    }
+
+    #[test]
+    fn bool_negation() {
+        let sol = r###"
+contract C {
+    bool done;
+
+    function f(bool flag) public {
+        bool a = !true;
+        bool b = !flag;
+        bool c = !!false;
+        require(!flag);
+        bool d = !flag;
+    }
+
+    function g() public {
+        require(!done);
+        done = true;
+        bool e = !done;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let bound = |elem: Option<Elem<Concrete>>| elem.unwrap().maybe_concrete().unwrap().val;
+        let range = |ctx: ContextNode, name: &str| {
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            (
+                bound(var.evaled_range_min(&analyzer)),
+                bound(var.evaled_range_max(&analyzer)),
+            )
+        };
+
+        let ctx = fn_ctx(&analyzer, entry, "f(bool)");
+        let (f, t) = (Concrete::Bool(false), Concrete::Bool(true));
+        assert_eq!(range(ctx, "a"), (f.clone(), f.clone()));
+        assert_eq!(range(ctx, "b"), (f.clone(), t.clone()));
+        assert_eq!(range(ctx, "c"), (f.clone(), f.clone()));
+        assert_eq!(range(ctx, "flag"), (f.clone(), f.clone()));
+        assert_eq!(range(ctx, "d"), (t.clone(), t.clone()));
+
+        // the flag is known again once it is set
+        let ctx = fn_ctx(&analyzer, entry, "g()");
+        assert!(!ctx.is_killed(&analyzer));
+        assert!(!ctx.is_killed(&analyzer));
+        assert_eq!(range(ctx, "e"), (f.clone(), f.clone()));
+
+        // the default bounds of a bool are its two values
+        assert_eq!(Concrete::min(&t), Some(f.clone()));
+        assert_eq!(Concrete::max(&f), Some(t));
+    }
+
+    #[test]
+    fn concrete_casts() {
+        let uint8 = |val: u64| Concrete::Uint(8, val.into());
+        let int8 = |val: i64| Concrete::Int(8, I256::from(val));
+        assert_eq!(int8(-1).cast(Builtin::Uint(8)), Some(uint8(255)));
+        assert_eq!(uint8(255).cast(Builtin::Int(8)), Some(int8(-1)));
+        assert_eq!(
+            int8(-1).cast(Builtin::Int(256)),
+            Some(Concrete::Int(256, I256::from(-1)))
+        );
+        assert_eq!(
+            Concrete::Uint(256, 0x1ff.into()).cast(Builtin::Uint(8)),
+            Some(uint8(0xff))
+        );
+        assert_eq!(
+            Concrete::Uint(256, 0x180.into()).cast(Builtin::Int(8)),
+            Some(int8(-128))
+        );
+
+        let mut word = [0u8; 32];
+        word[0] = 0xff;
+        assert_eq!(
+            int8(-1).cast(Builtin::Bytes(1)),
+            Some(Concrete::Bytes(1, ethers_core::types::H256::from(word)))
+        );
+        assert_eq!(
+            Concrete::Bytes(1, ethers_core::types::H256::from(word)).cast(Builtin::Int(8)),
+            Some(int8(-1))
+        );
+
+        let addr = ethers_core::types::Address::from_low_u64_be(0xbeef);
+        assert_eq!(
+            Concrete::Address(addr).cast(Builtin::Uint(160)),
+            Some(Concrete::Uint(160, 0xbeef.into()))
+        );
+        assert_eq!(
+            Concrete::Uint(160, 0xbeef.into()).cast(Builtin::Address),
+            Some(Concrete::Address(addr))
+        );
+
+        assert_eq!(Concrete::Bool(true).cast(Builtin::Uint(8)), Some(uint8(1)));
+        assert_eq!(uint8(0).cast(Builtin::Bool), Some(Concrete::Bool(false)));
+        assert_eq!(
+            Concrete::String("1".to_string()).cast(Builtin::Uint(256)),
+            None
+        );
+
+        // implicit widening only ever changes the width
+        assert_eq!(
+            int8(-1).widened_to(&Concrete::Int(16, I256::zero())),
+            Concrete::Int(16, I256::from(-1))
+        );
+        assert_eq!(uint8(7).widened_to(&int8(0)), uint8(7));
+    }
+
+    #[test]
+    fn enum_member_access() {
+        let sol = r###"
+contract C {
+    enum Status { Pending, Active, Closed }
+
+    function variants() public {
+        Status s = Status.Closed;
+        bool same = Status.Active == Status.Active;
+    }
+
+    function refined(Status status) public {
+        require(status == Status.Active);
+        Status s = status;
+    }
+
+    function contradiction(Status status) public {
+        require(status == Status.Active);
+        require(status == Status.Closed);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let bound = |elem: Option<Elem<Concrete>>| elem.unwrap().maybe_concrete().unwrap().val;
+        let range = |ctx: ContextNode, name: &str| {
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            (
+                bound(var.evaled_range_min(&analyzer)),
+                bound(var.evaled_range_max(&analyzer)),
+            )
+        };
+        let ordinal = |n: u64| (Concrete::Uint(8, n.into()), Concrete::Uint(8, n.into()));
+
+        let ctx = fn_ctx(&analyzer, entry, "variants()");
+        assert_eq!(range(ctx, "s"), ordinal(2));
+        assert_eq!(
+            range(ctx, "same"),
+            (Concrete::Bool(true), Concrete::Bool(true))
+        );
+
+        let ctx = fn_ctx(&analyzer, entry, "refined(enum Status)");
+        assert_eq!(range(ctx, "status"), ordinal(1));
+        assert_eq!(range(ctx, "s"), ordinal(1));
+
+        let ctx = fn_ctx(&analyzer, entry, "contradiction(enum Status)");
+        assert!(ctx.is_killed(&analyzer));
+    }
+
+    #[test]
+    fn graphml_export() {
+        let sol = r###"
+contract C {
+    function f(uint256 x) public {
+        require(x < 10);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        analyzer.parse_or_panic(sol, 0);
+        let graphml = analyzer.graphml_str();
+        assert!(graphml.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+        assert!(graphml.trim_end().ends_with("</graphml>"));
+        assert_eq!(
+            graphml.matches("<node id=").count(),
+            analyzer.nodes().count()
+        );
+        assert_eq!(
+            graphml.matches("<edge id=").count(),
+            analyzer.edges().count()
+        );
+        assert!(graphml.contains(r#"<data key="kind">Contract</data>"#));
+        assert!(graphml.contains(r#"<data key="edge">Context(Variable)</data>"#));
+        // labels are escaped
+        assert!(graphml.contains("x &lt; 10"));
+        assert!(!graphml.contains("x < 10"));
+    }
+
+    #[test]
+    fn compound_assignments() {
+        let sol = r###"
+pragma solidity ^0.8.0;
+contract C {
+    function ops(uint256 a) public {
+        require(a >= 8);
+        require(a <= 16);
+        uint256 sub = a;
+        sub -= 8;
+        uint256 mul = a;
+        mul *= 2;
+        uint256 div = a;
+        div /= 4;
+        uint256 rem = a;
+        rem %= 5;
+        uint256 and = a;
+        and &= 3;
+        uint256 or = a;
+        or |= 1;
+        uint256 shl = a;
+        shl <<= 2;
+        uint256 shr = a;
+        shr >>= 1;
+        uint256 replaced = a;
+        replaced = 3;
+    }
+
+    function underflows(uint256 a) public {
+        require(a < 5);
+        a -= 1;
+    }
+
+    function safe(uint256 a) public {
+        require(a >= 1);
+        a -= 1;
+    }
+
+    function wraps(uint8 a) public {
+        require(a < 5);
+        unchecked {
+            a -= 10;
+        }
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let file_mapping = analyzer.file_mapping();
+        let bound = |elem: Option<Elem<Concrete>>| {
+            elem.unwrap()
+                .maybe_concrete()
+                .unwrap()
+                .val
+                .uint_val()
+                .unwrap()
+                .as_u64()
+        };
+        let range = |ctx: ContextNode, name: &str| {
+            let var = ctx.latest_var_by_name(&analyzer, name).unwrap();
+            (
+                bound(var.evaled_range_min(&analyzer)),
+                bound(var.evaled_range_max(&analyzer)),
+            )
+        };
+
+        let ctx = fn_ctx(&analyzer, entry, "ops(uint256)");
+        assert_eq!(range(ctx, "sub"), (0, 8));
+        assert_eq!(range(ctx, "mul"), (16, 32));
+        assert_eq!(range(ctx, "div"), (2, 4));
+        assert_eq!(range(ctx, "shl"), (32, 64));
+        assert_eq!(range(ctx, "shr"), (4, 8));
+        assert_eq!(range(ctx, "replaced"), (3, 3));
+        let (_, rem_max) = range(ctx, "rem");
+        assert!(rem_max <= 4);
+        let (_, and_max) = range(ctx, "and");
+        assert!(and_max <= 3);
+        let (or_min, _) = range(ctx, "or");
+        assert!(or_min >= 1);
+
+        let overflows = |func: &str| {
+            let ctx = fn_ctx(&analyzer, entry, func);
+            analyzer
+                .overflow_report(&file_mapping, ctx, ReportConfig::default())
+                .overflows
+                .into_iter()
+                .map(|(_, overflow)| (overflow.op, overflow.underflow, overflow.unchecked))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            overflows("underflows(uint256)"),
+            vec![(RangeOp::Sub, true, false)]
+        );
+        assert!(overflows("safe(uint256)").is_empty());
+        assert_eq!(overflows("wraps(uint8)"), vec![(RangeOp::Sub, true, true)]);
+        let ctx = fn_ctx(&analyzer, entry, "safe(uint256)");
+        let a = ctx.latest_var_by_name(&analyzer, "a").unwrap();
+        assert_eq!(bound(a.evaled_range_min(&analyzer)), 0);
+    }
+
+    #[test]
+    fn unsupported_expressions_continue() {
+        let sol = r###"
+contract C {
+    enum E { A, B, C }
+
+    function units() public {
+        uint256 x = 0x10 days;
+        uint256 y = 1;
+    }
+
+    function casts(bool c, uint256 a) public {
+        require(a < 2);
+        E e = E(c ? a : 2);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        assert!(analyzer
+            .diagnostics()
+            .iter()
+            .any(|diag| diag.message.contains("Denominations")));
+        let ctx = fn_ctx(&analyzer, entry, "units()");
+        assert!(ctx.latest_var_by_name(&analyzer, "y").is_some());
+
+        let ctx = fn_ctx(&analyzer, entry, "casts(bool, uint256)");
+        let forks = ctx.live_forks(&analyzer);
+        assert_eq!(forks.len(), 2);
+        assert!(forks
+            .iter()
+            .all(|fork| fork.latest_var_by_name(&analyzer, "e").is_some()));
+    }
+
+    #[test]
+    fn call_through_fork() {
+        let sol = r###"
+contract C {
+    function f(uint256 a) external returns (uint256) {
+        return a;
+    }
+
+    function g(uint256 a) external returns (uint256) {
+        return a + 1;
+    }
+
+    function pick(bool c) public {
+        uint256 r = (c ? this.f : this.g)(1);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "pick(bool)");
+        let forks = ctx.live_forks(&analyzer);
+        assert_eq!(forks.len(), 2);
+        assert!(forks
+            .iter()
+            .all(|fork| fork.latest_var_by_name(&analyzer, "r").is_some()));
+    }
+
+    #[test]
+    fn abi_decode_unsupported() {
+        let sol = r###"
+contract C {
+    function forked(bool c, bytes memory a, bytes memory b) public {
+        uint256 x = abi.decode(c ? a : b, (uint256));
+    }
+
+    function nontype(bytes memory a) public {
+        uint256 x = abi.decode(a, (Missing));
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "forked(bool, bytes, bytes)");
+        let forks = ctx.live_forks(&analyzer);
+        assert_eq!(forks.len(), 2);
+        assert!(forks
+            .iter()
+            .all(|fork| fork.latest_var_by_name(&analyzer, "x").is_some()));
+
+        let ctx = fn_ctx(&analyzer, entry, "nontype(bytes)");
+        assert!(ctx.latest_var_by_name(&analyzer, "x").is_some());
+        assert!(analyzer
+            .diagnostics()
+            .iter()
+            .any(|diag| diag.message.contains("abi.decode")));
+    }
+
+    #[test]
+    fn uint_widths() {
+        assert_eq!(
+            Concrete::uint(256, U256::MAX),
+            Ok(Concrete::Uint(256, U256::MAX))
+        );
+        assert_eq!(Concrete::uint(7, U256::one()), Err(InvalidIntWidth(7)));
+        assert_eq!(Concrete::uint(264, U256::one()), Err(InvalidIntWidth(264)));
+        assert_eq!(
+            Concrete::uint(8, U256::from(0x1ff)),
+            Ok(Concrete::Uint(8, U256::from(0xff)))
+        );
+        assert_eq!(
+            Concrete::uint_with_max_width(64, U256::one(), 32),
+            Err(InvalidIntWidth(64))
+        );
+        assert_eq!(Concrete::from(U256::one()).cast(Builtin::Uint(7)), None);
+    }
+
+    #[test]
+    fn always_reverts() {
+        let sol = r###"
+contract C {
+    function dead() public {
+        revert();
+    }
+
+    function branches(uint256 x) public {
+        if (x > 5) {
+            revert();
+        } else {
+            revert();
+        }
+    }
+
+    function alive(uint256 x) public {
+        if (x > 5) {
+            revert();
+        }
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        analyzer.parse_or_panic(sol, 0);
+        let func = |name: &str| FunctionNode::from(analyzer.user_types[name]);
+        assert!(func("dead()").always_reverts(&analyzer));
+        assert!(func("branches(uint256)").always_reverts(&analyzer));
+        assert!(!func("alive(uint256)").always_reverts(&analyzer));
+    }
+
+    #[test]
+    fn state_mutability() {
+        let sol = r###"
+contract C {
+    uint256 public x;
+    uint256 y;
+
+    function p() public pure returns (uint256) {
+        return 1;
+    }
+
+    function v() public view returns (uint256) {
+        return y;
+    }
+
+    function pay() public payable {}
+
+    function set(uint256 z) public {
+        y = z;
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        analyzer.parse_or_panic(sol, 0);
+        let mutability = |name: &str| {
+            ContractNode::from(analyzer.user_types["C"])
+                .funcs(&analyzer)
+                .into_iter()
+                .find(|func| func.name(&analyzer) == name)
+                .unwrap()
+                .mutability(&analyzer)
+        };
+        assert_eq!(mutability("p()"), StateMutability::Pure);
+        assert_eq!(mutability("v()"), StateMutability::View);
+        assert_eq!(mutability("pay()"), StateMutability::Payable);
+        assert_eq!(mutability("set(uint256)"), StateMutability::NonPayable);
+        assert_eq!(mutability("x()"), StateMutability::View);
+    }
+
+    #[test]
+    fn termination_reason() {
+        let sol = r###"
+contract C {
+    function reverts() public {
+        revert();
+    }
+
+    function returning() public returns (uint256) {
+        return 1;
+    }
+
+    function destructs(address payable to) public {
+        selfdestruct(to);
+    }
+
+    function falls_through() public {
+        uint256 x = 1;
+    }
+
+    function mixed(uint256 x) public returns (uint256) {
+        if (x > 5) {
+            return 1;
+        } else {
+            revert();
+        }
+    }
+
+    function both_revert(uint256 x) public {
+        if (x > 5) {
+            revert();
+        } else {
+            require(false);
+        }
+    }
+
+    function both_panic(uint256 x) public {
+        if (x > 5) {
+            assert(false);
+        } else {
+            assert(false);
+        }
+    }
+
+    function revert_or_panic(uint256 x) public {
+        if (x > 5) {
+            revert();
+        } else {
+            assert(false);
+        }
+    }
+
+    function both_destruct(uint256 x, address payable to) public {
+        if (x > 5) {
+            selfdestruct(to);
+        } else {
+            selfdestruct(to);
+        }
+    }
+
+    function calls_mixed(uint256 x) public {
+        mixed(x);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let reason =
+            |fn_name: &str| fn_ctx(&analyzer, entry, fn_name).termination_reason(&analyzer);
+
+        let reverts = fn_ctx(&analyzer, entry, "reverts()");
+        assert!(reverts.is_terminated(&analyzer));
+        assert!(matches!(reason("reverts()"), Some(Termination::Revert(_))));
+        assert!(matches!(
+            reason("returning()"),
+            Some(Termination::Return(_))
+        ));
+        assert!(matches!(
+            reason("destructs(address payable)"),
+            Some(Termination::SelfDestruct(_))
+        ));
+        assert!(!fn_ctx(&analyzer, entry, "falls_through()").is_terminated(&analyzer));
+
+        // a body that forked ended the way every one of its forks did
+        assert_eq!(reason("mixed(uint256)"), None);
+        assert!(!fn_ctx(&analyzer, entry, "mixed(uint256)").is_killed(&analyzer));
+        assert!(matches!(
+            reason("both_revert(uint256)"),
+            Some(Termination::Revert(_))
+        ));
+        assert!(matches!(
+            reason("both_panic(uint256)"),
+            Some(Termination::Panic(_))
+        ));
+        assert_eq!(
+            fn_ctx(&analyzer, entry, "both_panic(uint256)").killed_kind(&analyzer),
+            Some(KilledKind::Panic)
+        );
+        assert!(matches!(
+            reason("revert_or_panic(uint256)"),
+            Some(Termination::Revert(_))
+        ));
+        assert!(matches!(
+            reason("both_destruct(uint256, address payable)"),
+            Some(Termination::SelfDestruct(_))
+        ));
+        assert!(
+            !fn_ctx(&analyzer, entry, "both_destruct(uint256, address payable)")
+                .is_killed(&analyzer)
+        );
+        // a call that may return doesn't end its caller
+        assert!(!fn_ctx(&analyzer, entry, "calls_mixed(uint256)").is_killed(&analyzer));
+    }
+
+    #[test]
+    fn cast_member_call() {
+        let sol = r###"
+interface IERC20 {
+    function balanceOf(address who) external view returns (uint256);
+}
+
+contract C {
+    function f(address token, address user) public {
+        uint256 bal = IERC20(token).balanceOf(user);
+    }
+}"###;
+        let mut analyzer = Analyzer::default();
+        let entry = analyzer.parse_or_panic(sol, 0);
+        let ctx = fn_ctx(&analyzer, entry, "f(address, address)");
+        let bal = ctx.latest_var_by_name(&analyzer, "bal").unwrap();
+        assert_eq!(bal.ty(&analyzer).as_string(&analyzer), "uint256");
+
+        let ret = analyzer
+            .graph
+            .node_indices()
+            .filter_map(|idx| match analyzer.node(idx) {
+                Node::ContextVar(cvar) if cvar.display_name == "balanceOf(address).0" => {
+                    Some(ContextVarNode::from(idx))
+                }
+                _ => None,
+            })
+            .next()
+            .unwrap();
+        assert_eq!(ret.ty(&analyzer).as_string(&analyzer), "uint256");
     }
 }
@@ -0,0 +1,7 @@
+import "./lib/math.sol";
+
+error Overdrawn(uint256 total);
+
+contract Base {
+	uint256 internal total;
+}
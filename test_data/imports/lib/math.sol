@@ -0,0 +1,9 @@
+contract Math {
+	function max(uint256 a, uint256 b) internal returns (uint256) {
+		if (a > b) {
+			return a;
+		} else {
+			return b;
+		}
+	}
+}
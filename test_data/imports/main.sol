@@ -0,0 +1,14 @@
+import "./base.sol";
+import { Math as M } from "./lib/math.sol";
+
+contract Main is Base {
+	function f(uint256 x) public returns (uint256) {
+		return x + 1;
+	}
+
+	function g(uint256 x) public {
+		if (x > total) {
+			revert Overdrawn(total);
+		}
+	}
+}
@@ -0,0 +1,6 @@
+contract Dep {
+	function scaled(uint256 y) public returns (uint256) {
+		uint256 z = y * 3;
+		return z;
+	}
+}
@@ -0,0 +1,7 @@
+import "./dep.sol";
+
+contract Main is Dep {
+	function f(uint256 x) public returns (uint256) {
+		return scaled(x);
+	}
+}